@@ -231,7 +231,9 @@ impl RateBucket {
     fn update_critical(&self, critical: &mut MutexGuard<Critical>) {
         if let Some((tokens, deadline)) = calculate_drain(critical.deadline, self.interval) {
             critical.deadline = deadline;
-            critical.balance = critical.balance.saturating_add(tokens);
+            critical.balance = critical
+                .balance
+                .saturating_add(tokens.saturating_mul(self.refill));
 
             if critical.balance > self.max {
                 critical.balance = self.max;
@@ -412,3 +414,17 @@ fn test_serialize_rate_limiter() {
     assert_eq!(limiter1, limiter2);
     assert_eq!(limiter1, limiter3);
 }
+
+#[test]
+fn test_refill_saturates() {
+    let limiter = RateBucket::builder()
+        .max(usize::MAX)
+        .interval(chrono::Duration::milliseconds(1))
+        .initial(usize::MAX - 1)
+        .refill(usize::MAX / 2)
+        .build();
+    // Several intervals have passed, refilled tokens overflow usize.
+    limiter.critical.lock().deadline = Utc::now() - chrono::Duration::milliseconds(10);
+    assert_eq!(limiter.acquire_closed(1), 1);
+    assert_eq!(limiter.balance(), usize::MAX - 1);
+}
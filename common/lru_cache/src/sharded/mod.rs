@@ -81,6 +81,9 @@ where
             .map(|v| unsafe { &(*v).v })
     }
 
+    /// Returns a reference to the value of `k`, the value may be evicted and
+    /// freed by other threads while the reference is alive, use `get_cloned`
+    /// when the cache is shared between threads.
     pub fn get<Q>(&self, k: &Q) -> Option<&V>
     where
         KeyPtr<K>: Borrow<Q>,
@@ -92,6 +95,20 @@ where
             .map(|v| unsafe { &(*v).v })
     }
 
+    /// Returns a clone of the value of `k`, the value is cloned while the shard
+    /// is locked.
+    pub fn get_cloned<Q>(&self, k: &Q) -> Option<V>
+    where
+        KeyPtr<K>: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.shard[Self::shard(k)]
+            .lock()
+            .get_value(k)
+            .map(|v| unsafe { (*v).v.clone() })
+    }
+
     pub fn get_mut<Q>(&self, k: &Q) -> Option<&mut V>
     where
         KeyPtr<K>: Borrow<Q>,
@@ -246,6 +263,20 @@ mod test {
         assert_eq!(lru.get(&"One"), Some(&1));
     }
 
+    #[test]
+    fn test_get_cloned() {
+        let lru = Arc::new(ShardedCache::<i32, Arc<i32>>::with_capacity(1));
+        lru.insert(1, Arc::new(100));
+        let v = lru.get_cloned(&1).unwrap();
+
+        // The cloned value outlives the value evicted from the cache.
+        lru.insert(1, Arc::new(200));
+        lru.remove(&1);
+        assert_eq!(*v, 100);
+        assert_eq!(Arc::strong_count(&v), 1);
+        assert!(lru.get_cloned(&1).is_none());
+    }
+
     #[test]
     fn test_remove() {
         let mut ct = ShardedCacheTester::new();
@@ -211,6 +211,76 @@ pub static COMPACTION_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
     .expect("tskv metric cannot be created")
 });
 
+pub static WRITE_RATE_LIMITED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "write_rate_limited_bytes_total",
+            "total bytes written through write rate limiter",
+        )
+        .namespace(NAMESPACE)
+        .subsystem(TSKV_SUBSYSTEM),
+        &["kind"],
+    )
+    .expect("tskv metric cannot be created")
+});
+
+pub static WRITE_RATE_LIMITED_WAIT: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "write_rate_limited_wait_milliseconds_total",
+            "total milliseconds waited for write rate limiter",
+        )
+        .namespace(NAMESPACE)
+        .subsystem(TSKV_SUBSYSTEM),
+        &["kind"],
+    )
+    .expect("tskv metric cannot be created")
+});
+
+pub fn incr_write_rate_limited_bytes(kind: &str, bytes: u64) {
+    WRITE_RATE_LIMITED_BYTES
+        .with_label_values(&[kind])
+        .inc_by(bytes)
+}
+
+pub fn incr_write_rate_limited_wait(kind: &str, millis: u64) {
+    WRITE_RATE_LIMITED_WAIT
+        .with_label_values(&[kind])
+        .inc_by(millis)
+}
+
+pub static DATA_BLOCK_CACHE_HIT: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::with_opts(
+        Opts::new(
+            "data_block_cache_hit_total",
+            "total hit num of decoded data block cache",
+        )
+        .namespace(NAMESPACE)
+        .subsystem(TSKV_SUBSYSTEM),
+    )
+    .expect("tskv metric cannot be created")
+});
+
+pub static DATA_BLOCK_CACHE_MISS: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::with_opts(
+        Opts::new(
+            "data_block_cache_miss_total",
+            "total miss num of decoded data block cache",
+        )
+        .namespace(NAMESPACE)
+        .subsystem(TSKV_SUBSYSTEM),
+    )
+    .expect("tskv metric cannot be created")
+});
+
+pub fn incr_data_block_cache_hit() {
+    DATA_BLOCK_CACHE_HIT.inc();
+}
+
+pub fn incr_data_block_cache_miss() {
+    DATA_BLOCK_CACHE_MISS.inc();
+}
+
 pub fn init_tskv_metrics_recorder() {
     default_registry()
         .register(Box::new(COMPACTION_SUCCESS.clone()))
@@ -221,6 +291,18 @@ pub fn init_tskv_metrics_recorder() {
     default_registry()
         .register(Box::new(COMPACTION_DURATION.clone()))
         .expect("tskv metrics collector cannot be registered");
+    default_registry()
+        .register(Box::new(WRITE_RATE_LIMITED_BYTES.clone()))
+        .expect("tskv metrics collector cannot be registered");
+    default_registry()
+        .register(Box::new(WRITE_RATE_LIMITED_WAIT.clone()))
+        .expect("tskv metrics collector cannot be registered");
+    default_registry()
+        .register(Box::new(DATA_BLOCK_CACHE_HIT.clone()))
+        .expect("tskv metrics collector cannot be registered");
+    default_registry()
+        .register(Box::new(DATA_BLOCK_CACHE_MISS.clone()))
+        .expect("tskv metrics collector cannot be registered");
 }
 
 pub fn incr_compaction_success() {
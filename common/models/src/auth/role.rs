@@ -230,6 +230,19 @@ impl<T: Id> CustomTenantRole<T> {
             })
         }
     }
+
+    /// Move the privilege of a database to its new name, returns false if
+    /// the role has no privilege of the database.
+    pub fn rename_database(&mut self, database_name: &str, new_database_name: &str) -> bool {
+        match self.additional_privileges.remove(database_name) {
+            Some(p) => {
+                self.additional_privileges
+                    .insert(new_database_name.to_string(), p);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl<T> Identifier<T> for CustomTenantRole<T> {
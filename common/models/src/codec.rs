@@ -2,25 +2,28 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-pub const BIGINT_CODEC: [Encoding; 4] = [
+pub const BIGINT_CODEC: [Encoding; 5] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Delta,
     Encoding::Quantile,
+    Encoding::Auto,
 ];
 // Because timestamp, bigint, and unsigned bigint are all integers,
 // so their compression algorithms are the same
-pub const TIMESTAMP_CODEC: [Encoding; 4] = BIGINT_CODEC;
-pub const UNSIGNED_BIGINT_CODEC: [Encoding; 4] = BIGINT_CODEC;
+pub const TIMESTAMP_CODEC: [Encoding; 5] = BIGINT_CODEC;
+pub const UNSIGNED_BIGINT_CODEC: [Encoding; 5] = BIGINT_CODEC;
 
-pub const DOUBLE_CODEC: [Encoding; 4] = [
+pub const DOUBLE_CODEC: [Encoding; 6] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Gorilla,
     Encoding::Quantile,
+    Encoding::Chimp,
+    Encoding::Auto,
 ];
 
-pub const STRING_CODEC: [Encoding; 7] = [
+pub const STRING_CODEC: [Encoding; 9] = [
     Encoding::Default,
     Encoding::Null,
     Encoding::Gzip,
@@ -28,9 +31,16 @@ pub const STRING_CODEC: [Encoding; 7] = [
     Encoding::Zstd,
     Encoding::Snappy,
     Encoding::Zlib,
+    Encoding::Dictionary,
+    Encoding::Auto,
 ];
 
-pub const BOOLEAN_CODEC: [Encoding; 3] = [Encoding::Default, Encoding::Null, Encoding::BitPack];
+pub const BOOLEAN_CODEC: [Encoding; 4] = [
+    Encoding::Default,
+    Encoding::Null,
+    Encoding::BitPack,
+    Encoding::Auto,
+];
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Hash, Default)]
 pub enum Encoding {
@@ -46,6 +56,11 @@ pub enum Encoding {
     Zstd = 8,
     Zlib = 9,
     BitPack = 10,
+    Chimp = 11,
+    Dictionary = 12,
+    /// Encode each block with all candidate codecs of the type and keep the
+    /// smallest one, the chosen codec is recorded in the encoded block.
+    Auto = 13,
     Unknown = 15,
 }
 
@@ -87,6 +102,9 @@ impl Encoding {
             Encoding::Zstd => "ZSTD",
             Encoding::Zlib => "ZLIB",
             Encoding::BitPack => "BITPACK",
+            Encoding::Chimp => "CHIMP",
+            Encoding::Dictionary => "DICTIONARY",
+            Encoding::Auto => "AUTO",
             Encoding::Unknown => "UNKNOWN",
         }
     }
@@ -107,6 +125,9 @@ impl FromStr for Encoding {
             "ZSTD" => Ok(Self::Zstd),
             "ZLIB" => Ok(Self::Zlib),
             "BITPACK" => Ok(Self::BitPack),
+            "CHIMP" => Ok(Self::Chimp),
+            "DICTIONARY" => Ok(Self::Dictionary),
+            "AUTO" => Ok(Self::Auto),
             _ => Err(s.to_string()),
         }
     }
@@ -126,6 +147,9 @@ impl From<u8> for Encoding {
             8 => Encoding::Zstd,
             9 => Encoding::Zlib,
             10 => Encoding::BitPack,
+            11 => Encoding::Chimp,
+            12 => Encoding::Dictionary,
+            13 => Encoding::Auto,
             _ => Encoding::Unknown,
         }
     }
@@ -0,0 +1,161 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Type of DECIMAL(precision, scale) fields, values are stored as unscaled
+/// 64-bit integers, e.g. 12.34 in DECIMAL(10, 2) is stored as 1234.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq, Hash)]
+pub struct Decimal {
+    pub precision: u8,
+    pub scale: u8,
+}
+
+impl Decimal {
+    /// Max number of digits of an unscaled 64-bit integer.
+    pub const MAX_PRECISION: u8 = 18;
+    pub const DEFAULT_PRECISION: u8 = 18;
+
+    pub fn try_new(precision: u8, scale: u8) -> Result<Self, String> {
+        if precision == 0 || precision > Self::MAX_PRECISION {
+            return Err(format!(
+                "precision of DECIMAL should be in range [1, {}], got {}",
+                Self::MAX_PRECISION,
+                precision
+            ));
+        }
+        if scale > precision {
+            return Err(format!(
+                "scale of DECIMAL should not be greater than precision {}, got {}",
+                precision, scale
+            ));
+        }
+        Ok(Self { precision, scale })
+    }
+
+    fn max_unscaled(&self) -> i64 {
+        10_i64.pow(self.precision as u32) - 1
+    }
+
+    fn scale_factor(&self) -> i64 {
+        10_i64.pow(self.scale as u32)
+    }
+
+    fn check_range(&self, unscaled: i64) -> Option<i64> {
+        if unscaled.abs() > self.max_unscaled() {
+            None
+        } else {
+            Some(unscaled)
+        }
+    }
+
+    /// Returns None if the unscaled value is out of the precision.
+    pub fn unscaled_from_i128(&self, unscaled: i128) -> Option<i64> {
+        i64::try_from(unscaled)
+            .ok()
+            .and_then(|v| self.check_range(v))
+    }
+
+    pub fn unscaled_from_i64(&self, value: i64) -> Option<i64> {
+        value
+            .checked_mul(self.scale_factor())
+            .and_then(|v| self.check_range(v))
+    }
+
+    pub fn unscaled_from_u64(&self, value: u64) -> Option<i64> {
+        i64::try_from(value)
+            .ok()
+            .and_then(|v| self.unscaled_from_i64(v))
+    }
+
+    /// Digits beyond the scale are rounded half away from zero.
+    pub fn unscaled_from_f64(&self, value: f64) -> Option<i64> {
+        let unscaled = (value * self.scale_factor() as f64).round();
+        if !unscaled.is_finite() || unscaled.abs() > self.max_unscaled() as f64 {
+            return None;
+        }
+        Some(unscaled as i64)
+    }
+
+    /// Parse a decimal literal like '-12.345', digits beyond the scale are
+    /// rounded half away from zero.
+    pub fn unscaled_from_str(&self, value: &str) -> Option<i64> {
+        let value = value.trim();
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(v) => (true, v),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part
+                .bytes()
+                .chain(frac_part.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let mut unscaled = 0_i64;
+        for b in int_part.bytes() {
+            unscaled = unscaled.checked_mul(10)?.checked_add((b - b'0') as i64)?;
+        }
+        let mut frac_digits = frac_part.bytes();
+        for _ in 0..self.scale {
+            let digit = frac_digits.next().map(|b| (b - b'0') as i64).unwrap_or(0);
+            unscaled = unscaled.checked_mul(10)?.checked_add(digit)?;
+        }
+        if matches!(frac_digits.next(), Some(b) if b >= b'5') {
+            unscaled = unscaled.checked_add(1)?;
+        }
+        if negative {
+            unscaled = -unscaled;
+        }
+        self.check_range(unscaled)
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Self {
+            precision: Self::DEFAULT_PRECISION,
+            scale: 0,
+        }
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DECIMAL({}, {})", self.precision, self.scale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Decimal;
+
+    #[test]
+    fn test_decimal_unscaled() {
+        assert!(Decimal::try_new(0, 0).is_err());
+        assert!(Decimal::try_new(19, 2).is_err());
+        assert!(Decimal::try_new(5, 6).is_err());
+
+        let d = Decimal::try_new(6, 2).unwrap();
+        assert_eq!(d.to_string(), "DECIMAL(6, 2)");
+        assert_eq!(d.unscaled_from_i64(12), Some(1200));
+        assert_eq!(d.unscaled_from_i64(10000), None);
+        assert_eq!(d.unscaled_from_u64(9999), Some(999900));
+        assert_eq!(d.unscaled_from_f64(12.345), Some(1235));
+        assert_eq!(d.unscaled_from_f64(-0.5), Some(-50));
+        assert_eq!(d.unscaled_from_f64(f64::NAN), None);
+        assert_eq!(d.unscaled_from_i128(-999999), Some(-999999));
+        assert_eq!(d.unscaled_from_i128(1000000), None);
+
+        assert_eq!(d.unscaled_from_str("12.34"), Some(1234));
+        assert_eq!(d.unscaled_from_str("-12.345"), Some(-1235));
+        assert_eq!(d.unscaled_from_str("+.5"), Some(50));
+        assert_eq!(d.unscaled_from_str("7"), Some(700));
+        assert_eq!(d.unscaled_from_str("9999.99"), Some(999999));
+        assert_eq!(d.unscaled_from_str("9999.995"), None);
+        assert_eq!(d.unscaled_from_str("."), None);
+        assert_eq!(d.unscaled_from_str("1e3"), None);
+    }
+}
@@ -10,6 +10,7 @@ pub use value_type::{PhysicalDType, ValueType};
 
 pub mod codec;
 pub mod consistency_level;
+pub mod decimal;
 pub mod errors;
 pub mod meta_data;
 pub mod node_info;
@@ -86,10 +86,124 @@ pub struct BucketInfo {
 }
 
 impl BucketInfo {
+    /// Number of shards the bucket was created with, replication sets split
+    /// from a shard are appended to the end of `shard_group`.
+    pub fn shard_num(&self) -> usize {
+        self.shard_group
+            .iter()
+            .enumerate()
+            .filter(|(i, set)| set.shard_idx(*i) == *i)
+            .count()
+    }
+
     pub fn vnode_for(&self, id: u64) -> ReplicationSet {
-        let index = id as usize % self.shard_group.len();
+        let index = id as usize % self.shard_num();
+
+        // The shard may be split into replication sets of hash ranges, a
+        // replication set which is still copying series doesn't take writes.
+        self.shard_group
+            .iter()
+            .enumerate()
+            .find(|(i, set)| {
+                set.shard_idx(*i) == index
+                    && !set.is_copying()
+                    && set.hash_range.map_or(true, |r| r.contains(id))
+            })
+            .map(|(_, set)| set.clone())
+            .unwrap_or_else(|| self.shard_group[index].clone())
+    }
+
+    /// Starts splitting the hash range of replication set `repl_id` into two
+    /// halves, `new_set` takes the upper half and is appended to `shard_group`
+    /// in state `SplitState::Copying`, the range of `repl_id` is not changed
+    /// until `cut_over_split()`.
+    ///
+    /// If `repl_id` is already being split, the replication set split from it
+    /// is returned and `new_set` is ignored, so an interrupted split can be
+    /// resumed.
+    ///
+    /// Returns None if `repl_id` is not found, it is being split from another
+    /// replication set, or its hash range can't be split.
+    pub fn split_replication_set(
+        &mut self,
+        repl_id: ReplicationSetId,
+        mut new_set: ReplicationSet,
+    ) -> Option<&ReplicationSet> {
+        if let Some(pos) = self
+            .shard_group
+            .iter()
+            .position(|set| set.split_from.map_or(false, |f| f.repl_id == repl_id))
+        {
+            return self.shard_group.get(pos);
+        }
+
+        let (index, set) = self
+            .shard_group
+            .iter()
+            .enumerate()
+            .find(|(_, set)| set.id == repl_id)?;
+        if set.split_from.is_some() {
+            return None;
+        }
+        let range = set
+            .hash_range
+            .unwrap_or_else(|| HashRange::full(index as u32));
+        let (_, upper) = range.split()?;
+        new_set.hash_range = Some(upper);
+        new_set.split_from = Some(SplitFrom {
+            repl_id,
+            state: SplitState::Copying,
+        });
+        self.shard_group.push(new_set);
+
+        self.shard_group.last()
+    }
+
+    /// Switches writes and queries of the hash range of replication set
+    /// `new_id` from the replication set it is split from to itself, the
+    /// replication set it is split from keeps the rest of its hash range.
+    ///
+    /// Returns false if `new_id` is not being split.
+    pub fn cut_over_split(&mut self, new_id: ReplicationSetId) -> bool {
+        let (from_id, new_range) = match self.shard_group.iter_mut().find(|set| set.id == new_id) {
+            Some(ReplicationSet {
+                split_from: Some(split_from),
+                hash_range: Some(range),
+                ..
+            }) => {
+                split_from.state = SplitState::Cleaning;
+                (split_from.repl_id, *range)
+            }
+            _ => return false,
+        };
+        for (i, set) in self.shard_group.iter_mut().enumerate() {
+            if set.id == from_id {
+                let range = set.hash_range.unwrap_or_else(|| HashRange::full(i as u32));
+                set.hash_range = Some(HashRange {
+                    end: new_range.start - 1,
+                    ..range
+                });
+            }
+        }
+        true
+    }
 
-        self.shard_group[index].clone()
+    /// Finishes splitting replication set `new_id`, after moved series are
+    /// removed from the replication set it is split from.
+    ///
+    /// Returns false if `new_id` is not being split or not cut over.
+    pub fn finish_split(&mut self, new_id: ReplicationSetId) -> bool {
+        match self.shard_group.iter_mut().find(|set| set.id == new_id) {
+            Some(set)
+                if set
+                    .split_from
+                    .map_or(false, |f| f.state == SplitState::Cleaning) =>
+            {
+                set.split_from = None;
+                true
+            }
+            _ => false,
+        }
     }
 }
 
@@ -97,12 +211,91 @@ impl BucketInfo {
 pub struct ReplicationSet {
     pub id: ReplicationSetId,
     pub vnodes: Vec<VnodeInfo>,
+    /// Hashes of series in the replication set, None if the shard is not split.
+    #[serde(default)]
+    pub hash_range: Option<HashRange>,
+    /// The replication set which this one is being split from, None if the
+    /// split is finished.
+    #[serde(default)]
+    pub split_from: Option<SplitFrom>,
 }
 
 impl ReplicationSet {
     pub fn new(id: ReplicationSetId, vnodes: Vec<VnodeInfo>) -> Self {
-        Self { id, vnodes }
+        Self {
+            id,
+            vnodes,
+            hash_range: None,
+            split_from: None,
+        }
     }
+
+    /// Whether series in the hash range are still being copied into the
+    /// replication set, it takes neither writes nor queries until cut over.
+    pub fn is_copying(&self) -> bool {
+        self.split_from
+            .map_or(false, |f| f.state == SplitState::Copying)
+    }
+
+    /// Index of the shard which the replication set belongs to, `pos` is the
+    /// position of it in `BucketInfo::shard_group`.
+    fn shard_idx(&self, pos: usize) -> usize {
+        self.hash_range.map_or(pos, |r| r.shard_idx as usize)
+    }
+}
+
+/// Range `[start, end]` of series hashes of a replication set split from a
+/// shard, replication sets split from the same shard have the same `shard_idx`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HashRange {
+    pub shard_idx: u32,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl HashRange {
+    /// The range of all series in a shard.
+    pub fn full(shard_idx: u32) -> Self {
+        Self {
+            shard_idx,
+            start: 0,
+            end: u64::MAX,
+        }
+    }
+
+    pub fn contains(&self, hash: u64) -> bool {
+        self.start <= hash && hash <= self.end
+    }
+
+    /// Splits the range into two halves, returns None if there is only one
+    /// hash in the range.
+    pub fn split(&self) -> Option<(Self, Self)> {
+        if self.start >= self.end {
+            return None;
+        }
+        let mid = self.start + (self.end - self.start) / 2;
+        Some((
+            Self { end: mid, ..*self },
+            Self {
+                start: mid + 1,
+                ..*self
+            },
+        ))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SplitFrom {
+    pub repl_id: ReplicationSetId,
+    pub state: SplitState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SplitState {
+    /// Series are being copied from the replication set split from.
+    Copying,
+    /// Series are being removed from the replication set split from.
+    Cleaning,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
@@ -303,10 +496,7 @@ pub fn allocation_replication_set(
     let mut group = vec![];
 
     for _ in 0..shards {
-        let mut repl_set = ReplicationSet {
-            id: incr_id,
-            vnodes: vec![],
-        };
+        let mut repl_set = ReplicationSet::new(incr_id, vec![]);
         incr_id += 1;
 
         for _ in 0..replica {
@@ -367,3 +557,81 @@ pub fn get_disk_info(path: &str) -> std::io::Result<u64> {
             * (disk_space_info.SectorsPerAllocationUnit * disk_space_info.BytesPerSector) as u64)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{BucketInfo, HashRange, ReplicationSet, VnodeInfo};
+
+    #[test]
+    fn test_split_replication_set() {
+        let mut bucket = BucketInfo {
+            id: 1,
+            start_time: 0,
+            end_time: 100,
+            shard_group: vec![
+                ReplicationSet::new(2, vec![VnodeInfo::new(3, 1)]),
+                ReplicationSet::new(4, vec![VnodeInfo::new(5, 1)]),
+            ],
+        };
+        let low_hash = 2_u64;
+        let high_hash = u64::MAX;
+        assert_eq!(bucket.vnode_for(low_hash).id, 2);
+        assert_eq!(bucket.vnode_for(high_hash).id, 4);
+
+        let new_set = ReplicationSet::new(6, vec![VnodeInfo::new(7, 1)]);
+        let split = bucket.split_replication_set(4, new_set).unwrap();
+        assert_eq!(
+            split.hash_range,
+            Some(HashRange {
+                shard_idx: 1,
+                start: u64::MAX / 2 + 1,
+                end: u64::MAX,
+            })
+        );
+        assert!(split.is_copying());
+        assert_eq!(bucket.shard_num(), 2);
+        // Writes are not routed to the new replication set until cut over.
+        assert_eq!(bucket.vnode_for(3).id, 4);
+        assert_eq!(bucket.vnode_for(high_hash).id, 4);
+
+        // Splitting again resumes the unfinished split.
+        let resumed = bucket
+            .split_replication_set(4, ReplicationSet::new(10, vec![]))
+            .unwrap();
+        assert_eq!(resumed.id, 6);
+        assert_eq!(bucket.shard_group.len(), 3);
+        // The replication set being split from another can't be split.
+        assert!(bucket
+            .split_replication_set(6, ReplicationSet::new(10, vec![]))
+            .is_none());
+        assert!(!bucket.finish_split(6));
+
+        assert!(bucket.cut_over_split(6));
+        assert_eq!(
+            bucket.shard_group[1].hash_range,
+            Some(HashRange {
+                shard_idx: 1,
+                start: 0,
+                end: u64::MAX / 2,
+            })
+        );
+        assert_eq!(bucket.vnode_for(low_hash).id, 2);
+        assert_eq!(bucket.vnode_for(3).id, 4);
+        assert_eq!(bucket.vnode_for(high_hash).id, 6);
+        assert!(bucket.finish_split(6));
+        assert_eq!(bucket.shard_group[2].split_from, None);
+
+        // Split the appended replication set again.
+        let new_set = ReplicationSet::new(8, vec![VnodeInfo::new(9, 1)]);
+        bucket.split_replication_set(6, new_set).unwrap();
+        assert!(bucket.cut_over_split(8));
+        assert!(bucket.finish_split(8));
+        assert_eq!(bucket.shard_num(), 2);
+        assert_eq!(bucket.vnode_for(3).id, 4);
+        assert_eq!(bucket.vnode_for(u64::MAX / 2 + 2).id, 6);
+        assert_eq!(bucket.vnode_for(high_hash).id, 8);
+        assert!(bucket
+            .split_replication_set(10, ReplicationSet::default())
+            .is_none());
+    }
+}
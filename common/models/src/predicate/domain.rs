@@ -767,6 +767,29 @@ impl Range {
         &self.low
     }
 
+    /// Determine if values in [min, max] may overlap this range.
+    ///
+    /// Returns true if values are not comparable with the bounds of this range.
+    fn may_overlap_values(&self, min: &ScalarValue, max: &ScalarValue) -> bool {
+        let above_low = match &self.low.value {
+            None => true,
+            Some(low) => match max.partial_cmp(low) {
+                Some(Ordering::Greater) | None => true,
+                Some(Ordering::Equal) => self.low.bound != Bound::Above,
+                Some(Ordering::Less) => false,
+            },
+        };
+        let below_high = match &self.high.value {
+            None => true,
+            Some(high) => match min.partial_cmp(high) {
+                Some(Ordering::Less) | None => true,
+                Some(Ordering::Equal) => self.high.bound != Bound::Below,
+                Some(Ordering::Greater) => false,
+            },
+        };
+        above_low && below_high
+    }
+
     pub fn high_ref(&self) -> &Marker {
         &self.high
     }
@@ -867,12 +890,203 @@ impl EqutableValueSet {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PatternKind {
+    /// SQL LIKE pattern, '%' matches any characters and '_' matches one character.
+    Like,
+    /// Regular expression.
+    Regex,
+}
+
+/// A pattern that string values are matched against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PatternMatch {
+    kind: PatternKind,
+    pattern: String,
+    escape_char: Option<char>,
+    case_insensitive: bool,
+    negated: bool,
+}
+
+impl PatternMatch {
+    pub fn like(
+        pattern: &str,
+        escape_char: Option<char>,
+        case_insensitive: bool,
+        negated: bool,
+    ) -> Self {
+        Self {
+            kind: PatternKind::Like,
+            pattern: pattern.to_string(),
+            escape_char,
+            case_insensitive,
+            negated,
+        }
+    }
+
+    pub fn regex(pattern: &str, case_insensitive: bool, negated: bool) -> Self {
+        Self {
+            kind: PatternKind::Regex,
+            pattern: pattern.to_string(),
+            escape_char: None,
+            case_insensitive,
+            negated,
+        }
+    }
+
+    pub fn kind(&self) -> PatternKind {
+        self.kind
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// The escape character of a LIKE pattern, defaults to '\\' as in SQL.
+    fn like_escape_char(&self) -> char {
+        self.escape_char.unwrap_or('\\')
+    }
+
+    /// Compile the pattern, LIKE pattern is translated to an anchored regular expression.
+    pub fn to_regex(&self) -> Result<regex::Regex> {
+        let mut expr = String::with_capacity(self.pattern.len() + 8);
+        if self.case_insensitive {
+            expr.push_str("(?i)");
+        }
+        match self.kind {
+            PatternKind::Regex => expr.push_str(&self.pattern),
+            PatternKind::Like => {
+                expr.push_str("(?s)^");
+                let escape_char = self.like_escape_char();
+                let mut chars = self.pattern.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        c if c == escape_char => {
+                            if let Some(next) = chars.next() {
+                                expr.push_str(&regex::escape(&next.to_string()));
+                            }
+                        }
+                        '%' => expr.push_str(".*"),
+                        '_' => expr.push('.'),
+                        c => expr.push_str(&regex::escape(&c.to_string())),
+                    }
+                }
+                expr.push('$');
+            }
+        }
+
+        regex::Regex::new(&expr).map_err(|e| Error::Internal {
+            err: format!("invalid pattern '{}': {}", self.pattern, e),
+        })
+    }
+
+    /// Returns the literal prefix that all matched values start with,
+    /// or None if the prefix is unknown.
+    pub fn literal_prefix(&self) -> Option<String> {
+        if self.negated || self.case_insensitive {
+            return None;
+        }
+        let mut prefix = String::new();
+        match self.kind {
+            PatternKind::Like => {
+                let escape_char = self.like_escape_char();
+                let mut chars = self.pattern.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        c if c == escape_char => match chars.next() {
+                            Some(next) => prefix.push(next),
+                            None => break,
+                        },
+                        '%' | '_' => break,
+                        c => prefix.push(c),
+                    }
+                }
+            }
+            PatternKind::Regex => {
+                // Only anchored expressions without alternation have a prefix.
+                let pattern = self.pattern.strip_prefix('^')?;
+                if pattern.contains('|') {
+                    return None;
+                }
+                let mut chars = pattern.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => match chars.next() {
+                            Some(next) if !next.is_ascii_alphanumeric() => prefix.push(next),
+                            _ => break,
+                        },
+                        '?' | '*' | '{' => {
+                            // The last character is optional.
+                            prefix.pop();
+                            break;
+                        }
+                        '.' | '+' | '(' | ')' | '[' | ']' | '$' | '^' => break,
+                        c => prefix.push(c),
+                    }
+                }
+            }
+        }
+
+        Some(prefix)
+    }
+}
+
+/// A set of string values that match all of the patterns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PatternValueSet {
+    patterns: Vec<PatternMatch>,
+}
+
+impl PatternValueSet {
+    pub fn patterns(&self) -> &[PatternMatch] {
+        &self.patterns
+    }
+
+    /// Returns the longest literal prefix of the patterns,
+    /// all values in the set start with it.
+    pub fn literal_prefix(&self) -> String {
+        self.patterns
+            .iter()
+            .filter_map(|p| p.literal_prefix())
+            .max_by_key(|p| p.len())
+            .unwrap_or_default()
+    }
+
+    /// Compile the patterns into a matcher.
+    pub fn matcher(&self) -> Result<PatternMatcher> {
+        let mut regexes = Vec::with_capacity(self.patterns.len());
+        for p in self.patterns.iter() {
+            regexes.push((p.to_regex()?, p.negated));
+        }
+        Ok(PatternMatcher { regexes })
+    }
+}
+
+/// Compiled patterns of a `PatternValueSet`.
+pub struct PatternMatcher {
+    regexes: Vec<(regex::Regex, bool)>,
+}
+
+impl PatternMatcher {
+    pub fn matches(&self, value: &str) -> bool {
+        self.regexes
+            .iter()
+            .all(|(regex, negated)| regex.is_match(value) != *negated)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Domain {
     Range(RangeValueSet),
     Equtable(EqutableValueSet),
     None,
     All,
+    // Appended after the other variants to keep the serialized variant indices.
+    Pattern(PatternValueSet),
 }
 
 impl Domain {
@@ -938,6 +1152,38 @@ impl Domain {
             entries,
         })
     }
+    /// Construct a set of string values that match the pattern.
+    pub fn of_pattern(pattern: PatternMatch) -> Domain {
+        Domain::Pattern(PatternValueSet {
+            patterns: vec![pattern],
+        })
+    }
+    /// Determine if any value in [min, max] may be included by this domain.
+    ///
+    /// Returns false only if no value in [min, max] can be included, used to
+    /// skip data by it's statistics.
+    pub fn may_contain_range(&self, min: &ScalarValue, max: &ScalarValue) -> bool {
+        match self {
+            Self::All => true,
+            Self::None => false,
+            Self::Range(val_set) => val_set
+                .low_indexed_ranges
+                .values()
+                .any(|range| range.may_overlap_values(min, max)),
+            Self::Equtable(val_set) => {
+                if val_set.white_list {
+                    val_set.entries.iter().any(|e| {
+                        e.value.partial_cmp(min) != Some(Ordering::Less)
+                            && e.value.partial_cmp(max) != Some(Ordering::Greater)
+                    })
+                } else {
+                    // Only if all values are the same and excluded.
+                    !(min == max && val_set.entries.iter().any(|e| &e.value == min))
+                }
+            }
+            Self::Pattern(_) => true,
+        }
+    }
     /// Calculates the intersection of two ranges, and returns None if the intersection does not exist
     ///
     /// This method returns the new value without changing the old value
@@ -949,9 +1195,22 @@ impl Domain {
             (Self::Equtable(ref self_val_set), Self::Equtable(ref other_val_set)) => {
                 Domain::value_intersect(self_val_set, other_val_set)
             }
+            (Self::Pattern(ref self_val_set), Self::Pattern(ref other_val_set)) => {
+                let mut patterns = self_val_set.patterns.clone();
+                for p in other_val_set.patterns.iter() {
+                    if !patterns.contains(p) {
+                        patterns.push(p.clone());
+                    }
+                }
+                Ok(Self::Pattern(PatternValueSet { patterns }))
+            }
             (Self::None, _) | (_, Self::None) => Ok(Self::None),
             (Self::All, _) => Ok(other.clone()),
             (_, Self::All) => Ok(self.clone()),
+            // Patterns can not be combined with other value sets, keep the other one,
+            // it's a superset of the intersection.
+            (Self::Pattern(_), _) => Ok(other.clone()),
+            (_, Self::Pattern(_)) => Ok(self.clone()),
             _ => Err(Error::Internal {
                 err: "mismatched ValueSet type".to_string(),
             }),
@@ -972,9 +1231,16 @@ impl Domain {
             (Self::Equtable(ref self_val_set), Self::Equtable(ref other_val_set)) => {
                 Domain::value_union(self_val_set, other_val_set)
             }
+            (Self::Pattern(ref self_val_set), Self::Pattern(ref other_val_set))
+                if self_val_set == other_val_set =>
+            {
+                Ok(self.clone())
+            }
             (Self::None, _) => Ok(other.clone()),
             (_, Self::None) => Ok(self.clone()),
             (Self::All, _) | (_, Self::All) => Ok(Self::All),
+            // Union of patterns can not be represented.
+            (Self::Pattern(_), _) | (_, Self::Pattern(_)) => Ok(Self::All),
             _ => Err(Error::Internal {
                 err: "mismatched ValueSet type".to_string(),
             }),
@@ -1369,6 +1635,20 @@ impl ResolvedPredicate {
     pub fn fields_filter(&self) -> &ColumnDomains<String> {
         &self.fields_filter
     }
+
+    pub fn encode(predicate: &ResolvedPredicate) -> Result<Vec<u8>> {
+        let bytes = bincode::serialize(predicate).map_err(|err| Error::InvalidSerdeMessage {
+            err: err.to_string(),
+        })?;
+
+        Ok(bytes)
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<ResolvedPredicate> {
+        bincode::deserialize::<ResolvedPredicate>(buf).map_err(|err| Error::InvalidSerdeMessage {
+            err: err.to_string(),
+        })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -1440,6 +1720,10 @@ pub struct QueryArgs {
 
     pub limit: Option<usize>,
     pub batch_size: usize,
+    /// Only read the latest row of each series.
+    pub last_value: bool,
+    /// Time buckets that pushed down aggregates are grouped by.
+    pub time_bucket: Option<TimeBucket>,
 }
 
 impl QueryArgs {
@@ -1484,7 +1768,7 @@ impl QueryExpr {
     }
 }
 
-pub fn encode_agg(agg: &Option<Vec<TableColumn>>) -> Result<Vec<u8>> {
+pub fn encode_agg(agg: &Option<Vec<AggregateColumn>>) -> Result<Vec<u8>> {
     let d = bincode::serialize(agg).map_err(|err| Error::InvalidSerdeMessage {
         err: err.to_string(),
     })?;
@@ -1492,8 +1776,8 @@ pub fn encode_agg(agg: &Option<Vec<TableColumn>>) -> Result<Vec<u8>> {
     Ok(d)
 }
 
-pub fn decode_agg(buf: &[u8]) -> Result<Option<Vec<TableColumn>>> {
-    let args = bincode::deserialize::<Option<Vec<TableColumn>>>(buf).map_err(|err| {
+pub fn decode_agg(buf: &[u8]) -> Result<Option<Vec<AggregateColumn>>> {
+    let args = bincode::deserialize::<Option<Vec<AggregateColumn>>>(buf).map_err(|err| {
         Error::InvalidSerdeMessage {
             err: err.to_string(),
         }
@@ -1505,6 +1789,74 @@ pub fn decode_agg(buf: &[u8]) -> Result<Option<Vec<TableColumn>>> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PushedAggregateFunction {
     Count(String),
+    Max(String),
+    Min(String),
+    Sum(String),
+}
+
+impl PushedAggregateFunction {
+    pub fn kind(&self) -> AggregateKind {
+        match self {
+            Self::Count(_) => AggregateKind::Count,
+            Self::Max(_) => AggregateKind::Max,
+            Self::Min(_) => AggregateKind::Min,
+            Self::Sum(_) => AggregateKind::Sum,
+        }
+    }
+
+    pub fn column_name(&self) -> &str {
+        match self {
+            Self::Count(c) | Self::Max(c) | Self::Min(c) | Self::Sum(c) => c,
+        }
+    }
+}
+
+/// Kind of aggregate functions that can be computed in tskv.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AggregateKind {
+    Count,
+    Max,
+    Min,
+    Sum,
+    /// Value of the maximum timestamp.
+    Last,
+}
+
+/// A pushed down aggregate function with the column to aggregate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregateColumn {
+    pub kind: AggregateKind,
+    pub column: TableColumn,
+}
+
+impl AggregateColumn {
+    pub fn new(kind: AggregateKind, column: TableColumn) -> Self {
+        Self { kind, column }
+    }
+}
+
+/// Time buckets that pushed down aggregates are grouped by, values are put into
+/// buckets like `date_bin(stride, time, origin)` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeBucket {
+    /// Width of buckets, in the unit of the time column.
+    pub stride: i64,
+    /// Timestamp that buckets are aligned to, in the unit of the time column.
+    pub origin: i64,
+}
+
+impl TimeBucket {
+    pub fn new(stride: i64, origin: i64) -> Self {
+        Self { stride, origin }
+    }
+
+    /// Returns the start timestamp of the bucket that contains the timestamp.
+    pub fn start_of(&self, ts: Timestamp) -> Timestamp {
+        let stride = self.stride as i128;
+        let origin = self.origin as i128;
+        let start = origin + (ts as i128 - origin).div_euclid(stride) * stride;
+        start.clamp(i64::MIN as i128, i64::MAX as i128) as Timestamp
+    }
 }
 
 #[cfg(test)]
@@ -1710,6 +2062,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_time_bucket() {
+        let bucket = TimeBucket::new(10, 3);
+        assert_eq!(bucket.start_of(3), 3);
+        assert_eq!(bucket.start_of(12), 3);
+        assert_eq!(bucket.start_of(13), 13);
+        assert_eq!(bucket.start_of(2), -7);
+        assert_eq!(bucket.start_of(-7), -7);
+        assert_eq!(bucket.start_of(-8), -17);
+        assert_eq!(bucket.start_of(i64::MIN), i64::MIN);
+        assert_eq!(TimeBucket::new(10, 0).start_of(i64::MAX), i64::MAX - 7);
+    }
+
     #[test]
     fn test_of_ranges() {
         let f1 = Range::lt(&DataType::Float64, &ScalarValue::Float64(Some(-1000000.1)));
@@ -1770,4 +2135,122 @@ mod tests {
             }
         };
     }
+
+    #[test]
+    fn test_may_contain_range() {
+        let v = |v: i64| ScalarValue::Int64(Some(v));
+
+        // value > 10
+        let domain = Domain::of_ranges(&[Range::gt(&DataType::Int64, &v(10))]).unwrap();
+        assert!(!domain.may_contain_range(&v(1), &v(10)));
+        assert!(domain.may_contain_range(&v(1), &v(11)));
+        assert!(domain.may_contain_range(&v(20), &v(30)));
+
+        // value <= 10 or value = 20
+        let domain = Domain::of_ranges(&[
+            Range::le(&DataType::Int64, &v(10)),
+            Range::eq(&DataType::Int64, &v(20)),
+        ])
+        .unwrap();
+        assert!(domain.may_contain_range(&v(10), &v(15)));
+        assert!(!domain.may_contain_range(&v(11), &v(19)));
+        assert!(domain.may_contain_range(&v(11), &v(20)));
+        assert!(!domain.may_contain_range(&v(21), &v(30)));
+
+        // value in (5, 7)
+        let domain = Domain::of_values(&DataType::Int64, true, &[&v(5), &v(7)]);
+        assert!(domain.may_contain_range(&v(1), &v(5)));
+        assert!(!domain.may_contain_range(&v(6), &v(6)));
+        assert!(!domain.may_contain_range(&v(8), &v(9)));
+
+        // value not in (5)
+        let domain = Domain::of_values(&DataType::Int64, false, &[&v(5)]);
+        assert!(!domain.may_contain_range(&v(5), &v(5)));
+        assert!(domain.may_contain_range(&v(5), &v(6)));
+
+        // Values are not comparable with the domain.
+        let domain = Domain::of_ranges(&[Range::gt(&DataType::Int64, &v(10))]).unwrap();
+        let f = ScalarValue::Float64(Some(1.0));
+        assert!(domain.may_contain_range(&f, &f));
+
+        assert!(Domain::All.may_contain_range(&v(1), &v(2)));
+        assert!(!Domain::None.may_contain_range(&v(1), &v(2)));
+    }
+
+    #[test]
+    fn test_pattern_match() {
+        let like = PatternMatch::like("web-%_1", None, false, false);
+        assert_eq!(like.literal_prefix(), Some("web-".to_string()));
+        let regex = like.to_regex().unwrap();
+        assert!(regex.is_match("web-a1"));
+        assert!(regex.is_match("web-abc1"));
+        assert!(!regex.is_match("web-1"));
+        assert!(!regex.is_match("xweb-a1"));
+
+        let like = PatternMatch::like("50!%%", Some('!'), false, false);
+        assert_eq!(like.literal_prefix(), Some("50%".to_string()));
+        let regex = like.to_regex().unwrap();
+        assert!(regex.is_match("50%off"));
+        assert!(!regex.is_match("500"));
+
+        let like = PatternMatch::like("a\\_b%", None, false, false);
+        assert_eq!(like.literal_prefix(), Some("a_b".to_string()));
+        let regex = like.to_regex().unwrap();
+        assert!(regex.is_match("a_b1"));
+        assert!(!regex.is_match("axb1"));
+
+        let like = PatternMatch::like("WEB%", None, true, false);
+        assert_eq!(like.literal_prefix(), None);
+        assert!(like.to_regex().unwrap().is_match("web-1"));
+
+        assert_eq!(
+            PatternMatch::regex("^web-\\d+", false, false).literal_prefix(),
+            Some("web-".to_string())
+        );
+        assert_eq!(
+            PatternMatch::regex("^ab?c", false, false).literal_prefix(),
+            Some("a".to_string())
+        );
+        assert_eq!(
+            PatternMatch::regex("^a|b", false, false).literal_prefix(),
+            None
+        );
+        assert_eq!(
+            PatternMatch::regex("web", false, false).literal_prefix(),
+            None
+        );
+        assert_eq!(
+            PatternMatch::regex("^web", false, true).literal_prefix(),
+            None
+        );
+        assert!(PatternMatch::regex("(", false, false).to_regex().is_err());
+    }
+
+    #[test]
+    fn test_pattern_domain() {
+        let d1 = Domain::of_pattern(PatternMatch::like("web-%", None, false, false));
+        let d2 = Domain::of_pattern(PatternMatch::regex("-1$", false, true));
+
+        let d = d1.intersect(&d2).unwrap();
+        match &d {
+            Domain::Pattern(val_set) => {
+                assert_eq!(val_set.patterns().len(), 2);
+                assert_eq!(val_set.literal_prefix(), "web-");
+                let matcher = val_set.matcher().unwrap();
+                assert!(matcher.matches("web-2"));
+                assert!(!matcher.matches("web-1"));
+                assert!(!matcher.matches("db-2"));
+            }
+            _ => panic!("excepted Domain::Pattern"),
+        }
+        assert_eq!(d1.union(&d1).unwrap(), d1);
+        assert_eq!(d1.union(&d2).unwrap(), Domain::All);
+
+        // Intersection with other value sets keeps the other one.
+        let s = ScalarValue::Utf8(Some("web-1".to_string()));
+        let range = Domain::of_ranges(&[Range::eq(&DataType::Utf8, &s)]).unwrap();
+        assert_eq!(d1.intersect(&range).unwrap(), range);
+        assert_eq!(range.intersect(&d1).unwrap(), range);
+        assert_eq!(d1.union(&range).unwrap(), Domain::All);
+    }
 }
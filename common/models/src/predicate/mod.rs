@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use self::domain::{
     ColumnDomains, PredicateRef, ResolvedPredicate, ResolvedPredicateRef, TimeRange, TimeRanges,
 };
-use crate::meta_data::{ReplicationSet, ReplicationSetId, VnodeInfo};
+use crate::meta_data::{HashRange, ReplicationSet, ReplicationSetId, VnodeInfo};
 use crate::schema::{ColumnType, TskvTableSchemaRef};
 
 pub mod domain;
@@ -142,4 +142,9 @@ impl PlacedSplit {
     pub fn replica_id(&self) -> ReplicationSetId {
         self.repl_set.id
     }
+
+    /// Range of hashes of series to read, None if all series in the vnode are read.
+    pub fn hash_range(&self) -> Option<HashRange> {
+        self.repl_set.hash_range
+    }
 }
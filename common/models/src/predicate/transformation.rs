@@ -5,11 +5,12 @@ use datafusion::arrow::datatypes::DataType;
 use datafusion::common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
 use datafusion::common::Result as DFResult;
 use datafusion::error::DataFusionError;
-use datafusion::logical_expr::{BinaryExpr, Operator};
+use datafusion::logical_expr::expr::InList;
+use datafusion::logical_expr::{BinaryExpr, Like, Operator};
 use datafusion::prelude::{Column, Expr};
 use datafusion::scalar::ScalarValue;
 
-use super::domain::{ColumnDomains, Domain, Range};
+use super::domain::{ColumnDomains, Domain, PatternMatch, Range};
 
 type Result<T> = result::Result<T, DataFusionError>;
 
@@ -168,11 +169,7 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
             // | Expr::QualifiedWildcard { .. }
             // | Expr::GetIndexedField { .. } => {}
             Expr::Column(_) | Expr::Literal(_) => Ok(VisitRecursion::Continue),
-            Expr::BinaryExpr(BinaryExpr {
-                left: _,
-                op,
-                right: _,
-            }) => {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => {
                 match op {
                     Operator::Eq
                     | Operator::NotEq
@@ -185,6 +182,20 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                         // support
                         Ok(VisitRecursion::Continue)
                     }
+                    Operator::RegexMatch
+                    | Operator::RegexIMatch
+                    | Operator::RegexNotMatch
+                    | Operator::RegexNotIMatch => {
+                        let case_insensitive =
+                            matches!(op, Operator::RegexIMatch | Operator::RegexNotIMatch);
+                        let negated =
+                            matches!(op, Operator::RegexNotMatch | Operator::RegexNotIMatch);
+                        let domains = Self::pattern_to_column_domains(left, right, |pattern| {
+                            PatternMatch::regex(pattern, case_insensitive, negated)
+                        });
+                        self.ctx.current_domain_stack.push_back(domains);
+                        Ok(VisitRecursion::Skip)
+                    }
                     _ => {
                         // not support
                         self.ctx
@@ -194,10 +205,27 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                     }
                 }
             }
+            Expr::Like(like) | Expr::ILike(like) => {
+                let case_insensitive = matches!(expr, Expr::ILike(_));
+                let Like {
+                    negated,
+                    expr: column,
+                    pattern,
+                    escape_char,
+                } = like;
+                let domains = Self::pattern_to_column_domains(column, pattern, |pattern| {
+                    PatternMatch::like(pattern, *escape_char, case_insensitive, *negated)
+                });
+                self.ctx.current_domain_stack.push_back(domains);
+                Ok(VisitRecursion::Skip)
+            }
+            Expr::InList(in_list) => {
+                let domains = Self::in_list_to_column_domains(in_list);
+                self.ctx.current_domain_stack.push_back(domains);
+                Ok(VisitRecursion::Skip)
+            }
             // TODO Currently not supported, follow-up support needs to implement the corresponding expression in post_visit
-            Expr::Like(_)
-            | Expr::ILike(_)
-            | Expr::SimilarTo(_)
+            Expr::SimilarTo(_)
             | Expr::Not(_)
             | Expr::IsNotNull(_)
             | Expr::IsNull(_)
@@ -207,8 +235,7 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
             | Expr::IsNotTrue(_)
             | Expr::IsNotFalse(_)
             | Expr::IsNotUnknown(_)
-            | Expr::Between { .. }
-            | Expr::InList { .. } => {
+            | Expr::Between { .. } => {
                 self.ctx
                     .current_domain_stack
                     .push_back(ColumnDomains::all());
@@ -258,7 +285,7 @@ impl TreeNodeVisitor for RowExpressionToDomainsVisitor<'_> {
                 }
             }
             // TODO The stack is the domain, and the domain is generated
-            Expr::Not(_) | Expr::Between { .. } | Expr::InList { .. } => {}
+            Expr::Not(_) | Expr::Between { .. } => {}
             _ => {}
         }
 
@@ -303,6 +330,66 @@ impl RowExpressionToDomainsVisitor<'_> {
         let val_set = Domain::of_values(&value.get_datatype(), is_eq_op, &[value]);
         ColumnDomains::of(col.to_owned(), &val_set)
     }
+    /// Convert `column LIKE 'pattern'` or `column ~ 'pattern'` to PatternValueSet
+    fn pattern_to_column_domains(
+        expr: &Expr,
+        pattern: &Expr,
+        to_pattern: impl FnOnce(&str) -> PatternMatch,
+    ) -> ColumnDomains<Column> {
+        match (expr, pattern) {
+            (Expr::Column(column), Expr::Literal(ScalarValue::Utf8(Some(pattern)))) => {
+                ColumnDomains::of(column.to_owned(), &Domain::of_pattern(to_pattern(pattern)))
+            }
+            _ => ColumnDomains::all(),
+        }
+    }
+    /// Convert `column IN (values)` to the union of `column = value`,
+    /// and `column NOT IN (values)` to the intersection of `column != value`
+    fn in_list_to_column_domains(in_list: &InList) -> ColumnDomains<Column> {
+        let column = match in_list.expr.as_ref() {
+            Expr::Column(column) => column,
+            _ => return ColumnDomains::all(),
+        };
+        let op = if in_list.negated {
+            Operator::NotEq
+        } else {
+            Operator::Eq
+        };
+
+        let mut result: Option<ColumnDomains<Column>> = None;
+        for e in in_list.list.iter() {
+            let nsc =
+                match NormalizedSimpleComparison::of(Expr::Column(column.clone()), op, e.clone()) {
+                    Some(nsc) => nsc,
+                    // Not a literal or is null
+                    None => return ColumnDomains::all(),
+                };
+            let domains = if nsc.is_orderable() {
+                Self::nsc_to_column_domains_with_range(&nsc)
+            } else {
+                Self::nsc_to_domains_with_equtable(&nsc)
+            };
+            result = Some(match result.take() {
+                None => domains,
+                Some(mut r) => {
+                    if in_list.negated {
+                        r.intersect(&domains);
+                    } else {
+                        r.column_wise_union(&domains);
+                    }
+                    r
+                }
+            });
+        }
+
+        result.unwrap_or_else(|| {
+            if in_list.negated {
+                ColumnDomains::all()
+            } else {
+                ColumnDomains::none()
+            }
+        })
+    }
     /// Construct comparison operations as simple column-value comparison data structures nsc.
     ///
     /// Choose a different NscToValueSet function based on whether the data type supports sorting.
@@ -519,6 +606,7 @@ mod tests {
     /// eg.
     ///   s1 like '%上证180' and time >= '2022-10-10 00:00:00'
     ///   ===>
+    ///   s1: like '%上证180'
     ///   time: ['2022-10-10 00:00:00', _)
     #[test]
     fn test_simple_and_to_domain_0() {
@@ -541,7 +629,11 @@ mod tests {
 
         let i1_domain = Domain::of_ranges(&[i1]).unwrap();
 
-        let except_column_domains = ColumnDomains::of(Column::from_name("time"), &i1_domain);
+        let mut except_column_domains = ColumnDomains::of(Column::from_name("time"), &i1_domain);
+        except_column_domains.insert_or_intersect(
+            Column::from_name("s1"),
+            &Domain::of_pattern(PatternMatch::like("%上证180", None, false, false)),
+        );
 
         let result = get_domains(&and);
 
@@ -1257,34 +1349,80 @@ mod tests {
         );
     }
 
-    /// not support push down - 2
+    /// in list push down
     /// eg.
-    ///   c1 in Values(1), (2), (3)
+    ///   c1 in (1, 2, 3)
     ///   ===>
-    ///   All
+    ///   c1: [1, 1], [2, 2], [3, 3]
+    ///   c1 not in (1, 2)
+    ///   ===>
+    ///   c1: (_, 1), (1, 2), (2, _)
     #[test]
-    fn test_not_support_expr_to_domain_2() {
-        let list = vec![lit(1), lit(2), lit(3)];
+    fn test_in_list_to_domain() {
+        let v = |v: i32| ScalarValue::Int32(Some(v));
+
+        let expr = in_list(col("c1"), vec![lit(1), lit(2), lit(3)], false);
+        let result = get_domains(&expr).unwrap();
+        let domain = Domain::of_ranges(&[
+            Range::eq(&DataType::Int32, &v(1)),
+            Range::eq(&DataType::Int32, &v(2)),
+            Range::eq(&DataType::Int32, &v(3)),
+        ])
+        .unwrap();
+        assert_eq!(result, ColumnDomains::of(Column::from_name("c1"), &domain));
+
+        let expr = in_list(col("c1"), vec![lit(1), lit(2)], true);
+        let result = get_domains(&expr).unwrap();
+        let ne_1 = Domain::of_ranges(&Range::ne(&DataType::Int32, &v(1))).unwrap();
+        let ne_2 = Domain::of_ranges(&Range::ne(&DataType::Int32, &v(2))).unwrap();
+        let mut except_column_domains = ColumnDomains::of(Column::from_name("c1"), &ne_1);
+        except_column_domains.insert_or_intersect(Column::from_name("c1"), &ne_2);
+        assert_eq!(result, except_column_domains);
+        match except_column_domains
+            .domains()
+            .unwrap()
+            .get(&Column::from_name("c1"))
+        {
+            Some(Domain::Range(val_set)) => {
+                assert_eq!(val_set.low_indexed_ranges().into_iter().count(), 3)
+            }
+            _ => panic!("excepted Domain::Range"),
+        }
 
-        let in_list = in_list(col("c1"), list, false);
+        // Not literal values
+        let expr = in_list(col("c1"), vec![lit(1), col("c2")], false);
+        assert!(get_domains(&expr).unwrap().is_all());
+    }
 
-        let result = get_domains(&in_list);
+    /// pattern push down
+    /// eg.
+    ///   s1 ~ '^web' and s1 not ilike '%-1'
+    ///   ===>
+    ///   s1: ~ '^web' and not ilike '%-1'
+    #[test]
+    fn test_pattern_to_domain() {
+        let regex = binary_expr(col("s1"), Operator::RegexMatch, lit("^web"));
+        let not_ilike = Expr::ILike(Like::new(
+            true,
+            Box::new(col("s1")),
+            Box::new(lit("%-1")),
+            None,
+        ));
+        let result = get_domains(&and(regex, not_ilike)).unwrap();
 
-        assert!(
-            result.is_ok(),
-            "convert expr {} to column domains err",
-            &in_list
+        let mut except_column_domains = ColumnDomains::of(
+            Column::from_name("s1"),
+            &Domain::of_pattern(PatternMatch::like("%-1", None, true, true)),
         );
-
-        // println!("{:#?}", result.as_ref().unwrap());
-
-        let column_domain = result.as_ref().unwrap();
-
-        assert!(
-            column_domain.is_all(),
-            "convert expr {} to column domains err, excepted ColumnDomains::All",
-            &in_list
+        except_column_domains.insert_or_intersect(
+            Column::from_name("s1"),
+            &Domain::of_pattern(PatternMatch::regex("^web", false, false)),
         );
+        assert_eq!(result, except_column_domains);
+
+        // Pattern is not a literal
+        let expr = binary_expr(col("s1"), Operator::RegexNotMatch, col("s2"));
+        assert!(get_domains(&expr).unwrap().is_all());
     }
 
     /// not support push down - 3
@@ -34,6 +34,7 @@ use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
 use crate::codec::Encoding;
+use crate::decimal::Decimal;
 use crate::gis::data_type::Geometry;
 use crate::oid::{Identifier, Oid};
 use crate::utils::{
@@ -80,6 +81,24 @@ impl TableSchema {
         }
     }
 
+    pub fn set_name(&mut self, name: &str) {
+        match self {
+            TableSchema::TsKvTableSchema(schema) => Arc::make_mut(schema).name = name.to_string(),
+            TableSchema::ExternalTableSchema(schema) => {
+                Arc::make_mut(schema).name = name.to_string()
+            }
+            TableSchema::StreamTableSchema(schema) => Arc::make_mut(schema).name = name.to_string(),
+        }
+    }
+
+    pub fn set_db(&mut self, db: &str) {
+        match self {
+            TableSchema::TsKvTableSchema(schema) => Arc::make_mut(schema).db = db.to_string(),
+            TableSchema::ExternalTableSchema(schema) => Arc::make_mut(schema).db = db.to_string(),
+            TableSchema::StreamTableSchema(schema) => Arc::make_mut(schema).db = db.to_string(),
+        }
+    }
+
     pub fn engine_name(&self) -> &str {
         match self {
             TableSchema::TsKvTableSchema(_) => "TSKV",
@@ -152,6 +171,9 @@ pub struct TskvTableSchema {
     pub name: String,
     pub schema_id: SchemaId,
     next_column_id: ColumnId,
+    /// Whether to keep the latest value of each series of the table in memory.
+    #[serde(default)]
+    pub last_value_cache: bool,
 
     columns: Vec<TableColumn>,
     //ColumnName -> ColumnsIndex
@@ -166,6 +188,7 @@ impl Default for TskvTableSchema {
             name: "template".to_string(),
             schema_id: 0,
             next_column_id: 0,
+            last_value_cache: false,
             columns: Default::default(),
             columns_index: Default::default(),
         }
@@ -191,6 +214,7 @@ impl TskvTableSchema {
             name,
             schema_id: 0,
             next_column_id: columns.len() as ColumnId,
+            last_value_cache: false,
             columns,
             columns_index,
         }
@@ -237,6 +261,7 @@ impl TskvTableSchema {
             None => return,
             Some(id) => *id,
         };
+        self.columns_index.remove(col_name);
         self.columns_index.insert(new_column.name.clone(), id);
         self.columns[id] = new_column;
     }
@@ -472,8 +497,12 @@ impl TableColumn {
             return self.encoding.is_bigint_encoding();
         } else if let ColumnType::Field(ValueType::Unsigned) = self.column_type {
             return self.encoding.is_unsigned_encoding();
+        } else if let ColumnType::Field(ValueType::Decimal(_)) = self.column_type {
+            return self.encoding.is_bigint_encoding();
         } else if let ColumnType::Field(ValueType::String) = self.column_type {
             return self.encoding.is_string_encoding();
+        } else if let ColumnType::Field(ValueType::Binary) = self.column_type {
+            return self.encoding.is_string_encoding();
         } else if let ColumnType::Time(_) = self.column_type {
             return self.encoding.is_timestamp_encoding();
         } else if let ColumnType::Tag = self.column_type {
@@ -495,6 +524,10 @@ impl From<ColumnType> for ArrowDataType {
             ColumnType::Field(ValueType::String) => ArrowDataType::Utf8,
             ColumnType::Field(ValueType::Boolean) => ArrowDataType::Boolean,
             ColumnType::Field(ValueType::Geometry(_)) => ArrowDataType::Utf8,
+            ColumnType::Field(ValueType::Decimal(Decimal { precision, scale })) => {
+                ArrowDataType::Decimal128(precision, scale as i8)
+            }
+            ColumnType::Field(ValueType::Binary) => ArrowDataType::Binary,
             _ => ArrowDataType::Null,
         }
     }
@@ -522,6 +555,8 @@ impl ColumnType {
             Self::Field(ValueType::Float) => "F64",
             Self::Field(ValueType::Boolean) => "BOOL",
             Self::Field(ValueType::String) => "STRING",
+            Self::Field(ValueType::Decimal(_)) => "DECIMAL",
+            Self::Field(ValueType::Binary) => "BINARY",
             _ => "Error filed type not supported",
         }
     }
@@ -534,13 +569,17 @@ impl ColumnType {
         }
     }
 
+    /// Returns the code of `FieldType` in flatbuffers points, which is decoded
+    /// by `from_i32`. Decimal fields are written as integers, so they are
+    /// decoded as integers.
     pub fn field_type(&self) -> u8 {
         match self {
             Self::Field(ValueType::Float) => 0,
-            Self::Field(ValueType::Integer) => 1,
+            Self::Field(ValueType::Integer) | Self::Field(ValueType::Decimal(_)) => 1,
             Self::Field(ValueType::Unsigned) => 2,
             Self::Field(ValueType::Boolean) => 3,
             Self::Field(ValueType::String) | Self::Field(ValueType::Geometry(_)) => 4,
+            Self::Field(ValueType::Binary) => 5,
             _ => 0,
         }
     }
@@ -552,6 +591,7 @@ impl ColumnType {
             2 => Self::Field(ValueType::Unsigned),
             3 => Self::Field(ValueType::Boolean),
             4 => Self::Field(ValueType::String),
+            5 => Self::Field(ValueType::Binary),
             _ => Self::Field(ValueType::Unknown),
         }
     }
@@ -573,6 +613,8 @@ impl ColumnType {
                 ValueType::Boolean => "BOOLEAN".into(),
                 ValueType::Unknown => "UNKNOWN".into(),
                 ValueType::Geometry(geo) => geo.to_string().into(),
+                ValueType::Decimal(decimal) => decimal.to_string().into(),
+                ValueType::Binary => "BINARY".into(),
             },
         }
     }
@@ -687,6 +729,18 @@ impl DatabaseSchema {
         };
         now - ttl
     }
+
+    // return the max timestamp value of data to be moved to cold storage
+    pub fn time_to_cold(&self) -> Option<i64> {
+        let cold_duration = self.config.cold_duration().as_ref()?;
+        let precision = *self.config.precision_or_default();
+        let now = match precision {
+            Precision::MS => crate::utils::now_timestamp_millis(),
+            Precision::US => crate::utils::now_timestamp_micros(),
+            Precision::NS => crate::utils::now_timestamp_nanos(),
+        };
+        Some(now.saturating_sub(cold_duration.to_precision(precision)))
+    }
 }
 
 pub fn make_owner(tenant_name: &str, database_name: &str) -> String {
@@ -716,6 +770,21 @@ pub struct DatabaseOptions {
     replica: Option<u64>,
     // timestamp precision
     precision: Option<Precision>,
+    // data older than it will be moved to cold storage
+    #[serde(default)]
+    cold_duration: Option<Duration>,
+    // strategy of picking files to compact
+    #[serde(default)]
+    compaction_strategy: Option<CompactionStrategy>,
+    // time range of a window of the time-window compaction strategy
+    #[serde(default)]
+    compaction_window: Option<Duration>,
+    // max number of series of the database in all vnodes on a data node
+    #[serde(default)]
+    max_series_per_database: Option<u64>,
+    // max number of series of a table in all vnodes of the database on a data node
+    #[serde(default)]
+    max_series_per_table: Option<u64>,
 }
 
 impl DatabaseOptions {
@@ -730,6 +799,11 @@ impl DatabaseOptions {
         unit: DurationUnit::Day,
     };
     pub const DEFAULT_PRECISION: Precision = Precision::NS;
+    pub const DEFAULT_COMPACTION_STRATEGY: CompactionStrategy = CompactionStrategy::Level;
+    pub const DEFAULT_COMPACTION_WINDOW: Duration = Duration {
+        time_num: 1,
+        unit: DurationUnit::Day,
+    };
 
     pub fn new(
         ttl: Option<Duration>,
@@ -744,6 +818,11 @@ impl DatabaseOptions {
             vnode_duration,
             replica,
             precision,
+            cold_duration: None,
+            compaction_strategy: None,
+            compaction_window: None,
+            max_series_per_database: None,
+            max_series_per_table: None,
         }
     }
 
@@ -791,6 +870,38 @@ impl DatabaseOptions {
             .unwrap_or(&DatabaseOptions::DEFAULT_PRECISION)
     }
 
+    pub fn cold_duration(&self) -> &Option<Duration> {
+        &self.cold_duration
+    }
+
+    pub fn compaction_strategy(&self) -> &Option<CompactionStrategy> {
+        &self.compaction_strategy
+    }
+
+    pub fn compaction_strategy_or_default(&self) -> &CompactionStrategy {
+        self.compaction_strategy
+            .as_ref()
+            .unwrap_or(&DatabaseOptions::DEFAULT_COMPACTION_STRATEGY)
+    }
+
+    pub fn compaction_window(&self) -> &Option<Duration> {
+        &self.compaction_window
+    }
+
+    pub fn compaction_window_or_default(&self) -> &Duration {
+        self.compaction_window
+            .as_ref()
+            .unwrap_or(&DatabaseOptions::DEFAULT_COMPACTION_WINDOW)
+    }
+
+    pub fn max_series_per_database(&self) -> &Option<u64> {
+        &self.max_series_per_database
+    }
+
+    pub fn max_series_per_table(&self) -> &Option<u64> {
+        &self.max_series_per_table
+    }
+
     pub fn with_ttl(&mut self, ttl: Duration) {
         self.ttl = Some(ttl);
     }
@@ -810,6 +921,54 @@ impl DatabaseOptions {
     pub fn with_precision(&mut self, precision: Precision) {
         self.precision = Some(precision)
     }
+
+    pub fn with_cold_duration(&mut self, cold_duration: Duration) {
+        self.cold_duration = Some(cold_duration);
+    }
+
+    pub fn with_compaction_strategy(&mut self, compaction_strategy: CompactionStrategy) {
+        self.compaction_strategy = Some(compaction_strategy);
+    }
+
+    pub fn with_compaction_window(&mut self, compaction_window: Duration) {
+        self.compaction_window = Some(compaction_window);
+    }
+
+    pub fn with_max_series_per_database(&mut self, max_series: u64) {
+        self.max_series_per_database = Some(max_series);
+    }
+
+    pub fn with_max_series_per_table(&mut self, max_series: u64) {
+        self.max_series_per_table = Some(max_series);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompactionStrategy {
+    /// Compact files level by level, from level 0 to level 4.
+    Level,
+    /// Compact files only with files in the same time window,
+    /// windows older than the newest one will be frozen after compacted.
+    TimeWindow,
+}
+
+impl CompactionStrategy {
+    pub fn new(text: &str) -> Option<Self> {
+        match text.to_uppercase().as_str() {
+            "LEVEL" => Some(CompactionStrategy::Level),
+            "TIME_WINDOW" => Some(CompactionStrategy::TimeWindow),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CompactionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompactionStrategy::Level => f.write_str("LEVEL"),
+            CompactionStrategy::TimeWindow => f.write_str("TIME_WINDOW"),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
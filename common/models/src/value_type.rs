@@ -2,6 +2,7 @@ use std::fmt::{Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
+use crate::decimal::Decimal;
 use crate::gis::data_type::Geometry;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq, Hash)]
@@ -13,6 +14,10 @@ pub enum ValueType {
     Boolean,
     String,
     Geometry(Geometry),
+    /// Stored as unscaled integers.
+    Decimal(Decimal),
+    /// Stored as strings without UTF-8 check.
+    Binary,
 }
 
 /// data type for tskv
@@ -36,10 +41,30 @@ impl ValueType {
             Self::Boolean => PhysicalDType::Boolean,
             Self::String => PhysicalDType::String,
             Self::Geometry(_) => PhysicalDType::String,
+            Self::Decimal(_) => PhysicalDType::Integer,
+            Self::Binary => PhysicalDType::String,
         }
     }
 }
 
+impl PhysicalDType {
+    /// Returns true if values of this type can be converted to `target`,
+    /// which is allowed when changing the data type of a field column.
+    pub fn can_convert_to(&self, target: PhysicalDType) -> bool {
+        matches!(
+            (self, target),
+            (Self::Integer, Self::Float)
+                | (Self::Unsigned, Self::Float)
+                | (Self::Integer, Self::Unsigned)
+                | (Self::Unsigned, Self::Integer)
+                | (Self::Float, Self::String)
+                | (Self::Integer, Self::String)
+                | (Self::Unsigned, Self::String)
+                | (Self::Boolean, Self::String)
+        ) || *self == target
+    }
+}
+
 impl Display for PhysicalDType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -86,7 +111,9 @@ impl From<protos::models::FieldType> for PhysicalDType {
             protos::models::FieldType::Integer => PhysicalDType::Integer,
             protos::models::FieldType::Unsigned => PhysicalDType::Unsigned,
             protos::models::FieldType::Boolean => PhysicalDType::Boolean,
-            protos::models::FieldType::String => PhysicalDType::String,
+            protos::models::FieldType::String | protos::models::FieldType::Bytes => {
+                PhysicalDType::String
+            }
             _ => PhysicalDType::Unknown,
         }
     }
@@ -1,16 +1,21 @@
 use std::collections::HashMap;
 
 use datafusion::arrow::array::{
-    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray,
-    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray, UInt64Array,
+    Array, ArrayRef, BinaryArray, BooleanArray, Decimal128Array, Float64Array, Int64Array,
+    StringArray, TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    UInt64Array,
 };
 use datafusion::arrow::datatypes::{SchemaRef, TimeUnit};
 use flatbuffers::{FlatBufferBuilder, WIPOffset};
-use models::schema::{PhysicalCType as ColumnType, TskvTableSchemaRef};
-use models::PhysicalDType as ValueType;
+use models::decimal::Decimal;
+use models::schema::{
+    ColumnType as LogicalColumnType, PhysicalCType as ColumnType, TskvTableSchema,
+    TskvTableSchemaRef,
+};
+use models::{PhysicalDType as ValueType, ValueType as LogicalValueType};
 use protos::models::{
-    Column as FbColumn, ColumnBuilder, ColumnType as FbColumnType, FieldType, PointsBuilder,
-    TableBuilder, ValuesBuilder,
+    BytesValue, BytesValueArgs, Column as FbColumn, ColumnBuilder, ColumnType as FbColumnType,
+    FieldType, PointsBuilder, TableBuilder, ValuesBuilder,
 };
 use utils::bitset::BitSet;
 
@@ -269,6 +274,41 @@ pub fn line_to_batches(lines: &[Line]) -> Result<HashMap<String, MutableBatch>>
     Ok(batches)
 }
 
+/// Convert values of decimal fields in the line to unscaled integers, other
+/// fields are not changed.
+pub fn convert_decimal_fields(line: &mut Line, table_schema: &TskvTableSchema) -> Result<()> {
+    for (field_key, field_value) in line.fields.iter_mut() {
+        let decimal = match table_schema.column(field_key) {
+            Some(column) => match column.column_type {
+                LogicalColumnType::Field(LogicalValueType::Decimal(decimal)) => decimal,
+                _ => continue,
+            },
+            None => continue,
+        };
+        let unscaled = match field_value {
+            FieldValue::F64(v) => decimal.unscaled_from_f64(*v),
+            FieldValue::I64(v) => decimal.unscaled_from_i64(*v),
+            FieldValue::U64(v) => decimal.unscaled_from_u64(*v),
+            FieldValue::Str(v) => std::str::from_utf8(v)
+                .ok()
+                .and_then(|v| decimal.unscaled_from_str(v)),
+            FieldValue::Bool(_) => None,
+        };
+        match unscaled {
+            Some(v) => *field_value = FieldValue::I64(v),
+            None => {
+                return Err(Error::Common {
+                    content: format!(
+                        "value {:?} of field {} can not be converted to {}",
+                        field_value, field_key, decimal
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn mutable_batches_to_point(db: &str, batches: HashMap<String, MutableBatch>) -> Vec<u8> {
     let fbb = &mut FlatBufferBuilder::new();
     let mut tables = Vec::with_capacity(batches.len());
@@ -368,24 +408,34 @@ pub fn arrow_array_to_points(
         let column_schema = table_schema.column(col_name).ok_or_else(|| Error::Common {
             content: format!("column {} not found in table {}", col_name, table_name),
         })?;
-        let fb_column = match column_schema.column_type.to_physical_type() {
-            ColumnType::Tag => build_string_column(column, col_name, FbColumnType::Tag, &mut fbb)?,
-            ColumnType::Time(ref time_unit) => {
-                build_timestamp_column(column, col_name, time_unit, &mut fbb)?
+        let fb_column = match column_schema.column_type {
+            LogicalColumnType::Field(LogicalValueType::Decimal(ref decimal)) => {
+                build_decimal_column(column, col_name, decimal, &mut fbb)?
             }
-            ColumnType::Field(value_type) => match value_type {
-                ValueType::Unknown => {
-                    return Err(Error::Common {
-                        content: format!("column {} type is unknown", col_name),
-                    })
+            LogicalColumnType::Field(LogicalValueType::Binary) => {
+                build_binary_column(column, col_name, &mut fbb)?
+            }
+            ref column_type => match column_type.to_physical_type() {
+                ColumnType::Tag => {
+                    build_string_column(column, col_name, FbColumnType::Tag, &mut fbb)?
                 }
-                ValueType::Float => build_f64_column(column, col_name, &mut fbb)?,
-                ValueType::Integer => build_i64_column(column, col_name, &mut fbb)?,
-                ValueType::Unsigned => build_u64_column(column, col_name, &mut fbb)?,
-                ValueType::Boolean => build_bool_column(column, col_name, &mut fbb)?,
-                ValueType::String => {
-                    build_string_column(column, col_name, FbColumnType::Field, &mut fbb)?
+                ColumnType::Time(ref time_unit) => {
+                    build_timestamp_column(column, col_name, time_unit, &mut fbb)?
                 }
+                ColumnType::Field(value_type) => match value_type {
+                    ValueType::Unknown => {
+                        return Err(Error::Common {
+                            content: format!("column {} type is unknown", col_name),
+                        })
+                    }
+                    ValueType::Float => build_f64_column(column, col_name, &mut fbb)?,
+                    ValueType::Integer => build_i64_column(column, col_name, &mut fbb)?,
+                    ValueType::Unsigned => build_u64_column(column, col_name, &mut fbb)?,
+                    ValueType::Boolean => build_bool_column(column, col_name, &mut fbb)?,
+                    ValueType::String => {
+                        build_string_column(column, col_name, FbColumnType::Field, &mut fbb)?
+                    }
+                },
             },
         };
         fb_columns.push(fb_column);
@@ -681,3 +731,99 @@ pub fn build_bool_column<'a>(
     column_builder.add_col_values(values);
     Ok(column_builder.finish())
 }
+
+pub fn build_decimal_column<'a>(
+    column: &ArrayRef,
+    col_name: &str,
+    decimal: &Decimal,
+    fbb: &mut FlatBufferBuilder<'a>,
+) -> Result<WIPOffset<FbColumn<'a>>> {
+    let name = fbb.create_string(col_name);
+    let values = column
+        .as_any()
+        .downcast_ref::<Decimal128Array>()
+        .ok_or(Error::Common {
+            content: format!("column {} is not decimal", col_name),
+        })?;
+    if values.scale() != decimal.scale as i8 {
+        return Err(Error::Common {
+            content: format!(
+                "scale of column {} is {}, expected {}",
+                col_name,
+                values.scale(),
+                decimal
+            ),
+        });
+    }
+    let mut nullbits = BitSet::new();
+    let mut col_values = Vec::with_capacity(values.len());
+    for value in values.iter() {
+        if let Some(value) = value {
+            let value = decimal.unscaled_from_i128(value).ok_or(Error::Common {
+                content: format!("value of column {} is out of {}", col_name, decimal),
+            })?;
+            nullbits.append_set(1);
+            col_values.push(value);
+        } else {
+            nullbits.append_unset(1);
+            col_values.push(0);
+        }
+    }
+    let nullbits = fbb.create_vector(nullbits.bytes());
+    let values = fbb.create_vector(&col_values);
+    let mut values_builder = ValuesBuilder::new(fbb);
+    values_builder.add_int_value(values);
+    let values = values_builder.finish();
+    let mut column_builder = ColumnBuilder::new(fbb);
+    column_builder.add_name(name);
+    column_builder.add_column_type(FbColumnType::Field);
+    column_builder.add_field_type(FieldType::Integer);
+    column_builder.add_nullbits(nullbits);
+    column_builder.add_col_values(values);
+    Ok(column_builder.finish())
+}
+
+pub fn build_binary_column<'a>(
+    column: &ArrayRef,
+    col_name: &str,
+    fbb: &mut FlatBufferBuilder<'a>,
+) -> Result<WIPOffset<FbColumn<'a>>> {
+    let name = fbb.create_string(col_name);
+    let values = column
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .ok_or(Error::Common {
+            content: format!("column {} is not binary", col_name),
+        })?;
+    let mut nullbits = BitSet::new();
+    let mut col_values = Vec::with_capacity(values.len());
+    values.iter().for_each(|value| {
+        let value = match value {
+            Some(value) => {
+                nullbits.append_set(1);
+                value
+            }
+            None => {
+                nullbits.append_unset(1);
+                &[]
+            }
+        };
+        let value = fbb.create_vector(value);
+        col_values.push(BytesValue::create(
+            fbb,
+            &BytesValueArgs { value: Some(value) },
+        ));
+    });
+    let nullbits = fbb.create_vector(nullbits.bytes());
+    let values = fbb.create_vector(&col_values);
+    let mut values_builder = ValuesBuilder::new(fbb);
+    values_builder.add_bytes_value(values);
+    let values = values_builder.finish();
+    let mut column_builder = ColumnBuilder::new(fbb);
+    column_builder.add_name(name);
+    column_builder.add_column_type(FbColumnType::Field);
+    column_builder.add_field_type(FieldType::Bytes);
+    column_builder.add_nullbits(nullbits);
+    column_builder.add_col_values(values);
+    Ok(column_builder.finish())
+}
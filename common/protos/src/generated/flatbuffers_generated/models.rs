@@ -21,16 +21,17 @@ pub mod models {
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 pub const ENUM_MIN_FIELD_TYPE: i32 = -1;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
-pub const ENUM_MAX_FIELD_TYPE: i32 = 4;
+pub const ENUM_MAX_FIELD_TYPE: i32 = 5;
 #[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
 #[allow(non_camel_case_types)]
-pub const ENUM_VALUES_FIELD_TYPE: [FieldType; 6] = [
+pub const ENUM_VALUES_FIELD_TYPE: [FieldType; 7] = [
   FieldType::Unknown,
   FieldType::Float,
   FieldType::Integer,
   FieldType::Unsigned,
   FieldType::Boolean,
   FieldType::String,
+  FieldType::Bytes,
 ];
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -44,9 +45,10 @@ impl FieldType {
   pub const Unsigned: Self = Self(2);
   pub const Boolean: Self = Self(3);
   pub const String: Self = Self(4);
+  pub const Bytes: Self = Self(5);
 
   pub const ENUM_MIN: i32 = -1;
-  pub const ENUM_MAX: i32 = 4;
+  pub const ENUM_MAX: i32 = 5;
   pub const ENUM_VALUES: &'static [Self] = &[
     Self::Unknown,
     Self::Float,
@@ -54,6 +56,7 @@ impl FieldType {
     Self::Unsigned,
     Self::Boolean,
     Self::String,
+    Self::Bytes,
   ];
   /// Returns the variant's name or "" if unknown.
   pub fn variant_name(self) -> Option<&'static str> {
@@ -64,6 +67,7 @@ impl FieldType {
       Self::Unsigned => Some("Unsigned"),
       Self::Boolean => Some("Boolean"),
       Self::String => Some("String"),
+      Self::Bytes => Some("Bytes"),
       _ => None,
     }
   }
@@ -313,6 +317,107 @@ impl core::fmt::Debug for PingBody<'_> {
       ds.finish()
   }
 }
+pub enum BytesValueOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct BytesValue<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for BytesValue<'a> {
+  type Inner = BytesValue<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> BytesValue<'a> {
+  pub const VT_VALUE: flatbuffers::VOffsetT = 4;
+
+  pub const fn get_fully_qualified_name() -> &'static str {
+    "models.BytesValue"
+  }
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    BytesValue { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr>,
+    args: &'args BytesValueArgs<'args>
+  ) -> flatbuffers::WIPOffset<BytesValue<'bldr>> {
+    let mut builder = BytesValueBuilder::new(_fbb);
+    if let Some(x) = args.value { builder.add_value(x); }
+    builder.finish()
+  }
+
+
+  #[inline]
+  pub fn value(&self) -> Option<flatbuffers::Vector<'a, u8>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, u8>>>(BytesValue::VT_VALUE, None)}
+  }
+}
+
+impl flatbuffers::Verifiable for BytesValue<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, u8>>>("value", Self::VT_VALUE, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct BytesValueArgs<'a> {
+    pub value: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, u8>>>,
+}
+impl<'a> Default for BytesValueArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    BytesValueArgs {
+      value: None,
+    }
+  }
+}
+
+pub struct BytesValueBuilder<'a: 'b, 'b> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b> BytesValueBuilder<'a, 'b> {
+  #[inline]
+  pub fn add_value(&mut self, value: flatbuffers::WIPOffset<flatbuffers::Vector<'b , u8>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(BytesValue::VT_VALUE, value);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> BytesValueBuilder<'a, 'b> {
+    let start = _fbb.start_table();
+    BytesValueBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<BytesValue<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for BytesValue<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("BytesValue");
+      ds.field("value", &self.value());
+      ds.finish()
+  }
+}
 pub enum ValuesOffset {}
 #[derive(Copy, Clone, PartialEq)]
 
@@ -334,6 +439,7 @@ impl<'a> Values<'a> {
   pub const VT_UINT_VALUE: flatbuffers::VOffsetT = 8;
   pub const VT_BOOL_VALUE: flatbuffers::VOffsetT = 10;
   pub const VT_STRING_VALUE: flatbuffers::VOffsetT = 12;
+  pub const VT_BYTES_VALUE: flatbuffers::VOffsetT = 14;
 
   pub const fn get_fully_qualified_name() -> &'static str {
     "models.Values"
@@ -349,6 +455,7 @@ impl<'a> Values<'a> {
     args: &'args ValuesArgs<'args>
   ) -> flatbuffers::WIPOffset<Values<'bldr>> {
     let mut builder = ValuesBuilder::new(_fbb);
+    if let Some(x) = args.bytes_value { builder.add_bytes_value(x); }
     if let Some(x) = args.string_value { builder.add_string_value(x); }
     if let Some(x) = args.bool_value { builder.add_bool_value(x); }
     if let Some(x) = args.uint_value { builder.add_uint_value(x); }
@@ -393,6 +500,13 @@ impl<'a> Values<'a> {
     // which contains a valid value in this slot
     unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>>>(Values::VT_STRING_VALUE, None)}
   }
+  #[inline]
+  pub fn bytes_value(&self) -> Option<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<BytesValue<'a>>>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<BytesValue>>>>(Values::VT_BYTES_VALUE, None)}
+  }
 }
 
 impl flatbuffers::Verifiable for Values<'_> {
@@ -407,6 +521,7 @@ impl flatbuffers::Verifiable for Values<'_> {
      .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, u64>>>("uint_value", Self::VT_UINT_VALUE, false)?
      .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, bool>>>("bool_value", Self::VT_BOOL_VALUE, false)?
      .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<&'_ str>>>>("string_value", Self::VT_STRING_VALUE, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<flatbuffers::Vector<'_, flatbuffers::ForwardsUOffset<BytesValue>>>>("bytes_value", Self::VT_BYTES_VALUE, false)?
      .finish();
     Ok(())
   }
@@ -417,6 +532,7 @@ pub struct ValuesArgs<'a> {
     pub uint_value: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, u64>>>,
     pub bool_value: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, bool>>>,
     pub string_value: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<&'a str>>>>,
+    pub bytes_value: Option<flatbuffers::WIPOffset<flatbuffers::Vector<'a, flatbuffers::ForwardsUOffset<BytesValue<'a>>>>>,
 }
 impl<'a> Default for ValuesArgs<'a> {
   #[inline]
@@ -427,6 +543,7 @@ impl<'a> Default for ValuesArgs<'a> {
       uint_value: None,
       bool_value: None,
       string_value: None,
+      bytes_value: None,
     }
   }
 }
@@ -457,6 +574,10 @@ impl<'a: 'b, 'b> ValuesBuilder<'a, 'b> {
     self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Values::VT_STRING_VALUE, string_value);
   }
   #[inline]
+  pub fn add_bytes_value(&mut self, bytes_value: flatbuffers::WIPOffset<flatbuffers::Vector<'b , flatbuffers::ForwardsUOffset<BytesValue<'b >>>>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Values::VT_BYTES_VALUE, bytes_value);
+  }
+  #[inline]
   pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a>) -> ValuesBuilder<'a, 'b> {
     let start = _fbb.start_table();
     ValuesBuilder {
@@ -479,6 +600,7 @@ impl core::fmt::Debug for Values<'_> {
       ds.field("uint_value", &self.uint_value());
       ds.field("bool_value", &self.bool_value());
       ds.field("string_value", &self.string_value());
+      ds.field("bytes_value", &self.bytes_value());
       ds.finish()
   }
 }
@@ -167,12 +167,115 @@ pub struct AlterColumnRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteFromTableRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub table: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "3")]
+    pub predicate: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BackupVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "3")]
+    pub location: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub backup_vnode_id: u32,
+    #[prost(string, tag = "4")]
+    pub location: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RenameTableRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub table: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub new_name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RenameDatabaseRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub new_name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    /// All tables if it's empty
+    #[prost(string, tag = "3")]
+    pub table: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub location: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "3")]
+    pub table: ::prost::alloc::string::String,
+    /// Rows encoded in arrow IPC format
+    #[prost(bytes = "vec", tag = "4")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SplitVnodeRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(uint32, tag = "3")]
+    pub new_vnode_id: u32,
+    /// Series whose hash is in \[hash_start, hash_end\] are moved into the new vnode
+    #[prost(uint64, tag = "4")]
+    pub hash_start: u64,
+    #[prost(uint64, tag = "5")]
+    pub hash_end: u64,
+    /// Remove the moved series from the vnode after they are copied
+    #[prost(bool, tag = "6")]
+    pub remove_moved_series: bool,
+}
+/// Change the write rate limits of the node, 0 means unlimited.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetWriteRateLimitRequest {
+    #[prost(uint64, optional, tag = "1")]
+    pub compact_write_rate_limit: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "2")]
+    pub flush_write_rate_limit: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AdminCommandRequest {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
     #[prost(
         oneof = "admin_command_request::Command",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19"
     )]
     pub command: ::core::option::Option<admin_command_request::Command>,
 }
@@ -199,6 +302,24 @@ pub mod admin_command_request {
         AddColumn(super::AddColumnRequest),
         #[prost(message, tag = "10")]
         AlterColumn(super::AlterColumnRequest),
+        #[prost(message, tag = "11")]
+        DeleteFromTable(super::DeleteFromTableRequest),
+        #[prost(message, tag = "12")]
+        BackupVnode(super::BackupVnodeRequest),
+        #[prost(message, tag = "13")]
+        RestoreVnode(super::RestoreVnodeRequest),
+        #[prost(message, tag = "14")]
+        RenameTable(super::RenameTableRequest),
+        #[prost(message, tag = "15")]
+        RenameDb(super::RenameDatabaseRequest),
+        #[prost(message, tag = "16")]
+        ExportVnode(super::ExportVnodeRequest),
+        #[prost(message, tag = "17")]
+        ImportVnode(super::ImportVnodeRequest),
+        #[prost(message, tag = "18")]
+        SplitVnode(super::SplitVnodeRequest),
+        #[prost(message, tag = "19")]
+        SetWriteRateLimit(super::SetWriteRateLimitRequest),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -209,10 +330,33 @@ pub struct FetchVnodeChecksumRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchSeriesCardinalityRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    /// All tables if it's empty
+    #[prost(string, tag = "3")]
+    pub table: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchTagValuesCardinalityRequest {
+    #[prost(string, tag = "1")]
+    pub db: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(string, tag = "3")]
+    pub table: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "4")]
+    pub tag_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AdminFetchCommandRequest {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
-    #[prost(oneof = "admin_fetch_command_request::Command", tags = "8")]
+    #[prost(oneof = "admin_fetch_command_request::Command", tags = "8, 9, 10")]
     pub command: ::core::option::Option<admin_fetch_command_request::Command>,
 }
 /// Nested message and enum types in `AdminFetchCommandRequest`.
@@ -222,6 +366,10 @@ pub mod admin_fetch_command_request {
     pub enum Command {
         #[prost(message, tag = "8")]
         FetchVnodeChecksum(super::FetchVnodeChecksumRequest),
+        #[prost(message, tag = "9")]
+        FetchSeriesCardinality(super::FetchSeriesCardinalityRequest),
+        #[prost(message, tag = "10")]
+        FetchTagValuesCardinality(super::FetchTagValuesCardinalityRequest),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
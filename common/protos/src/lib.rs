@@ -6,10 +6,11 @@ pub mod test_helper;
 
 use std::fmt::{Display, Formatter};
 
-use crate::models::{Column, Points, Table};
 use flatbuffers::{ForwardsUOffset, Vector};
 use snafu::Snafu;
 
+use crate::models::{BytesValue, Column, Points, Table};
+
 type PointsResult<T> = Result<T, PointsError>;
 
 #[derive(Debug, Snafu)]
@@ -109,6 +110,25 @@ impl<'a> Column<'a> {
         Ok(values)
     }
 
+    pub fn bytes_values_len(&self) -> PointsResult<usize> {
+        let len = self
+            .col_values()
+            .ok_or(PointsError::ColumnMissingValues)?
+            .bytes_value()
+            .map(|v| v.len())
+            .unwrap_or(0);
+        Ok(len)
+    }
+
+    pub fn bytes_values(&self) -> PointsResult<Vector<ForwardsUOffset<BytesValue>>> {
+        let values = self
+            .col_values()
+            .ok_or(PointsError::ColumnMissingValues)?
+            .bytes_value()
+            .unwrap_or_default();
+        Ok(values)
+    }
+
     pub fn bool_values_len(&self) -> PointsResult<usize> {
         let len = self
             .col_values()
@@ -238,9 +258,10 @@ impl<'a> Display for Table<'a> {
 
 #[cfg(test)]
 pub mod test {
-    use flatbuffers::FlatBufferBuilder;
     use std::collections::HashMap;
 
+    use flatbuffers::FlatBufferBuilder;
+
     use crate::models::{FieldType, Points};
     use crate::models_helper::create_const_points;
 
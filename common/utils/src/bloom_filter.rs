@@ -4,16 +4,36 @@ use crate::bkdr_hash::BkdrHasher;
 pub struct BloomFilter {
     b: Vec<u8>,
     mask: u64,
+    /// Number of hash functions.
+    k: u32,
 }
 
 impl BloomFilter {
-    /// Create a new instance of BloomFilter using m bits.
+    pub const MAX_HASHES: u32 = 16;
+
+    /// Create a new instance of BloomFilter using m bits and 1 hash function.
     /// The m should be a power of 2.
     pub fn new(m: u64) -> Self {
         let m = Self::pow2(m);
         let l = m as usize >> 3;
         let b: Vec<u8> = vec![0; l];
-        Self { b, mask: m - 1 }
+        Self {
+            b,
+            mask: m - 1,
+            k: 1,
+        }
+    }
+
+    /// Create a new instance of BloomFilter for n items with the false positive
+    /// rate, the number of bits is rounded up to a power of 2.
+    pub fn with_capacity(n: usize, fp_rate: f64) -> Self {
+        let n = n.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let m = (-n * fp_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let mut bloom_filter = Self::new(m);
+        let k = ((bloom_filter.mask + 1) as f64 / n * ln2).round() as u32;
+        bloom_filter.k = k.clamp(1, Self::MAX_HASHES);
+        bloom_filter
     }
 
     /// Similar to `new()`
@@ -25,19 +45,39 @@ impl BloomFilter {
         }
         let m = l << 3;
 
-        Self { b, mask: m - 1 }
+        Self {
+            b,
+            mask: m - 1,
+            k: 1,
+        }
+    }
+
+    /// Similar to `with_data()`, using k hash functions.
+    pub fn with_data_and_hashes(data: &[u8], k: u32) -> Self {
+        let mut bloom_filter = Self::with_data(data);
+        bloom_filter.k = k.clamp(1, Self::MAX_HASHES);
+        bloom_filter
     }
 
     pub fn insert(&mut self, data: &[u8]) {
         let hash = Self::hash(data);
-        let loc = self.location(hash);
-        self.b[loc >> 3] |= 1 << (loc & 7);
+        for i in 0..self.k {
+            let loc = self.location(hash, i);
+            self.b[loc >> 3] |= 1 << (loc & 7);
+        }
     }
 
     pub fn contains(&self, data: &[u8]) -> bool {
         let hash = Self::hash(data);
-        let loc = self.location(hash);
-        self.b[loc >> 3] & (1 << (loc & 7)) != 0
+        (0..self.k).all(|i| {
+            let loc = self.location(hash, i);
+            self.b[loc >> 3] & (1 << (loc & 7)) != 0
+        })
+    }
+
+    /// Returns the number of hash functions.
+    pub fn hashes(&self) -> u32 {
+        self.k
     }
 
     pub fn len(&self) -> usize {
@@ -52,8 +92,11 @@ impl BloomFilter {
         &self.b
     }
 
-    fn location(&self, hash: u64) -> usize {
-        (hash & self.mask) as usize
+    /// Location of the i-th hash function by double hashing, the first one is
+    /// the same as filters with only 1 hash function.
+    fn location(&self, hash: u64, i: u32) -> usize {
+        let delta = hash.wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(31) | 1;
+        (hash.wrapping_add(delta.wrapping_mul(i as u64)) & self.mask) as usize
     }
 
     fn hash(data: &[u8]) -> u64 {
@@ -95,4 +138,25 @@ mod test {
         let v2 = 11_u64.to_be_bytes();
         assert!(!bloom_filter.contains(&v2));
     }
+
+    #[test]
+    fn test_bloom_filter_with_capacity() {
+        let mut bloom_filter = BloomFilter::with_capacity(10000, 0.01);
+        assert_eq!(bloom_filter.len(), 16384);
+        assert_eq!(bloom_filter.hashes(), 9);
+        for i in 0..10000_u64 {
+            bloom_filter.insert(&i.to_be_bytes());
+        }
+        for i in 0..10000_u64 {
+            assert!(bloom_filter.contains(&i.to_be_bytes()));
+        }
+        let false_positives = (10000..20000_u64)
+            .filter(|i| bloom_filter.contains(&i.to_be_bytes()))
+            .count();
+        assert!(false_positives < 200, "false positives: {false_positives}");
+
+        let decoded =
+            BloomFilter::with_data_and_hashes(bloom_filter.bytes(), bloom_filter.hashes());
+        assert_eq!(decoded, bloom_filter);
+    }
 }
@@ -0,0 +1,175 @@
+/// HyperLogLog sketch to estimate the number of distinct items.
+///
+/// Sketches with the same precision can be merged, items inserted into
+/// several sketches are counted once in the merged sketch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// 4096 registers, the standard error is about 1.6%.
+    pub const DEFAULT_PRECISION: u8 = 12;
+    pub const MIN_PRECISION: u8 = 4;
+    pub const MAX_PRECISION: u8 = 16;
+
+    /// Create a new instance of HyperLogLog using 2^precision registers,
+    /// the precision should be in range [4, 16].
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision),
+            "precision of HyperLogLog should be in range [4, 16]"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn insert(&mut self, data: &[u8]) {
+        let hash = Self::hash(data);
+        let p = self.precision as u32;
+        let index = (hash >> (64 - p)) as usize;
+        // The lowest bit set makes rank not greater than 64 - p + 1.
+        let rank = ((hash << p) | (1 << (p - 1))).leading_zeros() as u8 + 1;
+        if self.registers[index] < rank {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Merge another sketch into this one, returns false if precisions
+    /// of the sketches are different, and nothing is merged.
+    pub fn merge(&mut self, other: &Self) -> bool {
+        if self.precision != other.precision {
+            return false;
+        }
+        for (r, o) in self.registers.iter_mut().zip(other.registers.iter()) {
+            if *r < *o {
+                *r = *o;
+            }
+        }
+        true
+    }
+
+    /// Returns the estimated number of distinct items.
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let mut sum = 0_f64;
+        let mut zeros = 0_usize;
+        for r in self.registers.iter() {
+            sum += 1.0 / (1_u64 << *r) as f64;
+            if *r == 0 {
+                zeros += 1;
+            }
+        }
+        let raw = alpha * m * m / sum;
+        // Use linear counting for small cardinalities, the large range correction
+        // is not needed for 64-bit hash.
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|r| *r == 0)
+    }
+
+    /// Returns bytes of the precision followed by registers.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(1 + self.registers.len());
+        buf.push(self.precision);
+        buf.extend_from_slice(&self.registers);
+        buf
+    }
+
+    /// Decode bytes returned by `encode()`, returns None if it's invalid.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let (precision, registers) = data.split_first()?;
+        if !(Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(precision)
+            || registers.len() != 1 << precision
+        {
+            return None;
+        }
+        Some(Self {
+            precision: *precision,
+            registers: registers.to_vec(),
+        })
+    }
+
+    /// FNV-1a hash with the finalizer of MurmurHash3 to mix bits.
+    fn hash(data: &[u8]) -> u64 {
+        let mut h: u64 = 0xcbf29ce484222325;
+        for b in data {
+            h ^= *b as u64;
+            h = h.wrapping_mul(0x100000001b3);
+        }
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51afd7ed558ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+        h ^= h >> 33;
+        h
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PRECISION)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HyperLogLog;
+
+    fn assert_estimate(hll: &HyperLogLog, expected: u64) {
+        let estimate = hll.estimate();
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(error < 0.05, "estimate {estimate}, expected {expected}");
+    }
+
+    #[test]
+    fn test_hyper_log_log() {
+        let mut hll = HyperLogLog::default();
+        assert!(hll.is_empty());
+        assert_eq!(hll.estimate(), 0);
+
+        for i in 0..10_u64 {
+            hll.insert(format!("host_{i}").as_bytes());
+            hll.insert(format!("host_{i}").as_bytes());
+        }
+        assert_eq!(hll.estimate(), 10);
+
+        let mut hll_1 = HyperLogLog::default();
+        let mut hll_2 = HyperLogLog::default();
+        for i in 0..100_000_u64 {
+            hll_1.insert(&i.to_be_bytes());
+        }
+        for i in 50_000..150_000_u64 {
+            hll_2.insert(&i.to_be_bytes());
+        }
+        assert_estimate(&hll_1, 100_000);
+        assert!(hll_1.merge(&hll_2));
+        assert_estimate(&hll_1, 150_000);
+        assert!(!hll_1.merge(&HyperLogLog::new(10)));
+
+        let hll_3 = HyperLogLog::decode(&hll_1.encode()).unwrap();
+        assert_eq!(hll_3, hll_1);
+        assert!(HyperLogLog::decode(&[12, 0, 0]).is_none());
+        assert!(HyperLogLog::decode(&[]).is_none());
+    }
+}
@@ -1,12 +1,14 @@
 pub use bkdr_hash::BkdrHasher;
 pub use bloom_filter::BloomFilter;
 pub use dedup::{dedup_front_by, dedup_front_by_key};
+pub use hyper_log_log::HyperLogLog;
 
 pub mod backtrace;
 pub mod bitset;
 mod bkdr_hash;
 mod bloom_filter;
 mod dedup;
+mod hyper_log_log;
 
 #[cfg(unix)]
 pub mod pprof_tools;
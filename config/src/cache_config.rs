@@ -13,6 +13,13 @@ pub struct CacheConfig {
     pub max_immutable_number: u16,
     #[serde(default = "CacheConfig::default_partitions")]
     pub partition: usize,
+    #[serde(default = "CacheConfig::default_last_value_cache_max_series")]
+    pub last_value_cache_max_series: usize,
+    #[serde(
+        with = "bytes_num",
+        default = "CacheConfig::default_data_block_cache_size"
+    )]
+    pub data_block_cache_size: u64,
 }
 
 impl CacheConfig {
@@ -27,6 +34,14 @@ impl CacheConfig {
         num_cpus::get()
     }
 
+    fn default_last_value_cache_max_series() -> usize {
+        100_000
+    }
+
+    fn default_data_block_cache_size() -> u64 {
+        128 * 1024 * 1024
+    }
+
     pub fn override_by_env(&mut self) {
         if let Ok(size) = std::env::var("CNOSDB_CACHE_MAX_BUFFER_SIZE") {
             self.max_buffer_size = size.parse::<u64>().unwrap();
@@ -37,6 +52,12 @@ impl CacheConfig {
         if let Ok(size) = std::env::var("CNOSDB_CACHE_PARTITIONS") {
             self.partition = size.parse::<usize>().unwrap();
         }
+        if let Ok(size) = std::env::var("CNOSDB_CACHE_LAST_VALUE_CACHE_MAX_SERIES") {
+            self.last_value_cache_max_series = size.parse::<usize>().unwrap();
+        }
+        if let Ok(size) = std::env::var("CNOSDB_CACHE_DATA_BLOCK_CACHE_SIZE") {
+            self.data_block_cache_size = size.parse::<u64>().unwrap();
+        }
     }
 }
 
@@ -46,6 +67,8 @@ impl Default for CacheConfig {
             max_buffer_size: Self::default_max_buffer_size(),
             max_immutable_number: Self::default_max_immutable_number(),
             partition: Self::default_partitions(),
+            last_value_cache_max_series: Self::default_last_value_cache_max_series(),
+            data_block_cache_size: Self::default_data_block_cache_size(),
         }
     }
 }
@@ -75,7 +98,7 @@ impl CheckConfig for CacheConfig {
 
         if self.partition > 1024 {
             ret.add_warn(CheckConfigItemResult {
-                config: config_name,
+                config: config_name.clone(),
                 item: "partition".to_string(),
                 message: "'partition' maybe too big(more than 1024)".to_string(),
             });
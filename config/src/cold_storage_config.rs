@@ -0,0 +1,163 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::{bytes_num, duration};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ColdStorageConfig {
+    /// Url of the object store, disabled if empty,
+    /// e.g. 'file:///var/lib/cnosdb/cold', 's3://bucket/path'.
+    #[serde(default = "ColdStorageConfig::default_url")]
+    pub url: String,
+
+    #[serde(default = "Default::default")]
+    pub endpoint: Option<String>,
+
+    #[serde(default = "Default::default")]
+    pub region: Option<String>,
+
+    #[serde(default = "Default::default")]
+    pub access_key_id: Option<String>,
+
+    #[serde(default = "Default::default")]
+    pub secret_access_key: Option<String>,
+
+    #[serde(default = "ColdStorageConfig::default_allow_http")]
+    pub allow_http: bool,
+
+    #[serde(
+        with = "duration",
+        default = "ColdStorageConfig::default_check_interval"
+    )]
+    pub check_interval: Duration,
+
+    #[serde(with = "bytes_num", default = "ColdStorageConfig::default_block_size")]
+    pub block_size: u64,
+
+    #[serde(
+        with = "bytes_num",
+        default = "ColdStorageConfig::default_block_cache_size"
+    )]
+    pub block_cache_size: u64,
+}
+
+impl ColdStorageConfig {
+    fn default_url() -> String {
+        "".to_string()
+    }
+
+    fn default_allow_http() -> bool {
+        false
+    }
+
+    fn default_check_interval() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn default_block_size() -> u64 {
+        1024 * 1024
+    }
+
+    fn default_block_cache_size() -> u64 {
+        256 * 1024 * 1024
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.url.is_empty()
+    }
+
+    pub fn override_by_env(&mut self) {
+        if let Ok(url) = std::env::var("CNOSDB_COLD_STORAGE_URL") {
+            self.url = url;
+        }
+        if let Ok(endpoint) = std::env::var("CNOSDB_COLD_STORAGE_ENDPOINT") {
+            self.endpoint = Some(endpoint);
+        }
+        if let Ok(region) = std::env::var("CNOSDB_COLD_STORAGE_REGION") {
+            self.region = Some(region);
+        }
+        if let Ok(key) = std::env::var("CNOSDB_COLD_STORAGE_ACCESS_KEY_ID") {
+            self.access_key_id = Some(key);
+        }
+        if let Ok(key) = std::env::var("CNOSDB_COLD_STORAGE_SECRET_ACCESS_KEY") {
+            self.secret_access_key = Some(key);
+        }
+        if let Ok(allow) = std::env::var("CNOSDB_COLD_STORAGE_ALLOW_HTTP") {
+            self.allow_http = allow.parse::<bool>().unwrap();
+        }
+        if let Ok(dur) = std::env::var("CNOSDB_COLD_STORAGE_CHECK_INTERVAL") {
+            self.check_interval = duration::parse_duration(&dur).unwrap();
+        }
+        if let Ok(size) = std::env::var("CNOSDB_COLD_STORAGE_BLOCK_SIZE") {
+            self.block_size = size.parse::<u64>().unwrap();
+        }
+        if let Ok(size) = std::env::var("CNOSDB_COLD_STORAGE_BLOCK_CACHE_SIZE") {
+            self.block_cache_size = size.parse::<u64>().unwrap();
+        }
+    }
+}
+
+impl Default for ColdStorageConfig {
+    fn default() -> Self {
+        Self {
+            url: Self::default_url(),
+            endpoint: None,
+            region: None,
+            access_key_id: None,
+            secret_access_key: None,
+            allow_http: Self::default_allow_http(),
+            check_interval: Self::default_check_interval(),
+            block_size: Self::default_block_size(),
+            block_cache_size: Self::default_block_cache_size(),
+        }
+    }
+}
+
+impl CheckConfig for ColdStorageConfig {
+    fn check(&self, _: &crate::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("cold_storage".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.is_enabled()
+            && !["file://", "s3://", "memory://"]
+                .iter()
+                .any(|scheme| self.url.starts_with(scheme))
+        {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "url".to_string(),
+                message: "'url' should start with 'file://', 's3://' or 'memory://'".to_string(),
+            });
+        }
+        if self.block_size < 64 * 1024 {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "block_size".to_string(),
+                message: "'block_size' maybe too small(less than 64K)".to_string(),
+            });
+        }
+        if self.block_cache_size < self.block_size {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "block_cache_size".to_string(),
+                message: "'block_cache_size' should not be less than 'block_size'".to_string(),
+            });
+        }
+        if self.check_interval < Duration::from_secs(1) {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name,
+                item: "check_interval".to_string(),
+                message: "'check_interval' maybe too small(less than 1 second)".to_string(),
+            });
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 pub use crate::cache_config::*;
 pub use crate::cluster_config::*;
+pub use crate::cold_storage_config::*;
 pub use crate::deployment_config::*;
 pub use crate::heartbeat_config::*;
 pub use crate::hinted_off_config::*;
@@ -24,6 +25,7 @@ mod cache_config;
 mod check;
 mod cluster_config;
 mod codec;
+mod cold_storage_config;
 mod deployment_config;
 mod heartbeat_config;
 mod hinted_off_config;
@@ -66,6 +68,10 @@ pub struct Config {
     #[serde(default = "Default::default")]
     pub cache: CacheConfig,
 
+    ///
+    #[serde(default = "Default::default")]
+    pub cold_storage: ColdStorageConfig,
+
     ///
     #[serde(default = "Default::default")]
     pub log: LogConfig,
@@ -104,6 +110,7 @@ impl Default for Config {
             storage: Default::default(),
             wal: Default::default(),
             cache: Default::default(),
+            cold_storage: Default::default(),
             log: Default::default(),
             security: Default::default(),
             cluster: Default::default(),
@@ -129,6 +136,7 @@ impl Config {
         self.storage.override_by_env();
         self.wal.override_by_env();
         self.cache.override_by_env();
+        self.cold_storage.override_by_env();
         self.query.override_by_env();
         self.node_basic.override_by_env();
     }
@@ -214,6 +222,9 @@ pub fn check_config(path: impl AsRef<Path>, show_warnings: bool) {
             if let Some(c) = cfg.cache.check(&cfg) {
                 check_results.add_all(c)
             }
+            if let Some(c) = cfg.cold_storage.check(&cfg) {
+                check_results.add_all(c)
+            }
             if let Some(c) = cfg.log.check(&cfg) {
                 check_results.add_all(c)
             }
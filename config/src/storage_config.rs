@@ -49,6 +49,24 @@ pub struct StorageConfig {
 
     #[serde(default = "StorageConfig::default_strict_write")]
     pub strict_write: bool,
+
+    #[serde(
+        with = "bytes_num",
+        default = "StorageConfig::default_compact_write_rate_limit"
+    )]
+    pub compact_write_rate_limit: u64,
+
+    #[serde(
+        with = "bytes_num",
+        default = "StorageConfig::default_flush_write_rate_limit"
+    )]
+    pub flush_write_rate_limit: u64,
+
+    #[serde(default = "StorageConfig::default_io_backend")]
+    pub io_backend: String,
+
+    #[serde(default = "StorageConfig::default_direct_io")]
+    pub direct_io: bool,
 }
 
 impl StorageConfig {
@@ -96,6 +114,22 @@ impl StorageConfig {
         false
     }
 
+    fn default_compact_write_rate_limit() -> u64 {
+        0
+    }
+
+    fn default_flush_write_rate_limit() -> u64 {
+        0
+    }
+
+    fn default_io_backend() -> String {
+        "blocking".to_string()
+    }
+
+    fn default_direct_io() -> bool {
+        false
+    }
+
     pub fn override_by_env(&mut self) {
         if let Ok(path) = std::env::var("CNOSDB_APPLICATION_PATH") {
             self.path = path;
@@ -127,6 +161,18 @@ impl StorageConfig {
         if let Ok(size) = std::env::var("CNOSDB_STORAGE_STRICT_WRITE") {
             self.strict_write = size.parse::<bool>().unwrap();
         }
+        if let Ok(size) = std::env::var("CNOSDB_STORAGE_COMPACT_WRITE_RATE_LIMIT") {
+            self.compact_write_rate_limit = size.parse::<u64>().unwrap();
+        }
+        if let Ok(size) = std::env::var("CNOSDB_STORAGE_FLUSH_WRITE_RATE_LIMIT") {
+            self.flush_write_rate_limit = size.parse::<u64>().unwrap();
+        }
+        if let Ok(backend) = std::env::var("CNOSDB_STORAGE_IO_BACKEND") {
+            self.io_backend = backend;
+        }
+        if let Ok(direct_io) = std::env::var("CNOSDB_STORAGE_DIRECT_IO") {
+            self.direct_io = direct_io.parse::<bool>().unwrap();
+        }
 
         self.introspect();
     }
@@ -152,6 +198,10 @@ impl Default for StorageConfig {
             max_compact_size: Self::default_max_compact_size(),
             max_concurrent_compaction: Self::default_max_concurrent_compaction(),
             strict_write: Self::default_strict_write(),
+            compact_write_rate_limit: Self::default_compact_write_rate_limit(),
+            flush_write_rate_limit: Self::default_flush_write_rate_limit(),
+            io_backend: Self::default_io_backend(),
+            direct_io: Self::default_direct_io(),
         }
     }
 }
@@ -206,11 +256,35 @@ impl CheckConfig for StorageConfig {
         }
         if self.max_compact_size < 1024 * 1024 {
             ret.add_warn(CheckConfigItemResult {
-                config: config_name,
+                config: config_name.clone(),
                 item: "max_compact_size".to_string(),
                 message: "'max_compact_size' maybe too small(less than 1M)".to_string(),
             });
         }
+        if self.compact_write_rate_limit != 0 && self.compact_write_rate_limit < 1024 * 1024 {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "compact_write_rate_limit".to_string(),
+                message: "'compact_write_rate_limit' maybe too small(less than 1M)".to_string(),
+            });
+        }
+        if self.flush_write_rate_limit != 0 && self.flush_write_rate_limit < 1024 * 1024 {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "flush_write_rate_limit".to_string(),
+                message: "'flush_write_rate_limit' maybe too small(less than 1M)".to_string(),
+            });
+        }
+        if !matches!(
+            self.io_backend.to_ascii_lowercase().as_str(),
+            "blocking" | "io_uring"
+        ) {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "io_backend".to_string(),
+                message: "'io_backend' should be 'blocking' or 'io_uring'".to_string(),
+            });
+        }
 
         if ret.is_empty() {
             None
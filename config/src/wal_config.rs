@@ -31,6 +31,10 @@ pub struct WalConfig {
 
     #[serde(with = "duration", default = "WalConfig::default_sync_interval")]
     pub sync_interval: Duration,
+
+    /// Compression of WAL write entries, one of 'none', 'snappy' or 'zstd'.
+    #[serde(default = "WalConfig::default_compression")]
+    pub compression: String,
 }
 
 impl WalConfig {
@@ -62,6 +66,10 @@ impl WalConfig {
         Duration::from_secs(0)
     }
 
+    fn default_compression() -> String {
+        "zstd".to_string()
+    }
+
     pub fn override_by_env(&mut self) {
         if let Ok(enabled) = std::env::var("CNOSDB_WAL_ENABLED") {
             self.enabled = enabled.as_str() == "true";
@@ -75,6 +83,9 @@ impl WalConfig {
         if let Ok(sync) = std::env::var("CNOSDB_WAL_SYNC") {
             self.sync = sync.as_str() == sync;
         }
+        if let Ok(compression) = std::env::var("CNOSDB_WAL_COMPRESSION") {
+            self.compression = compression;
+        }
     }
 
     pub fn introspect(&mut self) {
@@ -93,6 +104,7 @@ impl Default for WalConfig {
             flush_trigger_total_file_size: Self::default_flush_trigger_total_file_size(),
             sync: Self::default_sync(),
             sync_interval: Self::default_sync_interval(),
+            compression: Self::default_compression(),
         }
     }
 }
@@ -116,6 +128,13 @@ impl CheckConfig for WalConfig {
                 message: "'wal_req_channel_cap' maybe too small(less than 16)".to_string(),
             });
         }
+        if !["none", "snappy", "zstd"].contains(&self.compression.to_lowercase().as_str()) {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "compression".to_string(),
+                message: "'compression' should be one of 'none', 'snappy' or 'zstd'".to_string(),
+            });
+        }
         if self.sync_interval.as_nanos() < Duration::from_secs(1).as_nanos() {
             ret.add_warn(CheckConfigItemResult {
                 config: config_name,
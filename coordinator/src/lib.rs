@@ -58,12 +58,30 @@ pub enum VnodeManagerCmdType {
     Drop(u32),
     /// vnode id list
     Compact(Vec<u32>),
+    /// vnode id
+    Split(u32),
+}
+
+#[derive(Debug, Clone)]
+pub enum DatabaseManagerCmdType {
+    /// database name, backup location
+    Backup(String, String),
+    /// database name, backup location
+    Restore(String, String),
+    /// database name, table name (all tables if it's None), export location
+    Export(String, Option<String>, String),
+    /// database name, table name, location of exported files
+    Import(String, String, String),
 }
 
 #[derive(Debug, Clone)]
 pub enum VnodeSummarizerCmdType {
     /// replication set id
     Checksum(u32),
+    /// database name, table name (all tables if it's None)
+    SeriesCardinality(String, Option<String>),
+    /// database name, table name, tag keys
+    TagValuesCardinality(String, String, Vec<String>),
 }
 
 pub fn status_response_to_result(
@@ -129,6 +147,14 @@ pub trait Coordinator: Send + Sync {
         cmd_type: VnodeManagerCmdType,
     ) -> CoordinatorResult<()>;
 
+    /// Backup a database into a location, or restore a database from it;
+    /// export tables of a database into Parquet files, or import rows from them.
+    async fn database_manager(
+        &self,
+        tenant: &str,
+        cmd_type: DatabaseManagerCmdType,
+    ) -> CoordinatorResult<()>;
+
     /// A summarizer to summarize vnode info.
     async fn vnode_summarizer(
         &self,
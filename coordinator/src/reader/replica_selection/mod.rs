@@ -40,8 +40,14 @@ impl DynamicReplicaSelectioner {
 
     /// Select the best replica for reading from the given vnode and its replicas
     pub fn select(&self, shards: Vec<ReplicationSet>) -> CoordinatorResult<Vec<ReplicationSet>> {
-        let (ids, shards): (Vec<ReplicationSetId>, Vec<Vec<VnodeInfo>>) =
-            shards.into_iter().map(|e| (e.id, e.vnodes)).unzip();
+        // Keep other fields of replication sets, such as the hash range.
+        let (sets, shards): (Vec<ReplicationSet>, Vec<Vec<VnodeInfo>>) = shards
+            .into_iter()
+            .map(|mut e| {
+                let vnodes = std::mem::take(&mut e.vnodes);
+                (e, vnodes)
+            })
+            .unzip();
 
         // 1. 过滤掉不可用的副本
         let selected_shards = self.status.select(shards, 3);
@@ -52,8 +58,9 @@ impl DynamicReplicaSelectioner {
         let selected_shards = self.random.select(selected_shards, 2);
 
         let mut selected_replicas = Vec::new();
-        for (i, replicas) in selected_shards.into_iter().enumerate() {
-            selected_replicas.push(ReplicationSet::new(ids[i], replicas));
+        for (mut set, replicas) in sets.into_iter().zip(selected_shards) {
+            set.vnodes = replicas;
+            selected_replicas.push(set);
         }
 
         Ok(selected_replicas)
@@ -7,27 +7,34 @@ use std::vec;
 
 use config::{Config, HintedOffConfig};
 use datafusion::arrow::array::{
-    Array, ArrayRef, Int64Array, StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
-    TimestampNanosecondArray, UInt32Array,
+    new_null_array, Array, ArrayRef, Int64Array, StringArray, StringBuilder,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray, UInt32Array,
+    UInt64Builder,
 };
-use datafusion::arrow::compute::take;
-use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::arrow::compute::{concat_batches, take};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
 use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use meta::error::MetaError;
 use meta::model::{MetaClientRef, MetaRef};
 use metrics::count::U64Counter;
 use metrics::label::Labels;
 use metrics::metric::Metric;
 use metrics::metric_register::MetricsRegister;
-use models::meta_data::{ExpiredBucketInfo, ReplicationSet, ReplicationSetId, VnodeStatus};
+use models::meta_data::{
+    BucketInfo, DatabaseInfo, ExpiredBucketInfo, ReplicationSet, ReplicationSetId, VnodeInfo,
+    VnodeStatus,
+};
 use models::object_reference::ResolvedTable;
 use models::predicate::domain::{ResolvedPredicateRef, TimeRanges};
-use models::record_batch_decode;
 use models::schema::{
-    timestamp_convert, ColumnType, Precision, TskvTableSchemaRef, DEFAULT_CATALOG, TIME_FIELD,
+    make_owner, timestamp_convert, ColumnType, Precision, TskvTableSchemaRef, DEFAULT_CATALOG,
+    TIME_FIELD,
 };
+use models::tag::{sort_tags, tags_hash_id, Tag};
+use models::{record_batch_decode, record_batch_encode};
 use protocol_parser::lines_convert::{
-    arrow_array_to_points, line_to_batches, mutable_batches_to_point,
+    arrow_array_to_points, convert_decimal_fields, line_to_batches, mutable_batches_to_point,
 };
 use protocol_parser::Line;
 use protos::kv_service::admin_command_request::Command::*;
@@ -38,8 +45,10 @@ use tokio::sync::mpsc;
 use tonic::transport::Channel;
 use tower::timeout::Timeout;
 use trace::{debug, error, info, SpanContext, SpanExt, SpanRecorder};
+use tskv::backup::{database_info_key, BackupStorage};
+use tskv::index::cardinality::record_batch_to_cardinality_sketches;
 use tskv::EngineRef;
-use utils::BkdrHasher;
+use utils::{BkdrHasher, HyperLogLog};
 
 use crate::errors::*;
 use crate::hh_queue::HintedOffManager;
@@ -50,13 +59,15 @@ use crate::reader::tag_scan::opener::TemporaryTagScanOpener;
 use crate::reader::{CheckFuture, CheckedCoordinatorRecordBatchStream};
 use crate::writer::PointWriter;
 use crate::{
-    status_response_to_result, Coordinator, QueryOption, SendableCoordinatorRecordBatchStream,
-    VnodeManagerCmdType, VnodeSummarizerCmdType,
+    status_response_to_result, Coordinator, DatabaseManagerCmdType, QueryOption,
+    SendableCoordinatorRecordBatchStream, VnodeManagerCmdType, VnodeSummarizerCmdType,
 };
 
 pub type CoordinatorRef = Arc<dyn Coordinator>;
 
 const USAGE_SCHEMA: &str = "usage_schema";
+/// Max size of rows sent in a request to import rows into a vnode.
+const IMPORT_REQUEST_MAX_SIZE: usize = 32 * 1024 * 1024;
 
 #[derive(Clone)]
 pub struct CoordService {
@@ -278,6 +289,667 @@ impl CoordService {
         status_response_to_result(&response)
     }
 
+    /// Fetch cardinality sketches from the first vnode of each replication set of
+    /// the database, and merge them by name. Returns (name, estimated cardinality)
+    /// sorted by name.
+    async fn fetch_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        command: impl Fn(u32) -> admin_fetch_command_request::Command,
+    ) -> CoordinatorResult<Vec<(String, u64)>> {
+        let meta_client =
+            self.tenant_meta(tenant)
+                .await
+                .ok_or_else(|| CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                })?;
+        let db_info =
+            meta_client
+                .get_db_info(database)?
+                .ok_or_else(|| CoordinatorError::CommonError {
+                    msg: format!("database '{}' not found", database),
+                })?;
+
+        // Other replicas have the same series.
+        let mut req_futures = vec![];
+        for bucket in db_info.buckets.iter() {
+            for repl_set in bucket.shard_group.iter() {
+                let vnode = repl_set
+                    .vnodes
+                    .first()
+                    .ok_or(CoordinatorError::NoValidReplica { id: repl_set.id })?;
+                let cmd = AdminFetchCommandRequest {
+                    tenant: tenant.to_string(),
+                    command: Some(command(vnode.id)),
+                };
+                req_futures.push(self.exec_admin_fetch_command_on_node(vnode.node_id, cmd));
+            }
+        }
+        let record_batches = futures::future::try_join_all(req_futures).await?;
+
+        let mut sketches: HashMap<String, HyperLogLog> = HashMap::new();
+        for record_batch in record_batches.iter() {
+            for (name, sketch) in record_batch_to_cardinality_sketches(record_batch)? {
+                match sketches.get_mut(&name) {
+                    Some(merged) => {
+                        if !merged.merge(&sketch) {
+                            return Err(CoordinatorError::CommonError {
+                                msg: format!(
+                                    "precision of cardinality sketches of '{name}' mismatch"
+                                ),
+                            });
+                        }
+                    }
+                    None => {
+                        sketches.insert(name, sketch);
+                    }
+                }
+            }
+        }
+        let mut cardinalities = sketches
+            .into_iter()
+            .map(|(name, sketch)| (name, sketch.estimate()))
+            .collect::<Vec<_>>();
+        cardinalities.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(cardinalities)
+    }
+
+    async fn backup_database(
+        &self,
+        tenant: &str,
+        database: &str,
+        location: &str,
+    ) -> CoordinatorResult<()> {
+        let meta_client =
+            self.tenant_meta(tenant)
+                .await
+                .ok_or_else(|| CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                })?;
+        let mut db_info =
+            meta_client
+                .get_db_info(database)?
+                .ok_or_else(|| CoordinatorError::CommonError {
+                    msg: format!("database '{}' not found", database),
+                })?;
+
+        // Backup the first vnode of each replication set, other replicas are the same.
+        let mut req_futures = vec![];
+        for bucket in db_info.buckets.iter_mut() {
+            for repl_set in bucket.shard_group.iter_mut() {
+                let vnode = repl_set
+                    .vnodes
+                    .first()
+                    .cloned()
+                    .ok_or(CoordinatorError::NoValidReplica { id: repl_set.id })?;
+                repl_set.vnodes = vec![vnode.clone()];
+                let cmd = AdminCommandRequest {
+                    tenant: tenant.to_string(),
+                    command: Some(BackupVnode(BackupVnodeRequest {
+                        db: database.to_string(),
+                        vnode_id: vnode.id,
+                        location: location.to_string(),
+                    })),
+                };
+                req_futures.push(self.exec_admin_command_on_node(vnode.node_id, cmd));
+            }
+        }
+        for res in futures::future::join_all(req_futures).await {
+            res?
+        }
+
+        let data = serde_json::to_vec_pretty(&db_info)
+            .map_err(|e| CoordinatorError::InvalidSerdeMsg { err: e.to_string() })?;
+        let storage = BackupStorage::open(location)?;
+        storage
+            .put(&database_info_key(&make_owner(tenant, database)), data)
+            .await?;
+        info!("Backup database '{tenant}.{database}' to '{location}' finished");
+        Ok(())
+    }
+
+    async fn restore_database(
+        &self,
+        tenant: &str,
+        database: &str,
+        location: &str,
+    ) -> CoordinatorResult<()> {
+        let meta_client =
+            self.tenant_meta(tenant)
+                .await
+                .ok_or_else(|| CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                })?;
+        if meta_client.get_db_schema(database)?.is_some() {
+            return Err(CoordinatorError::CommonError {
+                msg: format!("database '{}' already exists", database),
+            });
+        }
+
+        let storage = BackupStorage::open(location)?;
+        let data = storage
+            .get(&database_info_key(&make_owner(tenant, database)))
+            .await?;
+        let backup_info: DatabaseInfo = serde_json::from_slice(&data)
+            .map_err(|e| CoordinatorError::InvalidSerdeMsg { err: e.to_string() })?;
+
+        meta_client.create_db(backup_info.schema.clone()).await?;
+        // Drop the partially restored database if restore failed, so that it can be retried.
+        if let Err(e) = self
+            .restore_database_data(&meta_client, tenant, database, location, &backup_info)
+            .await
+        {
+            error!("Restore database '{tenant}.{database}' from '{location}' failed: {e}, rolling back");
+            let req = AdminCommandRequest {
+                tenant: tenant.to_string(),
+                command: Some(DropDb(DropDbRequest {
+                    db: database.to_string(),
+                })),
+            };
+            if let Err(drop_err) = self.broadcast_command(req).await {
+                error!("Failed to drop vnodes of database '{tenant}.{database}': {drop_err}");
+            }
+            if let Err(drop_err) = meta_client.drop_db(database).await {
+                error!("Failed to drop database '{tenant}.{database}': {drop_err}");
+            }
+            return Err(e);
+        }
+
+        info!("Restore database '{tenant}.{database}' from '{location}' finished");
+        Ok(())
+    }
+
+    /// Creates tables and buckets of the backup, then restores vnodes in them.
+    async fn restore_database_data(
+        &self,
+        meta_client: &MetaClientRef,
+        tenant: &str,
+        database: &str,
+        location: &str,
+        backup_info: &DatabaseInfo,
+    ) -> CoordinatorResult<()> {
+        for table in backup_info.tables.values() {
+            meta_client.create_table(table).await?;
+        }
+
+        let mut req_futures = vec![];
+        for backup_bucket in backup_info.buckets.iter() {
+            let bucket = meta_client
+                .create_bucket(database, backup_bucket.start_time)
+                .await?;
+            if bucket.shard_group.len() != backup_bucket.shard_group.len() {
+                return Err(CoordinatorError::CommonError {
+                    msg: format!(
+                        "bucket {} has {} shards, but {} in the backup",
+                        bucket.id,
+                        bucket.shard_group.len(),
+                        backup_bucket.shard_group.len()
+                    ),
+                });
+            }
+            for (repl_set, backup_repl_set) in bucket
+                .shard_group
+                .iter()
+                .zip(backup_bucket.shard_group.iter())
+            {
+                let backup_vnode =
+                    backup_repl_set
+                        .vnodes
+                        .first()
+                        .ok_or(CoordinatorError::NoValidReplica {
+                            id: backup_repl_set.id,
+                        })?;
+                for vnode in repl_set.vnodes.iter() {
+                    let cmd = AdminCommandRequest {
+                        tenant: tenant.to_string(),
+                        command: Some(RestoreVnode(RestoreVnodeRequest {
+                            db: database.to_string(),
+                            vnode_id: vnode.id,
+                            backup_vnode_id: backup_vnode.id,
+                            location: location.to_string(),
+                        })),
+                    };
+                    req_futures.push(self.exec_admin_command_on_node(vnode.node_id, cmd));
+                }
+            }
+        }
+        for res in futures::future::join_all(req_futures).await {
+            res?
+        }
+
+        Ok(())
+    }
+
+    async fn export_database(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: Option<&str>,
+        location: &str,
+    ) -> CoordinatorResult<()> {
+        let meta_client =
+            self.tenant_meta(tenant)
+                .await
+                .ok_or_else(|| CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                })?;
+        let db_info =
+            meta_client
+                .get_db_info(database)?
+                .ok_or_else(|| CoordinatorError::CommonError {
+                    msg: format!("database '{}' not found", database),
+                })?;
+        if let Some(table) = table {
+            if !db_info.tables.contains_key(table) {
+                return Err(CoordinatorError::CommonError {
+                    msg: format!("table '{}.{}' not found", database, table),
+                });
+            }
+        }
+
+        // Export the first vnode of each replication set, other replicas are the same.
+        let mut req_futures = vec![];
+        for bucket in db_info.buckets.iter() {
+            for repl_set in bucket.shard_group.iter() {
+                let vnode = repl_set
+                    .vnodes
+                    .first()
+                    .ok_or(CoordinatorError::NoValidReplica { id: repl_set.id })?;
+                let cmd = AdminCommandRequest {
+                    tenant: tenant.to_string(),
+                    command: Some(ExportVnode(ExportVnodeRequest {
+                        db: database.to_string(),
+                        vnode_id: vnode.id,
+                        table: table.unwrap_or_default().to_string(),
+                        location: location.to_string(),
+                    })),
+                };
+                req_futures.push(self.exec_admin_command_on_node(vnode.node_id, cmd));
+            }
+        }
+        for res in futures::future::join_all(req_futures).await {
+            res?
+        }
+
+        info!("Export database '{tenant}.{database}' to '{location}' finished");
+        Ok(())
+    }
+
+    /// Read Parquet files of `table` in `location`, and send rows to all vnodes
+    /// of the replication sets which the rows belong to, the vnodes write rows
+    /// into TSM files directly.
+    async fn import_table(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        location: &str,
+    ) -> CoordinatorResult<()> {
+        let meta_client =
+            self.tenant_meta(tenant)
+                .await
+                .ok_or_else(|| CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                })?;
+        let table_schema = meta_client
+            .get_tskv_table_schema(database, table)?
+            .ok_or_else(|| CoordinatorError::CommonError {
+                msg: format!("table '{}.{}' not found", database, table),
+            })?;
+        let schema = table_schema.to_arrow_schema();
+
+        let storage = BackupStorage::open(location)?;
+        let prefix = format!("{}/{}/", make_owner(tenant, database), table);
+        let mut keys: Vec<String> = storage
+            .list(&prefix)
+            .await?
+            .into_iter()
+            .filter(|k| k.ends_with(".parquet"))
+            .collect();
+        keys.sort();
+
+        let mut import_rows = 0;
+        let mut buffers: HashMap<ReplicationSetId, ImportBuffer> = HashMap::new();
+        for key in keys.iter() {
+            let (path, is_tmp) = match &storage {
+                BackupStorage::Local(dir) => (dir.join(key), false),
+                BackupStorage::Remote(_) => {
+                    let path = std::env::temp_dir()
+                        .join(format!("cnosdb_import_{}", self.node_id))
+                        .join(key);
+                    storage.get_file(key, &path).await?;
+                    (path, true)
+                }
+            };
+            let result = self
+                .import_parquet_file(
+                    &meta_client,
+                    &table_schema,
+                    schema.clone(),
+                    &path,
+                    &mut buffers,
+                )
+                .await;
+            if is_tmp {
+                let _ = std::fs::remove_file(&path);
+            }
+            import_rows += result?;
+        }
+        self.send_import_buffers(&table_schema, &mut buffers, true)
+            .await?;
+
+        info!(
+            "Import {} rows from {} files into table '{tenant}.{database}.{table}' finished",
+            import_rows,
+            keys.len()
+        );
+        Ok(())
+    }
+
+    /// Sends rows in the buffers to vnodes, buffers smaller than
+    /// `IMPORT_REQUEST_MAX_SIZE` are kept unless `all` is true.
+    async fn send_import_buffers(
+        &self,
+        table_schema: &TskvTableSchemaRef,
+        buffers: &mut HashMap<ReplicationSetId, ImportBuffer>,
+        all: bool,
+    ) -> CoordinatorResult<()> {
+        let mut req_futures = vec![];
+        for buffer in buffers.values_mut() {
+            if all || buffer.memory_size >= IMPORT_REQUEST_MAX_SIZE {
+                req_futures.extend(self.import_requests(
+                    &table_schema.tenant,
+                    &table_schema.db,
+                    &table_schema.name,
+                    buffer,
+                )?);
+            }
+        }
+        for res in futures::future::join_all(req_futures).await {
+            res?
+        }
+        Ok(())
+    }
+
+    /// Reads rows in a Parquet file, and appends them to the buffer of the
+    /// replication set they belong to, returns the number of rows. Buffers
+    /// are sent after each record batch if they are large enough.
+    async fn import_parquet_file(
+        &self,
+        meta_client: &MetaClientRef,
+        table_schema: &TskvTableSchemaRef,
+        schema: SchemaRef,
+        path: &std::path::Path,
+        buffers: &mut HashMap<ReplicationSetId, ImportBuffer>,
+    ) -> CoordinatorResult<usize> {
+        let file = std::fs::File::open(path).map_err(|e| CoordinatorError::CommonError {
+            msg: format!("failed to open '{}': {}", path.display(), e),
+        })?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .and_then(|b| b.build())
+            .map_err(|e| CoordinatorError::CommonError {
+                msg: format!("failed to read '{}': {}", path.display(), e),
+            })?;
+
+        let mut rows = 0;
+        for batch in reader {
+            let batch = batch?;
+            let batch = cast_to_table_schema(&batch, table_schema, schema.clone())?;
+            for (repl_set, indices) in self
+                .group_rows_by_replication_set(meta_client, table_schema, &batch)
+                .await?
+            {
+                let indices = UInt32Array::from(indices);
+                let columns = batch
+                    .columns()
+                    .iter()
+                    .map(|c| take(c, &indices, None))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let rows_batch = RecordBatch::try_new(schema.clone(), columns)?;
+                buffers
+                    .entry(repl_set.id)
+                    .or_insert_with(|| ImportBuffer::new(repl_set, schema.clone()))
+                    .push(rows_batch);
+            }
+            rows += batch.num_rows();
+            self.send_import_buffers(table_schema, buffers, false)
+                .await?;
+        }
+        Ok(rows)
+    }
+
+    async fn group_rows_by_replication_set(
+        &self,
+        meta_client: &MetaClientRef,
+        table_schema: &TskvTableSchemaRef,
+        batch: &RecordBatch,
+    ) -> CoordinatorResult<HashMap<ReplicationSet, Vec<u32>>> {
+        let db = table_schema.db.as_str();
+        let mut time_array = None;
+        let mut tag_arrays = vec![];
+        for (column, array) in table_schema.columns().iter().zip(batch.columns()) {
+            match column.column_type {
+                ColumnType::Time(_) => {
+                    time_array = Some(datafusion::arrow::compute::cast(array, &DataType::Int64)?)
+                }
+                ColumnType::Tag => tag_arrays.push((column.name.as_bytes(), array)),
+                ColumnType::Field(_) => {}
+            }
+        }
+        let time_array = time_array.ok_or_else(|| CoordinatorError::CommonError {
+            msg: format!(
+                "column {} not found in table {}",
+                TIME_FIELD, table_schema.name
+            ),
+        })?;
+        let time_array = time_array
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| CoordinatorError::CommonError {
+                msg: format!("column {} is not timestamp", TIME_FIELD),
+            })?;
+        let tag_arrays = tag_arrays
+            .into_iter()
+            .map(|(name, array)| {
+                array
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .map(|a| (name, a))
+                    .ok_or_else(|| CoordinatorError::CommonError {
+                        msg: "tag column is not string".to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Buckets of the rows, rows in a batch are usually in a few buckets.
+        let mut buckets: Vec<BucketInfo> = vec![];
+        let mut repl_idx: HashMap<ReplicationSet, Vec<u32>> = HashMap::new();
+        for idx in 0..batch.num_rows() {
+            if time_array.is_null(idx) {
+                return Err(CoordinatorError::CommonError {
+                    msg: format!("column {} has null values", TIME_FIELD),
+                });
+            }
+            // Same as the hash of series keys in line protocol: tags sorted by key.
+            let mut tags = Vec::with_capacity(tag_arrays.len());
+            for (name, array) in tag_arrays.iter() {
+                if array.is_valid(idx) {
+                    tags.push(Tag::new(
+                        name.to_vec(),
+                        array.value(idx).as_bytes().to_vec(),
+                    ));
+                }
+            }
+            sort_tags(&mut tags);
+            let hash = tags_hash_id(&table_schema.name, &tags);
+            let ts = time_array.value(idx);
+            let bucket_contains = |b: &BucketInfo| ts >= b.start_time && ts < b.end_time;
+            let bucket_idx = match buckets.iter().position(bucket_contains) {
+                Some(i) => i,
+                None => {
+                    let bucket = match meta_client
+                        .mapping_bucket(db, ts, ts)?
+                        .into_iter()
+                        .find(bucket_contains)
+                    {
+                        Some(bucket) => bucket,
+                        None => meta_client.create_bucket(db, ts).await?,
+                    };
+                    buckets.push(bucket);
+                    buckets.len() - 1
+                }
+            };
+            let info = buckets[bucket_idx].vnode_for(hash);
+            repl_idx.entry(info).or_default().push(idx as u32);
+        }
+        Ok(repl_idx)
+    }
+
+    /// Takes rows in the buffer, returns requests to import the rows into all
+    /// vnodes of the replication set.
+    fn import_requests(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        buffer: &mut ImportBuffer,
+    ) -> CoordinatorResult<Vec<impl Future<Output = CoordinatorResult<()>> + '_>> {
+        let batch = match buffer.take()? {
+            Some(batch) => batch,
+            None => return Ok(vec![]),
+        };
+        let data = record_batch_encode(&batch)?;
+        let mut requests = Vec::with_capacity(buffer.repl_set.vnodes.len());
+        for vnode in buffer.repl_set.vnodes.iter() {
+            let cmd = AdminCommandRequest {
+                tenant: tenant.to_string(),
+                command: Some(ImportVnode(ImportVnodeRequest {
+                    db: database.to_string(),
+                    vnode_id: vnode.id,
+                    table: table.to_string(),
+                    data: data.clone(),
+                })),
+            };
+            requests.push(self.exec_admin_command_on_node(vnode.node_id, cmd));
+        }
+        Ok(requests)
+    }
+
+    /// Splits the replication set which the vnode belongs to, series in the
+    /// upper half of its hash range are moved into a new replication set, which
+    /// has a new vnode on each node of the replication set.
+    ///
+    /// The split is recorded in meta before moving series, and the replication
+    /// set keeps taking writes and queries of the moved series until they are
+    /// copied into the new vnodes, then meta is cut over and the moved series
+    /// are removed from the vnodes. An interrupted split is resumed by splitting
+    /// the vnode again.
+    async fn split_vnode(&self, tenant: &str, vnode_id: u32) -> CoordinatorResult<()> {
+        let all_info = crate::get_vnode_all_info(self.meta.clone(), tenant, vnode_id).await?;
+        let meta_client =
+            self.tenant_meta(tenant)
+                .await
+                .ok_or_else(|| CoordinatorError::TenantNotFound {
+                    name: tenant.to_string(),
+                })?;
+        let repl_set = meta_client
+            .get_replication_set(all_info.repl_set_id)
+            .ok_or_else(|| CoordinatorError::CommonError {
+                msg: format!("replication set {} not found", all_info.repl_set_id),
+            })?;
+
+        // Ids of the new replication set and its vnodes, not used if an
+        // unfinished split is resumed.
+        let begin_id = self
+            .meta
+            .retain_id(repl_set.vnodes.len() as u32 + 1)
+            .await?;
+        let new_vnodes = repl_set
+            .vnodes
+            .iter()
+            .enumerate()
+            .map(|(i, v)| VnodeInfo::new(begin_id + 1 + i as u32, v.node_id))
+            .collect();
+        let new_repl_set = meta_client
+            .split_replication_set(
+                &all_info.db_name,
+                all_info.bucket_id,
+                repl_set.id,
+                ReplicationSet::new(begin_id, new_vnodes),
+            )
+            .await?;
+        info!(
+            "Split replication set {} of vnode {vnode_id} into {}, hash range: {:?}, state: {:?}",
+            repl_set.id, new_repl_set.id, new_repl_set.hash_range, new_repl_set.split_from
+        );
+
+        if new_repl_set.is_copying() {
+            self.exec_split_vnode(tenant, &all_info.db_name, &repl_set, &new_repl_set, false)
+                .await?;
+            meta_client
+                .cut_over_split(&all_info.db_name, all_info.bucket_id, new_repl_set.id)
+                .await?;
+        }
+        self.exec_split_vnode(tenant, &all_info.db_name, &repl_set, &new_repl_set, true)
+            .await?;
+        meta_client
+            .finish_split(&all_info.db_name, all_info.bucket_id, new_repl_set.id)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Copies (or removes if `remove_moved_series` is true) series in the hash
+    /// range of `new_repl_set` on each node of `repl_set`.
+    async fn exec_split_vnode(
+        &self,
+        tenant: &str,
+        db: &str,
+        repl_set: &ReplicationSet,
+        new_repl_set: &ReplicationSet,
+        remove_moved_series: bool,
+    ) -> CoordinatorResult<()> {
+        let hash_range = new_repl_set
+            .hash_range
+            .ok_or_else(|| CoordinatorError::CommonError {
+                msg: format!(
+                    "hash range of replication set {} is missing",
+                    new_repl_set.id
+                ),
+            })?;
+        let mut req_futures = vec![];
+        for vnode in repl_set.vnodes.iter() {
+            let new_vnode = new_repl_set
+                .vnodes
+                .iter()
+                .find(|v| v.node_id == vnode.node_id)
+                .ok_or_else(|| CoordinatorError::CommonError {
+                    msg: format!(
+                        "replication set {} has no vnode on node {}",
+                        new_repl_set.id, vnode.node_id
+                    ),
+                })?;
+            let cmd = AdminCommandRequest {
+                tenant: tenant.to_string(),
+                command: Some(SplitVnode(SplitVnodeRequest {
+                    db: db.to_string(),
+                    vnode_id: vnode.id,
+                    new_vnode_id: new_vnode.id,
+                    hash_start: hash_range.start,
+                    hash_end: hash_range.end,
+                    remove_moved_series,
+                })),
+            };
+            req_futures.push(self.exec_admin_command_on_node(vnode.node_id, cmd));
+        }
+        for res in futures::future::join_all(req_futures).await {
+            res?
+        }
+
+        Ok(())
+    }
+
     async fn prune_shards(
         &self,
         table: &ResolvedTable,
@@ -291,7 +963,13 @@ impl CoordService {
             },
         )?;
         let buckets = meta.mapping_bucket(database, time_ranges.min_ts(), time_ranges.max_ts())?;
-        let shards = buckets.into_iter().flat_map(|b| b.shard_group).collect();
+        // Replication sets still copying series from a split replication set
+        // are not read, the split replication set has all series until cut over.
+        let shards = buckets
+            .into_iter()
+            .flat_map(|b| b.shard_group)
+            .filter(|set| !set.is_copying())
+            .collect();
 
         Ok(shards)
     }
@@ -428,7 +1106,7 @@ impl Coordinator for CoordService {
         tenant: &str,
         db: &str,
         precision: Precision,
-        lines: Vec<Line<'a>>,
+        mut lines: Vec<Line<'a>>,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         let mut write_bytes: usize = 0;
@@ -448,6 +1126,26 @@ impl Coordinator for CoordService {
                 })?;
         let db_precision = db_schema.config.precision_or_default();
 
+        // Values of decimal fields are written as unscaled integers.
+        let mut table_schemas: HashMap<String, Option<TskvTableSchemaRef>> = HashMap::new();
+        for line in lines.iter_mut() {
+            let table_schema = match table_schemas.get(line.table) {
+                Some(schema) => schema.clone(),
+                None => {
+                    let schema = meta_client.get_tskv_table_schema(db, line.table)?;
+                    table_schemas.insert(line.table.to_string(), schema.clone());
+                    schema
+                }
+            };
+            if let Some(table_schema) = table_schema {
+                convert_decimal_fields(line, &table_schema).map_err(|e| {
+                    CoordinatorError::CommonError {
+                        msg: format!("line to batch error: {}", e),
+                    }
+                })?;
+            }
+        }
+
         for line in lines {
             let ts = timestamp_convert(precision, *db_precision, line.timestamp).ok_or(
                 CoordinatorError::CommonError {
@@ -762,6 +1460,10 @@ impl Coordinator for CoordService {
 
                 return Ok(());
             }
+
+            VnodeManagerCmdType::Split(vnode_id) => {
+                return self.split_vnode(tenant, vnode_id).await;
+            }
         };
 
         self.exec_admin_command_on_node(req_node_id, grpc_req)
@@ -769,6 +1471,29 @@ impl Coordinator for CoordService {
             .map(|_| ())
     }
 
+    async fn database_manager(
+        &self,
+        tenant: &str,
+        cmd_type: DatabaseManagerCmdType,
+    ) -> CoordinatorResult<()> {
+        match cmd_type {
+            DatabaseManagerCmdType::Backup(database, location) => {
+                self.backup_database(tenant, &database, &location).await
+            }
+            DatabaseManagerCmdType::Restore(database, location) => {
+                self.restore_database(tenant, &database, &location).await
+            }
+            DatabaseManagerCmdType::Export(database, table, location) => {
+                self.export_database(tenant, &database, table.as_deref(), &location)
+                    .await
+            }
+            DatabaseManagerCmdType::Import(database, table, location) => {
+                self.import_table(tenant, &database, &table, &location)
+                    .await
+            }
+        }
+    }
+
     async fn vnode_summarizer(
         &self,
         tenant: &str,
@@ -810,6 +1535,35 @@ impl Coordinator for CoordService {
 
                 return Ok(record_batches);
             }
+            VnodeSummarizerCmdType::SeriesCardinality(database, table) => {
+                let cardinalities = self
+                    .fetch_cardinality(tenant, &database, |vnode_id| {
+                        admin_fetch_command_request::Command::FetchSeriesCardinality(
+                            FetchSeriesCardinalityRequest {
+                                db: database.clone(),
+                                vnode_id,
+                                table: table.clone().unwrap_or_default(),
+                            },
+                        )
+                    })
+                    .await?;
+                Ok(vec![cardinality_record_batch("table", cardinalities)?])
+            }
+            VnodeSummarizerCmdType::TagValuesCardinality(database, table, tag_keys) => {
+                let cardinalities = self
+                    .fetch_cardinality(tenant, &database, |vnode_id| {
+                        admin_fetch_command_request::Command::FetchTagValuesCardinality(
+                            FetchTagValuesCardinalityRequest {
+                                db: database.clone(),
+                                vnode_id,
+                                table: table.clone(),
+                                tag_keys: tag_keys.clone(),
+                            },
+                        )
+                    })
+                    .await?;
+                Ok(vec![cardinality_record_batch("key", cardinalities)?])
+            }
         }
     }
 
@@ -836,6 +1590,100 @@ impl<'a> VnodeLines<'a> {
     }
 }
 
+/// Rows to be imported into a replication set.
+struct ImportBuffer {
+    repl_set: ReplicationSet,
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    memory_size: usize,
+}
+
+impl ImportBuffer {
+    fn new(repl_set: ReplicationSet, schema: SchemaRef) -> Self {
+        Self {
+            repl_set,
+            schema,
+            batches: vec![],
+            memory_size: 0,
+        }
+    }
+
+    fn push(&mut self, batch: RecordBatch) {
+        self.memory_size += batch.get_array_memory_size();
+        self.batches.push(batch);
+    }
+
+    fn take(&mut self) -> CoordinatorResult<Option<RecordBatch>> {
+        if self.batches.is_empty() {
+            return Ok(None);
+        }
+        let batch = concat_batches(&self.schema, &self.batches)?;
+        self.batches.clear();
+        self.memory_size = 0;
+        Ok(Some(batch))
+    }
+}
+
+/// Casts columns of `batch` to types of the table schema, missing tag and
+/// field columns are filled with nulls.
+fn cast_to_table_schema(
+    batch: &RecordBatch,
+    table_schema: &TskvTableSchemaRef,
+    schema: SchemaRef,
+) -> CoordinatorResult<RecordBatch> {
+    let batch_schema = batch.schema();
+    for field in batch_schema.fields() {
+        if table_schema.column(field.name()).is_none() {
+            return Err(CoordinatorError::CommonError {
+                msg: format!(
+                    "column {} not found in table {}",
+                    field.name(),
+                    table_schema.name
+                ),
+            });
+        }
+    }
+    let mut columns = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        let column = match batch_schema.index_of(field.name()) {
+            Ok(i) => datafusion::arrow::compute::cast(batch.column(i), field.data_type())?,
+            Err(_) if field.is_nullable() => new_null_array(field.data_type(), batch.num_rows()),
+            Err(_) => {
+                return Err(CoordinatorError::CommonError {
+                    msg: format!(
+                        "column {} not found in file of table {}",
+                        field.name(),
+                        table_schema.name
+                    ),
+                })
+            }
+        };
+        columns.push(column);
+    }
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+fn cardinality_record_batch(
+    name_column: &str,
+    cardinalities: Vec<(String, u64)>,
+) -> CoordinatorResult<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(name_column, DataType::Utf8, false),
+        Field::new("cardinality", DataType::UInt64, false),
+    ]));
+    let mut names = StringBuilder::new();
+    let mut values = UInt64Builder::with_capacity(cardinalities.len());
+    for (name, cardinality) in cardinalities {
+        names.append_value(name);
+        values.append_value(cardinality);
+    }
+    let record_batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(names.finish()), Arc::new(values.finish())],
+    )?;
+    Ok(record_batch)
+}
+
 fn get_precision_and_value_from_arrow_column(
     column: &ArrayRef,
     idx: usize,
@@ -22,7 +22,8 @@ use tskv::EngineRef;
 use crate::errors::CoordinatorResult;
 use crate::service::CoordServiceMetrics;
 use crate::{
-    Coordinator, SendableCoordinatorRecordBatchStream, VnodeManagerCmdType, VnodeSummarizerCmdType,
+    Coordinator, DatabaseManagerCmdType, SendableCoordinatorRecordBatchStream, VnodeManagerCmdType,
+    VnodeSummarizerCmdType,
 };
 
 pub const WITH_NONEMPTY_DATABASE_FOR_TEST: &str = "with_nonempty_database";
@@ -173,6 +174,14 @@ impl Coordinator for MockCoordinator {
         Ok(())
     }
 
+    async fn database_manager(
+        &self,
+        tenant: &str,
+        cmd_type: DatabaseManagerCmdType,
+    ) -> CoordinatorResult<()> {
+        Ok(())
+    }
+
     async fn vnode_summarizer(
         &self,
         tenant: &str,
@@ -9,9 +9,9 @@ use futures::{Stream, StreamExt, TryStreamExt};
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
 use models::meta_data::VnodeInfo;
-use models::predicate::domain::{self, QueryArgs, QueryExpr};
-use models::record_batch_encode;
+use models::predicate::domain::{self, AggregateColumn, QueryArgs, QueryExpr, ResolvedPredicate};
 use models::schema::{Precision, TableColumn};
+use models::{record_batch_decode, record_batch_encode};
 use protos::kv_service::tskv_service_server::TskvService;
 use protos::kv_service::*;
 use protos::models::{PingBody, PingBodyBuilder};
@@ -22,6 +22,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Extensions, Request, Response, Status};
 use trace::{debug, error, info, SpanContext, SpanExt, SpanRecorder};
 use tskv::error::Result as TskvResult;
+use tskv::index::cardinality::cardinality_sketches_to_record_batch;
 use tskv::reader::query_executor::QueryExecutor;
 use tskv::reader::serialize::TonicRecordBatchEncoder;
 use tskv::reader::{QueryOption, SendableTskvRecordBatchStream};
@@ -81,6 +82,38 @@ impl TskvServiceImpl {
         self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
     }
 
+    async fn admin_rename_table(
+        &self,
+        tenant: &str,
+        request: &RenameTableRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        if let Err(err) = self
+            .kv_inst
+            .rename_table(tenant, &request.db, &request.table, &request.new_name)
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
+    async fn admin_rename_database(
+        &self,
+        tenant: &str,
+        request: &RenameDatabaseRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        if let Err(err) = self
+            .kv_inst
+            .rename_database(tenant, &request.db, &request.new_name)
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
     async fn admin_drop_column(
         &self,
         tenant: &str,
@@ -130,6 +163,27 @@ impl TskvServiceImpl {
         self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
     }
 
+    async fn admin_delete_from_table(
+        &self,
+        tenant: &str,
+        request: &DeleteFromTableRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        let predicate = match ResolvedPredicate::decode(&request.predicate) {
+            Ok(predicate) => predicate,
+            Err(err) => return self.status_response(FAILED_RESPONSE_CODE, err.to_string()),
+        };
+
+        if let Err(err) = self
+            .kv_inst
+            .delete_from_table(tenant, &request.db, &request.table, &predicate)
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
     async fn admin_delete_vnode(
         &self,
         tenant: &str,
@@ -184,6 +238,128 @@ impl TskvServiceImpl {
         }
     }
 
+    async fn admin_backup_vnode(
+        &self,
+        tenant: &str,
+        request: &BackupVnodeRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        if let Err(err) = self
+            .kv_inst
+            .backup_vnode(tenant, &request.db, request.vnode_id, &request.location)
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
+    async fn admin_restore_vnode(
+        &self,
+        tenant: &str,
+        request: &RestoreVnodeRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        if let Err(err) = self
+            .kv_inst
+            .restore_vnode(
+                tenant,
+                &request.db,
+                request.vnode_id,
+                request.backup_vnode_id,
+                &request.location,
+            )
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
+    async fn admin_export_vnode(
+        &self,
+        tenant: &str,
+        request: &ExportVnodeRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        let table = (!request.table.is_empty()).then_some(request.table.as_str());
+        if let Err(err) = self
+            .kv_inst
+            .export_vnode(
+                tenant,
+                &request.db,
+                request.vnode_id,
+                table,
+                &request.location,
+            )
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
+    async fn admin_import_vnode(
+        &self,
+        tenant: &str,
+        request: &ImportVnodeRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        let data = match record_batch_decode(&request.data) {
+            Ok(data) => data,
+            Err(err) => return self.status_response(FAILED_RESPONSE_CODE, err.to_string()),
+        };
+        if let Err(err) = self
+            .kv_inst
+            .import_vnode(tenant, &request.db, request.vnode_id, &request.table, data)
+            .await
+        {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
+    async fn admin_split_vnode(
+        &self,
+        tenant: &str,
+        request: &SplitVnodeRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        let hash_range = request.hash_start..=request.hash_end;
+        let res = if request.remove_moved_series {
+            self.kv_inst
+                .remove_moved_series(tenant, &request.db, request.vnode_id, &hash_range)
+                .await
+        } else {
+            self.kv_inst
+                .split_vnode(
+                    tenant,
+                    &request.db,
+                    request.vnode_id,
+                    request.new_vnode_id,
+                    &hash_range,
+                )
+                .await
+        };
+        if let Err(err) = res {
+            self.status_response(FAILED_RESPONSE_CODE, err.to_string())
+        } else {
+            self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+        }
+    }
+
+    fn admin_set_write_rate_limit(
+        &self,
+        request: &SetWriteRateLimitRequest,
+    ) -> Result<tonic::Response<StatusResponse>, tonic::Status> {
+        if let Some(bytes_per_second) = request.compact_write_rate_limit {
+            self.kv_inst.set_compact_write_rate_limit(bytes_per_second);
+        }
+        if let Some(bytes_per_second) = request.flush_write_rate_limit {
+            self.kv_inst.set_flush_write_rate_limit(bytes_per_second);
+        }
+        self.status_response(SUCCESS_RESPONSE_CODE, "".to_string())
+    }
+
     async fn admin_fetch_vnode_checksum(
         &self,
         _tenant: &str,
@@ -199,17 +375,70 @@ impl TskvServiceImpl {
         }
     }
 
+    async fn admin_fetch_series_cardinality(
+        &self,
+        tenant: &str,
+        request: &FetchSeriesCardinalityRequest,
+    ) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        let table = if request.table.is_empty() {
+            None
+        } else {
+            Some(request.table.as_str())
+        };
+        let sketches = match self
+            .kv_inst
+            .get_series_cardinality(tenant, &request.db, request.vnode_id, table)
+            .await
+        {
+            Ok(sketches) => sketches,
+            Err(err) => return Err(err.into()),
+        };
+        match cardinality_sketches_to_record_batch(&sketches).and_then(|r| record_batch_encode(&r))
+        {
+            Ok(bytes) => self.bytes_response(SUCCESS_RESPONSE_CODE, bytes),
+            Err(err) => Err(self.tonic_status(err.to_string())),
+        }
+    }
+
+    async fn admin_fetch_tag_values_cardinality(
+        &self,
+        tenant: &str,
+        request: &FetchTagValuesCardinalityRequest,
+    ) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        let sketches = match self
+            .kv_inst
+            .get_tag_values_cardinality(
+                tenant,
+                &request.db,
+                request.vnode_id,
+                &request.table,
+                &request.tag_keys,
+            )
+            .await
+        {
+            Ok(sketches) => sketches,
+            Err(err) => return Err(err.into()),
+        };
+        match cardinality_sketches_to_record_batch(&sketches).and_then(|r| record_batch_encode(&r))
+        {
+            Ok(bytes) => self.bytes_response(SUCCESS_RESPONSE_CODE, bytes),
+            Err(err) => Err(self.tonic_status(err.to_string())),
+        }
+    }
+
     fn query_record_batch_exec(
         self,
         args: QueryArgs,
         expr: QueryExpr,
-        aggs: Option<Vec<TableColumn>>,
+        aggs: Option<Vec<AggregateColumn>>,
         span_ctx: Option<&SpanContext>,
     ) -> TskvResult<SendableTskvRecordBatchStream> {
         let option = QueryOption::new(
             args.batch_size,
             expr.split,
             aggs,
+            args.time_bucket,
+            args.last_value,
             Arc::new(expr.df_schema),
             expr.table_schema,
         );
@@ -237,6 +466,8 @@ impl TskvServiceImpl {
             args.batch_size,
             expr.split,
             None,
+            None,
+            false,
             Arc::new(expr.df_schema),
             expr.table_schema,
         );
@@ -381,9 +612,48 @@ impl TskvService for TskvServiceImpl {
                 admin_command_request::Command::AlterColumn(command) => {
                     self.admin_alter_column(&inner.tenant, command).await
                 }
+                admin_command_request::Command::DeleteFromTable(command) => {
+                    self.admin_delete_from_table(&inner.tenant, command).await
+                }
+                admin_command_request::Command::BackupVnode(command) => {
+                    self.admin_backup_vnode(&inner.tenant, command).await
+                }
+                admin_command_request::Command::RestoreVnode(command) => {
+                    self.admin_restore_vnode(&inner.tenant, command).await
+                }
+                admin_command_request::Command::RenameTable(command) => {
+                    self.admin_rename_table(&inner.tenant, command).await
+                }
+                admin_command_request::Command::RenameDb(command) => {
+                    self.admin_rename_database(&inner.tenant, command).await
+                }
+                admin_command_request::Command::ExportVnode(command) => {
+                    self.admin_export_vnode(&inner.tenant, command).await
+                }
+                admin_command_request::Command::ImportVnode(command) => {
+                    self.admin_import_vnode(&inner.tenant, command).await
+                }
+                admin_command_request::Command::SplitVnode(command) => {
+                    self.admin_split_vnode(&inner.tenant, command).await
+                }
+                admin_command_request::Command::SetWriteRateLimit(command) => {
+                    self.admin_set_write_rate_limit(command)
+                }
             };
 
-            info!("admin command: {:?}, result: {:?}", command, resp);
+            // Don't log rows to be imported.
+            if let admin_command_request::Command::ImportVnode(command) = &command {
+                info!(
+                    "admin command: import {} bytes into vnode {} of table '{}.{}', result: {:?}",
+                    command.data.len(),
+                    command.vnode_id,
+                    command.db,
+                    command.table,
+                    resp
+                );
+            } else {
+                info!("admin command: {:?}, result: {:?}", command, resp);
+            }
             resp
         } else {
             self.status_response(FAILED_RESPONSE_CODE, "Command is None".to_string())
@@ -402,6 +672,14 @@ impl TskvService for TskvServiceImpl {
                     self.admin_fetch_vnode_checksum(&inner.tenant, command)
                         .await
                 }
+                admin_fetch_command_request::Command::FetchSeriesCardinality(command) => {
+                    self.admin_fetch_series_cardinality(&inner.tenant, command)
+                        .await
+                }
+                admin_fetch_command_request::Command::FetchTagValuesCardinality(command) => {
+                    self.admin_fetch_tag_values_cardinality(&inner.tenant, command)
+                        .await
+                }
             }
         } else {
             self.bytes_response(FAILED_RESPONSE_CODE, vec![])
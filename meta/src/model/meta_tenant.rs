@@ -427,6 +427,25 @@ impl TenantMeta {
         Ok(exist)
     }
 
+    pub async fn rename_db(&self, name: &str, new_name: &str) -> MetaResult<()> {
+        let req = command::WriteCommand::RenameDB(
+            self.cluster.clone(),
+            self.tenant_name(),
+            name.to_string(),
+            new_name.to_string(),
+        );
+
+        let rsp = self.client.write::<TenantMetaData>(&req).await?;
+        {
+            let mut data = self.data.write();
+            if rsp.version > data.version {
+                *data = rsp;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn create_table(&self, schema: &TableSchema) -> MetaResult<()> {
         let req = command::WriteCommand::CreateTable(
             self.cluster.clone(),
@@ -533,6 +552,26 @@ impl TenantMeta {
         self.client.write::<()>(&req).await
     }
 
+    pub async fn rename_table(&self, db: &str, table: &str, new_table: &str) -> MetaResult<()> {
+        let req = command::WriteCommand::RenameTable(
+            self.cluster.clone(),
+            self.tenant_name(),
+            db.to_string(),
+            table.to_string(),
+            new_table.to_string(),
+        );
+
+        let rsp = self.client.write::<TenantMetaData>(&req).await?;
+        {
+            let mut data = self.data.write();
+            if rsp.version > data.version {
+                *data = rsp;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn create_bucket(&self, db: &str, ts: i64) -> MetaResult<BucketInfo> {
         let req = command::WriteCommand::CreateBucket(
             self.cluster.clone(),
@@ -728,6 +767,60 @@ impl TenantMeta {
         self.client.write::<()>(&req).await
     }
 
+    /// Starts splitting the hash range of a replication set into two halves,
+    /// the upper half will be moved into `new_repl_set`, returns `new_repl_set`
+    /// with its hash range, or the replication set of the unfinished split.
+    pub async fn split_replication_set(
+        &self,
+        db: &str,
+        bucket_id: u32,
+        repl_id: u32,
+        new_repl_set: ReplicationSet,
+    ) -> MetaResult<ReplicationSet> {
+        let args = command::SplitReplicationSetArgs {
+            cluster: self.cluster.clone(),
+            tenant: self.tenant_name(),
+            db_name: db.to_string(),
+            bucket_id,
+            repl_id,
+            new_repl_set,
+        };
+
+        let req = command::WriteCommand::SplitReplicationSet(args);
+        self.client.write::<ReplicationSet>(&req).await
+    }
+
+    /// Routes writes and queries of the hash range of `repl_id` to it, after
+    /// series in the hash range are copied into it.
+    pub async fn cut_over_split(&self, db: &str, bucket_id: u32, repl_id: u32) -> MetaResult<()> {
+        let req =
+            command::WriteCommand::CutOverSplit(self.update_split_args(db, bucket_id, repl_id));
+        self.client.write::<()>(&req).await
+    }
+
+    /// Marks the split of `repl_id` finished, after moved series are removed
+    /// from the replication set it is split from.
+    pub async fn finish_split(&self, db: &str, bucket_id: u32, repl_id: u32) -> MetaResult<()> {
+        let req =
+            command::WriteCommand::FinishSplit(self.update_split_args(db, bucket_id, repl_id));
+        self.client.write::<()>(&req).await
+    }
+
+    fn update_split_args(
+        &self,
+        db: &str,
+        bucket_id: u32,
+        repl_id: u32,
+    ) -> command::UpdateSplitArgs {
+        command::UpdateSplitArgs {
+            cluster: self.cluster.clone(),
+            tenant: self.tenant_name(),
+            db_name: db.to_string(),
+            bucket_id,
+            repl_id,
+        }
+    }
+
     pub async fn version(&self) -> u64 {
         self.data.read().version
     }
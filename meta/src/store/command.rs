@@ -32,6 +32,27 @@ pub struct UpdateVnodeArgs {
     pub vnode_info: VnodeAllInfo,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SplitReplicationSetArgs {
+    pub cluster: String,
+    pub tenant: String,
+    pub db_name: String,
+    pub bucket_id: u32,
+    pub repl_id: u32,
+    /// The replication set which takes the upper half of the hash range.
+    pub new_repl_set: ReplicationSet,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateSplitArgs {
+    pub cluster: String,
+    pub tenant: String,
+    pub db_name: String,
+    pub bucket_id: u32,
+    /// The replication set which is being split from another one.
+    pub repl_id: u32,
+}
+
 /******************* write command *************************/
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum WriteCommand {
@@ -41,6 +62,12 @@ pub enum WriteCommand {
     UpdateVnodeReplSet(UpdateVnodeReplSetArgs),
 
     UpdateVnode(UpdateVnodeArgs),
+
+    SplitReplicationSet(SplitReplicationSetArgs),
+
+    CutOverSplit(UpdateSplitArgs),
+
+    FinishSplit(UpdateSplitArgs),
     // cluster, node info
     AddDataNode(String, NodeInfo),
 
@@ -56,6 +83,9 @@ pub enum WriteCommand {
     // cluster, tenant, db name
     DropDB(String, String, String),
 
+    // cluster, tenant, old db name, new db name
+    RenameDB(String, String, String, String),
+
     // cluster, tenant, db name, timestamp
     CreateBucket(String, String, String, i64),
 
@@ -67,6 +97,8 @@ pub enum WriteCommand {
     UpdateTable(String, String, TableSchema),
     // cluster, tenant, db name, table name
     DropTable(String, String, String, String),
+    // cluster, tenant, db name, old table name, new table name
+    RenameTable(String, String, String, String, String),
 
     // cluster, user_name, user_options, is_admin
     CreateUser(String, UserDesc),
@@ -511,9 +511,21 @@ impl StateMachine {
             WriteCommand::DropDB(cluster, tenant, db_name) => {
                 response_encode(self.process_drop_db(cluster, tenant, db_name))
             }
+            WriteCommand::RenameDB(cluster, tenant, db_name, new_db_name) => {
+                response_encode(self.process_rename_db(cluster, tenant, db_name, new_db_name))
+            }
             WriteCommand::DropTable(cluster, tenant, db_name, table_name) => {
                 response_encode(self.process_drop_table(cluster, tenant, db_name, table_name))
             }
+            WriteCommand::RenameTable(cluster, tenant, db_name, table_name, new_table_name) => {
+                response_encode(self.process_rename_table(
+                    cluster,
+                    tenant,
+                    db_name,
+                    table_name,
+                    new_table_name,
+                ))
+            }
             WriteCommand::CreateTable(cluster, tenant, schema) => {
                 response_encode(self.process_create_table(cluster, tenant, schema))
             }
@@ -605,6 +617,15 @@ impl StateMachine {
                 response_encode(self.process_update_vnode_repl_set(args))
             }
             WriteCommand::UpdateVnode(args) => response_encode(self.process_update_vnode(args)),
+            WriteCommand::SplitReplicationSet(args) => {
+                response_encode(self.process_split_repl_set(args))
+            }
+            WriteCommand::CutOverSplit(args) => {
+                response_encode(self.process_update_split(args, |b, id| b.cut_over_split(id)))
+            }
+            WriteCommand::FinishSplit(args) => {
+                response_encode(self.process_update_split(args, |b, id| b.finish_split(id)))
+            }
             WriteCommand::LimiterRequest {
                 cluster,
                 tenant,
@@ -682,6 +703,68 @@ impl StateMachine {
         Ok(())
     }
 
+    fn process_split_repl_set(&self, args: &SplitReplicationSetArgs) -> MetaResult<ReplicationSet> {
+        let key = key_path::KeyPath::tenant_bucket_id(
+            &args.cluster,
+            &args.tenant,
+            &args.db_name,
+            args.bucket_id,
+        );
+        let mut bucket = match self.get_struct::<BucketInfo>(&key)? {
+            Some(b) => b,
+            None => {
+                return Err(MetaError::BucketNotFound { id: args.bucket_id });
+            }
+        };
+
+        let new_repl_set =
+            match bucket.split_replication_set(args.repl_id, args.new_repl_set.clone()) {
+                Some(set) => set.clone(),
+                None => {
+                    return Err(MetaError::NotSupport {
+                        msg: format!(
+                            "split replication set {} in bucket {}",
+                            args.repl_id, args.bucket_id
+                        ),
+                    });
+                }
+            };
+
+        self.insert(&key, &value_encode(&bucket)?)?;
+        Ok(new_repl_set)
+    }
+
+    fn process_update_split(
+        &self,
+        args: &UpdateSplitArgs,
+        update: impl FnOnce(&mut BucketInfo, u32) -> bool,
+    ) -> MetaResult<()> {
+        let key = key_path::KeyPath::tenant_bucket_id(
+            &args.cluster,
+            &args.tenant,
+            &args.db_name,
+            args.bucket_id,
+        );
+        let mut bucket = match self.get_struct::<BucketInfo>(&key)? {
+            Some(b) => b,
+            None => {
+                return Err(MetaError::BucketNotFound { id: args.bucket_id });
+            }
+        };
+
+        if !update(&mut bucket, args.repl_id) {
+            return Err(MetaError::NotSupport {
+                msg: format!(
+                    "update split state of replication set {} in bucket {}",
+                    args.repl_id, args.bucket_id
+                ),
+            });
+        }
+
+        self.insert(&key, &value_encode(&bucket)?)?;
+        Ok(())
+    }
+
     fn process_retain_id(&self, cluster: &str, count: u32) -> MetaResult<u32> {
         let id = self.fetch_and_add_incr_id(cluster, count)?;
 
@@ -744,6 +827,94 @@ impl StateMachine {
         Ok(())
     }
 
+    fn process_rename_db(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        new_db_name: &str,
+    ) -> MetaResult<TenantMetaData> {
+        let key = KeyPath::tenant_db_name(cluster, tenant, db_name);
+        let schema = match self.get_struct::<DatabaseSchema>(&key)? {
+            Some(schema) => schema,
+            None => {
+                return Err(MetaError::DatabaseNotFound {
+                    database: db_name.to_string(),
+                })
+            }
+        };
+        let new_key = KeyPath::tenant_db_name(cluster, tenant, new_db_name);
+        if self.contains_key(&new_key)? {
+            return Err(MetaError::DatabaseAlreadyExists {
+                database: new_db_name.to_string(),
+            });
+        }
+
+        // The database must be created before its buckets and tables.
+        let new_schema = DatabaseSchema::new_with_options(tenant, new_db_name, schema.config);
+        self.insert(&new_key, &value_encode(&new_schema)?)?;
+
+        let buckets_path = KeyPath::tenant_db_buckets(cluster, tenant, db_name);
+        for (id, bucket) in self.children_data::<BucketInfo>(&buckets_path)? {
+            let id = id.parse::<u32>().map_err(|e| MetaError::CommonError {
+                msg: format!("invalid bucket id '{}': {}", id, e),
+            })?;
+            let new_bucket_key = KeyPath::tenant_bucket_id(cluster, tenant, new_db_name, id);
+            self.insert(&new_bucket_key, &value_encode(&bucket)?)?;
+        }
+
+        let schemas_path = KeyPath::tenant_schemas(cluster, tenant, db_name);
+        for (_, mut table) in self.children_data::<TableSchema>(&schemas_path)? {
+            table.set_db(new_db_name);
+            let new_table_key =
+                KeyPath::tenant_schema_name(cluster, tenant, new_db_name, table.name());
+            self.insert(&new_table_key, &value_encode(&table)?)?;
+        }
+
+        self.process_drop_db(cluster, tenant, db_name)?;
+
+        let roles_path = KeyPath::roles(cluster, tenant);
+        for (role_name, mut role) in self.children_data::<CustomTenantRole<Oid>>(&roles_path)? {
+            if role.rename_database(db_name, new_db_name) {
+                let role_key = KeyPath::role(cluster, tenant, &role_name);
+                self.insert(&role_key, &value_encode(&role)?)?;
+            }
+        }
+
+        self.to_tenant_meta_data(cluster, tenant)
+    }
+
+    fn process_rename_table(
+        &self,
+        cluster: &str,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        new_table_name: &str,
+    ) -> MetaResult<TenantMetaData> {
+        let key = KeyPath::tenant_schema_name(cluster, tenant, db_name, table_name);
+        let mut schema = match self.get_struct::<TableSchema>(&key)? {
+            Some(schema) => schema,
+            None => {
+                return Err(MetaError::TableNotFound {
+                    table: table_name.to_owned(),
+                })
+            }
+        };
+        let new_key = KeyPath::tenant_schema_name(cluster, tenant, db_name, new_table_name);
+        if self.contains_key(&new_key)? {
+            return Err(MetaError::TableAlreadyExists {
+                table_name: new_table_name.to_string(),
+            });
+        }
+
+        schema.set_name(new_table_name);
+        self.insert(&new_key, &value_encode(&schema)?)?;
+        self.remove(&key)?;
+
+        self.to_tenant_meta_data(cluster, tenant)
+    }
+
     fn process_drop_table(
         &self,
         cluster: &str,
@@ -3,24 +3,30 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::Duration;
 use coordinator::service::CoordinatorRef;
-use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::datatypes::{
+    IntervalDayTimeType, IntervalMonthDayNanoType, SchemaRef, TimeUnit,
+};
 use datafusion::datasource::{TableProvider, TableType};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::SessionState;
-use datafusion::logical_expr::expr::AggregateFunction;
+use datafusion::logical_expr::expr::{AggregateFunction, ScalarFunction};
 use datafusion::logical_expr::logical_plan::AggWithGrouping;
 use datafusion::logical_expr::{
-    aggregate_function, Expr, TableProviderAggregationPushDown, TableProviderFilterPushDown,
+    aggregate_function, BuiltinScalarFunction, Expr, TableProviderAggregationPushDown,
+    TableProviderFilterPushDown,
 };
 use datafusion::optimizer::utils::split_conjunction;
 use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::{project_schema, ExecutionPlan};
 use datafusion::prelude::Column;
+use datafusion::scalar::ScalarValue;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
-use models::predicate::domain::{Predicate, PredicateRef, PushedAggregateFunction};
-use models::schema::{TskvTableSchema, TskvTableSchemaRef};
+use models::predicate::domain::{Predicate, PredicateRef, PushedAggregateFunction, TimeBucket};
+use models::schema::{ColumnType, PhysicalCType, TskvTableSchema, TskvTableSchemaRef};
+use models::{PhysicalDType, ValueType};
 use trace::debug;
 
 use crate::data_source::sink::tskv::TskvRecordBatchSinkProvider;
@@ -39,6 +45,8 @@ pub struct ClusterTable {
     split_manager: SplitManagerRef,
     _meta: MetaClientRef,
     schema: TskvTableSchemaRef,
+    /// Only scan the latest row of each series.
+    last_value: bool,
 }
 
 impl ClusterTable {
@@ -64,13 +72,16 @@ impl ClusterTable {
             return Ok(Arc::new(EmptyExec::new(false, proj_schema)));
         }
 
-        Ok(Arc::new(TskvExec::new(
-            self.schema.clone(),
-            proj_schema,
-            predicate,
-            self.coord.clone(),
-            splits,
-        )))
+        Ok(Arc::new(
+            TskvExec::new(
+                self.schema.clone(),
+                proj_schema,
+                predicate,
+                self.coord.clone(),
+                splits,
+            )
+            .with_last_value(self.last_value),
+        ))
     }
 
     async fn create_agg_filter_scan(
@@ -80,12 +91,27 @@ impl ClusterTable {
         agg_with_grouping: &AggWithGrouping,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let AggWithGrouping {
-            group_expr: _,
+            group_expr,
             agg_expr,
             schema,
         } = agg_with_grouping;
         let proj_schema = SchemaRef::from(schema.deref());
 
+        // Only aggregates grouped by a time bucket are computed in tskv.
+        let time_bucket = match group_expr.as_slice() {
+            [] => None,
+            [expr] => Some(self.time_bucket(expr).ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "Invalid plan, pushed aggregate functions grouped by unsupported expression: {expr}"
+                ))
+            })?),
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "Invalid plan, pushed aggregate functions grouped by multiple expressions: {group_expr:?}"
+                )))
+            }
+        };
+
         let table_layout = TableLayoutHandle {
             table: self.schema.clone(),
             predicate: filter.clone(),
@@ -133,23 +159,32 @@ impl ClusterTable {
                 })
                 .collect::<Result<Vec<_>>>()
                 .and_then(|columns| {
+                    let column = columns
+                        .first()
+                        .ok_or_else(|| {
+                            DataFusionError::Internal(
+                                "Pushed aggregate functions's args is none.".to_string(),
+                            )
+                        })?
+                        .name
+                        .to_owned();
                     // Convert pushdown aggregate functions to intermediate structures
                     match fun {
                         aggregate_function::AggregateFunction::Count => {
-                            let column = columns
-                                .first()
-                                .ok_or_else(|| {
-                                    DataFusionError::Internal(
-                                        "Pushed aggregate functions's args is none.".to_string(),
-                                    )
-                                })?;
-                            Ok(PushedAggregateFunction::Count(column.name.to_owned()))
+                            Ok(PushedAggregateFunction::Count(column))
+                        }
+                        aggregate_function::AggregateFunction::Max => {
+                            Ok(PushedAggregateFunction::Max(column))
+                        }
+                        aggregate_function::AggregateFunction::Min => {
+                            Ok(PushedAggregateFunction::Min(column))
                         }
-                        // aggregate_function::AggregateFunction::Max => {},
-                        // aggregate_function::AggregateFunction::Min => {},
-                        _ => Err(DataFusionError::Internal(
-                            "Pushed aggregate functions's args is none.".to_string(),
-                        )),
+                        aggregate_function::AggregateFunction::Sum => {
+                            Ok(PushedAggregateFunction::Sum(column))
+                        }
+                        _ => Err(DataFusionError::Internal(format!(
+                            "Unsupported pushed aggregate function: {fun:?}."
+                        ))),
                     }
                 })
         })
@@ -160,6 +195,7 @@ impl ClusterTable {
             proj_schema,
             self.schema.clone(),
             pushed_aggs,
+            time_bucket,
             filter,
             splits,
         )))
@@ -212,13 +248,179 @@ impl ClusterTable {
             split_manager,
             _meta: meta,
             schema,
+            last_value: false,
+        }
+    }
+
+    /// Returns a table that scans only the latest row of each series.
+    pub fn with_last_value(&self) -> Self {
+        Self {
+            last_value: true,
+            ..self.clone()
         }
     }
 
+    pub fn is_last_value(&self) -> bool {
+        self.last_value
+    }
+
     pub fn table_schema(&self) -> TskvTableSchemaRef {
         self.schema.clone()
     }
 
+    /// Check if the aggregate function over the column can be computed in tskv.
+    fn supports_aggregate_field(
+        &self,
+        fun: &aggregate_function::AggregateFunction,
+        arg: &Expr,
+    ) -> bool {
+        let column = match arg {
+            Expr::Column(c) => c,
+            _ => return false,
+        };
+        let column_type = match self.schema.column(&column.name) {
+            Some(c) => &c.column_type,
+            None => return false,
+        };
+        // Sum of decimals returns a wider decimal type.
+        if let ColumnType::Field(ValueType::Decimal(_)) = column_type {
+            return !matches!(fun, aggregate_function::AggregateFunction::Sum);
+        }
+        match column_type.to_physical_type() {
+            PhysicalCType::Field(PhysicalDType::Float)
+            | PhysicalCType::Field(PhysicalDType::Integer)
+            | PhysicalCType::Field(PhysicalDType::Unsigned) => true,
+            PhysicalCType::Field(PhysicalDType::Boolean)
+            | PhysicalCType::Field(PhysicalDType::String) => {
+                !matches!(fun, aggregate_function::AggregateFunction::Sum)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the aggregate function can be computed in tskv, aggregates grouped by
+    /// time buckets only support `count`, `max`, `min` and `sum` of fields.
+    fn supports_pushed_aggregate(&self, expr: &Expr, group_by_time: bool) -> bool {
+        match expr {
+            Expr::AggregateFunction(AggregateFunction {
+                fun,
+                args,
+                distinct,
+                filter,
+                order_by,
+            }) => {
+                let support_agg_func = match fun {
+                    // count(field)
+                    aggregate_function::AggregateFunction::Count if group_by_time => {
+                        args.len() == 1 && self.supports_aggregate_field(fun, &args[0])
+                    }
+                    aggregate_function::AggregateFunction::Count => {
+                        // count(*) | count(1) | count(col)
+                        args.len() == 1
+                            && (matches!(args[0], Expr::Column(_))
+                                || matches!(args[0], Expr::Literal(_)))
+                    }
+                    // max(field) | min(field) | sum(field)
+                    aggregate_function::AggregateFunction::Max
+                    | aggregate_function::AggregateFunction::Min
+                    | aggregate_function::AggregateFunction::Sum => {
+                        args.len() == 1 && self.supports_aggregate_field(fun, &args[0])
+                    }
+                    _ => false,
+                };
+
+                support_agg_func
+                    // not distinct
+                    && !*distinct
+                    && filter.is_none()
+                    && order_by.is_none()
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the aggregates grouped by `date_bin(<interval>, time[, <origin>])`
+    /// can be computed in tskv, each vnode returns partial states of time buckets.
+    pub fn supports_time_bucket_aggregate_pushdown(
+        &self,
+        group_expr: &[Expr],
+        aggr_expr: &[Expr],
+    ) -> bool {
+        matches!(group_expr, [expr] if self.time_bucket(expr).is_some())
+            && !aggr_expr.is_empty()
+            && aggr_expr
+                .iter()
+                .all(|e| self.supports_pushed_aggregate(e, true))
+    }
+
+    /// Get the time bucket of `date_bin(<interval>, time[, <origin>])`, the stride
+    /// and the origin must be multiples of the unit of the time column.
+    fn time_bucket(&self, expr: &Expr) -> Option<TimeBucket> {
+        let (stride, source, origin) = match expr {
+            Expr::ScalarFunction(ScalarFunction {
+                fun: BuiltinScalarFunction::DateBin,
+                args,
+            }) => match args.as_slice() {
+                [stride, source] => (stride, source, None),
+                [stride, source, origin] => (stride, source, Some(origin)),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let time_unit = match source {
+            Expr::Column(c) => match self.schema.column(&c.name).map(|c| &c.column_type) {
+                Some(ColumnType::Time(unit)) => unit,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let unit_nanos = match time_unit {
+            TimeUnit::Second => 1_000_000_000,
+            TimeUnit::Millisecond => 1_000_000,
+            TimeUnit::Microsecond => 1_000,
+            TimeUnit::Nanosecond => 1,
+        };
+
+        let stride_nanos = match stride {
+            Expr::Literal(ScalarValue::IntervalDayTime(Some(v))) => {
+                let (days, ms) = IntervalDayTimeType::to_parts(*v);
+                (Duration::days(days as i64) + Duration::milliseconds(ms as i64))
+                    .num_nanoseconds()?
+            }
+            Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(v))) => {
+                let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(*v);
+                if months != 0 {
+                    return None;
+                }
+                (Duration::days(days as i64) + Duration::nanoseconds(nanos)).num_nanoseconds()?
+            }
+            _ => return None,
+        };
+        let origin_nanos = match origin {
+            None => 0,
+            Some(Expr::Literal(ScalarValue::TimestampNanosecond(Some(v), _))) => *v,
+            Some(Expr::Literal(ScalarValue::TimestampMicrosecond(Some(v), _))) => {
+                v.checked_mul(1_000)?
+            }
+            Some(Expr::Literal(ScalarValue::TimestampMillisecond(Some(v), _))) => {
+                v.checked_mul(1_000_000)?
+            }
+            Some(Expr::Literal(ScalarValue::TimestampSecond(Some(v), _))) => {
+                v.checked_mul(1_000_000_000)?
+            }
+            _ => return None,
+        };
+
+        if stride_nanos <= 0 || stride_nanos % unit_nanos != 0 || origin_nanos % unit_nanos != 0 {
+            return None;
+        }
+        Some(TimeBucket::new(
+            stride_nanos / unit_nanos,
+            origin_nanos / unit_nanos,
+        ))
+    }
+
     // Check and return the projected schema
     fn project_schema(&self, projection: Option<&Vec<usize>>) -> Result<SchemaRef> {
         valid_project(&self.schema, projection)
@@ -286,6 +488,10 @@ impl TableProvider for ClusterTable {
         Ok(TableProviderFilterPushDown::Inexact)
     }
 
+    /// Only ungrouped `count`, `max`, `min` and `sum` are pushed down here, each vnode
+    /// returns one partial state per aggregate. Aggregates grouped by time buckets are
+    /// pushed down by the `PushDownTimeBucketAggregate` rule, and aggregates grouped by
+    /// tags are computed by the query engine, since scans return no tags.
     fn supports_aggregate_pushdown(
         &self,
         group_expr: &[Expr],
@@ -295,35 +501,10 @@ impl TableProvider for ClusterTable {
             return Ok(TableProviderAggregationPushDown::Unsupported);
         }
 
-        let result = if aggr_expr.iter().all(|e| {
-            match e {
-                Expr::AggregateFunction(AggregateFunction {
-                    fun,
-                    args,
-                    distinct,
-                    filter,
-                    order_by,
-                }) => {
-                    let support_agg_func = matches!(
-                        fun,
-                        aggregate_function::AggregateFunction::Count // TODO
-                                                                     // | aggregate_function::AggregateFunction::Max
-                                                                     // | aggregate_function::AggregateFunction::Min
-                                                                     // | aggregate_function::AggregateFunction::Sum
-                    );
-
-                    support_agg_func
-                        && args.len() == 1
-                        // count(*) | count(1) | count(col)
-                        && (matches!(args[0], Expr::Column(_)) || matches!(args[0], Expr::Literal(_)))
-                        // not distinct
-                        && !*distinct
-                        && filter.is_none()
-                        && order_by.is_none()
-                }
-                _ => false,
-            }
-        }) {
+        let result = if aggr_expr
+            .iter()
+            .all(|e| self.supports_pushed_aggregate(e, false))
+        {
             TableProviderAggregationPushDown::Ungrouped
         } else {
             TableProviderAggregationPushDown::Unsupported
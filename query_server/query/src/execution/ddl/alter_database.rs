@@ -61,4 +61,19 @@ fn build_database_schema(database_options: &DatabaseOptions, config: &mut Databa
     if let Some(precision) = database_options.precision() {
         config.with_precision(*precision);
     }
+    if let Some(cold_duration) = database_options.cold_duration() {
+        config.with_cold_duration(cold_duration.clone());
+    }
+    if let Some(strategy) = database_options.compaction_strategy() {
+        config.with_compaction_strategy(*strategy);
+    }
+    if let Some(compaction_window) = database_options.compaction_window() {
+        config.with_compaction_window(compaction_window.clone());
+    }
+    if let Some(max_series) = database_options.max_series_per_database() {
+        config.with_max_series_per_database(*max_series);
+    }
+    if let Some(max_series) = database_options.max_series_per_table() {
+        config.with_max_series_per_table(*max_series);
+    }
 }
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use protos::kv_service::admin_command_request::Command;
+use protos::kv_service::{AdminCommandRequest, SetWriteRateLimitRequest};
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::AlterNodes;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct AlterNodesTask {
+    stmt: AlterNodes,
+}
+
+impl AlterNodesTask {
+    #[inline(always)]
+    pub fn new(stmt: AlterNodes) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for AlterNodesTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        // Rate limits are not persisted, they are reset to the configured ones on restart.
+        let req = AdminCommandRequest {
+            tenant: tenant.to_string(),
+            command: Some(Command::SetWriteRateLimit(SetWriteRateLimitRequest {
+                compact_write_rate_limit: self.stmt.compact_write_rate_limit,
+                flush_write_rate_limit: self.stmt.flush_write_rate_limit,
+            })),
+        };
+        query_state_machine.coord.broadcast_command(req).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
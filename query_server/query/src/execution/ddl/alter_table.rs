@@ -6,6 +6,7 @@ use models::schema::TableSchema;
 use protos::kv_service::admin_command_request::Command;
 use protos::kv_service::{
     AddColumnRequest, AdminCommandRequest, AlterColumnRequest, DropColumnRequest,
+    RenameTableRequest,
 };
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::{AlterTable, AlterTableAction};
@@ -34,6 +35,23 @@ impl DDLDefinitionTask for AlterTableTask {
             },
         )?;
 
+        if let AlterTableAction::RenameTable { new_table_name } = &self.stmt.alter_action {
+            client
+                .rename_table(table_name.database(), table_name.table(), new_table_name)
+                .await?;
+            let req = AdminCommandRequest {
+                tenant: tenant.to_string(),
+                command: Some(Command::RenameTable(RenameTableRequest {
+                    db: table_name.database().to_string(),
+                    table: table_name.table().to_string(),
+                    new_name: new_table_name.clone(),
+                })),
+            };
+            query_state_machine.coord.broadcast_command(req).await?;
+
+            return Ok(Output::Nil(()));
+        }
+
         let mut schema = client
             .get_tskv_table_schema(table_name.database(), table_name.table())?
             .ok_or(MetaError::TableNotFound {
@@ -42,6 +60,17 @@ impl DDLDefinitionTask for AlterTableTask {
             .as_ref()
             .clone();
 
+        if let AlterTableAction::SetLastValueCache { enabled } = &self.stmt.alter_action {
+            // Data nodes read the flag from the table schema in meta, nothing to broadcast.
+            schema.last_value_cache = *enabled;
+            schema.schema_id += 1;
+            client
+                .update_table(&TableSchema::TsKvTableSchema(Arc::new(schema)))
+                .await?;
+
+            return Ok(Output::Nil(()));
+        }
+
         let req = match &self.stmt.alter_action {
             AlterTableAction::AddColumn { table_column } => {
                 let table_column = table_column.to_owned();
@@ -90,6 +119,10 @@ impl DDLDefinitionTask for AlterTableTask {
                     })),
                 }
             }
+
+            AlterTableAction::RenameTable { .. } | AlterTableAction::SetLastValueCache { .. } => {
+                unreachable!("handled above")
+            }
         };
         schema.schema_id += 1;
 
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use coordinator::DatabaseManagerCmdType;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::BackupDatabase;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct BackupDatabaseTask {
+    stmt: BackupDatabase,
+}
+
+impl BackupDatabaseTask {
+    #[inline(always)]
+    pub fn new(stmt: BackupDatabase) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for BackupDatabaseTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let cmd_type = DatabaseManagerCmdType::Backup(
+            self.stmt.database_name.clone(),
+            self.stmt.location.clone(),
+        );
+        coord.database_manager(tenant, cmd_type).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use models::predicate::domain::ResolvedPredicate;
+use protos::kv_service::admin_command_request::Command;
+use protos::kv_service::{AdminCommandRequest, DeleteFromTableRequest};
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::DeleteFromTable;
+use spi::Result;
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct DeleteFromTableTask {
+    stmt: DeleteFromTable,
+}
+
+impl DeleteFromTableTask {
+    pub fn new(stmt: DeleteFromTable) -> DeleteFromTableTask {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for DeleteFromTableTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let table_name = &self.stmt.table_name;
+        let req = AdminCommandRequest {
+            tenant: table_name.tenant().to_string(),
+            command: Some(Command::DeleteFromTable(DeleteFromTableRequest {
+                db: table_name.database().to_string(),
+                table: table_name.table().to_string(),
+                predicate: ResolvedPredicate::encode(&self.stmt.predicate)?,
+            })),
+        };
+        query_state_machine.coord.broadcast_command(req).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -18,18 +18,30 @@ use self::drop_global_object::DropGlobalObjectTask;
 use self::drop_tenant_object::DropTenantObjectTask;
 use self::grant_revoke::GrantRevokeTask;
 use crate::execution::ddl::alter_database::AlterDatabaseTask;
+use crate::execution::ddl::alter_nodes::AlterNodesTask;
 use crate::execution::ddl::alter_table::AlterTableTask;
+use crate::execution::ddl::backup_database::BackupDatabaseTask;
 use crate::execution::ddl::checksum_group::ChecksumGroupTask;
 use crate::execution::ddl::compact_vnode::CompactVnodeTask;
 use crate::execution::ddl::copy_vnode::CopyVnodeTask;
 use crate::execution::ddl::create_database::CreateDatabaseTask;
+use crate::execution::ddl::delete_from_table::DeleteFromTableTask;
 use crate::execution::ddl::drop_vnode::DropVnodeTask;
+use crate::execution::ddl::export_database::ExportDatabaseTask;
+use crate::execution::ddl::import_database::ImportDatabaseTask;
 use crate::execution::ddl::move_node::MoveVnodeTask;
+use crate::execution::ddl::rename_database::RenameDatabaseTask;
+use crate::execution::ddl::restore_database::RestoreDatabaseTask;
+use crate::execution::ddl::show_series_cardinality::ShowSeriesCardinalityTask;
+use crate::execution::ddl::show_tag_values_cardinality::ShowTagValuesCardinalityTask;
+use crate::execution::ddl::split_vnode::SplitVnodeTask;
 
 mod alter_database;
+mod alter_nodes;
 mod alter_table;
 mod alter_tenant;
 mod alter_user;
+mod backup_database;
 mod checksum_group;
 mod compact_vnode;
 mod copy_vnode;
@@ -40,12 +52,20 @@ mod create_stream_table;
 mod create_table;
 mod create_tenant;
 mod create_user;
+mod delete_from_table;
 mod drop_database_object;
 mod drop_global_object;
 mod drop_tenant_object;
 mod drop_vnode;
+mod export_database;
 mod grant_revoke;
+mod import_database;
 mod move_node;
+mod rename_database;
+mod restore_database;
+mod show_series_cardinality;
+mod show_tag_values_cardinality;
+mod split_vnode;
 
 /// Traits that DDL tasks should implement
 #[async_trait]
@@ -153,17 +173,44 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::CreateUser(sub_plan) => Box::new(CreateUserTask::new(sub_plan.clone())),
             DDLPlan::CreateRole(sub_plan) => Box::new(CreateRoleTask::new(sub_plan.clone())),
             DDLPlan::AlterDatabase(sub_plan) => Box::new(AlterDatabaseTask::new(sub_plan.clone())),
+            DDLPlan::RenameDatabase(sub_plan) => {
+                Box::new(RenameDatabaseTask::new(sub_plan.clone()))
+            }
             DDLPlan::AlterTable(sub_plan) => Box::new(AlterTableTask::new(sub_plan.clone())),
+            DDLPlan::DeleteFromTable(sub_plan) => {
+                Box::new(DeleteFromTableTask::new(sub_plan.clone()))
+            }
             DDLPlan::AlterTenant(sub_plan) => Box::new(AlterTenantTask::new(sub_plan.clone())),
             DDLPlan::AlterUser(sub_plan) => Box::new(AlterUserTask::new(sub_plan.clone())),
+            DDLPlan::AlterNodes(sub_plan) => Box::new(AlterNodesTask::new(sub_plan.clone())),
             DDLPlan::GrantRevoke(sub_plan) => Box::new(GrantRevokeTask::new(sub_plan.clone())),
             DDLPlan::DropVnode(sub_plan) => Box::new(DropVnodeTask::new(sub_plan.clone())),
             DDLPlan::CopyVnode(sub_plan) => Box::new(CopyVnodeTask::new(sub_plan.clone())),
             DDLPlan::MoveVnode(sub_plan) => Box::new(MoveVnodeTask::new(sub_plan.clone())),
             DDLPlan::CompactVnode(sub_plan) => Box::new(CompactVnodeTask::new(sub_plan.clone())),
+            DDLPlan::SplitVnode(sub_plan) => Box::new(SplitVnodeTask::new(sub_plan.clone())),
             DDLPlan::ChecksumGroup(sub_plan) => {
                 Box::new(ChecksumGroupTask::new(sub_plan.clone(), self.plan.schema()))
             }
+            DDLPlan::ShowSeriesCardinality(sub_plan) => Box::new(ShowSeriesCardinalityTask::new(
+                sub_plan.clone(),
+                self.plan.schema(),
+            )),
+            DDLPlan::ShowTagValuesCardinality(sub_plan) => Box::new(
+                ShowTagValuesCardinalityTask::new(sub_plan.clone(), self.plan.schema()),
+            ),
+            DDLPlan::BackupDatabase(sub_plan) => {
+                Box::new(BackupDatabaseTask::new(sub_plan.clone()))
+            }
+            DDLPlan::RestoreDatabase(sub_plan) => {
+                Box::new(RestoreDatabaseTask::new(sub_plan.clone()))
+            }
+            DDLPlan::ExportDatabase(sub_plan) => {
+                Box::new(ExportDatabaseTask::new(sub_plan.clone()))
+            }
+            DDLPlan::ImportDatabase(sub_plan) => {
+                Box::new(ImportDatabaseTask::new(sub_plan.clone()))
+            }
             DDLPlan::CreateStreamTable(sub_plan) => {
                 let checker = self.stream_checker_manager.checker(&sub_plan.stream_type);
 
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use protos::kv_service::admin_command_request::Command;
+use protos::kv_service::{AdminCommandRequest, RenameDatabaseRequest};
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::RenameDatabase;
+use spi::Result;
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct RenameDatabaseTask {
+    stmt: RenameDatabase,
+}
+
+impl RenameDatabaseTask {
+    pub fn new(stmt: RenameDatabase) -> RenameDatabaseTask {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for RenameDatabaseTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let tenant = query_state_machine.session.tenant();
+        let client = query_state_machine.meta.tenant_meta(tenant).await.ok_or(
+            MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            },
+        )?;
+
+        // Storage units are moved to the new database after it's created in meta.
+        client
+            .rename_db(&self.stmt.database_name, &self.stmt.new_database_name)
+            .await?;
+
+        let req = AdminCommandRequest {
+            tenant: tenant.to_string(),
+            command: Some(Command::RenameDb(RenameDatabaseRequest {
+                db: self.stmt.database_name.clone(),
+                new_name: self.stmt.new_database_name.clone(),
+            })),
+        };
+        query_state_machine.coord.broadcast_command(req).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
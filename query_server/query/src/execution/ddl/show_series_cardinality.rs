@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+use coordinator::VnodeSummarizerCmdType;
+use datafusion::arrow::datatypes::SchemaRef;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowSeriesCardinality;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct ShowSeriesCardinalityTask {
+    schema: SchemaRef,
+    stmt: ShowSeriesCardinality,
+}
+
+impl ShowSeriesCardinalityTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowSeriesCardinality, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowSeriesCardinalityTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let cmd_type = VnodeSummarizerCmdType::SeriesCardinality(
+            self.stmt.database_name.clone(),
+            self.stmt.table_name.clone(),
+        );
+        let cardinalities = coord.vnode_summarizer(tenant, cmd_type).await?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), cardinalities);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+use coordinator::VnodeSummarizerCmdType;
+use datafusion::arrow::datatypes::SchemaRef;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::ShowTagValuesCardinality;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct ShowTagValuesCardinalityTask {
+    schema: SchemaRef,
+    stmt: ShowTagValuesCardinality,
+}
+
+impl ShowTagValuesCardinalityTask {
+    #[inline(always)]
+    pub fn new(stmt: ShowTagValuesCardinality, schema: SchemaRef) -> Self {
+        Self { schema, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for ShowTagValuesCardinalityTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let cmd_type = VnodeSummarizerCmdType::TagValuesCardinality(
+            self.stmt.database_name.clone(),
+            self.stmt.table_name.clone(),
+            self.stmt.tag_keys.clone(),
+        );
+        let cardinalities = coord.vnode_summarizer(tenant, cmd_type).await?;
+        let stream = RecordBatchStreamWrapper::new(self.schema.clone(), cardinalities);
+        Ok(Output::StreamData(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use coordinator::VnodeManagerCmdType;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::SplitVnode;
+use spi::Result;
+
+use super::DDLDefinitionTask;
+
+pub struct SplitVnodeTask {
+    stmt: SplitVnode,
+}
+
+impl SplitVnodeTask {
+    #[inline(always)]
+    pub fn new(stmt: SplitVnode) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for SplitVnodeTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> Result<Output> {
+        let vnode_id = self.stmt.vnode_id;
+        let tenant = query_state_machine.session.tenant();
+
+        let coord = query_state_machine.coord.clone();
+        let cmd_type = VnodeManagerCmdType::Split(vnode_id);
+        coord.vnode_manager(tenant, cmd_type).await?;
+
+        Ok(Output::Nil(()))
+    }
+}
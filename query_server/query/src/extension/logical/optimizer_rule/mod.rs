@@ -1,2 +1,4 @@
+pub mod push_down_time_bucket_aggregate;
 pub mod reject_cross_join;
+pub mod rewrite_last_value_scan;
 pub mod rewrite_tag_scan;
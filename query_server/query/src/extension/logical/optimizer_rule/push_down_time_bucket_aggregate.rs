@@ -0,0 +1,140 @@
+use datafusion::common::DFSchemaRef;
+use datafusion::datasource::source_as_provider;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::expr::AggregateFunction;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::logical_expr::{
+    aggregate_function, Aggregate, Expr, LogicalPlan, LogicalPlanBuilder, TableScan,
+};
+use datafusion::optimizer::optimizer::ApplyOrder;
+use datafusion::optimizer::{OptimizerConfig, OptimizerRule};
+use datafusion::prelude::{max, min, sum};
+
+use crate::data_source::batch::tskv::ClusterTable;
+
+/// Push down aggregates grouped by time buckets into the tskv table scan, each vnode
+/// returns partial states of time buckets, which are merged by the final aggregate.
+///
+/// Triggering conditions:
+/// 1. `Aggregate` groups by `date_bin(<interval>, time[, <origin>])` only
+/// 2. `Aggregate` only contains `count`, `max`, `min` and `sum` of fields
+/// 3. The input of `Aggregate` is the table scan, all filters are pushed down
+///
+/// ```text
+/// Projection: date_bin(..), MAX(MAX(t.value)) AS MAX(t.value), SUM(COUNT(t.value)) AS COUNT(t.value)
+///   Aggregate: groupBy=[[date_bin(..)]], aggr=[[MAX(MAX(t.value)), SUM(COUNT(t.value))]]
+///     TableScan: t, grouping=[date_bin(..)], agg=[MAX(t.value), COUNT(t.value)]
+/// ```
+pub struct PushDownTimeBucketAggregate {}
+
+impl OptimizerRule for PushDownTimeBucketAggregate {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _optimizer_config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        let (input, group_expr, aggr_expr, schema) = match plan {
+            LogicalPlan::Aggregate(Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+                ..
+            }) => (input, group_expr, aggr_expr, schema),
+            _ => return Ok(None),
+        };
+        let scan = match input.as_ref() {
+            LogicalPlan::TableScan(scan)
+                if scan.agg_with_grouping.is_none() && scan.fetch.is_none() =>
+            {
+                scan
+            }
+            _ => return Ok(None),
+        };
+        match source_as_provider(&scan.source)?
+            .as_any()
+            .downcast_ref::<ClusterTable>()
+        {
+            Some(t)
+                if !t.is_last_value()
+                    && t.supports_time_bucket_aggregate_pushdown(group_expr, aggr_expr) => {}
+            _ => return Ok(None),
+        }
+
+        // Columns of the pushed down aggregate are the same as the original aggregate.
+        let new_scan = LogicalPlan::TableScan(TableScan {
+            projection: None,
+            projected_schema: schema.clone(),
+            agg_with_grouping: Some(AggWithGrouping {
+                group_expr: group_expr.clone(),
+                agg_expr: aggr_expr.clone(),
+                schema: schema.clone(),
+            }),
+            ..scan.clone()
+        });
+
+        let fields = schema.fields();
+        let final_group_expr = fields[..group_expr.len()]
+            .iter()
+            .map(|f| Expr::Column(f.qualified_column()))
+            .collect::<Vec<_>>();
+        let final_aggr_expr = aggr_expr
+            .iter()
+            .zip(&fields[group_expr.len()..])
+            .map(|(e, f)| merge_partial_states(e, Expr::Column(f.qualified_column())))
+            .collect::<Result<Vec<_>>>()?;
+        let final_aggregate = LogicalPlanBuilder::from(new_scan)
+            .aggregate(final_group_expr, final_aggr_expr)?
+            .build()?;
+
+        let projection = restore_column_names(final_aggregate.schema(), schema);
+        let new_plan = LogicalPlanBuilder::from(final_aggregate)
+            .project(projection)?
+            .build()?;
+
+        Ok(Some(new_plan))
+    }
+
+    fn name(&self) -> &str {
+        "push_down_time_bucket_aggregate"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+}
+
+/// Returns the aggregate that merges partial states of the pushed down aggregate.
+fn merge_partial_states(pushed_aggr: &Expr, partial_state: Expr) -> Result<Expr> {
+    match pushed_aggr {
+        Expr::AggregateFunction(AggregateFunction { fun, .. }) => match fun {
+            aggregate_function::AggregateFunction::Max => Ok(max(partial_state)),
+            aggregate_function::AggregateFunction::Min => Ok(min(partial_state)),
+            aggregate_function::AggregateFunction::Count
+            | aggregate_function::AggregateFunction::Sum => Ok(sum(partial_state)),
+            _ => Err(DataFusionError::Internal(format!(
+                "Unsupported pushed aggregate function: {fun:?}."
+            ))),
+        },
+        _ => Err(DataFusionError::Internal(format!(
+            "Pushed aggregate expression is not an aggregate function: {pushed_aggr:?}."
+        ))),
+    }
+}
+
+/// Project columns of the final aggregate to columns of the original aggregate.
+fn restore_column_names(final_schema: &DFSchemaRef, original_schema: &DFSchemaRef) -> Vec<Expr> {
+    final_schema
+        .fields()
+        .iter()
+        .zip(original_schema.fields())
+        .map(|(f, original)| {
+            let column = Expr::Column(f.qualified_column());
+            if f.qualified_column() == original.qualified_column() {
+                column
+            } else {
+                column.alias(original.name())
+            }
+        })
+        .collect()
+}
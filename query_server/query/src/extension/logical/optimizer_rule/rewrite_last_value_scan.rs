@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use datafusion::datasource::{provider_as_source, source_as_provider};
+use datafusion::error::Result;
+use datafusion::logical_expr::expr::{AggregateFunction, AggregateUDF, Sort as SortExpr};
+use datafusion::logical_expr::{
+    aggregate_function, Aggregate, Expr, Filter, Limit, LogicalPlan, Projection, Sort, TableScan,
+};
+use datafusion::optimizer::optimizer::ApplyOrder;
+use datafusion::optimizer::utils::split_conjunction;
+use datafusion::optimizer::{OptimizerConfig, OptimizerRule};
+use models::schema::{ColumnType, TskvTableSchemaRef};
+
+use crate::data_source::batch::tskv::ClusterTable;
+use crate::extension::expr::aggregate_function::LAST_UDAF_NAME;
+
+/// Convert table scan to scan only the latest value of each field of each series
+///
+/// Triggering conditions:
+/// 1. `Aggregate` only contains `last(time, <field>)` or `max(time)`, and groups by tags
+/// 2. Or `Sort` by `time DESC` with fetch 1
+/// 3. Only tags and time are filtered between them and the table scan
+pub struct RewriteLastValueScan {}
+
+impl OptimizerRule for RewriteLastValueScan {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _optimizer_config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Aggregate(Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                ..
+            }) => {
+                let new_input = match rewrite_scan(input)? {
+                    Some((new_input, table_schema)) => {
+                        let valid = !aggr_expr.is_empty()
+                            && group_expr
+                                .iter()
+                                .all(|e| is_column_of(e, &table_schema, |c| c.is_tag()))
+                            && aggr_expr
+                                .iter()
+                                .all(|e| is_last_value_aggregate(e, &table_schema));
+                        if !valid {
+                            return Ok(None);
+                        }
+                        new_input
+                    }
+                    None => return Ok(None),
+                };
+                Ok(Some(plan.with_new_inputs(&[new_input])?))
+            }
+            LogicalPlan::Sort(Sort {
+                expr,
+                input,
+                fetch: Some(1),
+            }) => match rewrite_scan(input)? {
+                Some((new_input, table_schema)) if is_time_desc(expr, &table_schema) => {
+                    Ok(Some(plan.with_new_inputs(&[new_input])?))
+                }
+                _ => Ok(None),
+            },
+            LogicalPlan::Limit(Limit {
+                skip: 0,
+                fetch: Some(1),
+                input,
+            }) => match input.as_ref() {
+                LogicalPlan::Sort(Sort {
+                    expr,
+                    input: sort_input,
+                    ..
+                }) => match rewrite_scan(sort_input)? {
+                    Some((new_input, table_schema)) if is_time_desc(expr, &table_schema) => {
+                        let new_sort = input.with_new_inputs(&[new_input])?;
+                        Ok(Some(plan.with_new_inputs(&[new_sort])?))
+                    }
+                    _ => Ok(None),
+                },
+                _ => Ok(None),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "rewrite_last_value_scan"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::BottomUp)
+    }
+}
+
+/// Find the table scan through projections of columns and filters of tags and time,
+/// returns the plan with the table scan which only scans the latest row of each series.
+fn rewrite_scan(plan: &LogicalPlan) -> Result<Option<(LogicalPlan, TskvTableSchemaRef)>> {
+    match plan {
+        LogicalPlan::Projection(Projection { expr, input, .. })
+            if expr.iter().all(|e| matches!(e, Expr::Column(_))) =>
+        {
+            match rewrite_scan(input)? {
+                Some((new_input, table_schema)) => {
+                    Ok(Some((plan.with_new_inputs(&[new_input])?, table_schema)))
+                }
+                None => Ok(None),
+            }
+        }
+        LogicalPlan::Filter(Filter {
+            predicate, input, ..
+        }) => match rewrite_scan(input)? {
+            Some((new_input, table_schema)) if is_tag_or_time_filter(predicate, &table_schema) => {
+                Ok(Some((plan.with_new_inputs(&[new_input])?, table_schema)))
+            }
+            _ => Ok(None),
+        },
+        LogicalPlan::TableScan(scan) => {
+            let TableScan {
+                source,
+                projection,
+                filters,
+                fetch,
+                ..
+            } = scan;
+            let cluster_table = match source_as_provider(source)?
+                .as_any()
+                .downcast_ref::<ClusterTable>()
+            {
+                Some(t) if !t.is_last_value() => t.clone(),
+                _ => return Ok(None),
+            };
+            let table_schema = cluster_table.table_schema();
+
+            // Rows of the latest timestamp are read by values of fields.
+            let contain_field = match projection.as_ref() {
+                Some(p) => p
+                    .iter()
+                    .flat_map(|i| table_schema.column_by_index(*i))
+                    .any(|c| c.column_type.is_field()),
+                None => table_schema.field_num() > 0,
+            };
+            if !contain_field
+                || fetch.is_some()
+                || !filters
+                    .iter()
+                    .all(|f| is_tag_or_time_filter(f, &table_schema))
+            {
+                return Ok(None);
+            }
+
+            let new_scan = LogicalPlan::TableScan(TableScan {
+                source: provider_as_source(Arc::new(cluster_table.with_last_value())),
+                ..scan.clone()
+            });
+            Ok(Some((new_scan, table_schema)))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn is_column_of(
+    expr: &Expr,
+    table_schema: &TskvTableSchemaRef,
+    column_type_check: impl Fn(&ColumnType) -> bool,
+) -> bool {
+    match expr {
+        Expr::Column(c) => table_schema
+            .column(&c.name)
+            .map(|column| column_type_check(&column.column_type))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Check if the filter only references tags and time.
+fn is_tag_or_time_filter(filter: &Expr, table_schema: &TskvTableSchemaRef) -> bool {
+    split_conjunction(filter).into_iter().all(|e| {
+        e.to_columns()
+            .map(|columns| {
+                columns.iter().all(|c| {
+                    table_schema
+                        .column(&c.name)
+                        .map(|column| column.column_type.is_tag() || column.column_type.is_time())
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// Check if the aggregate is `last(time, <field>)` or `max(time)`.
+fn is_last_value_aggregate(expr: &Expr, table_schema: &TskvTableSchemaRef) -> bool {
+    match expr {
+        Expr::AggregateUDF(AggregateUDF {
+            fun,
+            args,
+            filter: None,
+            order_by: None,
+        }) => {
+            fun.name == LAST_UDAF_NAME
+                && args.len() == 2
+                && is_column_of(&args[0], table_schema, |c| c.is_time())
+                && is_column_of(&args[1], table_schema, |c| c.is_field())
+        }
+        Expr::AggregateFunction(AggregateFunction {
+            fun: aggregate_function::AggregateFunction::Max,
+            args,
+            distinct: false,
+            filter: None,
+            order_by: None,
+        }) => args.len() == 1 && is_column_of(&args[0], table_schema, |c| c.is_time()),
+        _ => false,
+    }
+}
+
+/// Check if the sort expression is `time DESC`.
+fn is_time_desc(sort_exprs: &[Expr], table_schema: &TskvTableSchemaRef) -> bool {
+    match sort_exprs {
+        [Expr::Sort(SortExpr {
+            expr, asc: false, ..
+        })] => is_column_of(expr, table_schema, |c| c.is_time()),
+        _ => false,
+    }
+}
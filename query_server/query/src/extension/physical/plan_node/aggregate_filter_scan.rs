@@ -13,7 +13,9 @@ use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use datafusion::physical_plan::{
     DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
 };
-use models::predicate::domain::{PredicateRef, PushedAggregateFunction};
+use models::predicate::domain::{
+    AggregateColumn, PredicateRef, PushedAggregateFunction, TimeBucket,
+};
 use models::predicate::PlacedSplit;
 use models::schema::TskvTableSchemaRef;
 use trace::{debug, SpanContext, SpanExt, SpanRecorder};
@@ -28,6 +30,8 @@ pub struct AggregateFilterTskvExec {
     schema: SchemaRef,
     table_schema: TskvTableSchemaRef,
     pushed_aggs: Vec<PushedAggregateFunction>,
+    /// Group aggregates by time buckets.
+    time_bucket: Option<TimeBucket>,
     filter: PredicateRef,
     splits: Vec<PlacedSplit>,
     metrics: ExecutionPlanMetricsSet,
@@ -39,6 +43,7 @@ impl AggregateFilterTskvExec {
         schema: SchemaRef,
         table_schema: TskvTableSchemaRef,
         pushed_aggs: Vec<PushedAggregateFunction>,
+        time_bucket: Option<TimeBucket>,
         filter: PredicateRef,
         splits: Vec<PlacedSplit>,
     ) -> Self {
@@ -47,6 +52,7 @@ impl AggregateFilterTskvExec {
             schema,
             table_schema,
             pushed_aggs,
+            time_bucket,
             filter,
             splits,
             metrics: ExecutionPlanMetricsSet::new(),
@@ -89,13 +95,13 @@ impl ExecutionPlan for AggregateFilterTskvExec {
     ) -> Result<SendableRecordBatchStream> {
         let mut agg_columns = Vec::with_capacity(self.pushed_aggs.len());
         for agg in self.pushed_aggs.iter() {
-            match agg {
-                PushedAggregateFunction::Count(column) => {
-                    if let Some(col) = self.table_schema.column(column) {
-                        agg_columns.push(col.clone());
-                    }
-                }
-            }
+            let column = self.table_schema.column(agg.column_name()).ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Column of pushed aggregate function not found: {:?}",
+                    agg
+                ))
+            })?;
+            agg_columns.push(AggregateColumn::new(agg.kind(), column.clone()));
         }
 
         let split = unsafe {
@@ -109,6 +115,8 @@ impl ExecutionPlan for AggregateFilterTskvExec {
             100_usize,
             split,
             Some(agg_columns),
+            self.time_bucket,
+            false,
             self.schema.clone(),
             self.table_schema.clone(),
         );
@@ -143,11 +151,11 @@ impl ExecutionPlan for AggregateFilterTskvExec {
     }
 
     fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "AggregateFilterTskvExec: agg=[{:?}], filter=[{:?}]",
-            self.pushed_aggs, self.filter
-        )
+        write!(f, "AggregateFilterTskvExec: agg=[{:?}], ", self.pushed_aggs)?;
+        if let Some(time_bucket) = self.time_bucket.as_ref() {
+            write!(f, "time_bucket=[{:?}], ", time_bucket)?;
+        }
+        write!(f, "filter=[{:?}]", self.filter)
     }
 }
 
@@ -157,6 +165,7 @@ impl Debug for AggregateFilterTskvExec {
             .field("schema", &self.schema)
             .field("table_schema", &self.table_schema)
             .field("pushed_aggs", &self.pushed_aggs)
+            .field("time_bucket", &self.time_bucket)
             .field("filter", &self.filter)
             .field("splits", &self.splits)
             .finish()
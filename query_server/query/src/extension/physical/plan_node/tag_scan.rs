@@ -259,6 +259,8 @@ impl TagScanStream {
             batch_size,
             split,
             None,
+            None,
+            false,
             proj_schema.clone(),
             proj_table_schema.into(),
         );
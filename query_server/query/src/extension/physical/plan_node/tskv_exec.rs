@@ -35,6 +35,8 @@ pub struct TskvExec {
     filter: PredicateRef,
     coord: CoordinatorRef,
     splits: Vec<PlacedSplit>,
+    /// Only scan the latest row of each series.
+    last_value: bool,
 
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
@@ -56,9 +58,16 @@ impl TskvExec {
             filter,
             coord,
             splits,
+            last_value: false,
             metrics,
         }
     }
+
+    pub(crate) fn with_last_value(mut self, last_value: bool) -> Self {
+        self.last_value = last_value;
+        self
+    }
+
     pub fn filter(&self) -> PredicateRef {
         self.filter.clone()
     }
@@ -95,6 +104,7 @@ impl ExecutionPlan for TskvExec {
             filter: self.filter.clone(),
             coord: self.coord.clone(),
             splits: self.splits.clone(),
+            last_value: self.last_value,
             metrics: self.metrics.clone(),
         }))
     }
@@ -130,6 +140,7 @@ impl ExecutionPlan for TskvExec {
             self.coord.clone(),
             split,
             batch_size,
+            self.last_value,
             metrics,
             SpanRecorder::new(span_ctx.child_span(format!("TableScanStream ({partition})"))),
         )
@@ -154,7 +165,11 @@ impl ExecutionPlan for TskvExec {
                     PredicateDisplay(&filter),
                     self.splits.len(),
                     fields.join(","),
-                )
+                )?;
+                if self.last_value {
+                    write!(f, ", last_value=true")?;
+                }
+                Ok(())
             }
         }
     }
@@ -176,6 +191,7 @@ impl std::fmt::Debug for TskvExec {
             .field("proj_schema", &self.proj_schema)
             .field("filter", &self.filter)
             .field("splits", &self.splits)
+            .field("last_value", &self.last_value)
             .finish()
     }
 }
@@ -210,12 +226,14 @@ pub struct TableScanStream {
 }
 
 impl TableScanStream {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         table_schema: TskvTableSchemaRef,
         proj_schema: SchemaRef,
         coord: CoordinatorRef,
         split: PlacedSplit,
         batch_size: usize,
+        last_value: bool,
         metrics: TableScanMetrics,
         span_recorder: SpanRecorder,
     ) -> Result<Self> {
@@ -261,6 +279,8 @@ impl TableScanStream {
             batch_size,
             split,
             None,
+            None,
+            last_value,
             proj_schema.clone(),
             proj_table_schema.into(),
         );
@@ -30,6 +30,8 @@ use spi::query::session::SessionCtx;
 use spi::Result;
 use trace::debug;
 
+use crate::extension::logical::optimizer_rule::push_down_time_bucket_aggregate::PushDownTimeBucketAggregate;
+use crate::extension::logical::optimizer_rule::rewrite_last_value_scan::RewriteLastValueScan;
 use crate::extension::logical::optimizer_rule::rewrite_tag_scan::RewriteTagScan;
 use crate::sql::analyzer::DefaultAnalyzer;
 
@@ -99,6 +101,8 @@ impl Default for DefaultLogicalOptimizer {
             // df default rules end
             // cnosdb rules
             Arc::new(RewriteTagScan {}),
+            Arc::new(RewriteLastValueScan {}),
+            Arc::new(PushDownTimeBucketAggregate {}),
         ];
 
         Self { analyzer, rules }
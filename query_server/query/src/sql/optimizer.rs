@@ -153,22 +153,28 @@ mod test {
     use std::sync::Arc;
 
     use coordinator::service_mock::{MockCoordinator, WITH_NONEMPTY_DATABASE_FOR_TEST};
+    use datafusion::arrow::datatypes::{IntervalDayTimeType, TimeUnit};
     use datafusion::datasource::provider_as_source;
     use datafusion::error::Result;
     use datafusion::execution::context::SessionState;
     use datafusion::execution::runtime_env::RuntimeEnv;
-    use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder, UNNAMED_TABLE};
+    use datafusion::logical_expr::expr::ScalarFunction;
+    use datafusion::logical_expr::{
+        BuiltinScalarFunction, LogicalPlan, LogicalPlanBuilder, UNNAMED_TABLE,
+    };
     use datafusion::optimizer::optimizer::Optimizer;
     use datafusion::optimizer::{OptimizerContext, OptimizerRule};
     use datafusion::physical_plan::displayable;
     use datafusion::physical_planner::{DefaultPhysicalPlanner, PhysicalPlanner};
-    use datafusion::prelude::{col, count, max, min, sum, Expr, SessionConfig};
+    use datafusion::prelude::{col, count, lit, max, min, sum, Expr, SessionConfig};
+    use datafusion::scalar::ScalarValue;
     use meta::model::meta_tenant::TenantMeta;
     use models::schema::{ColumnType, TableColumn, TskvTableSchema};
     use models::ValueType;
 
     use crate::data_source::batch::tskv::ClusterTable;
     use crate::data_source::split;
+    use crate::extension::logical::optimizer_rule::push_down_time_bucket_aggregate::PushDownTimeBucketAggregate;
 
     fn observe(_plan: &LogicalPlan, _rule: &dyn OptimizerRule) {}
 
@@ -371,11 +377,13 @@ mod test {
             test_plan(
                 plan,
                 "\
-                Aggregate: groupBy=[[]], aggr=[[MAX(?table?.value)]]\
-                \n  TableScan: ?table? projection=[value]",
+                Projection: MAX(MAX(?table?.value)) AS MAX(?table?.value)\
+                \n  Aggregate: groupBy=[[]], aggr=[[MAX(MAX(?table?.value))]]\
+                \n    TableScan: ?table?, grouping=[], agg=[AggregateFunction(AggregateFunction { fun: Max, args: [Column(Column { relation: Some(Bare { table: \"?table?\" }), name: \"value\" })], distinct: false, filter: None, order_by: None })]",
                 "\
-                AggregateExec: mode=Single, gby=[], aggr=[MAX(?table?.value)]\
-                \n  EmptyExec: produce_one_row=false\
+                ProjectionExec: expr=[MAX(MAX(?table?.value))@0 as MAX(?table?.value)]\
+                \n  AggregateExec: mode=Single, gby=[], aggr=[MAX(MAX(?table?.value))]\
+                \n    EmptyExec: produce_one_row=false\
                 \n",
             )
             .await?;
@@ -388,13 +396,15 @@ mod test {
         test_plan(
             plan,
             "\
-            Aggregate: groupBy=[[]], aggr=[[MAX(?table?.value)]]\
-            \n  TableScan: ?table? projection=[value]",
+            Projection: MAX(MAX(?table?.value)) AS MAX(?table?.value)\
+            \n  Aggregate: groupBy=[[]], aggr=[[MAX(MAX(?table?.value))]]\
+            \n    TableScan: ?table?, grouping=[], agg=[AggregateFunction(AggregateFunction { fun: Max, args: [Column(Column { relation: Some(Bare { table: \"?table?\" }), name: \"value\" })], distinct: false, filter: None, order_by: None })]",
             "\
-            AggregateExec: mode=Final, gby=[], aggr=[MAX(?table?.value)]\
-            \n  CoalescePartitionsExec\
-            \n    AggregateExec: mode=Partial, gby=[], aggr=[MAX(?table?.value)]\
-            \n      TskvExec: limit=None, predicate=ColumnDomains { column_to_domain: Some({}) }, split_num=8, projection=[value]\
+            ProjectionExec: expr=[MAX(MAX(?table?.value))@0 as MAX(?table?.value)]\
+            \n  AggregateExec: mode=Final, gby=[], aggr=[MAX(MAX(?table?.value))]\
+            \n    CoalescePartitionsExec\
+            \n      AggregateExec: mode=Partial, gby=[], aggr=[MAX(MAX(?table?.value))]\
+            \n        AggregateFilterTskvExec: agg=[[Max(\"value\")]], filter=[Predicate { pushed_down_domains: ColumnDomains { column_to_domain: Some({}) }, limit: None }]\
             \n",
         ).await
     }
@@ -451,11 +461,13 @@ mod test {
             test_plan(
                 plan,
                 "\
-                Aggregate: groupBy=[[]], aggr=[[MIN(?table?.value)]]\
-                \n  TableScan: ?table? projection=[value]",
+                Projection: MIN(MIN(?table?.value)) AS MIN(?table?.value)\
+                \n  Aggregate: groupBy=[[]], aggr=[[MIN(MIN(?table?.value))]]\
+                \n    TableScan: ?table?, grouping=[], agg=[AggregateFunction(AggregateFunction { fun: Min, args: [Column(Column { relation: Some(Bare { table: \"?table?\" }), name: \"value\" })], distinct: false, filter: None, order_by: None })]",
                 "\
-                AggregateExec: mode=Single, gby=[], aggr=[MIN(?table?.value)]\
-                \n  EmptyExec: produce_one_row=false\
+                ProjectionExec: expr=[MIN(MIN(?table?.value))@0 as MIN(?table?.value)]\
+                \n  AggregateExec: mode=Single, gby=[], aggr=[MIN(MIN(?table?.value))]\
+                \n    EmptyExec: produce_one_row=false\
                 \n",
             )
             .await?;
@@ -468,13 +480,15 @@ mod test {
         test_plan(
             plan,
             "\
-            Aggregate: groupBy=[[]], aggr=[[MIN(?table?.value)]]\
-            \n  TableScan: ?table? projection=[value]",
+            Projection: MIN(MIN(?table?.value)) AS MIN(?table?.value)\
+            \n  Aggregate: groupBy=[[]], aggr=[[MIN(MIN(?table?.value))]]\
+            \n    TableScan: ?table?, grouping=[], agg=[AggregateFunction(AggregateFunction { fun: Min, args: [Column(Column { relation: Some(Bare { table: \"?table?\" }), name: \"value\" })], distinct: false, filter: None, order_by: None })]",
             "\
-            AggregateExec: mode=Final, gby=[], aggr=[MIN(?table?.value)]\
-            \n  CoalescePartitionsExec\
-            \n    AggregateExec: mode=Partial, gby=[], aggr=[MIN(?table?.value)]\
-            \n      TskvExec: limit=None, predicate=ColumnDomains { column_to_domain: Some({}) }, split_num=8, projection=[value]\
+            ProjectionExec: expr=[MIN(MIN(?table?.value))@0 as MIN(?table?.value)]\
+            \n  AggregateExec: mode=Final, gby=[], aggr=[MIN(MIN(?table?.value))]\
+            \n    CoalescePartitionsExec\
+            \n      AggregateExec: mode=Partial, gby=[], aggr=[MIN(MIN(?table?.value))]\
+            \n        AggregateFilterTskvExec: agg=[[Min(\"value\")]], filter=[Predicate { pushed_down_domains: ColumnDomains { column_to_domain: Some({}) }, limit: None }]\
             \n",
         ).await
     }
@@ -531,11 +545,13 @@ mod test {
             test_plan(
                 plan,
                 "\
-                Aggregate: groupBy=[[]], aggr=[[SUM(?table?.value)]]\
-                \n  TableScan: ?table? projection=[value]",
+                Projection: SUM(SUM(?table?.value)) AS SUM(?table?.value)\
+                \n  Aggregate: groupBy=[[]], aggr=[[SUM(SUM(?table?.value))]]\
+                \n    TableScan: ?table?, grouping=[], agg=[AggregateFunction(AggregateFunction { fun: Sum, args: [Column(Column { relation: Some(Bare { table: \"?table?\" }), name: \"value\" })], distinct: false, filter: None, order_by: None })]",
                 "\
-                AggregateExec: mode=Single, gby=[], aggr=[SUM(?table?.value)]\
-                \n  EmptyExec: produce_one_row=false\
+                ProjectionExec: expr=[SUM(SUM(?table?.value))@0 as SUM(?table?.value)]\
+                \n  AggregateExec: mode=Single, gby=[], aggr=[SUM(SUM(?table?.value))]\
+                \n    EmptyExec: produce_one_row=false\
                 \n",
             )
             .await?;
@@ -548,14 +564,97 @@ mod test {
         test_plan(
             plan,
             "\
-            Aggregate: groupBy=[[]], aggr=[[SUM(?table?.value)]]\
-            \n  TableScan: ?table? projection=[value]",
+            Projection: SUM(SUM(?table?.value)) AS SUM(?table?.value)\
+            \n  Aggregate: groupBy=[[]], aggr=[[SUM(SUM(?table?.value))]]\
+            \n    TableScan: ?table?, grouping=[], agg=[AggregateFunction(AggregateFunction { fun: Sum, args: [Column(Column { relation: Some(Bare { table: \"?table?\" }), name: \"value\" })], distinct: false, filter: None, order_by: None })]",
             "\
-            AggregateExec: mode=Final, gby=[], aggr=[SUM(?table?.value)]\
-            \n  CoalescePartitionsExec\
-            \n    AggregateExec: mode=Partial, gby=[], aggr=[SUM(?table?.value)]\
-            \n      TskvExec: limit=None, predicate=ColumnDomains { column_to_domain: Some({}) }, split_num=8, projection=[value]\
+            ProjectionExec: expr=[SUM(SUM(?table?.value))@0 as SUM(?table?.value)]\
+            \n  AggregateExec: mode=Final, gby=[], aggr=[SUM(SUM(?table?.value))]\
+            \n    CoalescePartitionsExec\
+            \n      AggregateExec: mode=Partial, gby=[], aggr=[SUM(SUM(?table?.value))]\
+            \n        AggregateFilterTskvExec: agg=[[Sum(\"value\")]], filter=[Predicate { pushed_down_domains: ColumnDomains { column_to_domain: Some({}) }, limit: None }]\
             \n",
         ).await
     }
+
+    #[test]
+    fn test_push_down_time_bucket_aggregate() -> Result<()> {
+        let mut schema = TskvTableSchema::default();
+        schema.add_column(TableColumn::new_time_column(0, TimeUnit::Nanosecond));
+        schema.add_column(TableColumn::new_tag_column(1, "flag".to_string()));
+        schema.add_column(TableColumn::new_with_default(
+            "value".to_string(),
+            ColumnType::Field(ValueType::Integer),
+        ));
+        let provider = Arc::new(ClusterTable::new(
+            Arc::new(MockCoordinator::default()),
+            split::default_split_manager_ref_only_for_test(),
+            Arc::new(TenantMeta::mock()),
+            Arc::new(schema),
+        ));
+        let scan =
+            LogicalPlanBuilder::scan(UNNAMED_TABLE, provider_as_source(provider), None)?.build()?;
+        let one_minute = lit(ScalarValue::IntervalDayTime(Some(
+            IntervalDayTimeType::make_value(0, 60_000),
+        )));
+        let date_bin = Expr::ScalarFunction(ScalarFunction::new(
+            BuiltinScalarFunction::DateBin,
+            vec![one_minute, col("time")],
+        ));
+        let rule = PushDownTimeBucketAggregate {};
+        let config = OptimizerContext::new();
+
+        let plan = LogicalPlanBuilder::from(scan.clone())
+            .aggregate(
+                vec![date_bin.clone()],
+                vec![max(col("value")), count(col("value"))],
+            )?
+            .build()?;
+        let opt_plan = optimize_plan(&plan)?;
+        let new_plan = rule.try_optimize(&opt_plan, &config)?.unwrap();
+        assert_eq!(
+            new_plan.schema().field_names(),
+            opt_plan.schema().field_names()
+        );
+        let final_aggregate = match &new_plan {
+            LogicalPlan::Projection(p) => p.input.as_ref(),
+            _ => panic!("unexpected plan: {new_plan:?}"),
+        };
+        let pushed_scan = match final_aggregate {
+            LogicalPlan::Aggregate(a) => {
+                assert_eq!(a.group_expr.len(), 1);
+                assert_eq!(
+                    format!("{:?}", a.aggr_expr),
+                    "[MAX(MAX(?table?.value)), SUM(COUNT(?table?.value))]"
+                );
+                a.input.as_ref()
+            }
+            _ => panic!("unexpected plan: {final_aggregate:?}"),
+        };
+        match pushed_scan {
+            LogicalPlan::TableScan(s) => {
+                let agg_with_grouping = s.agg_with_grouping.as_ref().unwrap();
+                assert_eq!(agg_with_grouping.group_expr.len(), 1);
+                assert_eq!(agg_with_grouping.agg_expr.len(), 2);
+            }
+            _ => panic!("unexpected plan: {pushed_scan:?}"),
+        }
+
+        // Aggregates grouped by tags are not pushed down.
+        let plan = LogicalPlanBuilder::from(scan.clone())
+            .aggregate(vec![col("flag")], vec![max(col("value"))])?
+            .build()?;
+        assert!(rule
+            .try_optimize(&optimize_plan(&plan)?, &config)?
+            .is_none());
+        // Count of time is not pushed down with time buckets.
+        let plan = LogicalPlanBuilder::from(scan)
+            .aggregate(vec![date_bin], vec![count(col("time"))])?
+            .build()?;
+        assert!(rule
+            .try_optimize(&optimize_plan(&plan)?, &config)?
+            .is_none());
+
+        Ok(())
+    }
 }
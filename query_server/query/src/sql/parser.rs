@@ -16,13 +16,15 @@ use models::codec::Encoding;
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use snafu::ResultExt;
 use spi::query::ast::{
-    self, parse_string_value, Action, AlterDatabase, AlterTable, AlterTableAction, AlterTenant,
-    AlterTenantOperation, AlterUser, AlterUserOperation, ChecksumGroup, ColumnOption, CompactVnode,
-    CopyIntoLocation, CopyIntoTable, CopyTarget, CopyVnode, CreateDatabase, CreateRole,
-    CreateStream, CreateTable, CreateTenant, CreateUser, DatabaseOptions, DescribeDatabase,
-    DescribeTable, DropDatabaseObject, DropGlobalObject, DropTenantObject, DropVnode, Explain,
-    ExtStatement, GrantRevoke, MoveVnode, OutputMode, Privilege, ShowSeries, ShowTagBody,
-    ShowTagValues, Trigger, UriLocation, With,
+    self, parse_string_value, Action, AlterDatabase, AlterNodes, AlterTable, AlterTableAction,
+    AlterTenant, AlterTenantOperation, AlterUser, AlterUserOperation, BackupDatabase,
+    ChecksumGroup, ColumnOption, CompactVnode, CopyIntoLocation, CopyIntoTable, CopyTarget,
+    CopyVnode, CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant, CreateUser,
+    DatabaseOptions, DeleteFromTable, DescribeDatabase, DescribeTable, DropDatabaseObject,
+    DropGlobalObject, DropTenantObject, DropVnode, Explain, ExportDatabase, ExtStatement,
+    GrantRevoke, ImportDatabase, MoveVnode, OutputMode, Privilege, RenameDatabase, RestoreDatabase,
+    ShowSeries, ShowSeriesCardinality, ShowTagBody, ShowTagValues, ShowTagValuesCardinality,
+    SplitVnode, Trigger, UriLocation, With,
 };
 use spi::query::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
 use spi::query::parser::Parser as CnosdbParser;
@@ -56,6 +58,16 @@ enum CnosKeyWord {
     REPLICA,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     PRECISION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COLD_DURATION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACTION_STRATEGY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACTION_WINDOW,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    MAX_SERIES_PER_DATABASE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    MAX_SERIES_PER_TABLE,
 
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     QUERIES,
@@ -70,6 +82,8 @@ enum CnosKeyWord {
     REMOVE,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     SERIES,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CARDINALITY,
 
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     FILES,
@@ -89,8 +103,18 @@ enum CnosKeyWord {
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     COMPACT,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    SPLIT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     CHECKSUM,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    BACKUP,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    RESTORE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EXPORT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    IMPORT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     STREAM,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     STREAMS,
@@ -108,6 +132,14 @@ enum CnosKeyWord {
     APPEND,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     UNSET,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    LAST_VALUE_CACHE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    NODES,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    COMPACT_WRITE_RATE_LIMIT,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    FLUSH_WRITE_RATE_LIMIT,
 }
 
 impl FromStr for CnosKeyWord {
@@ -123,6 +155,11 @@ impl FromStr for CnosKeyWord {
             "VNODE_DURATION" => Ok(CnosKeyWord::VNODE_DURATION),
             "REPLICA" => Ok(CnosKeyWord::REPLICA),
             "PRECISION" => Ok(CnosKeyWord::PRECISION),
+            "COLD_DURATION" => Ok(CnosKeyWord::COLD_DURATION),
+            "COMPACTION_STRATEGY" => Ok(CnosKeyWord::COMPACTION_STRATEGY),
+            "COMPACTION_WINDOW" => Ok(CnosKeyWord::COMPACTION_WINDOW),
+            "MAX_SERIES_PER_DATABASE" => Ok(CnosKeyWord::MAX_SERIES_PER_DATABASE),
+            "MAX_SERIES_PER_TABLE" => Ok(CnosKeyWord::MAX_SERIES_PER_TABLE),
             "DATABASES" => Ok(CnosKeyWord::DATABASES),
             "QUERIES" => Ok(CnosKeyWord::QUERIES),
             "TENANT" => Ok(CnosKeyWord::TENANT),
@@ -131,6 +168,7 @@ impl FromStr for CnosKeyWord {
             "WRITE" => Ok(CnosKeyWord::WRITE),
             "REMOVE" => Ok(CnosKeyWord::REMOVE),
             "SERIES" => Ok(CnosKeyWord::SERIES),
+            "CARDINALITY" => Ok(CnosKeyWord::CARDINALITY),
             "FILES" => Ok(CnosKeyWord::FILES),
             "PATTERN" => Ok(CnosKeyWord::PATTERN),
             "FILE_FORMAT" => Ok(CnosKeyWord::FILE_FORMAT),
@@ -139,7 +177,12 @@ impl FromStr for CnosKeyWord {
             "NODE" => Ok(CnosKeyWord::NODE),
             "MOVE" => Ok(CnosKeyWord::MOVE),
             "COMPACT" => Ok(CnosKeyWord::COMPACT),
+            "SPLIT" => Ok(CnosKeyWord::SPLIT),
             "CHECKSUM" => Ok(CnosKeyWord::CHECKSUM),
+            "BACKUP" => Ok(CnosKeyWord::BACKUP),
+            "RESTORE" => Ok(CnosKeyWord::RESTORE),
+            "EXPORT" => Ok(CnosKeyWord::EXPORT),
+            "IMPORT" => Ok(CnosKeyWord::IMPORT),
             "STREAM" => Ok(CnosKeyWord::STREAM),
             "STREAMS" => Ok(CnosKeyWord::STREAMS),
             "TRIGGER" => Ok(CnosKeyWord::TRIGGER),
@@ -149,6 +192,10 @@ impl FromStr for CnosKeyWord {
             "COMPLETE" => Ok(CnosKeyWord::COMPLETE),
             "APPEND" => Ok(CnosKeyWord::APPEND),
             "UNSET" => Ok(CnosKeyWord::UNSET),
+            "LAST_VALUE_CACHE" => Ok(CnosKeyWord::LAST_VALUE_CACHE),
+            "NODES" => Ok(CnosKeyWord::NODES),
+            "COMPACT_WRITE_RATE_LIMIT" => Ok(CnosKeyWord::COMPACT_WRITE_RATE_LIMIT),
+            "FLUSH_WRITE_RATE_LIMIT" => Ok(CnosKeyWord::FLUSH_WRITE_RATE_LIMIT),
             _ => Err(ParserError::ParserError(format!(
                 "fail parse {} to CnosKeyWord",
                 s
@@ -272,6 +319,10 @@ impl<'a> ExtParser<'a> {
                     self.parser.next_token();
                     self.parse_explain()
                 }
+                Keyword::DELETE => {
+                    self.parser.next_token();
+                    self.parse_delete()
+                }
                 _ => {
                     if let Ok(word) = CnosKeyWord::from_str(&w.to_string()) {
                         return match word {
@@ -283,10 +334,30 @@ impl<'a> ExtParser<'a> {
                                 self.parser.next_token();
                                 self.parse_compact()
                             }
+                            CnosKeyWord::SPLIT => {
+                                self.parser.next_token();
+                                self.parse_split()
+                            }
                             CnosKeyWord::CHECKSUM => {
                                 self.parser.next_token();
                                 self.parse_checksum()
                             }
+                            CnosKeyWord::BACKUP => {
+                                self.parser.next_token();
+                                self.parse_backup()
+                            }
+                            CnosKeyWord::RESTORE => {
+                                self.parser.next_token();
+                                self.parse_restore()
+                            }
+                            CnosKeyWord::EXPORT => {
+                                self.parser.next_token();
+                                self.parse_export()
+                            }
+                            CnosKeyWord::IMPORT => {
+                                self.parser.next_token();
+                                self.parse_import()
+                            }
                             _ => Ok(ExtStatement::SqlStatement(Box::new(
                                 self.parser.parse_statement()?,
                             ))),
@@ -318,10 +389,18 @@ impl<'a> ExtParser<'a> {
         } else if self.parse_cnos_keyword(CnosKeyWord::DATABASES) {
             self.parse_show_databases()
         } else if self.parse_cnos_keyword(CnosKeyWord::SERIES) {
-            self.parse_show_series()
+            if self.parse_cnos_keyword(CnosKeyWord::CARDINALITY) {
+                self.parse_show_series_cardinality()
+            } else {
+                self.parse_show_series()
+            }
         } else if self.parse_cnos_keyword(CnosKeyWord::TAG) {
             if self.parser.parse_keyword(Keyword::VALUES) {
-                self.parse_show_tag_values()
+                if self.parse_cnos_keyword(CnosKeyWord::CARDINALITY) {
+                    self.parse_show_tag_values_cardinality()
+                } else {
+                    self.parse_show_tag_values()
+                }
             } else {
                 self.expected("VALUES", self.parser.peek_token())
             }
@@ -456,6 +535,35 @@ impl<'a> ExtParser<'a> {
         })))
     }
 
+    /// Parse `SHOW SERIES CARDINALITY [ON <database>] [FROM <table>]`
+    fn parse_show_series_cardinality(&mut self) -> Result<ExtStatement> {
+        let database_name = self.parse_on_database()?;
+        let table = if self.parser.parse_keyword(Keyword::FROM) {
+            Some(self.parser.parse_identifier()?)
+        } else {
+            None
+        };
+        Ok(ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+            database_name,
+            table,
+        }))
+    }
+
+    /// Parse `SHOW TAG VALUES CARDINALITY [ON <database>] FROM <table> WITH KEY ...`
+    fn parse_show_tag_values_cardinality(&mut self) -> Result<ExtStatement> {
+        let database_name = self.parse_on_database()?;
+        self.parser.expect_keyword(Keyword::FROM)?;
+        let table = self.parser.parse_identifier()?;
+        let with = self.parse_with()?;
+        Ok(ExtStatement::ShowTagValuesCardinality(Box::new(
+            ShowTagValuesCardinality {
+                database_name,
+                table,
+                with,
+            },
+        )))
+    }
+
     fn parse_explain(&mut self) -> Result<ExtStatement> {
         let analyze = self.parser.parse_keyword(Keyword::ANALYZE);
         let verbose = self.parser.parse_keyword(Keyword::VERBOSE);
@@ -530,11 +638,43 @@ impl<'a> ExtParser<'a> {
             self.parse_alter_tenant()
         } else if self.parser.parse_keyword(Keyword::USER) {
             self.parse_alter_user()
+        } else if self.parse_cnos_keyword(CnosKeyWord::NODES) {
+            self.parse_alter_nodes()
         } else {
-            self.expected("TABLE/DATABASE/TENANT/USER", self.parser.peek_token())
+            self.expected("TABLE/DATABASE/TENANT/USER/NODES", self.parser.peek_token())
         }
     }
 
+    /// Parse: ALTER NODES SET COMPACT_WRITE_RATE_LIMIT | FLUSH_WRITE_RATE_LIMIT <bytes_per_second>
+    fn parse_alter_nodes(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::SET)?;
+        let mut stmt = AlterNodes::default();
+        if self.parse_cnos_keyword(CnosKeyWord::COMPACT_WRITE_RATE_LIMIT) {
+            stmt.compact_write_rate_limit = Some(self.parse_number::<u64>()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::FLUSH_WRITE_RATE_LIMIT) {
+            stmt.flush_write_rate_limit = Some(self.parse_number::<u64>()?);
+        } else {
+            return self.expected(
+                "COMPACT_WRITE_RATE_LIMIT or FLUSH_WRITE_RATE_LIMIT",
+                self.parser.peek_token(),
+            );
+        }
+        Ok(ExtStatement::AlterNodes(stmt))
+    }
+
+    /// Parse a SQL DELETE statement:
+    /// DELETE FROM table_name [WHERE condition]
+    fn parse_delete(&mut self) -> Result<ExtStatement> {
+        self.parser.expect_keyword(Keyword::FROM)?;
+        let table_name = self.parser.parse_object_name()?;
+        let selection = self.parse_where()?;
+
+        Ok(ExtStatement::DeleteFromTable(DeleteFromTable {
+            table_name,
+            selection,
+        }))
+    }
+
     fn parse_alter_table(&mut self) -> Result<ExtStatement> {
         let table_name = self.parser.parse_object_name()?;
 
@@ -544,9 +684,56 @@ impl<'a> ExtParser<'a> {
             self.parse_alter_table_alter_column(table_name)
         } else if self.parser.parse_keyword(Keyword::DROP) {
             self.parse_alter_table_drop_column(table_name)
+        } else if self.parser.parse_keyword(Keyword::RENAME) {
+            self.parse_alter_table_rename(table_name)
+        } else if self.parser.parse_keyword(Keyword::SET) {
+            self.parse_alter_table_set(table_name)
         } else {
-            self.expected("ADD or ALTER or DROP", self.parser.peek_token())
+            self.expected(
+                "ADD or ALTER or DROP or RENAME or SET",
+                self.parser.peek_token(),
+            )
+        }
+    }
+
+    fn parse_alter_table_set(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
+        // parse: SET LAST_VALUE_CACHE TRUE | FALSE
+        self.expect_cnos_keyword(CnosKeyWord::LAST_VALUE_CACHE)?;
+        let enabled = if self.parser.parse_keyword(Keyword::TRUE) {
+            true
+        } else if self.parser.parse_keyword(Keyword::FALSE) {
+            false
+        } else {
+            return self.expected("TRUE or FALSE", self.parser.peek_token());
+        };
+        Ok(ExtStatement::AlterTable(AlterTable {
+            table_name,
+            alter_action: AlterTableAction::SetLastValueCache { enabled },
+        }))
+    }
+
+    fn parse_alter_table_rename(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
+        // parse: RENAME COLUMN old_name TO new_name
+        if self.parser.parse_keyword(Keyword::COLUMN) {
+            let old_column_name = self.parser.parse_identifier()?;
+            self.parser.expect_keyword(Keyword::TO)?;
+            let new_column_name = self.parser.parse_identifier()?;
+            return Ok(ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                },
+            }));
         }
+
+        // parse: RENAME TO new_name
+        self.parser.expect_keyword(Keyword::TO)?;
+        let new_table_name = self.parser.parse_identifier()?;
+        Ok(ExtStatement::AlterTable(AlterTable {
+            table_name,
+            alter_action: AlterTableAction::RenameTable { new_table_name },
+        }))
     }
 
     fn parse_alter_table_add_column(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
@@ -577,10 +764,23 @@ impl<'a> ExtParser<'a> {
     }
 
     fn parse_alter_table_alter_column(&mut self, table_name: ObjectName) -> Result<ExtStatement> {
+        let _ = self.parser.parse_keyword(Keyword::COLUMN);
         let column_name = self.parser.parse_identifier()?;
-        // parse: SET CODEC(encoding_type)
         self.parser.expect_keyword(Keyword::SET)?;
 
+        // parse: SET DATA TYPE data_type
+        if self.parser.parse_keywords(&[Keyword::DATA, Keyword::TYPE]) {
+            let data_type = self.parser.parse_data_type()?;
+            return Ok(ExtStatement::AlterTable(AlterTable {
+                table_name,
+                alter_action: AlterTableAction::AlterColumnDataType {
+                    column_name,
+                    data_type,
+                },
+            }));
+        }
+
+        // parse: SET CODEC(encoding_type)
         self.expect_cnos_keyword(CnosKeyWord::CODEC)?;
         let encoding = self.parse_codec_type()?;
         Ok(ExtStatement::AlterTable(AlterTable {
@@ -594,6 +794,14 @@ impl<'a> ExtParser<'a> {
 
     fn parse_alter_database(&mut self) -> Result<ExtStatement> {
         let database_name = self.parser.parse_identifier()?;
+        if self.parser.parse_keyword(Keyword::RENAME) {
+            self.parser.expect_keyword(Keyword::TO)?;
+            let new_name = self.parser.parse_identifier()?;
+            return Ok(ExtStatement::RenameDatabase(RenameDatabase {
+                name: database_name,
+                new_name,
+            }));
+        }
         self.parser.expect_keyword(Keyword::SET)?;
         let mut options = DatabaseOptions::default();
         if !self.parse_database_option(&mut options)? {
@@ -864,6 +1072,16 @@ impl<'a> ExtParser<'a> {
             options.replica = Some(self.parse_number::<u64>()?);
         } else if self.parse_cnos_keyword(CnosKeyWord::PRECISION) {
             options.precision = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::COLD_DURATION) {
+            options.cold_duration = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::COMPACTION_STRATEGY) {
+            options.compaction_strategy = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::COMPACTION_WINDOW) {
+            options.compaction_window = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::MAX_SERIES_PER_DATABASE) {
+            options.max_series_per_database = Some(self.parse_number::<u64>()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::MAX_SERIES_PER_TABLE) {
+            options.max_series_per_table = Some(self.parse_number::<u64>()?);
         } else {
             return Ok(false);
         }
@@ -1444,6 +1662,15 @@ impl<'a> ExtParser<'a> {
         }
     }
 
+    fn parse_split(&mut self) -> Result<ExtStatement> {
+        if self.parse_cnos_keyword(CnosKeyWord::VNODE) {
+            let vnode_id = self.parse_number::<VnodeId>()?;
+            Ok(ExtStatement::SplitVnode(SplitVnode { vnode_id }))
+        } else {
+            parser_err!("Expected VNODE, after SPLIT")
+        }
+    }
+
     fn parse_checksum(&mut self) -> Result<ExtStatement> {
         if self.parser.parse_keyword(Keyword::GROUP) {
             let replication_set_id = self.parse_number::<ReplicationSetId>()?;
@@ -1455,6 +1682,75 @@ impl<'a> ExtParser<'a> {
         }
     }
 
+    /// Parse `BACKUP DATABASE <name> TO '<location>'`
+    fn parse_backup(&mut self) -> Result<ExtStatement> {
+        if self.parser.parse_keyword(Keyword::DATABASE) {
+            let name = self.parser.parse_identifier()?;
+            self.parser.expect_keyword(Keyword::TO)?;
+            let location = self.parser.parse_literal_string()?;
+            Ok(ExtStatement::BackupDatabase(BackupDatabase {
+                name,
+                location,
+            }))
+        } else {
+            parser_err!("Expected DATABASE, after BACKUP")
+        }
+    }
+
+    /// Parse `RESTORE DATABASE <name> FROM '<location>'`
+    fn parse_restore(&mut self) -> Result<ExtStatement> {
+        if self.parser.parse_keyword(Keyword::DATABASE) {
+            let name = self.parser.parse_identifier()?;
+            self.parser.expect_keyword(Keyword::FROM)?;
+            let location = self.parser.parse_literal_string()?;
+            Ok(ExtStatement::RestoreDatabase(RestoreDatabase {
+                name,
+                location,
+            }))
+        } else {
+            parser_err!("Expected DATABASE, after RESTORE")
+        }
+    }
+
+    /// Parse `EXPORT DATABASE <name> [TABLE <table>] TO '<location>'`
+    fn parse_export(&mut self) -> Result<ExtStatement> {
+        if self.parser.parse_keyword(Keyword::DATABASE) {
+            let name = self.parser.parse_identifier()?;
+            let table = if self.parser.parse_keyword(Keyword::TABLE) {
+                Some(self.parser.parse_identifier()?)
+            } else {
+                None
+            };
+            self.parser.expect_keyword(Keyword::TO)?;
+            let location = self.parser.parse_literal_string()?;
+            Ok(ExtStatement::ExportDatabase(ExportDatabase {
+                name,
+                table,
+                location,
+            }))
+        } else {
+            parser_err!("Expected DATABASE, after EXPORT")
+        }
+    }
+
+    /// Parse `IMPORT DATABASE <name> TABLE <table> FROM '<location>'`
+    fn parse_import(&mut self) -> Result<ExtStatement> {
+        if self.parser.parse_keyword(Keyword::DATABASE) {
+            let name = self.parser.parse_identifier()?;
+            self.parser.expect_keyword(Keyword::TABLE)?;
+            let table = self.parser.parse_identifier()?;
+            self.parser.expect_keyword(Keyword::FROM)?;
+            let location = self.parser.parse_literal_string()?;
+            Ok(ExtStatement::ImportDatabase(ImportDatabase {
+                name,
+                table,
+                location,
+            }))
+        } else {
+            parser_err!("Expected DATABASE, after IMPORT")
+        }
+    }
+
     fn consume_token(&mut self, expected: &Token) -> bool {
         if self.parser.peek_token().token == *expected {
             self.parser.next_token();
@@ -1904,14 +2200,14 @@ mod tests {
 
     #[test]
     fn test_create_database() {
-        let sql = "CREATE DATABASE test WITH TTl '10d' SHARD 5 VNOdE_DURATiON '3d' REPLICA 10 pRECISIOn 'us';";
+        let sql = "CREATE DATABASE test WITH TTl '10d' SHARD 5 VNOdE_DURATiON '3d' REPLICA 10 pRECISIOn 'us' COLD_DURATION '30d' COMPACTION_STRATEGY 'time_window' COMPACTION_WINDOW '1d' MAX_SERIES_PER_DATABASE 1000000 MAX_SERIES_PER_TABLE 100000;";
         let statements = ExtParser::parse_sql(sql).unwrap();
         assert_eq!(statements.len(), 1);
         match statements[0] {
             ExtStatement::CreateDatabase(ref stmt) => {
                 let ans = format!("{:?}", stmt);
                 println!("{ans}");
-                let expectd = "CreateDatabase { name: Ident { value: \"test\", quote_style: None }, if_not_exists: false, options: DatabaseOptions { ttl: Some(\"10d\"), shard_num: Some(5), vnode_duration: Some(\"3d\"), replica: Some(10), precision: Some(\"us\") } }";
+                let expectd = "CreateDatabase { name: Ident { value: \"test\", quote_style: None }, if_not_exists: false, options: DatabaseOptions { ttl: Some(\"10d\"), shard_num: Some(5), vnode_duration: Some(\"3d\"), replica: Some(10), precision: Some(\"us\"), cold_duration: Some(\"30d\"), compaction_strategy: Some(\"time_window\"), compaction_window: Some(\"1d\"), max_series_per_database: Some(1000000), max_series_per_table: Some(100000) } }";
                 assert_eq!(ans, expectd);
             }
             _ => panic!("impossible"),
@@ -1933,6 +2229,10 @@ mod tests {
             ALTER TABLE m DROP f;
             ALTER TABLE m ALTER f SET CODEC(DEFAULT);
             ALTER TABLE m ALTER TIME SET CODEC(NULL);
+            ALTER TABLE m ALTER COLUMN f SET DATA TYPE DOUBLE;
+            ALTER TABLE m RENAME COLUMN f TO f2;
+            ALTER TABLE m RENAME TO m2;
+            ALTER TABLE m SET LAST_VALUE_CACHE TRUE;
         "#;
         let statement = ExtParser::parse_sql(sql).unwrap();
         let statement: Vec<AlterTable> = statement
@@ -1992,11 +2292,74 @@ mod tests {
                         column_name: Ident::from("TIME"),
                         encoding: Encoding::Null
                     }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::AlterColumnDataType {
+                        column_name: Ident::from("f"),
+                        data_type: DataType::Double
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::RenameColumn {
+                        old_column_name: Ident::from("f"),
+                        new_column_name: Ident::from("f2")
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::RenameTable {
+                        new_table_name: Ident::from("m2")
+                    }
+                },
+                AlterTable {
+                    table_name: ObjectName(vec![Ident::from("m")]),
+                    alter_action: AlterTableAction::SetLastValueCache { enabled: true }
                 }
             ]
         );
     }
 
+    #[test]
+    fn test_rename_database() {
+        let sql = "ALTER DATABASE db1 RENAME TO db2";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::RenameDatabase(RenameDatabase {
+                name: Ident::from("db1"),
+                new_name: Ident::from("db2"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_delete_from_table() {
+        let sql = r#"
+            DELETE FROM m;
+            DELETE FROM public.m WHERE t = 'a' AND time < 100;
+        "#;
+        let statement: Vec<DeleteFromTable> = ExtParser::parse_sql(sql)
+            .unwrap()
+            .into_iter()
+            .map(|s| match s {
+                ExtStatement::DeleteFromTable(s) => s,
+                _ => panic!("Expect DeleteFromTable"),
+            })
+            .collect();
+        assert_eq!(statement[0].table_name, ObjectName(vec![Ident::from("m")]));
+        assert!(statement[0].selection.is_none());
+        assert_eq!(
+            statement[1].table_name,
+            ObjectName(vec![Ident::from("public"), Ident::from("m")])
+        );
+        assert_eq!(
+            statement[1].selection.as_ref().unwrap().to_string(),
+            "t = 'a' AND time < 100"
+        );
+    }
+
     #[test]
     fn test_parse_copy_into() {
         let sql = r#"
@@ -2071,6 +2434,129 @@ mod tests {
                 replication_set_id: 10
             })
         );
+        let sql6 = "split vnode 11;";
+        let statement = ExtParser::parse_sql(sql6).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::SplitVnode(SplitVnode { vnode_id: 11 })
+        );
+    }
+
+    #[test]
+    fn test_alter_nodes() {
+        let sql = "alter nodes set compact_write_rate_limit 104857600;";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::AlterNodes(AlterNodes {
+                compact_write_rate_limit: Some(104857600),
+                flush_write_rate_limit: None,
+            })
+        );
+        let sql = "ALTER NODES SET FLUSH_WRITE_RATE_LIMIT 0";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::AlterNodes(AlterNodes {
+                compact_write_rate_limit: None,
+                flush_write_rate_limit: Some(0),
+            })
+        );
+        assert!(ExtParser::parse_sql("ALTER NODES SET WRITE_RATE_LIMIT 1").is_err());
+        assert!(ExtParser::parse_sql("ALTER NODES SET FLUSH_WRITE_RATE_LIMIT -1").is_err());
+    }
+
+    #[test]
+    fn test_backup_and_restore_database() {
+        let sql = "backup database db1 to '/tmp/backup';";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::BackupDatabase(BackupDatabase {
+                name: Ident::new("db1"),
+                location: "/tmp/backup".to_string(),
+            })
+        );
+        let sql = "restore database db1 from 's3://bucket/backup';";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::RestoreDatabase(RestoreDatabase {
+                name: Ident::new("db1"),
+                location: "s3://bucket/backup".to_string(),
+            })
+        );
+        assert!(ExtParser::parse_sql("backup db1 to '/tmp/backup'").is_err());
+        assert!(ExtParser::parse_sql("restore database db1 to '/tmp/backup'").is_err());
+    }
+
+    #[test]
+    fn test_export_and_import_database() {
+        let sql = "export database db1 to '/tmp/export';";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ExportDatabase(ExportDatabase {
+                name: Ident::new("db1"),
+                table: None,
+                location: "/tmp/export".to_string(),
+            })
+        );
+        let sql = "export database db1 table tb1 to 's3://bucket/export';";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ExportDatabase(ExportDatabase {
+                name: Ident::new("db1"),
+                table: Some(Ident::new("tb1")),
+                location: "s3://bucket/export".to_string(),
+            })
+        );
+        let sql = "import database db1 table tb1 from '/tmp/export';";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ImportDatabase(ImportDatabase {
+                name: Ident::new("db1"),
+                table: Ident::new("tb1"),
+                location: "/tmp/export".to_string(),
+            })
+        );
+        assert!(ExtParser::parse_sql("export database db1 from '/tmp/export'").is_err());
+        assert!(ExtParser::parse_sql("import database db1 from '/tmp/export'").is_err());
+    }
+
+    #[test]
+    fn test_show_cardinality() {
+        let sql = "show series cardinality on db1 from tb1;";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+                database_name: Some(Ident::new("db1")),
+                table: Some(Ident::new("tb1")),
+            })
+        );
+        let sql = "show series cardinality;";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowSeriesCardinality(ShowSeriesCardinality {
+                database_name: None,
+                table: None,
+            })
+        );
+        let sql = "show tag values cardinality from tb1 with key in (t1, t2);";
+        let statement = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(
+            statement[0],
+            ExtStatement::ShowTagValuesCardinality(Box::new(ShowTagValuesCardinality {
+                database_name: None,
+                table: Ident::new("tb1"),
+                with: With::In(vec![Ident::new("t1"), Ident::new("t2")]),
+            }))
+        );
+        assert!(ExtParser::parse_sql("show tag values cardinality from tb1").is_err());
     }
 
     #[test]
@@ -31,13 +31,15 @@ use datafusion::logical_expr::{
     EmptyRelation, Explain, Expr, LogicalPlan, LogicalPlanBuilder, Operator, PlanType,
     SubqueryAlias, TableSource, ToStringifiedPlan, Union,
 };
+use datafusion::optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext};
+use datafusion::physical_expr::execution_props::ExecutionProps;
 use datafusion::prelude::{col, SessionConfig};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::parser::CreateExternalTable as AstCreateExternalTable;
 use datafusion::sql::planner::{object_name_to_table_reference, PlannerContext, SqlToRel};
 use datafusion::sql::sqlparser::ast::{
-    DataType as SQLDataType, Expr as ASTExpr, Ident, ObjectName, Offset, OrderByExpr, Query,
-    SqlOption, Statement, TableAlias, TableFactor, TimezoneInfo,
+    DataType as SQLDataType, ExactNumberInfo, Expr as ASTExpr, Ident, ObjectName, Offset,
+    OrderByExpr, Query, SqlOption, Statement, TableAlias, TableFactor, TimezoneInfo,
 };
 use datafusion::sql::sqlparser::parser::ParserError;
 use datafusion::sql::TableReference;
@@ -48,38 +50,48 @@ use models::auth::privilege::{
 };
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::User;
+use models::codec::Encoding;
+use models::decimal::Decimal;
 use models::gis::data_type::{Geometry, GeometryType};
 use models::object_reference::{Resolve, ResolvedTable};
 use models::oid::{Identifier, Oid};
+use models::predicate::domain::Predicate;
 use models::schema::{
-    ColumnType, DatabaseOptions, Duration, Precision, TableColumn, Tenant, TskvTableSchema,
-    TskvTableSchemaRef, Watermark, DEFAULT_CATALOG, DEFAULT_DATABASE, TIME_FIELD,
+    ColumnType, CompactionStrategy, DatabaseOptions, Duration, Precision, TableColumn, Tenant,
+    TskvTableSchema, TskvTableSchemaRef, Watermark, DEFAULT_CATALOG, DEFAULT_DATABASE, TIME_FIELD,
 };
 use models::utils::SeqIdGenerator;
 use models::{ColumnId, ValueType};
 use object_store::ObjectStore;
 use spi::query::ast;
 use spi::query::ast::{
-    AlterDatabase as ASTAlterDatabase, AlterTable as ASTAlterTable,
+    AlterDatabase as ASTAlterDatabase, AlterNodes as ASTAlterNodes, AlterTable as ASTAlterTable,
     AlterTableAction as ASTAlterTableAction, AlterTenantOperation, AlterUserOperation,
-    ChecksumGroup as ASTChecksumGroup, ColumnOption, CompactVnode as ASTCompactVnode,
-    CopyIntoTable, CopyTarget, CopyVnode as ASTCopyVnode, CreateDatabase as ASTCreateDatabase,
-    CreateTable as ASTCreateTable, DatabaseOptions as ASTDatabaseOptions,
+    BackupDatabase as ASTBackupDatabase, ChecksumGroup as ASTChecksumGroup, ColumnOption,
+    CompactVnode as ASTCompactVnode, CopyIntoTable, CopyTarget, CopyVnode as ASTCopyVnode,
+    CreateDatabase as ASTCreateDatabase, CreateTable as ASTCreateTable,
+    DatabaseOptions as ASTDatabaseOptions, DeleteFromTable as ASTDeleteFromTable,
     DescribeDatabase as DescribeDatabaseOptions, DescribeTable as DescribeTableOptions,
-    DropVnode as ASTDropVnode, ExtStatement, MoveVnode as ASTMoveVnode,
-    ShowSeries as ASTShowSeries, ShowTagBody, ShowTagValues as ASTShowTagValues, UriLocation, With,
+    DropVnode as ASTDropVnode, ExportDatabase as ASTExportDatabase, ExtStatement,
+    ImportDatabase as ASTImportDatabase, MoveVnode as ASTMoveVnode,
+    RenameDatabase as ASTRenameDatabase, RestoreDatabase as ASTRestoreDatabase,
+    ShowSeries as ASTShowSeries, ShowSeriesCardinality as ASTShowSeriesCardinality, ShowTagBody,
+    ShowTagValues as ASTShowTagValues, ShowTagValuesCardinality as ASTShowTagValuesCardinality,
+    SplitVnode as ASTSplitVnode, UriLocation, With,
 };
 use spi::query::datasource::{self, UriSchema};
 use spi::query::logical_planner::{
     normalize_sql_object_name_to_string, parse_connection_options,
     sql_option_to_alter_tenant_action, sql_options_to_map, sql_options_to_tenant_options,
-    sql_options_to_user_options, unset_option_to_alter_tenant_action, AlterDatabase, AlterTable,
-    AlterTableAction, AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser,
-    AlterUser, AlterUserAction, ChecksumGroup, CompactVnode, CopyOptions, CopyOptionsBuilder,
-    CopyVnode, CreateDatabase, CreateRole, CreateStreamTable, CreateTable, CreateTenant,
-    CreateUser, DDLPlan, DatabaseObjectType, DropDatabaseObject, DropGlobalObject,
-    DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder, GlobalObjectType,
-    GrantRevoke, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan, SYSPlan,
+    sql_options_to_user_options, unset_option_to_alter_tenant_action, AlterDatabase, AlterNodes,
+    AlterTable, AlterTableAction, AlterTenant, AlterTenantAction, AlterTenantAddUser,
+    AlterTenantSetUser, AlterUser, AlterUserAction, BackupDatabase, ChecksumGroup, CompactVnode,
+    CopyOptions, CopyOptionsBuilder, CopyVnode, CreateDatabase, CreateRole, CreateStreamTable,
+    CreateTable, CreateTenant, CreateUser, DDLPlan, DatabaseObjectType, DeleteFromTable,
+    DropDatabaseObject, DropGlobalObject, DropTenantObject, DropVnode, ExportDatabase,
+    FileFormatOptions, FileFormatOptionsBuilder, GlobalObjectType, GrantRevoke, ImportDatabase,
+    LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan, RenameDatabase,
+    RestoreDatabase, SYSPlan, ShowSeriesCardinality, ShowTagValuesCardinality, SplitVnode,
     TenantObjectType,
 };
 use spi::query::session::SessionCtx;
@@ -161,6 +173,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::ShowDatabases() => self.show_databases_to_plan(session),
             ExtStatement::ShowTables(stmt) => self.show_tables_to_plan(stmt, session),
             ExtStatement::AlterDatabase(stmt) => self.database_to_alter(stmt, session),
+            ExtStatement::RenameDatabase(stmt) => self.database_to_rename(stmt, session),
             ExtStatement::ShowSeries(stmt) => self.show_series_to_plan(*stmt, session),
             ExtStatement::Explain(stmt) => {
                 self.explain_statement_to_plan(
@@ -172,9 +185,17 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                 .await
             }
             ExtStatement::ShowTagValues(stmt) => self.show_tag_values(*stmt, session),
+            ExtStatement::ShowSeriesCardinality(stmt) => {
+                self.show_series_cardinality_to_plan(stmt, session)
+            }
+            ExtStatement::ShowTagValuesCardinality(stmt) => {
+                self.show_tag_values_cardinality_to_plan(*stmt, session)
+            }
             ExtStatement::AlterTable(stmt) => self.table_to_alter(stmt, session),
+            ExtStatement::DeleteFromTable(stmt) => self.delete_from_table_to_plan(stmt, session),
             ExtStatement::AlterTenant(stmt) => self.alter_tenant_to_plan(stmt).await,
             ExtStatement::AlterUser(stmt) => self.alter_user_to_plan(stmt).await,
+            ExtStatement::AlterNodes(stmt) => self.alter_nodes_to_plan(stmt),
             ExtStatement::GrantRevoke(stmt) => self.grant_revoke_to_plan(stmt, session),
             // system statement
             ExtStatement::ShowQueries => {
@@ -191,7 +212,12 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::CopyVnode(stmt) => self.copy_vnode_to_plan(stmt),
             ExtStatement::MoveVnode(stmt) => self.move_vnode_to_plan(stmt),
             ExtStatement::CompactVnode(stmt) => self.compact_vnode_to_plan(stmt),
+            ExtStatement::SplitVnode(stmt) => self.split_vnode_to_plan(stmt),
             ExtStatement::ChecksumGroup(stmt) => self.checksum_group_to_plan(stmt),
+            ExtStatement::BackupDatabase(stmt) => self.backup_database_to_plan(stmt),
+            ExtStatement::RestoreDatabase(stmt) => self.restore_database_to_plan(stmt),
+            ExtStatement::ExportDatabase(stmt) => self.export_database_to_plan(stmt),
+            ExtStatement::ImportDatabase(stmt) => self.import_database_to_plan(stmt),
             ExtStatement::CreateStream(_) => Err(QueryError::NotImplemented {
                 err: "CreateStream Planner.".to_string(),
             }),
@@ -836,6 +862,97 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     new_column,
                 }
             }
+            ASTAlterTableAction::AlterColumnDataType {
+                column_name,
+                data_type,
+            } => {
+                let column_name = normalize_ident(column_name);
+                let column = table_schema.column(&column_name).ok_or_else(|| {
+                    QueryError::ColumnNotExists {
+                        column: column_name.to_string(),
+                        table: table_schema.name.to_string(),
+                    }
+                })?;
+                if column.column_type.is_time() {
+                    return Err(QueryError::TimeColumnAlter);
+                }
+
+                let column_type = self.make_data_type(&column_name, &data_type, time_unit)?;
+                let change_type_err = || QueryError::ChangeColumnDataType {
+                    column: column_name.to_string(),
+                    from: column.column_type.to_string(),
+                    to: data_type.to_string(),
+                };
+                // Only values of primitive field types are converted.
+                let is_convertible = match (&column.column_type, &column_type) {
+                    (ColumnType::Field(from), ColumnType::Field(to)) => {
+                        is_primitive_value_type(from)
+                            && is_primitive_value_type(to)
+                            && from != to
+                            && from
+                                .to_physical_type()
+                                .can_convert_to(to.to_physical_type())
+                    }
+                    _ => false,
+                };
+                if !is_convertible {
+                    return Err(change_type_err());
+                }
+
+                let mut new_column = column.clone();
+                new_column.column_type = column_type;
+                // Reset the encoding if it's not supported by the new data type.
+                let column_opt = ColumnOption::new_field(
+                    Ident::new(&column_name),
+                    data_type,
+                    Some(column.encoding),
+                );
+                if Self::check_column_encoding(&column_opt).is_err() {
+                    new_column.encoding = Encoding::Default;
+                }
+
+                AlterTableAction::AlterColumn {
+                    column_name,
+                    new_column,
+                }
+            }
+            ASTAlterTableAction::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                let column_name = normalize_ident(old_column_name);
+                let column = table_schema.column(&column_name).ok_or_else(|| {
+                    QueryError::ColumnNotExists {
+                        column: column_name.to_string(),
+                        table: table_schema.name.to_string(),
+                    }
+                })?;
+                if column.column_type.is_time() {
+                    return Err(QueryError::TimeColumnAlter);
+                }
+
+                let new_column_name = normalize_ident(new_column_name);
+                if table_schema.contains_column(&new_column_name) {
+                    return Err(QueryError::ColumnAlreadyExists {
+                        table: table_schema.name.to_string(),
+                        column: new_column_name,
+                    });
+                }
+
+                let mut new_column = column.clone();
+                new_column.name = new_column_name;
+
+                AlterTableAction::AlterColumn {
+                    column_name,
+                    new_column,
+                }
+            }
+            ASTAlterTableAction::RenameTable { new_table_name } => AlterTableAction::RenameTable {
+                new_table_name: normalize_ident(new_table_name),
+            },
+            ASTAlterTableAction::SetLastValueCache { enabled } => {
+                AlterTableAction::SetLastValueCache { enabled }
+            }
         };
         let plan = Plan::DDL(DDLPlan::AlterTable(AlterTable {
             table_name,
@@ -855,6 +972,70 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn delete_from_table_to_plan(
+        &self,
+        statement: ASTDeleteFromTable,
+        session: &SessionCtx,
+    ) -> Result<PlanWithPrivileges> {
+        let ASTDeleteFromTable {
+            table_name,
+            selection,
+        } = statement;
+
+        let table_ref = normalize_sql_object_name(table_name)?;
+        let table_name = table_ref
+            .clone()
+            .resolve_object(session.tenant(), session.default_database())?;
+        let table_schema = self.get_tskv_schema(table_ref.clone())?;
+
+        let (source_plan, _) = self.create_table_relation(table_ref, None, &Default::default())?;
+        let df_schema = source_plan.schema().clone();
+
+        let filters = match selection {
+            Some(expr) => {
+                let expr =
+                    self.df_planner
+                        .sql_to_expr(expr, &df_schema, &mut Default::default())?;
+                // Coerce literals to the column types and fold constants,
+                // e.g. time > '2023-01-01 00:00:00' and BETWEEN.
+                let props = ExecutionProps::new();
+                let simplifier = ExprSimplifier::new(
+                    SimplifyContext::new(&props).with_schema(df_schema.clone()),
+                );
+                let expr = simplifier.simplify(simplifier.coerce(expr, df_schema.clone())?)?;
+
+                let mut columns = HashSet::new();
+                expr_to_columns(&expr, &mut columns)?;
+                check_delete_columns(&columns, &table_schema)?;
+                check_delete_expr(&expr)?;
+
+                vec![expr]
+            }
+            None => vec![],
+        };
+
+        let predicate = Predicate::default()
+            .push_down_filter(&filters, &table_schema)
+            .resolve(&table_schema)
+            .map_err(|reason| QueryError::AnalyzePushedFilter { reason })?;
+
+        let plan = Plan::DDL(DDLPlan::DeleteFromTable(DeleteFromTable {
+            table_name,
+            predicate,
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(
+                    DatabasePrivilege::Write,
+                    Some(table_schema.db.clone()),
+                ),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     fn show_databases_to_plan(&self, session: &SessionCtx) -> Result<PlanWithPrivileges> {
         let projections = vec![col(DATABASES_DATABASE_NAME)];
         let sorts = vec![col(DATABASES_DATABASE_NAME).sort(true, true)];
@@ -1024,6 +1205,90 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         )
     }
 
+    fn show_series_cardinality_to_plan(
+        &self,
+        stmt: ASTShowSeriesCardinality,
+        session: &SessionCtx,
+    ) -> Result<PlanWithPrivileges> {
+        let ASTShowSeriesCardinality {
+            database_name,
+            table,
+        } = stmt;
+
+        let (database_name, table_name) = match table {
+            Some(table) => {
+                let table_name = database_name
+                    .map(|e| ObjectName(vec![e, table.clone()]))
+                    .unwrap_or_else(|| ObjectName(vec![table]));
+                let table_schema = self.get_tskv_schema(normalize_sql_object_name(table_name)?)?;
+                (table_schema.db.clone(), Some(table_schema.name.clone()))
+            }
+            None => {
+                let database_name = database_name
+                    .map(normalize_ident)
+                    .unwrap_or_else(|| session.default_database().to_string());
+                self.schema_provider
+                    .database_table_exist(&database_name, None)?;
+                (database_name, None)
+            }
+        };
+
+        let plan = Plan::DDL(DDLPlan::ShowSeriesCardinality(ShowSeriesCardinality {
+            database_name: database_name.clone(),
+            table_name,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(database_name)),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
+    fn show_tag_values_cardinality_to_plan(
+        &self,
+        stmt: ASTShowTagValuesCardinality,
+        session: &SessionCtx,
+    ) -> Result<PlanWithPrivileges> {
+        let ASTShowTagValuesCardinality {
+            database_name,
+            table,
+            with,
+        } = stmt;
+
+        let table_name = database_name
+            .map(|e| ObjectName(vec![e, table.clone()]))
+            .unwrap_or_else(|| ObjectName(vec![table]));
+        let table_schema = self.get_tskv_schema(normalize_sql_object_name(table_name)?)?;
+        let mut tag_key_filter = tag_key_filter(with)?;
+        let tag_keys = table_schema
+            .columns()
+            .iter()
+            .filter(|column| column.column_type.is_tag())
+            .filter(|column| tag_key_filter(column))
+            .map(|column| column.name.clone())
+            .collect();
+
+        let plan = Plan::DDL(DDLPlan::ShowTagValuesCardinality(
+            ShowTagValuesCardinality {
+                database_name: table_schema.db.clone(),
+                table_name: table_schema.name.clone(),
+                tag_keys,
+            },
+        ));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(
+                    DatabasePrivilege::Read,
+                    Some(table_schema.db.clone()),
+                ),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     fn database_to_plan(
         &self,
         stmt: ASTCreateDatabase,
@@ -1177,6 +1442,33 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn database_to_rename(
+        &self,
+        stmt: ASTRenameDatabase,
+        session: &SessionCtx,
+    ) -> Result<PlanWithPrivileges> {
+        let ASTRenameDatabase { name, new_name } = stmt;
+        let database_name = normalize_ident(name);
+        let new_database_name = normalize_ident(new_name);
+        let plan = Plan::DDL(DDLPlan::RenameDatabase(RenameDatabase {
+            database_name: database_name.clone(),
+            new_database_name,
+        }));
+        // privileges: drop the old database and create the new one
+        let tenant_id = *session.tenant_id();
+        let privileges = vec![
+            Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Full, Some(database_name)),
+                Some(tenant_id),
+            ),
+            Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Write, None),
+                Some(tenant_id),
+            ),
+        ];
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
     fn make_database_option(&self, options: ASTDatabaseOptions) -> Result<DatabaseOptions> {
         let mut plan_options = DatabaseOptions::default();
         if let Some(ttl) = options.ttl {
@@ -1199,6 +1491,28 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                 )),
             })?);
         }
+        if let Some(cold_duration) = options.cold_duration {
+            plan_options.with_cold_duration(self.str_to_duration(&cold_duration)?);
+        }
+        if let Some(strategy) = options.compaction_strategy {
+            plan_options.with_compaction_strategy(CompactionStrategy::new(&strategy).ok_or(
+                QueryError::Parser {
+                    source: ParserError::ParserError(format!(
+                        "{} is not a valid compaction strategy, use like 'level', 'time_window'",
+                        strategy
+                    )),
+                },
+            )?);
+        }
+        if let Some(compaction_window) = options.compaction_window {
+            plan_options.with_compaction_window(self.str_to_duration(&compaction_window)?);
+        }
+        if let Some(max_series) = options.max_series_per_database {
+            plan_options.with_max_series_per_database(max_series);
+        }
+        if let Some(max_series) = options.max_series_per_table {
+            plan_options.with_max_series_per_table(max_series);
+        }
         Ok(plan_options)
     }
 
@@ -1231,6 +1545,12 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             SQLDataType::Double => Ok(ColumnType::Field(ValueType::Float)),
             SQLDataType::String => Ok(ColumnType::Field(ValueType::String)),
             SQLDataType::Boolean => Ok(ColumnType::Field(ValueType::Boolean)),
+            SQLDataType::Decimal(info) | SQLDataType::Numeric(info) => {
+                make_decimal_data_type(info).map_err(unsupport_type_err)
+            }
+            SQLDataType::Binary(_) | SQLDataType::Varbinary(_) | SQLDataType::Bytea => {
+                Ok(ColumnType::Field(ValueType::Binary))
+            }
             SQLDataType::Custom(name, params) => {
                 make_custom_data_type(name, params).map_err(unsupport_type_err)
             }
@@ -1247,10 +1567,16 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let encoding = column.encoding.unwrap_or_default();
         let is_ok = match column.data_type {
             SQLDataType::Timestamp(_, _) => encoding.is_timestamp_encoding(),
-            SQLDataType::BigInt(_) => encoding.is_bigint_encoding(),
+            SQLDataType::BigInt(_) | SQLDataType::Decimal(_) | SQLDataType::Numeric(_) => {
+                encoding.is_bigint_encoding()
+            }
             SQLDataType::UnsignedBigInt(_) => encoding.is_unsigned_encoding(),
             SQLDataType::Double => encoding.is_double_encoding(),
-            SQLDataType::String | SQLDataType::Custom(_, _) => encoding.is_string_encoding(),
+            SQLDataType::String
+            | SQLDataType::Custom(_, _)
+            | SQLDataType::Binary(_)
+            | SQLDataType::Varbinary(_)
+            | SQLDataType::Bytea => encoding.is_string_encoding(),
             SQLDataType::Boolean => encoding.is_bool_encoding(),
             _ => false,
         };
@@ -1595,6 +1921,32 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn split_vnode_to_plan(&self, stmt: ASTSplitVnode) -> Result<PlanWithPrivileges> {
+        let ASTSplitVnode { vnode_id } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::SplitVnode(SplitVnode { vnode_id }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn alter_nodes_to_plan(&self, stmt: ASTAlterNodes) -> Result<PlanWithPrivileges> {
+        let ASTAlterNodes {
+            compact_write_rate_limit,
+            flush_write_rate_limit,
+        } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::AlterNodes(AlterNodes {
+            compact_write_rate_limit,
+            flush_write_rate_limit,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
     fn checksum_group_to_plan(&self, stmt: ASTChecksumGroup) -> Result<PlanWithPrivileges> {
         let ASTChecksumGroup { replication_set_id } = stmt;
 
@@ -1605,6 +1957,68 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn backup_database_to_plan(&self, stmt: ASTBackupDatabase) -> Result<PlanWithPrivileges> {
+        let ASTBackupDatabase { name, location } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::BackupDatabase(BackupDatabase {
+            database_name: normalize_ident(name),
+            location,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn restore_database_to_plan(&self, stmt: ASTRestoreDatabase) -> Result<PlanWithPrivileges> {
+        let ASTRestoreDatabase { name, location } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::RestoreDatabase(RestoreDatabase {
+            database_name: normalize_ident(name),
+            location,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn export_database_to_plan(&self, stmt: ASTExportDatabase) -> Result<PlanWithPrivileges> {
+        let ASTExportDatabase {
+            name,
+            table,
+            location,
+        } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::ExportDatabase(ExportDatabase {
+            database_name: normalize_ident(name),
+            table_name: table.map(normalize_ident),
+            location,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
+    fn import_database_to_plan(&self, stmt: ASTImportDatabase) -> Result<PlanWithPrivileges> {
+        let ASTImportDatabase {
+            name,
+            table,
+            location,
+        } = stmt;
+
+        let plan = Plan::DDL(DDLPlan::ImportDatabase(ImportDatabase {
+            database_name: normalize_ident(name),
+            table_name: normalize_ident(table),
+            location,
+        }));
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::System)],
+        })
+    }
+
     fn create_stream_table_to_plan(
         &self,
         stmt: Statement,
@@ -2111,6 +2525,92 @@ fn check_show_series_expr(columns: &HashSet<Column>, table_schema: &TskvTableSch
     Ok(())
 }
 
+fn check_delete_columns(columns: &HashSet<Column>, table_schema: &TskvTableSchema) -> Result<()> {
+    for column in columns.iter() {
+        match table_schema.column(&column.name) {
+            Some(table_column) => {
+                if table_column.column_type.is_field() {
+                    return Err(QueryError::DeleteWhereContainsField {
+                        column: column.to_string(),
+                    });
+                }
+            }
+
+            None => {
+                return Err(QueryError::ColumnNotExists {
+                    column: column.to_string(),
+                    table: table_schema.name.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Only comparisons between a column and a literal, combined by AND, or by OR
+/// on the same column, can be converted to tag domains and time ranges exactly.
+/// Any other expression would be widened when pushed down, which may delete
+/// more data than the user asked, so reject it.
+fn check_delete_expr(expr: &Expr) -> Result<()> {
+    fn or_column(expr: &Expr) -> Option<&Column> {
+        match expr {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => match op {
+                Operator::And | Operator::Or => {
+                    let l = or_column(left)?;
+                    let r = or_column(right)?;
+                    (l == r).then_some(l)
+                }
+                _ => comparison_column(left, *op, right),
+            },
+            _ => None,
+        }
+    }
+
+    fn comparison_column<'a>(left: &'a Expr, op: Operator, right: &'a Expr) -> Option<&'a Column> {
+        if !matches!(
+            op,
+            Operator::Eq
+                | Operator::NotEq
+                | Operator::Lt
+                | Operator::LtEq
+                | Operator::Gt
+                | Operator::GtEq
+        ) {
+            return None;
+        }
+        match (left, right) {
+            (Expr::Column(c), Expr::Literal(v)) | (Expr::Literal(v), Expr::Column(c))
+                if !v.is_null() =>
+            {
+                Some(c)
+            }
+            _ => None,
+        }
+    }
+
+    let supported = match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::And,
+            right,
+        }) => {
+            check_delete_expr(left)?;
+            check_delete_expr(right)?;
+            true
+        }
+        _ => or_column(expr).is_some(),
+    };
+
+    if supported {
+        Ok(())
+    } else {
+        Err(QueryError::UnsupportedDeleteExpr {
+            expr: expr.to_string(),
+        })
+    }
+}
+
 fn show_series_projection(
     table_schema: &TskvTableSchema,
     mut plan_builder: LogicalPlanBuilder,
@@ -2152,13 +2652,9 @@ fn show_series_projection(
     Ok(plan_builder.project(iter::once(concat_ws))?.build()?)
 }
 
-fn show_tag_value_projections(
-    table_schema: &TskvTableSchema,
-    mut plan_builder: LogicalPlanBuilder,
-    where_contain_time: bool,
-    with: With,
-) -> Result<LogicalPlan> {
-    let mut tag_key_filter: Box<dyn FnMut(&TableColumn) -> bool> = match with {
+/// Returns a filter of tag columns by the `WITH KEY` clause.
+fn tag_key_filter(with: With) -> Result<Box<dyn FnMut(&TableColumn) -> bool>> {
+    let tag_key_filter: Box<dyn FnMut(&TableColumn) -> bool> = match with {
         With::Equal(ident) => {
             Box::new(move |column| normalize_ident(ident.clone()).eq(&column.name))
         }
@@ -2185,6 +2681,16 @@ fn show_tag_value_projections(
             });
         }
     };
+    Ok(tag_key_filter)
+}
+
+fn show_tag_value_projections(
+    table_schema: &TskvTableSchema,
+    mut plan_builder: LogicalPlanBuilder,
+    where_contain_time: bool,
+    with: With,
+) -> Result<LogicalPlan> {
+    let mut tag_key_filter = tag_key_filter(with)?;
 
     let tags = table_schema
         .columns()
@@ -2362,6 +2868,29 @@ fn make_custom_data_type(
     }
 }
 
+fn is_primitive_value_type(value_type: &ValueType) -> bool {
+    matches!(
+        value_type,
+        ValueType::Float
+            | ValueType::Integer
+            | ValueType::Unsigned
+            | ValueType::Boolean
+            | ValueType::String
+    )
+}
+
+fn make_decimal_data_type(info: &ExactNumberInfo) -> std::result::Result<ColumnType, String> {
+    let (precision, scale) = match info {
+        ExactNumberInfo::None => (Decimal::DEFAULT_PRECISION as u64, 0),
+        ExactNumberInfo::Precision(p) => (*p, 0),
+        ExactNumberInfo::PrecisionAndScale(p, s) => (*p, *s),
+    };
+    let precision = u8::try_from(precision).map_err(|e| e.to_string())?;
+    let scale = u8::try_from(scale).map_err(|e| e.to_string())?;
+    let decimal = Decimal::try_new(precision, scale)?;
+    Ok(ColumnType::Field(ValueType::Decimal(decimal)))
+}
+
 fn make_geometry_data_type(params: &[String]) -> std::result::Result<ColumnType, String> {
     if params.len() != 2 {
         return Err("format: GEOMETRY(<sub_type>, <srid>)".to_string());
@@ -2691,7 +3220,7 @@ mod tests {
         if let Plan::DDL(DDLPlan::CreateDatabase(create)) = plan.plan {
             let ans = format!("{:?}", create);
             println!("{ans}");
-            let expected = r#"CreateDatabase { name: "test", if_not_exists: false, options: DatabaseOptions { ttl: Some(Duration { time_num: 10, unit: Day }), shard_num: Some(5), vnode_duration: Some(Duration { time_num: 3, unit: Day }), replica: Some(10), precision: Some(US) } }"#;
+            let expected = r#"CreateDatabase { name: "test", if_not_exists: false, options: DatabaseOptions { ttl: Some(Duration { time_num: 10, unit: Day }), shard_num: Some(5), vnode_duration: Some(Duration { time_num: 3, unit: Day }), replica: Some(10), precision: Some(US), cold_duration: None, compaction_strategy: None, compaction_window: None, max_series_per_database: None, max_series_per_table: None } }"#;
             assert_eq!(ans, expected);
         } else {
             panic!("expected create table plan")
@@ -2788,6 +3317,40 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_create_table_with_decimal_and_binary_type() {
+        let sql = "CREATE TABLE air (price decimal(10, 2), payload varbinary);";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        assert_eq!(statements.len(), 1);
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session())
+            .await
+            .unwrap()
+            .plan;
+
+        if let Plan::DDL(DDLPlan::CreateTable(create)) = plan {
+            assert_eq!(
+                create.schema[1].column_type,
+                ColumnType::Field(ValueType::Decimal(Decimal::try_new(10, 2).unwrap()))
+            );
+            assert_eq!(
+                create.schema[2].column_type,
+                ColumnType::Field(ValueType::Binary)
+            );
+        } else {
+            panic!("expected create table plan")
+        }
+
+        let sql = "CREATE TABLE air (price decimal(19, 2));";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        assert!(planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session())
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_insert_select() {
         let sql = "insert test_tb(field_int, field_string)
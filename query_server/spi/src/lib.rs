@@ -508,6 +508,34 @@ pub enum QueryError {
     InvalidGeometryType {
         reason: String,
     },
+
+    #[snafu(display(
+        "Semantic error: DELETE does not support where clause contains field {}",
+        column
+    ))]
+    #[error_code(code = 74)]
+    DeleteWhereContainsField {
+        column: String,
+    },
+
+    #[snafu(display("Semantic error: DELETE does not support where clause: {}", expr))]
+    #[error_code(code = 75)]
+    UnsupportedDeleteExpr {
+        expr: String,
+    },
+
+    #[snafu(display(
+        "Semantic error: Can't change data type of column {} from {} to {}",
+        column,
+        from,
+        to
+    ))]
+    #[error_code(code = 76)]
+    ChangeColumnDataType {
+        column: String,
+        from: String,
+        to: String,
+    },
 }
 
 impl From<ParserError> for QueryError {
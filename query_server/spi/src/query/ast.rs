@@ -28,6 +28,8 @@ pub enum ExtStatement {
     CreateUser(CreateUser),
     CreateRole(CreateRole),
 
+    DeleteFromTable(DeleteFromTable),
+
     CreateStream(CreateStream),
     DropStream(DropStream),
     ShowStreams(ShowStreams),
@@ -44,21 +46,58 @@ pub enum ExtStatement {
     ShowTables(Option<Ident>),
     ShowSeries(Box<ShowSeries>),
     ShowTagValues(Box<ShowTagValues>),
+    ShowSeriesCardinality(ShowSeriesCardinality),
+    ShowTagValuesCardinality(Box<ShowTagValuesCardinality>),
     Explain(Explain),
 
     // system cmd
     ShowQueries,
     AlterDatabase(AlterDatabase),
+    RenameDatabase(RenameDatabase),
     AlterTable(AlterTable),
     AlterTenant(AlterTenant),
     AlterUser(AlterUser),
+    AlterNodes(AlterNodes),
 
     // vnode cmd
     DropVnode(DropVnode),
     CopyVnode(CopyVnode),
     MoveVnode(MoveVnode),
     CompactVnode(CompactVnode),
+    SplitVnode(SplitVnode),
     ChecksumGroup(ChecksumGroup),
+
+    BackupDatabase(BackupDatabase),
+    RestoreDatabase(RestoreDatabase),
+    ExportDatabase(ExportDatabase),
+    ImportDatabase(ImportDatabase),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupDatabase {
+    pub name: Ident,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreDatabase {
+    pub name: Ident,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportDatabase {
+    pub name: Ident,
+    /// All tables if it's None.
+    pub table: Option<Ident>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDatabase {
+    pub name: Ident,
+    pub table: Ident,
+    pub location: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -71,6 +110,18 @@ pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitVnode {
+    pub vnode_id: VnodeId,
+}
+
+/// Options of all data nodes to change, 0 write rate limit means unlimited.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AlterNodes {
+    pub compact_write_rate_limit: Option<u64>,
+    pub flush_write_rate_limit: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MoveVnode {
     pub vnode_id: VnodeId,
@@ -126,6 +177,12 @@ pub struct AlterTable {
     pub alter_action: AlterTableAction,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteFromTable {
+    pub table_name: ObjectName,
+    pub selection: Option<Expr>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Explain {
     pub analyze: bool,
@@ -143,9 +200,23 @@ pub enum AlterTableAction {
         column_name: Ident,
         encoding: Encoding,
     },
+    AlterColumnDataType {
+        column_name: Ident,
+        data_type: DataType,
+    },
     DropColumn {
         column_name: Ident,
     },
+    RenameColumn {
+        old_column_name: Ident,
+        new_column_name: Ident,
+    },
+    RenameTable {
+        new_table_name: Ident,
+    },
+    SetLastValueCache {
+        enabled: bool,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -154,6 +225,12 @@ pub struct AlterDatabase {
     pub options: DatabaseOptions,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameDatabase {
+    pub name: Ident,
+    pub new_name: Ident,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AlterTenant {
     /// tenant name
@@ -308,6 +385,16 @@ pub struct DatabaseOptions {
     pub replica: Option<u64>,
     // timestamp precision
     pub precision: Option<String>,
+    // data older than it will be moved to cold storage
+    pub cold_duration: Option<String>,
+    // strategy of picking files to compact
+    pub compaction_strategy: Option<String>,
+    // time range of a window of the time-window compaction strategy
+    pub compaction_window: Option<String>,
+    // max number of series in a vnode of the database
+    pub max_series_per_database: Option<u64>,
+    // max number of series of a table in a vnode of the database
+    pub max_series_per_table: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -362,6 +449,21 @@ pub struct ShowTagValues {
     pub with: With,
 }
 
+/// Estimated number of series of each table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShowSeriesCardinality {
+    pub database_name: Option<Ident>,
+    pub table: Option<Ident>,
+}
+
+/// Estimated number of tag values of each tag key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShowTagValuesCardinality {
+    pub database_name: Option<Ident>,
+    pub table: Ident,
+    pub with: With,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ObjectType {
@@ -25,6 +25,7 @@ use models::auth::user::{UserOptions, UserOptionsBuilder};
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use models::object_reference::ResolvedTable;
 use models::oid::{Identifier, Oid};
+use models::predicate::domain::ResolvedPredicateRef;
 use models::schema::{
     DatabaseOptions, TableColumn, Tenant, TenantOptions, TenantOptionsBuilder, Watermark,
 };
@@ -125,8 +126,12 @@ pub enum DDLPlan {
 
     AlterDatabase(AlterDatabase),
 
+    RenameDatabase(RenameDatabase),
+
     AlterTable(AlterTable),
 
+    DeleteFromTable(DeleteFromTable),
+
     AlterTenant(AlterTenant),
 
     AlterUser(AlterUser),
@@ -140,8 +145,21 @@ pub enum DDLPlan {
     MoveVnode(MoveVnode),
 
     CompactVnode(CompactVnode),
+    SplitVnode(SplitVnode),
 
     ChecksumGroup(ChecksumGroup),
+
+    ShowSeriesCardinality(ShowSeriesCardinality),
+
+    ShowTagValuesCardinality(ShowTagValuesCardinality),
+
+    BackupDatabase(BackupDatabase),
+
+    RestoreDatabase(RestoreDatabase),
+
+    ExportDatabase(ExportDatabase),
+
+    ImportDatabase(ImportDatabase),
 }
 
 impl DDLPlan {
@@ -151,21 +169,87 @@ impl DDLPlan {
                 Field::new("VNODE_ID", DataType::UInt32, false),
                 Field::new("CHECK_SUM", DataType::Utf8, false),
             ])),
+            DDLPlan::ShowSeriesCardinality(_) => Arc::new(Schema::new(vec![
+                Field::new("table", DataType::Utf8, false),
+                Field::new("cardinality", DataType::UInt64, false),
+            ])),
+            DDLPlan::ShowTagValuesCardinality(_) => Arc::new(Schema::new(vec![
+                Field::new("key", DataType::Utf8, false),
+                Field::new("cardinality", DataType::UInt64, false),
+            ])),
             _ => Arc::new(Schema::empty()),
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct DeleteFromTable {
+    pub table_name: ResolvedTable,
+    pub predicate: ResolvedPredicateRef,
+}
+
 #[derive(Debug, Clone)]
 pub struct ChecksumGroup {
     pub replication_set_id: ReplicationSetId,
 }
 
+#[derive(Debug, Clone)]
+pub struct ShowSeriesCardinality {
+    pub database_name: String,
+    /// All tables if it's None.
+    pub table_name: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowTagValuesCardinality {
+    pub database_name: String,
+    pub table_name: String,
+    pub tag_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BackupDatabase {
+    pub database_name: String,
+    pub location: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RestoreDatabase {
+    pub database_name: String,
+    pub location: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportDatabase {
+    pub database_name: String,
+    /// All tables if it's None.
+    pub table_name: Option<String>,
+    pub location: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportDatabase {
+    pub database_name: String,
+    pub table_name: String,
+    pub location: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct CompactVnode {
     pub vnode_ids: Vec<VnodeId>,
 }
 
+#[derive(Debug, Clone)]
+pub struct SplitVnode {
+    pub vnode_id: VnodeId,
+}
+
+#[derive(Debug, Clone)]
+pub struct AlterNodes {
+    pub compact_write_rate_limit: Option<u64>,
+    pub flush_write_rate_limit: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct MoveVnode {
     pub vnode_id: VnodeId,
@@ -497,6 +581,12 @@ pub struct AlterDatabase {
     pub database_options: DatabaseOptions,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameDatabase {
+    pub database_name: String,
+    pub new_database_name: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AlterTable {
     pub table_name: ResolvedTable,
@@ -515,6 +605,13 @@ pub enum AlterTableAction {
     DropColumn {
         column_name: String,
     },
+    RenameTable {
+        new_table_name: String,
+    },
+    /// Keep the latest value of each series of the table in memory.
+    SetLastValueCache {
+        enabled: bool,
+    },
 }
 
 #[async_trait]
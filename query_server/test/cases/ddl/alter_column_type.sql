@@ -0,0 +1,23 @@
+--#DATABASE=alter_column_type
+--#SLEEP=100
+DROP DATABASE IF EXISTS alter_column_type;
+CREATE DATABASE alter_column_type WITH TTL '100000d';
+CREATE TABLE test (
+    f0 BIGINT CODEC(DELTA),
+    f1 BOOLEAN,
+    TAGS(t0)
+);
+INSERT INTO test (TIME, t0, f0, f1) VALUES (1, 'a', 1, true), (2, 'a', -2, false);
+
+ALTER TABLE test ALTER COLUMN f0 SET DATA TYPE DOUBLE;
+DESCRIBE TABLE test;
+INSERT INTO test (TIME, t0, f0, f1) VALUES (3, 'a', 3.5, true);
+SELECT * FROM test ORDER BY TIME ASC;
+
+ALTER TABLE test ALTER COLUMN f1 SET DATA TYPE STRING;
+DESCRIBE TABLE test;
+SELECT time, f1 FROM test ORDER BY TIME ASC;
+
+ALTER TABLE test ALTER COLUMN f1 SET DATA TYPE BIGINT;
+ALTER TABLE test ALTER COLUMN t0 SET DATA TYPE DOUBLE;
+ALTER TABLE test ALTER COLUMN time SET DATA TYPE BIGINT;
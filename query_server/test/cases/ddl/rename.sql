@@ -0,0 +1,30 @@
+--#DATABASE=rename_db
+--#SLEEP=100
+DROP DATABASE IF EXISTS rename_db;
+DROP DATABASE IF EXISTS rename_db2;
+CREATE DATABASE rename_db WITH TTL '100000d';
+CREATE TABLE test (
+    f0 BIGINT,
+    f1 BOOLEAN,
+    TAGS(t0, t1)
+);
+CREATE TABLE test1 (f0 BIGINT, TAGS(t0));
+INSERT INTO test (TIME, t0, t1, f0, f1) VALUES (1, 'a', 'b', 1, true), (2, 'a', 'c', 2, false);
+
+ALTER TABLE test RENAME COLUMN f0 TO f2;
+ALTER TABLE test RENAME COLUMN t1 TO t2;
+ALTER TABLE test RENAME COLUMN f1 TO t0;
+ALTER TABLE test RENAME COLUMN time TO t3;
+ALTER TABLE test RENAME COLUMN f3 TO f4;
+DESCRIBE TABLE test;
+SELECT * FROM test WHERE t2 = 'b' ORDER BY TIME ASC;
+
+ALTER TABLE test RENAME TO test1;
+ALTER TABLE test RENAME TO test2;
+SELECT * FROM test2 ORDER BY TIME ASC;
+INSERT INTO test2 (TIME, t0, t2, f2, f1) VALUES (3, 'a', 'b', 3, true);
+SELECT * FROM test2 WHERE t2 = 'b' ORDER BY TIME ASC;
+
+ALTER DATABASE rename_db RENAME TO rename_db2;
+SELECT * FROM rename_db2.test2 ORDER BY TIME ASC;
+DROP DATABASE IF EXISTS rename_db2;
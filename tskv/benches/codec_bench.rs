@@ -0,0 +1,76 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use models::codec::Encoding;
+use tskv::tsm::codec::{get_f64_codec, get_str_codec};
+
+const NUM_VALUES: usize = 1000;
+
+/// Values like readings of a sensor, with 2 decimal digits.
+fn sensor_values() -> Vec<f64> {
+    let mut value = 20.0_f64;
+    (0..NUM_VALUES)
+        .map(|i| {
+            value += (i as f64 * 0.1).sin() * 0.5;
+            (value * 100.0).round() / 100.0
+        })
+        .collect()
+}
+
+/// Values like status codes of http responses.
+fn status_values() -> Vec<String> {
+    let status = ["200", "200", "200", "201", "301", "404", "500"];
+    (0..NUM_VALUES)
+        .map(|_| status[rand::random::<usize>() % status.len()].to_string())
+        .collect()
+}
+
+fn bench_f64_codec(c: &mut Criterion) {
+    let values = sensor_values();
+    let mut group = c.benchmark_group("f64_codec");
+    for encoding in [Encoding::Gorilla, Encoding::Quantile, Encoding::Chimp] {
+        let codec = get_f64_codec(encoding);
+        let mut encoded = vec![];
+        codec.encode(&values, &mut encoded).unwrap();
+
+        group.bench_function(BenchmarkId::new("encode", encoding.as_str()), |b| {
+            b.iter(|| {
+                let mut dst = vec![];
+                codec.encode(&values, &mut dst).unwrap();
+            })
+        });
+        group.bench_function(BenchmarkId::new("decode", encoding.as_str()), |b| {
+            b.iter(|| {
+                let mut dst = vec![];
+                codec.decode(&encoded, &mut dst).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_str_codec(c: &mut Criterion) {
+    let values = status_values();
+    let values: Vec<&[u8]> = values.iter().map(|s| s.as_bytes()).collect();
+    let mut group = c.benchmark_group("str_codec");
+    for encoding in [Encoding::Snappy, Encoding::Zstd, Encoding::Dictionary] {
+        let codec = get_str_codec(encoding);
+        let mut encoded = vec![];
+        codec.encode(&values, &mut encoded).unwrap();
+
+        group.bench_function(BenchmarkId::new("encode", encoding.as_str()), |b| {
+            b.iter(|| {
+                let mut dst = vec![];
+                codec.encode(&values, &mut dst).unwrap();
+            })
+        });
+        group.bench_function(BenchmarkId::new("decode", encoding.as_str()), |b| {
+            b.iter(|| {
+                let mut dst = vec![];
+                codec.decode(&encoded, &mut dst).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_f64_codec, bench_str_codec);
+criterion_main!(benches);
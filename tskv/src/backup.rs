@@ -0,0 +1,427 @@
+//! Backups of vnodes, in a local directory or an object store.
+//!
+//! ```text
+//! {location}/{owner}/database.json            - schema, buckets and tables of the database
+//! {location}/{owner}/{vnode_id}/summary        - encoded VersionEdit of the vnode
+//! {location}/{owner}/{vnode_id}/tsm/_{id}.tsm  - tsm files
+//! {location}/{owner}/{vnode_id}/tsm/_{id}.tombstone
+//! {location}/{owner}/{vnode_id}/delta/_{id}.delta
+//! {location}/{owner}/{vnode_id}/delta/_{id}.tombstone
+//! {location}/{owner}/{vnode_id}/index/...      - files in the index directory
+//! ```
+//!
+//! Column files already moved to the cold storage are copied as local files,
+//! tombstones of column files are copied with them, and the index is copied
+//! from a consistent snapshot.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use config::ColdStorageConfig;
+use object_store::path::Path as ObjectPath;
+use snafu::ResultExt;
+use trace::{error, info};
+
+use crate::cold_storage::ColdStorage;
+use crate::error::{self, Error, Result};
+use crate::file_system::file_manager::try_exists;
+use crate::index::ts_index::TSIndex;
+use crate::kv_option::{StorageOptions, DELTA_PATH, INDEX_PATH, TSM_PATH};
+use crate::summary::VersionEdit;
+use crate::{file_utils, TseriesFamilyId};
+
+pub const DATABASE_INFO_FILE: &str = "database.json";
+const SUMMARY_FILE: &str = "summary";
+
+pub enum BackupStorage {
+    /// A local directory, files are hard-linked if possible, so files put into
+    /// it must not be modified later.
+    Local(PathBuf),
+    /// An object store.
+    Remote(Arc<ColdStorage>),
+}
+
+impl BackupStorage {
+    /// Open a local directory, or an object store by url like 's3://bucket/path'.
+    pub fn open(location: &str) -> Result<Self> {
+        if !location.contains("://") {
+            return Ok(Self::Local(PathBuf::from(location)));
+        }
+        if let Some(path) = location.strip_prefix("file://") {
+            return Ok(Self::Local(PathBuf::from(path)));
+        }
+        let config = ColdStorageConfig {
+            url: location.to_string(),
+            ..Default::default()
+        };
+        match ColdStorage::open(&config)? {
+            Some(storage) => Ok(Self::Remote(storage)),
+            None => Err(Error::InvalidParam {
+                reason: format!("invalid backup location '{location}'"),
+            }),
+        }
+    }
+
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Local(dir) => {
+                let path = dir.join(key);
+                create_parent_dir(&path).await?;
+                tokio::fs::write(&path, data).await.context(error::IOSnafu)
+            }
+            Self::Remote(storage) => storage.put(&storage.child_path(key), data).await,
+        }
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Local(dir) => tokio::fs::read(dir.join(key)).await.context(error::IOSnafu),
+            Self::Remote(storage) => storage.get(&storage.child_path(key)).await,
+        }
+    }
+
+    /// Copy a local file into the backup.
+    pub async fn put_file(&self, key: &str, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        match self {
+            Self::Local(dir) => {
+                let dst = dir.join(key);
+                create_parent_dir(&dst).await?;
+                // Column files and index snapshots are immutable, so hard link is enough.
+                if tokio::fs::hard_link(path, &dst).await.is_err() {
+                    tokio::fs::copy(path, &dst).await.context(error::IOSnafu)?;
+                }
+                Ok(())
+            }
+            Self::Remote(storage) => storage.upload(path, &storage.child_path(key)).await,
+        }
+    }
+
+    /// Copy a file in the backup to a local path.
+    pub async fn get_file(&self, key: &str, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        create_parent_dir(path).await?;
+        match self {
+            Self::Local(dir) => {
+                tokio::fs::copy(dir.join(key), path)
+                    .await
+                    .context(error::IOSnafu)?;
+                Ok(())
+            }
+            Self::Remote(storage) => storage.download(&storage.child_path(key), path).await,
+        }
+    }
+
+    /// Returns keys of all files under `key_prefix`.
+    pub async fn list(&self, key_prefix: &str) -> Result<Vec<String>> {
+        match self {
+            Self::Local(dir) => {
+                let mut keys = Vec::new();
+                let base = dir.join(key_prefix);
+                if !base.exists() {
+                    return Ok(keys);
+                }
+                for entry in walkdir::WalkDir::new(&base) {
+                    let entry = entry.map_err(|e| Error::IO { source: e.into() })?;
+                    if entry.file_type().is_file() {
+                        if let Ok(key) = entry.path().strip_prefix(dir) {
+                            keys.push(key.to_string_lossy().to_string());
+                        }
+                    }
+                }
+                Ok(keys)
+            }
+            Self::Remote(storage) => storage.list(key_prefix).await,
+        }
+    }
+}
+
+async fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .context(error::IOSnafu)?;
+    }
+    Ok(())
+}
+
+pub fn database_info_key(owner: &str) -> String {
+    format!("{owner}/{DATABASE_INFO_FILE}")
+}
+
+fn vnode_key(owner: &str, vnode_id: TseriesFamilyId) -> String {
+    format!("{owner}/{vnode_id}")
+}
+
+/// Copy column files in the `summary` and a snapshot of the index of a vnode
+/// into the backup, then save the summary. Caller should hold the version of
+/// the summary so that files in it won't be deleted during the backup.
+///
+/// Files in the cold storage are copied too, they are local files in the
+/// saved summary, so the backup doesn't depend on the cold storage.
+pub async fn backup_vnode(
+    storage_opt: &StorageOptions,
+    cold_storage: Option<&ColdStorage>,
+    owner: &str,
+    vnode_id: TseriesFamilyId,
+    summary: &VersionEdit,
+    ts_index: &TSIndex,
+    storage: &BackupStorage,
+) -> Result<()> {
+    // Files are copied into the snapshot directory before put into the backup,
+    // it's removed no matter the backup succeeded or not.
+    let snapshot_dir = storage_opt.backup_dir(owner, vnode_id);
+    if snapshot_dir.exists() {
+        tokio::fs::remove_dir_all(&snapshot_dir)
+            .await
+            .context(error::IOSnafu)?;
+    }
+    let res = backup_vnode_snapshot(
+        storage_opt,
+        cold_storage,
+        owner,
+        vnode_id,
+        summary,
+        ts_index,
+        &snapshot_dir,
+        storage,
+    )
+    .await;
+    if let Err(e) = tokio::fs::remove_dir_all(&snapshot_dir).await {
+        error!(
+            "Failed to remove backup snapshot dir '{}': {e}",
+            snapshot_dir.display()
+        );
+    }
+    res
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn backup_vnode_snapshot(
+    storage_opt: &StorageOptions,
+    cold_storage: Option<&ColdStorage>,
+    owner: &str,
+    vnode_id: TseriesFamilyId,
+    summary: &VersionEdit,
+    ts_index: &TSIndex,
+    snapshot_dir: &Path,
+    storage: &BackupStorage,
+) -> Result<()> {
+    let vnode_key = vnode_key(owner, vnode_id);
+    let mut summary = summary.clone();
+    for f in summary.add_files.iter_mut() {
+        let path = f.file_path(storage_opt, owner, vnode_id);
+        let sub_dir = if f.is_delta { DELTA_PATH } else { TSM_PATH };
+        let file_name = path
+            .file_name()
+            .expect("path must not be '..'")
+            .to_string_lossy()
+            .to_string();
+        let key = format!("{vnode_key}/{sub_dir}/{file_name}");
+        match f.remote_path.take() {
+            Some(remote_path) => {
+                let cold_storage = cold_storage.ok_or_else(|| Error::CommonError {
+                    reason: format!(
+                        "file '{remote_path}' is in the cold storage, but cold storage is not configured"
+                    ),
+                })?;
+                let location =
+                    ObjectPath::parse(&remote_path).context(error::ObjectStorePathSnafu)?;
+                let local_path = snapshot_dir.join(sub_dir).join(&file_name);
+                create_parent_dir(&local_path).await?;
+                cold_storage.download(&location, &local_path).await?;
+                storage.put_file(&key, &local_path).await?;
+            }
+            None => storage.put_file(&key, &path).await?,
+        }
+
+        // Tombstones are local files even if the column file is in the cold storage,
+        // they may be modified later, so they are copied into the snapshot first.
+        let tombstone_path = file_utils::make_tsm_tombstone_file_name(
+            path.parent().unwrap_or(snapshot_dir),
+            f.file_id,
+        );
+        if try_exists(&tombstone_path) {
+            let tombstone_name = tombstone_path
+                .file_name()
+                .expect("path must not be '..'")
+                .to_string_lossy()
+                .to_string();
+            let local_path = snapshot_dir.join(sub_dir).join(&tombstone_name);
+            create_parent_dir(&local_path).await?;
+            tokio::fs::copy(&tombstone_path, &local_path)
+                .await
+                .context(error::IOSnafu)?;
+            storage
+                .put_file(
+                    &format!("{vnode_key}/{sub_dir}/{tombstone_name}"),
+                    &local_path,
+                )
+                .await?;
+        }
+    }
+
+    let index_dir = snapshot_dir.join(INDEX_PATH);
+    ts_index.snapshot_to(&index_dir).await?;
+    for entry in walkdir::WalkDir::new(&index_dir) {
+        let entry = entry.map_err(|e| Error::IO { source: e.into() })?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Ok(relative) = entry.path().strip_prefix(&index_dir) {
+            let key = format!("{vnode_key}/{INDEX_PATH}/{}", relative.to_string_lossy());
+            storage.put_file(&key, entry.path()).await?;
+        }
+    }
+
+    storage
+        .put(&format!("{vnode_key}/{SUMMARY_FILE}"), summary.encode()?)
+        .await?;
+    info!(
+        "Backup vnode {owner}.{vnode_id} finished with {} files",
+        summary.add_files.len()
+    );
+    Ok(())
+}
+
+/// Copy files of vnode `backup_vnode_id` in the backup into the move directory
+/// of vnode `vnode_id`, returns the summary to be applied.
+pub async fn restore_vnode(
+    storage_opt: &StorageOptions,
+    owner: &str,
+    vnode_id: TseriesFamilyId,
+    backup_vnode_id: TseriesFamilyId,
+    storage: &BackupStorage,
+) -> Result<VersionEdit> {
+    let backup_key = vnode_key(owner, backup_vnode_id);
+    let summary =
+        VersionEdit::decode(&storage.get(&format!("{backup_key}/{SUMMARY_FILE}")).await?)?;
+
+    let move_dir = storage_opt.move_dir(owner, vnode_id);
+    // Index directory is required even if it's empty.
+    tokio::fs::create_dir_all(move_dir.join(INDEX_PATH))
+        .await
+        .context(error::IOSnafu)?;
+    for key in storage.list(&backup_key).await? {
+        let relative = match key.strip_prefix(&format!("{backup_key}/")) {
+            Some(r) if r != SUMMARY_FILE => r,
+            _ => continue,
+        };
+        storage.get_file(&key, move_dir.join(relative)).await?;
+    }
+    info!(
+        "Restore vnode {owner}.{vnode_id} from backup of vnode {backup_vnode_id} with {} files",
+        summary.add_files.len()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use models::predicate::domain::TimeRange;
+    use tokio::runtime;
+
+    use super::BackupStorage;
+    use crate::split::test::{
+        init_tskv, read_values, tag_predicate, write_series, DATABASE, TABLE, TENANT,
+    };
+    use crate::Engine;
+
+    #[tokio::test]
+    async fn test_local_backup_storage() {
+        let dir = "/tmp/test/backup/1";
+        let _ = std::fs::remove_dir_all(dir);
+        let storage = BackupStorage::open(dir).unwrap();
+
+        storage.put("a/b/1", b"hello".to_vec()).await.unwrap();
+        assert_eq!(storage.get("a/b/1").await.unwrap(), b"hello");
+
+        let src = format!("{dir}/src");
+        std::fs::write(&src, b"world").unwrap();
+        storage.put_file("a/c/2", &src).await.unwrap();
+        let dst = format!("{dir}/dst/2");
+        storage.get_file("a/c/2", &dst).await.unwrap();
+        assert_eq!(std::fs::read(&dst).unwrap(), b"world");
+
+        let mut keys = storage.list("a").await.unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["a/b/1".to_string(), "a/c/2".to_string()]);
+        assert!(storage.list("b").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_backup_restore_vnode() {
+        let runtime = Arc::new(
+            runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+        let rt = runtime.clone();
+        rt.block_on(async move {
+            let dir = "/tmp/test/backup/backup_vnode";
+            let engine = init_tskv(dir, runtime).await;
+            let location = format!("{dir}/backup");
+            let (vnode_id, new_vnode_id) = (1, 2);
+            let tag_values: Vec<String> = (0..4).map(|i| format!("a{i}")).collect();
+
+            write_series(&engine, vnode_id, &tag_values, &[1, 2], 0)
+                .await
+                .unwrap();
+            engine
+                .flush_tsfamily(TENANT, DATABASE, vnode_id)
+                .await
+                .unwrap();
+            // Values deleted by tombstones are not restored.
+            engine
+                .delete_from_table(
+                    TENANT,
+                    DATABASE,
+                    TABLE,
+                    &tag_predicate("a0", TimeRange::new(2, 2)),
+                )
+                .await
+                .unwrap();
+            // Values in the memcache are flushed by the backup.
+            write_series(&engine, vnode_id, &tag_values, &[3], 0)
+                .await
+                .unwrap();
+            engine
+                .backup_vnode(TENANT, DATABASE, vnode_id, &location)
+                .await
+                .unwrap();
+            // Values written after the backup are not restored.
+            write_series(&engine, vnode_id, &tag_values, &[4], 0)
+                .await
+                .unwrap();
+            // The snapshot directory is removed after backup.
+            let snapshot_dir = format!("{dir}/data/{TENANT}.{DATABASE}/{vnode_id}/backup");
+            assert!(!std::path::Path::new(&snapshot_dir).exists());
+
+            engine
+                .restore_vnode(TENANT, DATABASE, new_vnode_id, vnode_id, &location)
+                .await
+                .unwrap();
+            let db = engine.get_db(TENANT, DATABASE).await.unwrap();
+            let ts_index = db.read().await.get_ts_index(new_vnode_id).unwrap();
+            let sids = ts_index.get_series_id_list(TABLE, &[]).await.unwrap();
+            assert_eq!(sids.len(), tag_values.len());
+            for sid in sids {
+                let key = ts_index.get_series_key(sid).await.unwrap().unwrap();
+                let i: i64 = std::str::from_utf8(&key.tags()[0].value).unwrap()[1..]
+                    .parse()
+                    .unwrap();
+                let values = read_values(&engine, new_vnode_id, sid).await;
+                if i == 0 {
+                    assert_eq!(values, vec![10, 30], "series {key}");
+                } else {
+                    assert_eq!(values, vec![10 + i, 20 + i, 30 + i], "series {key}");
+                }
+            }
+
+            engine.close().await;
+        });
+    }
+}
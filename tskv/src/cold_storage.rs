@@ -0,0 +1,532 @@
+//! Moves cold column files to an object store, and reads them back by blocks.
+//!
+//! Column files in level 1-4 of a database with option `COLD_DURATION`, whose
+//! timestamps are all older than it, will be uploaded to the object store and
+//! recorded in `VersionEdit` with the object path. Tombstones of these files
+//! stay in the local tsm directory.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::io::{self, IoSlice};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use config::ColdStorageConfig;
+use futures::TryStreamExt;
+use lru_cache::asynchronous::ShardedCache;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::memory::InMemory;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use snafu::ResultExt;
+use tokio::io::AsyncWriteExt;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{oneshot, RwLock};
+use trace::{error, info};
+use url::Url;
+use utils::BloomFilter;
+
+use crate::error::{self, Error, Result};
+use crate::file_system::file::IFile;
+use crate::summary::{CompactMeta, SummaryTask, VersionEdit};
+use crate::tseries_family::{ColumnFile, TseriesFamily};
+use crate::version_set::VersionSet;
+use crate::{ColumnFileId, TseriesFamilyId};
+
+pub struct ColdStorage {
+    store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+    check_interval: Duration,
+    block_size: u64,
+    /// Blocks read from the object store, key is `{object_path}#{block_index}`.
+    block_cache: ShardedCache<String, Bytes>,
+}
+
+impl ColdStorage {
+    /// Open the object store by url, returns None if cold storage is disabled.
+    pub fn open(config: &ColdStorageConfig) -> Result<Option<Arc<Self>>> {
+        if !config.is_enabled() {
+            return Ok(None);
+        }
+        let url = Url::parse(&config.url).map_err(|e| Error::InvalidParam {
+            reason: format!("invalid cold storage url '{}': {}", config.url, e),
+        })?;
+        let (store, prefix): (Arc<dyn ObjectStore>, ObjectPath) = match url.scheme() {
+            "file" => {
+                std::fs::create_dir_all(url.path()).context(error::IOSnafu)?;
+                let store = LocalFileSystem::new_with_prefix(url.path())
+                    .context(error::ObjectStoreSnafu)?;
+                (Arc::new(store), ObjectPath::default())
+            }
+            "memory" => (Arc::new(InMemory::new()), ObjectPath::default()),
+            "s3" => {
+                let bucket = url.host_str().ok_or_else(|| Error::InvalidParam {
+                    reason: format!("bucket is missing in cold storage url '{}'", config.url),
+                })?;
+                // Credentials may also be set by environment variables like 'AWS_ACCESS_KEY_ID'.
+                let mut builder = AmazonS3Builder::from_env()
+                    .with_bucket_name(bucket)
+                    .with_allow_http(config.allow_http);
+                if let Some(endpoint) = &config.endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                if let Some(region) = &config.region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(access_key_id) = &config.access_key_id {
+                    builder = builder.with_access_key_id(access_key_id);
+                }
+                if let Some(secret_access_key) = &config.secret_access_key {
+                    builder = builder.with_secret_access_key(secret_access_key);
+                }
+                let store = builder.build().context(error::ObjectStoreSnafu)?;
+                let prefix = ObjectPath::parse(url.path()).context(error::ObjectStorePathSnafu)?;
+                (Arc::new(store), prefix)
+            }
+            scheme => {
+                return Err(Error::InvalidParam {
+                    reason: format!("unsupported cold storage scheme '{scheme}'"),
+                });
+            }
+        };
+
+        let block_size = config.block_size.max(1);
+        let block_cache_capacity = (config.block_cache_size / block_size).max(1) as usize;
+        Ok(Some(Arc::new(Self {
+            store,
+            prefix,
+            check_interval: config.check_interval,
+            block_size,
+            block_cache: ShardedCache::with_capacity(block_cache_capacity),
+        })))
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        self.check_interval
+    }
+
+    /// Object path of a tsm file: `{prefix}/{owner}/{ts_family_id}/{file_name}`.
+    pub fn object_path(
+        &self,
+        owner: &str,
+        ts_family_id: TseriesFamilyId,
+        tsm_path: impl AsRef<Path>,
+    ) -> ObjectPath {
+        let file_name = tsm_path
+            .as_ref()
+            .file_name()
+            .expect("path must not be '..'")
+            .to_string_lossy()
+            .to_string();
+        self.prefix
+            .child(owner)
+            .child(ts_family_id.to_string())
+            .child(file_name)
+    }
+
+    /// Object path of `key` under the prefix, `key` is split by '/'.
+    pub fn child_path(&self, key: &str) -> ObjectPath {
+        key.split('/')
+            .filter(|p| !p.is_empty())
+            .fold(self.prefix.clone(), |path, part| path.child(part))
+    }
+
+    /// Returns keys of all objects under `{prefix}/{key_prefix}`, keys are relative to the prefix.
+    pub async fn list(&self, key_prefix: &str) -> Result<Vec<String>> {
+        let location = self.child_path(key_prefix);
+        let objects: Vec<_> = self
+            .store
+            .list(Some(&location))
+            .await
+            .context(error::ObjectStoreSnafu)?
+            .try_collect()
+            .await
+            .context(error::ObjectStoreSnafu)?;
+        let prefix = self.prefix.to_string();
+        Ok(objects
+            .into_iter()
+            .map(|o| {
+                let key = o.location.to_string();
+                match key.strip_prefix(&prefix) {
+                    Some(k) if !prefix.is_empty() => k.trim_start_matches('/').to_string(),
+                    _ => key,
+                }
+            })
+            .collect())
+    }
+
+    pub async fn put(&self, location: &ObjectPath, data: Vec<u8>) -> Result<()> {
+        self.store
+            .put(location, Bytes::from(data))
+            .await
+            .context(error::ObjectStoreSnafu)
+    }
+
+    pub async fn get(&self, location: &ObjectPath) -> Result<Vec<u8>> {
+        let data = self
+            .store
+            .get(location)
+            .await
+            .context(error::ObjectStoreSnafu)?
+            .bytes()
+            .await
+            .context(error::ObjectStoreSnafu)?;
+        Ok(data.to_vec())
+    }
+
+    /// Download an object to a local file.
+    pub async fn download(&self, location: &ObjectPath, path: impl AsRef<Path>) -> Result<()> {
+        let mut stream = self
+            .store
+            .get(location)
+            .await
+            .context(error::ObjectStoreSnafu)?
+            .into_stream();
+        let mut file = tokio::fs::File::create(path.as_ref())
+            .await
+            .context(error::IOSnafu)?;
+        while let Some(bytes) = stream.try_next().await.context(error::ObjectStoreSnafu)? {
+            file.write_all(&bytes).await.context(error::IOSnafu)?;
+        }
+        file.sync_all().await.context(error::IOSnafu)
+    }
+
+    /// Upload a local file to the object store.
+    pub async fn upload(&self, path: impl AsRef<Path>, location: &ObjectPath) -> Result<()> {
+        let mut file = tokio::fs::File::open(path.as_ref())
+            .await
+            .context(error::IOSnafu)?;
+        let (multipart_id, mut writer) = self
+            .store
+            .put_multipart(location)
+            .await
+            .context(error::ObjectStoreSnafu)?;
+        let res = match tokio::io::copy(&mut file, &mut writer).await {
+            Ok(_) => writer.shutdown().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            if let Err(abort_err) = self.store.abort_multipart(location, &multipart_id).await {
+                error!("Failed to abort uploading '{location}': {abort_err}");
+            }
+            return Err(Error::IO { source: e });
+        }
+        Ok(())
+    }
+
+    pub async fn copy(&self, from: &str, to: &ObjectPath) -> Result<()> {
+        let from = ObjectPath::parse(from).context(error::ObjectStorePathSnafu)?;
+        self.store
+            .copy(&from, to)
+            .await
+            .context(error::ObjectStoreSnafu)
+    }
+
+    pub async fn delete(&self, location: &str) -> Result<()> {
+        let location = ObjectPath::parse(location).context(error::ObjectStorePathSnafu)?;
+        self.store
+            .delete(&location)
+            .await
+            .context(error::ObjectStoreSnafu)
+    }
+
+    /// Open a file in the object store for reading.
+    pub async fn open_file(self: &Arc<Self>, location: &str) -> Result<RemoteFile> {
+        let location = ObjectPath::parse(location).context(error::ObjectStorePathSnafu)?;
+        let meta = self
+            .store
+            .head(&location)
+            .await
+            .context(error::ObjectStoreSnafu)?;
+        Ok(RemoteFile {
+            storage: self.clone(),
+            location,
+            size: meta.size as u64,
+        })
+    }
+
+    async fn read_block(
+        &self,
+        location: &ObjectPath,
+        file_size: u64,
+        block_idx: u64,
+    ) -> io::Result<Bytes> {
+        let key = format!("{location}#{block_idx}");
+        if let Some(block) = self.block_cache.get(&key).await {
+            return Ok(block.clone());
+        }
+        let start = block_idx * self.block_size;
+        let end = (start + self.block_size).min(file_size);
+        let block = self
+            .store
+            .get_range(location, start as usize..end as usize)
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        self.block_cache.insert(key, block.clone()).await;
+        Ok(block)
+    }
+}
+
+impl Debug for ColdStorage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColdStorage")
+            .field("store", &self.store.to_string())
+            .field("prefix", &self.prefix)
+            .field("block_size", &self.block_size)
+            .finish()
+    }
+}
+
+/// Read-only file in the object store, data is read by blocks through the block cache.
+pub struct RemoteFile {
+    storage: Arc<ColdStorage>,
+    location: ObjectPath,
+    size: u64,
+}
+
+#[async_trait::async_trait]
+impl IFile for RemoteFile {
+    async fn write_vec<'a>(&self, _pos: u64, _bufs: &'a mut [IoSlice<'a>]) -> io::Result<usize> {
+        Err(read_only_error())
+    }
+
+    async fn write_at(&self, _pos: u64, _data: &[u8]) -> io::Result<usize> {
+        Err(read_only_error())
+    }
+
+    async fn read_at(&self, pos: u64, data: &mut [u8]) -> io::Result<usize> {
+        if pos >= self.size {
+            return Ok(0);
+        }
+        let end = (pos + data.len() as u64).min(self.size);
+        let mut offset = pos;
+        while offset < end {
+            let block_idx = offset / self.storage.block_size;
+            let block = self
+                .storage
+                .read_block(&self.location, self.size, block_idx)
+                .await?;
+            let block_off = (offset - block_idx * self.storage.block_size) as usize;
+            let len = (block.len() - block_off).min((end - offset) as usize);
+            if len == 0 {
+                break;
+            }
+            let data_off = (offset - pos) as usize;
+            data[data_off..data_off + len].copy_from_slice(&block[block_off..block_off + len]);
+            offset += len as u64;
+        }
+        Ok((offset - pos) as usize)
+    }
+
+    async fn sync_data(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn truncate(&self, _size: u64) -> io::Result<()> {
+        Err(read_only_error())
+    }
+
+    fn len(&self) -> u64 {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+fn read_only_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "file in cold storage is read-only",
+    )
+}
+
+pub fn run(
+    cold_storage: Arc<ColdStorage>,
+    runtime: Arc<Runtime>,
+    version_set: Arc<RwLock<VersionSet>>,
+    summary_task_sender: Sender<SummaryTask>,
+) {
+    runtime.spawn(async move {
+        let mut check_interval =
+            tokio::time::interval(cold_storage.check_interval().max(Duration::from_secs(1)));
+        check_interval.tick().await;
+        loop {
+            check_interval.tick().await;
+            let databases = version_set.read().await.get_all_db().clone();
+            for database in databases.into_values() {
+                let db = database.read().await;
+                let time_to_cold = match db.get_schema() {
+                    Ok(schema) => match schema.time_to_cold() {
+                        Some(ts) => ts,
+                        None => continue,
+                    },
+                    Err(e) => {
+                        error!("Failed to get schema of database '{}': {e}", db.owner());
+                        continue;
+                    }
+                };
+                let ts_families = db.ts_families().clone();
+                drop(db);
+
+                for ts_family in ts_families.into_values() {
+                    if let Err(e) = move_cold_files(
+                        &cold_storage,
+                        ts_family,
+                        time_to_cold,
+                        summary_task_sender.clone(),
+                    )
+                    .await
+                    {
+                        error!("Failed to move files to cold storage: {e}");
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Upload files with all timestamps not greater than `time_to_cold` to the cold storage,
+/// then replace them with the uploaded files in a new version.
+pub async fn move_cold_files(
+    cold_storage: &ColdStorage,
+    ts_family: Arc<RwLock<TseriesFamily>>,
+    time_to_cold: i64,
+    summary_task_sender: Sender<SummaryTask>,
+) -> Result<()> {
+    let version = ts_family.read().await.version();
+    let owner = version.database();
+    let ts_family_id = version.tf_id();
+
+    let files: Vec<Arc<ColumnFile>> = version
+        .levels_info
+        .iter()
+        .skip(1)
+        .flat_map(|level| level.files.iter())
+        .filter(|f| {
+            !f.is_remote()
+                && !f.is_delta()
+                && f.time_range().max_ts <= time_to_cold
+                && f.mark_compacting()
+        })
+        .cloned()
+        .collect();
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let mut version_edit = VersionEdit::new(ts_family_id);
+    let mut file_metas: HashMap<ColumnFileId, Arc<BloomFilter>> = HashMap::new();
+    let mut uploaded = Vec::with_capacity(files.len());
+    for file in files.iter() {
+        let location = cold_storage.object_path(&owner, ts_family_id, file.file_path());
+        if let Err(e) = cold_storage.upload(file.file_path(), &location).await {
+            error!(
+                "Failed to upload file '{}' to cold storage: {e}",
+                file.file_path().display()
+            );
+            file.unmark_compacting();
+            continue;
+        }
+        info!(
+            "Uploaded file '{}' to cold storage at '{location}'",
+            file.file_path().display()
+        );
+
+        let mut meta = CompactMeta::from(file.as_ref());
+        meta.tsf_id = ts_family_id;
+        meta.remote_path = Some(location.to_string());
+        version_edit.del_file(file.level(), file.file_id(), false);
+        version_edit.add_file(meta, version.max_level_ts);
+        file_metas.insert(file.file_id(), file.field_id_filter());
+        uploaded.push(location.to_string());
+    }
+    if uploaded.is_empty() {
+        return Ok(());
+    }
+
+    let (summary_tx, summary_rx) = oneshot::channel();
+    let _ = summary_task_sender
+        .send(SummaryTask::new(
+            vec![version_edit],
+            Some(file_metas),
+            None,
+            summary_tx,
+        ))
+        .await;
+    let res = match summary_rx.await {
+        Ok(res) => res,
+        Err(e) => Err(Error::ChannelReceive {
+            source: error::ChannelReceiveError::WriteSummaryResult { source: e },
+        }),
+    };
+    if let Err(e) = res {
+        // Local files are still in use, remove the uploaded ones.
+        for file in files.iter() {
+            file.unmark_compacting();
+        }
+        for location in uploaded.iter() {
+            if let Err(e) = cold_storage.delete(location).await {
+                error!("Failed to delete '{location}' from cold storage: {e}");
+            }
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use config::ColdStorageConfig;
+
+    use super::ColdStorage;
+    use crate::file_system::file::IFile;
+
+    #[tokio::test]
+    async fn test_upload_and_read() {
+        let dir = "/tmp/test/cold_storage/upload_and_read";
+        let _ = std::fs::remove_dir_all(dir);
+        std::fs::create_dir_all(dir).unwrap();
+        let path = PathBuf::from(dir).join("_000001.tsm");
+        let data: Vec<u8> = (0..10000_u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let config = ColdStorageConfig {
+            url: "memory://".to_string(),
+            block_size: 1024,
+            block_cache_size: 4096,
+            ..Default::default()
+        };
+        let cold_storage = ColdStorage::open(&config).unwrap().unwrap();
+        let location = cold_storage.object_path("cnosdb.public", 1, &path);
+        assert_eq!(location.to_string(), "cnosdb.public/1/_000001.tsm");
+        cold_storage.upload(&path, &location).await.unwrap();
+
+        let file = cold_storage.open_file(&location.to_string()).await.unwrap();
+        assert_eq!(file.len(), data.len() as u64);
+        for (pos, len) in [
+            (0_u64, 10_usize),
+            (1000, 100),
+            (1020, 3000),
+            (9990, 100),
+            (10000, 10),
+        ] {
+            let mut buf = vec![0_u8; len];
+            let read = file.read_at(pos, &mut buf).await.unwrap();
+            let start = (pos as usize).min(data.len());
+            let end = (pos as usize + len).min(data.len());
+            assert_eq!(&buf[..read], &data[start..end]);
+        }
+
+        cold_storage.delete(&location.to_string()).await.unwrap();
+        assert!(cold_storage.open_file(&location.to_string()).await.is_err());
+    }
+}
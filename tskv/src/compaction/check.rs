@@ -1,7 +1,6 @@
 use std::cmp;
 use std::collections::HashMap;
 use std::fmt::{Display, Write};
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use blake3::Hasher;
@@ -16,7 +15,7 @@ use tokio::sync::RwLock;
 
 use crate::compaction::CompactIterator;
 use crate::error::{Error, Result};
-use crate::tseries_family::TseriesFamily;
+use crate::tseries_family::{ColumnFile, TseriesFamily};
 use crate::tsm::{DataBlock, TsmReader};
 use crate::TseriesFamilyId;
 
@@ -228,13 +227,13 @@ pub(crate) async fn vnode_hash_tree(
         (vnode_rlock.version(), vnode_rlock.tf_id())
     };
     let mut readers: Vec<Arc<TsmReader>> = Vec::new();
-    let tsm_paths: Vec<&PathBuf> = version
+    let column_files: Vec<&Arc<ColumnFile>> = version
         .levels_info()
         .iter()
-        .flat_map(|l| l.files.iter().map(|f| f.file_path()))
+        .flat_map(|l| l.files.iter())
         .collect();
-    for p in tsm_paths {
-        let r = version.get_tsm_reader(p).await?;
+    for f in column_files {
+        let r = version.get_tsm_reader(f).await?;
         readers.push(r);
     }
 
@@ -475,7 +474,7 @@ mod test {
     use crate::compaction::check::{vnode_hash_tree, TimeRangeHashTreeNode, DEFAULT_DURATION};
     use crate::compaction::flush;
     use crate::context::GlobalContext;
-    use crate::tseries_family::TseriesFamily;
+    use crate::tseries_family::{ColumnFile, TseriesFamily};
     use crate::tsm::codec::DataBlockEncoding;
     use crate::tsm::DataBlock;
     use crate::{Engine, Options, TsKv, TseriesFamilyId};
@@ -3,18 +3,20 @@ use std::fmt::{Display, Formatter};
 use std::pin::Pin;
 use std::sync::Arc;
 
+use models::codec::Encoding;
 use models::predicate::domain::TimeRange;
-use models::{FieldId, Timestamp};
+use models::{FieldId, PhysicalDType as ValueType, Timestamp};
 use snafu::ResultExt;
 use trace::{error, info, trace};
 use utils::BloomFilter;
 
 use super::iterator::BufferedIterator;
-use crate::compaction::CompactReq;
+use crate::compaction::{CompactReq, WriteRateLimiter};
 use crate::context::GlobalContext;
 use crate::error::{self, Result};
 use crate::summary::{CompactMeta, VersionEdit};
 use crate::tseries_family::TseriesFamily;
+use crate::tsm::codec::DataBlockEncoding;
 use crate::tsm::{
     self, BlockMeta, BlockMetaIterator, DataBlock, EncodedDataBlock, IndexIterator, IndexMeta,
     TsmReader, TsmWriter,
@@ -84,8 +86,9 @@ impl CompactingBlockMeta {
     }
 
     pub async fn get_data_block(&self) -> Result<DataBlock> {
+        // Files being compacted will be deleted, don't fill the data block cache.
         self.reader
-            .get_data_block(&self.meta)
+            .get_data_block_opt(&self.meta, false)
             .await
             .context(error::ReadTsmSnafu)
     }
@@ -127,19 +130,28 @@ impl CompactingBlockMetaGroup {
         self.time_range.merge(&other.time_range);
     }
 
+    /// Merges the blocks with the previous merged block. If `field_column` (the
+    /// type and encoding of the field column in the table schema) is not None,
+    /// blocks are converted to the type if it differs from the type of the block,
+    /// and merged blocks are re-encoded with the encoding of the column; a single
+    /// block written as raw keeps its encoding.
     pub async fn merge(
         mut self,
         previous_block: Option<CompactingBlock>,
         max_block_size: usize,
+        field_column: Option<(ValueType, Encoding)>,
     ) -> Result<Vec<CompactingBlock>> {
         if self.blk_metas.is_empty() {
             return Ok(vec![]);
         }
         self.blk_metas
             .sort_by(|a, b| a.reader_idx.cmp(&b.reader_idx).reverse());
+        let (field_type, encoding) = field_column.unzip();
+        let convert_to =
+            field_type.filter(|t| self.blk_metas.iter().any(|m| m.meta.field_type() != *t));
 
-        let merged_block;
-        if self.blk_metas.len() == 1 && !self.blk_metas[0].has_tombstone() {
+        let mut merged_block;
+        if self.blk_metas.len() == 1 && !self.blk_metas[0].has_tombstone() && convert_to.is_none() {
             // Only one compacting block and has no tombstone, write as raw block.
             trace!("only one compacting block, write as raw block");
             let meta_0 = &self.blk_metas[0].meta;
@@ -182,13 +194,17 @@ impl CompactingBlockMetaGroup {
                 )]);
             }
         } else {
-            // One block with tombstone or multi compacting blocks, decode and merge these data block.
+            // One block with tombstone or in another type, or multi compacting blocks,
+            // decode and merge these data block.
             trace!(
                 "there are {} compacting blocks, need to decode and merge",
                 self.blk_metas.len()
             );
             let head = &mut self.blk_metas[0];
             let mut head_block = head.get_data_block().await?;
+            if let Some(t) = convert_to {
+                head_block = head_block.convert_to(t)?;
+            }
 
             if let Some(compacting_block) = previous_block {
                 let mut data_block = compacting_block.decode()?;
@@ -198,11 +214,17 @@ impl CompactingBlockMetaGroup {
 
             for blk_meta in self.blk_metas[1..].iter_mut() {
                 // Merge decoded data block.
-                let blk_block = blk_meta.get_data_block().await?;
+                let mut blk_block = blk_meta.get_data_block().await?;
+                if let Some(t) = convert_to {
+                    blk_block = blk_block.convert_to(t)?;
+                }
                 head_block = head_block.merge(blk_block);
             }
             merged_block = head_block;
         }
+        if let Some(encoding) = encoding {
+            merged_block.set_encoding(DataBlockEncoding::new(Encoding::Default, encoding));
+        }
 
         self.chunk_merged_block(merged_block, max_block_size)
     }
@@ -645,7 +667,7 @@ fn overlaps_tuples(r1: (i64, i64), r2: (i64, i64)) -> bool {
 }
 
 pub async fn run_compaction_job(
-    request: CompactReq,
+    mut request: CompactReq,
     kernel: Arc<GlobalContext>,
 ) -> Result<Option<(VersionEdit, HashMap<ColumnFileId, Arc<BloomFilter>>)>> {
     info!(
@@ -676,7 +698,7 @@ pub async fn run_compaction_job(
     let tsf_id = request.ts_family_id;
     let mut tsm_readers = Vec::new();
     for col_file in request.files.iter() {
-        let tsm_reader = request.version.get_tsm_reader(col_file.file_path()).await?;
+        let tsm_reader = request.version.get_tsm_reader(col_file).await?;
         tsm_readers.push(tsm_reader);
     }
 
@@ -692,6 +714,7 @@ pub async fn run_compaction_job(
     let mut version_edit = VersionEdit::new(tsf_id);
     let mut file_metas: HashMap<ColumnFileId, Arc<BloomFilter>> = HashMap::new();
 
+    let mut field_type_resolver = request.field_type_resolver.take();
     let mut previous_merged_block: Option<CompactingBlock> = None;
     let mut fid = iter.curr_fid;
     while let Some(blk_meta_group) = iter.next().await {
@@ -706,6 +729,7 @@ pub async fn run_compaction_job(
                     &mut file_metas,
                     &mut version_edit,
                     &request,
+                    kernel.compact_rate_limiter(),
                 )
                 .await?
                 {
@@ -721,8 +745,12 @@ pub async fn run_compaction_job(
         }
 
         fid = iter.curr_fid;
+        let field_column = match (field_type_resolver.as_mut(), fid) {
+            (Some(resolver), Some(field_id)) => resolver.field_column(field_id).await,
+            _ => None,
+        };
         let mut compacting_blks = blk_meta_group
-            .merge(previous_merged_block.take(), max_block_size)
+            .merge(previous_merged_block.take(), max_block_size, field_column)
             .await?;
         if compacting_blks.len() == 1 && compacting_blks[0].len() < max_block_size {
             // The only one data block too small, try to extend the next compacting blocks.
@@ -744,6 +772,7 @@ pub async fn run_compaction_job(
                 &mut file_metas,
                 &mut version_edit,
                 &request,
+                kernel.compact_rate_limiter(),
             )
             .await?
             {
@@ -763,6 +792,7 @@ pub async fn run_compaction_job(
             &mut file_metas,
             &mut version_edit,
             &request,
+            kernel.compact_rate_limiter(),
         )
         .await?;
     }
@@ -794,6 +824,7 @@ async fn write_tsm(
     file_metas: &mut HashMap<ColumnFileId, Arc<BloomFilter>>,
     version_edit: &mut VersionEdit,
     request: &CompactReq,
+    rate_limiter: &WriteRateLimiter,
 ) -> Result<bool> {
     let write_ret = match blk {
         CompactingBlock::Decoded {
@@ -808,8 +839,9 @@ async fn write_tsm(
         } => tsm_writer.write_encoded_block(field_id, &data_block).await,
         CompactingBlock::Raw { meta, raw, .. } => tsm_writer.write_raw(&meta, &raw).await,
     };
-    if let Err(e) = write_ret {
-        match e {
+    match write_ret {
+        Ok(size) => rate_limiter.request(size).await,
+        Err(e) => match e {
             tsm::WriteTsmError::WriteIO { source } => {
                 // TODO try re-run compaction on other time.
                 error!("Failed compaction: IO error when write tsm: {:?}", source);
@@ -840,7 +872,7 @@ async fn write_tsm(
                     path.display()
                 );
             }
-        }
+        },
     }
 
     Ok(false)
@@ -890,6 +922,7 @@ fn new_compact_meta(
         high_seq: 0,
         low_seq: 0,
         is_delta: false,
+        remote_path: None,
     }
 }
 
@@ -902,9 +935,11 @@ pub mod test {
 
     use lru_cache::asynchronous::ShardedCache;
     use minivec::MiniVec;
+    use models::codec::Encoding;
     use models::predicate::domain::TimeRange;
     use models::{FieldId, PhysicalDType as ValueType, Timestamp};
 
+    use super::{CompactIterator, CompactingBlock};
     use crate::compaction::{run_compaction_job, CompactReq};
     use crate::context::GlobalContext;
     use crate::file_system::file_manager;
@@ -1021,6 +1056,8 @@ pub mod test {
             LevelInfo::init_levels(database.clone(), 0, opt.storage.clone()),
             1000,
             Arc::new(ShardedCache::with_capacity(1)),
+            None,
+            None,
         ));
         let compact_req = CompactReq {
             ts_family_id: 1,
@@ -1029,6 +1066,7 @@ pub mod test {
             files,
             version,
             out_level: 2,
+            field_type_resolver: None,
         };
         let kernel = Arc::new(GlobalContext::new());
         kernel.set_file_id(next_file_id);
@@ -1600,4 +1638,43 @@ pub mod test {
 
         check_column_file(dir, version_edit, expected_data).await;
     }
+
+    #[tokio::test]
+    async fn test_merge_with_column_encoding() {
+        #[rustfmt::skip]
+        let data = vec![
+            HashMap::from([
+                (1, vec![DataBlock::I64 { ts: vec![1, 2, 3], val: vec![1, 2, 3], enc: DataBlockEncoding::default() }]),
+            ]),
+            HashMap::from([
+                (1, vec![DataBlock::I64 { ts: vec![3, 4, 5], val: vec![3, 4, 5], enc: DataBlockEncoding::default() }]),
+            ]),
+        ];
+
+        let dir = "/tmp/test/compaction/merge_with_column_encoding";
+        let _ = std::fs::remove_dir_all(dir);
+        let (_, files) = write_data_blocks_to_column_file(dir, data).await;
+        let mut tsm_readers = Vec::new();
+        for file in files.iter() {
+            tsm_readers.push(Arc::new(TsmReader::open(file.file_path()).await.unwrap()));
+        }
+
+        let mut iter = CompactIterator::new(tsm_readers, 2, false);
+        let blk_meta_group = iter.next().await.unwrap();
+        let compacting_blks = blk_meta_group
+            .merge(None, 2, Some((ValueType::Integer, Encoding::Null)))
+            .await
+            .unwrap();
+        assert_eq!(compacting_blks.len(), 2);
+        for blk in compacting_blks {
+            match blk {
+                CompactingBlock::Encoded { data_block, .. } => {
+                    // Encoding of the column rather than of the source blocks is used.
+                    let (_, val_encoding) = data_block.decode().unwrap().encodings().split();
+                    assert_eq!(val_encoding, Encoding::Null);
+                }
+                _ => panic!("expected encoded compacting block, got {blk}"),
+            }
+        }
+    }
 }
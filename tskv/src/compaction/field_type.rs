@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use models::codec::Encoding;
+use models::schema::{PhysicalCType, TskvTableSchemaRef};
+use models::utils::split_id;
+use models::{FieldId, PhysicalDType as ValueType, SeriesId};
+use trace::warn;
+
+use crate::index::ts_index::TSIndex;
+use crate::schema::schemas::DBschemas;
+use crate::version_set::VersionSet;
+use crate::TseriesFamilyId;
+
+/// Resolves types and encodings of field columns in the current table schemas,
+/// data blocks written before the type of a field column is changed are converted
+/// to it during compaction, and merged data blocks are encoded with the encoding
+/// of the field column.
+pub struct FieldTypeResolver {
+    ts_index: Arc<TSIndex>,
+    schemas: Arc<DBschemas>,
+    /// Table schema of each resolved series, None if the table is not found.
+    series_schemas: HashMap<SeriesId, Option<TskvTableSchemaRef>>,
+}
+
+impl FieldTypeResolver {
+    pub fn new(ts_index: Arc<TSIndex>, schemas: Arc<DBschemas>) -> Self {
+        Self {
+            ts_index,
+            schemas,
+            series_schemas: HashMap::new(),
+        }
+    }
+
+    /// Creates a `FieldTypeResolver` for a vnode of the database `owner`,
+    /// returns None if the database or the index of the vnode is not found.
+    pub async fn for_vnode(
+        version_set: &VersionSet,
+        owner: &str,
+        vnode_id: TseriesFamilyId,
+    ) -> Option<Self> {
+        let db = version_set.get_all_db().get(owner)?.read().await;
+        let ts_index = db.get_ts_index(vnode_id)?;
+        Some(Self::new(ts_index, db.get_schemas()))
+    }
+
+    /// Returns the type and the encoding of the field column in the current
+    /// table schema, returns None if the series, table or column is not found.
+    pub async fn field_column(&mut self, field_id: FieldId) -> Option<(ValueType, Encoding)> {
+        let (column_id, series_id) = split_id(field_id);
+        if !self.series_schemas.contains_key(&series_id) {
+            let schema = self.table_schema(series_id).await;
+            self.series_schemas.insert(series_id, schema);
+        }
+        let schema = self.series_schemas.get(&series_id)?.as_ref()?;
+        let column = schema.columns().iter().find(|c| c.id == column_id)?;
+        match column.column_type.to_physical_type() {
+            PhysicalCType::Field(value_type) => Some((value_type, column.encoding)),
+            _ => None,
+        }
+    }
+
+    async fn table_schema(&self, series_id: SeriesId) -> Option<TskvTableSchemaRef> {
+        let series_key = match self.ts_index.get_series_key(series_id).await {
+            Ok(key) => key?,
+            Err(e) => {
+                warn!("failed to get series key of {}: {}", series_id, e);
+                return None;
+            }
+        };
+        match self.schemas.get_table_schema(series_key.table()) {
+            Ok(schema) => schema,
+            Err(e) => {
+                warn!(
+                    "failed to get schema of table {}: {}",
+                    series_key.table(),
+                    e
+                );
+                None
+            }
+        }
+    }
+}
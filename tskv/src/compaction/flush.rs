@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -18,7 +18,7 @@ use utils::BloomFilter;
 use crate::compaction::{CompactTask, FlushReq};
 use crate::context::{GlobalContext, GlobalSequenceContext};
 use crate::error::{self, Result};
-use crate::memcache::{FieldVal, MemCache, SeriesData};
+use crate::memcache::{MemCache, SeriesData};
 use crate::summary::{CompactMeta, CompactMetaBuilder, SummaryTask, VersionEdit};
 use crate::tseries_family::Version;
 use crate::tsm::codec::DataBlockEncoding;
@@ -125,47 +125,44 @@ impl FlushTask {
         let mut writer = WriterWrapper::new(self.ts_family_id, max_level_ts, max_data_block_size);
 
         let mut column_encoding_map: HashMap<ColumnId, Encoding> = HashMap::new();
-        let mut column_values_map: HashMap<ColumnId, (ValueType, Vec<(Timestamp, FieldVal)>)> =
-            HashMap::new();
+        let mut column_blocks_map: HashMap<ColumnId, DataBlock> = HashMap::new();
         for (sid, series_datas) in caches_data.iter_mut() {
             column_encoding_map.clear();
-            column_values_map.clear();
+            column_blocks_map.clear();
 
             // Iterates [ MemCache ] -> next_series_id -> [ SeriesData ]
             for series_data in series_datas.iter_mut() {
-                // Iterates SeriesData -> [ RowGroups{ schema_id, schema, [ RowData ] } ]
-                for (_sch_id, sch_cols, rows) in series_data.read().flat_groups() {
+                // Iterates SeriesData -> [ ColumnGroup{ schema_id, schema, [ Arrays ] } ]
+                for (_sch_id, sch_cols, group) in series_data.read().flat_groups() {
                     for i in sch_cols.columns().iter() {
                         column_encoding_map.insert(i.id, i.encoding);
                     }
-                    // Iterates [ RowData ]
-                    for row in rows.iter() {
-                        // Iterates RowData -> [ Option<FieldVal>, column_id ]
-                        for (val, col) in row.fields.iter().zip(sch_cols.fields().iter()) {
-                            if let Some(v) = val {
-                                let (_, col_vals) = column_values_map
-                                    .entry(col.id)
-                                    .or_insert_with(|| (v.value_type(), Vec::with_capacity(64)));
-                                col_vals.push((row.ts, v.clone()));
+                    // Iterates ColumnGroup -> [ column_id, DataBlock ]
+                    for (col_id, data_block) in group.field_data_blocks() {
+                        match column_blocks_map.get_mut(&col_id) {
+                            Some(blk) => blk.extend(data_block),
+                            None => {
+                                column_blocks_map.insert(col_id, data_block);
                             }
                         }
                     }
                 }
             }
 
-            for (col_id, (value_type, values)) in column_values_map.iter_mut() {
-                // Sort by timestamp.
-                values.sort_by_key(|a| a.0);
-                // Dedup by timestamp.
-                utils::dedup_front_by_key(values, |a| a.0);
+            for (col_id, data_block) in column_blocks_map.drain() {
+                // Sort and dedup by timestamp.
+                let data_block = sort_dedup_data_block(data_block);
 
-                let field_id = model_utils::unite_id(*col_id, *sid);
+                let field_id = model_utils::unite_id(col_id, *sid);
                 let encoding = DataBlockEncoding::new(
                     Encoding::Default,
-                    column_encoding_map.get(col_id).copied().unwrap_or_default(),
+                    column_encoding_map
+                        .get(&col_id)
+                        .copied()
+                        .unwrap_or_default(),
                 );
                 writer
-                    .write_field(field_id, values, value_type, encoding, self)
+                    .write_field(field_id, &data_block, encoding, self)
                     .await?;
             }
         }
@@ -174,6 +171,27 @@ impl FlushTask {
         writer.finish().await
     }
 
+    /// Writes values of fields (sorted and deduplicated by timestamp) into a
+    /// `.tsm` file and a `.delta` file, returns `CompactMeta`s of the wrote files.
+    pub async fn write_data_blocks(
+        &self,
+        data_blocks: BTreeMap<FieldId, (DataBlock, Encoding)>,
+        max_level_ts: Timestamp,
+    ) -> Result<Vec<(CompactMeta, Arc<BloomFilter>)>> {
+        let mut writer = WriterWrapper::new(
+            self.ts_family_id,
+            max_level_ts,
+            tsm::MAX_BLOCK_VALUES as usize,
+        );
+        for (field_id, (data_block, encoding)) in data_blocks.iter() {
+            let encoding = DataBlockEncoding::new(Encoding::Default, *encoding);
+            writer
+                .write_field(*field_id, data_block, encoding, self)
+                .await?;
+        }
+        writer.finish().await
+    }
+
     async fn new_tsm_writer(&self, is_delta: bool) -> Result<TsmWriter> {
         let dir = if is_delta {
             &self.path_delta
@@ -276,6 +294,26 @@ pub async fn run_flush_memtable_job(
     Ok(())
 }
 
+/// Sorts values of a `DataBlock` by timestamp, keeps the last written value
+/// if there are many values with the same timestamp.
+pub(crate) fn sort_dedup_data_block(data_block: DataBlock) -> DataBlock {
+    let ts = data_block.ts();
+    let mut indices: Vec<usize> = (0..ts.len()).collect();
+    indices.sort_by_key(|i| ts[*i]);
+    utils::dedup_front_by_key(&mut indices, |i| ts[*i]);
+    if indices.len() == ts.len() && indices.iter().enumerate().all(|(i, j)| i == *j) {
+        return data_block;
+    }
+
+    let mut sorted_data_block = DataBlock::new(indices.len(), data_block.field_type());
+    for i in indices {
+        if let Some(val) = data_block.get(i) {
+            sorted_data_block.insert(val);
+        }
+    }
+    sorted_data_block
+}
+
 struct WriterWrapper {
     ts_family_id: TseriesFamilyId,
     max_level_ts: Timestamp,
@@ -313,18 +351,18 @@ impl WriterWrapper {
         }
     }
 
+    /// Writes values of a field sorted and deduplicated by timestamp.
     pub async fn write_field(
         &mut self,
         field_id: FieldId,
-        values: &[(Timestamp, FieldVal)],
-        value_type: &ValueType,
+        data_block: &DataBlock,
         encoding: DataBlockEncoding,
         flush_task: &FlushTask,
     ) -> Result<()> {
-        if values.is_empty() {
+        if data_block.is_empty() {
             return Ok(());
         }
-        let buf_idx = match value_type {
+        let buf_idx = match data_block.field_type() {
             ValueType::Float => 0,
             ValueType::Integer => 1,
             ValueType::Unsigned => 2,
@@ -337,29 +375,19 @@ impl WriterWrapper {
         };
 
         // Split values for level-0 and levle-1.
-        let splited_values = match values.binary_search_by(|v| v.0.cmp(&self.max_level_ts)) {
-            Ok(i) => {
-                if i == values.len() - 1 {
-                    [values, &[]]
-                } else {
-                    [&values[..=i], &values[(i + 1)..]]
-                }
-            }
-            Err(i) => {
-                if i == 0 {
-                    [&[], values]
-                } else if i < values.len() {
-                    [&values[..i], &values[i..]]
-                } else {
-                    [values, &[]]
-                }
-            }
+        let len = data_block.len();
+        let split_idx = match data_block.ts().binary_search(&self.max_level_ts) {
+            Ok(i) => i + 1,
+            Err(i) => i,
         };
+        let splited_ranges = [0..split_idx, split_idx..len];
         // Fill buffer and write to disk if buffer is full.
-        for (level_idx, values) in splited_values.into_iter().enumerate() {
-            for (ts, val) in values {
+        for (level_idx, range) in splited_ranges.into_iter().enumerate() {
+            for i in range {
                 let buffer = &mut self.buffers[level_idx][buf_idx];
-                buffer.insert(val.data_value(*ts));
+                if let Some(val) = data_block.get(i) {
+                    buffer.insert(val);
+                }
                 if buffer.len() > self.max_data_block_size {
                     buffer.set_encoding(encoding);
                     Self::write_tsm(&mut self.writers, flush_task, level_idx, field_id, buffer)
@@ -401,10 +429,16 @@ impl WriterWrapper {
                 writer_opt.insert(writer)
             }
         };
-        writer
+        let size = writer
             .write_block(field_id, data_block)
             .await
-            .context(error::WriteTsmSnafu)
+            .context(error::WriteTsmSnafu)?;
+        flush_task
+            .global_context
+            .flush_rate_limiter()
+            .request(size)
+            .await;
+        Ok(size)
     }
 
     pub async fn finish(&mut self) -> Result<Vec<(CompactMeta, Arc<BloomFilter>)>> {
@@ -457,7 +491,7 @@ pub mod flush_tests {
     use parking_lot::RwLock;
     use utils::dedup_front_by_key;
 
-    use super::FlushTask;
+    use super::{sort_dedup_data_block, FlushTask};
     use crate::compaction::flush::WriterWrapper;
     use crate::context::GlobalContext;
     use crate::file_utils;
@@ -503,6 +537,23 @@ pub mod flush_tests {
         assert_eq!(&data, &vec![(1, 12), (2, 22), (3, 3), (4, 42)]);
     }
 
+    #[test]
+    fn test_sort_dedup_data_block() {
+        let data_block = DataBlock::I64 {
+            ts: vec![1, 1, 2, 3, 2, 4, 4],
+            val: vec![11, 12, 21, 3, 22, 41, 42],
+            enc: DataBlockEncoding::default(),
+        };
+        assert_eq!(
+            sort_dedup_data_block(data_block),
+            DataBlock::I64 {
+                ts: vec![1, 2, 3, 4],
+                val: vec![12, 22, 3, 42],
+                enc: DataBlockEncoding::default(),
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_flush() {
         let mut config = config::get_config_for_test();
@@ -531,6 +582,8 @@ pub mod flush_tests {
             max_level_ts: test_case.max_level_ts_before,
             levels_info: LevelInfo::init_levels(database, 0, options.storage),
             tsm_reader_cache: Arc::new(ShardedCache::with_capacity(1)),
+            cold_storage: None,
+            data_block_cache: None,
         });
         let flush_task =
             FlushTask::new(test_case.caches(), 1, global_context, &tsm_dir, &delta_dir);
@@ -783,21 +836,16 @@ pub mod flush_tests {
         expected_delta_data: &mut HashMap<FieldId, Vec<DataBlock>>,
         expected_tsm_data: &mut HashMap<FieldId, Vec<DataBlock>>,
     ) {
-        let value_type = field_val.value_type();
         let (col_id, _) = model_utils::split_id(field_id);
         let encoding = DataBlockEncoding::new(
             Encoding::Default,
             col_enc_map.get(&col_id).copied().unwrap_or_default(),
         );
 
-        let values = vec![
-            (max_level_ts - 2, field_val.clone()),
-            (max_level_ts - 1, field_val.clone()),
-            (max_level_ts, field_val.clone()),
-            (max_level_ts + 1, field_val.clone()),
-            (max_level_ts + 2, field_val.clone()),
-            (max_level_ts + 3, field_val.clone()),
-        ];
+        let mut data_block = DataBlock::new(6, field_val.value_type());
+        for ts in max_level_ts - 2..=max_level_ts + 3 {
+            data_block.insert(field_val.data_value(ts));
+        }
         let mut delta_data_block = DataBlock::new(1, field_val.value_type());
         delta_data_block.insert(field_val.data_value(max_level_ts - 2));
         delta_data_block.insert(field_val.data_value(max_level_ts - 1));
@@ -811,7 +859,7 @@ pub mod flush_tests {
         expected_tsm_data.insert(field_id, vec![tsm_data_block]);
 
         writer
-            .write_field(field_id, &values, &value_type, encoding, flush_task)
+            .write_field(field_id, &data_block, encoding, flush_task)
             .await
             .unwrap();
     }
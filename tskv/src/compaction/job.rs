@@ -7,7 +7,7 @@ use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{oneshot, RwLock, Semaphore};
 use trace::{error, info};
 
-use crate::compaction::{flush, CompactTask, LevelCompactionPicker, Picker};
+use crate::compaction::{flush, new_picker, CompactTask, FieldTypeResolver};
 use crate::context::{GlobalContext, GlobalSequenceContext};
 use crate::kv_option::StorageOptions;
 use crate::summary::SummaryTask;
@@ -85,9 +85,24 @@ pub fn run(
                         info!("forbidden compaction on moving vnode {}", vnode_id);
                         return;
                     }
-                    let picker = LevelCompactionPicker::new(storage_opt.clone());
                     let version = tsf.read().await.version();
-                    let compact_req = picker.pick_compaction(version);
+                    let db_schema = version_set
+                        .read()
+                        .await
+                        .get_db_schema_by_owner(&version.database)
+                        .await
+                        .ok()
+                        .flatten();
+                    let field_type_resolver = FieldTypeResolver::for_vnode(
+                        &*version_set.read().await,
+                        &version.database,
+                        vnode_id,
+                    )
+                    .await;
+                    let picker = new_picker(storage_opt.clone(), db_schema.as_ref());
+                    let compact_req = picker
+                        .pick_compaction(version)
+                        .map(|req| req.with_field_type_resolver(field_type_resolver));
                     if let Some(req) = compact_req {
                         let database = req.database.clone();
                         let compact_ts_family = req.ts_family_id;
@@ -1,16 +1,20 @@
 pub mod check;
 mod compact;
+mod field_type;
 mod flush;
 mod iterator;
 pub mod job;
 mod picker;
+mod rate_limiter;
 
 use std::sync::Arc;
 
 pub use compact::*;
+pub use field_type::*;
 pub use flush::*;
 use parking_lot::RwLock;
 pub use picker::*;
+pub use rate_limiter::*;
 
 use crate::kv_option::StorageOptions;
 use crate::memcache::MemCache;
@@ -30,6 +34,32 @@ pub struct CompactReq {
     files: Vec<Arc<ColumnFile>>,
     version: Arc<Version>,
     pub out_level: LevelId,
+    /// Resolves the current types of field columns, None if types are not checked.
+    field_type_resolver: Option<FieldTypeResolver>,
+}
+
+impl CompactReq {
+    /// Creates a request compacting `files` of `version` into `out_level`.
+    pub(crate) fn new(
+        version: Arc<Version>,
+        files: Vec<Arc<ColumnFile>>,
+        out_level: LevelId,
+    ) -> Self {
+        Self {
+            ts_family_id: version.ts_family_id,
+            database: version.database.clone(),
+            storage_opt: version.storage_opt.clone(),
+            files,
+            version,
+            out_level,
+            field_type_resolver: None,
+        }
+    }
+
+    pub fn with_field_type_resolver(mut self, resolver: Option<FieldTypeResolver>) -> Self {
+        self.field_type_resolver = resolver;
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use models::predicate::domain::TimeRange;
+use models::schema::{CompactionStrategy, DatabaseSchema};
 use trace::{debug, info};
 
 use crate::compaction::CompactReq;
@@ -14,6 +16,28 @@ pub trait Picker: Send + Sync + Debug {
     fn pick_compaction(&self, version: Arc<Version>) -> Option<CompactReq>;
 }
 
+/// Returns the compaction picker of the compaction strategy of the database,
+/// returns LevelCompactionPicker if database schema not found.
+pub fn new_picker(
+    storage_opt: Arc<StorageOptions>,
+    db_schema: Option<&DatabaseSchema>,
+) -> Box<dyn Picker> {
+    match db_schema {
+        Some(schema)
+            if schema.config.compaction_strategy_or_default()
+                == &CompactionStrategy::TimeWindow =>
+        {
+            let precision = *schema.config.precision_or_default();
+            let window = schema
+                .config
+                .compaction_window_or_default()
+                .to_precision(precision);
+            Box::new(TimeWindowCompactionPicker::new(window))
+        }
+        _ => Box::new(LevelCompactionPicker::new(storage_opt)),
+    }
+}
+
 /// Compaction picker for picking files in level
 #[derive(Debug)]
 pub struct LevelCompactionPicker {
@@ -109,7 +133,10 @@ impl Picker for LevelCompactionPicker {
             if file.time_range().min_ts > picking_time_range.max_ts {
                 break;
             }
-            if file.is_compacting() || !file.time_range().overlaps(&picking_time_range) {
+            if file.is_remote()
+                || file.is_compacting()
+                || !file.time_range().overlaps(&picking_time_range)
+            {
                 continue;
             }
             if !file.mark_compacting() {
@@ -151,6 +178,7 @@ impl Picker for LevelCompactionPicker {
             files: picking_files,
             version: version.clone(),
             out_level,
+            field_type_resolver: None,
         })
     }
 }
@@ -216,13 +244,14 @@ impl LevelCompactionPicker {
         let mut level_scores: Vec<(LevelId, u64, usize, f64, f64)> =
             Vec::with_capacity(levels.len());
         for lvl in levels.iter() {
-            // Ignore level 0 (delta files)
-            if lvl.level == 0 || lvl.cur_size == 0 || lvl.files.len() <= 1 {
+            // Ignore level 0 (delta files), files in cold storage are not compacted.
+            let local_files = lvl.files.iter().filter(|f| !f.is_remote()).count();
+            if lvl.level == 0 || lvl.cur_size == 0 || local_files <= 1 {
                 continue;
             }
             let mut compacting_files = 0_usize;
             for file in lvl.files.iter() {
-                if file.is_compacting() {
+                if !file.is_remote() && file.is_compacting() {
                     compacting_files += 1;
                 }
             }
@@ -234,8 +263,8 @@ impl LevelCompactionPicker {
             //     * Self::level_weight_remaining_size(lvl.level);
             // let level_score = 10e6 * (level_file_num_weight / level_remaining_size_weight);
 
-            let level_score: f64 = (lvl.files.len() - compacting_files) as f64
-                * Self::level_weight_file_num(lvl.level);
+            let level_score: f64 =
+                (local_files - compacting_files) as f64 * Self::level_weight_file_num(lvl.level);
 
             level_scores.push((lvl.level, lvl.cur_size, compacting_files, 0.0, level_score));
         }
@@ -273,8 +302,8 @@ impl LevelCompactionPicker {
         let mut picking_file_size = 0_u64;
         let mut picking_time_range = TimeRange::none();
         for file in src_files.iter() {
-            if file.is_compacting() || !file.mark_compacting() {
-                // If file already compacting, continue to next file.
+            if file.is_remote() || file.is_compacting() || !file.mark_compacting() {
+                // If file is in cold storage or already compacting, continue to next file.
                 continue;
             }
             picking_file_size += file.size();
@@ -292,6 +321,129 @@ impl LevelCompactionPicker {
     }
 }
 
+/// Compaction picker for picking files in the same time window.
+///
+/// Files are bucketed into time windows by their max timestamp. Files in the
+/// newest window are compacted into level 1 when the number of them reaches
+/// `compact_trigger_file_num`, files in older windows are compacted into
+/// level 4, then the window is frozen and will never be compacted again,
+/// unless there are files of it written later.
+#[derive(Debug)]
+pub struct TimeWindowCompactionPicker {
+    /// Time range of a window, in precision of the database.
+    window: i64,
+}
+
+impl Picker for TimeWindowCompactionPicker {
+    fn pick_compaction(&self, version: Arc<Version>) -> Option<CompactReq> {
+        //! 1. Bucket all local files of **version** by time window of their max_ts.
+        //! 2. From the newest window to the oldest, skip windows that have files
+        //!    being compacted, and pick the first window that:
+        //!    - is the newest window, and the number of files reaches compact_trigger_file_num,
+        //!    - or is an older window, and is not frozen (only tsm files in level 4 in it).
+        //! 3. Get files from the picked window, sorted by min_ts(ascending)
+        //!    and size(ascending), pick ColumnFile until picking_files_size reaches
+        //!    max_compact_size.
+        //! 4. Build CompactReq using **version**, picked files and level 1 or 4.
+
+        let storage_opt = version.storage_opt();
+        let windows = self.bucket_files(version.levels_info());
+        let newest_window = *windows.keys().next_back()?;
+        let trigger_file_num = (storage_opt.compact_trigger_file_num as usize).max(2);
+
+        for (window, files) in windows.into_iter().rev() {
+            if files.iter().any(|f| f.is_compacting()) {
+                continue;
+            }
+            let out_level = if window == newest_window {
+                if files.len() < trigger_file_num {
+                    continue;
+                }
+                1
+            } else {
+                if files.iter().all(|f| f.level() == 4 && !f.is_delta()) {
+                    // The window is frozen, it may have more than 1 file since
+                    // output of a compaction rolls to new files by size.
+                    continue;
+                }
+                4
+            };
+
+            let mut files = files;
+            files.sort_by(LevelCompactionPicker::compare_column_file);
+            let mut picking_files: Vec<Arc<ColumnFile>> = Vec::new();
+            LevelCompactionPicker::pick_files(
+                files,
+                storage_opt.max_compact_size,
+                &mut picking_files,
+            );
+            if picking_files.is_empty() {
+                continue;
+            }
+
+            info!(
+                "Picker: Picked files of time window {}: [ {} ]",
+                window,
+                picking_files
+                    .iter()
+                    .map(|f| {
+                        format!(
+                            "{{ Level-{}, file_id: {}, time_range: {}-{} }}",
+                            f.level(),
+                            f.file_id(),
+                            f.time_range().min_ts,
+                            f.time_range().max_ts
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+
+            return Some(CompactReq {
+                ts_family_id: version.ts_family_id,
+                database: version.database.clone(),
+                storage_opt: version.storage_opt.clone(),
+                files: picking_files,
+                version: version.clone(),
+                out_level,
+                field_type_resolver: None,
+            });
+        }
+
+        info!("Picker: picked no time window");
+        None
+    }
+}
+
+impl TimeWindowCompactionPicker {
+    pub fn new(window: i64) -> TimeWindowCompactionPicker {
+        Self {
+            window: window.max(1),
+        }
+    }
+
+    fn window_of(&self, ts: i64) -> i64 {
+        ts.div_euclid(self.window)
+    }
+
+    /// Returns files of all levels bucketed by time window, files in cold storage are ignored.
+    fn bucket_files(&self, levels: &[LevelInfo]) -> BTreeMap<i64, Vec<Arc<ColumnFile>>> {
+        let mut windows: BTreeMap<i64, Vec<Arc<ColumnFile>>> = BTreeMap::new();
+        for lvl in levels.iter() {
+            for file in lvl.files.iter() {
+                if file.is_remote() {
+                    continue;
+                }
+                windows
+                    .entry(self.window_of(file.time_range().max_ts))
+                    .or_default()
+                    .push(file.clone());
+            }
+        }
+        windows
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
@@ -303,7 +455,7 @@ mod test {
     use tokio::sync::mpsc;
 
     use crate::compaction::test::create_options;
-    use crate::compaction::{LevelCompactionPicker, Picker};
+    use crate::compaction::{LevelCompactionPicker, Picker, TimeWindowCompactionPicker};
     use crate::file_utils::make_tsm_file_name;
     use crate::kv_option::Options;
     use crate::kvcore::COMPACT_REQ_CHANNEL_CAP;
@@ -375,6 +527,8 @@ mod test {
             level_infos,
             1000,
             Arc::new(ShardedCache::with_capacity(1)),
+            None,
+            None,
         ));
         let (flush_task_sender, _) = mpsc::channel(opt.storage.flush_req_channel_cap);
         let (compactt_task_sender, _) = mpsc::channel(COMPACT_REQ_CHANNEL_CAP);
@@ -434,4 +588,126 @@ mod test {
         assert_eq!(compact_req.out_level, 2);
         assert_eq!(compact_req.files.len(), 2);
     }
+
+    #[test]
+    fn test_pick_time_window_1() {
+        //! Files in the newest time window reaches compact_trigger_file_num.
+        //! In this case, all files in the newest time window will be picked,
+        //! and compact to Level 1.
+        let dir = "/tmp/test/pick/time_window_1";
+        let opt = create_options(dir.to_string());
+
+        #[rustfmt::skip]
+        let levels_sketch: LevelsSketch = vec![
+            // vec![( level, Timestamp_Begin, Timestamp_end, vec![(file_id, Timestamp_Begin, Timestamp_end, size, being_compact)] )]
+            (0_u32, 3001_i64, 3500_i64, vec![
+                (11_u64, 3001_i64, 3500_i64, 100_u64, false),
+            ]),
+            (1, 1000, 3600, vec![
+                (5, 1000, 1500, 1000, false),
+                (6, 1501, 1999, 1000, false),
+                (7, 3001, 3200, 1000, false),
+                (8, 3201, 3400, 1000, false),
+                (9, 3401, 3600, 1000, false),
+            ]),
+            (4, 1, 999, vec![
+                (1, 1, 999, 10000, false),
+            ]),
+        ];
+
+        let tsf = create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch);
+        let picker = TimeWindowCompactionPicker::new(1000);
+        let compact_req = picker.pick_compaction(tsf.version()).unwrap();
+        assert_eq!(compact_req.out_level, 1);
+        let mut file_ids: Vec<u64> = compact_req.files.iter().map(|f| f.file_id()).collect();
+        file_ids.sort();
+        assert_eq!(file_ids, vec![7, 8, 9, 11]);
+    }
+
+    #[test]
+    fn test_pick_time_window_2() {
+        //! Files in the newest time window does not reach compact_trigger_file_num,
+        //! the oldest time window is frozen.
+        //! In this case, files in the middle time window will be picked,
+        //! and compact to Level 4.
+        let dir = "/tmp/test/pick/time_window_2";
+        let opt = create_options(dir.to_string());
+
+        #[rustfmt::skip]
+        let levels_sketch: LevelsSketch = vec![
+            // vec![( level, Timestamp_Begin, Timestamp_end, vec![(file_id, Timestamp_Begin, Timestamp_end, size, being_compact)] )]
+            (0_u32, 1200_i64, 1300_i64, vec![
+                (11_u64, 1200_i64, 1300_i64, 100_u64, false),
+            ]),
+            (1, 1000, 3600, vec![
+                (5, 1000, 1500, 1000, false),
+                (6, 1501, 1999, 1000, false),
+                (7, 3001, 3200, 1000, false),
+                (8, 3201, 3400, 1000, false),
+            ]),
+            (4, 1, 999, vec![
+                (1, 1, 999, 10000, false),
+            ]),
+        ];
+
+        let tsf = create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch);
+        let picker = TimeWindowCompactionPicker::new(1000);
+        let compact_req = picker.pick_compaction(tsf.version()).unwrap();
+        assert_eq!(compact_req.out_level, 4);
+        let mut file_ids: Vec<u64> = compact_req.files.iter().map(|f| f.file_id()).collect();
+        file_ids.sort();
+        assert_eq!(file_ids, vec![5, 6, 11]);
+    }
+
+    #[test]
+    fn test_pick_time_window_3() {
+        //! The middle time window is now in compaction, and other time windows
+        //! are frozen or does not reach compact_trigger_file_num.
+        //! In this case, no files will be picked.
+        let dir = "/tmp/test/pick/time_window_3";
+        let opt = create_options(dir.to_string());
+
+        #[rustfmt::skip]
+        let levels_sketch: LevelsSketch = vec![
+            // vec![( level, Timestamp_Begin, Timestamp_end, vec![(file_id, Timestamp_Begin, Timestamp_end, size, being_compact)] )]
+            (1_u32, 1000_i64, 3600_i64, vec![
+                (5_u64, 1000_i64, 1500_i64, 1000_u64, true),
+                (6, 1501, 1999, 1000, false),
+                (7, 3001, 3200, 1000, false),
+            ]),
+            (4, 1, 2999, vec![
+                (1, 1, 999, 10000, false),
+                (2, 2000, 2999, 10000, false),
+            ]),
+        ];
+
+        let tsf = create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch);
+        let picker = TimeWindowCompactionPicker::new(1000);
+        assert!(picker.pick_compaction(tsf.version()).is_none());
+    }
+    #[test]
+    fn test_pick_time_window_4() {
+        //! The oldest time window has 2 files in Level 4 rolled by size, and other
+        //! time windows are frozen or does not reach compact_trigger_file_num.
+        //! In this case, no files will be picked.
+        let dir = "/tmp/test/pick/time_window_4";
+        let opt = create_options(dir.to_string());
+
+        #[rustfmt::skip]
+        let levels_sketch: LevelsSketch = vec![
+            // vec![( level, Timestamp_Begin, Timestamp_end, vec![(file_id, Timestamp_Begin, Timestamp_end, size, being_compact)] )]
+            (1_u32, 3001_i64, 3200_i64, vec![
+                (7_u64, 3001_i64, 3200_i64, 1000_u64, false),
+            ]),
+            (4, 1, 2999, vec![
+                (1, 1, 500, 10000, false),
+                (2, 501, 999, 10000, false),
+                (3, 2000, 2999, 10000, false),
+            ]),
+        ];
+
+        let tsf = create_tseries_family(Arc::new("dba".to_string()), opt, levels_sketch);
+        let picker = TimeWindowCompactionPicker::new(1000);
+        assert!(picker.pick_compaction(tsf.version()).is_none());
+    }
 }
@@ -0,0 +1,121 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use limiter_bucket::RateBucket;
+use parking_lot::RwLock;
+use trace::info;
+
+/// Interval in milliseconds to refill the rate bucket.
+const REFILL_INTERVAL_MS: u64 = 100;
+
+/// Throttles bytes written by compaction or flush, using a rate bucket
+/// refilled every 100ms. The rate can be changed at runtime.
+#[derive(Debug)]
+pub struct WriteRateLimiter {
+    kind: &'static str,
+    bytes_per_second: AtomicU64,
+    bucket: RwLock<Option<Arc<RateBucket>>>,
+}
+
+impl WriteRateLimiter {
+    pub const COMPACTION: &'static str = "compaction";
+    pub const FLUSH: &'static str = "flush";
+
+    /// Create a limiter for `kind` of writers, 0 `bytes_per_second` means unlimited.
+    pub fn new(kind: &'static str, bytes_per_second: u64) -> Self {
+        Self {
+            kind,
+            bytes_per_second: AtomicU64::new(bytes_per_second),
+            bucket: RwLock::new(Self::build_bucket(bytes_per_second)),
+        }
+    }
+
+    fn build_bucket(bytes_per_second: u64) -> Option<Arc<RateBucket>> {
+        if bytes_per_second == 0 {
+            return None;
+        }
+        let refill = (bytes_per_second * REFILL_INTERVAL_MS / 1000).max(1) as usize;
+        let bucket = RateBucket::builder()
+            .max(bytes_per_second as usize)
+            .initial(refill)
+            .refill(refill)
+            .interval(chrono::Duration::milliseconds(REFILL_INTERVAL_MS as i64))
+            .build();
+        Some(Arc::new(bucket))
+    }
+
+    pub fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second.load(Ordering::Acquire)
+    }
+
+    /// Change the rate, 0 `bytes_per_second` means unlimited.
+    pub fn set_bytes_per_second(&self, bytes_per_second: u64) {
+        let mut bucket = self.bucket.write();
+        *bucket = Self::build_bucket(bytes_per_second);
+        self.bytes_per_second
+            .store(bytes_per_second, Ordering::Release);
+        info!(
+            "Write rate limiter of {} changed to {} B/s",
+            self.kind, bytes_per_second
+        );
+    }
+
+    /// Wait until `bytes` are allowed to be written.
+    pub async fn request(&self, bytes: usize) {
+        if bytes == 0 {
+            return;
+        }
+        let start = Instant::now();
+        let mut acquired = 0_usize;
+        loop {
+            let bucket = match self.bucket.read().clone() {
+                Some(b) => b,
+                // Rate limit was removed.
+                None => break,
+            };
+            acquired += bucket.acquire_closed(bytes - acquired);
+            if acquired >= bytes {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(REFILL_INTERVAL_MS)).await;
+        }
+
+        metrics::incr_write_rate_limited_bytes(self.kind, bytes as u64);
+        let waited = start.elapsed().as_millis() as u64;
+        if waited > 0 {
+            metrics::incr_write_rate_limited_wait(self.kind, waited);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::WriteRateLimiter;
+
+    #[tokio::test]
+    async fn test_write_rate_limiter() {
+        let limiter = WriteRateLimiter::new(WriteRateLimiter::COMPACTION, 0);
+        let start = Instant::now();
+        limiter.request(100 * 1024 * 1024).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // Initial 1K, refill 1K every 100ms.
+        limiter.set_bytes_per_second(10 * 1024);
+        assert_eq!(limiter.bytes_per_second(), 10 * 1024);
+        let start = Instant::now();
+        limiter.request(5 * 1024).await;
+        assert!(start.elapsed() >= Duration::from_millis(300));
+
+        limiter.set_bytes_per_second(0);
+        let start = Instant::now();
+        limiter.request(100 * 1024 * 1024).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
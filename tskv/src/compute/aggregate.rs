@@ -0,0 +1,800 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use datafusion::scalar::ScalarValue;
+use models::predicate::domain::{AggregateKind, TimeBucket, TimeRange, TimeRanges};
+use models::schema::TskvTableSchema;
+use models::{utils as model_utils, ColumnId, FieldId, PhysicalDType as ValueType, SeriesId};
+use tokio::runtime::Runtime;
+use trace::trace;
+
+use crate::memcache::DataType;
+use crate::tseries_family::{ColumnFile, SuperVersion};
+use crate::tsm::{BlockMeta, TsmReader};
+use crate::{Error, Result};
+
+/// Compute pushed down aggregate:
+///
+/// `SELECT max|min|sum(<field>) FROM <table> WHERE <time_range_predicates>`
+///
+/// Returns the partial aggregate state of the given series, the timestamp of the returned
+/// `DataType` is the timestamp of the value for max and min, and is meaningless for sum.
+/// Returns None if there is no value in the time ranges.
+pub async fn aggregate_field_values(
+    runtime: Arc<Runtime>,
+    super_version: Arc<SuperVersion>,
+    series_ids: Arc<Vec<SeriesId>>,
+    column_id: ColumnId,
+    field_type: ValueType,
+    kind: AggregateKind,
+    time_ranges: Arc<TimeRanges>,
+) -> Result<Option<DataType>> {
+    let states = aggregate_series_field_values(
+        runtime,
+        super_version,
+        series_ids,
+        column_id,
+        field_type,
+        kind,
+        time_ranges,
+        None,
+    )
+    .await?;
+    Ok(states.into_values().next())
+}
+
+/// Compute pushed down aggregate grouped by time buckets:
+///
+/// `SELECT date_bin(<stride>, time, <origin>), max|min|sum|count(<field>) FROM <table>
+/// WHERE <time_range_predicates> GROUP BY date_bin(<stride>, time, <origin>)`
+///
+/// Returns partial aggregate states of the given series by the start timestamp of
+/// buckets, buckets without values in the time ranges are not returned. The state of
+/// count is an `i64`.
+#[allow(clippy::too_many_arguments)]
+pub async fn aggregate_field_values_by_time_bucket(
+    runtime: Arc<Runtime>,
+    super_version: Arc<SuperVersion>,
+    series_ids: Arc<Vec<SeriesId>>,
+    column_id: ColumnId,
+    field_type: ValueType,
+    kind: AggregateKind,
+    time_ranges: Arc<TimeRanges>,
+    time_bucket: TimeBucket,
+) -> Result<BTreeMap<i64, DataType>> {
+    aggregate_series_field_values(
+        runtime,
+        super_version,
+        series_ids,
+        column_id,
+        field_type,
+        kind,
+        time_ranges,
+        Some(time_bucket),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn aggregate_series_field_values(
+    runtime: Arc<Runtime>,
+    super_version: Arc<SuperVersion>,
+    series_ids: Arc<Vec<SeriesId>>,
+    column_id: ColumnId,
+    field_type: ValueType,
+    kind: AggregateKind,
+    time_ranges: Arc<TimeRanges>,
+    time_bucket: Option<TimeBucket>,
+) -> Result<BTreeMap<i64, DataType>> {
+    let column_files = Arc::new(super_version.column_files(&time_ranges));
+    let mut jh_vec = Vec::with_capacity(series_ids.len());
+
+    for series_id in series_ids.iter() {
+        let field_id = model_utils::unite_id(column_id, *series_id);
+        jh_vec.push(runtime.spawn(aggregate_field_values_inner(
+            super_version.clone(),
+            field_id,
+            GroupedAccumulator::new(kind, field_type, time_bucket),
+            column_files.clone(),
+            time_ranges.clone(),
+        )));
+    }
+
+    let mut accumulator = GroupedAccumulator::new(kind, field_type, time_bucket);
+    for jh in jh_vec {
+        // JoinHandle returns JoinError if task was paniced.
+        let states = jh.await.map_err(|e| Error::IO { source: e.into() })??;
+        accumulator.merge(states)?;
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// Get the last value of a field of a series in the time ranges.
+///
+/// If the table is cached by the last value cache, the cached value is used when it's
+/// known to be the latest one, otherwise the latest value is read from caches and files
+/// and then filled into the last value cache.
+pub async fn last_field_value(
+    super_version: Arc<SuperVersion>,
+    table_schema: &TskvTableSchema,
+    series_id: SeriesId,
+    column_id: ColumnId,
+    field_type: ValueType,
+    time_ranges: Arc<TimeRanges>,
+) -> Result<Option<DataType>> {
+    let field_id = model_utils::unite_id(column_id, series_id);
+    let last_cache = super_version.last_cache.clone();
+    if !last_cache.is_enabled(table_schema) {
+        return read_last_field_value(super_version, field_id, field_type, time_ranges).await;
+    }
+
+    let epoch = last_cache.epoch();
+    let latest = match last_cache.get(series_id, column_id) {
+        // Files may contain newer values than the unverified cached value.
+        Some(last)
+            if last.verified || last.value.timestamp() >= super_version.version.max_level_ts =>
+        {
+            Some(last.value)
+        }
+        _ => {
+            let latest = read_last_field_value(
+                super_version.clone(),
+                field_id,
+                field_type,
+                Arc::new(TimeRanges::all()),
+            )
+            .await?;
+            if let Some(value) = latest.as_ref() {
+                last_cache.fill(epoch, series_id, column_id, value.clone());
+            }
+            latest
+        }
+    };
+    trace!("Latest value of field {field_id:02X}: {latest:?}");
+    let latest = latest.and_then(|v| v.convert_to(field_type));
+
+    match latest {
+        Some(value) if time_ranges.is_boundless() || time_ranges.contains(value.timestamp()) => {
+            Ok(Some(value))
+        }
+        Some(_) => read_last_field_value(super_version, field_id, field_type, time_ranges).await,
+        None => Ok(None),
+    }
+}
+
+async fn read_last_field_value(
+    super_version: Arc<SuperVersion>,
+    field_id: FieldId,
+    field_type: ValueType,
+    time_ranges: Arc<TimeRanges>,
+) -> Result<Option<DataType>> {
+    let column_files = Arc::new(super_version.column_files(&time_ranges));
+    let states = aggregate_field_values_inner(
+        super_version,
+        field_id,
+        GroupedAccumulator::new(AggregateKind::Last, field_type, None),
+        column_files,
+        time_ranges,
+    )
+    .await?;
+    Ok(states.into_values().next())
+}
+
+/// Aggregate values in time ranges of a field, returns states by the start timestamp
+/// of time buckets.
+async fn aggregate_field_values_inner(
+    super_version: Arc<SuperVersion>,
+    field_id: FieldId,
+    mut accumulator: GroupedAccumulator,
+    column_files: Arc<Vec<Arc<ColumnFile>>>,
+    time_ranges: Arc<TimeRanges>,
+) -> Result<BTreeMap<i64, DataType>> {
+    // Values in caches overwrite values in files with the same timestamp.
+    let cached_data = get_field_data_in_caches(&super_version, field_id, &time_ranges);
+    let cached_time_range = match (cached_data.keys().next(), cached_data.keys().next_back()) {
+        (Some(min_ts), Some(max_ts)) => TimeRange::new(*min_ts, *max_ts),
+        _ => TimeRange::none(),
+    };
+
+    let read_tasks =
+        create_file_read_tasks(&super_version, &column_files, field_id, &time_ranges).await?;
+    let mut groups = group_read_tasks(read_tasks);
+    if accumulator.kind == AggregateKind::Last {
+        // Find the last value from the latest data blocks.
+        groups.reverse();
+    }
+
+    for group in groups {
+        if accumulator.kind == AggregateKind::Last && !accumulator.states.is_empty() {
+            // Groups are disjoint in time, the last value is found.
+            break;
+        }
+        if group.len() == 1
+            && !group[0].time_range_intersected
+            && !cached_time_range.overlaps(&group[0].time_range)
+            && accumulator.update_by_block_meta(&group[0])?
+        {
+            trace!(
+                "Aggregated by statistics of block: {}",
+                group[0].block_meta.as_ref()
+            );
+            continue;
+        }
+        let values =
+            read_values_in_files(group, &time_ranges, &cached_data, &cached_time_range).await?;
+        for v in values.into_values() {
+            accumulator.update(v)?;
+        }
+    }
+    for v in cached_data.into_values() {
+        accumulator.update(v)?;
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// Get values of a field in time ranges from all mutable and immutable caches,
+/// the newest value of a timestamp is kept.
+fn get_field_data_in_caches(
+    super_version: &SuperVersion,
+    field_id: FieldId,
+    time_ranges: &TimeRanges,
+) -> BTreeMap<i64, DataType> {
+    let time_predicate = |ts| time_ranges.is_boundless() || time_ranges.contains(ts);
+    let mut cached_data: BTreeMap<i64, DataType> = BTreeMap::new();
+    super_version.caches.read_field_data(
+        field_id,
+        time_predicate,
+        |_| true,
+        |d| {
+            cached_data.insert(d.timestamp(), d);
+        },
+    );
+
+    cached_data
+}
+
+struct ReadTask {
+    /// Reader for a file.
+    tsm_reader: Arc<TsmReader>,
+    /// Priority of the file, data in file with higher priority overwrites data
+    /// in file with lower priority if they have the same timestamp.
+    file_priority: (bool, u64),
+    /// BlockMeta in a file.
+    block_meta: Arc<BlockMeta>,
+    /// Time range by BlockMeta::time_range() .
+    time_range: TimeRange,
+    /// Is time_range is intersected with time range predicates.
+    time_range_intersected: bool,
+}
+
+/// Filter block metas in files by time ranges, open files and then create file read tasks.
+async fn create_file_read_tasks(
+    super_version: &SuperVersion,
+    files: &[Arc<ColumnFile>],
+    field_id: FieldId,
+    time_ranges: &TimeRanges,
+) -> Result<Vec<ReadTask>> {
+    let mut read_tasks: Vec<ReadTask> = Vec::new();
+
+    for cf in files {
+        if !cf.contains_field_id(field_id) {
+            continue;
+        }
+        let reader = super_version.version.get_tsm_reader(cf).await?;
+        // Data in level 0 overwrites data in level 1-4, newer file overwrites older file.
+        let file_priority = (cf.level() == 0, cf.file_id());
+        for idx in reader.index_iterator_opt(field_id) {
+            for blk_meta in idx.block_iterator() {
+                let blk_tr = blk_meta.time_range();
+                if !time_ranges.overlaps(&blk_tr) {
+                    continue;
+                }
+                read_tasks.push(ReadTask {
+                    tsm_reader: reader.clone(),
+                    file_priority,
+                    block_meta: Arc::new(blk_meta),
+                    time_range: blk_tr,
+                    time_range_intersected: !time_ranges.includes(&blk_tr),
+                });
+            }
+        }
+    }
+
+    read_tasks.sort_by(|a, b| a.time_range.cmp(&b.time_range));
+    Ok(read_tasks)
+}
+
+/// Group read tasks which have overlapped time ranges, read tasks must be sorted by time range.
+fn group_read_tasks(read_tasks: Vec<ReadTask>) -> Vec<Vec<ReadTask>> {
+    let mut groups: Vec<Vec<ReadTask>> = Vec::new();
+    let mut grouped_tr = TimeRange::none();
+    for read_task in read_tasks {
+        match groups.last_mut() {
+            Some(group) if grouped_tr.overlaps(&read_task.time_range) => {
+                grouped_tr.merge(&read_task.time_range);
+                group.push(read_task);
+            }
+            _ => {
+                grouped_tr = read_task.time_range;
+                groups.push(vec![read_task]);
+            }
+        }
+    }
+    groups
+}
+
+/// Decode grouped read tasks and get values in time ranges, values with timestamps
+/// in `cached_data` are excluded.
+async fn read_values_in_files(
+    mut reader_blk_metas: Vec<ReadTask>,
+    time_ranges: &TimeRanges,
+    cached_data: &BTreeMap<i64, DataType>,
+    cached_time_range: &TimeRange,
+) -> Result<BTreeMap<i64, DataType>> {
+    // Read blocks from lower priority to higher priority, so that newer values overwrite older.
+    reader_blk_metas.sort_by_key(|t| t.file_priority);
+    let mut values: BTreeMap<i64, DataType> = BTreeMap::new();
+    for read_task in reader_blk_metas {
+        let blk = read_task
+            .tsm_reader
+            .get_data_block(&read_task.block_meta)
+            .await?;
+        let check_cache = cached_time_range.overlaps(&read_task.time_range);
+        for i in 0..blk.len() {
+            if let Some(v) = blk.get(i) {
+                let ts = v.timestamp();
+                if read_task.time_range_intersected && !time_ranges.contains(ts) {
+                    continue;
+                }
+                if check_cache && cached_data.contains_key(&ts) {
+                    continue;
+                }
+                values.insert(ts, v);
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Accumulators of values grouped by time buckets, all values are accumulated
+/// by one accumulator if there is no time bucket.
+struct GroupedAccumulator {
+    kind: AggregateKind,
+    field_type: ValueType,
+    time_bucket: Option<TimeBucket>,
+    states: BTreeMap<i64, Accumulator>,
+}
+
+impl GroupedAccumulator {
+    fn new(kind: AggregateKind, field_type: ValueType, time_bucket: Option<TimeBucket>) -> Self {
+        Self {
+            kind,
+            field_type,
+            time_bucket,
+            states: BTreeMap::new(),
+        }
+    }
+
+    fn group_of(&self, ts: i64) -> i64 {
+        match self.time_bucket.as_ref() {
+            Some(bucket) => bucket.start_of(ts),
+            None => i64::MIN,
+        }
+    }
+
+    fn accumulator(&mut self, group: i64) -> &mut Accumulator {
+        let (kind, field_type) = (self.kind, self.field_type);
+        self.states
+            .entry(group)
+            .or_insert_with(|| Accumulator::new(kind, field_type))
+    }
+
+    fn update(&mut self, value: DataType) -> Result<()> {
+        let group = self.group_of(value.timestamp());
+        let accumulator = self.accumulator(group);
+        accumulator.update(value)?;
+        if accumulator.state.is_none() {
+            // Value is not converted to the field type.
+            self.states.remove(&group);
+        }
+        Ok(())
+    }
+
+    /// Update state by statistics in BlockMeta, returns false if the block is not
+    /// in one time bucket, or the statistics can not be used.
+    fn update_by_block_meta(&mut self, read_task: &ReadTask) -> Result<bool> {
+        let group = self.group_of(read_task.time_range.min_ts);
+        if group != self.group_of(read_task.time_range.max_ts) {
+            return Ok(false);
+        }
+        let accumulator = self.accumulator(group);
+        let updated = accumulator.update_by_block_meta(read_task)?;
+        if accumulator.state.is_none() {
+            self.states.remove(&group);
+        }
+        Ok(updated)
+    }
+
+    /// Merge partial states by the start timestamp of time buckets.
+    fn merge(&mut self, states: BTreeMap<i64, DataType>) -> Result<()> {
+        for (group, state) in states {
+            self.accumulator(group).merge(state)?;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> BTreeMap<i64, DataType> {
+        self.states
+            .into_iter()
+            .filter_map(|(group, accumulator)| accumulator.finish().map(|s| (group, s)))
+            .collect()
+    }
+}
+
+struct Accumulator {
+    kind: AggregateKind,
+    field_type: ValueType,
+    state: Option<DataType>,
+}
+
+impl Accumulator {
+    fn new(kind: AggregateKind, field_type: ValueType) -> Self {
+        Self {
+            kind,
+            field_type,
+            state: None,
+        }
+    }
+
+    fn update(&mut self, value: DataType) -> Result<()> {
+        // Values written before the type of the field is changed.
+        let value = match value.convert_to(self.field_type) {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+        if self.kind == AggregateKind::Count {
+            return self.merge(DataType::I64(value.timestamp(), 1));
+        }
+        self.merge(value)
+    }
+
+    /// Merge a partial state of the same kind into the state.
+    fn merge(&mut self, partial: DataType) -> Result<()> {
+        let state = match self.state.take() {
+            Some(s) => s,
+            None => {
+                self.state = Some(partial);
+                return Ok(());
+            }
+        };
+        let new_state = match self.kind {
+            AggregateKind::Max => match compare_values(&partial, &state) {
+                Ordering::Greater => partial,
+                _ => state,
+            },
+            AggregateKind::Min => match compare_values(&partial, &state) {
+                Ordering::Less => partial,
+                _ => state,
+            },
+            AggregateKind::Last => {
+                if partial.timestamp() > state.timestamp() {
+                    partial
+                } else {
+                    state
+                }
+            }
+            AggregateKind::Sum | AggregateKind::Count => match (state, partial) {
+                (DataType::U64(ts, a), DataType::U64(_, b)) => DataType::U64(ts, a.wrapping_add(b)),
+                (DataType::I64(ts, a), DataType::I64(_, b)) => DataType::I64(ts, a.wrapping_add(b)),
+                (DataType::F64(ts, a), DataType::F64(_, b)) => DataType::F64(ts, a + b),
+                (state, _) => {
+                    return Err(Error::CommonError {
+                        reason: format!("unsupported sum of value: {}", state),
+                    });
+                }
+            },
+        };
+        self.state = Some(new_state);
+        Ok(())
+    }
+
+    /// Update state by statistics in BlockMeta, returns false if the statistics can
+    /// not be used, and the block need to be decoded.
+    ///
+    /// The caller must make sure that all values in this block are in time ranges,
+    /// and are not overwritten by other data.
+    fn update_by_block_meta(&mut self, read_task: &ReadTask) -> Result<bool> {
+        let block_meta = read_task.block_meta.as_ref();
+        if block_meta.field_type() != self.field_type {
+            return Ok(false);
+        }
+        if read_task.tsm_reader.has_tombstone()
+            && read_task
+                .tsm_reader
+                .get_block_tombstone_time_ranges(block_meta)
+                .is_some()
+        {
+            return Ok(false);
+        }
+        let value = match (self.kind, block_meta.stats()) {
+            (AggregateKind::Count, _) => Some(ScalarValue::Int64(Some(block_meta.count() as i64))),
+            (AggregateKind::Max, Some(stats)) => stats.max(self.field_type),
+            (AggregateKind::Min, Some(stats)) => stats.min(self.field_type),
+            (AggregateKind::Sum, Some(stats)) => stats.sum(self.field_type),
+            _ => None,
+        };
+        match value.and_then(|v| scalar_to_data_type(block_meta.min_ts(), v)) {
+            Some(v) => {
+                self.merge(v)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn finish(self) -> Option<DataType> {
+        self.state
+    }
+}
+
+/// Compare values of the same type, NaN is greater than any other float value.
+fn compare_values(a: &DataType, b: &DataType) -> Ordering {
+    match (a, b) {
+        (DataType::U64(_, a), DataType::U64(_, b)) => a.cmp(b),
+        (DataType::I64(_, a), DataType::I64(_, b)) => a.cmp(b),
+        (DataType::F64(_, a), DataType::F64(_, b)) => a.total_cmp(b),
+        (DataType::Str(_, a), DataType::Str(_, b)) => a.cmp(b),
+        (DataType::Bool(_, a), DataType::Bool(_, b)) => a.cmp(b),
+        _ => Ordering::Equal,
+    }
+}
+
+fn scalar_to_data_type(ts: i64, value: ScalarValue) -> Option<DataType> {
+    match value {
+        ScalarValue::UInt64(Some(v)) => Some(DataType::U64(ts, v)),
+        ScalarValue::Int64(Some(v)) => Some(DataType::I64(ts, v)),
+        ScalarValue::Float64(Some(v)) => Some(DataType::F64(ts, v)),
+        ScalarValue::Boolean(Some(v)) => Some(DataType::Bool(ts, v)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use memory_pool::{GreedyMemoryPool, MemoryPoolRef};
+    use models::predicate::domain::{AggregateKind, TimeBucket, TimeRanges};
+    use models::schema::TskvTableSchema;
+    use models::{utils as model_utils, PhysicalDType as ValueType, SeriesId};
+    use parking_lot::RwLock;
+    use tokio::runtime::Runtime;
+
+    use crate::compaction::test::write_data_blocks_to_column_file;
+    use crate::compute::aggregate::{
+        aggregate_field_values, aggregate_field_values_by_time_bucket, last_field_value,
+    };
+    use crate::memcache::{DataType, LastValueCache, MemCache};
+    use crate::tseries_family::test_tseries_family::build_version_by_column_files;
+    use crate::tseries_family::{CacheGroup, SuperVersion};
+    use crate::tsm::codec::DataBlockEncoding;
+    use crate::tsm::DataBlock;
+    use crate::{Options, Result};
+
+    struct TestHelper {
+        runtime: Arc<Runtime>,
+        super_version: Arc<SuperVersion>,
+    }
+
+    impl TestHelper {
+        fn run(
+            &self,
+            series_ids: &[SeriesId],
+            kind: AggregateKind,
+            time_ranges: impl Into<TimeRanges>,
+        ) -> Result<Option<DataType>> {
+            let time_ranges = Arc::new(time_ranges.into());
+            self.runtime.block_on(aggregate_field_values(
+                self.runtime.clone(),
+                self.super_version.clone(),
+                Arc::new(series_ids.to_vec()),
+                1,
+                ValueType::Integer,
+                kind,
+                time_ranges,
+            ))
+        }
+
+        /// Returns integer states by the start timestamp of time buckets.
+        fn run_by_time_bucket(
+            &self,
+            series_ids: &[SeriesId],
+            kind: AggregateKind,
+            time_bucket: TimeBucket,
+        ) -> Result<Vec<(i64, i64)>> {
+            let states = self
+                .runtime
+                .block_on(aggregate_field_values_by_time_bucket(
+                    self.runtime.clone(),
+                    self.super_version.clone(),
+                    Arc::new(series_ids.to_vec()),
+                    1,
+                    ValueType::Integer,
+                    kind,
+                    Arc::new(TimeRanges::all()),
+                    time_bucket,
+                ))?
+                .into_iter()
+                .map(|(bucket, state)| match state {
+                    DataType::I64(_, v) => (bucket, v),
+                    _ => panic!("unexpected state: {state}"),
+                })
+                .collect();
+            Ok(states)
+        }
+    }
+
+    #[test]
+    fn test_super_version_aggregate_file() {
+        let dir = "/tmp/test/ts_family/super_version_aggregate_file";
+        let mut global_config = config::get_config_for_test();
+        global_config.storage.path = dir.to_string();
+
+        // Values of series 1 in file 2 overwrite values in file 1 at timestamp 4.
+        #[rustfmt::skip]
+        let data = vec![
+            HashMap::from([
+                (model_utils::unite_id(1, 1), vec![DataBlock::I64 { ts: vec![1, 2, 3, 4], val: vec![5, 3, 8, 100], enc: DataBlockEncoding::default() }]),
+                (model_utils::unite_id(1, 2), vec![DataBlock::I64 { ts: vec![10, 20, 30, 40], val: vec![1, 2, 3, 4], enc: DataBlockEncoding::default() }]),
+            ]),
+            HashMap::from([
+                (model_utils::unite_id(1, 1), vec![DataBlock::I64 { ts: vec![4, 5, 6], val: vec![-1, 7, 2], enc: DataBlockEncoding::default() }]),
+            ]),
+        ];
+
+        let opt = Arc::new(Options::from(&global_config));
+        let database = Arc::new("dba".to_string());
+        let ts_family_id = 1;
+        let dir = opt.storage.tsm_dir(&database, 1);
+        let runtime = Arc::new(
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+
+        let (_, files) = runtime.block_on(write_data_blocks_to_column_file(&dir, data));
+        let version = Arc::new(build_version_by_column_files(
+            opt.storage.clone(),
+            database,
+            ts_family_id,
+            files,
+        ));
+        let test_helper = TestHelper {
+            runtime,
+            super_version: Arc::new(SuperVersion::new(
+                ts_family_id,
+                opt.storage.clone(),
+                CacheGroup {
+                    mut_cache: Arc::new(RwLock::new(MemCache::new(
+                        ts_family_id,
+                        1000,
+                        2,
+                        1,
+                        &(Arc::new(GreedyMemoryPool::default()) as MemoryPoolRef),
+                    ))),
+                    immut_cache: vec![],
+                },
+                Arc::new(LastValueCache::default()),
+                version.clone(),
+                1,
+            )),
+        };
+
+        let max = test_helper
+            .run(&[1], AggregateKind::Max, (i64::MIN, i64::MAX))
+            .unwrap();
+        assert!(matches!(max, Some(DataType::I64(3, 8))));
+        let sum = test_helper
+            .run(&[1, 2], AggregateKind::Sum, (i64::MIN, i64::MAX))
+            .unwrap();
+        assert!(matches!(sum, Some(DataType::I64(_, 34))));
+        let min = test_helper.run(&[1], AggregateKind::Min, (1, 3)).unwrap();
+        assert!(matches!(min, Some(DataType::I64(2, 3))));
+        let min = test_helper
+            .run(&[1, 2], AggregateKind::Min, (i64::MIN, i64::MAX))
+            .unwrap();
+        assert!(matches!(min, Some(DataType::I64(4, -1))));
+        let none = test_helper
+            .run(&[1], AggregateKind::Max, (100, 200))
+            .unwrap();
+        assert!(none.is_none());
+
+        // Aggregate by time buckets.
+        let sum = test_helper
+            .run_by_time_bucket(&[1, 2], AggregateKind::Sum, TimeBucket::new(5, 0))
+            .unwrap();
+        assert_eq!(
+            sum,
+            vec![(0, 15), (5, 9), (10, 1), (20, 2), (30, 3), (40, 4)]
+        );
+        let max = test_helper
+            .run_by_time_bucket(&[1], AggregateKind::Max, TimeBucket::new(3, 0))
+            .unwrap();
+        assert_eq!(max, vec![(0, 5), (3, 8), (6, 2)]);
+        let count = test_helper
+            .run_by_time_bucket(&[1], AggregateKind::Count, TimeBucket::new(5, 1))
+            .unwrap();
+        assert_eq!(count, vec![(1, 5), (6, 1)]);
+        // The block of series 2 is in one bucket, statistics of the block are used.
+        let sum = test_helper
+            .run_by_time_bucket(&[2], AggregateKind::Sum, TimeBucket::new(100, 0))
+            .unwrap();
+        assert_eq!(sum, vec![(0, 10)]);
+        let count = test_helper
+            .run_by_time_bucket(&[2], AggregateKind::Count, TimeBucket::new(100, 0))
+            .unwrap();
+        assert_eq!(count, vec![(0, 4)]);
+
+        // Read last values with the last value cache.
+        let mut table_schema = TskvTableSchema::new(
+            "cnosdb".to_string(),
+            "dba".to_string(),
+            "table".to_string(),
+            vec![],
+        );
+        table_schema.last_value_cache = true;
+        let last_cache = Arc::new(LastValueCache::new(1000));
+        let super_version = Arc::new(SuperVersion::new(
+            ts_family_id,
+            opt.storage.clone(),
+            CacheGroup {
+                mut_cache: Arc::new(RwLock::new(MemCache::new(
+                    ts_family_id,
+                    1000,
+                    2,
+                    1,
+                    &(Arc::new(GreedyMemoryPool::default()) as MemoryPoolRef),
+                ))),
+                immut_cache: vec![],
+            },
+            last_cache.clone(),
+            version,
+            1,
+        ));
+        let last_value = |series_id: SeriesId, time_ranges: (i64, i64)| {
+            test_helper.runtime.block_on(last_field_value(
+                super_version.clone(),
+                &table_schema,
+                series_id,
+                1,
+                ValueType::Integer,
+                Arc::new(time_ranges.into()),
+            ))
+        };
+
+        // The latest value read from files is filled into the cache.
+        let last = last_value(1, (i64::MIN, i64::MAX)).unwrap();
+        assert!(matches!(last, Some(DataType::I64(6, 2))));
+        let cached = last_cache.get(1, 1).unwrap();
+        assert!(cached.verified);
+        assert!(matches!(cached.value, DataType::I64(6, 2)));
+        // The latest value is not in the time ranges.
+        let last = last_value(1, (1, 3)).unwrap();
+        assert!(matches!(last, Some(DataType::I64(3, 8))));
+        // Unverified value older than files is not used.
+        last_cache.update(2, vec![(1, DataType::I64(20, 9))]);
+        let last = last_value(2, (i64::MIN, i64::MAX)).unwrap();
+        assert!(matches!(last, Some(DataType::I64(40, 4))));
+        assert!(last_cache.get(2, 1).unwrap().verified);
+        // Newer value written into the cache is used.
+        last_cache.update(2, vec![(1, DataType::I64(50, 5))]);
+        let last = last_value(2, (i64::MIN, i64::MAX)).unwrap();
+        assert!(matches!(last, Some(DataType::I64(50, 5))));
+    }
+}
@@ -217,8 +217,7 @@ async fn create_file_read_tasks<'a>(
     let mut read_tasks: Vec<ReadTask> = Vec::new();
 
     for cf in files {
-        let path = cf.file_path();
-        let reader = super_version.version.get_tsm_reader(&path).await?;
+        let reader = super_version.version.get_tsm_reader(cf).await?;
         let idx_meta_iter = match counting_object {
             CountingObject::Field(field_id) => {
                 if !cf.contains_field_id(*field_id) {
@@ -353,7 +352,7 @@ mod test {
     use crate::compaction::test::write_data_blocks_to_column_file;
     use crate::compute::count::count_column_non_null_values;
     use crate::memcache::test::put_rows_to_cache;
-    use crate::memcache::MemCache;
+    use crate::memcache::{LastValueCache, MemCache};
     use crate::tseries_family::test_tseries_family::build_version_by_column_files;
     use crate::tseries_family::{CacheGroup, SuperVersion};
     use crate::tsm::codec::DataBlockEncoding;
@@ -429,6 +428,7 @@ mod test {
                     mut_cache: Arc::new(RwLock::new(MemCache::new(ts_family_id, 1, 2, 1, &pool))),
                     immut_cache: vec![],
                 },
+                Arc::new(LastValueCache::default()),
                 Arc::new(version),
                 1,
             )),
@@ -503,6 +503,7 @@ mod test {
                 ts_family_id,
                 opt.storage.clone(),
                 cache_group,
+                Arc::new(LastValueCache::default()),
                 Arc::new(version),
                 1,
             )),
@@ -597,6 +598,7 @@ mod test {
                 ts_family_id,
                 opt.storage.clone(),
                 cache_group,
+                Arc::new(LastValueCache::default()),
                 Arc::new(version),
                 1,
             )),
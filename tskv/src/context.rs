@@ -6,14 +6,19 @@ use parking_lot::RwLock;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::Receiver;
 
+use crate::compaction::WriteRateLimiter;
 use crate::TseriesFamilyId;
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct GlobalContext {
     /// Database file id
     file_id: AtomicU64,
     /// Sequence-No of the latest write request.
     last_seq: AtomicU64,
+    /// Rate limiter of bytes written by compactions.
+    compact_rate_limiter: WriteRateLimiter,
+    /// Rate limiter of bytes written by flushes.
+    flush_rate_limiter: WriteRateLimiter,
 }
 
 impl GlobalContext {
@@ -21,10 +26,18 @@ impl GlobalContext {
         Self {
             file_id: AtomicU64::new(0),
             last_seq: AtomicU64::new(0),
+            compact_rate_limiter: WriteRateLimiter::new(WriteRateLimiter::COMPACTION, 0),
+            flush_rate_limiter: WriteRateLimiter::new(WriteRateLimiter::FLUSH, 0),
         }
     }
 }
 
+impl Default for GlobalContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GlobalContext {
     /// Get the current file id.
     pub fn file_id(&self) -> u64 {
@@ -51,6 +64,14 @@ impl GlobalContext {
         self.last_seq.store(v, Ordering::Release);
     }
 
+    pub fn compact_rate_limiter(&self) -> &WriteRateLimiter {
+        &self.compact_rate_limiter
+    }
+
+    pub fn flush_rate_limiter(&self) -> &WriteRateLimiter {
+        &self.flush_rate_limiter
+    }
+
     pub fn mark_file_id_used(&self, v: u64) {
         let mut old = self.file_id.load(Ordering::Acquire);
         while old <= v {
@@ -6,6 +6,7 @@ use flatbuffers::{ForwardsUOffset, Vector};
 use lru_cache::asynchronous::ShardedCache;
 use memory_pool::MemoryPoolRef;
 use meta::model::MetaRef;
+use metrics::gauge::U64Gauge;
 use metrics::metric_register::MetricsRegister;
 use models::predicate::domain::TimeRange;
 use models::schema::{DatabaseSchema, Precision, TskvTableSchema, TskvTableSchemaRef};
@@ -21,6 +22,7 @@ use utils::BloomFilter;
 use crate::compaction::{CompactTask, FlushReq};
 use crate::context::GlobalContext;
 use crate::error::{Result, SchemaSnafu};
+use crate::index::ts_index::SeriesLimit;
 use crate::index::{self, IndexResult};
 use crate::kv_option::{Options, INDEX_PATH};
 use crate::memcache::{MemCache, RowData, RowGroup};
@@ -99,6 +101,40 @@ impl Database {
             .insert(ver.tf_id(), Arc::new(RwLock::new(tf)));
     }
 
+    /// Opens a vnode moved from another database of the node, files of the vnode
+    /// in `version_edit` are already in the directory of the vnode in this database.
+    pub async fn open_moved_tsfamily(
+        &mut self,
+        version_edit: &VersionEdit,
+        mut file_metas: HashMap<ColumnFileId, Arc<BloomFilter>>,
+        flush_task_sender: Sender<FlushReq>,
+        compact_task_sender: Sender<CompactTask>,
+    ) -> Result<()> {
+        let tsf_id = version_edit.tsf_id;
+        if self.ts_families.contains_key(&tsf_id) {
+            return Err(Error::CommonError {
+                reason: format!("vnode:{}, already exist", tsf_id),
+            });
+        }
+        let ver = Version::new(
+            tsf_id,
+            self.owner.clone(),
+            self.opt.storage.clone(),
+            version_edit.seq_no,
+            LevelInfo::init_levels(self.owner.clone(), tsf_id, self.opt.storage.clone()),
+            i64::MIN,
+            Arc::new(ShardedCache::with_capacity(
+                self.opt.storage.max_cached_readers,
+            )),
+            self.opt.cold_storage.clone(),
+            self.opt.data_block_cache.clone(),
+        )
+        .copy_apply_version_edits(vec![version_edit.clone()], &mut file_metas, None);
+        self.open_tsfamily(Arc::new(ver), flush_task_sender, compact_task_sender);
+        self.get_ts_index_or_add(tsf_id).await?;
+        Ok(())
+    }
+
     // todo: Maybe TseriesFamily::new() should be refactored.
     #[allow(clippy::too_many_arguments)]
     pub async fn add_tsfamily(
@@ -120,10 +156,25 @@ impl Database {
                 for f in ve.add_files.iter_mut() {
                     let new_file_id = global_ctx.file_id_next();
                     f.tsf_id = tsf_id;
-                    let file_path = f
-                        .rename_file(&self.opt.storage, &self.owner, f.tsf_id, new_file_id)
-                        .await?;
-                    let file_reader = crate::tsm::TsmReader::open(file_path).await?;
+                    let file_reader = match (f.remote_path.clone(), &self.opt.cold_storage) {
+                        (Some(remote_path), Some(cold_storage)) => {
+                            // Files in cold storage are copied, they may be deleted by the source vnode.
+                            f.file_id = new_file_id;
+                            let file_path = f.file_path(&self.opt.storage, &self.owner, tsf_id);
+                            let location =
+                                cold_storage.object_path(&self.owner, tsf_id, &file_path);
+                            cold_storage.copy(&remote_path, &location).await?;
+                            f.remote_path = Some(location.to_string());
+                            let file = cold_storage.open_file(&location.to_string()).await?;
+                            crate::tsm::TsmReader::open_remote(file_path, file).await?
+                        }
+                        _ => {
+                            let file_path = f
+                                .rename_file(&self.opt.storage, &self.owner, f.tsf_id, new_file_id)
+                                .await?;
+                            crate::tsm::TsmReader::open(file_path).await?
+                        }
+                    };
                     file_metas.insert(new_file_id, file_reader.bloom_filter());
                 }
                 for f in ve.del_files.iter_mut() {
@@ -165,6 +216,8 @@ impl Database {
             Arc::new(ShardedCache::with_capacity(
                 self.opt.storage.max_cached_readers,
             )),
+            self.opt.cold_storage.clone(),
+            self.opt.data_block_cache.clone(),
         ));
         let tf = TseriesFamily::new(
             tsf_id,
@@ -200,6 +253,14 @@ impl Database {
         Ok(tf)
     }
 
+    /// Closes and removes the vnode moved into another database, the summary
+    /// is written when the vnode is opened in that database.
+    pub async fn close_moved_tsfamily(&mut self, tf_id: u32) {
+        if let Some(tf) = self.ts_families.remove(&tf_id) {
+            tf.read().await.close();
+        }
+    }
+
     pub async fn del_tsfamily(&mut self, tf_id: u32, summary_task_sender: Sender<SummaryTask>) {
         if let Some(tf) = self.ts_families.remove(&tf_id) {
             tf.read().await.close();
@@ -237,6 +298,7 @@ impl Database {
         tables: FlatBufferTable<'_>,
         ts_index: Arc<index::ts_index::TSIndex>,
     ) -> Result<HashMap<(SeriesId, SchemaId), RowGroup>> {
+        let series_limit = self.series_limit(&ts_index)?;
         // (series id, schema id) -> RowGroup
         let mut map = HashMap::new();
         for table in tables {
@@ -253,6 +315,7 @@ impl Database {
                 &fb_schema.tag_indexes,
                 num_rows,
                 ts_index.clone(),
+                &series_limit,
             )
             .await?;
 
@@ -281,6 +344,7 @@ impl Database {
         tables: FlatBufferTable<'_>,
         ts_index: Arc<index::ts_index::TSIndex>,
     ) -> Result<HashMap<(SeriesId, SchemaId), RowGroup>> {
+        let series_limit = self.series_limit(&ts_index)?;
         let mut map = HashMap::new();
         for table in tables {
             let table_name = table.tab_ext()?;
@@ -296,6 +360,7 @@ impl Database {
                 &fb_schema.tag_indexes,
                 num_rows,
                 ts_index.clone(),
+                &series_limit,
             )
             .await?;
 
@@ -391,6 +456,60 @@ impl Database {
         Ok(())
     }
 
+    /// Returns limits of series to add into `ts_index`, series in other
+    /// vnodes of the database are counted.
+    pub(crate) fn series_limit(
+        &self,
+        ts_index: &Arc<index::ts_index::TSIndex>,
+    ) -> Result<SeriesLimit> {
+        let db_schema = self.schemas.db_schema()?;
+        let mut limit = SeriesLimit::from(db_schema.options());
+        limit.other_indexes = self
+            .ts_indexes
+            .values()
+            .filter(|idx| !Arc::ptr_eq(idx, ts_index))
+            .cloned()
+            .collect();
+        Ok(limit)
+    }
+
+    /// Returns `Error::SeriesMoved` if any point of `tables` belongs to a series
+    /// moved out of the vnode of `ts_index` by split.
+    pub fn check_moved_series(
+        vnode_id: TseriesFamilyId,
+        db_name: &str,
+        tables: FlatBufferTable<'_>,
+        ts_index: &index::ts_index::TSIndex,
+    ) -> Result<()> {
+        for table in tables {
+            let table_name = table.tab_ext()?;
+            let columns = table.columns().ok_or(Error::CommonError {
+                reason: "table missing columns".to_string(),
+            })?;
+            let fb_schema = FbSchema::from_fb_column(columns)?;
+            for row_count in 0..table.num_rows() as usize {
+                let series_key = SeriesKey::build_series_key(
+                    db_name,
+                    table_name,
+                    &columns,
+                    &fb_schema.tag_indexes,
+                    row_count,
+                )
+                .map_err(|e| Error::CommonError {
+                    reason: e.to_string(),
+                })?;
+                if ts_index.is_moved(&series_key) {
+                    return Err(Error::SeriesMoved {
+                        vnode_id,
+                        series: series_key.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     async fn build_index(
         db_name: &str,
         tab_name: &str,
@@ -398,6 +517,7 @@ impl Database {
         tag_idx: &[usize],
         row_num: usize,
         ts_index: Arc<index::ts_index::TSIndex>,
+        series_limit: &SeriesLimit,
     ) -> Result<Vec<u32>> {
         let mut res_sids = Vec::with_capacity(row_num);
         let mut series_keys = Vec::with_capacity(row_num);
@@ -416,7 +536,7 @@ impl Database {
         }
 
         let mut ids = ts_index
-            .add_series_if_not_exists(series_keys)
+            .add_series_if_not_exists(series_keys, series_limit)
             .await?
             .into_iter();
         for item in res_sids.iter_mut() {
@@ -490,7 +610,11 @@ impl Database {
     }
 
     pub fn del_ts_index(&mut self, id: u32) {
-        self.ts_indexes.remove(&id);
+        if self.ts_indexes.remove(&id).is_some() {
+            self.metrics_register
+                .metric::<U64Gauge>("vnode_series_cardinality", "number of series of vnode")
+                .remove_recorder([("vnode_id", id.to_string().as_str())]);
+        }
     }
 
     pub fn get_ts_index(&self, id: u32) -> Option<Arc<index::ts_index::TSIndex>> {
@@ -513,6 +637,12 @@ impl Database {
         let path = self.opt.storage.index_dir(&self.owner, id);
 
         let idx = index::ts_index::TSIndex::new(path).await?;
+        self.metrics_register
+            .metric::<U64Gauge>("vnode_series_cardinality", "number of series of vnode")
+            .register_recorder(
+                [("vnode_id", id.to_string().as_str())],
+                idx.series_cardinality_gauge(),
+            );
 
         self.ts_indexes.insert(id, idx.clone());
 
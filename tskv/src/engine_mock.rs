@@ -1,17 +1,19 @@
 #![allow(dead_code, unused_variables)]
 
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use datafusion::arrow::record_batch::RecordBatch;
 use models::meta_data::VnodeId;
-use models::predicate::domain::{ColumnDomains, TimeRange};
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate};
 use models::schema::{Precision, TableColumn};
-use models::{ColumnId, SeriesId, SeriesKey};
+use models::{SeriesId, SeriesKey};
 use protos::kv_service::{WritePointsRequest, WritePointsResponse};
 use protos::models as fb_models;
 use trace::{debug, SpanContext};
+use utils::HyperLogLog;
 
 use crate::error::Result;
 use crate::kv_option::StorageOptions;
@@ -78,15 +80,15 @@ impl Engine for MockEngine {
         Ok(())
     }
 
-    async fn delete_series(
+    async fn delete_from_table(
         &self,
         tenant: &str,
         database: &str,
-        series_ids: &[SeriesId],
-        field_ids: &[ColumnId],
-        time_range: &TimeRange,
+        table: &str,
+        predicate: &ResolvedPredicate,
     ) -> Result<()> {
-        todo!()
+        println!("delete_from_table db:{:?}, table:{:?}", database, table);
+        Ok(())
     }
 
     async fn get_series_id_by_filter(
@@ -110,6 +112,27 @@ impl Engine for MockEngine {
         Ok(None)
     }
 
+    async fn get_series_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: Option<&str>,
+    ) -> Result<Vec<(String, HyperLogLog)>> {
+        Ok(vec![])
+    }
+
+    async fn get_tag_values_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: &str,
+        tag_keys: &[String],
+    ) -> Result<Vec<(String, HyperLogLog)>> {
+        Ok(vec![])
+    }
+
     async fn get_db_version(
         &self,
         tenant: &str,
@@ -138,10 +161,88 @@ impl Engine for MockEngine {
         todo!()
     }
 
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        location: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        backup_vnode_id: VnodeId,
+        location: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn export_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: Option<&str>,
+        location: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn import_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: &str,
+        data: RecordBatch,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn split_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        new_vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn remove_moved_series(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     // fn alter_database(&self, schema: &DatabaseSchema) -> Result<()> {
     //     todo!()
     // }
 
+    async fn rename_database(&self, tenant: &str, database: &str, new_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn rename_table(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        new_name: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
     async fn add_table_column(
         &self,
         tenant: &str,
@@ -177,6 +278,10 @@ impl Engine for MockEngine {
         todo!()
     }
 
+    fn set_compact_write_rate_limit(&self, _bytes_per_second: u64) {}
+
+    fn set_flush_write_rate_limit(&self, _bytes_per_second: u64) {}
+
     async fn drop_vnode(&self, id: TseriesFamilyId) -> Result<()> {
         todo!()
     }
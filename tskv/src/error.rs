@@ -4,6 +4,7 @@ use datafusion::arrow::error::ArrowError;
 use error_code::{ErrorCode, ErrorCoder};
 use http_protocol::response::ErrorResponse;
 use meta::error::MetaError;
+use models::PhysicalDType;
 use protos::PointsError;
 use snafu::Snafu;
 use tonic::{Code, Status};
@@ -80,6 +81,30 @@ pub enum Error {
         source: WriteTsmError,
     },
 
+    #[snafu(display("{}", source))]
+    #[error_code(code = 10)]
+    SeriesLimitExceeded {
+        source: IndexError,
+    },
+
+    #[snafu(display(
+        "series '{}' is moved out of vnode {} by split, retry later",
+        series,
+        vnode_id
+    ))]
+    #[error_code(code = 11)]
+    SeriesMoved {
+        vnode_id: u32,
+        series: String,
+    },
+
+    #[snafu(display("values of type {} can't be converted to {}", from, to))]
+    #[error_code(code = 12)]
+    ConvertValueType {
+        from: PhysicalDType,
+        to: PhysicalDType,
+    },
+
     // Internal Error
     #[snafu(display("{}", source))]
     IO {
@@ -227,6 +252,21 @@ pub enum Error {
         message: String,
         source: std::str::Utf8Error,
     },
+
+    #[snafu(display("object store error: {}", source))]
+    ObjectStore {
+        source: object_store::Error,
+    },
+
+    #[snafu(display("invalid object store path: {}", source))]
+    ObjectStorePath {
+        source: object_store::path::Error,
+    },
+
+    #[snafu(display("parquet error: {}", source))]
+    Parquet {
+        source: datafusion::parquet::errors::ParquetError,
+    },
 }
 
 impl From<PointsError> for Error {
@@ -246,7 +286,10 @@ impl From<SchemaError> for Error {
 
 impl From<IndexError> for Error {
     fn from(value: IndexError) -> Self {
-        Error::IndexErr { source: value }
+        match value {
+            e @ IndexError::SeriesLimitExceeded { .. } => Error::SeriesLimitExceeded { source: e },
+            other => Error::IndexErr { source: other },
+        }
     }
 }
 
@@ -342,6 +385,11 @@ pub enum ChannelReceiveError {
     WriteWalResult {
         source: tokio::sync::oneshot::error::RecvError,
     },
+
+    #[snafu(display("Failed to receive write summary result: {source}"))]
+    WriteSummaryResult {
+        source: tokio::sync::oneshot::error::RecvError,
+    },
 }
 
 #[test]
@@ -0,0 +1,82 @@
+use std::alloc::{self, Layout};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::slice;
+
+/// Alignment of offsets, lengths and buffers of I/O on files opened with O_DIRECT.
+pub const DIRECT_IO_ALIGN: usize = 4096;
+
+pub fn align_down(n: u64) -> u64 {
+    n & !(DIRECT_IO_ALIGN as u64 - 1)
+}
+
+pub fn align_up(n: u64) -> u64 {
+    align_down(n + DIRECT_IO_ALIGN as u64 - 1)
+}
+
+/// Zero-initialized heap buffer aligned to `DIRECT_IO_ALIGN`, owned by I/O
+/// operations until they are completed.
+pub struct AlignedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    layout: Layout,
+}
+
+unsafe impl Send for AlignedBuf {}
+unsafe impl Sync for AlignedBuf {}
+
+impl AlignedBuf {
+    pub fn zeroed(len: usize) -> Self {
+        let layout = Layout::from_size_align(len.max(1), DIRECT_IO_ALIGN)
+            .expect("aligned buffer size overflow");
+        let ptr = match NonNull::new(unsafe { alloc::alloc_zeroed(layout) }) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(layout),
+        };
+        Self { ptr, len, layout }
+    }
+
+    pub fn from_slice(data: &[u8]) -> Self {
+        let mut buf = Self::zeroed(data.len());
+        buf.copy_from_slice(data);
+        buf
+    }
+}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{align_down, align_up, AlignedBuf, DIRECT_IO_ALIGN};
+
+    #[test]
+    fn test_aligned_buf() {
+        let buf = AlignedBuf::from_slice(&[1, 2, 3]);
+        assert_eq!(buf.as_ptr() as usize % DIRECT_IO_ALIGN, 0);
+        assert_eq!(&buf[..], &[1, 2, 3]);
+        assert!(AlignedBuf::zeroed(0).is_empty());
+
+        assert_eq!(align_down(4097), 4096);
+        assert_eq!(align_up(4097), 8192);
+        assert_eq!(align_up(4096), 4096);
+        assert_eq!(align_up(0), 0);
+    }
+}
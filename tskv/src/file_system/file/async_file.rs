@@ -1,86 +1,314 @@
+use std::fmt::{Display, Formatter};
 use std::fs::{File, OpenOptions};
 use std::io::{Error, ErrorKind, IoSlice, Result};
 use std::ops::Deref;
 use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
 
+use tokio::sync::Mutex;
 use tokio::task::spawn_blocking;
+use trace::{error, warn};
 
+use super::aligned_buf::{align_down, align_up, AlignedBuf, DIRECT_IO_ALIGN};
 use super::os;
+use super::uring::UringRuntime;
 use crate::file_system::file::IFile;
 
-#[derive(Debug)]
-#[cfg(not(feature = "io_uring"))]
-struct RawFile(Arc<File>);
+/// Writes to a file opened with O_DIRECT are buffered until the buffer
+/// reaches this size, then the aligned part of it is written.
+const DIRECT_WRITE_BUFFER_SIZE: usize = 1024 * 1024;
 
-#[derive(Debug)]
-#[cfg(feature = "io_uring")]
-struct RawFile(Arc<File>, Arc<rio::Rio>);
+/// Backend to do reads and writes of files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoBackend {
+    /// pread/pwrite in blocking threads of tokio.
+    Blocking,
+    /// io_uring, available on Linux 5.6+ with feature 'io_uring'.
+    IoUring,
+}
+
+impl FromStr for IoBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "blocking" => Ok(Self::Blocking),
+            "io_uring" => Ok(Self::IoUring),
+            _ => Err(format!(
+                "unknown io backend '{}', expected 'blocking' or 'io_uring'",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for IoBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Blocking => write!(f, "blocking"),
+            Self::IoUring => write!(f, "io_uring"),
+        }
+    }
+}
+
+/// Writes not yet written to a file opened with O_DIRECT.
+#[derive(Default)]
+struct DirectWriteBuf {
+    /// Aligned position in file of `data[0]`.
+    start: u64,
+    /// Bytes from `start`, the bytes before the first write are read from file.
+    data: Vec<u8>,
+}
+
+impl DirectWriteBuf {
+    fn end(&self) -> u64 {
+        self.start + self.data.len() as u64
+    }
+
+    /// Copies data to an aligned buffer padded to the end of the last block.
+    fn padded_buf(&self) -> AlignedBuf {
+        let mut buf = AlignedBuf::zeroed((align_up(self.end()) - self.start) as usize);
+        buf[..self.data.len()].copy_from_slice(&self.data);
+        buf
+    }
+
+    /// Copies the bytes after the end of data in the last block to `buf`.
+    fn merge_tail(&self, buf: &mut AlignedBuf, last_block: &[u8]) {
+        let end = self.end();
+        let tail_start = align_down(end);
+        let from = (end - tail_start) as usize;
+        let offset = (tail_start - self.start) as usize;
+        buf[offset + from..].copy_from_slice(&last_block[from..]);
+    }
+}
+
+struct RawFile {
+    file: Arc<File>,
+    ctx: Arc<FsRuntime>,
+    /// Buffer of writes if the file is opened with O_DIRECT.
+    direct_buf: Option<Mutex<DirectWriteBuf>>,
+}
 
 impl RawFile {
     fn file_size(&self) -> Result<u64> {
-        os::file_size(os::fd(self.0.as_ref()))
+        os::file_size(os::fd(self.file.as_ref()))
     }
 
-    async fn pwrite(&self, pos: u64, data: &[u8]) -> Result<usize> {
-        #[cfg(feature = "io_uring")]
-        {
-            let completion = self.1.write_at(&self.0, &data, pos).await?;
-            Ok(data.len())
+    /// Reads into a owned buffer, returns number of bytes read and the buffer.
+    async fn pread_buf(&self, pos: u64, mut buf: AlignedBuf) -> Result<(usize, AlignedBuf)> {
+        if let Some(uring) = &self.ctx.uring {
+            let (ret, buf) = uring.read_at(self.file.clone(), pos, buf).await;
+            return ret.map(|len| (len, buf));
+        }
+        let file = self.file.clone();
+        asyncify(move || {
+            let ptr = buf.as_mut_ptr() as u64;
+            let len = os::pread(os::fd(file.as_ref()), pos, buf.len(), ptr)?;
+            Ok((len, buf))
+        })
+        .await
+    }
+
+    /// Writes a owned buffer, returns number of bytes written.
+    async fn pwrite_buf(&self, pos: u64, buf: AlignedBuf) -> Result<usize> {
+        if let Some(uring) = &self.ctx.uring {
+            return uring.write_at(self.file.clone(), pos, buf).await.0;
         }
+        let file = self.file.clone();
+        asyncify(move || os::pwrite(os::fd(file.as_ref()), pos, buf.len(), buf.as_ptr() as u64))
+            .await
+    }
 
-        #[cfg(not(feature = "io_uring"))]
-        {
-            let len = data.len();
-            let ptr = data.as_ptr() as u64;
-            let fd = os::fd(self.0.as_ref());
-            asyncify(move || os::pwrite(fd, pos, len, ptr)).await
+    async fn pwrite_buf_all(&self, pos: u64, buf: AlignedBuf) -> Result<()> {
+        let len = buf.len();
+        if self.pwrite_buf(pos, buf).await? < len {
+            return Err(Error::new(
+                ErrorKind::WriteZero,
+                "failed to write whole buffer",
+            ));
         }
+        Ok(())
+    }
+
+    async fn pwrite(&self, pos: u64, data: &[u8]) -> Result<usize> {
+        if let Some(direct_buf) = &self.direct_buf {
+            let mut direct_buf = direct_buf.lock().await;
+            self.direct_write(&mut direct_buf, pos, data).await?;
+            return Ok(data.len());
+        }
+        if self.ctx.uring.is_some() {
+            return self.pwrite_buf(pos, AlignedBuf::from_slice(data)).await;
+        }
+
+        let len = data.len();
+        let ptr = data.as_ptr() as u64;
+        let fd = os::fd(self.file.as_ref());
+        asyncify(move || os::pwrite(fd, pos, len, ptr)).await
     }
 
     async fn pread(&self, pos: u64, data: &mut [u8]) -> Result<usize> {
-        #[cfg(feature = "io_uring")]
-        {
-            let completion = self.1.read_at(&self.0, &data, pos).await?;
-            Ok(data.len())
+        if let Some(direct_buf) = &self.direct_buf {
+            // Buffered writes should be visible to reads.
+            self.flush_direct(&mut *direct_buf.lock().await).await?;
+            let start = align_down(pos);
+            let end = align_up(pos + data.len() as u64);
+            let (read, buf) = self
+                .pread_buf(start, AlignedBuf::zeroed((end - start) as usize))
+                .await?;
+            let skip = (pos - start) as usize;
+            let len = read.saturating_sub(skip).min(data.len());
+            data[..len].copy_from_slice(&buf[skip..skip + len]);
+            return Ok(len);
+        }
+        if self.ctx.uring.is_some() {
+            let (len, buf) = self.pread_buf(pos, AlignedBuf::zeroed(data.len())).await?;
+            data[..len].copy_from_slice(&buf[..len]);
+            return Ok(len);
+        }
+
+        let len = data.len();
+        let ptr = data.as_ptr() as u64;
+        let fd = os::fd(self.file.as_ref());
+        let len = asyncify(move || os::pread(fd, pos, len, ptr)).await?;
+        Ok(len)
+    }
+
+    /// Appends to the write buffer if `pos` is the end of it, otherwise flushes
+    /// the write buffer and starts a new one from `pos`. The aligned part of the
+    /// buffer is written when it's big enough.
+    async fn direct_write(
+        &self,
+        direct_buf: &mut DirectWriteBuf,
+        pos: u64,
+        data: &[u8],
+    ) -> Result<()> {
+        if direct_buf.data.is_empty() || pos != direct_buf.end() {
+            self.flush_direct(direct_buf).await?;
+            direct_buf.start = align_down(pos);
+            if pos > direct_buf.start {
+                // Keep the bytes before `pos` in the first block.
+                let (_, block) = self
+                    .pread_buf(direct_buf.start, AlignedBuf::zeroed(DIRECT_IO_ALIGN))
+                    .await?;
+                let head = (pos - direct_buf.start) as usize;
+                direct_buf.data.extend_from_slice(&block[..head]);
+            }
         }
-        #[cfg(not(feature = "io_uring"))]
-        {
-            let len = data.len();
-            let ptr = data.as_ptr() as u64;
-            let fd = os::fd(self.0.as_ref());
-            let len = asyncify(move || os::pread(fd, pos, len, ptr)).await?;
-            Ok(len)
+        direct_buf.data.extend_from_slice(data);
+
+        if direct_buf.data.len() >= DIRECT_WRITE_BUFFER_SIZE {
+            let len = align_down(direct_buf.data.len() as u64) as usize;
+            self.pwrite_buf_all(
+                direct_buf.start,
+                AlignedBuf::from_slice(&direct_buf.data[..len]),
+            )
+            .await?;
+            direct_buf.data.drain(..len);
+            direct_buf.start += len as u64;
+        }
+        Ok(())
+    }
+
+    /// Writes all bytes in the write buffer, the last block is merged with the
+    /// bytes after it in file, and the file is truncated if it's extended by
+    /// the padding of the last block.
+    async fn flush_direct(&self, direct_buf: &mut DirectWriteBuf) -> Result<()> {
+        if direct_buf.data.is_empty() {
+            return Ok(());
+        }
+        let end = direct_buf.end();
+        let aligned_end = align_up(end);
+        let file_size = self.file_size()?;
+        let mut buf = direct_buf.padded_buf();
+        if aligned_end > end && file_size > end {
+            let (_, block) = self
+                .pread_buf(align_down(end), AlignedBuf::zeroed(DIRECT_IO_ALIGN))
+                .await?;
+            direct_buf.merge_tail(&mut buf, &block);
         }
+        self.pwrite_buf_all(direct_buf.start, buf).await?;
+        let new_size = file_size.max(end);
+        if aligned_end > new_size {
+            let file = self.file.clone();
+            asyncify(move || file.set_len(new_size)).await?;
+        }
+        direct_buf.data.clear();
+        Ok(())
     }
 
     async fn sync_data(&self) -> Result<()> {
-        #[cfg(feature = "io_uring")]
-        {
-            self.1.fsync(&self.0).await?;
-            Ok(())
+        if let Some(direct_buf) = &self.direct_buf {
+            self.flush_direct(&mut *direct_buf.lock().await).await?;
         }
-        #[cfg(not(feature = "io_uring"))]
-        {
-            let file = self.0.clone();
-            asyncify(move || file.sync_data()).await
+        if let Some(uring) = &self.ctx.uring {
+            return uring.sync_data(self.file.clone()).await;
         }
+        let file = self.file.clone();
+        asyncify(move || file.sync_data()).await
     }
 
     async fn truncate(&self, size: u64) -> Result<()> {
-        #[cfg(feature = "io_uring")]
-        {
-            let file = self.0.clone();
-            asyncify(move || file.set_len(size)).await
+        if let Some(direct_buf) = &self.direct_buf {
+            self.flush_direct(&mut *direct_buf.lock().await).await?;
+        }
+        let file = self.file.clone();
+        asyncify(move || file.set_len(size)).await
+    }
+}
+
+impl Drop for RawFile {
+    /// Writes not flushed by `sync_data` are written in a blocking thread of
+    /// tokio, or in the current thread if it's not in a tokio runtime.
+    fn drop(&mut self) {
+        let mut direct_buf = match self.direct_buf.take() {
+            Some(buf) => buf.into_inner(),
+            None => return,
+        };
+        if direct_buf.data.is_empty() {
+            return;
         }
-        #[cfg(not(feature = "io_uring"))]
-        {
-            let file = self.0.clone();
-            asyncify(move || file.set_len(size)).await
+        let file = self.file.clone();
+        let flush = move || {
+            if let Err(e) = flush_direct_blocking(file.as_ref(), &mut direct_buf) {
+                error!("failed to write buffered data when closing file: {}", e);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(flush);
+            }
+            Err(_) => flush(),
         }
     }
 }
 
+/// Same as `RawFile::flush_direct` but uses blocking I/O, for dropping the file.
+fn flush_direct_blocking(file: &File, direct_buf: &mut DirectWriteBuf) -> Result<()> {
+    let fd = os::fd(file);
+    let end = direct_buf.end();
+    let aligned_end = align_up(end);
+    let file_size = os::file_size(fd)?;
+    let mut buf = direct_buf.padded_buf();
+    if aligned_end > end && file_size > end {
+        let mut block = AlignedBuf::zeroed(DIRECT_IO_ALIGN);
+        os::pread(fd, align_down(end), block.len(), block.as_mut_ptr() as u64)?;
+        direct_buf.merge_tail(&mut buf, &block);
+    }
+    if os::pwrite(fd, direct_buf.start, buf.len(), buf.as_ptr() as u64)? < buf.len() {
+        return Err(Error::new(
+            ErrorKind::WriteZero,
+            "failed to write whole buffer",
+        ));
+    }
+    let new_size = file_size.max(end);
+    if aligned_end > new_size {
+        file.set_len(new_size)?;
+    }
+    direct_buf.data.clear();
+    Ok(())
+}
+
 pub(crate) async fn asyncify<F, T>(f: F) -> Result<T>
 where
     F: FnOnce() -> Result<T> + Send + 'static,
@@ -96,29 +324,47 @@ where
 }
 
 pub struct FsRuntime {
-    #[cfg(feature = "io_uring")]
-    rio: Arc<Rio>,
+    /// Is None if the backend is `IoBackend::Blocking`.
+    uring: Option<UringRuntime>,
+    /// Open TSM files with O_DIRECT.
+    direct_io: bool,
 }
 
-unsafe impl Send for FsRuntime {}
-
 impl FsRuntime {
     pub fn new_runtime() -> Self {
-        #[cfg(feature = "io_uring")]
-        {
-            let rio = Arc::new(rio::new().unwrap());
-            FsRuntime { rio }
-        }
-        #[cfg(not(feature = "io_uring"))]
-        {
-            FsRuntime {}
+        Self::new(IoBackend::Blocking, false)
+    }
+
+    /// Creates a `FsRuntime` of the backend, falls back to `IoBackend::Blocking`
+    /// if io_uring is not supported.
+    pub fn new(backend: IoBackend, direct_io: bool) -> Self {
+        let uring = match backend {
+            IoBackend::Blocking => None,
+            IoBackend::IoUring => match UringRuntime::new() {
+                Ok(uring) => Some(uring),
+                Err(e) => {
+                    warn!(
+                        "io_uring is not available, fall back to blocking I/O: {}",
+                        e
+                    );
+                    None
+                }
+            },
+        };
+        Self { uring, direct_io }
+    }
+
+    pub fn backend(&self) -> IoBackend {
+        if self.uring.is_some() {
+            IoBackend::IoUring
+        } else {
+            IoBackend::Blocking
         }
     }
 }
 
 pub struct AsyncFile {
     inner: RawFile,
-    ctx: Arc<FsRuntime>,
     size: u64,
 }
 
@@ -162,26 +408,58 @@ impl AsyncFile {
         path: P,
         ctx: Arc<FsRuntime>,
         options: OpenOptions,
+    ) -> Result<AsyncFile> {
+        Self::open_opt(path, ctx, options, false).await
+    }
+
+    /// Open a file with O_DIRECT if `direct_io` of the runtime is enabled,
+    /// falls back to buffered I/O if the file system doesn't support it.
+    pub async fn open_direct<P: AsRef<Path>>(
+        path: P,
+        ctx: Arc<FsRuntime>,
+        options: OpenOptions,
+    ) -> Result<AsyncFile> {
+        let direct_io = ctx.direct_io;
+        Self::open_opt(path, ctx, options, direct_io).await
+    }
+
+    async fn open_opt<P: AsRef<Path>>(
+        path: P,
+        ctx: Arc<FsRuntime>,
+        options: OpenOptions,
+        direct_io: bool,
     ) -> Result<AsyncFile> {
         let path = path.as_ref().to_owned();
-        #[cfg(feature = "io_uring")]
-        {
-            let file = asyncify(move || options.open(path)).await?;
-            let inner = RawFile(Arc::new(file), ctx.rio.clone());
-            let size = inner.file_size()?;
-            Ok(AsyncFile { inner, ctx, size })
-        }
-        #[cfg(not(feature = "io_uring"))]
-        {
-            let file = asyncify(move || options.open(path)).await?;
-            let inner = RawFile(Arc::new(file));
-            let size = inner.file_size()?;
-            Ok(AsyncFile { inner, ctx, size })
-        }
+        let (file, direct_io) = asyncify(move || {
+            if direct_io {
+                match os::open_direct(&path, options.clone()) {
+                    Ok(file) => return Ok((file, true)),
+                    Err(e)
+                        if matches!(e.kind(), ErrorKind::InvalidInput | ErrorKind::Unsupported) =>
+                    {
+                        warn!(
+                            "O_DIRECT is not supported for '{}', fall back to buffered I/O: {}",
+                            path.display(),
+                            e
+                        );
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            options.open(&path).map(|file| (file, false))
+        })
+        .await?;
+        let inner = RawFile {
+            file: Arc::new(file),
+            ctx,
+            direct_buf: direct_io.then(|| Mutex::new(DirectWriteBuf::default())),
+        };
+        let size = inner.file_size()?;
+        Ok(AsyncFile { inner, size })
     }
 
     pub fn fd(&self) -> usize {
-        os::fd(&self.inner.0)
+        os::fd(self.inner.file.as_ref())
     }
 }
 
@@ -1,12 +1,29 @@
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::{Error, Result};
 use std::mem::MaybeUninit;
 use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
 
 pub fn fd(file: &File) -> usize {
     file.as_raw_fd() as usize
 }
 
+/// Open a file with O_DIRECT.
+#[cfg(target_os = "linux")]
+pub fn open_direct(path: &Path, mut options: OpenOptions) -> Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    options.custom_flags(libc::O_DIRECT).open(path)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn open_direct(_path: &Path, _options: OpenOptions) -> Result<File> {
+    Err(Error::new(
+        std::io::ErrorKind::Unsupported,
+        "O_DIRECT is not supported",
+    ))
+}
+
 pub fn pread(raw_fd: usize, pos: u64, len: usize, ptr: u64) -> Result<usize> {
     check_err_size(unsafe {
         libc::pread(raw_fd as RawFd, ptr as *mut _, len as _, pos as libc::off_t)
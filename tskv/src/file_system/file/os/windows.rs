@@ -1,9 +1,10 @@
 use std::convert::TryFrom;
-use std::fs::File;
-use std::io::{Error, Result};
+use std::fs::{File, OpenOptions};
+use std::io::{Error, ErrorKind, Result};
 use std::mem::MaybeUninit;
 use std::os::windows::io::AsRawHandle;
 use std::os::windows::prelude::RawHandle;
+use std::path::Path;
 
 use winapi::shared::minwindef::*;
 use winapi::um::fileapi::*;
@@ -13,6 +14,13 @@ pub fn fd(file: &File) -> usize {
     file.as_raw_handle() as usize
 }
 
+pub fn open_direct(_path: &Path, _options: OpenOptions) -> Result<File> {
+    Err(Error::new(
+        ErrorKind::Unsupported,
+        "O_DIRECT is not supported",
+    ))
+}
+
 pub fn pread(raw_handle: usize, pos: u64, len: usize, buf_ptr: u64) -> Result<usize> {
     let mut bytes: DWORD = 0;
     let mut ov = overlapped(pos);
@@ -0,0 +1,279 @@
+//! I/O backend submitting reads, writes and syncs to an io_uring instance
+//! owned by a dedicated thread.
+
+pub use imp::UringRuntime;
+
+#[cfg(all(target_os = "linux", feature = "io_uring"))]
+mod imp {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::{Error, ErrorKind, Result};
+    use std::os::unix::io::AsRawFd;
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    use crossbeam_channel::{Receiver, Sender, TryRecvError};
+    use io_uring::{opcode, squeue, types, IoUring};
+    use parking_lot::Mutex;
+    use trace::error;
+
+    use crate::file_system::file::aligned_buf::AlignedBuf;
+
+    /// Number of entries of the submission queue.
+    const URING_ENTRIES: u32 = 256;
+
+    /// Time to wait before submitting again if io_uring returns an error and no
+    /// operation is completed, so that a persistent error doesn't spin the thread.
+    const URING_ERROR_BACKOFF: Duration = Duration::from_millis(10);
+
+    enum UringOp {
+        Read(u64),
+        Write(u64),
+        Fsync,
+    }
+
+    struct UringTask {
+        op: UringOp,
+        /// Keeps the file opened until the operation is completed.
+        file: Arc<File>,
+        /// Buffer of the operation, owned by the task so that it's still valid
+        /// if the future waiting for the operation is dropped.
+        buf: AlignedBuf,
+        sender: tokio::sync::oneshot::Sender<(Result<usize>, AlignedBuf)>,
+    }
+
+    pub struct UringRuntime {
+        sender: Mutex<Option<Sender<UringTask>>>,
+        handle: Mutex<Option<JoinHandle<()>>>,
+    }
+
+    impl UringRuntime {
+        /// Creates the io_uring instance and the thread polling it, returns
+        /// error if the kernel doesn't support io_uring.
+        pub fn new() -> Result<Self> {
+            let ring = IoUring::new(URING_ENTRIES)?;
+            let (sender, receiver) = crossbeam_channel::unbounded();
+            let handle = std::thread::Builder::new()
+                .name("tskv-io-uring".to_string())
+                .spawn(move || run(ring, receiver))?;
+            Ok(Self {
+                sender: Mutex::new(Some(sender)),
+                handle: Mutex::new(Some(handle)),
+            })
+        }
+
+        pub async fn read_at(
+            &self,
+            file: Arc<File>,
+            pos: u64,
+            buf: AlignedBuf,
+        ) -> (Result<usize>, AlignedBuf) {
+            self.submit(UringOp::Read(pos), file, buf).await
+        }
+
+        pub async fn write_at(
+            &self,
+            file: Arc<File>,
+            pos: u64,
+            buf: AlignedBuf,
+        ) -> (Result<usize>, AlignedBuf) {
+            self.submit(UringOp::Write(pos), file, buf).await
+        }
+
+        pub async fn sync_data(&self, file: Arc<File>) -> Result<()> {
+            self.submit(UringOp::Fsync, file, AlignedBuf::zeroed(0))
+                .await
+                .0
+                .map(|_| ())
+        }
+
+        async fn submit(
+            &self,
+            op: UringOp,
+            file: Arc<File>,
+            buf: AlignedBuf,
+        ) -> (Result<usize>, AlignedBuf) {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let task = UringTask {
+                op,
+                file,
+                buf,
+                sender,
+            };
+            let send_ret = match self.sender.lock().as_ref() {
+                Some(s) => s.send(task).map_err(|e| e.into_inner()),
+                None => Err(task),
+            };
+            if let Err(task) = send_ret {
+                return (Err(stopped_error()), task.buf);
+            }
+            match receiver.await {
+                Ok(ret) => ret,
+                Err(_) => (Err(stopped_error()), AlignedBuf::zeroed(0)),
+            }
+        }
+    }
+
+    impl Drop for UringRuntime {
+        fn drop(&mut self) {
+            // Thread exits after all submitted tasks are completed.
+            self.sender.lock().take();
+            if let Some(handle) = self.handle.lock().take() {
+                let _ = handle.join();
+            }
+        }
+    }
+
+    fn stopped_error() -> Error {
+        Error::new(ErrorKind::Other, "io_uring runtime stopped")
+    }
+
+    fn build_entry(task: &mut UringTask, user_data: u64) -> squeue::Entry {
+        let fd = types::Fd(task.file.as_raw_fd());
+        let entry = match task.op {
+            UringOp::Read(pos) => {
+                opcode::Read::new(fd, task.buf.as_mut_ptr(), task.buf.len() as u32)
+                    .offset(pos)
+                    .build()
+            }
+            UringOp::Write(pos) => opcode::Write::new(fd, task.buf.as_ptr(), task.buf.len() as u32)
+                .offset(pos)
+                .build(),
+            UringOp::Fsync => opcode::Fsync::new(fd)
+                .flags(types::FsyncFlags::DATASYNC)
+                .build(),
+        };
+        entry.user_data(user_data)
+    }
+
+    fn complete(task: UringTask, ret: i32) {
+        let ret = if ret < 0 {
+            Err(Error::from_raw_os_error(-ret))
+        } else {
+            Ok(ret as usize)
+        };
+        let _ = task.sender.send((ret, task.buf));
+    }
+
+    /// Completes tasks of entries in the completion queue, returns the number
+    /// of completed tasks.
+    fn reap_completions(ring: &mut IoUring, in_flight: &mut HashMap<u64, UringTask>) -> usize {
+        let mut completed = 0;
+        for cqe in ring.completion() {
+            if let Some(task) = in_flight.remove(&cqe.user_data()) {
+                complete(task, cqe.result());
+                completed += 1;
+            }
+        }
+        completed
+    }
+
+    /// Handles an error of submitting entries: completion queue may be full
+    /// (EBUSY), so completions are reaped, waits a while if there are none.
+    fn handle_submit_error(
+        ring: &mut IoUring,
+        in_flight: &mut HashMap<u64, UringTask>,
+        err: Error,
+    ) {
+        if err.kind() == ErrorKind::Interrupted {
+            return;
+        }
+        error!("failed to submit io_uring entries: {}", err);
+        if reap_completions(ring, in_flight) == 0 {
+            std::thread::sleep(URING_ERROR_BACKOFF);
+        }
+    }
+
+    fn run(mut ring: IoUring, receiver: Receiver<UringTask>) {
+        let mut in_flight: HashMap<u64, UringTask> = HashMap::new();
+        let mut next_user_data = 0_u64;
+        let mut disconnected = false;
+        loop {
+            // Block for new tasks only if no task is waiting for completion.
+            let mut new_tasks = Vec::new();
+            if in_flight.is_empty() {
+                if disconnected {
+                    return;
+                }
+                match receiver.recv() {
+                    Ok(t) => new_tasks.push(t),
+                    Err(_) => return,
+                }
+            }
+            loop {
+                match receiver.try_recv() {
+                    Ok(t) => new_tasks.push(t),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
+                }
+            }
+
+            for mut task in new_tasks {
+                let user_data = next_user_data;
+                next_user_data = next_user_data.wrapping_add(1);
+                let entry = build_entry(&mut task, user_data);
+                in_flight.insert(user_data, task);
+                // Safety: buffers and files of entries are owned by tasks in `in_flight`
+                // until the entries are completed.
+                while unsafe { ring.submission().push(&entry) }.is_err() {
+                    if let Err(e) = ring.submit() {
+                        handle_submit_error(&mut ring, &mut in_flight, e);
+                    }
+                }
+            }
+
+            if let Err(e) = ring.submit_and_wait(1) {
+                handle_submit_error(&mut ring, &mut in_flight, e);
+            }
+            reap_completions(&mut ring, &mut in_flight);
+        }
+    }
+}
+
+#[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+mod imp {
+    use std::fs::File;
+    use std::io::{Error, ErrorKind, Result};
+    use std::sync::Arc;
+
+    use crate::file_system::file::aligned_buf::AlignedBuf;
+
+    /// io_uring is not supported on this platform or without feature 'io_uring'.
+    pub struct UringRuntime;
+
+    impl UringRuntime {
+        pub fn new() -> Result<Self> {
+            Err(unsupported_error())
+        }
+
+        pub async fn read_at(
+            &self,
+            _file: Arc<File>,
+            _pos: u64,
+            buf: AlignedBuf,
+        ) -> (Result<usize>, AlignedBuf) {
+            (Err(unsupported_error()), buf)
+        }
+
+        pub async fn write_at(
+            &self,
+            _file: Arc<File>,
+            _pos: u64,
+            buf: AlignedBuf,
+        ) -> (Result<usize>, AlignedBuf) {
+            (Err(unsupported_error()), buf)
+        }
+
+        pub async fn sync_data(&self, _file: Arc<File>) -> Result<()> {
+            Err(unsupported_error())
+        }
+    }
+
+    fn unsupported_error() -> Error {
+        Error::new(ErrorKind::Unsupported, "io_uring is not supported")
+    }
+}
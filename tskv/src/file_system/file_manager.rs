@@ -5,8 +5,10 @@ use std::sync::Arc;
 
 use once_cell::sync::OnceCell;
 use snafu::{ResultExt, Snafu};
+use trace::warn;
 
-use crate::file_system::file::async_file::{AsyncFile, FsRuntime};
+use crate::file_system::file::async_file::{AsyncFile, FsRuntime, IoBackend};
+use crate::kv_option::StorageOptions;
 use crate::{error, Error, Result};
 
 #[derive(Snafu, Debug)]
@@ -37,19 +39,50 @@ static INSTANCE: OnceCell<FileManager> = OnceCell::new();
 
 pub struct FileManager {
     fs_runtime: Arc<FsRuntime>,
+    /// I/O backend and O_DIRECT options the `FileManager` is initialized with.
+    init_options: (IoBackend, bool),
 }
 
 pub fn get_file_manager() -> &'static FileManager {
     INSTANCE.get_or_init(FileManager::new)
 }
 
+/// Initialize the global `FileManager` with the I/O backend and O_DIRECT
+/// options. If the `FileManager` is already initialized, it's not changed,
+/// and a warning is logged if it's initialized with other options.
+pub fn init_file_manager(options: &StorageOptions) {
+    let backend = options.io_backend.parse::<IoBackend>().unwrap_or_else(|e| {
+        warn!("{}, use blocking I/O", e);
+        IoBackend::Blocking
+    });
+    let init_options = (backend, options.direct_io);
+    let file_manager = INSTANCE.get_or_init(|| {
+        FileManager::with_runtime(FsRuntime::new(backend, options.direct_io), init_options)
+    });
+    if file_manager.init_options != init_options {
+        warn!(
+            "File manager is already initialized with io_backend '{}' and direct_io {}, ignore io_backend '{}' and direct_io {}",
+            file_manager.init_options.0, file_manager.init_options.1, backend, options.direct_io
+        );
+    }
+}
+
 impl FileManager {
     fn new() -> Self {
+        Self::with_runtime(FsRuntime::new_runtime(), (IoBackend::Blocking, false))
+    }
+
+    fn with_runtime(fs_runtime: FsRuntime, init_options: (IoBackend, bool)) -> Self {
         Self {
-            fs_runtime: Arc::new(FsRuntime::new_runtime()),
+            fs_runtime: Arc::new(fs_runtime),
+            init_options,
         }
     }
 
+    pub fn io_backend(&self) -> IoBackend {
+        self.fs_runtime.backend()
+    }
+
     pub async fn open_file_with(
         &self,
         path: impl AsRef<Path>,
@@ -70,6 +103,18 @@ impl FileManager {
         self.open_file_with(path, opt).await
     }
 
+    /// Open a file to read, with O_DIRECT if direct I/O is enabled.
+    pub async fn open_direct_file(&self, path: impl AsRef<Path>) -> Result<AsyncFile> {
+        let mut opt = OpenOptions::new();
+        opt.read(true);
+        AsyncFile::open_direct(path.as_ref(), self.fs_runtime.clone(), opt)
+            .await
+            .map_err(|e| Error::OpenFile {
+                path: path.as_ref().to_path_buf(),
+                source: e,
+            })
+    }
+
     fn create_dir_if_not_exists(parent: Option<&Path>) -> Result<()> {
         if let Some(p) = parent {
             if !try_exists(p) {
@@ -88,6 +133,21 @@ impl FileManager {
         self.open_file_with(p, opt).await
     }
 
+    /// Create a file if not exists, overwrite if already existed, with O_DIRECT
+    /// if direct I/O is enabled.
+    pub async fn create_direct_file(&self, path: impl AsRef<Path>) -> Result<AsyncFile> {
+        let p = path.as_ref();
+        Self::create_dir_if_not_exists(p.parent())?;
+        let mut opt = OpenOptions::new();
+        opt.read(true).write(true).create(true);
+        AsyncFile::open_direct(p, self.fs_runtime.clone(), opt)
+            .await
+            .map_err(|e| Error::OpenFile {
+                path: p.to_path_buf(),
+                source: e,
+            })
+    }
+
     /// Open a file to read or write(append mode), if file does not exists then create it.
     pub async fn open_create_file(&self, path: impl AsRef<Path>) -> Result<AsyncFile> {
         let p = path.as_ref();
@@ -171,6 +231,19 @@ pub async fn create_file(path: impl AsRef<Path>) -> Result<AsyncFile> {
     get_file_manager().create_file(path).await
 }
 
+/// Open a file to read, with O_DIRECT if direct I/O is enabled.
+#[inline(always)]
+pub async fn open_direct_file(path: impl AsRef<Path>) -> Result<AsyncFile> {
+    get_file_manager().open_direct_file(path).await
+}
+
+/// Create a file if not exists, overwrite if already existed, with O_DIRECT
+/// if direct I/O is enabled.
+#[inline(always)]
+pub async fn create_direct_file(path: impl AsRef<Path>) -> Result<AsyncFile> {
+    get_file_manager().create_direct_file(path).await
+}
+
 /// Open a file to read or write(append mode), if file does not exists then create it.
 #[inline(always)]
 pub async fn open_create_file(path: impl AsRef<Path>) -> Result<AsyncFile> {
@@ -184,6 +257,7 @@ mod test {
     use trace::info;
 
     use super::FileManager;
+    use crate::file_system::file::async_file::{FsRuntime, IoBackend};
     use crate::file_system::file::cursor::FileCursor;
     use crate::file_system::file::IFile;
     use crate::file_system::file_manager;
@@ -366,39 +440,78 @@ mod test {
         assert_eq!(buf, [0, 1, 2, 3, 4, 0, 0, 0]);
     }
 
-    #[test]
-    #[cfg(feature = "io_uring")]
-    fn test_io_uring() {
-        tokio_uring::start(async {
-            let file = tokio_uring::fs::File::create("/tmp/test/file_manager/hello.txt")
-                .await
-                .unwrap();
-            let (res, buf) = file.write_at(&b"hello"[..], 0).await;
-            let fd = file.as_raw_fd();
-            println!("main thread: file: {:?}, {:?}", file, fd);
-            let (sender, mut receiver) = mpsc::channel();
-            drop(file);
-            let thread = std::thread::spawn(move || {
-                let fd = receiver.blocking_recv().unwrap();
-                println!("thread 2: fd {:?}", fd);
-                tokio_uring::start(async {
-                    tokio_uring::spawn(async move {
-                        let file2 = unsafe { tokio_uring::fs::File::from_raw_fd(fd) };
-                        println!("thread 2: file: {:?}, {:?}", file2, fd);
-                        let (res, buf) = file2.write_at(&b"hello world"[..], 0).await;
-                        let n = res.unwrap();
-                        println!("wrote {} bytes", n);
-                        // // Sync data to the file system.
-                        // file2.sync_all().await.unwrap();
-                        // // Close the file
-                        // file2.close().await.unwrap();
-                    })
-                    .await
-                    .unwrap()
-                })
-            });
-            sender.send(fd).unwrap();
-            thread.join().unwrap();
-        })
+    async fn check_io_backend(file_manager: &FileManager, dir: &str) {
+        let _ = std::fs::remove_dir_all(dir);
+        let path = PathBuf::from(dir).join("test.txt");
+
+        let data: Vec<u8> = (0..10000_u32).map(|i| (i % 251) as u8).collect();
+        {
+            let file = file_manager.create_direct_file(&path).await.unwrap();
+            // Write sequentially in unaligned pieces.
+            let mut pos = 0_u64;
+            for chunk in data.chunks(999) {
+                let len = file.write_at(pos, chunk).await.unwrap();
+                assert_eq!(len, chunk.len());
+                pos += len as u64;
+            }
+            // Overwrite in the middle.
+            file.write_at(5000, &[255, 255, 255]).await.unwrap();
+            file.sync_data().await.unwrap();
+
+            let mut buf = vec![0_u8; 10];
+            let len = file.read_at(4998, &mut buf).await.unwrap();
+            assert_eq!(len, 10);
+            assert_eq!(&buf[0..2], &data[4998..5000]);
+            assert_eq!(&buf[2..5], &[255, 255, 255]);
+            assert_eq!(&buf[5..10], &data[5003..5008]);
+        }
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), data.len() as u64);
+
+        let file = file_manager.open_direct_file(&path).await.unwrap();
+        assert_eq!(file.len(), data.len() as u64);
+        let mut buf = vec![0_u8; 200];
+        let len = file.read_at(9900, &mut buf).await.unwrap();
+        assert_eq!(len, 100);
+        assert_eq!(&buf[..100], &data[9900..]);
+
+        // Not synced writes are written when the file is dropped.
+        {
+            let file = file_manager.create_direct_file(&path).await.unwrap();
+            file.write_at(10000, &[1, 2, 3]).await.unwrap();
+        }
+        let file = file_manager.open_file(&path).await.unwrap();
+        assert_eq!(file.len(), 10003);
+        let mut buf = vec![0_u8; 4];
+        let len = file.read_at(9999, &mut buf).await.unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(buf, [data[9999], 1, 2, 3]);
+
+        {
+            let file = file_manager.create_file(&path).await.unwrap();
+            file.truncate(3).await.unwrap();
+        }
+        let file = file_manager.open_file(&path).await.unwrap();
+        assert_eq!(file.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_io_backends() {
+        let dir = "/tmp/test/file_manager/test_io_backends";
+        for (i, backend) in [IoBackend::Blocking, IoBackend::IoUring]
+            .into_iter()
+            .enumerate()
+        {
+            for direct_io in [false, true] {
+                // io_uring falls back to blocking I/O if not supported.
+                let file_manager = FileManager::with_runtime(
+                    FsRuntime::new(backend, direct_io),
+                    (backend, direct_io),
+                );
+                if backend == IoBackend::Blocking {
+                    assert_eq!(file_manager.io_backend(), IoBackend::Blocking);
+                }
+                check_io_backend(&file_manager, &format!("{dir}/{i}_{direct_io}")).await;
+            }
+        }
     }
 }
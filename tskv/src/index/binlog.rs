@@ -238,6 +238,11 @@ impl BinlogReader {
         Ok(header_buf)
     }
 
+    /// Moves to the first block of the file, including blocks before the read offset.
+    pub fn seek_to_first_block(&mut self) {
+        self.cursor.set_pos(SEGMENT_FILE_HEADER_SIZE as u64);
+    }
+
     pub fn read_over(&mut self) -> bool {
         self.cursor.pos() >= self.cursor.len()
     }
@@ -39,7 +39,7 @@ impl ForwardIndexCache {
     pub fn get_series_id_by_key(&self, key: &SeriesKey) -> Option<SeriesId> {
         let hash = key.hash();
 
-        if let Some(info) = self.hash_map.get(&hash) {
+        if let Some(info) = self.hash_map.get_cloned(&hash) {
             if info.key.eq(key) {
                 return Some(info.id);
             }
@@ -49,6 +49,6 @@ impl ForwardIndexCache {
     }
 
     pub fn get_series_key_by_id(&self, id: SeriesId) -> Option<SeriesKey> {
-        self.id_map.get(&id).map(|info| info.key.clone())
+        self.id_map.get_cloned(&id).map(|info| info.key.clone())
     }
 }
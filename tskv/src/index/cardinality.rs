@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, BinaryArray, BinaryBuilder, StringArray, StringBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::error::ArrowError;
+use datafusion::arrow::record_batch::RecordBatch;
+use utils::HyperLogLog;
+
+/// Schema of record batches to transfer cardinality sketches between nodes,
+/// the name is a table name or a tag key.
+pub fn cardinality_sketches_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("NAME", DataType::Utf8, false),
+        Field::new("SKETCH", DataType::Binary, false),
+    ]))
+}
+
+pub fn cardinality_sketches_to_record_batch(
+    sketches: &[(String, HyperLogLog)],
+) -> Result<RecordBatch, ArrowError> {
+    let mut name_array = StringBuilder::with_capacity(sketches.len(), 32 * sketches.len());
+    let mut sketch_array = BinaryBuilder::new();
+    for (name, sketch) in sketches {
+        name_array.append_value(name);
+        sketch_array.append_value(sketch.encode());
+    }
+    RecordBatch::try_new(
+        cardinality_sketches_schema(),
+        vec![
+            Arc::new(name_array.finish()),
+            Arc::new(sketch_array.finish()),
+        ],
+    )
+}
+
+pub fn record_batch_to_cardinality_sketches(
+    record_batch: &RecordBatch,
+) -> Result<Vec<(String, HyperLogLog)>, ArrowError> {
+    if record_batch.num_columns() != 2 {
+        return Err(ArrowError::SchemaError(format!(
+            "expected 2 columns of cardinality sketches, found {}",
+            record_batch.num_columns()
+        )));
+    }
+    let downcast_err = || ArrowError::CastError("invalid cardinality sketches".to_string());
+    let names = record_batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(downcast_err)?;
+    let sketches = record_batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<BinaryArray>()
+        .ok_or_else(downcast_err)?;
+
+    let mut ret = Vec::with_capacity(names.len());
+    for i in 0..names.len() {
+        let sketch = HyperLogLog::decode(sketches.value(i)).ok_or_else(downcast_err)?;
+        ret.push((names.value(i).to_string(), sketch));
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod test {
+    use utils::HyperLogLog;
+
+    use super::{cardinality_sketches_to_record_batch, record_batch_to_cardinality_sketches};
+
+    #[test]
+    fn test_cardinality_sketches_record_batch() {
+        let mut hll = HyperLogLog::default();
+        hll.insert(b"host=h1");
+        let sketches = vec![
+            ("table_1".to_string(), hll),
+            ("table_2".to_string(), HyperLogLog::default()),
+        ];
+        let record_batch = cardinality_sketches_to_record_batch(&sketches).unwrap();
+        assert_eq!(record_batch.num_rows(), 2);
+        let decoded = record_batch_to_cardinality_sketches(&record_batch).unwrap();
+        assert_eq!(decoded, sketches);
+    }
+}
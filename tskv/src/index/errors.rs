@@ -44,6 +44,23 @@ pub enum IndexError {
 
     #[snafu(display("column '{}' already exists", column))]
     ColumnAlreadyExists { column: String },
+
+    #[snafu(display("series '{}' already exists", series_key))]
+    SeriesAlreadyExists { series_key: String },
+
+    #[snafu(display(
+        "Number of series exceeds {} limit {} of table '{}', rejected series '{}'",
+        limit_name,
+        limit,
+        table,
+        series_key
+    ))]
+    SeriesLimitExceeded {
+        limit_name: &'static str,
+        limit: u64,
+        table: String,
+        series_key: String,
+    },
 }
 
 impl From<sled::Error> for IndexError {
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::fmt;
-use std::ops::{BitAnd, BitOr, Bound, RangeBounds};
+use std::ops::{BitAnd, BitOr, Bound, RangeBounds, RangeInclusive};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
@@ -9,12 +9,15 @@ use std::time::Duration;
 use bytes::BufMut;
 use datafusion::arrow::datatypes::DataType;
 use datafusion::scalar::ScalarValue;
+use metrics::gauge::U64Gauge;
 use models::predicate::domain::{utf8_from, Domain, Range};
-use models::tag::TagFromParts;
+use models::schema::DatabaseOptions;
+use models::tag::{sort_tags, TagFromParts};
 use models::{utils, SeriesKey, Tag};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::sync::RwLock;
 use trace::{debug, error, info};
+use utils::HyperLogLog;
 
 use super::binlog::{IndexBinlog, SeriesKeyBlock};
 use super::cache::{ForwardIndexCache, SeriesKeyInfo};
@@ -28,6 +31,28 @@ use crate::{byte_utils, file_utils};
 const SERIES_ID_PREFIX: &str = "_id_";
 const SERIES_KEY_PREFIX: &str = "_key_";
 const AUTO_INCR_ID_KEY: &str = "_auto_incr_id";
+const MOVED_HASH_RANGES_KEY: &str = "_moved_hash_ranges";
+const TABLE_STATS_PREFIX: &str = "_stats_";
+
+/// Limits of the number of series of a database on this node, `None` means unlimited.
+#[derive(Debug, Default, Clone)]
+pub struct SeriesLimit {
+    pub max_series_per_database: Option<u64>,
+    pub max_series_per_table: Option<u64>,
+    /// Indexes of the other vnodes of the database, series in them are counted
+    /// into the limits as well.
+    pub other_indexes: Vec<Arc<TSIndex>>,
+}
+
+impl From<&DatabaseOptions> for SeriesLimit {
+    fn from(options: &DatabaseOptions) -> Self {
+        Self {
+            max_series_per_database: *options.max_series_per_database(),
+            max_series_per_table: *options.max_series_per_table(),
+            other_indexes: vec![],
+        }
+    }
+}
 
 /// Used to maintain forward and inverted indexes
 ///
@@ -94,6 +119,123 @@ pub struct TSIndex {
     storage: Arc<RwLock<IndexEngine>>,
     forward_cache: ForwardIndexCache,
     binlog_change_sender: UnboundedSender<()>,
+
+    /// Series statistics of each table.
+    table_stats: parking_lot::Mutex<HashMap<String, TableSeriesStats>>,
+    series_cardinality: U64Gauge,
+    /// Hash ranges of series moved into other vnodes by split.
+    moved_hash_ranges: parking_lot::RwLock<Vec<RangeInclusive<u64>>>,
+}
+
+/// Statistics of series of a table in an index, they are persisted in the
+/// storage by `TSIndex::flush_storage`.
+///
+/// Sketches can not remove values, series and tag values deleted are still
+/// counted until all series of the table are deleted.
+#[derive(Debug, Default)]
+struct TableSeriesStats {
+    /// Number of series.
+    count: u64,
+    /// Sketch of series keys.
+    series: HyperLogLog,
+    /// Sketches of tag values of each tag key.
+    tag_values: HashMap<Vec<u8>, HyperLogLog>,
+    /// Whether the stats are changed since written into the storage.
+    changed: bool,
+}
+
+impl TableSeriesStats {
+    /// Adds a series key encoded by `encode_series_key`.
+    fn add_series(&mut self, series_key_buf: &[u8]) {
+        self.count += 1;
+        self.series.insert(series_key_buf);
+        if let Some((_, tags)) = decode_series_key(series_key_buf) {
+            for (tag_key, tag_val) in tags {
+                match self.tag_values.get_mut(tag_key) {
+                    Some(sketch) => sketch.insert(tag_val),
+                    None => {
+                        let mut sketch = HyperLogLog::default();
+                        sketch.insert(tag_val);
+                        self.tag_values.insert(tag_key.to_vec(), sketch);
+                    }
+                }
+            }
+        }
+        self.changed = true;
+    }
+
+    /// Returns false if there is no series to remove.
+    fn remove_series(&mut self) -> bool {
+        match self.count {
+            0 => return false,
+            // Sketches are reset when no series left.
+            1 => *self = Self::default(),
+            _ => self.count -= 1,
+        }
+        self.changed = true;
+        true
+    }
+
+    fn rename_tag(&mut self, tag_key: &[u8], new_tag_key: &[u8]) {
+        if let Some(sketch) = self.tag_values.remove(tag_key) {
+            match self.tag_values.get_mut(new_tag_key) {
+                Some(new_sketch) => {
+                    new_sketch.merge(&sketch);
+                }
+                None => {
+                    self.tag_values.insert(new_tag_key.to_vec(), sketch);
+                }
+            }
+            self.changed = true;
+        }
+    }
+
+    /// Returns bytes of the count and sketches, a sketch is prefixed with the
+    /// length of it, a tag key is prefixed with the length of it.
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.put_u64(self.count);
+        let data = self.series.encode();
+        buf.put_u32(data.len() as u32);
+        buf.extend_from_slice(&data);
+        for (tag_key, sketch) in self.tag_values.iter() {
+            buf.put_u16(tag_key.len() as u16);
+            buf.extend_from_slice(tag_key);
+            let data = sketch.encode();
+            buf.put_u32(data.len() as u32);
+            buf.extend_from_slice(&data);
+        }
+        buf
+    }
+
+    /// Decode bytes returned by `encode()`, returns None if it's invalid.
+    fn decode(data: &[u8]) -> Option<Self> {
+        fn next_part<'a>(data: &'a [u8], pos: &mut usize, len_size: usize) -> Option<&'a [u8]> {
+            let len_buf = data.get(*pos..*pos + len_size)?;
+            let len = len_buf
+                .iter()
+                .fold(0_usize, |len, b| len << 8 | *b as usize);
+            let part = data.get(*pos + len_size..*pos + len_size + len)?;
+            *pos += len_size + len;
+            Some(part)
+        }
+
+        let count = byte_utils::decode_be_u64(data.get(..8)?);
+        let mut pos = 8;
+        let series = HyperLogLog::decode(next_part(data, &mut pos, 4)?)?;
+        let mut tag_values = HashMap::new();
+        while pos < data.len() {
+            let tag_key = next_part(data, &mut pos, 2)?;
+            let sketch = HyperLogLog::decode(next_part(data, &mut pos, 4)?)?;
+            tag_values.insert(tag_key.to_vec(), sketch);
+        }
+        Some(Self {
+            count,
+            series,
+            tag_values,
+            changed: false,
+        })
+    }
 }
 
 impl TSIndex {
@@ -108,6 +250,14 @@ impl TSIndex {
             None => 0,
         };
 
+        let moved_hash_ranges = match storage.get(MOVED_HASH_RANGES_KEY.as_bytes())? {
+            Some(data) => data
+                .chunks_exact(16)
+                .map(|b| byte_utils::decode_be_u64(&b[..8])..=byte_utils::decode_be_u64(&b[8..]))
+                .collect(),
+            None => vec![],
+        };
+
         let (binlog_change_sender, binlog_change_reciver) = unbounded_channel();
 
         let mut ts_index = Self {
@@ -118,8 +268,13 @@ impl TSIndex {
             path: path.into(),
             forward_cache: ForwardIndexCache::new(1_000_000),
             binlog_change_sender,
+            table_stats: parking_lot::Mutex::new(HashMap::new()),
+            series_cardinality: U64Gauge::default(),
+            moved_hash_ranges: parking_lot::RwLock::new(moved_hash_ranges),
         };
 
+        // Series recovered from the binlog are counted into the stats loaded.
+        ts_index.load_series_stats().await?;
         ts_index.recover().await?;
         let ts_index = Arc::new(ts_index);
         run_index_job(ts_index.clone(), binlog_change_reciver);
@@ -131,6 +286,34 @@ impl TSIndex {
         Ok(ts_index)
     }
 
+    /// Creates an index at `path` with `series` keeping their series ids,
+    /// series ids allocated later by the index start after `min_incr_id`.
+    pub async fn rebuild(
+        path: impl AsRef<Path>,
+        series: Vec<(u32, SeriesKey)>,
+        min_incr_id: u32,
+    ) -> IndexResult<Arc<Self>> {
+        let path = path.as_ref();
+        {
+            let mut binlog = IndexBinlog::new(path).await?;
+            let mut blocks_data = Vec::new();
+            for (id, series_key) in series {
+                let block =
+                    SeriesKeyBlock::new(utils::now_timestamp_nanos(), id, series_key.encode());
+                blocks_data.extend_from_slice(&block.encode());
+            }
+            binlog.write(&blocks_data).await?;
+            binlog.close().await?;
+        }
+
+        // Series in the binlog are recovered into the storage.
+        let ts_index = Self::new(path).await?;
+        ts_index.incr_id.fetch_max(min_incr_id, Ordering::Relaxed);
+        ts_index.flush().await?;
+
+        Ok(ts_index)
+    }
+
     async fn recover(&mut self) -> IndexResult<()> {
         let path = self.path.clone();
         let files = file_manager::list_file_names(&path);
@@ -157,7 +340,12 @@ impl TSIndex {
 
                 let mut storage_w = self.storage.write().await;
                 let id = block.series_id;
+                self.remove_replaced_series_key(&mut storage_w, id, &series_key)?;
                 let key_buf = encode_series_key(series_key.table(), series_key.tags());
+                // Series keys flushed into the storage are already counted.
+                if storage_w.get(&key_buf)?.is_none() {
+                    self.add_series_stats(series_key.table(), &key_buf);
+                }
                 storage_w.set(&key_buf, &id.to_be_bytes())?;
                 storage_w.set(&encode_series_id_key(id), &block.data)?;
                 for tag in series_key.tags() {
@@ -180,16 +368,191 @@ impl TSIndex {
         self.incr_id.store(max_id, Ordering::Relaxed);
 
         let id_bytes = self.incr_id.load(Ordering::Relaxed).to_be_bytes();
-        self.storage
-            .write()
-            .await
-            .set(AUTO_INCR_ID_KEY.as_bytes(), &id_bytes)?;
-        self.storage.write().await.flush()?;
+        let mut storage_w = self.storage.write().await;
+        storage_w.set(AUTO_INCR_ID_KEY.as_bytes(), &id_bytes)?;
+        self.flush_storage(&mut storage_w)?;
+        drop(storage_w);
         reader_file.advance_read_offset(0).await?;
 
         Ok(())
     }
 
+    /// Loads series statistics persisted in the storage, or builds them from
+    /// series keys in the storage if the index has no statistics persisted.
+    async fn load_series_stats(&self) -> IndexResult<()> {
+        let mut table_stats: HashMap<String, TableSeriesStats> = HashMap::new();
+        let storage_r = self.storage.read().await;
+        for item in storage_r.prefix(TABLE_STATS_PREFIX.as_bytes())? {
+            let item = item.map_err(|e| IndexError::IndexStroage { msg: e.to_string() })?;
+            // Inverted index keys of a table named like '_stats_xxx' also have the prefix.
+            let table = match decode_table_stats_key(item.0.as_ref()) {
+                Some(table) => table,
+                None => continue,
+            };
+            if let Some(stats) = TableSeriesStats::decode(&storage_r.load(&item.1)?) {
+                table_stats.insert(table.to_string(), stats);
+            }
+        }
+        if table_stats.is_empty() {
+            for item in storage_r.prefix(SERIES_KEY_PREFIX.as_bytes())? {
+                let item = item.map_err(|e| IndexError::IndexStroage { msg: e.to_string() })?;
+                let key_buf = item.0.as_ref();
+                // Inverted index keys of a table named like '_key_xxx' also have the prefix.
+                if let Some((table, _)) = decode_series_key(key_buf) {
+                    add_table_series(&mut table_stats, table, key_buf);
+                }
+            }
+        }
+        self.series_cardinality
+            .set(table_stats.values().map(|s| s.count).sum());
+        *self.table_stats.lock() = table_stats;
+
+        Ok(())
+    }
+
+    /// Number of series in the index.
+    pub fn series_cardinality(&self) -> u64 {
+        self.series_cardinality.fetch()
+    }
+
+    /// Gauge of the number of series in the index, for registering into metrics.
+    pub fn series_cardinality_gauge(&self) -> U64Gauge {
+        self.series_cardinality.clone()
+    }
+
+    /// Number of series of the table in the index.
+    pub fn table_series_cardinality(&self, tab: &str) -> u64 {
+        self.table_stats
+            .lock()
+            .get(tab)
+            .map(|s| s.count)
+            .unwrap_or(0)
+    }
+
+    /// Returns sketches of series keys of each table, or only of `table` if
+    /// it's specified, tables without any series are skipped. The estimate is
+    /// approximate after series are deleted: deleted series are still counted
+    /// until all series of the table are deleted.
+    pub fn series_cardinality_sketches(&self, table: Option<&str>) -> Vec<(String, HyperLogLog)> {
+        self.table_stats
+            .lock()
+            .iter()
+            .filter(|(tab, s)| s.count > 0 && table.map_or(true, |t| t == tab.as_str()))
+            .map(|(tab, s)| (tab.clone(), s.series.clone()))
+            .collect()
+    }
+
+    /// Returns sketches of tag values of each tag key in `tag_keys` of the table,
+    /// tag keys without any values are skipped. Like series sketches, values of
+    /// deleted series are still counted until all series of the table are deleted.
+    pub fn tag_values_cardinality_sketches(
+        &self,
+        table: &str,
+        tag_keys: &[String],
+    ) -> Vec<(String, HyperLogLog)> {
+        let table_stats = self.table_stats.lock();
+        let stats = match table_stats.get(table) {
+            Some(stats) => stats,
+            None => return vec![],
+        };
+        tag_keys
+            .iter()
+            .filter_map(|tag_key| {
+                let sketch = stats.tag_values.get(tag_key.as_bytes())?;
+                Some((tag_key.clone(), sketch.clone()))
+            })
+            .collect()
+    }
+
+    /// Count new series, returns error without counting any of them if they
+    /// exceed `limit`.
+    fn incr_series_count(
+        &self,
+        new_series: &[(SeriesKey, Vec<u8>)],
+        limit: &SeriesLimit,
+    ) -> IndexResult<()> {
+        // Counts of other indexes are read before locking this one, so that
+        // indexes checking limits concurrently do not lock each other.
+        let mut database_count: u64 = limit
+            .other_indexes
+            .iter()
+            .map(|idx| idx.series_cardinality())
+            .sum();
+        let mut table_counts: HashMap<&str, u64> = HashMap::new();
+        if limit.max_series_per_table.is_some() {
+            for (series_key, _) in new_series {
+                table_counts.entry(series_key.table()).or_insert_with(|| {
+                    limit
+                        .other_indexes
+                        .iter()
+                        .map(|idx| idx.table_series_cardinality(series_key.table()))
+                        .sum()
+                });
+            }
+        }
+
+        let mut table_stats = self.table_stats.lock();
+        database_count += self.series_cardinality.fetch();
+        for (table, count) in table_counts.iter_mut() {
+            *count += table_stats.get(*table).map(|s| s.count).unwrap_or(0);
+        }
+        for (series_key, _) in new_series {
+            let limit_exceeded = |limit_name: &'static str, limit: u64| {
+                Err(IndexError::SeriesLimitExceeded {
+                    limit_name,
+                    limit,
+                    table: series_key.table().to_string(),
+                    series_key: series_key.to_string(),
+                })
+            };
+            if let Some(max) = limit.max_series_per_database {
+                if database_count >= max {
+                    return limit_exceeded("max_series_per_database", max);
+                }
+            }
+            database_count += 1;
+            if let Some(max) = limit.max_series_per_table {
+                let table_count = table_counts.entry(series_key.table()).or_default();
+                if *table_count >= max {
+                    return limit_exceeded("max_series_per_table", max);
+                }
+                *table_count += 1;
+            }
+        }
+
+        for (series_key, series_key_buf) in new_series {
+            add_table_series(&mut table_stats, series_key.table(), series_key_buf);
+        }
+        self.series_cardinality.inc(new_series.len() as u64);
+
+        Ok(())
+    }
+
+    fn add_series_stats(&self, tab: &str, series_key_buf: &[u8]) {
+        add_table_series(&mut self.table_stats.lock(), tab, series_key_buf);
+        self.series_cardinality.inc(1);
+    }
+
+    fn decr_series_count(&self, tab: &str) {
+        if let Some(stats) = self.table_stats.lock().get_mut(tab) {
+            if stats.remove_series() {
+                self.series_cardinality.dec(1);
+            }
+        }
+    }
+
+    /// Flushes the storage with series statistics changed, so statistics
+    /// persisted are consistent with series keys persisted.
+    fn flush_storage(&self, storage_w: &mut IndexEngine) -> IndexResult<()> {
+        for (tab, stats) in self.table_stats.lock().iter_mut() {
+            if stats.changed {
+                storage_w.set(&encode_table_stats_key(tab), &stats.encode())?;
+                stats.changed = false;
+            }
+        }
+        storage_w.flush()
+    }
+
     pub async fn get_series_id(&self, series_key: &SeriesKey) -> IndexResult<Option<u32>> {
         if let Some(id) = self.forward_cache.get_series_id_by_key(series_key) {
             return Ok(Some(id));
@@ -235,41 +598,64 @@ impl TSIndex {
         Ok(None)
     }
 
+    /// Returns series ids of `series_keys`, new series are added if the
+    /// number of series does not exceed `limit`, otherwise returns
+    /// `IndexError::SeriesLimitExceeded` and none of the new series are added.
     pub async fn add_series_if_not_exists(
         &self,
         series_keys: Vec<SeriesKey>,
+        limit: &SeriesLimit,
     ) -> IndexResult<Vec<u32>> {
-        let mut ids = Vec::with_capacity(series_keys.len());
-        let mut blocks_data = Vec::new();
+        let mut storage_w = self.storage.write().await;
+
+        // Series ids of existing series, or indexes of new series in `new_series`.
+        let mut ids: Vec<Result<u32, usize>> = Vec::with_capacity(series_keys.len());
+        let mut new_series: Vec<(SeriesKey, Vec<u8>)> = Vec::new();
+        let mut new_series_index: HashMap<Vec<u8>, usize> = HashMap::new();
         for series_key in series_keys.into_iter() {
-            let encode = series_key.encode();
             let key_buf = encode_series_key(series_key.table(), series_key.tags());
-            {
-                let mut storage_w = self.storage.write().await;
-                if let Some(val) = storage_w.get(&key_buf)? {
-                    ids.push(byte_utils::decode_be_u32(&val));
-                    continue;
-                }
-                let id = self.incr_id.fetch_add(1, Ordering::Relaxed) + 1;
-                storage_w.set(&key_buf, &id.to_be_bytes())?;
-                let block = SeriesKeyBlock {
-                    ts: utils::now_timestamp_nanos(),
-                    series_id: id,
-                    data_len: encode.len() as u32,
-                    data: encode,
-                };
-                ids.push(id);
-                blocks_data.extend_from_slice(&block.encode());
+            if let Some(val) = storage_w.get(&key_buf)? {
+                ids.push(Ok(byte_utils::decode_be_u32(&val)));
+            } else if let Some(i) = new_series_index.get(&key_buf) {
+                ids.push(Err(*i));
+            } else {
+                new_series_index.insert(key_buf.clone(), new_series.len());
+                ids.push(Err(new_series.len()));
+                new_series.push((series_key, key_buf));
             }
         }
+        if new_series.is_empty() {
+            return Ok(ids.into_iter().flatten().collect());
+        }
+        self.incr_series_count(&new_series, limit)?;
+
+        let mut new_ids = Vec::with_capacity(new_series.len());
+        let mut blocks_data = Vec::new();
+        for (series_key, key_buf) in new_series {
+            let id = self.incr_id.fetch_add(1, Ordering::Relaxed) + 1;
+            storage_w.set(&key_buf, &id.to_be_bytes())?;
+            let encode = series_key.encode();
+            let block = SeriesKeyBlock {
+                ts: utils::now_timestamp_nanos(),
+                series_id: id,
+                data_len: encode.len() as u32,
+                data: encode,
+            };
+            new_ids.push(id);
+            blocks_data.extend_from_slice(&block.encode());
+        }
         self.binlog.write().await.write(&blocks_data).await?;
+        drop(storage_w);
         self.binlog_change_sender
             .send(())
             .map_err(|e| IndexError::IndexStroage {
                 msg: format!("Send binlog change failed, err: {}", e),
             })?;
 
-        Ok(ids)
+        Ok(ids
+            .into_iter()
+            .map(|id| id.unwrap_or_else(|i| new_ids[i]))
+            .collect())
     }
 
     async fn check_to_flush(&self, force: bool) -> IndexResult<()> {
@@ -281,7 +667,7 @@ impl TSIndex {
         let mut storage_w = self.storage.write().await;
         let id_bytes = self.incr_id.load(Ordering::Relaxed).to_be_bytes();
         storage_w.set(AUTO_INCR_ID_KEY.as_bytes(), &id_bytes)?;
-        storage_w.flush()?;
+        self.flush_storage(&mut storage_w)?;
 
         let current_id;
         {
@@ -340,6 +726,9 @@ impl TSIndex {
         if let Some(series_key) = series_key {
             self.forward_cache.del(sid, series_key.hash());
             let key_buf = encode_series_key(series_key.table(), series_key.tags());
+            if let Ok(Some(_)) = storage_w.get(&key_buf) {
+                self.decr_series_count(series_key.table());
+            }
             let _ = storage_w.delete(&key_buf);
             for tag in series_key.tags() {
                 let key = encode_inverted_index_key(series_key.table(), &tag.key, &tag.value);
@@ -350,6 +739,176 @@ impl TSIndex {
         Ok(())
     }
 
+    /// Renames the table of series keys of table `tab` to `new_tab`.
+    pub async fn rename_table(&self, tab: &str, new_tab: &str) -> IndexResult<()> {
+        self.rename_series_keys(
+            tab,
+            |series_key| {
+                series_key.table = new_tab.to_string();
+            },
+            |storage_w, table_stats| {
+                if let Some(mut stats) = table_stats.remove(tab) {
+                    storage_w.delete(&encode_table_stats_key(tab))?;
+                    stats.changed = true;
+                    table_stats.insert(new_tab.to_string(), stats);
+                }
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Renames the tag key `tag_key` of series keys of table `tab` to `new_tag_key`.
+    pub async fn rename_tag(&self, tab: &str, tag_key: &str, new_tag_key: &str) -> IndexResult<()> {
+        self.rename_series_keys(
+            tab,
+            |series_key| {
+                for tag in series_key.tags.iter_mut() {
+                    if tag.key == tag_key.as_bytes() {
+                        tag.key = new_tag_key.as_bytes().to_vec();
+                    }
+                }
+                sort_tags(&mut series_key.tags);
+            },
+            |_, table_stats| {
+                if let Some(stats) = table_stats.get_mut(tab) {
+                    stats.rename_tag(tag_key.as_bytes(), new_tag_key.as_bytes());
+                }
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Replaces series keys of table `tab` with the keys changed by `rename`,
+    /// series ids are not changed. Series keys and series statistics changed by
+    /// `rename_stats` are replaced at once, the forward index and inverted index
+    /// are replaced when the binlog is handled.
+    async fn rename_series_keys(
+        &self,
+        tab: &str,
+        rename: impl Fn(&mut SeriesKey),
+        rename_stats: impl FnOnce(
+            &mut IndexEngine,
+            &mut HashMap<String, TableSeriesStats>,
+        ) -> IndexResult<()>,
+    ) -> IndexResult<()> {
+        let mut storage_w = self.storage.write().await;
+        let mut series = Vec::new();
+        for item in storage_w.prefix(SERIES_KEY_PREFIX.as_bytes())? {
+            let item = item.map_err(|e| IndexError::IndexStroage { msg: e.to_string() })?;
+            let key_buf = item.0.as_ref();
+            let (table, tags) = match decode_series_key(key_buf) {
+                Some((table, tags)) if table == tab => (table, tags),
+                _ => continue,
+            };
+            // Inverted index keys of a table named like '_key_xxx' are not series ids.
+            let val = storage_w.load(&item.1)?;
+            if val.len() != 4 {
+                continue;
+            }
+            let sid = byte_utils::decode_be_u32(&val);
+            let tags = tags
+                .into_iter()
+                .map(|(k, v)| Tag::new(k.to_vec(), v.to_vec()))
+                .collect();
+            series.push((
+                key_buf.to_vec(),
+                SeriesKey {
+                    id: sid,
+                    tags,
+                    table: table.to_string(),
+                    db: String::new(),
+                },
+            ));
+        }
+
+        let mut renamed = Vec::with_capacity(series.len());
+        for (key_buf, mut series_key) in series {
+            if let Some(data) = storage_w.get(&encode_series_id_key(series_key.id))? {
+                if let Ok(stored_key) = SeriesKey::decode(&data) {
+                    series_key.db = stored_key.db;
+                }
+            }
+            let old_hash = series_key.hash();
+            rename(&mut series_key);
+            let new_key_buf = encode_series_key(series_key.table(), series_key.tags());
+            if storage_w.get(&new_key_buf)?.is_some() {
+                return Err(IndexError::SeriesAlreadyExists {
+                    series_key: series_key.string(),
+                });
+            }
+            renamed.push((key_buf, new_key_buf, old_hash, series_key));
+        }
+
+        let mut blocks_data = Vec::new();
+        for (key_buf, new_key_buf, old_hash, series_key) in renamed {
+            let sid = series_key.id;
+            storage_w.delete(&key_buf)?;
+            storage_w.set(&new_key_buf, &sid.to_be_bytes())?;
+            self.forward_cache.del(sid, old_hash);
+            let encode = series_key.encode();
+            let block = SeriesKeyBlock {
+                ts: utils::now_timestamp_nanos(),
+                series_id: sid,
+                data_len: encode.len() as u32,
+                data: encode,
+            };
+            blocks_data.extend_from_slice(&block.encode());
+        }
+        rename_stats(&mut *storage_w, &mut *self.table_stats.lock())?;
+        drop(storage_w);
+
+        if blocks_data.is_empty() {
+            return Ok(());
+        }
+        self.binlog.write().await.write(&blocks_data).await?;
+        self.binlog_change_sender
+            .send(())
+            .map_err(|e| IndexError::IndexStroage {
+                msg: format!("Send binlog change failed, err: {}", e),
+            })?;
+
+        Ok(())
+    }
+
+    /// Removes the forward index and inverted index of the series key stored
+    /// for `sid` if it's replaced by a different `series_key`.
+    fn remove_replaced_series_key(
+        &self,
+        storage_w: &mut IndexEngine,
+        sid: u32,
+        series_key: &SeriesKey,
+    ) -> IndexResult<()> {
+        let old_key = match storage_w.get(&encode_series_id_key(sid))? {
+            Some(data) => SeriesKey::decode(&data)
+                .map_err(|e| IndexError::DecodeSeriesKey { msg: e.to_string() })?,
+            None => return Ok(()),
+        };
+        if old_key.table() == series_key.table() && old_key.tags() == series_key.tags() {
+            return Ok(());
+        }
+
+        self.forward_cache.del(sid, old_key.hash());
+        let old_key_buf = encode_series_key(old_key.table(), old_key.tags());
+        if let Some(val) = storage_w.get(&old_key_buf)? {
+            if byte_utils::decode_be_u32(&val) == sid {
+                storage_w.delete(&old_key_buf)?;
+                self.decr_series_count(old_key.table());
+            }
+        }
+        for tag in old_key.tags() {
+            let key = encode_inverted_index_key(old_key.table(), &tag.key, &tag.value);
+            storage_w.modify(&key, sid, false)?;
+        }
+        if old_key.tags().is_empty() {
+            let key = encode_inverted_index_key(old_key.table(), &[], &[]);
+            storage_w.modify(&key, sid, false)?;
+        }
+
+        Ok(())
+    }
+
     pub async fn get_series_ids_by_domains(
         &self,
         tab: &str,
@@ -459,8 +1018,46 @@ impl TSIndex {
                     }
                 } else {
                     // Does not contain a given value, that is, a value other than a given value
-                    // TODO will not deal with this situation for the time being
-                    bitmap = self.get_series_id_bitmap(tab, &[]).await?;
+                    let excluded: Vec<&[u8]> = val
+                        .entries()
+                        .into_iter()
+                        .filter_map(|e| utf8_from(e.value()).map(|v| v.as_bytes()))
+                        .collect();
+                    let prefix = encode_inverted_index_key(tab, tag_key.as_bytes(), &[]);
+                    for item in storage_r.prefix(&prefix)? {
+                        let item =
+                            item.map_err(|e| IndexError::IndexStroage { msg: e.to_string() })?;
+                        let tag_val = &item.0.as_ref()[prefix.len()..];
+                        if !excluded.contains(&tag_val) {
+                            bitmap = bitmap.bitor(storage_r.load_rb(&item.1)?);
+                        }
+                    }
+                }
+            }
+            Domain::Pattern(pattern_set) => {
+                let matcher = match pattern_set.matcher() {
+                    Ok(m) => m,
+                    Err(e) => {
+                        // Filter will be checked again by the query engine.
+                        debug!("Index get sids: ignore invalid pattern: {}", e);
+                        return self.get_series_id_bitmap(tab, &[]).await;
+                    }
+                };
+                // Anchored patterns only need to scan the tag values with the literal prefix,
+                // otherwise all tag values of the tag key are scanned.
+                let value_offset = encode_inverted_index_key(tab, tag_key.as_bytes(), &[]).len();
+                let prefix = encode_inverted_index_key(
+                    tab,
+                    tag_key.as_bytes(),
+                    pattern_set.literal_prefix().as_bytes(),
+                );
+                let storage_r = self.storage.read().await;
+                for item in storage_r.prefix(&prefix)? {
+                    let item = item.map_err(|e| IndexError::IndexStroage { msg: e.to_string() })?;
+                    let tag_val = String::from_utf8_lossy(&item.0.as_ref()[value_offset..]);
+                    if matcher.matches(&tag_val) {
+                        bitmap = bitmap.bitor(storage_r.load_rb(&item.1)?);
+                    }
                 }
             }
             Domain::None => {
@@ -482,11 +1079,72 @@ impl TSIndex {
         self.path.clone()
     }
 
+    /// Returns the max series id allocated by the index.
+    pub fn max_series_id(&self) -> u32 {
+        self.incr_id.load(Ordering::Relaxed)
+    }
+
+    /// Marks series whose hash is in `range` moved into another vnode by
+    /// split, points of them are rejected by the vnode since then.
+    pub async fn add_moved_hash_range(&self, range: RangeInclusive<u64>) -> IndexResult<()> {
+        let mut ranges = self.moved_hash_ranges.read().clone();
+        if ranges.contains(&range) {
+            return Ok(());
+        }
+        ranges.push(range);
+        let mut buf = Vec::with_capacity(ranges.len() * 16);
+        for r in ranges.iter() {
+            buf.put_u64(*r.start());
+            buf.put_u64(*r.end());
+        }
+        {
+            let mut storage_w = self.storage.write().await;
+            storage_w.set(MOVED_HASH_RANGES_KEY.as_bytes(), &buf)?;
+            self.flush_storage(&mut storage_w)?;
+        }
+        *self.moved_hash_ranges.write() = ranges;
+
+        Ok(())
+    }
+
+    /// Returns true if any series is moved into another vnode by split.
+    pub fn has_moved_series(&self) -> bool {
+        !self.moved_hash_ranges.read().is_empty()
+    }
+
+    /// Returns true if the series is moved into another vnode by split.
+    pub fn is_moved(&self, series_key: &SeriesKey) -> bool {
+        let hash = series_key.hash();
+        self.moved_hash_ranges
+            .read()
+            .iter()
+            .any(|r| r.contains(&hash))
+    }
+
     pub async fn flush(&self) -> IndexResult<()> {
         self.check_to_flush(true).await?;
 
         Ok(())
     }
+
+    /// Copies files of the index into `dir`. Writes to the index are blocked
+    /// until the copy is finished, so the copied files are a consistent snapshot.
+    pub async fn snapshot_to(&self, dir: impl AsRef<Path>) -> IndexResult<()> {
+        let dir = dir.as_ref();
+        tokio::fs::create_dir_all(dir).await?;
+
+        let mut storage_w = self.storage.write().await;
+        let id_bytes = self.incr_id.load(Ordering::Relaxed).to_be_bytes();
+        storage_w.set(AUTO_INCR_ID_KEY.as_bytes(), &id_bytes)?;
+        self.flush_storage(&mut storage_w)?;
+        // Binlog blocks not yet applied to the storage are replayed when the copy is opened.
+        let _binlog_w = self.binlog.write().await;
+        for filename in file_manager::list_file_names(&self.path) {
+            tokio::fs::copy(self.path.join(&filename), dir.join(&filename)).await?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Debug for TSIndex {
@@ -614,6 +1272,59 @@ pub fn encode_series_key(tab: &str, tags: &[Tag]) -> Vec<u8> {
     buf
 }
 
+fn add_table_series(
+    table_stats: &mut HashMap<String, TableSeriesStats>,
+    tab: &str,
+    series_key_buf: &[u8],
+) {
+    match table_stats.get_mut(tab) {
+        Some(stats) => stats.add_series(series_key_buf),
+        None => {
+            let mut stats = TableSeriesStats::default();
+            stats.add_series(series_key_buf);
+            table_stats.insert(tab.to_string(), stats);
+        }
+    }
+}
+
+fn encode_table_stats_key(tab: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(TABLE_STATS_PREFIX.len() + 2 + tab.len());
+    buf.extend_from_slice(TABLE_STATS_PREFIX.as_bytes());
+    buf.put_u16(tab.len() as u16);
+    buf.extend_from_slice(tab.as_bytes());
+    buf
+}
+
+/// Returns the table name of a key encoded by `encode_table_stats_key`.
+fn decode_table_stats_key(key: &[u8]) -> Option<&str> {
+    let pos = TABLE_STATS_PREFIX.len();
+    let len_buf = key.get(pos..pos + 2)?;
+    let len = u16::from_be_bytes([len_buf[0], len_buf[1]]) as usize;
+    if key.len() != pos + 2 + len {
+        return None;
+    }
+    std::str::from_utf8(&key[pos + 2..]).ok()
+}
+
+/// Returns the table name and tags of a key encoded by `encode_series_key`.
+fn decode_series_key(key: &[u8]) -> Option<(&str, Vec<(&[u8], &[u8])>)> {
+    fn next_part<'a>(key: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+        let len_buf = key.get(*pos..*pos + 2)?;
+        let len = u16::from_be_bytes([len_buf[0], len_buf[1]]) as usize;
+        let part = key.get(*pos + 2..*pos + 2 + len)?;
+        *pos += 2 + len;
+        Some(part)
+    }
+
+    let mut pos = SERIES_KEY_PREFIX.len();
+    let table = std::str::from_utf8(next_part(key, &mut pos)?).ok()?;
+    let mut tags = Vec::new();
+    while pos < key.len() {
+        tags.push((next_part(key, &mut pos)?, next_part(key, &mut pos)?));
+    }
+    Some((table, tags))
+}
+
 pub fn decode_series_id_list(data: &[u8]) -> IndexResult<Vec<u32>> {
     if data.len() % 4 != 0 {
         return Err(IndexError::DecodeSeriesIDList);
@@ -701,6 +1412,13 @@ pub fn run_index_job(ts_index: Arc<TSIndex>, mut binlog_change_reciver: Unbounde
                                         }
                                     }
                                 }
+                                if let Err(e) = ts_index.remove_replaced_series_key(
+                                    &mut storage_w,
+                                    block.series_id,
+                                    &series_key,
+                                ) {
+                                    error!("Remove replaced series key failed, err: {}", e);
+                                }
                                 if let Err(e) = storage_w
                                     .set(&encode_series_id_key(block.series_id), &block.data)
                                 {
@@ -754,10 +1472,13 @@ mod test {
     use std::time::Duration;
 
     use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::scalar::ScalarValue;
+    use models::predicate::domain::{Domain, PatternMatch};
     use models::schema::ExternalTableSchema;
     use models::{SeriesId, SeriesKey, Tag};
 
-    use super::TSIndex;
+    use super::{SeriesLimit, TSIndex};
+    use crate::index::IndexError;
 
     /// ( sid, database, table, [(tag_key, tag_value)] )
     type SeriesKeyDesc<'a> = (SeriesId, &'a str, &'a str, Vec<(&'a str, &'a str)>);
@@ -818,7 +1539,7 @@ mod test {
             let mut series_keys_sids = Vec::with_capacity(series_keys_desc.len());
             for (i, series_key) in series_keys.iter().enumerate() {
                 let sid = ts_index
-                    .add_series_if_not_exists(vec![series_key.clone()])
+                    .add_series_if_not_exists(vec![series_key.clone()], &SeriesLimit::default())
                     .await
                     .unwrap();
                 tokio::time::sleep(Duration::from_millis(100)).await;
@@ -896,7 +1617,7 @@ mod test {
             let prev_max_sid = max_sid;
             for (i, series_key) in series_keys.iter().enumerate() {
                 let sid = ts_index
-                    .add_series_if_not_exists(vec![series_key.clone()])
+                    .add_series_if_not_exists(vec![series_key.clone()], &SeriesLimit::default())
                     .await
                     .unwrap();
                 tokio::time::sleep(Duration::from_millis(100)).await;
@@ -924,7 +1645,7 @@ mod test {
         let prev_max_sid = max_sid;
         for (i, series_key) in series_keys.iter().enumerate() {
             let sid = ts_index
-                .add_series_if_not_exists(vec![series_key.clone()])
+                .add_series_if_not_exists(vec![series_key.clone()], &SeriesLimit::default())
                 .await
                 .unwrap();
             tokio::time::sleep(Duration::from_millis(100)).await;
@@ -936,6 +1657,335 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_series_ids_by_domain() {
+        let dir = "/tmp/test/ts_index/2";
+        let _ = std::fs::remove_dir_all(dir);
+        let database = "db_test";
+        let table = "table_test";
+        #[rustfmt::skip]
+        let series_keys_desc: Vec<SeriesKeyDesc> = vec![
+            (0, database, table, vec![("host", "web-1")]),
+            (0, database, table, vec![("host", "web-2")]),
+            (0, database, table, vec![("host", "db-1")]),
+            (0, database, table, vec![("host", "WEB-3")]),
+        ];
+        let series_keys = build_series_keys(&series_keys_desc);
+        let ts_index = TSIndex::new(dir).await.unwrap();
+        let sids = ts_index
+            .add_series_if_not_exists(series_keys, &SeriesLimit::default())
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let get_sids = |domain: Domain| {
+            let ts_index = ts_index.clone();
+            async move {
+                let mut list: Vec<u32> = ts_index
+                    .get_series_ids_by_domain(table, "host", &domain)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .collect();
+                list.sort();
+                list
+            }
+        };
+
+        // host LIKE 'web-%'
+        let domain = Domain::of_pattern(PatternMatch::like("web-%", None, false, false));
+        assert_eq!(get_sids(domain).await, vec![sids[0], sids[1]]);
+        // host ILIKE 'web-%'
+        let domain = Domain::of_pattern(PatternMatch::like("web-%", None, true, false));
+        assert_eq!(get_sids(domain).await, vec![sids[0], sids[1], sids[3]]);
+        // host !~ '-1$'
+        let domain = Domain::of_pattern(PatternMatch::regex("-1$", false, true));
+        assert_eq!(get_sids(domain).await, vec![sids[1], sids[3]]);
+        // host NOT IN ('web-1', 'db-1')
+        let v = |v: &str| ScalarValue::Utf8(Some(v.to_string()));
+        let domain = Domain::of_values(&DataType::Utf8, false, &[&v("web-1"), &v("db-1")]);
+        assert_eq!(get_sids(domain).await, vec![sids[1], sids[3]]);
+    }
+
+    #[tokio::test]
+    async fn test_series_limit() {
+        let dir = "/tmp/test/ts_index/3";
+        let _ = std::fs::remove_dir_all(dir);
+        let database = "db_test";
+        #[rustfmt::skip]
+        let series_keys_desc: Vec<SeriesKeyDesc> = vec![
+            (0, database, "tab_1", vec![("host", "h1")]),
+            (0, database, "tab_1", vec![("host", "h2")]),
+            (0, database, "tab_2", vec![("host", "h1")]),
+            (0, database, "tab_1", vec![("host", "h3")]),
+            (0, database, "tab_2", vec![("host", "h2")]),
+        ];
+        let series_keys = build_series_keys(&series_keys_desc);
+        let limit = SeriesLimit {
+            max_series_per_database: Some(4),
+            max_series_per_table: Some(2),
+            other_indexes: vec![],
+        };
+
+        {
+            let ts_index = TSIndex::new(dir).await.unwrap();
+            ts_index
+                .add_series_if_not_exists(series_keys[..3].to_vec(), &limit)
+                .await
+                .unwrap();
+            assert_eq!(ts_index.series_cardinality(), 3);
+            assert_eq!(ts_index.table_series_cardinality("tab_1"), 2);
+
+            // Existing series are not limited.
+            ts_index
+                .add_series_if_not_exists(series_keys[..2].to_vec(), &limit)
+                .await
+                .unwrap();
+            let err = ts_index
+                .add_series_if_not_exists(vec![series_keys[3].clone()], &limit)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                IndexError::SeriesLimitExceeded { limit_name: "max_series_per_table", ref table, .. } if table == "tab_1"
+            ));
+            // None of the series in a batch exceeding the limit are added.
+            let err = ts_index
+                .add_series_if_not_exists(
+                    vec![series_keys[4].clone(), series_keys[3].clone()],
+                    &limit,
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                IndexError::SeriesLimitExceeded {
+                    limit_name: "max_series_per_table",
+                    ..
+                }
+            ));
+            assert_eq!(ts_index.series_cardinality(), 3);
+            assert_eq!(ts_index.table_series_cardinality("tab_2"), 1);
+            assert_eq!(ts_index.get_series_id(&series_keys[4]).await.unwrap(), None);
+            ts_index.flush().await.unwrap();
+        }
+
+        {
+            // Series counts are recovered after re-open.
+            let ts_index = TSIndex::new(dir).await.unwrap();
+            assert_eq!(ts_index.series_cardinality(), 3);
+            let mut sketches = ts_index.series_cardinality_sketches(None);
+            sketches.sort_by(|a, b| a.0.cmp(&b.0));
+            assert_eq!(sketches.len(), 2);
+            assert_eq!(
+                (sketches[0].0.as_str(), sketches[0].1.estimate()),
+                ("tab_1", 2)
+            );
+            assert_eq!(
+                (sketches[1].0.as_str(), sketches[1].1.estimate()),
+                ("tab_2", 1)
+            );
+            let sketches = ts_index
+                .tag_values_cardinality_sketches("tab_1", &["host".to_string(), "ip".to_string()]);
+            assert_eq!(sketches.len(), 1);
+            assert_eq!(sketches[0].1.estimate(), 2);
+            ts_index
+                .add_series_if_not_exists(vec![series_keys[4].clone()], &limit)
+                .await
+                .unwrap();
+            let err = ts_index
+                .add_series_if_not_exists(
+                    vec![series_keys[3].clone()],
+                    &SeriesLimit {
+                        max_series_per_database: Some(4),
+                        max_series_per_table: None,
+                        other_indexes: vec![],
+                    },
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                IndexError::SeriesLimitExceeded {
+                    limit_name: "max_series_per_database",
+                    ..
+                }
+            ));
+
+            // Series in other vnodes of the database are counted.
+            let other_dir = "/tmp/test/ts_index/3_other";
+            let _ = std::fs::remove_dir_all(other_dir);
+            let other_index = TSIndex::new(other_dir).await.unwrap();
+            let limit = SeriesLimit {
+                max_series_per_database: Some(5),
+                max_series_per_table: Some(3),
+                other_indexes: vec![ts_index.clone()],
+            };
+            other_index
+                .add_series_if_not_exists(vec![series_keys[3].clone()], &limit)
+                .await
+                .unwrap();
+            let err = other_index
+                .add_series_if_not_exists(vec![series_keys[0].clone()], &limit)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                IndexError::SeriesLimitExceeded {
+                    limit_name: "max_series_per_database",
+                    ..
+                }
+            ));
+
+            // Sketches still count deleted series.
+            let sid = ts_index
+                .get_series_id(&series_keys[0])
+                .await
+                .unwrap()
+                .unwrap();
+            ts_index.del_series_info(sid).await.unwrap();
+            assert_eq!(ts_index.table_series_cardinality("tab_1"), 1);
+            let sketches = ts_index.tag_values_cardinality_sketches("tab_1", &["host".to_string()]);
+            assert_eq!(sketches[0].1.estimate(), 2);
+            let sketches = ts_index.series_cardinality_sketches(Some("tab_1"));
+            assert_eq!(sketches[0].1.estimate(), 2);
+        }
+
+        {
+            // Series in the binlog not flushed are counted once after re-open.
+            let ts_index = TSIndex::new(dir).await.unwrap();
+            assert_eq!(ts_index.series_cardinality(), 3);
+            assert_eq!(ts_index.table_series_cardinality("tab_1"), 1);
+            assert_eq!(ts_index.table_series_cardinality("tab_2"), 2);
+            let sketches = ts_index.tag_values_cardinality_sketches("tab_2", &["host".to_string()]);
+            assert_eq!(sketches[0].1.estimate(), 2);
+
+            // Sketches are reset when all series of the table are deleted.
+            let sid = ts_index
+                .get_series_id(&series_keys[1])
+                .await
+                .unwrap()
+                .unwrap();
+            ts_index.del_series_info(sid).await.unwrap();
+            assert!(ts_index
+                .series_cardinality_sketches(Some("tab_1"))
+                .is_empty());
+            assert!(ts_index
+                .tag_values_cardinality_sketches("tab_1", &["host".to_string()])
+                .is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename_series_keys() {
+        let dir = "/tmp/test/ts_index/4";
+        let _ = std::fs::remove_dir_all(dir);
+        let database = "db_test";
+        #[rustfmt::skip]
+        let series_keys_desc: Vec<SeriesKeyDesc> = vec![
+            (0, database, "tab_1", vec![("host", "h1"), ("loc", "bj")]),
+            (0, database, "tab_1", vec![("host", "h2"), ("loc", "nj")]),
+            (0, database, "tab_2", vec![("host", "h1")]),
+        ];
+        let series_keys = build_series_keys(&series_keys_desc);
+        let host_h1 = Tag::new(b"host".to_vec(), b"h1".to_vec());
+
+        let sids = {
+            let ts_index = TSIndex::new(dir).await.unwrap();
+            let sids = ts_index
+                .add_series_if_not_exists(series_keys.clone(), &SeriesLimit::default())
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            ts_index.rename_table("tab_1", "tab_3").await.unwrap();
+            // Series key is renamed at once.
+            let mut renamed_key = series_keys[0].clone();
+            renamed_key.table = "tab_3".to_string();
+            assert_eq!(
+                ts_index.get_series_id(&renamed_key).await.unwrap(),
+                Some(sids[0])
+            );
+            assert_eq!(ts_index.get_series_id(&series_keys[0]).await.unwrap(), None);
+            assert_eq!(ts_index.table_series_cardinality("tab_3"), 2);
+            assert_eq!(ts_index.table_series_cardinality("tab_1"), 0);
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let series_key = ts_index.get_series_key(sids[0]).await.unwrap().unwrap();
+            assert_eq!(series_key.table(), "tab_3");
+            let mut list = ts_index.get_series_id_list("tab_3", &[]).await.unwrap();
+            list.sort();
+            assert_eq!(list, vec![sids[0], sids[1]]);
+            assert!(ts_index
+                .get_series_id_list("tab_1", &[])
+                .await
+                .unwrap()
+                .is_empty());
+
+            // Tags are sorted again after renaming tag key.
+            ts_index.rename_tag("tab_3", "host", "node").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let series_key = ts_index.get_series_key(sids[0]).await.unwrap().unwrap();
+            assert_eq!(series_key.string(), "tab_3,loc=bj,node=h1");
+            let node_h1 = Tag::new(b"node".to_vec(), b"h1".to_vec());
+            assert_eq!(
+                ts_index
+                    .get_series_id_list("tab_3", &[node_h1])
+                    .await
+                    .unwrap(),
+                vec![sids[0]]
+            );
+            assert!(ts_index
+                .get_series_id_list("tab_3", &[host_h1.clone()])
+                .await
+                .unwrap()
+                .is_empty());
+            // Other tables are not changed.
+            assert_eq!(
+                ts_index
+                    .get_series_id_list("tab_2", &[host_h1])
+                    .await
+                    .unwrap(),
+                vec![sids[2]]
+            );
+
+            // Series keys can't be renamed to existing series keys.
+            let mut conflict_key = series_key.clone();
+            conflict_key.table = "tab_4".to_string();
+            ts_index
+                .add_series_if_not_exists(vec![conflict_key], &SeriesLimit::default())
+                .await
+                .unwrap();
+            let err = ts_index.rename_table("tab_4", "tab_3").await.unwrap_err();
+            assert!(matches!(err, IndexError::SeriesAlreadyExists { .. }));
+            ts_index.flush().await.unwrap();
+            sids
+        };
+
+        {
+            // Renamed series keys are recovered.
+            let ts_index = TSIndex::new(dir).await.unwrap();
+            let series_key = ts_index.get_series_key(sids[1]).await.unwrap().unwrap();
+            assert_eq!(series_key.string(), "tab_3,loc=nj,node=h2");
+            assert_eq!(
+                ts_index.get_series_id(&series_key).await.unwrap(),
+                Some(sids[1])
+            );
+            // Statistics of renamed tables and tag keys are recovered.
+            assert_eq!(ts_index.table_series_cardinality("tab_1"), 0);
+            assert_eq!(ts_index.table_series_cardinality("tab_3"), 2);
+            let sketches = ts_index.tag_values_cardinality_sketches(
+                "tab_3",
+                &["host".to_string(), "node".to_string()],
+            );
+            assert_eq!(sketches.len(), 1);
+            assert_eq!(
+                (sketches[0].0.as_str(), sketches[0].1.estimate()),
+                ("node", 2)
+            );
+        }
+    }
+
     #[test]
     fn test_serde() {
         let schema = Schema::new(vec![
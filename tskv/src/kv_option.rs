@@ -4,8 +4,11 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use config::Config;
+use config::{ColdStorageConfig, Config};
+use models::codec::Encoding;
 
+use crate::cold_storage::ColdStorage;
+use crate::tsm::DataBlockCache;
 use crate::TseriesFamilyId;
 
 const SUMMARY_PATH: &str = "summary";
@@ -14,6 +17,8 @@ const DATA_PATH: &str = "data";
 pub const TSM_PATH: &str = "tsm";
 pub const DELTA_PATH: &str = "delta";
 pub const MOVE_PATH: &str = "move";
+pub const BACKUP_PATH: &str = "backup";
+pub const VNODE_RENAME_EXTENSION: &str = "rename";
 
 #[derive(Debug, Clone)]
 pub struct Options {
@@ -21,6 +26,10 @@ pub struct Options {
     pub wal: Arc<WalOptions>,
     pub cache: Arc<CacheOptions>,
     pub query: Arc<QueryOptions>,
+    /// Opened by `TsKv::open()` from `cold_storage_config`.
+    pub cold_storage: Option<Arc<ColdStorage>>,
+    pub cold_storage_config: Arc<ColdStorageConfig>,
+    pub data_block_cache: Option<Arc<DataBlockCache>>,
 }
 
 impl From<&Config> for Options {
@@ -30,6 +39,9 @@ impl From<&Config> for Options {
             wal: Arc::new(WalOptions::from(config)),
             cache: Arc::new(CacheOptions::from(config)),
             query: Arc::new(QueryOptions::from(config)),
+            cold_storage: None,
+            cold_storage_config: Arc::new(config.cold_storage.clone()),
+            data_block_cache: DataBlockCache::open(config.cache.data_block_cache_size),
         }
     }
 }
@@ -47,6 +59,11 @@ pub struct StorageOptions {
     pub max_compact_size: u64,
     pub max_concurrent_compaction: u16,
     pub strict_write: bool,
+    pub compact_write_rate_limit: u64,
+    pub flush_write_rate_limit: u64,
+    /// 'blocking' or 'io_uring'.
+    pub io_backend: String,
+    pub direct_io: bool,
 }
 
 // database/data/ts_family_id/tsm
@@ -62,8 +79,18 @@ impl StorageOptions {
         self.path.join(SUMMARY_PATH)
     }
 
+    /// Returns the path of the file recording the database a vnode is being moved into.
+    pub fn vnode_rename_file(&self, ts_family_id: TseriesFamilyId) -> PathBuf {
+        self.summary_dir()
+            .join(format!("{}.{}", ts_family_id, VNODE_RENAME_EXTENSION))
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        self.path.join(DATA_PATH)
+    }
+
     pub fn database_dir(&self, database: &str) -> PathBuf {
-        self.path.join(DATA_PATH).join(database)
+        self.data_dir().join(database)
     }
 
     pub fn ts_family_dir(&self, database: &str, ts_family_id: TseriesFamilyId) -> PathBuf {
@@ -88,6 +115,12 @@ impl StorageOptions {
             .join(MOVE_PATH)
     }
 
+    pub fn backup_dir(&self, database: &str, ts_family_id: TseriesFamilyId) -> PathBuf {
+        self.database_dir(database)
+            .join(ts_family_id.to_string())
+            .join(BACKUP_PATH)
+    }
+
     pub fn delta_dir(&self, database: &str, ts_family_id: TseriesFamilyId) -> PathBuf {
         self.database_dir(database)
             .join(ts_family_id.to_string())
@@ -113,6 +146,10 @@ impl From<&Config> for StorageOptions {
             max_compact_size: config.storage.max_compact_size,
             max_concurrent_compaction: config.storage.max_concurrent_compaction,
             strict_write: config.storage.strict_write,
+            compact_write_rate_limit: config.storage.compact_write_rate_limit,
+            flush_write_rate_limit: config.storage.flush_write_rate_limit,
+            io_backend: config.storage.io_backend.clone(),
+            direct_io: config.storage.direct_io,
         }
     }
 }
@@ -153,6 +190,7 @@ pub struct WalOptions {
     pub flush_trigger_total_file_size: u64,
     pub sync: bool,
     pub sync_interval: Duration,
+    pub compression: Encoding,
 }
 
 impl From<&Config> for WalOptions {
@@ -165,6 +203,11 @@ impl From<&Config> for WalOptions {
             flush_trigger_total_file_size: config.wal.flush_trigger_total_file_size,
             sync: config.wal.sync,
             sync_interval: config.wal.sync_interval,
+            compression: match config.wal.compression.to_lowercase().as_str() {
+                "none" => Encoding::Null,
+                "snappy" => Encoding::Snappy,
+                _ => Encoding::Zstd,
+            },
         }
     }
 }
@@ -181,6 +224,8 @@ pub struct CacheOptions {
     pub max_buffer_size: u64,
     pub max_immutable_number: u16,
     pub partition: usize,
+    pub last_value_cache_max_series: usize,
+    pub data_block_cache_size: u64,
 }
 
 impl From<&Config> for CacheOptions {
@@ -189,6 +234,8 @@ impl From<&Config> for CacheOptions {
             max_buffer_size: config.cache.max_buffer_size,
             max_immutable_number: config.cache.max_immutable_number,
             partition: config.cache.partition,
+            last_value_cache_max_series: config.cache.last_value_cache_max_series,
+            data_block_cache_size: config.cache.data_block_cache_size,
         }
     }
 }
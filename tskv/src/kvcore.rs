@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
 use std::panic;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -7,12 +8,13 @@ use datafusion::arrow::record_batch::RecordBatch;
 use memory_pool::{MemoryPool, MemoryPoolRef};
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
-use models::codec::Encoding;
 use models::meta_data::{VnodeId, VnodeStatus};
-use models::predicate::domain::{ColumnDomains, TimeRange};
-use models::schema::{make_owner, DatabaseSchema, Precision, TableColumn};
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRange};
+use models::schema::{
+    make_owner, split_owner, DatabaseSchema, Precision, TableColumn, TskvTableSchema,
+};
 use models::utils::unite_id;
-use models::{ColumnId, SeriesId, SeriesKey, Timestamp};
+use models::{ColumnId, FieldId, SeriesId, SeriesKey, Timestamp};
 use protos::kv_service::{WritePointsRequest, WritePointsResponse};
 use protos::models as fb_models;
 use snafu::ResultExt;
@@ -21,23 +23,25 @@ use tokio::sync::broadcast::{self, Sender as BroadcastSender};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{oneshot, RwLock};
 use trace::{debug, error, info, warn, SpanContext, SpanExt, SpanRecorder};
+use utils::{BloomFilter, HyperLogLog};
 
-use crate::compaction::{
-    self, check, run_flush_memtable_job, CompactTask, FlushReq, LevelCompactionPicker, Picker,
-};
+use crate::backup::{self, BackupStorage};
+use crate::compaction::{self, check, run_flush_memtable_job, CompactTask, FlushReq, FlushTask};
 use crate::context::{self, GlobalContext, GlobalSequenceContext, GlobalSequenceTask};
 use crate::database::Database;
 use crate::error::{self, Result};
 use crate::file_system::file_manager;
-use crate::index::ts_index;
+use crate::index::ts_index::{self, SeriesLimit};
 use crate::kv_option::{Options, StorageOptions};
 use crate::schema::error::SchemaError;
-use crate::summary::{Summary, SummaryProcessor, SummaryTask, VersionEdit};
-use crate::tseries_family::{SuperVersion, TseriesFamily};
-use crate::tsm::codec::get_str_codec;
+use crate::summary::{self, CompactMeta, Summary, SummaryProcessor, SummaryTask, VersionEdit};
+use crate::tseries_family::{ColumnFile, SuperVersion, TseriesFamily};
 use crate::version_set::VersionSet;
 use crate::wal::{self, WalDecoder, WalEntry, WalManager, WalTask};
-use crate::{file_utils, tenant_name_from_request, Engine, Error, TseriesFamilyId};
+use crate::{
+    cold_storage, file_utils, parquet_io, split, tenant_name_from_request, ColumnFileId, Engine,
+    Error, TseriesFamilyId,
+};
 
 // TODO: A small summay channel capacity can cause a block
 pub const COMPACT_REQ_CHANNEL_CAP: usize = 1024;
@@ -71,6 +75,11 @@ impl TsKv {
         memory_pool: MemoryPoolRef,
         metrics: Arc<MetricsRegister>,
     ) -> Result<TsKv> {
+        file_manager::init_file_manager(&opt.storage);
+        let mut opt = opt;
+        if opt.cold_storage.is_none() {
+            opt.cold_storage = cold_storage::ColdStorage::open(&opt.cold_storage_config)?;
+        }
         let shared_options = Arc::new(opt);
         let (flush_task_sender, flush_task_receiver) =
             mpsc::channel::<FlushReq>(shared_options.storage.flush_req_channel_cap);
@@ -94,12 +103,19 @@ impl TsKv {
             metrics.clone(),
         )
         .await;
+        let global_ctx = summary.global_context();
+        global_ctx
+            .compact_rate_limiter()
+            .set_bytes_per_second(shared_options.storage.compact_write_rate_limit);
+        global_ctx
+            .flush_rate_limiter()
+            .set_bytes_per_second(shared_options.storage.flush_write_rate_limit);
         let global_seq_ctx = version_set.read().await.get_global_sequence_context().await;
         let global_seq_ctx = Arc::new(global_seq_ctx);
 
         let core = Self {
             options: shared_options.clone(),
-            global_ctx: summary.global_context(),
+            global_ctx,
             global_seq_ctx: global_seq_ctx.clone(),
             version_set,
             meta_manager,
@@ -133,6 +149,14 @@ impl TsKv {
             summary.version_set(),
             summary_task_sender.clone(),
         );
+        if let Some(cold_storage) = shared_options.cold_storage.clone() {
+            cold_storage::run(
+                cold_storage,
+                core.runtime.clone(),
+                summary.version_set(),
+                summary_task_sender.clone(),
+            );
+        }
         core.run_summary_job(summary, summary_task_receiver);
         context::run_global_context_job(
             core.runtime.clone(),
@@ -242,6 +266,17 @@ impl TsKv {
                                         trace::error!("Recover: failed to delete table: {e}");
                                     }
                                 }
+                                WalEntry::DeleteFromTable(blk) => {
+                                    let vnode_id = blk.vnode_id();
+                                    if vnode_last_seq_map.vnode_min_seq(vnode_id) >= seq {
+                                        // Data deleted by this record was already flushed.
+                                        continue;
+                                    }
+                                    if let Err(e) = self.delete_from_table_from_wal(&blk).await {
+                                        // Ignore delete from table error.
+                                        trace::error!("Recover: failed to delete from table: {e}");
+                                    }
+                                }
                                 _ => {}
                             }
                         }
@@ -459,8 +494,31 @@ impl TsKv {
         let opt_index = db.read().await.get_ts_index(id);
         match opt_index {
             Some(v) => Ok(v),
-            None => db.write().await.get_ts_index_or_add(id).await,
+            None => {
+                let version_set = self.version_set.read().await;
+                Self::check_vnode_owner(&version_set, &db, id).await?;
+                db.write().await.get_ts_index_or_add(id).await
+            }
+        }
+    }
+
+    /// Returns an error if the vnode belongs to another database, the vnode may be
+    /// being moved into `db` (e.g. by rename_database()), which holds the version
+    /// set until the vnode is moved.
+    async fn check_vnode_owner(
+        version_set: &VersionSet,
+        db: &Arc<RwLock<Database>>,
+        id: TseriesFamilyId,
+    ) -> Result<()> {
+        if let Some(tsf) = version_set.get_tsfamily_by_tf_id(id).await {
+            let owner = tsf.read().await.database();
+            if owner != db.read().await.owner() {
+                return Err(Error::CommonError {
+                    reason: format!("vnode:{}, belongs to '{}'", id, owner),
+                });
+            }
         }
+        Ok(())
     }
 
     pub(crate) async fn get_tsfamily_or_else_create(
@@ -474,6 +532,8 @@ impl TsKv {
         match opt_tsf {
             Some(v) => Ok(v),
             None => {
+                let version_set = self.version_set.read().await;
+                Self::check_vnode_owner(&version_set, &db, id).await?;
                 db.write()
                     .await
                     .add_tsfamily(
@@ -595,6 +655,244 @@ impl TsKv {
         Ok(())
     }
 
+    /// Flushes all vnodes of the database before the schema is changed (e.g. a
+    /// table is renamed), so that points in WAL written with the old schema
+    /// won't be replayed.
+    async fn flush_database(&self, db: &Arc<RwLock<Database>>) -> Result<()> {
+        let (owner, vnode_ids) = {
+            let db = db.read().await;
+            let vnode_ids: Vec<VnodeId> = db.ts_families().keys().copied().collect();
+            (db.owner(), vnode_ids)
+        };
+        let (tenant, database) = split_owner(&owner);
+        for vnode_id in vnode_ids {
+            self.flush_tsfamily(tenant, database, vnode_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the vnode, its index and table schemas of the database.
+    async fn split_vnode_context(
+        &self,
+        db: &Arc<RwLock<Database>>,
+        vnode_id: VnodeId,
+    ) -> Result<(
+        Arc<RwLock<TseriesFamily>>,
+        Arc<ts_index::TSIndex>,
+        Vec<Arc<TskvTableSchema>>,
+    )> {
+        let db = db.read().await;
+        let (tsf, ts_index) = match (db.get_tsfamily(vnode_id), db.get_ts_index(vnode_id)) {
+            (Some(tsf), Some(ts_index)) => (tsf, ts_index),
+            _ => {
+                return Err(Error::CommonError {
+                    reason: format!("vnode:{}, not found in '{}'", vnode_id, db.owner()),
+                })
+            }
+        };
+        let schemas = db.get_schemas();
+        let mut table_schemas = Vec::new();
+        for table in schemas.list_tables()? {
+            if let Some(schema) = schemas.get_table_schema(&table)? {
+                table_schemas.push(schema);
+            }
+        }
+        Ok((tsf, ts_index, table_schemas))
+    }
+
+    /// Copies values of series in `hash_range` of `vnode_id` into `new_vnode_id`.
+    ///
+    /// Files of the vnode are copied into the same levels of the new vnode, then
+    /// the series are rejected by the vnode, and files flushed meanwhile are
+    /// copied into level 0 of the new vnode, so that newer values have bigger
+    /// file ids or higher levels, the same as in the vnode.
+    async fn copy_moved_series(
+        &self,
+        db: Arc<RwLock<Database>>,
+        tsf: Arc<RwLock<TseriesFamily>>,
+        vnode_id: VnodeId,
+        new_vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()> {
+        let owner = db.read().await.owner();
+        // Moved series are counted in the database already, they are not limited.
+        let series_limit = SeriesLimit::default();
+        let (tenant, database) = split_owner(&owner);
+        self.flush_tsfamily(tenant, database, vnode_id).await?;
+        let (_, ts_index, table_schemas) = self.split_vnode_context(&db, vnode_id).await?;
+        let new_ts_index = self
+            .get_ts_index_or_else_create(db.clone(), new_vnode_id)
+            .await?;
+        let new_tsf = self
+            .get_tsfamily_or_else_create(0, new_vnode_id, None, db.clone())
+            .await?;
+        new_tsf.write().await.update_status(VnodeStatus::Copying);
+
+        let tsm_dir = self.options.storage.tsm_dir(&owner, new_vnode_id);
+        let delta_dir = self.options.storage.delta_dir(&owner, new_vnode_id);
+        let target = split::CopyTarget {
+            vnode_id: new_vnode_id,
+            global_ctx: &self.global_ctx,
+            tsm_dir: &tsm_dir,
+            delta_dir: &delta_dir,
+        };
+
+        // Hold the version until values are copied, so that files in it won't be deleted.
+        let version = tsf.read().await.version();
+        let files = parquet_io::sorted_column_files(&version);
+        let series = split::moved_series(
+            &table_schemas,
+            &ts_index,
+            &new_ts_index,
+            hash_range,
+            &series_limit,
+        )
+        .await?;
+        info!(
+            "Split vnode: copying {} series of vnode {vnode_id} in '{owner}' into vnode {new_vnode_id}",
+            series.len()
+        );
+        let mut column_file_metas =
+            split::copy_series(&version, &files, &series, None, &target).await?;
+
+        // Points of the moved series written since then are rejected, flush and copy
+        // points written during the copy.
+        ts_index.add_moved_hash_range(hash_range.clone()).await?;
+        self.flush_tsfamily(tenant, database, vnode_id).await?;
+        let last_version = tsf.read().await.version();
+        let last_files = parquet_io::sorted_column_files(&last_version);
+        let copied: HashSet<ColumnFileId> = files.iter().map(|f| f.file_id()).collect();
+        let last_file_ids: HashSet<ColumnFileId> = last_files.iter().map(|f| f.file_id()).collect();
+        if !copied.is_subset(&last_file_ids) {
+            return Err(Error::CommonError {
+                reason: format!("vnode {vnode_id} is compacted while being split, retry later"),
+            });
+        }
+        let flushed_files: Vec<Arc<ColumnFile>> = last_files
+            .into_iter()
+            .filter(|f| !copied.contains(&f.file_id()))
+            .collect();
+        let series = split::moved_series(
+            &table_schemas,
+            &ts_index,
+            &new_ts_index,
+            hash_range,
+            &series_limit,
+        )
+        .await?;
+        column_file_metas.extend(
+            split::copy_series(&last_version, &flushed_files, &series, Some(0), &target).await?,
+        );
+
+        self.add_column_files(new_vnode_id, version.max_level_ts, column_file_metas)
+            .await?;
+        new_ts_index.flush().await?;
+        new_tsf.write().await.update_status(VnodeStatus::Running);
+
+        Ok(())
+    }
+
+    /// Add new files of a storage unit into the current version, files newer
+    /// than `max_level_ts` of the version are added into level 1.
+    async fn add_column_files(
+        &self,
+        vnode_id: VnodeId,
+        max_level_ts: Timestamp,
+        column_file_metas: Vec<(CompactMeta, Arc<BloomFilter>)>,
+    ) -> Result<()> {
+        if column_file_metas.is_empty() {
+            return Ok(());
+        }
+        let max_level_ts = column_file_metas
+            .iter()
+            .filter(|(cm, _)| !cm.is_delta)
+            .map(|(cm, _)| cm.max_ts)
+            .fold(max_level_ts, Timestamp::max);
+        let mut edit = VersionEdit::new(vnode_id);
+        let mut file_metas = HashMap::with_capacity(column_file_metas.len());
+        for (cm, field_filter) in column_file_metas {
+            file_metas.insert(cm.file_id, field_filter);
+            edit.add_file(cm, max_level_ts);
+        }
+        let (summary_tx, summary_rx) = oneshot::channel();
+        self.summary_task_sender
+            .send(SummaryTask::new(
+                vec![edit],
+                Some(file_metas),
+                None,
+                summary_tx,
+            ))
+            .await
+            .map_err(|e| Error::CommonError {
+                reason: format!("failed to send summary task: {e}"),
+            })?;
+        summary_rx.await.map_err(|e| Error::CommonError {
+            reason: format!("failed to receive summary task result: {e}"),
+        })?
+    }
+
+    /// Delete data of a table in a storage unit, which matches the
+    /// tags filter and time ranges of the predicate.
+    async fn delete_from_vnode(
+        &self,
+        database: Arc<RwLock<Database>>,
+        vnode_id: VnodeId,
+        table: &str,
+        predicate: &ResolvedPredicate,
+    ) -> Result<()> {
+        let db_rlock = database.read().await;
+        let db_owner = db_rlock.owner();
+
+        let column_ids: Vec<ColumnId> = match db_rlock.get_schemas().get_table_schema(table)? {
+            Some(schema) => schema.fields().iter().map(|f| f.id).collect(),
+            None => return Ok(()),
+        };
+        let (ts_family, ts_index) = match (
+            db_rlock.get_tsfamily(vnode_id),
+            db_rlock.get_ts_index(vnode_id),
+        ) {
+            (Some(tsf), Some(idx)) => (tsf, idx),
+            _ => return Ok(()),
+        };
+
+        let tags_filter = predicate.tags_filter();
+        let series_ids = if tags_filter.is_all() {
+            ts_index.get_series_id_list(table, &[]).await?
+        } else if tags_filter.is_none() {
+            vec![]
+        } else {
+            let domains = unsafe { tags_filter.domains_unsafe() };
+            ts_index.get_series_ids_by_domains(table, domains).await?
+        };
+        if series_ids.is_empty() {
+            return Ok(());
+        }
+
+        let field_ids: Vec<u64> = series_ids
+            .iter()
+            .flat_map(|sid| column_ids.iter().map(|fid| unite_id(*fid, *sid)))
+            .collect();
+        info!(
+            "Delete from table: vnode {vnode_id} deleting {} fields in table: {db_owner}.{table}",
+            field_ids.len()
+        );
+
+        let time_ranges = predicate.time_ranges();
+        for time_range in time_ranges.time_ranges() {
+            ts_family
+                .read()
+                .await
+                .delete_series(&series_ids, time_range);
+
+            let version = ts_family.read().await.super_version();
+            for column_file in version.version.column_files(&field_ids, time_range) {
+                column_file.add_tombstone(&field_ids, time_range).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     async fn write_wal(
         &self,
         vnode_id: VnodeId,
@@ -607,13 +905,14 @@ impl TsKv {
             return Ok(0);
         }
 
-        let mut enc_points = Vec::with_capacity(points.len() / 2);
-        get_str_codec(Encoding::Zstd)
-            .encode(&[&points], &mut enc_points)
-            .with_context(|_| error::EncodeSnafu)?;
-        drop(points);
-
-        let (wal_task, rx) = WalTask::new_write(tenant, db_name, vnode_id, precision, enc_points);
+        let (wal_task, rx) = WalTask::new_write(
+            tenant,
+            db_name,
+            vnode_id,
+            precision,
+            &points,
+            self.options.wal.compression,
+        )?;
         self.wal_sender
             .send(wal_task)
             .await
@@ -650,7 +949,7 @@ impl TsKv {
             }
         };
         let precision = block.precision();
-        let points = match block_decoder.decode(block.points())? {
+        let points = match block_decoder.decode(block)? {
             Some(p) => p,
             None => return Ok(()),
         };
@@ -672,19 +971,23 @@ impl TsKv {
         let ts_index = self
             .get_ts_index_or_else_create(db.clone(), vnode_id)
             .await?;
+        let tables = fb_points.tables().ok_or(Error::CommonError {
+            reason: "points missing table".to_string(),
+        })?;
+        // Points of series moved into another vnode by split were flushed before
+        // being copied, or rejected when written, skip them.
+        if ts_index.has_moved_series() {
+            if let Err(e) = Database::check_moved_series(vnode_id, db_name, tables, &ts_index) {
+                warn!("Recover: skip wal entry {seq} of vnode {vnode_id}: {e}");
+                return Ok(());
+            }
+        }
 
         // Write data assuming schemas were created (strict mode).
         let write_group = db
             .read()
             .await
-            .build_write_group_strict_mode(
-                db_name,
-                precision,
-                fb_points.tables().ok_or(Error::CommonError {
-                    reason: "points missing table".to_string(),
-                })?,
-                ts_index,
-            )
+            .build_write_group_strict_mode(db_name, precision, tables, ts_index)
             .await?;
         tsf.read().await.put_points(seq, write_group)?;
 
@@ -708,6 +1011,28 @@ impl TsKv {
         Ok(())
     }
 
+    /// Delete data of a table in a storage unit.
+    ///
+    /// Data is from the WAL(write-ahead-log), so won't write back to WAL.
+    async fn delete_from_table_from_wal(&self, block: &wal::DeleteFromTableBlock) -> Result<()> {
+        let vnode_id = block.vnode_id();
+        let tenant = block.tenant_utf8()?;
+        let database = block.database_utf8()?;
+        let table = block.table_utf8()?;
+        info!(
+            "Recover: delete from table, tenant: {}, database: {}, vnode_id: {vnode_id}, table: {}",
+            &tenant, &database, &table
+        );
+        let predicate = bincode::deserialize::<ResolvedPredicate>(block.predicate())
+            .map_err(|e| Error::RecordFileDecode { source: e })?;
+        if let Some(db) = self.version_set.read().await.get_db(tenant, database) {
+            return self
+                .delete_from_vnode(db, vnode_id, table, &predicate)
+                .await;
+        }
+        Ok(())
+    }
+
     /// Remove the storage unit(caches and files) managed by TsKv,
     /// then remove directory of the storage unit.
     ///
@@ -780,7 +1105,7 @@ impl Engine for TsKv {
             let mut span_recorder = span_recorder.child("build write group");
             db.read()
                 .await
-                .build_write_group(db_name, precision, tables, ts_index)
+                .build_write_group(db_name, precision, tables, ts_index.clone())
                 .await
                 .map_err(|err| {
                     span_recorder.error(err.to_string());
@@ -804,7 +1129,17 @@ impl Engine for TsKv {
 
         let res = {
             let mut span_recorder = span_recorder.child("put points");
-            match tsf.read().await.put_points(seq, write_group) {
+            let tsf_rlock = tsf.read().await;
+            // Points of series moved into another vnode by split are rejected, they
+            // are routed to the new vnode after the split is cut over. It's checked
+            // with the vnode locked, so points are either rejected or put before the
+            // vnode is flushed by split.
+            let checked = if ts_index.has_moved_series() {
+                Database::check_moved_series(vnode_id, db_name, tables, &ts_index)
+            } else {
+                Ok(())
+            };
+            match checked.and_then(|_| tsf_rlock.put_points(seq, write_group)) {
                 Ok(points_number) => Ok(WritePointsResponse { points_number }),
                 Err(err) => {
                     span_recorder.error(err.to_string());
@@ -867,6 +1202,130 @@ impl Engine for TsKv {
         Ok(())
     }
 
+    async fn rename_database(&self, tenant: &str, database: &str, new_name: &str) -> Result<()> {
+        let db = match self.version_set.read().await.get_db(tenant, database) {
+            Some(db) => db,
+            None => return Ok(()),
+        };
+        let owner = make_owner(tenant, database);
+        let new_owner = make_owner(tenant, new_name);
+        let storage_opt = &self.options.storage;
+        let vnode_ids: Vec<VnodeId> = db.read().await.ts_families().keys().copied().collect();
+        for vnode_id in vnode_ids {
+            let version_edit = loop {
+                // Flush caches so that data of the vnode is all in files, and points of
+                // the vnode in WAL won't be replayed.
+                self.prepare_copy_vnode(tenant, database, vnode_id).await?;
+                // Holds the version set, so points of the vnode won't be written into
+                // the new database before the vnode is moved.
+                let mut version_set = self.version_set.write().await;
+                let tsf = match db.read().await.get_tsfamily(vnode_id) {
+                    Some(tsf) => tsf,
+                    None => break None,
+                };
+                let mut file_metas = HashMap::new();
+                let version_edit = {
+                    let tsf = tsf.read().await;
+                    if !tsf.cache().read().is_empty() {
+                        // Points were written after the vnode was flushed.
+                        continue;
+                    }
+                    tsf.snapshot(Arc::new(new_owner.clone()), &mut file_metas)
+                };
+                // If the node stops before the vnode is written into summary with the
+                // new owner, the rename is completed by Summary::recover().
+                summary::write_vnode_rename(storage_opt, vnode_id, &new_owner)?;
+                {
+                    let mut db_wlock = db.write().await;
+                    db_wlock.del_ts_index(vnode_id);
+                    db_wlock.close_moved_tsfamily(vnode_id).await;
+                }
+                file_utils::rename(
+                    storage_opt.ts_family_dir(&owner, vnode_id),
+                    storage_opt.ts_family_dir(&new_owner, vnode_id),
+                )
+                .await?;
+                let new_db = match version_set.get_db(tenant, new_name) {
+                    Some(new_db) => new_db,
+                    None => {
+                        version_set
+                            .create_db(
+                                DatabaseSchema::new(tenant, new_name),
+                                self.meta_manager.clone(),
+                                self.memory_pool.clone(),
+                            )
+                            .await?
+                    }
+                };
+                new_db
+                    .write()
+                    .await
+                    .open_moved_tsfamily(
+                        &version_edit,
+                        file_metas,
+                        self.flush_task_sender.clone(),
+                        self.compact_task_sender.clone(),
+                    )
+                    .await?;
+                break Some(version_edit);
+            };
+            let version_edit = match version_edit {
+                Some(version_edit) => version_edit,
+                None => continue,
+            };
+
+            // Files in the summary are already in the version of the opened vnode.
+            let (summary_tx, summary_rx) = oneshot::channel();
+            self.summary_task_sender
+                .send(SummaryTask::new(vec![version_edit], None, None, summary_tx))
+                .await
+                .map_err(|e| Error::CommonError {
+                    reason: format!("failed to send summary task: {e}"),
+                })?;
+            summary_rx.await.map_err(|e| Error::ChannelReceive {
+                source: error::ChannelReceiveError::WriteSummaryResult { source: e },
+            })??;
+            summary::remove_vnode_rename(storage_opt, vnode_id)?;
+
+            // Points of the vnode in WAL were flushed, move them with the vnode.
+            let (wal_task, rx) =
+                WalTask::new_move_vnode(tenant.to_string(), new_name.to_string(), vnode_id);
+            self.wal_sender
+                .send(wal_task)
+                .await
+                .map_err(|_| Error::ChannelSend {
+                    source: error::ChannelSendError::WalTask,
+                })?;
+            rx.await.map_err(|e| Error::ChannelReceive {
+                source: error::ChannelReceiveError::WriteWalResult { source: e },
+            })??;
+            info!("Renamed vnode {vnode_id} from '{owner}' to '{new_owner}'");
+        }
+
+        self.version_set.write().await.delete_db(tenant, database);
+        let db_dir = storage_opt.database_dir(&owner);
+        if let Err(e) = std::fs::remove_dir_all(&db_dir) {
+            error!("Failed to remove dir '{}', e: {}", db_dir.display(), e);
+        }
+        Ok(())
+    }
+
+    async fn rename_table(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        new_name: &str,
+    ) -> Result<()> {
+        let db = self.get_db(tenant, database).await?;
+        self.flush_database(&db).await?;
+        let ts_indexes = db.read().await.ts_indexes();
+        for ts_index in ts_indexes.values() {
+            ts_index.rename_table(table, new_name).await?;
+        }
+        Ok(())
+    }
+
     async fn remove_tsfamily(&self, tenant: &str, database: &str, vnode_id: VnodeId) -> Result<()> {
         if let Some(db) = self.version_set.read().await.get_db(tenant, database) {
             // Store this action in WAL.
@@ -957,17 +1416,19 @@ impl Engine for TsKv {
 
     async fn add_table_column(
         &self,
-        _tenant: &str,
-        _database: &str,
-        _table: &str,
-        _new_column: TableColumn,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        new_column: TableColumn,
     ) -> Result<()> {
-        // let db = self.get_db(tenant, database).await?;
-        // let db = db.read().await;
-        // let sids = db.get_table_sids(table).await?;
-        // for (_ts_family_id, ts_family) in db.ts_families().iter() {
-        //     ts_family.read().await.add_column(&sids, &new_column);
-        // }
+        let db = self.get_db(tenant, database).await?;
+        let db = db.read().await;
+        for (ts_family_id, ts_family) in db.ts_families().iter() {
+            if let Some(ts_index) = db.get_ts_index(*ts_family_id) {
+                let series_ids = ts_index.get_series_id_list(table, &[]).await?;
+                ts_family.read().await.add_column(&series_ids, &new_column);
+            }
+        }
         Ok(())
     }
 
@@ -1002,54 +1463,70 @@ impl Engine for TsKv {
 
     async fn change_table_column(
         &self,
-        _tenant: &str,
-        _database: &str,
-        _table: &str,
-        _column_name: &str,
-        _new_column: TableColumn,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        column_name: &str,
+        new_column: TableColumn,
     ) -> Result<()> {
-        // let db = self.get_db(tenant, database).await?;
-        // let db = db.read().await;
-        // let sids = db.get_table_sids(table).await?;
-
-        // for (_ts_family_id, ts_family) in db.ts_families().iter() {
-        //     ts_family
-        //         .read()
-        //         .await
-        //         .change_column(&sids, column_name, &new_column);
-        // }
+        // Data in files is converted to the new column type when it's read or compacted,
+        // only the cached data is changed here.
+        let db = self.get_db(tenant, database).await?;
+        self.flush_database(&db).await?;
+        let db = db.read().await;
+        let rename_tag = new_column.column_type.is_tag() && new_column.name != column_name;
+        for (ts_family_id, ts_family) in db.ts_families().iter() {
+            if let Some(ts_index) = db.get_ts_index(*ts_family_id) {
+                if rename_tag {
+                    ts_index
+                        .rename_tag(table, column_name, &new_column.name)
+                        .await?;
+                }
+                let series_ids = ts_index.get_series_id_list(table, &[]).await?;
+                ts_family
+                    .read()
+                    .await
+                    .change_column(&series_ids, column_name, &new_column);
+            }
+        }
         Ok(())
     }
 
-    async fn delete_series(
+    async fn delete_from_table(
         &self,
-        _tenant: &str,
-        _database: &str,
-        _series_ids: &[SeriesId],
-        _field_ids: &[ColumnId],
-        _time_range: &TimeRange,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        predicate: &ResolvedPredicate,
     ) -> Result<()> {
-        // let storage_field_ids: Vec<u64> = series_ids
-        //     .iter()
-        //     .flat_map(|sid| field_ids.iter().map(|fid| unite_id(*fid, *sid)))
-        //     .collect();
-        // let res = self.version_set.read().get_db(tenant, database);
-        // if let Some(db) = res {
-        //     let readable_db = db.read();
-        //     for (ts_family_id, ts_family) in readable_db.ts_families() {
-        //         // TODO Cancel current and prevent next flush or compaction in TseriesFamily provisionally.
-        //         ts_family
-        //             .write()
-        //             .delete_series(&storage_field_ids, time_range);
-
-        //         let version = ts_family.read().super_version();
-        //         for column_file in version.version.column_files(&storage_field_ids, time_range) {
-        //             self.runtime
-        //                 .block_on(column_file.add_tombstone(&storage_field_ids, time_range))?;
-        //         }
-        //         // TODO Start next flush or compaction.
-        //     }
-        // }
+        if let Some(db) = self.version_set.read().await.get_db(tenant, database) {
+            let encoded_predicate =
+                bincode::serialize(predicate).map_err(|e| Error::RecordFileEncode { source: e })?;
+            let vnode_ids: Vec<VnodeId> = db.read().await.ts_families().keys().copied().collect();
+            for vnode_id in vnode_ids {
+                let (wal_task, rx) = WalTask::new_delete_from_table(
+                    tenant.to_string(),
+                    database.to_string(),
+                    vnode_id,
+                    table.to_string(),
+                    encoded_predicate.clone(),
+                );
+                self.wal_sender
+                    .send(wal_task)
+                    .await
+                    .map_err(|_| Error::ChannelSend {
+                        source: error::ChannelSendError::WalTask,
+                    })?;
+                // Receive WAL write action result.
+                let _ = rx.await.map_err(|e| Error::ChannelReceive {
+                    source: error::ChannelReceiveError::WriteWalResult { source: e },
+                })??;
+
+                self.delete_from_vnode(db.clone(), vnode_id, table, predicate)
+                    .await?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1101,6 +1578,43 @@ impl Engine for TsKv {
         Ok(None)
     }
 
+    async fn get_series_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: Option<&str>,
+    ) -> Result<Vec<(String, HyperLogLog)>> {
+        let ts_index = match self.version_set.read().await.get_db(tenant, database) {
+            Some(db) => match db.read().await.get_ts_index(vnode_id) {
+                Some(ts_index) => ts_index,
+                None => return Ok(vec![]),
+            },
+            None => return Ok(vec![]),
+        };
+
+        Ok(ts_index.series_cardinality_sketches(table))
+    }
+
+    async fn get_tag_values_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: &str,
+        tag_keys: &[String],
+    ) -> Result<Vec<(String, HyperLogLog)>> {
+        let ts_index = match self.version_set.read().await.get_db(tenant, database) {
+            Some(db) => match db.read().await.get_ts_index(vnode_id) {
+                Some(ts_index) => ts_index,
+                None => return Ok(vec![]),
+            },
+            None => return Ok(vec![]),
+        };
+
+        Ok(ts_index.tag_values_cardinality_sketches(table, tag_keys))
+    }
+
     async fn get_db_version(
         &self,
         tenant: &str,
@@ -1133,6 +1647,18 @@ impl Engine for TsKv {
         self.options.storage.clone()
     }
 
+    fn set_compact_write_rate_limit(&self, bytes_per_second: u64) {
+        self.global_ctx
+            .compact_rate_limiter()
+            .set_bytes_per_second(bytes_per_second);
+    }
+
+    fn set_flush_write_rate_limit(&self, bytes_per_second: u64) {
+        self.global_ctx
+            .flush_rate_limiter()
+            .set_bytes_per_second(bytes_per_second);
+    }
+
     async fn get_vnode_summary(
         &self,
         tenant: &str,
@@ -1201,6 +1727,285 @@ impl Engine for TsKv {
         Ok(())
     }
 
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        location: &str,
+    ) -> Result<()> {
+        self.flush_tsfamily(tenant, database, vnode_id).await?;
+
+        let db = match self.version_set.read().await.get_db(tenant, database) {
+            Some(db) => db,
+            None => {
+                return Err(SchemaError::DatabaseNotFound {
+                    database: format!("{}.{}", tenant, database),
+                }
+                .into())
+            }
+        };
+        let (owner, tsf, ts_index) = {
+            let db = db.read().await;
+            match (db.get_tsfamily(vnode_id), db.get_ts_index(vnode_id)) {
+                (Some(tsf), Some(ts_index)) => (db.owner(), tsf, ts_index),
+                _ => {
+                    return Err(Error::CommonError {
+                        reason: format!("vnode:{}, not found in '{}'", vnode_id, db.owner()),
+                    })
+                }
+            }
+        };
+        // Hold the version until backup finished, so that files in it won't be deleted.
+        let (_version, summary) = {
+            let tsf = tsf.read().await;
+            let mut file_metas = HashMap::new();
+            (tsf.version(), tsf.snapshot(owner.clone(), &mut file_metas))
+        };
+
+        let storage = BackupStorage::open(location)?;
+        backup::backup_vnode(
+            &self.options.storage,
+            self.options.cold_storage.as_deref(),
+            &owner,
+            vnode_id,
+            &summary,
+            &ts_index,
+            &storage,
+        )
+        .await
+    }
+
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        backup_vnode_id: VnodeId,
+        location: &str,
+    ) -> Result<()> {
+        let owner = make_owner(tenant, database);
+        let storage = BackupStorage::open(location)?;
+        let summary = backup::restore_vnode(
+            &self.options.storage,
+            &owner,
+            vnode_id,
+            backup_vnode_id,
+            &storage,
+        )
+        .await?;
+        self.apply_vnode_summary(tenant, database, vnode_id, summary)
+            .await
+    }
+
+    async fn export_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: Option<&str>,
+        location: &str,
+    ) -> Result<()> {
+        self.flush_tsfamily(tenant, database, vnode_id).await?;
+
+        let db = self.get_db(tenant, database).await?;
+        let (owner, tsf, ts_index, schemas) = {
+            let db = db.read().await;
+            match (db.get_tsfamily(vnode_id), db.get_ts_index(vnode_id)) {
+                (Some(tsf), Some(ts_index)) => (db.owner(), tsf, ts_index, db.get_schemas()),
+                _ => {
+                    return Err(Error::CommonError {
+                        reason: format!("vnode:{}, not found in '{}'", vnode_id, db.owner()),
+                    })
+                }
+            }
+        };
+        let tables = match table {
+            Some(t) => vec![t.to_string()],
+            None => schemas.list_tables()?,
+        };
+        let mut table_schemas = Vec::with_capacity(tables.len());
+        for table in tables {
+            match schemas.get_table_schema(&table)? {
+                Some(schema) => table_schemas.push(schema),
+                None => {
+                    return Err(SchemaError::TableNotFound {
+                        database: owner.to_string(),
+                        table,
+                    }
+                    .into())
+                }
+            }
+        }
+        // Hold the version until export finished, so that files in it won't be deleted.
+        let version = tsf.read().await.version();
+
+        let storage = BackupStorage::open(location)?;
+        parquet_io::export_vnode(
+            &version,
+            &ts_index,
+            &table_schemas,
+            &owner,
+            vnode_id,
+            &storage,
+        )
+        .await
+    }
+
+    async fn import_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: &str,
+        data: RecordBatch,
+    ) -> Result<()> {
+        let db = self.get_db_or_else_create(tenant, database).await?;
+        let ts_index = self
+            .get_ts_index_or_else_create(db.clone(), vnode_id)
+            .await?;
+        let (table_schema, series_limit) = {
+            let db = db.read().await;
+            let table_schema =
+                db.get_table_schema(table)?
+                    .ok_or_else(|| SchemaError::TableNotFound {
+                        database: db.owner().to_string(),
+                        table: table.to_string(),
+                    })?;
+            (table_schema, db.series_limit(&ts_index)?)
+        };
+        let tsf = self
+            .get_tsfamily_or_else_create(0, vnode_id, None, db.clone())
+            .await?;
+
+        let data_blocks = parquet_io::record_batch_to_data_blocks(
+            database,
+            &table_schema,
+            &data,
+            &ts_index,
+            &series_limit,
+        )
+        .await?;
+        if data_blocks.is_empty() {
+            return Ok(());
+        }
+
+        let (owner, version) = {
+            let tsf = tsf.read().await;
+            (tsf.database(), tsf.version())
+        };
+        let flush_task = FlushTask::new(
+            vec![],
+            vnode_id,
+            self.global_ctx.clone(),
+            self.options.storage.tsm_dir(&owner, vnode_id),
+            self.options.storage.delta_dir(&owner, vnode_id),
+        );
+        let field_ids: Vec<FieldId> = data_blocks.keys().copied().collect();
+        let column_file_metas = flush_task
+            .write_data_blocks(data_blocks, version.max_level_ts)
+            .await?;
+        self.add_column_files(vnode_id, version.max_level_ts, column_file_metas)
+            .await?;
+        // Imported values may be newer than the cached latest values.
+        tsf.read().await.invalidate_last_values(&field_ids);
+        ts_index.flush().await?;
+
+        Ok(())
+    }
+
+    async fn split_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        new_vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()> {
+        let db = self.get_db(tenant, database).await?;
+        let tsf = db
+            .read()
+            .await
+            .get_tsfamily(vnode_id)
+            .ok_or_else(|| Error::CommonError {
+                reason: format!("vnode:{}, not found in '{}.{}'", vnode_id, tenant, database),
+            })?;
+
+        // Values copied by an interrupted split are dropped and copied again, the
+        // new vnode takes no writes until the split is cut over.
+        self.drop_vnode(new_vnode_id).await?;
+        let owner = make_owner(tenant, database);
+        for dir in [
+            self.options.storage.tsfamily_dir(&owner, new_vnode_id),
+            self.options.storage.index_dir(&owner, new_vnode_id),
+        ] {
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir).context(error::IOSnafu)?;
+            }
+        }
+
+        // Files of the vnode are not compacted while they are being copied.
+        tsf.write().await.update_status(VnodeStatus::Copying);
+        let res = self
+            .copy_moved_series(db, tsf.clone(), vnode_id, new_vnode_id, hash_range)
+            .await;
+        tsf.write().await.update_status(VnodeStatus::Running);
+        res
+    }
+
+    async fn remove_moved_series(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()> {
+        let db = self.get_db(tenant, database).await?;
+        let (tsf, ts_index, table_schemas) = self.split_vnode_context(&db, vnode_id).await?;
+        ts_index.add_moved_hash_range(hash_range.clone()).await?;
+
+        let series = split::series_in_range(&table_schemas, &ts_index, hash_range).await?;
+        let series_ids: Vec<SeriesId> = series
+            .iter()
+            .flat_map(|(_, table_series)| table_series.iter().map(|(sid, _)| *sid))
+            .collect();
+        info!(
+            "Split vnode: removing {} series moved out of vnode {vnode_id} in '{tenant}.{database}'",
+            series_ids.len()
+        );
+        if series_ids.is_empty() {
+            return Ok(());
+        }
+
+        // Points of the moved series were all flushed and copied before they are
+        // rejected by the vnode, so only values in files are deleted.
+        let version = tsf.read().await.version();
+        let max_ts = version
+            .levels_info()
+            .iter()
+            .flat_map(|l| l.files.iter())
+            .map(|f| f.time_range().max_ts)
+            .max();
+        if let Some(max_ts) = max_ts {
+            let time_range = &TimeRange {
+                min_ts: Timestamp::MIN,
+                max_ts,
+            };
+            tsf.read().await.delete_series(&series_ids, time_range);
+            let field_ids = split::moved_field_ids(&series);
+            for column_file in version.column_files(&field_ids, time_range) {
+                column_file.add_tombstone(&field_ids, time_range).await?;
+            }
+        }
+        // Series are removed from the index at last, so removing can be retried.
+        for sid in series_ids.iter() {
+            ts_index.del_series_info(*sid).await?;
+        }
+        ts_index.flush().await?;
+
+        Ok(())
+    }
+
     async fn drop_vnode(&self, vnode_id: TseriesFamilyId) -> Result<()> {
         let r_version_set = self.version_set.read().await;
         let all_db = r_version_set.get_all_db();
@@ -1262,9 +2067,25 @@ impl Engine for TsKv {
                     }
                 }
 
-                let picker = LevelCompactionPicker::new(self.options.storage.clone());
                 let version = ts_family.read().await.version();
+                let db_schema = self
+                    .version_set
+                    .read()
+                    .await
+                    .get_db_schema_by_owner(&version.database)
+                    .await
+                    .ok()
+                    .flatten();
+                let field_type_resolver = compaction::FieldTypeResolver::for_vnode(
+                    &*self.version_set.read().await,
+                    &version.database,
+                    vnode_id,
+                )
+                .await;
+                let picker =
+                    compaction::new_picker(self.options.storage.clone(), db_schema.as_ref());
                 if let Some(req) = picker.pick_compaction(version) {
+                    let req = req.with_field_type_resolver(field_type_resolver);
                     match compaction::run_compaction_job(req, self.global_ctx.clone()).await {
                         Ok(Some((version_edit, file_metas))) => {
                             let (summary_tx, _summary_rx) = oneshot::channel();
@@ -1338,6 +2159,10 @@ impl Engine for TsKv {
 
 #[cfg(test)]
 impl TsKv {
+    pub(crate) fn meta_manager(&self) -> MetaRef {
+        self.meta_manager.clone()
+    }
+
     pub(crate) fn global_ctx(&self) -> Arc<GlobalContext> {
         self.global_ctx.clone()
     }
@@ -1362,3 +2187,93 @@ impl TsKv {
         self.compact_task_sender.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use models::predicate::domain::TimeRange;
+    use models::SeriesId;
+    use tokio::runtime;
+
+    use super::TsKv;
+    use crate::split::test::{
+        init_tskv, open_tskv, read_values, tag_predicate, write_series, DATABASE, TABLE, TENANT,
+    };
+    use crate::{Engine, TseriesFamilyId};
+
+    /// Returns series ids of the table, indexed by `i` of tag value 'a{i}'.
+    async fn series_ids(engine: &TsKv, vnode_id: TseriesFamilyId, num: usize) -> Vec<SeriesId> {
+        let db = engine.get_db(TENANT, DATABASE).await.unwrap();
+        let ts_index = db.read().await.get_ts_index(vnode_id).unwrap();
+        let mut sids = vec![0; num];
+        for sid in ts_index.get_series_id_list(TABLE, &[]).await.unwrap() {
+            let key = ts_index.get_series_key(sid).await.unwrap().unwrap();
+            let i: usize = std::str::from_utf8(&key.tags()[0].value).unwrap()[1..]
+                .parse()
+                .unwrap();
+            sids[i] = sid;
+        }
+        sids
+    }
+
+    #[test]
+    fn test_delete_from_table() {
+        let runtime = Arc::new(
+            runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+        let rt = runtime.clone();
+        rt.block_on(async move {
+            let dir = "/tmp/test/kvcore/delete_from_table";
+            let vnode_id = 1;
+            let tag_values: Vec<String> = (0..2).map(|i| format!("a{i}")).collect();
+
+            // Values of timestamp 1 to 3 are in files, values of 4 and 5 are in the memcache.
+            let engine = init_tskv(dir, runtime.clone()).await;
+            write_series(&engine, vnode_id, &tag_values, &[1, 2, 3], 0)
+                .await
+                .unwrap();
+            engine
+                .flush_tsfamily(TENANT, DATABASE, vnode_id)
+                .await
+                .unwrap();
+            write_series(&engine, vnode_id, &tag_values, &[4, 5], 0)
+                .await
+                .unwrap();
+            let sids = series_ids(&engine, vnode_id, tag_values.len()).await;
+
+            // DELETE FROM tab WHERE ta = 'a0' AND time BETWEEN 2 AND 4
+            let predicate = tag_predicate("a0", TimeRange::new(2, 4));
+            engine
+                .delete_from_table(TENANT, DATABASE, TABLE, &predicate)
+                .await
+                .unwrap();
+
+            // Deleted rows in files are excluded by tombstones.
+            assert_eq!(read_values(&engine, vnode_id, sids[0]).await, vec![10]);
+            assert_eq!(
+                read_values(&engine, vnode_id, sids[1]).await,
+                vec![11, 21, 31]
+            );
+            engine.close().await;
+            drop(engine);
+
+            // Tombstones are kept after restart, rows in the memcache are deleted
+            // again when the WAL is replayed.
+            let engine = open_tskv(dir, runtime).await;
+            engine
+                .flush_tsfamily(TENANT, DATABASE, vnode_id)
+                .await
+                .unwrap();
+            assert_eq!(read_values(&engine, vnode_id, sids[0]).await, vec![10, 50]);
+            assert_eq!(
+                read_values(&engine, vnode_id, sids[1]).await,
+                vec![11, 21, 31, 41, 51]
+            );
+            engine.close().await;
+        });
+    }
+}
@@ -4,17 +4,19 @@
 #![feature(maybe_uninit_uninit_array)]
 
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 pub use compaction::check::vnode_table_checksum_schema;
 use datafusion::arrow::record_batch::RecordBatch;
 use models::meta_data::VnodeId;
-use models::predicate::domain::{ColumnDomains, TimeRange};
+use models::predicate::domain::{ColumnDomains, ResolvedPredicate};
 use models::schema::{Precision, TableColumn};
-use models::{ColumnId, SeriesId, SeriesKey};
+use models::{SeriesId, SeriesKey};
 use protos::kv_service::{WritePointsRequest, WritePointsResponse};
 use trace::SpanContext;
+use utils::HyperLogLog;
 
 pub use crate::error::{Error, Result};
 pub use crate::kv_option::Options;
@@ -23,9 +25,11 @@ pub use crate::kvcore::TsKv;
 pub use crate::summary::{print_summary_statistics, Summary, VersionEdit};
 use crate::tseries_family::SuperVersion;
 pub use crate::tsm::print_tsm_statistics;
-pub use crate::wal::print_wal_statistics;
+pub use crate::wal::{print_wal_statistics, repair_wal_file};
 
+pub mod backup;
 pub mod byte_utils;
+mod cold_storage;
 mod compaction;
 mod compute;
 mod context;
@@ -38,11 +42,14 @@ pub mod index;
 pub mod kv_option;
 mod kvcore;
 mod memcache;
+pub mod parquet_io;
 // TODO supposedly private
 pub mod reader;
 mod record_file;
 mod schema;
+mod split;
 mod summary;
+pub mod tools;
 mod tseries_family;
 pub mod tsm;
 mod version_set;
@@ -88,6 +95,19 @@ pub trait Engine: Send + Sync + Debug {
     /// Delete all data of a table.
     async fn drop_table(&self, tenant: &str, database: &str, table: &str) -> Result<()>;
 
+    /// Rename a database, storage units of the database are moved to the
+    /// directory of the new database.
+    async fn rename_database(&self, tenant: &str, database: &str, new_name: &str) -> Result<()>;
+
+    /// Rename a table in series keys of all storage units of the database.
+    async fn rename_table(
+        &self,
+        tenant: &str,
+        database: &str,
+        table: &str,
+        new_name: &str,
+    ) -> Result<()>;
+
     /// Remove the storage unit(caches and files) managed by engine,
     /// then remove directory of the storage unit.
     async fn remove_tsfamily(&self, tenant: &str, database: &str, vnode_id: VnodeId) -> Result<()>;
@@ -103,7 +123,7 @@ pub trait Engine: Send + Sync + Debug {
     /// Flush all caches of the storage unit into a file.
     async fn flush_tsfamily(&self, tenant: &str, database: &str, vnode_id: VnodeId) -> Result<()>;
 
-    // TODO this method is not completed,
+    /// Add a column to cached rows of a table.
     async fn add_table_column(
         &self,
         tenant: &str,
@@ -112,7 +132,7 @@ pub trait Engine: Send + Sync + Debug {
         column: TableColumn,
     ) -> Result<()>;
 
-    // TODO this method is not completed,
+    /// Delete data of a column in all storage units of the database.
     async fn drop_table_column(
         &self,
         tenant: &str,
@@ -121,7 +141,9 @@ pub trait Engine: Send + Sync + Debug {
         column: &str,
     ) -> Result<()>;
 
-    // TODO this method is not completed,
+    /// Change a column of a table, values of a field column are converted to
+    /// the new data type when they are read or compacted, a renamed tag column
+    /// is renamed in series keys.
     async fn change_table_column(
         &self,
         tenant: &str,
@@ -131,14 +153,14 @@ pub trait Engine: Send + Sync + Debug {
         new_column: TableColumn,
     ) -> Result<()>;
 
-    // TODO this method is not completed,
-    async fn delete_series(
+    /// Delete data of a table in all storage units of the database,
+    /// which matches the tags filter and time ranges of the predicate.
+    async fn delete_from_table(
         &self,
         tenant: &str,
         database: &str,
-        series_ids: &[SeriesId],
-        field_ids: &[ColumnId],
-        time_range: &TimeRange,
+        table: &str,
+        predicate: &ResolvedPredicate,
     ) -> Result<()>;
 
     /// Read index of a storage unit, find series ids that matches the filter.
@@ -160,6 +182,30 @@ pub trait Engine: Send + Sync + Debug {
         series_id: SeriesId,
     ) -> Result<Option<SeriesKey>>;
 
+    /// Read index of a storage unit, get sketches of series keys of each table,
+    /// or only of `table` if it's specified. The estimate is approximate after
+    /// series are deleted, deleted series are counted until all series of the
+    /// table are deleted.
+    async fn get_series_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: Option<&str>,
+    ) -> Result<Vec<(String, HyperLogLog)>>;
+
+    /// Read index of a storage unit, get sketches of tag values of each tag key
+    /// in `tag_keys` of the table, tag values of deleted series are counted like
+    /// `get_series_cardinality`.
+    async fn get_tag_values_cardinality(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: &str,
+        tag_keys: &[String],
+    ) -> Result<Vec<(String, HyperLogLog)>>;
+
     /// Get a `SuperVersion` that contains the latest version of caches and files
     /// of the storage unit.
     async fn get_db_version(
@@ -172,6 +218,12 @@ pub trait Engine: Send + Sync + Debug {
     /// Get the storage options which was used to install the engine.
     fn get_storage_options(&self) -> Arc<StorageOptions>;
 
+    /// Change the maximum bytes per second written by compactions, 0 means unlimited.
+    fn set_compact_write_rate_limit(&self, bytes_per_second: u64);
+
+    /// Change the maximum bytes per second written by flushes, 0 means unlimited.
+    fn set_flush_write_rate_limit(&self, bytes_per_second: u64);
+
     /// Get the summary(information of files) of the storae unit.
     async fn get_vnode_summary(
         &self,
@@ -190,6 +242,77 @@ pub trait Engine: Send + Sync + Debug {
         summary: VersionEdit,
     ) -> Result<()>;
 
+    /// Flush caches of the storage unit, then copy files of the current version
+    /// and the summary into `location`, a local directory or an object store url.
+    async fn backup_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        location: &str,
+    ) -> Result<()>;
+
+    /// Build a new storage unit from the backup of storage unit `backup_vnode_id`
+    /// in `location`.
+    async fn restore_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        backup_vnode_id: VnodeId,
+        location: &str,
+    ) -> Result<()>;
+
+    /// Flush caches of the storage unit, then write rows of `table` (or all
+    /// tables if it's None) in files of the current version into Parquet files
+    /// in `location`, a local directory or an object store url.
+    async fn export_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: Option<&str>,
+        location: &str,
+    ) -> Result<()>;
+
+    /// Write rows of `table` in `data` into new TSM files of the storage unit
+    /// and add the files to the current version, rows are not written into WAL
+    /// and caches.
+    async fn import_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        table: &str,
+        data: RecordBatch,
+    ) -> Result<()>;
+
+    /// Copy series of the storage unit whose hash is in `hash_range` into the
+    /// new storage unit `new_vnode_id`, values of the series are written into
+    /// new TSM files of `new_vnode_id`, and then points of the series are
+    /// rejected by `vnode_id`.
+    ///
+    /// `new_vnode_id` is re-created if it exists, so an interrupted split can
+    /// be copied again.
+    async fn split_vnode(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        new_vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()>;
+
+    /// Remove series whose hash is in `hash_range` from the index and files of
+    /// the storage unit, after they are copied by `split_vnode()`.
+    async fn remove_moved_series(
+        &self,
+        tenant: &str,
+        database: &str,
+        vnode_id: VnodeId,
+        hash_range: &RangeInclusive<u64>,
+    ) -> Result<()>;
+
     // TODO this method is the same as remove_tsfamily and not be referenced,
     // we can delete it.
     #[deprecated]
@@ -1,109 +1,218 @@
-use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-const ARG_PRINT: &str = "print"; // To print something
-const ARG_REPAIR: &str = "repair"; // To repair something
-const ARG_TSM: &str = "--tsm"; // To print a .tsm file
-const ARG_TOMBSTONE: &str = "--tombstone"; // To print a .tsm file with tombsotne
-const ARG_SUMMARY: &str = "--summary"; // To print a summary file
-const ARG_WAL: &str = "--wal"; // To print a wal file
-const ARG_INDEX: &str = "--index"; // To print a wal file
+use clap::{Parser, Subcommand, ValueEnum};
+use meta::model::meta_admin::AdminMeta;
+use meta::model::MetaRef;
+use tskv::tools::{self, DumpFormat};
 
-/// # Example
-/// tskv print [--tsm <tsm_path>] [--tombstone]
-/// tskv print [--summary <summary_path>]
-/// tskv print [--wal <wal_path>]
-/// tskv repair [--index <file_name>]
-/// - --tsm <tsm_path> print statistics for .tsm file at <tsm_path> .
-/// - --tombstone also print tombstone for every field_id in .tsm file.
-#[tokio::main]
-async fn main() {
-    let mut args = env::args().peekable();
-
-    let mut show_tsm = false;
-    let mut tsm_path: Option<String> = None;
-    let mut show_tombstone = false;
+/// Offline tools for files of a stopped CnosDB node.
+#[derive(Debug, Parser)]
+#[command(name = "tskv")]
+#[command(long_about = r#"Offline tools for files of a stopped CnosDB node.
+Examples:
+    # Check files of a vnode:
+    tskv verify /var/lib/cnosdb/data/cnosdb.public/3
+    # Dump a vnode as line protocol:
+    tskv dump /var/lib/cnosdb/data/cnosdb.public/3 --format line-protocol -o 3.txt
+    # Compact vnode 3, the meta service in the configuration file must be reachable:
+    tskv compact --config ./config/config.toml --vnode-id 3"#)]
+struct Cli {
+    #[command(subcommand)]
+    subcmd: CliCommand,
+    /// Level of logs printed to stderr and written into `--log-dir`.
+    #[arg(long, global = true, default_value = "warn")]
+    log_level: String,
+    /// Directory of log files.
+    #[arg(long, global = true, default_value_os_t = std::env::temp_dir().join("cnosdb_tskv"))]
+    log_dir: PathBuf,
+}
 
-    let mut show_summary = false;
-    let mut summary_path: Option<String> = None;
+#[derive(Debug, Subcommand)]
+enum CliCommand {
+    /// Check TSM, delta, tombstone, WAL and summary files, exit with 1 if any file is corrupted.
+    Verify {
+        /// Files or directories to check, directories are walked recursively.
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Dump values of a TSM (or delta) file or a vnode directory.
+    Dump {
+        /// Path of a TSM file or a vnode directory.
+        path: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+        /// Output file, the default is stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Regenerate the index of a vnode directory from series in its TSM and delta files.
+    RebuildIndex {
+        /// Path of the vnode directory.
+        vnode_dir: PathBuf,
+    },
+    /// Run a full compaction on a vnode of a stopped node.
+    Compact {
+        /// Path to the configuration file of the node.
+        #[arg(long)]
+        config: PathBuf,
+        /// ID of the vnode.
+        #[arg(long)]
+        vnode_id: u32,
+        /// Do not read table schemas from the meta service, data of field columns
+        /// whose type or encoding was changed keep the old type or encoding.
+        #[arg(long)]
+        no_meta: bool,
+    },
+    /// Print statistics of a file.
+    Print {
+        /// Print statistics of a .tsm file.
+        #[arg(long)]
+        tsm: Option<PathBuf>,
+        /// Also print tombstone for every field_id in the .tsm file.
+        #[arg(long)]
+        tombstone: bool,
+        /// Print a summary file.
+        #[arg(long)]
+        summary: Option<PathBuf>,
+        /// Print a WAL file.
+        #[arg(long)]
+        wal: Option<PathBuf>,
+    },
+    /// Repair a file.
+    Repair {
+        /// Repair an index binlog file.
+        #[arg(long)]
+        index: Option<String>,
+        /// Truncate torn bytes after the last valid record of a .wal file.
+        #[arg(long)]
+        wal: Option<PathBuf>,
+    },
+}
 
-    let mut show_wal = false;
-    let mut wal_path: Option<String> = None;
+#[derive(Debug, Copy, Clone, ValueEnum)]
+enum Format {
+    Csv,
+    LineProtocol,
+}
 
-    let mut repair_index = false;
-    let mut index_file: Option<String> = None;
+impl From<Format> for DumpFormat {
+    fn from(value: Format) -> Self {
+        match value {
+            Format::Csv => DumpFormat::Csv,
+            Format::LineProtocol => DumpFormat::LineProtocol,
+        }
+    }
+}
 
-    while let Some(arg) = args.peek() {
-        // --print [--tsm <path>]
-        if arg.as_str() == ARG_PRINT {
-            while let Some(print_arg) = args.next() {
-                match print_arg.as_str() {
-                    ARG_TSM => {
-                        show_tsm = true;
-                        tsm_path = args.next();
-                        if tsm_path.is_none() {
-                            println!("Invalid arguments: --tsm <tsm_path>");
-                        }
-                    }
-                    ARG_TOMBSTONE => {
-                        show_tombstone = true;
-                    }
-                    ARG_SUMMARY => {
-                        show_summary = true;
-                        summary_path = args.next();
-                        if summary_path.is_none() {
-                            println!("Invalid arguments: --summary <summary_path>")
-                        }
-                    }
-                    ARG_WAL => {
-                        show_wal = true;
-                        wal_path = args.next();
-                        if wal_path.is_none() {
-                            println!("Invalid arguments: --wal <wal_path>")
-                        }
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let _log_guards = trace::init_global_tracing(&cli.log_dir, &cli.log_level, "tskv.log", None);
+    let result = match cli.subcmd {
+        CliCommand::Verify { paths } => tools::verify(&paths).await.map(|report| {
+            println!("{report}");
+            report.is_ok()
+        }),
+        CliCommand::Dump {
+            path,
+            format,
+            output,
+        } => {
+            let writer: Box<dyn Write> = match output {
+                Some(p) => match File::create(&p) {
+                    Ok(f) => Box::new(f),
+                    Err(e) => {
+                        eprintln!("Failed to create output file '{}': {e}", p.display());
+                        return ExitCode::FAILURE;
                     }
-                    _ => {}
+                },
+                None => Box::new(std::io::stdout().lock()),
+            };
+            let mut writer = BufWriter::new(writer);
+            tools::dump(&path, format.into(), &mut writer)
+                .await
+                .map(|_| true)
+        }
+        CliCommand::RebuildIndex { vnode_dir } => {
+            tools::rebuild_index(&vnode_dir).await.map(|report| {
+                println!("{report}");
+                true
+            })
+        }
+        CliCommand::Compact {
+            config,
+            vnode_id,
+            no_meta,
+        } => {
+            let config = match config::get_config(&config) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("{e}");
+                    return ExitCode::FAILURE;
                 }
+            };
+            let opt = tskv::Options::from(&config);
+            let meta = if no_meta {
+                None
+            } else {
+                let meta: MetaRef = AdminMeta::new(config).await;
+                Some(meta)
+            };
+            tools::compact_vnode(&opt, vnode_id, meta)
+                .await
+                .map(|report| {
+                    println!("{report}");
+                    true
+                })
+        }
+        CliCommand::Print {
+            tsm,
+            tombstone,
+            summary,
+            wal,
+        } => {
+            if let Some(p) = tsm {
+                println!("TSM Path: {}, ShowTombstone: {}", p.display(), tombstone);
+                tskv::print_tsm_statistics(p, tombstone).await;
             }
-        } else if arg.as_str() == ARG_REPAIR {
-            while let Some(repair_arg) = args.next() {
-                if repair_arg.as_str() == ARG_INDEX {
-                    repair_index = true;
-                    index_file = args.next();
-                    if index_file.is_none() {
-                        println!("Invalid arguments: --index <index file>");
-                    }
-                }
+            if let Some(p) = summary {
+                println!("Summary Path: {}", p.display());
+                tskv::print_summary_statistics(p).await;
             }
+            if let Some(p) = wal {
+                println!("Wal Path: {}", p.display());
+                tskv::print_wal_statistics(p).await;
+            }
+            Ok(true)
         }
-        args.next();
-    }
-
-    if show_tsm {
-        if let Some(p) = tsm_path {
-            println!("TSM Path: {}, ShowTombstone: {}", p, show_tombstone);
-            tskv::print_tsm_statistics(p, show_tombstone).await;
-        }
-    }
-
-    if show_summary {
-        if let Some(p) = summary_path {
-            println!("Summary Path: {}", p);
-            tskv::print_summary_statistics(p).await;
-        }
-    }
-
-    if show_wal {
-        if let Some(p) = wal_path {
-            println!("Wal Path: {}", p);
-            tskv::print_wal_statistics(p).await;
+        CliCommand::Repair { index, wal } => {
+            if let Some(name) = index {
+                println!("repair index: {}", name);
+                let result = tskv::index::binlog::repair_index_file(&name).await;
+                println!("repair index result: {:?}", result);
+            }
+            if let Some(p) = wal {
+                println!("repair wal: {}", p.display());
+                match tskv::repair_wal_file(&p).await {
+                    Ok((max_seq, truncated)) => println!(
+                        "repair wal result: max sequence: {max_seq}, truncated: {truncated} bytes"
+                    ),
+                    Err(e) => println!("repair wal result: {:?}", e),
+                }
+            }
+            Ok(true)
         }
-    }
+    };
 
-    if repair_index {
-        if let Some(name) = index_file {
-            println!("repair index: {}", name);
-            let result = tskv::index::binlog::repair_index_file(&name).await;
-            println!("repair index result: {:?}", result);
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
         }
     }
 }
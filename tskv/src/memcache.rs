@@ -1,15 +1,21 @@
-use std::collections::{HashMap, LinkedList};
+use std::collections::{HashMap, HashSet, LinkedList};
 use std::fmt::Display;
 use std::mem::size_of_val;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
+use datafusion::arrow::array::{
+    Array, ArrayBuilder, ArrayRef, BinaryArray, BinaryBuilder, BooleanArray, BooleanBuilder,
+    Float64Array, Float64Builder, Int64Array, Int64Builder, NullArray, UInt64Array, UInt64Builder,
+};
+use datafusion::arrow::compute::filter;
+use datafusion::arrow::datatypes::DataType as ArrowDataType;
 use flatbuffers::{ForwardsUOffset, Vector};
 use memory_pool::{MemoryConsumer, MemoryPoolRef, MemoryReservation};
 use minivec::{mini_vec, MiniVec};
 use models::predicate::domain::TimeRange;
 use models::schema::{
-    timestamp_convert, Precision, TableColumn, TskvTableSchema, TskvTableSchemaRef,
+    timestamp_convert, PhysicalCType, Precision, TableColumn, TskvTableSchema, TskvTableSchemaRef,
 };
 use models::utils::split_id;
 use models::{
@@ -21,6 +27,7 @@ use trace::error;
 use utils::bitset::ImmutBitSet;
 
 use crate::error::Result;
+use crate::tsm::DataBlock;
 use crate::{byte_utils, Error, TseriesFamilyId};
 
 #[derive(Debug, Clone)]
@@ -79,6 +86,37 @@ impl FieldVal {
         }
     }
 
+    /// Converts the value to `value_type` of a field column whose data type
+    /// is changed, values out of the range of `value_type` are saturated.
+    /// Returns None if values of the type can't be converted to `value_type`.
+    pub fn convert_to(self, value_type: ValueType) -> Option<FieldVal> {
+        if self.value_type() == value_type {
+            return Some(self);
+        }
+        let val = match (self, value_type) {
+            (FieldVal::Integer(v), ValueType::Float) => FieldVal::Float(v as f64),
+            (FieldVal::Unsigned(v), ValueType::Float) => FieldVal::Float(v as f64),
+            (FieldVal::Integer(v), ValueType::Unsigned) => FieldVal::Unsigned(v.max(0) as u64),
+            (FieldVal::Unsigned(v), ValueType::Integer) => {
+                FieldVal::Integer(v.min(i64::MAX as u64) as i64)
+            }
+            (FieldVal::Float(v), ValueType::String) => {
+                FieldVal::Bytes(v.to_string().as_bytes().into())
+            }
+            (FieldVal::Integer(v), ValueType::String) => {
+                FieldVal::Bytes(v.to_string().as_bytes().into())
+            }
+            (FieldVal::Unsigned(v), ValueType::String) => {
+                FieldVal::Bytes(v.to_string().as_bytes().into())
+            }
+            (FieldVal::Boolean(v), ValueType::String) => {
+                FieldVal::Bytes(v.to_string().as_bytes().into())
+            }
+            _ => return None,
+        };
+        Some(val)
+    }
+
     pub fn heap_size(&self) -> usize {
         if let FieldVal::Bytes(val) = self {
             val.capacity()
@@ -239,6 +277,27 @@ impl RowData {
                         }
                     }
                 }
+                FieldType::Bytes => {
+                    let len = column.bytes_values_len()?;
+                    let column_nullbits =
+                        ImmutBitSet::new_without_check(len, column_nullbit.bytes());
+                    if !column_nullbits.get(row_count) {
+                        continue;
+                    }
+                    let val = column.bytes_values()?.get(row_count);
+                    match schema.column(column_name) {
+                        None => {
+                            error!("column {} not found in schema", column_name);
+                        }
+                        Some(column) => {
+                            let field_id = column.id;
+                            let field_idx = fields_id.get(&field_id).unwrap();
+                            let val = val.value().map(|v| v.bytes()).unwrap_or_default();
+                            fields[*field_idx] = Some(FieldVal::Bytes(MiniVec::from(val)));
+                            has_fields = true;
+                        }
+                    }
+                }
                 _ => {
                     error!("unsupported field type");
                 }
@@ -286,11 +345,499 @@ pub struct RowGroup {
     pub size: usize,
 }
 
+/// Number of rows appended to the arrow builders of a `ColumnGroup` before
+/// they are frozen into arrays.
+const COLUMN_GROUP_BATCH_ROWS: usize = 1024;
+
+/// Builder of values of a field column, typed by the first non-null value.
+#[derive(Debug)]
+enum FieldBuilder {
+    /// Only nulls are appended, holds the number of them.
+    Null(usize),
+    Float(Float64Builder),
+    Integer(Int64Builder),
+    Unsigned(UInt64Builder),
+    Boolean(BooleanBuilder),
+    Bytes(BinaryBuilder),
+}
+
+impl FieldBuilder {
+    fn append(&mut self, value: Option<&FieldVal>) {
+        let value = match value {
+            Some(v) => v,
+            None => {
+                match self {
+                    Self::Null(n) => *n += 1,
+                    Self::Float(b) => b.append_null(),
+                    Self::Integer(b) => b.append_null(),
+                    Self::Unsigned(b) => b.append_null(),
+                    Self::Boolean(b) => b.append_null(),
+                    Self::Bytes(b) => b.append_null(),
+                }
+                return;
+            }
+        };
+        if let Self::Null(nulls) = *self {
+            *self = Self::with_nulls(value.value_type(), nulls);
+        }
+        match (self, value) {
+            (Self::Float(b), FieldVal::Float(v)) => b.append_value(*v),
+            (Self::Integer(b), FieldVal::Integer(v)) => b.append_value(*v),
+            (Self::Unsigned(b), FieldVal::Unsigned(v)) => b.append_value(*v),
+            (Self::Boolean(b), FieldVal::Boolean(v)) => b.append_value(*v),
+            (Self::Bytes(b), FieldVal::Bytes(v)) => b.append_value(&v[..]),
+            (builder, v) => {
+                // The data type of the column is changed, values are checked
+                // by `check_value_type()` before appended.
+                match v.clone().convert_to(builder.value_type()) {
+                    Some(v) => builder.append(Some(&v)),
+                    None => {
+                        error!(
+                            "value type {} mismatched with column values type {}",
+                            v.value_type(),
+                            builder.value_type()
+                        );
+                        builder.append(None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the type of values in a builder of `builder_type` after a value
+    /// of `value_type` is appended, or an error if the value can't be converted
+    /// to `builder_type`.
+    fn check_value_type(builder_type: ValueType, value_type: ValueType) -> Result<ValueType> {
+        match builder_type {
+            ValueType::Unknown => Ok(value_type),
+            t if value_type.can_convert_to(t) => Ok(t),
+            t => Err(Error::ConvertValueType {
+                from: value_type,
+                to: t,
+            }),
+        }
+    }
+
+    fn with_nulls(value_type: ValueType, nulls: usize) -> Self {
+        // Not preallocated for `COLUMN_GROUP_BATCH_ROWS` rows, memory of
+        // builders grows with the rows reserved by `MemCache::write_group()`.
+        let capacity = nulls + 1;
+        let mut builder = match value_type {
+            ValueType::Float => Self::Float(Float64Builder::with_capacity(capacity)),
+            ValueType::Integer => Self::Integer(Int64Builder::with_capacity(capacity)),
+            ValueType::Unsigned => Self::Unsigned(UInt64Builder::with_capacity(capacity)),
+            ValueType::Boolean => Self::Boolean(BooleanBuilder::with_capacity(capacity)),
+            ValueType::String => Self::Bytes(BinaryBuilder::new()),
+            ValueType::Unknown => return Self::Null(nulls),
+        };
+        match &mut builder {
+            Self::Null(n) => *n += nulls,
+            Self::Float(b) => b.append_nulls(nulls),
+            Self::Integer(b) => b.append_nulls(nulls),
+            Self::Unsigned(b) => b.append_nulls(nulls),
+            Self::Boolean(b) => b.append_nulls(nulls),
+            Self::Bytes(b) => (0..nulls).for_each(|_| b.append_null()),
+        }
+        builder
+    }
+
+    fn value_type(&self) -> ValueType {
+        match self {
+            Self::Null(_) => ValueType::Unknown,
+            Self::Float(_) => ValueType::Float,
+            Self::Integer(_) => ValueType::Integer,
+            Self::Unsigned(_) => ValueType::Unsigned,
+            Self::Boolean(_) => ValueType::Boolean,
+            Self::Bytes(_) => ValueType::String,
+        }
+    }
+
+    /// Builds the array and resets this builder.
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Null(n) => {
+                let array: ArrayRef = Arc::new(NullArray::new(*n));
+                *n = 0;
+                array
+            }
+            Self::Float(b) => Arc::new(b.finish()),
+            Self::Integer(b) => Arc::new(b.finish()),
+            Self::Unsigned(b) => Arc::new(b.finish()),
+            Self::Boolean(b) => Arc::new(b.finish()),
+            Self::Bytes(b) => Arc::new(b.finish()),
+        }
+    }
+
+    fn finish_cloned(&self) -> ArrayRef {
+        match self {
+            Self::Null(n) => Arc::new(NullArray::new(*n)),
+            Self::Float(b) => Arc::new(b.finish_cloned()),
+            Self::Integer(b) => Arc::new(b.finish_cloned()),
+            Self::Unsigned(b) => Arc::new(b.finish_cloned()),
+            Self::Boolean(b) => Arc::new(b.finish_cloned()),
+            Self::Bytes(b) => Arc::new(b.finish_cloned()),
+        }
+    }
+}
+
+/// Returns the value at `i` of an array built by `FieldBuilder`.
+fn array_field_val(array: &dyn Array, i: usize) -> Option<FieldVal> {
+    if array.is_null(i) {
+        return None;
+    }
+    let array = array.as_any();
+    if let Some(a) = array.downcast_ref::<Float64Array>() {
+        Some(FieldVal::Float(a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<Int64Array>() {
+        Some(FieldVal::Integer(a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<UInt64Array>() {
+        Some(FieldVal::Unsigned(a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<BooleanArray>() {
+        Some(FieldVal::Boolean(a.value(i)))
+    } else {
+        array
+            .downcast_ref::<BinaryArray>()
+            .map(|a| FieldVal::Bytes(MiniVec::from(a.value(i))))
+    }
+}
+
+/// Converts values of an array built by `FieldBuilder` to `value_type`,
+/// values can't be converted are replaced by nulls.
+fn convert_array(array: &ArrayRef, value_type: ValueType) -> ArrayRef {
+    let data_type = match value_type {
+        ValueType::Float => ArrowDataType::Float64,
+        ValueType::Integer => ArrowDataType::Int64,
+        ValueType::Unsigned => ArrowDataType::UInt64,
+        ValueType::Boolean => ArrowDataType::Boolean,
+        ValueType::String => ArrowDataType::Binary,
+        ValueType::Unknown => return array.clone(),
+    };
+    if array.data_type() == &data_type || array.data_type() == &ArrowDataType::Null {
+        return array.clone();
+    }
+    let mut builder = FieldBuilder::with_nulls(value_type, 0);
+    for i in 0..array.len() {
+        builder.append(array_field_val(array.as_ref(), i).as_ref());
+    }
+    builder.finish()
+}
+
+/// Appends the non-null values of an array built by `FieldBuilder` and the
+/// timestamps of them to a `DataBlock`, creates the `DataBlock` if it's None.
+fn append_array_to_data_block(
+    data_block: &mut Option<DataBlock>,
+    time_array: &Int64Array,
+    array: &dyn Array,
+) {
+    let value_type = match array.data_type() {
+        ArrowDataType::Float64 => ValueType::Float,
+        ArrowDataType::Int64 => ValueType::Integer,
+        ArrowDataType::UInt64 => ValueType::Unsigned,
+        ArrowDataType::Boolean => ValueType::Boolean,
+        ArrowDataType::Binary => ValueType::String,
+        _ => return,
+    };
+    let data_block = data_block.get_or_insert_with(|| DataBlock::new(array.len(), value_type));
+    let array = array.as_any();
+    match data_block {
+        DataBlock::F64 { ts, val, .. } => {
+            if let Some(a) = array.downcast_ref::<Float64Array>() {
+                for (t, v) in time_array.values().iter().zip(a.iter()) {
+                    if let Some(v) = v {
+                        ts.push(*t);
+                        val.push(v);
+                    }
+                }
+            }
+        }
+        DataBlock::I64 { ts, val, .. } => {
+            if let Some(a) = array.downcast_ref::<Int64Array>() {
+                for (t, v) in time_array.values().iter().zip(a.iter()) {
+                    if let Some(v) = v {
+                        ts.push(*t);
+                        val.push(v);
+                    }
+                }
+            }
+        }
+        DataBlock::U64 { ts, val, .. } => {
+            if let Some(a) = array.downcast_ref::<UInt64Array>() {
+                for (t, v) in time_array.values().iter().zip(a.iter()) {
+                    if let Some(v) = v {
+                        ts.push(*t);
+                        val.push(v);
+                    }
+                }
+            }
+        }
+        DataBlock::Bool { ts, val, .. } => {
+            if let Some(a) = array.downcast_ref::<BooleanArray>() {
+                for (t, v) in time_array.values().iter().zip(a.iter()) {
+                    if let Some(v) = v {
+                        ts.push(*t);
+                        val.push(v);
+                    }
+                }
+            }
+        }
+        DataBlock::Str { ts, val, .. } => {
+            if let Some(a) = array.downcast_ref::<BinaryArray>() {
+                for (t, v) in time_array.values().iter().zip(a.iter()) {
+                    if let Some(v) = v {
+                        ts.push(*t);
+                        val.push(MiniVec::from(v));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Frozen rows of a `ColumnGroup`.
+#[derive(Debug, Clone)]
+struct ColumnChunk {
+    time: Int64Array,
+    /// Field columns in the order of column id.
+    fields: Vec<ArrayRef>,
+}
+
+impl ColumnChunk {
+    fn len(&self) -> usize {
+        self.time.len()
+    }
+}
+
+/// Rows of a series written with the same schema, stored column by column
+/// in arrow arrays. Builders are not preallocated, so the memory used is
+/// bounded by the sizes of row groups reserved in the `MemCache`.
+#[derive(Debug)]
+pub struct ColumnGroup {
+    pub schema: TskvTableSchemaRef,
+    pub range: TimeRange,
+    /// Frozen rows, the arrays are shared with readers without copying.
+    chunks: Vec<ColumnChunk>,
+    /// Builders of rows not frozen.
+    time_builder: Int64Builder,
+    field_builders: Vec<FieldBuilder>,
+}
+
+impl ColumnGroup {
+    pub fn new(schema: TskvTableSchemaRef) -> Self {
+        let field_builders = (0..schema.field_num())
+            .map(|_| FieldBuilder::Null(0))
+            .collect();
+        Self {
+            schema,
+            range: TimeRange {
+                min_ts: i64::MAX,
+                max_ts: i64::MIN,
+            },
+            chunks: vec![],
+            time_builder: Int64Builder::with_capacity(0),
+            field_builders,
+        }
+    }
+
+    /// Appends rows of which `fields` are in the order of column id, returns
+    /// an error and appends nothing if a value can't be converted to the type
+    /// of its column.
+    pub fn append_rows(&mut self, rows: LinkedList<RowData>) -> Result<()> {
+        let mut value_types: Vec<ValueType> =
+            self.field_builders.iter().map(|b| b.value_type()).collect();
+        for row in rows.iter() {
+            for (value_type, field) in value_types.iter_mut().zip(row.fields.iter()) {
+                if let Some(v) = field {
+                    *value_type = FieldBuilder::check_value_type(*value_type, v.value_type())?;
+                }
+            }
+        }
+
+        for row in rows {
+            self.time_builder.append_value(row.ts);
+            for (i, builder) in self.field_builders.iter_mut().enumerate() {
+                builder.append(row.fields.get(i).and_then(|f| f.as_ref()));
+            }
+            if self.time_builder.len() >= COLUMN_GROUP_BATCH_ROWS {
+                self.freeze();
+            }
+        }
+        Ok(())
+    }
+
+    /// Freezes rows in builders into a `ColumnChunk`.
+    fn freeze(&mut self) {
+        if self.time_builder.len() == 0 {
+            return;
+        }
+        self.chunks.push(ColumnChunk {
+            time: self.time_builder.finish(),
+            fields: self.field_builders.iter_mut().map(|b| b.finish()).collect(),
+        });
+    }
+
+    /// Returns all rows in `ColumnChunk`s, rows in builders are copied.
+    fn chunks(&self) -> Vec<ColumnChunk> {
+        let mut chunks = self.chunks.clone();
+        if self.time_builder.len() > 0 {
+            chunks.push(ColumnChunk {
+                time: self.time_builder.finish_cloned(),
+                fields: self
+                    .field_builders
+                    .iter()
+                    .map(|b| b.finish_cloned())
+                    .collect(),
+            });
+        }
+        chunks
+    }
+
+    /// Returns (timestamps, values) of a field column in chunks.
+    fn field_chunks(&self, field_index: usize) -> Vec<(Int64Array, ArrayRef)> {
+        let mut chunks: Vec<(Int64Array, ArrayRef)> = self
+            .chunks
+            .iter()
+            .filter_map(|c| Some((c.time.clone(), c.fields.get(field_index)?.clone())))
+            .collect();
+        if self.time_builder.len() > 0 {
+            if let Some(builder) = self.field_builders.get(field_index) {
+                chunks.push((self.time_builder.finish_cloned(), builder.finish_cloned()));
+            }
+        }
+        chunks
+    }
+
+    fn time_chunks(&self) -> Vec<Int64Array> {
+        let mut chunks: Vec<Int64Array> = self.chunks.iter().map(|c| c.time.clone()).collect();
+        if self.time_builder.len() > 0 {
+            chunks.push(self.time_builder.finish_cloned());
+        }
+        chunks
+    }
+
+    /// Returns the physical type of field columns in the order of column id.
+    fn field_types(&self) -> Vec<ValueType> {
+        let mut field_columns: Vec<&TableColumn> = self
+            .schema
+            .columns()
+            .iter()
+            .filter(|c| c.column_type.is_field())
+            .collect();
+        field_columns.sort_by_key(|c| c.id);
+        field_columns
+            .iter()
+            .map(|c| match c.column_type.to_physical_type() {
+                PhysicalCType::Field(t) => t,
+                _ => ValueType::Unknown,
+            })
+            .collect()
+    }
+
+    /// Changes the type of values of a field column, rows already written are
+    /// frozen and converted when they are read.
+    fn change_field_type(&mut self, field_index: usize, value_type: ValueType) {
+        if field_index >= self.field_builders.len()
+            || self.field_builders[field_index].value_type() == value_type
+        {
+            return;
+        }
+        self.freeze();
+        self.field_builders[field_index] = FieldBuilder::with_nulls(value_type, 0);
+    }
+
+    /// Returns non-null values of each field column as a `DataBlock`,
+    /// values are in the order they are written.
+    pub fn field_data_blocks(&self) -> Vec<(ColumnId, DataBlock)> {
+        let chunks = self.chunks();
+        let field_types = self.field_types();
+        let mut data_blocks = Vec::with_capacity(self.field_builders.len());
+        for (column_id, field_index) in self.schema.fields_id() {
+            let value_type = field_types
+                .get(field_index)
+                .copied()
+                .unwrap_or(ValueType::Unknown);
+            let mut data_block = None;
+            for chunk in chunks.iter() {
+                if let Some(array) = chunk.fields.get(field_index) {
+                    let array = convert_array(array, value_type);
+                    append_array_to_data_block(&mut data_block, &chunk.time, array.as_ref());
+                }
+            }
+            if let Some(data_block) = data_block {
+                data_blocks.push((column_id, data_block));
+            }
+        }
+        data_blocks
+    }
+
+    fn delete_field(&mut self, field_index: usize) {
+        if field_index >= self.field_builders.len() {
+            return;
+        }
+        self.field_builders.remove(field_index);
+        for chunk in self.chunks.iter_mut() {
+            chunk.fields.remove(field_index);
+        }
+    }
+
+    fn insert_null_field(&mut self, field_index: usize) {
+        if field_index > self.field_builders.len() {
+            return;
+        }
+        self.field_builders
+            .insert(field_index, FieldBuilder::Null(self.time_builder.len()));
+        for chunk in self.chunks.iter_mut() {
+            chunk
+                .fields
+                .insert(field_index, Arc::new(NullArray::new(chunk.len())));
+        }
+    }
+
+    fn delete_rows(&mut self, range: &TimeRange) {
+        self.freeze();
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        for chunk in self.chunks.drain(..) {
+            let predicate = BooleanArray::from(
+                chunk
+                    .time
+                    .values()
+                    .iter()
+                    .map(|ts| *ts < range.min_ts || *ts > range.max_ts)
+                    .collect::<Vec<bool>>(),
+            );
+            if predicate.true_count() == chunk.len() {
+                chunks.push(chunk);
+                continue;
+            }
+            if predicate.true_count() == 0 {
+                continue;
+            }
+            let time = filter(&chunk.time, &predicate).map(|a| Int64Array::from(a.to_data()));
+            let fields = chunk
+                .fields
+                .iter()
+                .map(|a| filter(a.as_ref(), &predicate))
+                .collect::<std::result::Result<Vec<_>, _>>();
+            match (time, fields) {
+                (Ok(time), Ok(fields)) => chunks.push(ColumnChunk { time, fields }),
+                (Err(e), _) | (_, Err(e)) => {
+                    error!("failed to delete rows in memcache: {}", e);
+                    chunks.push(chunk);
+                }
+            }
+        }
+        self.chunks = chunks;
+    }
+
+    pub fn rows_num(&self) -> usize {
+        self.chunks.iter().map(|c| c.len()).sum::<usize>() + self.time_builder.len()
+    }
+}
+
 #[derive(Debug)]
 pub struct SeriesData {
     pub series_id: SeriesId,
     pub range: TimeRange,
-    pub groups: LinkedList<RowGroup>,
+    pub groups: LinkedList<ColumnGroup>,
 }
 
 impl SeriesData {
@@ -305,19 +852,23 @@ impl SeriesData {
         }
     }
 
-    pub fn write(&mut self, mut group: RowGroup) {
-        self.range.merge(&group.range);
-
+    pub fn write(&mut self, group: RowGroup) -> Result<()> {
         for item in self.groups.iter_mut() {
             if item.schema.schema_id == group.schema.schema_id {
+                item.append_rows(group.rows)?;
                 item.range.merge(&group.range);
-                item.rows.append(&mut group.rows);
                 item.schema = group.schema;
-                return;
+                self.range.merge(&group.range);
+                return Ok(());
             }
         }
 
-        self.groups.push_back(group);
+        let mut item = ColumnGroup::new(group.schema);
+        item.append_rows(group.rows)?;
+        item.range = group.range;
+        self.range.merge(&group.range);
+        self.groups.push_back(item);
+        Ok(())
     }
 
     pub fn delete_column(&mut self, column_id: ColumnId) {
@@ -330,9 +881,7 @@ impl SeriesData {
                 None => continue,
                 Some(index) => *index,
             };
-            for row in item.rows.iter_mut() {
-                row.fields.remove(index);
-            }
+            item.delete_field(index);
             let mut schema_t = item.schema.as_ref().clone();
             schema_t.drop_column(&name);
             //schema_t.schema_id += 1;
@@ -345,15 +894,26 @@ impl SeriesData {
             let mut schema_t = item.schema.as_ref().clone();
             schema_t.change_column(column_name, new_column.clone());
             schema_t.schema_id += 1;
+            if let PhysicalCType::Field(value_type) = new_column.column_type.to_physical_type() {
+                if let Some(index) = schema_t.fields_id().get(&new_column.id) {
+                    item.change_field_type(*index, value_type);
+                }
+            }
             item.schema = Arc::new(schema_t)
         }
     }
 
     pub fn add_column(&mut self, new_column: &TableColumn) {
         for item in self.groups.iter_mut() {
+            let field_num = item.schema.field_num();
             let mut schema_t = item.schema.as_ref().clone();
             schema_t.add_column(new_column.clone());
             schema_t.schema_id += 1;
+            if schema_t.field_num() > field_num {
+                if let Some(index) = schema_t.fields_id().get(&new_column.id) {
+                    item.insert_null_field(*index);
+                }
+            }
             item.schema = Arc::new(schema_t)
         }
     }
@@ -364,12 +924,7 @@ impl SeriesData {
         }
 
         for item in self.groups.iter_mut() {
-            item.rows = item
-                .rows
-                .iter()
-                .filter(|row| row.ts < range.min_ts || row.ts > range.max_ts)
-                .cloned()
-                .collect();
+            item.delete_rows(range);
         }
     }
 
@@ -386,17 +941,24 @@ impl SeriesData {
                 None => continue,
                 Some(v) => v,
             };
-            group
-                .rows
-                .iter()
-                .filter(|row| time_predicate(row.ts))
-                .for_each(|row| {
-                    if let Some(Some(field)) = row.fields.get(*index) {
-                        if value_predicate(field) {
-                            handle_data(field.data_value(row.ts))
+            let value_type = group
+                .field_types()
+                .get(*index)
+                .copied()
+                .unwrap_or(ValueType::Unknown);
+            for (time_array, array) in group.field_chunks(*index) {
+                let array = convert_array(&array, value_type);
+                for (i, ts) in time_array.values().iter().enumerate() {
+                    if !time_predicate(*ts) {
+                        continue;
+                    }
+                    if let Some(field) = array_field_val(array.as_ref(), i) {
+                        if value_predicate(&field) {
+                            handle_data(field.data_value(*ts))
                         }
                     }
-                });
+                }
+            }
         }
     }
 
@@ -406,18 +968,20 @@ impl SeriesData {
         mut handle_data: impl FnMut(Timestamp),
     ) {
         for group in self.groups.iter() {
-            group
-                .rows
-                .iter()
-                .filter(|row| time_predicate(row.ts))
-                .for_each(|row| handle_data(row.ts));
+            for time_array in group.time_chunks() {
+                time_array
+                    .values()
+                    .iter()
+                    .filter(|ts| time_predicate(**ts))
+                    .for_each(|ts| handle_data(*ts));
+            }
         }
     }
 
-    pub fn flat_groups(&self) -> Vec<(SchemaId, TskvTableSchemaRef, &LinkedList<RowData>)> {
+    pub fn flat_groups(&self) -> Vec<(SchemaId, TskvTableSchemaRef, &ColumnGroup)> {
         self.groups
             .iter()
-            .map(|g| (g.schema.schema_id, g.schema.clone(), &g.rows))
+            .map(|g| (g.schema.schema_id, g.schema.clone(), g))
             .collect()
     }
 }
@@ -475,19 +1039,26 @@ impl MemCache {
             .write()
             .try_grow(group.size)
             .map_err(|_| Error::MemoryExhausted)?;
+        let size = group.size;
         let index = (sid as usize) % self.part_count;
         let mut series_map = self.partions[index].write();
-        if let Some(series_data) = series_map.get(&sid) {
+        let res = if let Some(series_data) = series_map.get(&sid) {
             let series_data_ptr = series_data.clone();
             let mut series_data_ptr_w = series_data_ptr.write();
             drop(series_map);
-            series_data_ptr_w.write(group);
+            series_data_ptr_w.write(group)
         } else {
             let mut series_data = SeriesData::new(sid);
-            series_data.write(group);
-            series_map.insert(sid, Arc::new(RwLock::new(series_data)));
+            let res = series_data.write(group);
+            if res.is_ok() {
+                series_map.insert(sid, Arc::new(RwLock::new(series_data)));
+            }
+            res
+        };
+        if res.is_err() {
+            self.memory.write().shrink(size);
         }
-        Ok(())
+        res
     }
 
     pub fn read_field_data(
@@ -621,6 +1192,190 @@ impl MemCache {
     }
 }
 
+/// The latest value of a field of a series.
+#[derive(Debug, Clone)]
+pub struct LastValue {
+    pub value: DataType,
+    /// Whether the value is known to be not older than any value of the field in column files.
+    /// Values written after the vnode was opened are not verified until a query reads the files.
+    pub verified: bool,
+}
+
+/// Cache of the latest value of each field of series in a vnode, maintained
+/// on the write path for tables with `last_value_cache` enabled.
+#[derive(Debug, Default)]
+pub struct LastValueCache {
+    /// Maximum number of series to cache, values of other series are not cached.
+    max_series: usize,
+    /// Tables seen enabled, cached values are dropped when one of them is seen disabled,
+    /// since values written while disabled are missing.
+    tables: RwLock<HashSet<String>>,
+    /// Increased when cached values are invalidated, values read before that
+    /// will not be filled into the cache.
+    epoch: AtomicU64,
+    series: RwLock<HashMap<SeriesId, HashMap<ColumnId, LastValue>>>,
+}
+
+impl LastValueCache {
+    pub fn new(max_series: usize) -> Self {
+        Self {
+            max_series,
+            tables: RwLock::new(HashSet::new()),
+            epoch: AtomicU64::new(0),
+            series: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self, schema: &TskvTableSchema) -> bool {
+        let seen = self.tables.read().contains(&schema.name);
+        match (schema.last_value_cache, seen) {
+            (true, true) | (false, false) => {}
+            (true, false) => {
+                self.tables.write().insert(schema.name.clone());
+            }
+            (false, true) => {
+                if self.tables.write().remove(&schema.name) {
+                    self.clear();
+                }
+            }
+        }
+        schema.last_value_cache && self.max_series > 0
+    }
+
+    /// Get the latest value of each field in the row group.
+    pub fn collect_row_group(group: &RowGroup) -> Vec<(ColumnId, DataType)> {
+        let mut latest: Vec<Option<(Timestamp, &FieldVal)>> = vec![None; group.schema.field_num()];
+        for row in group.rows.iter() {
+            for (field, last) in row.fields.iter().zip(latest.iter_mut()) {
+                if let Some(val) = field {
+                    // Rows written later overwrite rows with the same timestamp.
+                    if !matches!(last, Some((ts, _)) if *ts > row.ts) {
+                        *last = Some((row.ts, val));
+                    }
+                }
+            }
+        }
+
+        group
+            .schema
+            .fields_id()
+            .into_iter()
+            .filter_map(|(column_id, i)| {
+                latest
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .map(|(ts, val)| (column_id, val.data_value(ts)))
+            })
+            .collect()
+    }
+
+    /// Update the cache by values just written.
+    pub fn update(&self, sid: SeriesId, values: Vec<(ColumnId, DataType)>) {
+        if values.is_empty() {
+            return;
+        }
+        let mut series = self.series.write();
+        if !series.contains_key(&sid) && series.len() >= self.max_series {
+            return;
+        }
+        let columns = series.entry(sid).or_default();
+        for (column_id, value) in values {
+            match columns.get_mut(&column_id) {
+                Some(last) => {
+                    if value.timestamp() >= last.value.timestamp() {
+                        last.value = value;
+                    }
+                }
+                None => {
+                    columns.insert(
+                        column_id,
+                        LastValue {
+                            value,
+                            verified: false,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Fill the cache by the latest value read from both caches and files,
+    /// if no values were invalidated since `epoch`.
+    pub fn fill(&self, epoch: u64, sid: SeriesId, column_id: ColumnId, value: DataType) {
+        let mut series = self.series.write();
+        if self.epoch.load(Ordering::Acquire) != epoch {
+            return;
+        }
+        if !series.contains_key(&sid) && series.len() >= self.max_series {
+            return;
+        }
+        let columns = series.entry(sid).or_default();
+        match columns.get_mut(&column_id) {
+            // Value written after the read is newer.
+            Some(last) if last.value.timestamp() >= value.timestamp() => {
+                last.verified = true;
+            }
+            _ => {
+                columns.insert(
+                    column_id,
+                    LastValue {
+                        value,
+                        verified: true,
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn get(&self, sid: SeriesId, column_id: ColumnId) -> Option<LastValue> {
+        self.series
+            .read()
+            .get(&sid)
+            .and_then(|columns| columns.get(&column_id))
+            .cloned()
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    fn clear(&self) {
+        let mut series = self.series.write();
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+        series.clear();
+    }
+
+    pub fn delete_series(&self, sids: &[SeriesId]) {
+        let mut series = self.series.write();
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+        for sid in sids {
+            series.remove(sid);
+        }
+    }
+
+    pub fn delete_columns(&self, field_ids: &[FieldId]) {
+        let mut series = self.series.write();
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+        for fid in field_ids {
+            let (column_id, sid) = split_id(*fid);
+            if let Some(columns) = series.get_mut(&sid) {
+                columns.remove(&column_id);
+            }
+        }
+    }
+
+    pub fn delete_column(&self, sids: &[SeriesId], column_id: ColumnId) {
+        let mut series = self.series.write();
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+        for sid in sids {
+            if let Some(columns) = series.get_mut(sid) {
+                columns.remove(&column_id);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DataType {
     U64(i64, u64),
@@ -682,6 +1437,37 @@ impl DataType {
         }
     }
 
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            DataType::U64(..) => ValueType::Unsigned,
+            DataType::I64(..) => ValueType::Integer,
+            DataType::Str(..) => ValueType::String,
+            DataType::F64(..) => ValueType::Float,
+            DataType::Bool(..) => ValueType::Boolean,
+        }
+    }
+
+    pub fn into_field_val(self) -> FieldVal {
+        match self {
+            DataType::U64(_, val) => FieldVal::Unsigned(val),
+            DataType::I64(_, val) => FieldVal::Integer(val),
+            DataType::Str(_, val) => FieldVal::Bytes(val),
+            DataType::F64(_, val) => FieldVal::Float(val),
+            DataType::Bool(_, val) => FieldVal::Boolean(val),
+        }
+    }
+
+    /// Converts the value to `value_type`, see `FieldVal::convert_to`.
+    pub fn convert_to(self, value_type: ValueType) -> Option<DataType> {
+        if self.value_type() == value_type {
+            return Some(self);
+        }
+        let ts = self.timestamp();
+        self.into_field_val()
+            .convert_to(value_type)
+            .map(|v| DataType::with_field_val(ts, v))
+    }
+
     #[cfg(test)]
     pub fn to_bytes(&self) -> MiniVec<u8> {
         match self {
@@ -733,13 +1519,13 @@ impl Display for DataType {
 }
 
 pub(crate) mod test {
-    use std::collections::{HashMap, LinkedList};
+    use std::collections::{BTreeMap, HashMap, LinkedList};
     use std::mem::size_of;
     use std::sync::Arc;
 
     use models::predicate::domain::TimeRange;
     use models::schema::TskvTableSchema;
-    use models::{SchemaId, SeriesId, Timestamp};
+    use models::{ColumnId, SchemaId, SeriesId, Timestamp};
     use parking_lot::RwLock;
 
     use super::{FieldVal, MemCache, RowData, RowGroup};
@@ -785,25 +1571,22 @@ pub(crate) mod test {
         let mut fname_vals_map: HashMap<String, Vec<(Timestamp, FieldVal)>> = HashMap::new();
         let series_data = cache.read().read_series_data();
         for (_sid, sdata) in series_data {
-            let sdata_rlock = sdata.read();
-            let schema_groups = sdata_rlock.flat_groups();
-            for (_sch_id, sch, row) in schema_groups {
-                let fields = sch.fields();
-                for r in row {
-                    for (i, f) in r.fields.iter().enumerate() {
-                        if let Some(fv) = f {
-                            if let Some(c) = fields.get(i) {
-                                if &c.name != "time" {
-                                    fname_vals_map
-                                        .entry(c.name.clone())
-                                        .or_default()
-                                        .push((r.ts, fv.clone()))
-                                }
-                            };
-                        }
-                    }
+            let sdata = sdata.read();
+            let mut field_names: BTreeMap<ColumnId, String> = BTreeMap::new();
+            for (_, schema, _) in sdata.flat_groups() {
+                for column in schema.fields() {
+                    field_names.insert(column.id, column.name);
                 }
             }
+            for (column_id, name) in field_names {
+                let vals = fname_vals_map.entry(name).or_default();
+                sdata.read_data(
+                    column_id,
+                    |_| true,
+                    |_| true,
+                    |d| vals.push((d.timestamp(), d.into_field_val())),
+                );
+            }
         }
 
         fname_vals_map
@@ -817,11 +1600,14 @@ mod test_memcache {
 
     use datafusion::arrow::datatypes::TimeUnit;
     use memory_pool::{GreedyMemoryPool, MemoryPool};
+    use minivec::mini_vec;
     use models::predicate::domain::TimeRange;
     use models::schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::utils::unite_id;
     use models::{SeriesId, ValueType};
 
-    use super::{FieldVal, MemCache, RowData, RowGroup};
+    use super::{DataType, FieldVal, LastValueCache, MemCache, RowData, RowGroup};
+    use crate::Error;
 
     #[test]
     fn test_write_group() {
@@ -865,7 +1651,9 @@ mod test_memcache {
             assert_eq!(sid, series_data.series_id);
             assert_eq!(TimeRange::new(1, 3), series_data.range);
             assert_eq!(1, series_data.groups.len());
-            assert_eq!(row_group_1, series_data.groups.front().unwrap().clone());
+            let group = series_data.groups.front().unwrap();
+            assert_eq!(row_group_1.schema, group.schema);
+            assert_eq!(2, group.rows_num());
         }
 
         #[rustfmt::skip]
@@ -899,7 +1687,226 @@ mod test_memcache {
             assert_eq!(sid, series_data.series_id);
             assert_eq!(TimeRange::new(1, 5), series_data.range);
             assert_eq!(2, series_data.groups.len());
-            assert_eq!(row_group_2, series_data.groups.back().unwrap().clone());
+            let group = series_data.groups.back().unwrap();
+            assert_eq!(row_group_2.schema, group.schema);
+            assert_eq!(2, group.rows_num());
         }
+
+        let mut data = vec![];
+        mem_cache.read_field_data(unite_id(4, sid), |_| true, |_| true, |d| data.push(d));
+        assert_eq!(
+            data.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec!["(1, 1)", "(3, 3)", "(5, 5)"]
+        );
+        data.clear();
+        mem_cache.read_field_data(unite_id(5, sid), |ts| ts > 3, |_| true, |d| data.push(d));
+        assert_eq!(
+            data.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec!["(5, 5)"]
+        );
+
+        let series_data = mem_cache.read_series_data();
+        let mut blocks = series_data[0]
+            .1
+            .read()
+            .groups
+            .back()
+            .unwrap()
+            .field_data_blocks();
+        blocks.sort_by_key(|(column_id, _)| *column_id);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!((blocks[0].0, blocks[0].1.len()), (4, 1));
+        assert_eq!((blocks[1].0, blocks[1].1.len()), (5, 2));
+
+        mem_cache.delete_series(&[sid], &TimeRange::new(2, 3));
+        data.clear();
+        mem_cache.read_field_data(unite_id(4, sid), |_| true, |_| true, |d| data.push(d));
+        assert_eq!(
+            data.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec!["(1, 1)", "(5, 5)"]
+        );
+    }
+
+    #[test]
+    fn test_change_column_type() {
+        let sid: SeriesId = 1;
+        let memory_pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1024 * 1024 * 1024));
+        let mem_cache = MemCache::new(1, 1000, 2, 1, &memory_pool);
+
+        #[rustfmt::skip]
+        let mut schema = TskvTableSchema::new(
+            "test_tenant".to_string(), "test_db".to_string(), "test_table".to_string(),
+            vec![
+                TableColumn::new_time_column(1, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(2, "tag_col_1".to_string()),
+                TableColumn::new(3, "f_col_1".to_string(), ColumnType::Field(ValueType::Integer), Default::default()),
+            ],
+        );
+        schema.schema_id = 1;
+        #[rustfmt::skip]
+        let row_group = RowGroup {
+            schema: Arc::new(schema.clone()),
+            range: TimeRange::new(1, 2),
+            rows: LinkedList::from([
+                RowData { ts: 1, fields: vec![Some(FieldVal::Integer(1))] },
+                RowData { ts: 2, fields: vec![Some(FieldVal::Integer(2))] },
+            ]),
+            size: 10,
+        };
+        mem_cache.write_group(sid, 1, row_group).unwrap();
+
+        let new_column = TableColumn::new(
+            3,
+            "f_col_1".to_string(),
+            ColumnType::Field(ValueType::Float),
+            Default::default(),
+        );
+        mem_cache.change_column(&[sid], "f_col_1", &new_column);
+        schema.change_column("f_col_1", new_column);
+        schema.schema_id = 2;
+        let row_group_schema = Arc::new(schema);
+        #[rustfmt::skip]
+        let row_group = RowGroup {
+            schema: row_group_schema.clone(),
+            range: TimeRange::new(3, 3),
+            rows: LinkedList::from([
+                RowData { ts: 3, fields: vec![Some(FieldVal::Float(3.5))] },
+            ]),
+            size: 10,
+        };
+        mem_cache.write_group(sid, 2, row_group).unwrap();
+
+        let mut data = vec![];
+        mem_cache.read_field_data(unite_id(3, sid), |_| true, |_| true, |d| data.push(d));
+        assert!(data.iter().all(|d| matches!(d, DataType::F64(..))));
+        assert_eq!(
+            data.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec!["(1, 1)", "(2, 2)", "(3, 3.5)"]
+        );
+
+        // Values can't be converted to the column type are rejected, no row is written.
+        let cache_size = mem_cache.cache_size();
+        #[rustfmt::skip]
+        let row_group = RowGroup {
+            schema: row_group_schema.clone(),
+            range: TimeRange::new(4, 5),
+            rows: LinkedList::from([
+                RowData { ts: 4, fields: vec![Some(FieldVal::Float(4.5))] },
+                RowData { ts: 5, fields: vec![Some(FieldVal::Bytes(mini_vec![b'5']))] },
+            ]),
+            size: 10,
+        };
+        let res = mem_cache.write_group(sid, 3, row_group);
+        assert!(
+            matches!(res, Err(Error::ConvertValueType { .. })),
+            "{res:?}"
+        );
+        assert_eq!(mem_cache.cache_size(), cache_size);
+        data.clear();
+        mem_cache.read_field_data(unite_id(3, sid), |_| true, |_| true, |d| data.push(d));
+        assert_eq!(data.len(), 3);
+
+        let series_data = mem_cache.read_series_data();
+        let series_data = series_data[0].1.read();
+        let group = series_data.groups.front().unwrap();
+        let blocks = group.field_data_blocks();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].1.len(), 3);
+        assert_eq!(blocks[0].1.field_type(), models::PhysicalDType::Float);
+
+        assert!(matches!(
+            DataType::I64(1, -1).convert_to(models::PhysicalDType::Unsigned),
+            Some(DataType::U64(1, 0))
+        ));
+        assert!(matches!(
+            DataType::U64(1, u64::MAX).convert_to(models::PhysicalDType::Integer),
+            Some(DataType::I64(1, i64::MAX))
+        ));
+        assert!(DataType::Str(1, mini_vec![b'7'])
+            .convert_to(models::PhysicalDType::Integer)
+            .is_none());
+        assert!(matches!(
+            DataType::U64(1, 7).convert_to(models::PhysicalDType::String),
+            Some(DataType::Str(1, v)) if &v[..] == b"7"
+        ));
+    }
+
+    #[test]
+    fn test_last_value_cache() {
+        let sid: SeriesId = 1;
+
+        #[rustfmt::skip]
+        let mut schema = TskvTableSchema::new(
+            "test_tenant".to_string(), "test_db".to_string(), "test_table".to_string(),
+            vec![
+                TableColumn::new_time_column(1, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(2, "tag_col_1".to_string()),
+                TableColumn::new(3, "f_col_1".to_string(), ColumnType::Field(ValueType::Float), Default::default()),
+                TableColumn::new(4, "f_col_2".to_string(), ColumnType::Field(ValueType::Integer), Default::default()),
+            ],
+        );
+        let cache = LastValueCache::new(1);
+        assert!(!cache.is_enabled(&schema));
+        schema.last_value_cache = true;
+        assert!(cache.is_enabled(&schema));
+
+        #[rustfmt::skip]
+        let row_group = RowGroup {
+            schema: Arc::new(schema.clone()),
+            range: TimeRange::new(1, 3),
+            rows: LinkedList::from([
+                RowData { ts: 1, fields: vec![Some(FieldVal::Float(1.0)), Some(FieldVal::Integer(1))] },
+                RowData { ts: 3, fields: vec![Some(FieldVal::Float(3.0)), None] },
+                RowData { ts: 2, fields: vec![Some(FieldVal::Float(2.0)), Some(FieldVal::Integer(2))] },
+            ]),
+            size: 10,
+        };
+        let mut values = LastValueCache::collect_row_group(&row_group);
+        values.sort_by_key(|(column_id, _)| *column_id);
+        assert_eq!(values.len(), 2);
+        assert_eq!(
+            (values[0].0, values[0].1.to_string()),
+            (3, DataType::F64(3, 3.0).to_string())
+        );
+        assert_eq!(
+            (values[1].0, values[1].1.to_string()),
+            (4, DataType::I64(2, 2).to_string())
+        );
+
+        cache.update(sid, values);
+        // Older values do not overwrite the latest value.
+        cache.update(sid, vec![(3, DataType::F64(2, 5.0))]);
+        let last = cache.get(sid, 3).unwrap();
+        assert!(!last.verified);
+        assert_eq!(last.value.to_string(), DataType::F64(3, 3.0).to_string());
+
+        // Values read before an invalidation are not filled.
+        let epoch = cache.epoch();
+        cache.delete_column(&[sid], 4);
+        assert!(cache.get(sid, 4).is_none());
+        cache.fill(epoch, sid, 4, DataType::I64(2, 2));
+        assert!(cache.get(sid, 4).is_none());
+
+        cache.fill(cache.epoch(), sid, 4, DataType::I64(2, 2));
+        assert!(cache.get(sid, 4).unwrap().verified);
+        cache.fill(cache.epoch(), sid, 3, DataType::F64(1, 1.0));
+        let last = cache.get(sid, 3).unwrap();
+        assert!(last.verified);
+        assert_eq!(last.value.to_string(), DataType::F64(3, 3.0).to_string());
+
+        // Values of series beyond the limit are not cached.
+        cache.update(2, vec![(3, DataType::F64(1, 1.0))]);
+        cache.fill(cache.epoch(), 2, 3, DataType::F64(1, 1.0));
+        assert!(cache.get(2, 3).is_none());
+
+        cache.delete_series(&[sid]);
+        assert!(cache.get(sid, 3).is_none());
+
+        // Values are dropped once the table is seen disabled.
+        cache.update(sid, vec![(3, DataType::F64(5, 5.0))]);
+        assert!(cache.get(sid, 3).is_some());
+        schema.last_value_cache = false;
+        assert!(!cache.is_enabled(&schema));
+        assert!(cache.get(sid, 3).is_none());
     }
 }
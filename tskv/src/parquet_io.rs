@@ -0,0 +1,505 @@
+//! Exports of vnodes to Parquet files and imports of rows into TSM files.
+//!
+//! ```text
+//! {location}/{owner}/{table}/{vnode_id}.parquet - rows of the table in the vnode
+//! ```
+//!
+//! Columns of a Parquet file are the time, tag and field columns of the table
+//! schema, each row is a series at a timestamp.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Decimal128Array, Float64Array, Int64Array,
+    StringArray, UInt64Array,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType as ArrowDataType;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::parquet::arrow::ArrowWriter;
+use minivec::MiniVec;
+use models::codec::Encoding;
+use models::meta_data::VnodeId;
+use models::schema::{ColumnType, PhysicalCType, TableColumn, TskvTableSchema};
+use models::tag::{self, Tag};
+use models::utils::unite_id;
+use models::{FieldId, SeriesId, SeriesKey, Timestamp};
+use snafu::ResultExt;
+use trace::info;
+
+use crate::backup::BackupStorage;
+use crate::compaction::sort_dedup_data_block;
+use crate::error::{self, Error, Result};
+use crate::index::ts_index::{SeriesLimit, TSIndex};
+use crate::memcache::DataType;
+use crate::reader::{ArrayBuilderPtr, RowIterator};
+use crate::tseries_family::{ColumnFile, Version};
+use crate::tsm::DataBlock;
+
+/// Max number of rows in a row group of exported Parquet files.
+const EXPORT_BATCH_SIZE: usize = 8192;
+
+pub fn table_file_key(owner: &str, table: &str, vnode_id: VnodeId) -> String {
+    format!("{owner}/{table}/{vnode_id}.parquet")
+}
+
+/// Writes rows of each table in files of `version` into a Parquet file in
+/// `storage`, tables without series in the vnode are skipped. Caller should
+/// hold the `version` so that files in it won't be deleted during the export.
+pub async fn export_vnode(
+    version: &Version,
+    ts_index: &TSIndex,
+    table_schemas: &[Arc<TskvTableSchema>],
+    owner: &str,
+    vnode_id: VnodeId,
+    storage: &BackupStorage,
+) -> Result<()> {
+    let files = sorted_column_files(version);
+    let tmp_dir = version.storage_opt().move_dir(owner, vnode_id);
+    std::fs::create_dir_all(&tmp_dir)?;
+    for table_schema in table_schemas {
+        let tmp_path = tmp_dir.join(format!("{}.parquet", table_schema.name));
+        let rows = export_table(version, &files, ts_index, table_schema, &tmp_path).await;
+        let result = match rows {
+            Ok(0) => Ok(()),
+            Ok(rows) => {
+                let key = table_file_key(owner, &table_schema.name, vnode_id);
+                info!(
+                    "Export {rows} rows of table '{owner}.{}' in vnode {vnode_id} to '{key}'",
+                    table_schema.name
+                );
+                storage.put_file(&key, &tmp_path).await
+            }
+            Err(e) => Err(e),
+        };
+        let _ = std::fs::remove_file(&tmp_path);
+        result?;
+    }
+    Ok(())
+}
+
+/// Writes rows of a table into a Parquet file at `path`, returns the number of rows.
+async fn export_table(
+    version: &Version,
+    files: &[Arc<ColumnFile>],
+    ts_index: &TSIndex,
+    table_schema: &TskvTableSchema,
+    path: &Path,
+) -> Result<usize> {
+    let mut series_ids = ts_index.get_series_id_list(&table_schema.name, &[]).await?;
+    if series_ids.is_empty() {
+        return Ok(0);
+    }
+    series_ids.sort_unstable();
+
+    let schema = table_schema.to_arrow_schema();
+    let file = std::fs::File::create(path).context(error::CreateFileSnafu { path })?;
+    let mut writer =
+        ArrowWriter::try_new(file, schema.clone(), None).context(error::ParquetSnafu)?;
+    let mut builders = table_schema
+        .columns()
+        .iter()
+        .map(|c| {
+            RowIterator::new_column_builder(&c.column_type, EXPORT_BATCH_SIZE)
+                .map(|b| ArrayBuilderPtr::new(b, c.column_type.to_physical_type()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut total_rows = 0_usize;
+    let mut batch_rows = 0_usize;
+    for sid in series_ids {
+        let series_key = match ts_index.get_series_key(sid).await? {
+            Some(key) => key,
+            None => continue,
+        };
+        // Values of each field column, None for other columns.
+        let mut data_blocks: Vec<Option<DataBlock>> = Vec::with_capacity(builders.len());
+        for column in table_schema.columns() {
+            let data_block = match column.column_type.to_physical_type() {
+                PhysicalCType::Field(value_type) => {
+                    match read_field(version, files, unite_id(column.id, sid)).await? {
+                        Some(b) => Some(b.convert_to(value_type)?),
+                        None => None,
+                    }
+                }
+                _ => None,
+            };
+            data_blocks.push(data_block);
+        }
+        let mut timestamps: Vec<Timestamp> = data_blocks
+            .iter()
+            .flatten()
+            .flat_map(|b| b.ts().iter().copied())
+            .collect();
+        timestamps.sort_unstable();
+        timestamps.dedup();
+
+        let mut cursors = vec![0_usize; data_blocks.len()];
+        for ts in timestamps {
+            for (i, column) in table_schema.columns().iter().enumerate() {
+                let builder = &mut builders[i];
+                match &column.column_type {
+                    ColumnType::Time(unit) => builder.append_timestamp(unit, ts),
+                    ColumnType::Tag => match series_key.tag_val(&column.name) {
+                        Some(val) => builder.append_string(String::from_utf8_lossy(&val).into()),
+                        None => builder.append_null_string(),
+                    },
+                    ColumnType::Field(value_type) => {
+                        let value = match &data_blocks[i] {
+                            Some(b) if b.ts().get(cursors[i]) == Some(&ts) => {
+                                cursors[i] += 1;
+                                b.get(cursors[i] - 1)
+                            }
+                            _ => None,
+                        };
+                        builder.append_value(value_type.to_physical_type(), value, &column.name)?;
+                    }
+                }
+            }
+            batch_rows += 1;
+            if batch_rows >= EXPORT_BATCH_SIZE {
+                let batch = finish_builders(&mut builders, schema.clone())?;
+                writer.write(&batch).context(error::ParquetSnafu)?;
+                total_rows += batch_rows;
+                batch_rows = 0;
+            }
+        }
+    }
+    if batch_rows > 0 {
+        let batch = finish_builders(&mut builders, schema)?;
+        writer.write(&batch).context(error::ParquetSnafu)?;
+        total_rows += batch_rows;
+    }
+    writer.close().context(error::ParquetSnafu)?;
+
+    Ok(total_rows)
+}
+
+/// Returns files of `version` sorted by file id, values in newer files
+/// overwrite values in older files.
+pub(crate) fn sorted_column_files(version: &Version) -> Vec<Arc<ColumnFile>> {
+    let mut files: Vec<Arc<ColumnFile>> = version
+        .levels_info()
+        .iter()
+        .flat_map(|l| l.files.iter().cloned())
+        .collect();
+    files.sort_by_key(|f| f.file_id());
+    files
+}
+
+/// Reads values of a field in `files` (sorted by file id), returns None if
+/// the field has no value.
+pub(crate) async fn read_field(
+    version: &Version,
+    files: &[Arc<ColumnFile>],
+    field_id: FieldId,
+) -> Result<Option<DataBlock>> {
+    let mut merged: Option<DataBlock> = None;
+    for file in files {
+        if !file.contains_field_id(field_id) {
+            continue;
+        }
+        let reader = version.get_tsm_reader(file).await?;
+        for idx in reader.index_iterator_opt(field_id) {
+            for blk in idx.block_iterator() {
+                let data_block = reader
+                    .get_data_block(&blk)
+                    .await
+                    .context(error::ReadTsmSnafu)?;
+                match merged.as_mut() {
+                    Some(b) if b.field_type() == data_block.field_type() => b.extend(data_block),
+                    Some(b) => b.extend(data_block.convert_to(b.field_type())?),
+                    None => merged = Some(data_block),
+                }
+            }
+        }
+    }
+    Ok(merged.map(sort_dedup_data_block))
+}
+
+fn finish_builders(
+    builders: &mut [ArrayBuilderPtr],
+    schema: datafusion::arrow::datatypes::SchemaRef,
+) -> Result<RecordBatch> {
+    let columns: Vec<ArrayRef> = builders.iter_mut().map(|b| b.ptr.finish()).collect();
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Converts rows of `table_schema` in `batch` into values of fields, series of
+/// the rows are added into `ts_index` if they don't exist.
+///
+/// Columns in `batch` are casted to types of the table schema, values of a
+/// field are sorted and deduplicated by timestamp.
+pub async fn record_batch_to_data_blocks(
+    db_name: &str,
+    table_schema: &TskvTableSchema,
+    batch: &RecordBatch,
+    ts_index: &TSIndex,
+    series_limit: &SeriesLimit,
+) -> Result<BTreeMap<FieldId, (DataBlock, Encoding)>> {
+    let mut time_array: Option<ArrayRef> = None;
+    let mut tag_columns: Vec<(&TableColumn, ArrayRef)> = Vec::new();
+    let mut field_columns: Vec<(&TableColumn, ArrayRef)> = Vec::new();
+    let batch_schema = batch.schema();
+    for (field, array) in batch_schema.fields().iter().zip(batch.columns()) {
+        let column = table_schema
+            .column(field.name())
+            .ok_or_else(|| Error::InvalidParam {
+                reason: format!(
+                    "column '{}' not found in table '{}'",
+                    field.name(),
+                    table_schema.name
+                ),
+            })?;
+        let data_type: ArrowDataType = column.column_type.clone().into();
+        let array = cast(array, &data_type)?;
+        match column.column_type {
+            ColumnType::Time(_) => time_array = Some(cast(&array, &ArrowDataType::Int64)?),
+            ColumnType::Tag => tag_columns.push((column, array)),
+            ColumnType::Field(_) => field_columns.push((column, array)),
+        }
+    }
+    let time_array = match time_array {
+        Some(a) if a.null_count() == 0 => a,
+        _ => {
+            return Err(Error::InvalidParam {
+                reason: format!(
+                    "time column of table '{}' is missing or has nulls",
+                    table_schema.name
+                ),
+            })
+        }
+    };
+    let time_array = time_array
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .expect("casted to Int64Array");
+
+    let series_ids = build_series_ids(
+        db_name,
+        &table_schema.name,
+        &tag_columns,
+        batch.num_rows(),
+        ts_index,
+        series_limit,
+    )
+    .await?;
+
+    let mut data_blocks: BTreeMap<FieldId, (DataBlock, Encoding)> = BTreeMap::new();
+    for (column, array) in field_columns {
+        let value_type = match column.column_type.to_physical_type() {
+            PhysicalCType::Field(t) => t,
+            _ => continue,
+        };
+        for (i, sid) in series_ids.iter().enumerate() {
+            if let Some(value) = array_value(array.as_ref(), i, time_array.value(i)) {
+                data_blocks
+                    .entry(unite_id(column.id, *sid))
+                    .or_insert_with(|| (DataBlock::new(0, value_type), column.encoding))
+                    .0
+                    .insert(value);
+            }
+        }
+    }
+    for (data_block, _) in data_blocks.values_mut() {
+        let block = std::mem::replace(data_block, DataBlock::new(0, data_block.field_type()));
+        *data_block = sort_dedup_data_block(block);
+    }
+
+    Ok(data_blocks)
+}
+
+/// Returns series ids of rows, tags of a row are the non-null values in tag columns.
+async fn build_series_ids(
+    db_name: &str,
+    table: &str,
+    tag_columns: &[(&TableColumn, ArrayRef)],
+    num_rows: usize,
+    ts_index: &TSIndex,
+    series_limit: &SeriesLimit,
+) -> Result<Vec<SeriesId>> {
+    let tag_arrays = tag_columns
+        .iter()
+        .map(|(c, a)| {
+            a.as_any()
+                .downcast_ref::<StringArray>()
+                .map(|a| (c.name.as_bytes(), a))
+                .expect("casted to StringArray")
+        })
+        .collect::<Vec<_>>();
+
+    let mut row_tags = Vec::with_capacity(num_rows);
+    let mut series_ids: HashMap<Vec<Tag>, Option<SeriesId>> = HashMap::new();
+    for i in 0..num_rows {
+        let mut tags = Vec::with_capacity(tag_arrays.len());
+        for (key, array) in tag_arrays.iter() {
+            if array.is_valid(i) {
+                tags.push(Tag::new(key.to_vec(), array.value(i).as_bytes().to_vec()));
+            }
+        }
+        tag::sort_tags(&mut tags);
+        series_ids.entry(tags.clone()).or_insert(None);
+        row_tags.push(tags);
+    }
+
+    let mut new_series = Vec::new();
+    for (tags, id) in series_ids.iter_mut() {
+        let series_key = SeriesKey {
+            id: 0,
+            tags: tags.clone(),
+            table: table.to_string(),
+            db: db_name.to_string(),
+        };
+        match ts_index.get_series_id(&series_key).await? {
+            Some(sid) => *id = Some(sid),
+            None => new_series.push(series_key),
+        }
+    }
+    let new_ids = ts_index
+        .add_series_if_not_exists(new_series.clone(), series_limit)
+        .await?;
+    for (series_key, sid) in new_series.into_iter().zip(new_ids) {
+        series_ids.insert(series_key.tags, Some(sid));
+    }
+
+    row_tags
+        .iter()
+        .map(|tags| {
+            series_ids
+                .get(tags)
+                .copied()
+                .flatten()
+                .ok_or_else(|| Error::CommonError {
+                    reason: "add series failed, new series id is missing".to_string(),
+                })
+        })
+        .collect()
+}
+
+/// Returns the value at `i` of an array casted to the type of a field column.
+fn array_value(array: &dyn Array, i: usize, ts: Timestamp) -> Option<DataType> {
+    if array.is_null(i) {
+        return None;
+    }
+    let array = array.as_any();
+    if let Some(a) = array.downcast_ref::<Float64Array>() {
+        Some(DataType::F64(ts, a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<Int64Array>() {
+        Some(DataType::I64(ts, a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<UInt64Array>() {
+        Some(DataType::U64(ts, a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<BooleanArray>() {
+        Some(DataType::Bool(ts, a.value(i)))
+    } else if let Some(a) = array.downcast_ref::<StringArray>() {
+        Some(DataType::Str(ts, MiniVec::from(a.value(i).as_bytes())))
+    } else if let Some(a) = array.downcast_ref::<BinaryArray>() {
+        Some(DataType::Str(ts, MiniVec::from(a.value(i))))
+    } else {
+        // Decimal fields are stored as unscaled integers.
+        array
+            .downcast_ref::<Decimal128Array>()
+            .and_then(|a| i64::try_from(a.value(i)).ok())
+            .map(|v| DataType::I64(ts, v))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Decimal128Array, Float64Array, StringArray};
+    use datafusion::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use models::schema::make_owner;
+    use tokio::runtime;
+
+    use super::{array_value, table_file_key};
+    use crate::memcache::DataType;
+    use crate::split::test::{init_tskv, read_values, write_series, DATABASE, TABLE, TENANT};
+    use crate::Engine;
+
+    #[test]
+    fn test_array_value() {
+        let array = Float64Array::from(vec![Some(1.5), None]);
+        assert!(matches!(array_value(&array, 0, 10), Some(DataType::F64(10, v)) if v == 1.5));
+        assert!(array_value(&array, 1, 10).is_none());
+
+        let array = Arc::new(StringArray::from(vec!["a"]));
+        assert!(matches!(
+            array_value(array.as_ref(), 0, 1),
+            Some(DataType::Str(1, v)) if &v[..] == b"a"
+        ));
+
+        let array = Decimal128Array::from(vec![12345_i128, i128::MAX])
+            .with_precision_and_scale(38, 2)
+            .unwrap();
+        assert!(matches!(
+            array_value(&array, 0, 1),
+            Some(DataType::I64(1, 12345))
+        ));
+        assert!(array_value(&array, 1, 1).is_none());
+    }
+
+    #[test]
+    fn test_export_import_vnode() {
+        let runtime = Arc::new(
+            runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+        let rt = runtime.clone();
+        rt.block_on(async move {
+            let dir = "/tmp/test/parquet_io/export_import_vnode";
+            let engine = init_tskv(dir, runtime).await;
+            let location = format!("{dir}/export");
+            let (vnode_id, new_vnode_id) = (1, 2);
+            let tag_values: Vec<String> = (0..4).map(|i| format!("a{i}")).collect();
+
+            write_series(&engine, vnode_id, &tag_values, &[1, 2], 0)
+                .await
+                .unwrap();
+            engine
+                .flush_tsfamily(TENANT, DATABASE, vnode_id)
+                .await
+                .unwrap();
+            // Values in the memcache are flushed by the export.
+            write_series(&engine, vnode_id, &tag_values, &[3], 0)
+                .await
+                .unwrap();
+            engine
+                .export_vnode(TENANT, DATABASE, vnode_id, Some(TABLE), &location)
+                .await
+                .unwrap();
+
+            let key = table_file_key(&make_owner(TENANT, DATABASE), TABLE, vnode_id);
+            let file = std::fs::File::open(format!("{location}/{key}")).unwrap();
+            let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+                .unwrap()
+                .build()
+                .unwrap();
+            for batch in reader {
+                engine
+                    .import_vnode(TENANT, DATABASE, new_vnode_id, TABLE, batch.unwrap())
+                    .await
+                    .unwrap();
+            }
+
+            let db = engine.get_db(TENANT, DATABASE).await.unwrap();
+            let ts_index = db.read().await.get_ts_index(new_vnode_id).unwrap();
+            let sids = ts_index.get_series_id_list(TABLE, &[]).await.unwrap();
+            assert_eq!(sids.len(), tag_values.len());
+            for sid in sids {
+                let key = ts_index.get_series_key(sid).await.unwrap().unwrap();
+                let i: i64 = std::str::from_utf8(&key.tags()[0].value).unwrap()[1..]
+                    .parse()
+                    .unwrap();
+                let values = read_values(&engine, new_vnode_id, sid).await;
+                assert_eq!(values, vec![10 + i, 20 + i, 30 + i], "series {key}");
+            }
+
+            engine.close().await;
+        });
+    }
+}
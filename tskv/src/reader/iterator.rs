@@ -1,26 +1,36 @@
 use std::cmp::{Ordering, Reverse};
-use std::collections::{BinaryHeap, HashSet};
+use std::collections::{btree_map, BTreeMap, BinaryHeap, HashMap, HashSet};
 use std::sync::Arc;
 
 use datafusion::arrow::array::{
-    ArrayBuilder, ArrayRef, BooleanArray, BooleanBuilder, Float64Builder, Int64Builder,
-    PrimitiveArray, PrimitiveBuilder, StringArray, StringBuilder, TimestampMicrosecondBuilder,
-    TimestampMillisecondBuilder, TimestampNanosecondBuilder, TimestampSecondBuilder, UInt64Builder,
+    ArrayBuilder, ArrayRef, BinaryBuilder, BooleanArray, BooleanBuilder, Decimal128Builder,
+    Float64Builder, Int64Builder, PrimitiveArray, PrimitiveBuilder, StringArray, StringBuilder,
+    TimestampMicrosecondBuilder, TimestampMillisecondBuilder, TimestampNanosecondBuilder,
+    TimestampSecondBuilder, UInt64Builder,
 };
 use datafusion::arrow::datatypes::{
-    ArrowPrimitiveType, Float64Type, Int64Type, SchemaRef, TimeUnit, TimestampMicrosecondType,
-    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt64Type,
+    ArrowPrimitiveType, Decimal128Type, Float64Type, Int64Type, SchemaRef, TimeUnit,
+    TimestampMicrosecondType, TimestampMillisecondType, TimestampNanosecondType,
+    TimestampSecondType, UInt64Type,
 };
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::physical_plan::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder};
 use futures::future::join_all;
 use minivec::MiniVec;
+use models::decimal::Decimal;
 use models::meta_data::VnodeId;
-use models::predicate::domain::{self, QueryArgs, QueryExpr, TimeRanges};
+use models::predicate::domain::{
+    self, AggregateColumn, AggregateKind, Domain, QueryArgs, QueryExpr, TimeBucket, TimeRanges,
+};
 use models::predicate::PlacedSplit;
-use models::schema::{PhysicalCType as ColumnType, TableColumn, TskvTableSchemaRef};
+use models::schema::{
+    ColumnType as LogicalColumnType, PhysicalCType as ColumnType, TskvTableSchemaRef,
+};
 use models::utils::{min_num, unite_id};
-use models::{FieldId, PhysicalDType as ValueType, SeriesId, Timestamp};
+use models::{
+    ColumnId, FieldId, PhysicalDType as ValueType, SeriesId, Timestamp,
+    ValueType as LogicalValueType,
+};
 use protos::kv_service::QueryRecordBatchRequest;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
@@ -28,12 +38,15 @@ use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 use trace::{debug, error, SpanRecorder};
 
+use crate::compute::aggregate::{
+    aggregate_field_values, aggregate_field_values_by_time_bucket, last_field_value,
+};
 use crate::compute::count::count_column_non_null_values;
 use crate::error::Result;
 use crate::memcache::DataType;
 use crate::reader::Cursor;
 use crate::tseries_family::{ColumnFile, SuperVersion, Version};
-use crate::tsm::{BlockMetaIterator, DataBlockReader, TsmReader};
+use crate::tsm::{BlockMeta, BlockMetaIterator, DataBlockReader, TsmReader};
 use crate::{EngineRef, Error};
 
 pub type CursorPtr = Box<dyn Cursor>;
@@ -86,7 +99,14 @@ impl ArrayBuilderPtr {
                 });
             }
             ValueType::String => {
-                if let Some(DataType::Str(_, val)) = value {
+                // Binary fields are stored as strings.
+                if self.ptr.as_any().is::<BinaryBuilder>() {
+                    if let Some(DataType::Str(_, val)) = value {
+                        self.append_binary(&val);
+                    } else {
+                        self.append_null_binary();
+                    }
+                } else if let Some(DataType::Str(_, val)) = value {
                     let data =
                         String::from_utf8(val.to_vec()).map_err(|_| Error::ErrCharacterSet)?;
                     self.append_string(data);
@@ -109,7 +129,14 @@ impl ArrayBuilderPtr {
                 }
             }
             ValueType::Integer => {
-                if let Some(DataType::I64(_, val)) = value {
+                // Decimal fields are stored as unscaled integers.
+                if self.ptr.as_any().is::<Decimal128Builder>() {
+                    if let Some(DataType::I64(_, val)) = value {
+                        self.append_primitive::<Decimal128Type>(val as i128);
+                    } else {
+                        self.append_primitive_null::<Decimal128Type>();
+                    }
+                } else if let Some(DataType::I64(_, val)) = value {
                     self.append_primitive::<Int64Type>(val);
                 } else {
                     self.append_primitive_null::<Int64Type>();
@@ -181,6 +208,28 @@ impl ArrayBuilderPtr {
         }
     }
 
+    pub fn append_binary(&mut self, data: &[u8]) {
+        if let Some(b) = self.ptr.as_any_mut().downcast_mut::<BinaryBuilder>() {
+            b.append_value(data);
+        } else {
+            error!(
+                "Failed to get binary array builder to insert {:?} array",
+                self.column_type
+            );
+        }
+    }
+
+    pub fn append_null_binary(&mut self) {
+        if let Some(b) = self.ptr.as_any_mut().downcast_mut::<BinaryBuilder>() {
+            b.append_null();
+        } else {
+            error!(
+                "Failed to get binary array builder to insert {:?} array",
+                self.column_type
+            );
+        }
+    }
+
     fn extend_primitive_array<T: ArrowPrimitiveType>(&mut self, array: ArrayRef) {
         let builder = self.builder::<T>();
         let array = array.as_any().downcast_ref::<PrimitiveArray<T>>();
@@ -342,7 +391,12 @@ pub struct QueryOption {
     pub split: PlacedSplit,
     pub df_schema: SchemaRef,
     pub table_schema: TskvTableSchemaRef,
-    pub aggregates: Option<Vec<TableColumn>>, // TODO: Use PushedAggregateFunction
+    pub aggregates: Option<Vec<AggregateColumn>>,
+    /// Group aggregates by time buckets, the start timestamp of each bucket is
+    /// returned in the first column.
+    pub time_bucket: Option<TimeBucket>,
+    /// Only read the latest row of each series, by the latest values of fields.
+    pub last_value: bool,
 }
 
 impl QueryOption {
@@ -350,7 +404,9 @@ impl QueryOption {
     pub fn new(
         batch_size: usize,
         split: PlacedSplit,
-        aggregates: Option<Vec<TableColumn>>, // TODO: Use PushedAggregateFunction
+        aggregates: Option<Vec<AggregateColumn>>,
+        time_bucket: Option<TimeBucket>,
+        last_value: bool,
         df_schema: SchemaRef,
         table_schema: TskvTableSchemaRef,
     ) -> Self {
@@ -358,6 +414,8 @@ impl QueryOption {
             batch_size,
             split,
             aggregates,
+            time_bucket,
+            last_value,
             df_schema,
             table_schema,
         }
@@ -375,6 +433,8 @@ impl QueryOption {
             vnode_ids,
             limit: self.split.limit(),
             batch_size: self.batch_size,
+            last_value: self.last_value,
+            time_bucket: self.time_bucket,
         };
         let expr = QueryExpr {
             split: self.split.clone(),
@@ -398,6 +458,11 @@ pub struct FieldFileLocation {
     reader: Arc<TsmReader>,
     block_meta_iter: BlockMetaIterator,
     time_ranges: Arc<TimeRanges>,
+    /// Pushed down domain of field values, used to skip data blocks by statistics.
+    value_domain: Option<Arc<Domain>>,
+    /// Type of the field column in the current schema, data blocks written
+    /// before the type is changed are converted to it.
+    value_type: ValueType,
 
     data_block_reader: DataBlockReader,
 }
@@ -455,10 +520,11 @@ async fn open_field_file_location(
     column_file: Arc<ColumnFile>,
     version: Arc<Version>,
     time_ranges: Arc<TimeRanges>,
+    value_domain: Option<Arc<Domain>>,
     field_id: FieldId,
     value_type: ValueType,
 ) -> Result<Vec<FieldFileLocation>> {
-    let tsm_reader = version.get_tsm_reader(column_file.file_path()).await?;
+    let tsm_reader = version.get_tsm_reader(&column_file).await?;
     let res = tsm_reader
         .index_iterator_opt(field_id)
         .map(move |index_meta| {
@@ -468,15 +534,19 @@ async fn open_field_file_location(
                 index_meta.block_iterator_opt(time_ranges.clone()),
                 value_type,
             )
+            .with_value_domain(value_domain.clone())
         })
         .collect();
     Ok(res)
 }
 
 impl Level14TSDataStream {
+    /// Data blocks of which values are not in `value_domain` will be skipped,
+    /// it's only safe when there are no newer data to overwrite these files.
     pub async fn new(
         version: Arc<Version>,
         time_ranges: Arc<TimeRanges>,
+        value_domain: Option<Arc<Domain>>,
         column_files: Vec<Arc<ColumnFile>>,
         field_id: FieldId,
         value_type: ValueType,
@@ -486,6 +556,7 @@ impl Level14TSDataStream {
                 f,
                 version.clone(),
                 time_ranges.clone(),
+                value_domain.clone(),
                 field_id,
                 value_type,
             )
@@ -537,6 +608,7 @@ impl Level0TSDataStream {
                 f,
                 version.clone(),
                 time_ranges.clone(),
+                None,
                 field_id,
                 value_type,
             )
@@ -628,10 +700,37 @@ impl FieldFileLocation {
             reader,
             block_meta_iter,
             time_ranges,
+            value_domain: None,
+            value_type: vtype,
             data_block_reader: DataBlockReader::new_uninit(vtype),
         }
     }
 
+    pub fn with_value_domain(mut self, value_domain: Option<Arc<Domain>>) -> Self {
+        self.value_domain = value_domain;
+        self
+    }
+
+    /// Check if values of the BlockMeta may be included by `value_domain`.
+    fn may_contain_values(&self, meta: &BlockMeta) -> bool {
+        let domain = match self.value_domain.as_ref() {
+            Some(d) => d,
+            None => return true,
+        };
+        // Statistics of blocks written in another type can't be compared with the domain.
+        if meta.field_type() != self.value_type {
+            return true;
+        }
+        let stats = match meta.stats() {
+            Some(s) => s,
+            None => return true,
+        };
+        match (stats.min(meta.field_type()), stats.max(meta.field_type())) {
+            (Some(min), Some(max)) => domain.may_contain_range(&min, &max),
+            _ => true,
+        }
+    }
+
     // if return None
     pub async fn next_data(&mut self) -> Result<Option<DataType>> {
         let res = self.data_block_reader.next();
@@ -656,11 +755,18 @@ impl FieldFileLocation {
             if meta.count() == 0 {
                 continue;
             }
+            // Skip the DataBlock if its values can't match the pushed down field filter.
+            if !self.may_contain_values(&meta) {
+                continue;
+            }
             let time_range = meta.time_range();
             // Check if the time range of the BlockMeta intersected with the given time ranges.
             if let Some(intersected_tr) = self.time_ranges.intersect(&time_range) {
                 // Load a DataBlock from reader by BlockMeta.
-                let block = self.reader.get_data_block(&meta).await?;
+                let mut block = self.reader.get_data_block(&meta).await?;
+                if meta.field_type() != self.value_type {
+                    block = block.convert_to(self.value_type)?;
+                }
                 let mut data_block_reader = DataBlockReader::new(block, intersected_tr);
                 if data_block_reader.has_next() {
                     return Ok(Some(data_block_reader));
@@ -939,6 +1045,28 @@ impl RowIterator {
                     err
                 })?
         };
+        // Series moved into another vnode by split are kept in the vnode
+        // until they are removed, only read series in the hash range.
+        let series_ids = match query_option.split.hash_range() {
+            Some(range) => {
+                let mut ids = Vec::with_capacity(series_ids.len());
+                for sid in series_ids {
+                    let key = engine
+                        .get_series_key(
+                            &query_option.table_schema.tenant,
+                            &query_option.table_schema.db,
+                            vnode_id,
+                            sid,
+                        )
+                        .await?;
+                    if key.map_or(false, |k| range.contains(k.hash())) {
+                        ids.push(sid);
+                    }
+                }
+                ids
+            }
+            None => series_ids,
+        };
 
         debug!(
             "Iterating rows: vnode_id: {vnode_id}, serie_ids_count: {}",
@@ -1035,6 +1163,7 @@ impl RowIterator {
                 .child(format!("SeriesGroupRowIterator [{}, {})", start, end)),
             metrics: SeriesGroupRowIteratorMetrics::new(&self.metrics_set, start),
             row_cols,
+            time_bucket_rows: None,
         };
         let can_tok = self.series_iter_closer.clone();
         self.runtime.spawn(async move {
@@ -1061,12 +1190,28 @@ impl RowIterator {
     fn build_record_builders(query_option: &QueryOption) -> Result<Vec<ArrayBuilderPtr>> {
         // Get builders for aggregating.
         if let Some(aggregates) = query_option.aggregates.as_ref() {
-            let mut builders: Vec<ArrayBuilderPtr> = Vec::with_capacity(aggregates.len());
-            for _ in 0..aggregates.len() {
-                builders.push(ArrayBuilderPtr::new(
-                    Box::new(Int64Builder::with_capacity(query_option.batch_size)),
-                    ColumnType::Field(ValueType::Integer),
-                ));
+            let mut builders: Vec<ArrayBuilderPtr> = Vec::with_capacity(aggregates.len() + 1);
+            if query_option.time_bucket.is_some() {
+                // Start timestamps of time buckets.
+                let time_column = query_option.table_schema.time_column();
+                let builder_item =
+                    Self::new_column_builder(&time_column.column_type, query_option.batch_size)?;
+                let kv_dt = time_column.column_type.to_physical_type();
+                builders.push(ArrayBuilderPtr::new(builder_item, kv_dt));
+            }
+            for agg in aggregates.iter() {
+                if agg.kind == AggregateKind::Count {
+                    builders.push(ArrayBuilderPtr::new(
+                        Box::new(Int64Builder::with_capacity(query_option.batch_size)),
+                        ColumnType::Field(ValueType::Integer),
+                    ));
+                } else {
+                    // Other aggregates returns values of the same type as the column.
+                    let builder_item =
+                        Self::new_column_builder(&agg.column.column_type, query_option.batch_size)?;
+                    let kv_dt = agg.column.column_type.to_physical_type();
+                    builders.push(ArrayBuilderPtr::new(builder_item, kv_dt));
+                }
             }
             return Ok(builders);
         }
@@ -1079,20 +1224,25 @@ impl RowIterator {
                 "Building record builder: schema info {:02X} {}",
                 item.id, item.name
             );
+            let builder_item =
+                Self::new_column_builder(&item.column_type, query_option.batch_size)?;
             let kv_dt = item.column_type.to_physical_type();
-            let builder_item = Self::new_column_builder(&kv_dt, query_option.batch_size)?;
             builders.push(ArrayBuilderPtr::new(builder_item, kv_dt))
         }
         Ok(builders)
     }
 
-    fn new_column_builder(
-        column_type: &ColumnType,
+    /// Create an array builder by the column type in table schema, decimal and binary
+    /// fields need builders different from their physical types.
+    pub(crate) fn new_column_builder(
+        column_type: &LogicalColumnType,
         batch_size: usize,
     ) -> Result<Box<dyn ArrayBuilder>> {
         Ok(match column_type {
-            ColumnType::Tag => Box::new(StringBuilder::with_capacity(batch_size, batch_size * 32)),
-            ColumnType::Time(unit) => match unit {
+            LogicalColumnType::Tag => {
+                Box::new(StringBuilder::with_capacity(batch_size, batch_size * 32))
+            }
+            LogicalColumnType::Time(unit) => match unit {
                 TimeUnit::Second => Box::new(TimestampSecondBuilder::with_capacity(batch_size)),
                 TimeUnit::Millisecond => {
                     Box::new(TimestampMillisecondBuilder::with_capacity(batch_size))
@@ -1104,7 +1254,18 @@ impl RowIterator {
                     Box::new(TimestampNanosecondBuilder::with_capacity(batch_size))
                 }
             },
-            ColumnType::Field(t) => match t {
+            LogicalColumnType::Field(LogicalValueType::Decimal(Decimal { precision, scale })) => {
+                let builder = Decimal128Builder::with_capacity(batch_size)
+                    .with_precision_and_scale(*precision, *scale as i8)
+                    .map_err(|e| Error::CommonError {
+                        reason: format!("failed to create column builder: {e}"),
+                    })?;
+                Box::new(builder)
+            }
+            LogicalColumnType::Field(LogicalValueType::Binary) => {
+                Box::new(BinaryBuilder::with_capacity(batch_size, batch_size * 32))
+            }
+            LogicalColumnType::Field(t) => match t.to_physical_type() {
                 ValueType::Float => Box::new(Float64Builder::with_capacity(batch_size)),
                 ValueType::Integer => Box::new(Int64Builder::with_capacity(batch_size)),
                 ValueType::Unsigned => Box::new(UInt64Builder::with_capacity(batch_size)),
@@ -1225,6 +1386,8 @@ struct SeriesGroupRowIterator {
     metrics: SeriesGroupRowIteratorMetrics,
     // row_cols_cache
     row_cols: Vec<Option<DataType>>,
+    /// Remaining rows of aggregates grouped by time buckets.
+    time_bucket_rows: Option<btree_map::IntoIter<Timestamp, Vec<Option<DataType>>>>,
 }
 
 impl SeriesGroupRowIterator {
@@ -1324,6 +1487,12 @@ impl SeriesGroupRowIterator {
             )
             .await?
         {
+            let mut last_values = if self.query_option.last_value {
+                Some(self.read_series_last_values(series_id).await?)
+            } else {
+                None
+            };
+
             self.columns.clear();
             for item in self.query_option.table_schema.columns() {
                 debug!(
@@ -1347,13 +1516,23 @@ impl SeriesGroupRowIterator {
                             todo!("return an empty-cursor for unknown type field");
                         }
                         _ => {
-                            let cursor = self
-                                .build_field_cursor(
-                                    unite_id(item.id, series_id),
+                            let cursor = match last_values.as_mut() {
+                                Some(values) => FieldCursor::new(
                                     Arc::new(item.name.clone()),
                                     vtype,
-                                )
-                                .await?;
+                                    Box::new(values.remove(&item.id).into_iter()),
+                                    None,
+                                    None,
+                                ),
+                                None => {
+                                    self.build_field_cursor(
+                                        unite_id(item.id, series_id),
+                                        Arc::new(item.name.clone()),
+                                        vtype,
+                                    )
+                                    .await?
+                                }
+                            };
                             Box::new(cursor)
                         }
                     },
@@ -1371,10 +1550,46 @@ impl SeriesGroupRowIterator {
         Ok(())
     }
 
+    /// Read the latest value of each field of the series, fields may be at different
+    /// timestamps, as a field may not be written in the latest rows.
+    async fn read_series_last_values(
+        &self,
+        series_id: SeriesId,
+    ) -> Result<HashMap<ColumnId, DataType>> {
+        let super_version = match self.super_version {
+            Some(ref v) => v.clone(),
+            None => return Ok(HashMap::new()),
+        };
+
+        let time_ranges = self.query_option.split.time_ranges();
+        let mut values = HashMap::new();
+        for item in self.query_option.table_schema.fields() {
+            let vtype = match item.column_type.to_physical_type() {
+                ColumnType::Field(vtype) if vtype != ValueType::Unknown => vtype,
+                _ => continue,
+            };
+            if let Some(value) = last_field_value(
+                super_version.clone(),
+                &self.query_option.table_schema,
+                series_id,
+                item.id,
+                vtype,
+                time_ranges.clone(),
+            )
+            .await?
+            {
+                values.insert(item.id, value);
+            }
+        }
+
+        Ok(values)
+    }
+
     async fn build_level_ts_stream(
         &self,
         version: Arc<Version>,
         time_ranges: Arc<TimeRanges>,
+        value_domain: Option<Arc<Domain>>,
         field_id: FieldId,
         value_type: ValueType,
     ) -> Result<(Option<Level0TSDataStream>, Option<Level14TSDataStream>)> {
@@ -1417,6 +1632,9 @@ impl SeriesGroupRowIterator {
             debug_assert!(time_range_cp.eq(&time_range));
         }
 
+        // Data in level 1-4 may be overwritten by data in level 0 in the same timestamp,
+        // so blocks can be skipped by value statistics only if there is no level 0 data.
+        let value_domain = if l0.is_none() { value_domain } else { None };
         let l14 = if fs.is_empty() {
             None
         } else {
@@ -1424,6 +1642,7 @@ impl SeriesGroupRowIterator {
                 Level14TSDataStream::new(
                     version.clone(),
                     time_ranges.clone(),
+                    value_domain,
                     fs,
                     field_id,
                     value_type,
@@ -1467,12 +1686,25 @@ impl SeriesGroupRowIterator {
             field_id,
             cache_data.len()
         );
+        // Data in files may be overwritten by cached data in the same timestamp,
+        // so blocks can be skipped by value statistics only if there is no cached data.
+        let value_domain = if cache_data.is_empty() {
+            self.query_option
+                .split
+                .fields_filter()
+                .domains()
+                .and_then(|domains| domains.get(field_name.as_str()))
+                .map(|domain| Arc::new(domain.clone()))
+        } else {
+            None
+        };
         let cache_data_iter = cache_data.into_iter().rev();
 
         let (l0_stream, l14_stream) = self
             .build_level_ts_stream(
                 super_version.version.clone(),
                 time_ranges_ref.clone(),
+                value_domain,
                 field_id,
                 field_type,
             )
@@ -1580,6 +1812,11 @@ impl SeriesGroupRowIterator {
         &mut self,
         builder: &mut [ArrayBuilderPtr],
     ) -> Result<Option<()>> {
+        if let Some(time_bucket) = self.query_option.time_bucket {
+            return self
+                .collect_time_bucket_aggregate_row_data(builder, time_bucket)
+                .await;
+        }
         if self.is_finished {
             return Ok(None);
         }
@@ -1590,8 +1827,34 @@ impl SeriesGroupRowIterator {
             self.query_option.aggregates.as_ref(),
         ) {
             (Some(version), Some(aggregates)) => {
-                for (i, item) in aggregates.iter().enumerate() {
+                for (i, agg) in aggregates.iter().enumerate() {
+                    let item = &agg.column;
                     let kv_dt = item.column_type.to_physical_type();
+                    if agg.kind != AggregateKind::Count {
+                        let vtype = match kv_dt {
+                            ColumnType::Field(vtype) if vtype != ValueType::Unknown => vtype,
+                            _ => {
+                                return Err(Error::CommonError {
+                                    reason: format!(
+                                        "unsupported aggregate {:?} of column {}",
+                                        agg.kind, item.name
+                                    ),
+                                });
+                            }
+                        };
+                        let agg_ret = aggregate_field_values(
+                            self.runtime.clone(),
+                            version.clone(),
+                            self.series_ids.clone(),
+                            item.id,
+                            vtype,
+                            agg.kind,
+                            self.query_option.split.time_ranges(),
+                        )
+                        .await?;
+                        builder[i].append_value(vtype, agg_ret, &item.name)?;
+                        continue;
+                    }
                     match kv_dt {
                         ColumnType::Tag => todo!("collect count for tag"),
                         ColumnType::Time(_) => {
@@ -1631,6 +1894,92 @@ impl SeriesGroupRowIterator {
             _ => Ok(None),
         }
     }
+
+    /// Collect a row of aggregates of a time bucket, aggregates of all time buckets
+    /// are computed on the first call.
+    async fn collect_time_bucket_aggregate_row_data(
+        &mut self,
+        builder: &mut [ArrayBuilderPtr],
+        time_bucket: TimeBucket,
+    ) -> Result<Option<()>> {
+        if self.time_bucket_rows.is_none() {
+            let rows = self.aggregate_time_buckets(time_bucket).await?;
+            self.time_bucket_rows = Some(rows.into_iter());
+        }
+        let (bucket, states) = match self.time_bucket_rows.as_mut().and_then(|rows| rows.next()) {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let aggregates = match self.query_option.aggregates.as_ref() {
+            Some(aggregates) => aggregates,
+            None => return Ok(None),
+        };
+
+        // The first builder is built by the type of the time column.
+        let time_unit = match &builder[0].column_type {
+            ColumnType::Time(unit) => unit.clone(),
+            _ => TimeUnit::Nanosecond,
+        };
+        builder[0].append_timestamp(&time_unit, bucket);
+        for (i, (agg, state)) in aggregates.iter().zip(states).enumerate() {
+            let value_type = match (agg.kind, agg.column.column_type.to_physical_type()) {
+                (AggregateKind::Count, _) => ValueType::Integer,
+                (_, ColumnType::Field(vtype)) => vtype,
+                _ => ValueType::Unknown,
+            };
+            builder[i + 1].append_value(value_type, state, &agg.column.name)?;
+        }
+
+        Ok(Some(()))
+    }
+
+    /// Compute aggregates grouped by time buckets, returns states of aggregates
+    /// by the start timestamp of time buckets.
+    async fn aggregate_time_buckets(
+        &self,
+        time_bucket: TimeBucket,
+    ) -> Result<BTreeMap<Timestamp, Vec<Option<DataType>>>> {
+        let mut rows: BTreeMap<Timestamp, Vec<Option<DataType>>> = BTreeMap::new();
+        let (version, aggregates) = match (
+            self.super_version.as_ref(),
+            self.query_option.aggregates.as_ref(),
+        ) {
+            (Some(version), Some(aggregates)) => (version, aggregates),
+            _ => return Ok(rows),
+        };
+
+        for (i, agg) in aggregates.iter().enumerate() {
+            let item = &agg.column;
+            let vtype = match item.column_type.to_physical_type() {
+                ColumnType::Field(vtype) if vtype != ValueType::Unknown => vtype,
+                _ => {
+                    return Err(Error::CommonError {
+                        reason: format!(
+                            "unsupported aggregate {:?} of column {} by time buckets",
+                            agg.kind, item.name
+                        ),
+                    });
+                }
+            };
+            let states = aggregate_field_values_by_time_bucket(
+                self.runtime.clone(),
+                version.clone(),
+                self.series_ids.clone(),
+                item.id,
+                vtype,
+                agg.kind,
+                self.query_option.split.time_ranges(),
+                time_bucket,
+            )
+            .await?;
+            for (bucket, state) in states {
+                rows.entry(bucket)
+                    .or_insert_with(|| vec![None; aggregates.len()])[i] = Some(state);
+            }
+        }
+
+        Ok(rows)
+    }
 }
 
 #[cfg(test)]
@@ -37,6 +37,7 @@ impl LocalTskvTagScanStream {
                 option.table_schema.name.as_str(),
             );
 
+            let hash_range = option.split.hash_range();
             let mut keys = Vec::new();
 
             for series_id in kv
@@ -45,7 +46,10 @@ impl LocalTskvTagScanStream {
                 .into_iter()
             {
                 if let Some(key) = kv.get_series_key(tenant, db, vnode_id, series_id).await? {
-                    keys.push(key)
+                    // Skip series moved into another vnode by split.
+                    if hash_range.map_or(true, |r| r.contains(key.hash())) {
+                        keys.push(key)
+                    }
                 }
             }
 
@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 use std::path::{Path, PathBuf};
 
+use snafu::ResultExt;
+
 use super::{
     file_crc_source_len, Record, FILE_FOOTER_CRC32_NUMBER_LEN, FILE_FOOTER_LEN,
     FILE_FOOTER_MAGIC_NUMBER_LEN, FILE_MAGIC_NUMBER_LEN, READER_BUF_SIZE, RECORD_CRC32_NUMBER_LEN,
@@ -8,7 +10,7 @@ use super::{
     RECORD_MAGIC_NUMBER, RECORD_MAGIC_NUMBER_LEN,
 };
 use crate::byte_utils::decode_be_u32;
-use crate::error::{Error, Result};
+use crate::error::{self, Error, Result};
 use crate::file_system::file::async_file::AsyncFile;
 use crate::file_system::file::IFile;
 use crate::file_system::file_manager;
@@ -22,6 +24,8 @@ pub struct Reader {
     buf_use: usize,
     footer: Option<[u8; FILE_FOOTER_LEN]>,
     footer_pos: u64,
+    /// End position of the last valid record.
+    valid_pos: u64,
 }
 
 impl Reader {
@@ -55,6 +59,7 @@ impl Reader {
             buf_use: 0,
             footer,
             footer_pos,
+            valid_pos: FILE_MAGIC_NUMBER_LEN as u64,
         })
     }
 
@@ -100,10 +105,18 @@ impl Reader {
     async fn find_record_header(&mut self) -> Result<(usize, &[u8])> {
         loop {
             let magic_number_sli = self.read_buf(RECORD_MAGIC_NUMBER_LEN).await?;
+            if magic_number_sli.len() < RECORD_MAGIC_NUMBER_LEN {
+                // Torn bytes at the end of file.
+                return Err(Error::Eof);
+            }
             let magic_number = decode_be_u32(magic_number_sli);
             if magic_number == RECORD_MAGIC_NUMBER {
                 let pos = self.pos;
                 let header = self.read_buf(RECORD_HEADER_LEN).await?;
+                if header.len() < RECORD_HEADER_LEN {
+                    // Torn record header at the end of file.
+                    return Err(Error::Eof);
+                }
                 return Ok((pos, header));
             } else {
                 self.set_pos(self.pos + 1).await?;
@@ -191,6 +204,7 @@ impl Reader {
             });
         }
 
+        self.valid_pos = (data_pos + data_size as usize) as u64;
         Ok(Record {
             data_type,
             data_version,
@@ -199,6 +213,28 @@ impl Reader {
         })
     }
 
+    /// Truncates bytes after the last valid record, returns count of bytes
+    /// truncated. Should be called after all records were read. A file with
+    /// footer was closed normally, so it won't be truncated.
+    pub async fn truncate_invalid_tail(self) -> Result<u64> {
+        if self.footer.is_some() || self.valid_pos >= self.file.len() {
+            return Ok(0);
+        }
+        let truncated = self.file.len() - self.valid_pos;
+        trace::warn!(
+            "Record file: Truncating '{}' from {} to {}, {truncated} bytes lost",
+            self.path.display(),
+            self.file.len(),
+            self.valid_pos
+        );
+        let file = file_manager::open_create_file(&self.path).await?;
+        file.truncate(self.valid_pos)
+            .await
+            .context(error::IOSnafu)?;
+        file.sync_data().await.context(error::IOSnafu)?;
+        Ok(truncated)
+    }
+
     /// Returns footer position and footer data.
     pub async fn read_footer(path: impl AsRef<Path>) -> Result<(u64, [u8; FILE_FOOTER_LEN])> {
         let path = path.as_ref();
@@ -288,6 +324,11 @@ impl Reader {
     pub fn pos(&self) -> usize {
         self.pos
     }
+
+    /// Returns end position of the last valid record.
+    pub fn valid_pos(&self) -> u64 {
+        self.valid_pos
+    }
 }
 
 #[cfg(test)]
@@ -557,4 +598,43 @@ pub(crate) mod test {
         find_record_header_and_check(&mut reader, Some(1786), Some(1786)).await;
         find_record_header_and_check(&mut reader, Some(1787), None).await;
     }
+
+    #[tokio::test]
+    async fn test_record_file_truncate_invalid_tail() {
+        let dir = PathBuf::from("/tmp/test/record_file/reader/3");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("1.log");
+        let records = vec![b"hello".to_vec(), b"world".to_vec()];
+        {
+            let mut writer = Writer::open(&path, RecordDataType::Summary).await.unwrap();
+            for r in records.iter() {
+                writer.write_record(1, 1, &[r]).await.unwrap();
+            }
+            writer.sync().await.unwrap();
+        }
+        let valid_len = std::fs::metadata(&path).unwrap().len();
+        {
+            // Append a torn record: magic number and part of the header.
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .unwrap();
+            std::io::Write::write_all(&mut file, &RECORD_MAGIC_NUMBER.to_be_bytes()).unwrap();
+            std::io::Write::write_all(&mut file, &[1, 1, 0, 0]).unwrap();
+        }
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), valid_len + 8);
+
+        let mut reader = Reader::open(&path).await.unwrap();
+        for r in records.iter() {
+            let record = reader.read_record().await.unwrap();
+            assert_eq!(&record.data, r);
+        }
+        assert!(matches!(reader.read_record().await, Err(Error::Eof)));
+        assert_eq!(reader.valid_pos(), valid_len);
+        assert_eq!(reader.truncate_invalid_tail().await.unwrap(), 8);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), valid_len);
+        assert_record_file_data_eq(&path, [[&records[0]], [&records[1]]], false).await;
+    }
 }
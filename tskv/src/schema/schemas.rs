@@ -59,11 +59,12 @@ impl DBschemas {
 
         for (field_name, field_type) in field_names.iter().zip(field_type) {
             if let Some(v) = schema.column(field_name) {
-                if field_type.0 != v.column_type.field_type() as i32 {
+                // Decimal and binary fields are written as integers and strings.
+                let point_dt = ColumnType::from_i32(field_type.0).to_physical_type();
+                if point_dt != v.column_type.to_physical_type() {
                     error!(
-                        "type mismatch, point: {}, schema: {}",
-                        field_type.0,
-                        v.column_type.field_type()
+                        "type mismatch, point: {:?}, schema: {}",
+                        field_type, &v.column_type
                     );
                     return Err(SchemaError::FieldType {
                         field: field_name.to_string(),
@@ -0,0 +1,477 @@
+//! Moving series of a vnode into a new vnode when the vnode is split.
+//!
+//! Series are divided by the hash of their series keys, which is the same as
+//! the hash used to locate the replication set of written points.
+
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::Arc;
+
+use models::schema::TskvTableSchema;
+use models::utils::unite_id;
+use models::{FieldId, SeriesId, SeriesKey};
+use snafu::ResultExt;
+use utils::BloomFilter;
+
+use crate::context::GlobalContext;
+use crate::error::{self, Result};
+use crate::index::ts_index::{SeriesLimit, TSIndex};
+use crate::summary::CompactMeta;
+use crate::tseries_family::{ColumnFile, Version};
+use crate::tsm::{self, TsmWriter};
+use crate::{LevelId, TseriesFamilyId};
+
+/// A series moved from the vnode into the new vnode.
+pub struct MovedSeries {
+    /// Series id in the vnode.
+    pub src_id: SeriesId,
+    /// Series id in the new vnode.
+    pub dst_id: SeriesId,
+    table_schema: Arc<TskvTableSchema>,
+}
+
+impl MovedSeries {
+    /// Returns ids of fields of the series in the vnode and the new vnode.
+    fn field_ids(&self) -> impl Iterator<Item = (FieldId, FieldId)> + '_ {
+        self.table_schema
+            .fields()
+            .into_iter()
+            .map(move |c| (unite_id(c.id, self.src_id), unite_id(c.id, self.dst_id)))
+    }
+}
+
+/// Returns series in `ts_index` whose hash is in `hash_range`, grouped by table.
+pub async fn series_in_range(
+    table_schemas: &[Arc<TskvTableSchema>],
+    ts_index: &TSIndex,
+    hash_range: &RangeInclusive<u64>,
+) -> Result<Vec<(Arc<TskvTableSchema>, Vec<(SeriesId, SeriesKey)>)>> {
+    let mut series = Vec::new();
+    for table_schema in table_schemas {
+        let mut table_series = Vec::new();
+        for sid in ts_index.get_series_id_list(&table_schema.name, &[]).await? {
+            if let Some(key) = ts_index.get_series_key(sid).await? {
+                if hash_range.contains(&key.hash()) {
+                    table_series.push((sid, key));
+                }
+            }
+        }
+        if !table_series.is_empty() {
+            series.push((table_schema.clone(), table_series));
+        }
+    }
+
+    Ok(series)
+}
+
+/// Returns series in `ts_index` whose hash is in `hash_range`, the series are
+/// added into `new_ts_index` if they don't exist.
+pub async fn moved_series(
+    table_schemas: &[Arc<TskvTableSchema>],
+    ts_index: &TSIndex,
+    new_ts_index: &TSIndex,
+    hash_range: &RangeInclusive<u64>,
+    series_limit: &SeriesLimit,
+) -> Result<Vec<MovedSeries>> {
+    let mut series = Vec::new();
+    for (table_schema, table_series) in series_in_range(table_schemas, ts_index, hash_range).await?
+    {
+        let (src_ids, series_keys): (Vec<SeriesId>, Vec<SeriesKey>) =
+            table_series.into_iter().unzip();
+        let dst_ids = new_ts_index
+            .add_series_if_not_exists(series_keys, series_limit)
+            .await?;
+        series.extend(
+            src_ids
+                .into_iter()
+                .zip(dst_ids)
+                .map(|(src_id, dst_id)| MovedSeries {
+                    src_id,
+                    dst_id,
+                    table_schema: table_schema.clone(),
+                }),
+        );
+    }
+
+    Ok(series)
+}
+
+/// Where values of moved series are copied into.
+pub struct CopyTarget<'a> {
+    pub vnode_id: TseriesFamilyId,
+    pub global_ctx: &'a GlobalContext,
+    pub tsm_dir: &'a Path,
+    pub delta_dir: &'a Path,
+}
+
+/// Copies values of `series` in `files` of `version` into new files, values
+/// are renamed to series ids in the new vnode. Each file is copied into a new
+/// file in the order of `files`, so newer values are in files with bigger ids.
+///
+/// Files are copied into the same level if `out_level` is None, or all into
+/// `out_level`. Values are copied block by block, and returns `CompactMeta`s
+/// of the wrote files.
+pub async fn copy_series(
+    version: &Version,
+    files: &[Arc<ColumnFile>],
+    series: &[MovedSeries],
+    out_level: Option<LevelId>,
+    target: &CopyTarget<'_>,
+) -> Result<Vec<(CompactMeta, Arc<BloomFilter>)>> {
+    let mut column_file_metas = Vec::new();
+    for file in files {
+        let level = out_level.unwrap_or_else(|| file.level());
+        let mut writer: Option<TsmWriter> = None;
+        let reader = version.get_tsm_reader(file).await?;
+        for (src_id, dst_id) in series.iter().flat_map(|s| s.field_ids()) {
+            if !file.contains_field_id(src_id) {
+                continue;
+            }
+            for idx in reader.index_iterator_opt(src_id) {
+                for blk in idx.block_iterator() {
+                    let data_block = reader
+                        .get_data_block_opt(&blk, false)
+                        .await
+                        .context(error::ReadTsmSnafu)?;
+                    if data_block.is_empty() {
+                        continue;
+                    }
+                    let w = match writer.as_mut() {
+                        Some(w) => w,
+                        None => {
+                            let (dir, is_delta) = if level == 0 {
+                                (target.delta_dir, true)
+                            } else {
+                                (target.tsm_dir, false)
+                            };
+                            let w = tsm::new_tsm_writer(
+                                dir,
+                                target.global_ctx.file_id_next(),
+                                is_delta,
+                                0,
+                            )
+                            .await?;
+                            writer.insert(w)
+                        }
+                    };
+                    w.write_block(dst_id, &data_block)
+                        .await
+                        .context(error::WriteTsmSnafu)?;
+                }
+            }
+        }
+
+        if let Some(mut w) = writer {
+            w.write_index().await.context(error::WriteTsmSnafu)?;
+            w.finish().await.context(error::WriteTsmSnafu)?;
+            let cm = CompactMeta {
+                file_id: w.sequence(),
+                file_size: w.size(),
+                tsf_id: target.vnode_id,
+                level,
+                min_ts: w.min_ts(),
+                max_ts: w.max_ts(),
+                high_seq: 0,
+                low_seq: 0,
+                is_delta: level == 0,
+                remote_path: None,
+            };
+            column_file_metas.push((cm, Arc::new(w.bloom_filter_cloned())));
+        }
+    }
+
+    Ok(column_file_metas)
+}
+
+/// Returns ids of fields of `series` (grouped by table) in the vnode.
+pub fn moved_field_ids(
+    series: &[(Arc<TskvTableSchema>, Vec<(SeriesId, SeriesKey)>)],
+) -> Vec<FieldId> {
+    let mut field_ids = Vec::new();
+    for (table_schema, table_series) in series {
+        let columns = table_schema.fields();
+        for (sid, _) in table_series {
+            field_ids.extend(columns.iter().map(|c| unite_id(c.id, *sid)));
+        }
+    }
+    field_ids
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use datafusion::arrow::datatypes::{DataType, TimeUnit};
+    use datafusion::scalar::ScalarValue;
+    use memory_pool::GreedyMemoryPool;
+    use meta::model::meta_admin::AdminMeta;
+    use meta::model::MetaRef;
+    use metrics::metric_register::MetricsRegister;
+    use models::meta_data::{BucketInfo, HashRange, ReplicationSet, VnodeInfo};
+    use models::predicate::domain::{
+        ColumnDomains, Domain, ResolvedPredicate, TimeRange, TimeRanges,
+    };
+    use models::schema::{
+        ColumnType, DatabaseSchema, Precision, TableColumn, TableSchema, TenantOptions,
+        TskvTableSchema,
+    };
+    use models::tag::tags_hash_id;
+    use models::utils::unite_id;
+    use models::{SeriesId, Tag, ValueType};
+    use protos::kv_service::{Meta, WritePointsRequest};
+    use protos::models::FieldType;
+    use protos::models_helper::create_points;
+    use tokio::runtime::{self, Runtime};
+
+    use crate::parquet_io::{read_field, sorted_column_files};
+    use crate::tsm::DataBlock;
+    use crate::{Engine, Error, Options, TsKv, TseriesFamilyId};
+
+    pub(crate) const TENANT: &str = "cnosdb";
+    pub(crate) const DATABASE: &str = "test_split_vnode";
+    pub(crate) const TABLE: &str = "tab";
+    const FIELD_COLUMN_ID: u32 = 1;
+
+    /// Writes value `ts * 10 + i + delta` of the field at `ts` of each series `i`.
+    pub(crate) async fn write_series(
+        engine: &TsKv,
+        vnode_id: TseriesFamilyId,
+        tag_values: &[String],
+        timestamps: &[i64],
+        delta: i64,
+    ) -> crate::Result<()> {
+        let mut tags = Vec::new();
+        let mut values = Vec::new();
+        let mut time = Vec::new();
+        for (i, tag_value) in tag_values.iter().enumerate() {
+            for ts in timestamps {
+                tags.push(tag_value.as_str());
+                values.push((ts * 10 + i as i64 + delta).to_be_bytes().to_vec());
+                time.push(*ts);
+            }
+        }
+        let mut fbb = flatbuffers::FlatBufferBuilder::new();
+        let points = create_points(
+            &mut fbb,
+            DATABASE,
+            TABLE,
+            HashMap::from([("ta", tags)]),
+            HashMap::from([("fi", values.iter().map(|v| v.as_slice()).collect())]),
+            HashMap::from([("fi", FieldType::Integer)]),
+            &time,
+            time.len(),
+        );
+        fbb.finish(points, None);
+        let write_batch = WritePointsRequest {
+            version: 1,
+            meta: Some(Meta {
+                tenant: TENANT.to_string(),
+                user: None,
+                password: None,
+            }),
+            points: fbb.finished_data().to_vec(),
+        };
+        engine
+            .write(None, vnode_id, Precision::NS, write_batch)
+            .await
+            .map(|_| ())
+    }
+
+    /// Opens a TsKv in `dir`, data already in `dir` is recovered.
+    pub(crate) async fn open_tskv(dir: &str, runtime: Arc<Runtime>) -> TsKv {
+        let mut config = config::get_config_for_test();
+        config.storage.path = dir.to_string();
+        config.wal.path = format!("{dir}/wal");
+        let options = Options::from(&config);
+
+        let meta: MetaRef = AdminMeta::new(config).await;
+        meta.add_data_node().await.unwrap();
+        let _ = meta
+            .create_tenant(TENANT.to_string(), TenantOptions::default())
+            .await;
+        TsKv::open(
+            meta,
+            options,
+            runtime,
+            Arc::new(GreedyMemoryPool::default()),
+            Arc::new(MetricsRegister::default()),
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Opens a TsKv in the emptied `dir`, with the database and table re-created.
+    pub(crate) async fn init_tskv(dir: &str, runtime: Arc<Runtime>) -> TsKv {
+        let _ = std::fs::remove_dir_all(dir);
+        let engine = open_tskv(dir, runtime).await;
+        let meta_client = engine.meta_manager().tenant_meta(TENANT).await.unwrap();
+        let _ = meta_client.drop_db(DATABASE).await;
+        meta_client
+            .create_db(DatabaseSchema::new(TENANT, DATABASE))
+            .await
+            .unwrap();
+        meta_client
+            .create_table(&TableSchema::TsKvTableSchema(Arc::new(
+                TskvTableSchema::new(
+                    TENANT.to_string(),
+                    DATABASE.to_string(),
+                    TABLE.to_string(),
+                    vec![
+                        TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                        TableColumn::new(
+                            FIELD_COLUMN_ID,
+                            "fi".to_string(),
+                            ColumnType::Field(ValueType::Integer),
+                            Default::default(),
+                        ),
+                        TableColumn::new_tag_column(2, "ta".to_string()),
+                    ],
+                ),
+            )))
+            .await
+            .unwrap();
+
+        engine
+    }
+
+    /// Returns the predicate of `ta = '{tag_value}' AND time BETWEEN {min_ts} AND {max_ts}`.
+    pub(crate) fn tag_predicate(tag_value: &str, time_range: TimeRange) -> ResolvedPredicate {
+        let tags_filter = ColumnDomains::of(
+            "ta".to_string(),
+            &Domain::of_values(
+                &DataType::Utf8,
+                true,
+                &[&ScalarValue::Utf8(Some(tag_value.to_string()))],
+            ),
+        );
+        ResolvedPredicate::new(
+            Arc::new(TimeRanges::new(vec![time_range])),
+            tags_filter,
+            ColumnDomains::all(),
+        )
+    }
+
+    /// Returns values of the field of the series in files of the vnode.
+    pub(crate) async fn read_values(
+        engine: &TsKv,
+        vnode_id: TseriesFamilyId,
+        sid: SeriesId,
+    ) -> Vec<i64> {
+        let db = engine.get_db(TENANT, DATABASE).await.unwrap();
+        let tsf = db.read().await.get_tsfamily(vnode_id).unwrap();
+        let version = tsf.read().await.version();
+        let files = sorted_column_files(&version);
+        match read_field(&version, &files, unite_id(FIELD_COLUMN_ID, sid))
+            .await
+            .unwrap()
+        {
+            Some(DataBlock::I64 { val, .. }) => val,
+            Some(b) => panic!("unexpected data block {b}"),
+            None => vec![],
+        }
+    }
+
+    #[test]
+    fn test_split_vnode() {
+        let runtime = Arc::new(
+            runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+        let rt = runtime.clone();
+        rt.block_on(async move {
+            let engine = init_tskv("/tmp/test/split/split_vnode", runtime).await;
+            let (vnode_id, new_vnode_id) = (1, 2);
+            let tag_values: Vec<String> = (0..16).map(|i| format!("a{i}")).collect();
+
+            // Values of timestamp 1 are overwritten by values in the memcache.
+            write_series(&engine, vnode_id, &tag_values, &[1, 2], 0)
+                .await
+                .unwrap();
+            engine
+                .flush_tsfamily(TENANT, DATABASE, vnode_id)
+                .await
+                .unwrap();
+            write_series(&engine, vnode_id, &tag_values, &[1, 3], 100)
+                .await
+                .unwrap();
+
+            let db = engine.get_db(TENANT, DATABASE).await.unwrap();
+            let ts_index = db.read().await.get_ts_index(vnode_id).unwrap();
+            let mut series = Vec::new();
+            for sid in ts_index.get_series_id_list(TABLE, &[]).await.unwrap() {
+                series.push((sid, ts_index.get_series_key(sid).await.unwrap().unwrap()));
+            }
+            assert_eq!(series.len(), tag_values.len());
+
+            let (_, upper) = HashRange::full(0).split().unwrap();
+            let hash_range = upper.start..=upper.end;
+            engine
+                .split_vnode(TENANT, DATABASE, vnode_id, new_vnode_id, &hash_range)
+                .await
+                .unwrap();
+            engine
+                .remove_moved_series(TENANT, DATABASE, vnode_id, &hash_range)
+                .await
+                .unwrap();
+            let new_ts_index = db.read().await.get_ts_index(new_vnode_id).unwrap();
+
+            // Writes are routed by the same hash as series keys.
+            let mut bucket = BucketInfo {
+                id: 1,
+                start_time: 0,
+                end_time: 100,
+                shard_group: vec![ReplicationSet::new(3, vec![VnodeInfo::new(vnode_id, 1)])],
+            };
+            bucket
+                .split_replication_set(
+                    3,
+                    ReplicationSet::new(4, vec![VnodeInfo::new(new_vnode_id, 1)]),
+                )
+                .unwrap();
+            assert!(bucket.cut_over_split(4));
+
+            let mut moved_num = 0;
+            for (sid, key) in series.iter() {
+                let i: i64 = std::str::from_utf8(&key.tags()[0].value).unwrap()[1..]
+                    .parse()
+                    .unwrap();
+                let expected = vec![10 + i + 100, 20 + i, 30 + i + 100];
+                let hash = tags_hash_id(key.table(), key.tags());
+                assert_eq!(hash, key.hash());
+
+                let old_values = read_values(&engine, vnode_id, *sid).await;
+                let new_sid = new_ts_index.get_series_id(key).await.unwrap();
+                if hash_range.contains(&hash) {
+                    moved_num += 1;
+                    assert_eq!(bucket.vnode_for(hash).vnodes[0].id, new_vnode_id);
+                    let new_values = read_values(&engine, new_vnode_id, new_sid.unwrap()).await;
+                    assert_eq!(new_values, expected, "series {key} in new vnode");
+                    assert!(old_values.is_empty(), "series {key} in old vnode");
+                    assert!(ts_index.get_series_key(*sid).await.unwrap().is_none());
+                } else {
+                    assert_eq!(bucket.vnode_for(hash).vnodes[0].id, vnode_id);
+                    assert_eq!(old_values, expected, "series {key} in old vnode");
+                    assert!(new_sid.is_none(), "series {key} in new vnode");
+                    assert!(ts_index.get_series_id(key).await.unwrap().is_some());
+                }
+            }
+            assert!(moved_num > 0 && moved_num < series.len());
+
+            // Points of the moved series are rejected by the old vnode.
+            let moved_tag = tag_values
+                .iter()
+                .find(|v| {
+                    let tags = vec![Tag::new(b"ta".to_vec(), v.as_bytes().to_vec())];
+                    hash_range.contains(&tags_hash_id(&TABLE.to_string(), &tags))
+                })
+                .unwrap();
+            let res = write_series(&engine, vnode_id, &[moved_tag.clone()], &[4], 0).await;
+            assert!(matches!(res, Err(Error::SeriesMoved { .. })), "{res:?}");
+
+            engine.close().await;
+        });
+    }
+}
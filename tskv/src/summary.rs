@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::fs::{remove_file, rename};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -21,7 +22,7 @@ use crate::compaction::{CompactTask, FlushReq};
 use crate::context::{GlobalContext, GlobalSequenceTask};
 use crate::error::{Error, Result};
 use crate::file_system::file_manager::try_exists;
-use crate::kv_option::{Options, StorageOptions, DELTA_PATH, TSM_PATH};
+use crate::kv_option::{Options, StorageOptions, DELTA_PATH, TSM_PATH, VNODE_RENAME_EXTENSION};
 use crate::memcache::MemCache;
 use crate::record_file::{Reader, RecordDataType, RecordDataVersion, Writer};
 use crate::tseries_family::{ColumnFile, LevelInfo, Version};
@@ -42,6 +43,12 @@ pub struct CompactMeta {
     pub high_seq: u64,
     pub low_seq: u64,
     pub is_delta: bool,
+    /// Path in the cold storage if the file was moved to it.
+    ///
+    /// It's not a part of the bincode layout of `CompactMeta`, see
+    /// `VersionEdit::encode()`.
+    #[serde(skip)]
+    pub remote_path: Option<String>,
 }
 
 impl Default for CompactMeta {
@@ -56,6 +63,7 @@ impl Default for CompactMeta {
             high_seq: u64::MIN,
             low_seq: u64::MIN,
             is_delta: false,
+            remote_path: None,
         }
     }
 }
@@ -73,6 +81,7 @@ impl From<&ColumnFile> for CompactMeta {
             min_ts: file.time_range().min_ts,
             max_ts: file.time_range().max_ts,
             is_delta: file.is_delta(),
+            remote_path: file.remote_path().map(|p| p.to_string()),
             ..Default::default()
         }
     }
@@ -94,6 +103,8 @@ impl CompactMeta {
         }
     }
 
+    /// Renames the file in the move directory of the vnode into the vnode
+    /// with `file_id`, and the tombstone file of it if exists.
     pub async fn rename_file(
         &mut self,
         storage_opt: &StorageOptions,
@@ -101,25 +112,38 @@ impl CompactMeta {
         ts_family_id: TseriesFamilyId,
         file_id: ColumnFileId,
     ) -> Result<PathBuf> {
-        let old_name = if self.is_delta {
-            let base_dir = storage_opt
-                .move_dir(database, ts_family_id)
-                .join(DELTA_PATH);
-            file_utils::make_delta_file_name(base_dir, self.file_id)
+        let (old_dir, new_dir) = if self.is_delta {
+            (
+                storage_opt
+                    .move_dir(database, ts_family_id)
+                    .join(DELTA_PATH),
+                storage_opt.delta_dir(database, ts_family_id),
+            )
         } else {
-            let base_dir = storage_opt.move_dir(database, ts_family_id).join(TSM_PATH);
-            file_utils::make_tsm_file_name(base_dir, self.file_id)
+            (
+                storage_opt.move_dir(database, ts_family_id).join(TSM_PATH),
+                storage_opt.tsm_dir(database, ts_family_id),
+            )
         };
-
-        let new_name = if self.is_delta {
-            let base_dir = storage_opt.delta_dir(database, ts_family_id);
-            file_utils::make_delta_file_name(base_dir, file_id)
+        let (old_name, new_name) = if self.is_delta {
+            (
+                file_utils::make_delta_file_name(&old_dir, self.file_id),
+                file_utils::make_delta_file_name(&new_dir, file_id),
+            )
         } else {
-            let base_dir = storage_opt.tsm_dir(database, ts_family_id);
-            file_utils::make_tsm_file_name(base_dir, file_id)
+            (
+                file_utils::make_tsm_file_name(&old_dir, self.file_id),
+                file_utils::make_tsm_file_name(&new_dir, file_id),
+            )
         };
         trace::info!("rename file from {:?} to {:?}", &old_name, &new_name);
         file_utils::rename(old_name, &new_name).await?;
+
+        let old_tombstone = file_utils::make_tsm_tombstone_file_name(&old_dir, self.file_id);
+        if try_exists(&old_tombstone) {
+            let new_tombstone = file_utils::make_tsm_tombstone_file_name(&new_dir, file_id);
+            file_utils::rename(old_tombstone, new_tombstone).await?;
+        }
         self.file_id = file_id;
         Ok(new_name)
     }
@@ -216,19 +240,44 @@ impl VersionEdit {
         }
     }
 
+    /// Encodes the `VersionEdit` in the layout of summary files written by
+    /// versions without the cold storage, then the paths of added files in the
+    /// cold storage (if there are any) are appended as `Vec<(file_id, path)>`.
     pub fn encode(&self) -> Result<Vec<u8>> {
-        bincode::serialize(self).map_err(|e| Error::RecordFileEncode { source: (e) })
+        let mut buf =
+            bincode::serialize(self).map_err(|e| Error::RecordFileEncode { source: (e) })?;
+        let remote_files: Vec<(ColumnFileId, &str)> = self
+            .add_files
+            .iter()
+            .filter_map(|f| f.remote_path.as_deref().map(|p| (f.file_id, p)))
+            .collect();
+        if !remote_files.is_empty() {
+            bincode::serialize_into(&mut buf, &remote_files)
+                .map_err(|e| Error::RecordFileEncode { source: (e) })?;
+        }
+        Ok(buf)
     }
 
     pub fn decode(buf: &[u8]) -> Result<Self> {
-        bincode::deserialize(buf).map_err(|e| Error::RecordFileDecode { source: (e) })
+        let mut reader = buf;
+        let mut ve: Self = bincode::deserialize_from(&mut reader)
+            .map_err(|e| Error::RecordFileDecode { source: (e) })?;
+        if !reader.is_empty() {
+            let remote_files: Vec<(ColumnFileId, String)> = bincode::deserialize(reader)
+                .map_err(|e| Error::RecordFileDecode { source: (e) })?;
+            for (file_id, remote_path) in remote_files {
+                if let Some(f) = ve.add_files.iter_mut().find(|f| f.file_id == file_id) {
+                    f.remote_path = Some(remote_path);
+                }
+            }
+        }
+        Ok(ve)
     }
 
     pub fn encode_vec(data: &[Self]) -> Result<Vec<u8>> {
         let mut buf: Vec<u8> = Vec::with_capacity(data.len() * 32);
         for ve in data {
-            let ve_buf =
-                bincode::serialize(ve).map_err(|e| Error::RecordFileEncode { source: (e) })?;
+            let ve_buf = ve.encode()?;
             let pos = buf.len();
             buf.resize(pos + 4 + ve_buf.len(), 0_u8);
             buf[pos..pos + 4].copy_from_slice((ve_buf.len() as u32).to_be_bytes().as_slice());
@@ -356,6 +405,7 @@ impl Summary {
         metrics_register: Arc<MetricsRegister>,
     ) -> Result<Self> {
         let summary_path = opt.storage.summary_dir();
+        complete_vnode_renames(&opt.storage).await?;
         let path = file_utils::make_summary_file(&summary_path, 0);
         let writer = Writer::open(path, RecordDataType::Summary).await.unwrap();
         let ctx = Arc::new(GlobalContext::default());
@@ -378,7 +428,7 @@ impl Summary {
         )
         .await?;
 
-        Ok(Self {
+        let mut summary = Self {
             file_no: 0,
             version_set: Arc::new(RwLock::new(vs)),
             ctx,
@@ -387,7 +437,40 @@ impl Summary {
             runtime,
             sequence_task_sender,
             metrics_register,
-        })
+        };
+        summary.finish_vnode_renames().await?;
+        Ok(summary)
+    }
+
+    /// Writes vnodes moved into another database by an interrupted rename into
+    /// the summary file with their new owners, then removes the rename files.
+    async fn finish_vnode_renames(&mut self) -> Result<()> {
+        for (vnode_id, owner) in read_vnode_renames(&self.opt.storage)? {
+            let tsf = self
+                .version_set
+                .read()
+                .await
+                .get_tsfamily_by_tf_id(vnode_id)
+                .await;
+            if let Some(tsf) = tsf {
+                let edit = tsf
+                    .read()
+                    .await
+                    .snapshot(Arc::new(owner.clone()), &mut HashMap::new());
+                let buf = edit.encode()?;
+                self.writer
+                    .write_record(
+                        RecordDataVersion::V1.into(),
+                        RecordDataType::Summary.into(),
+                        &[&buf],
+                    )
+                    .await?;
+                self.writer.sync().await?;
+                trace::info!("Recover: renamed vnode {vnode_id} to '{owner}'");
+            }
+            remove_vnode_rename(&self.opt.storage, vnode_id)?;
+        }
+        Ok(())
     }
 
     /// Recover from summary file
@@ -407,37 +490,11 @@ impl Summary {
         load_field_filter: bool,
         metrics_register: Arc<MetricsRegister>,
     ) -> Result<VersionSet> {
-        let mut tsf_edits_map: HashMap<TseriesFamilyId, Vec<VersionEdit>> = HashMap::new();
-        let mut database_map: HashMap<String, Arc<String>> = HashMap::new();
-        let mut tsf_database_map: HashMap<TseriesFamilyId, Arc<String>> = HashMap::new();
-
-        loop {
-            let res = reader.read_record().await;
-            match res {
-                Ok(result) => {
-                    let ed = VersionEdit::decode(&result.data)?;
-                    if ed.add_tsf {
-                        let db_ref = database_map
-                            .entry(ed.tsf_name.clone())
-                            .or_insert_with(|| Arc::new(ed.tsf_name.clone()));
-                        tsf_database_map.insert(ed.tsf_id, db_ref.clone());
-                        if ed.has_file_id {
-                            tsf_edits_map.insert(ed.tsf_id, vec![ed]);
-                        } else {
-                            tsf_edits_map.insert(ed.tsf_id, vec![]);
-                        }
-                    } else if ed.del_tsf {
-                        tsf_edits_map.remove(&ed.tsf_id);
-                        tsf_database_map.remove(&ed.tsf_id);
-                    } else if let Some(data) = tsf_edits_map.get_mut(&ed.tsf_id) {
-                        data.push(ed);
-                    }
-                }
-                Err(Error::Eof) => break,
-                Err(Error::RecordFileHashCheckFailed { .. }) => continue,
-                Err(e) => {
-                    return Err(e);
-                }
+        let (tsf_edits_map, mut tsf_database_map) = read_tsf_edits(&mut reader).await?;
+        // Vnodes being renamed were moved into the directory of the new owner.
+        for (tsf_id, owner) in read_vnode_renames(&opt.storage)? {
+            if let Some(database) = tsf_database_map.get_mut(&tsf_id) {
+                *database = Arc::new(owner);
             }
         }
 
@@ -479,7 +536,13 @@ impl Summary {
             for meta in files.into_values() {
                 let field_filter = if load_field_filter {
                     let tsm_path = meta.file_path(opt.storage.as_ref(), &database, tsf_id);
-                    let tsm_reader = TsmReader::open(tsm_path).await?;
+                    let tsm_reader = match (&meta.remote_path, &opt.cold_storage) {
+                        (Some(remote_path), Some(cold_storage)) => {
+                            let file = cold_storage.open_file(remote_path).await?;
+                            TsmReader::open_remote(tsm_path, file).await?
+                        }
+                        _ => TsmReader::open(tsm_path).await?,
+                    };
                     tsm_reader.bloom_filter()
                 } else {
                     Arc::new(BloomFilter::default())
@@ -488,6 +551,8 @@ impl Summary {
                     &meta,
                     field_filter,
                     weak_tsm_reader_cache.clone(),
+                    opt.cold_storage.clone(),
+                    opt.data_block_cache.clone(),
                 );
             }
             let ver = Version::new(
@@ -498,6 +563,8 @@ impl Summary {
                 levels,
                 max_level_ts,
                 tsm_reader_cache,
+                opt.cold_storage.clone(),
+                opt.data_block_cache.clone(),
             );
             versions.insert(tsf_id, Arc::new(ver));
         }
@@ -654,6 +721,125 @@ impl Summary {
     }
 }
 
+/// Reads all `VersionEdit`s of a summary file, returns edits of each existing
+/// ts_family since it was added, and the owner of each ts_family.
+pub(crate) async fn read_tsf_edits(
+    reader: &mut Reader,
+) -> Result<(
+    HashMap<TseriesFamilyId, Vec<VersionEdit>>,
+    HashMap<TseriesFamilyId, Arc<String>>,
+)> {
+    let mut tsf_edits_map: HashMap<TseriesFamilyId, Vec<VersionEdit>> = HashMap::new();
+    let mut database_map: HashMap<String, Arc<String>> = HashMap::new();
+    let mut tsf_database_map: HashMap<TseriesFamilyId, Arc<String>> = HashMap::new();
+
+    loop {
+        let res = reader.read_record().await;
+        match res {
+            Ok(result) => {
+                let ed = VersionEdit::decode(&result.data)?;
+                if ed.add_tsf {
+                    let db_ref = database_map
+                        .entry(ed.tsf_name.clone())
+                        .or_insert_with(|| Arc::new(ed.tsf_name.clone()));
+                    tsf_database_map.insert(ed.tsf_id, db_ref.clone());
+                    if ed.has_file_id {
+                        tsf_edits_map.insert(ed.tsf_id, vec![ed]);
+                    } else {
+                        tsf_edits_map.insert(ed.tsf_id, vec![]);
+                    }
+                } else if ed.del_tsf {
+                    tsf_edits_map.remove(&ed.tsf_id);
+                    tsf_database_map.remove(&ed.tsf_id);
+                } else if let Some(data) = tsf_edits_map.get_mut(&ed.tsf_id) {
+                    data.push(ed);
+                }
+            }
+            Err(Error::Eof) => break,
+            Err(Error::RecordFileHashCheckFailed { .. }) => continue,
+            Err(e) => {
+                return Err(e);
+            }
+        }
+    }
+
+    Ok((tsf_edits_map, tsf_database_map))
+}
+
+/// Records that the vnode is being moved into the database `owner`, it's
+/// removed after the vnode is written into the summary file with the new owner.
+pub(crate) fn write_vnode_rename(
+    storage_opt: &StorageOptions,
+    vnode_id: TseriesFamilyId,
+    owner: &str,
+) -> Result<()> {
+    let path = storage_opt.vnode_rename_file(vnode_id);
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(owner.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+pub(crate) fn remove_vnode_rename(
+    storage_opt: &StorageOptions,
+    vnode_id: TseriesFamilyId,
+) -> Result<()> {
+    let path = storage_opt.vnode_rename_file(vnode_id);
+    if try_exists(&path) {
+        remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Returns ids of vnodes being moved into another database and their new owners.
+pub(crate) fn read_vnode_renames(
+    storage_opt: &StorageOptions,
+) -> Result<HashMap<TseriesFamilyId, String>> {
+    let mut renames = HashMap::new();
+    let dir = storage_opt.summary_dir();
+    if !try_exists(&dir) {
+        return Ok(renames);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some(VNODE_RENAME_EXTENSION) {
+            continue;
+        }
+        let vnode_id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<TseriesFamilyId>().ok());
+        if let Some(vnode_id) = vnode_id {
+            renames.insert(vnode_id, std::fs::read_to_string(&path)?);
+        }
+    }
+    Ok(renames)
+}
+
+/// Moves directories of vnodes being renamed into the directories of their
+/// new owners, if they were not moved before the node stopped.
+async fn complete_vnode_renames(storage_opt: &StorageOptions) -> Result<()> {
+    for (vnode_id, owner) in read_vnode_renames(storage_opt)? {
+        let new_dir = storage_opt.tsfamily_dir(&owner, vnode_id);
+        if try_exists(&new_dir) {
+            continue;
+        }
+        for entry in std::fs::read_dir(storage_opt.data_dir())? {
+            let old_dir = entry?.path().join(vnode_id.to_string());
+            if try_exists(&old_dir) {
+                trace::info!(
+                    "Recover: moving vnode {vnode_id} from '{}' to '{}'",
+                    old_dir.display(),
+                    new_dir.display()
+                );
+                file_utils::rename(&old_dir, &new_dir).await?;
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
 pub async fn print_summary_statistics(path: impl AsRef<Path>) {
     let mut reader = Reader::open(&path).await.unwrap();
     println!("============================================================");
@@ -834,11 +1020,16 @@ mod test {
     use crate::compaction::{CompactTask, FlushReq};
     use crate::context::{GlobalContext, GlobalSequenceTask};
     use crate::file_system::file_manager;
-    use crate::kv_option::Options;
+    use crate::file_utils;
+    use crate::kv_option::{Options, StorageOptions};
     use crate::kvcore::{
         COMPACT_REQ_CHANNEL_CAP, GLOBAL_TASK_REQ_CHANNEL_CAP, SUMMARY_REQ_CHANNEL_CAP,
     };
-    use crate::summary::{CompactMeta, Summary, SummaryTask, VersionEdit};
+    use crate::record_file::{Reader, RecordDataType, RecordDataVersion, Writer};
+    use crate::summary::{
+        complete_vnode_renames, read_tsf_edits, read_vnode_renames, remove_vnode_rename,
+        write_vnode_rename, CompactMeta, Summary, SummaryTask, VersionEdit,
+    };
 
     #[test]
     fn test_version_edit() {
@@ -864,6 +1055,168 @@ mod test {
         let ves_buf = VersionEdit::encode_vec(&ves).unwrap();
         let ves_2 = VersionEdit::decode_vec(&ves_buf).unwrap();
         assert_eq!(ves, ves_2);
+
+        let mut ve = VersionEdit::default();
+        let add_file_102 = CompactMeta {
+            file_id: 102,
+            remote_path: Some("db/1/102.tsm".to_string()),
+            ..Default::default()
+        };
+        ve.add_file(add_file_102, 100_000_000);
+        let ve_buf = ve.encode().unwrap();
+        let ve2 = VersionEdit::decode(&ve_buf).unwrap();
+        assert_eq!(ve2, ve);
+        assert_eq!(
+            ve2.add_files[0].remote_path.as_deref(),
+            Some("db/1/102.tsm")
+        );
+    }
+
+    /// `CompactMeta` and `VersionEdit` in summary files written by versions
+    /// without the cold storage.
+    mod legacy {
+        use models::Timestamp;
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        pub struct CompactMeta {
+            pub file_id: u64,
+            pub file_size: u64,
+            pub tsf_id: u32,
+            pub level: u32,
+            pub min_ts: Timestamp,
+            pub max_ts: Timestamp,
+            pub high_seq: u64,
+            pub low_seq: u64,
+            pub is_delta: bool,
+        }
+
+        #[derive(Serialize)]
+        pub struct VersionEdit {
+            pub has_seq_no: bool,
+            pub seq_no: u64,
+            pub has_file_id: bool,
+            pub file_id: u64,
+            pub max_level_ts: Timestamp,
+            pub add_files: Vec<CompactMeta>,
+            pub del_files: Vec<CompactMeta>,
+            pub del_tsf: bool,
+            pub add_tsf: bool,
+            pub tsf_id: u32,
+            pub tsf_name: String,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_legacy_summary() {
+        let dir = "/tmp/test/summary/legacy";
+        let _ = fs::remove_dir_all(dir);
+        let path = file_utils::make_summary_file(dir, 0);
+
+        let add_vnode = legacy::VersionEdit {
+            has_seq_no: true,
+            seq_no: 1,
+            has_file_id: false,
+            file_id: 0,
+            max_level_ts: i64::MIN,
+            add_files: vec![],
+            del_files: vec![],
+            del_tsf: false,
+            add_tsf: true,
+            tsf_id: 1,
+            tsf_name: "cnosdb.public".to_string(),
+        };
+        let add_file = legacy::VersionEdit {
+            has_seq_no: true,
+            seq_no: 10,
+            has_file_id: true,
+            file_id: 5,
+            max_level_ts: 200,
+            add_files: vec![legacy::CompactMeta {
+                file_id: 5,
+                file_size: 1024,
+                tsf_id: 1,
+                level: 1,
+                min_ts: 100,
+                max_ts: 200,
+                high_seq: 10,
+                low_seq: 1,
+                is_delta: false,
+            }],
+            del_files: vec![],
+            del_tsf: false,
+            add_tsf: false,
+            tsf_id: 1,
+            tsf_name: String::new(),
+        };
+        let mut writer = Writer::open(&path, RecordDataType::Summary).await.unwrap();
+        for ve in [add_vnode, add_file] {
+            writer
+                .write_record(
+                    RecordDataVersion::V1.into(),
+                    RecordDataType::Summary.into(),
+                    &[&bincode::serialize(&ve).unwrap()],
+                )
+                .await
+                .unwrap();
+        }
+        writer.close().await.unwrap();
+
+        let mut reader = Reader::open(&path).await.unwrap();
+        let (tsf_edits_map, tsf_database_map) = read_tsf_edits(&mut reader).await.unwrap();
+        assert_eq!(
+            tsf_database_map.get(&1).map(|d| d.as_str()),
+            Some("cnosdb.public")
+        );
+        let edits = tsf_edits_map.get(&1).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(
+            edits[0].add_files,
+            vec![CompactMeta {
+                file_id: 5,
+                file_size: 1024,
+                tsf_id: 1,
+                level: 1,
+                min_ts: 100,
+                max_ts: 200,
+                high_seq: 10,
+                low_seq: 1,
+                is_delta: false,
+                remote_path: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_complete_vnode_renames() {
+        let dir = "/tmp/test/summary/vnode_renames";
+        let _ = fs::remove_dir_all(dir);
+        let storage_opt = StorageOptions {
+            path: dir.into(),
+            ..Default::default()
+        };
+        let (owner, new_owner) = (make_owner("cnosdb", "db1"), make_owner("cnosdb", "db2"));
+        fs::create_dir_all(storage_opt.summary_dir()).unwrap();
+        fs::create_dir_all(storage_opt.tsm_dir(&owner, 1)).unwrap();
+        fs::create_dir_all(storage_opt.tsm_dir(&owner, 2)).unwrap();
+
+        // The node stopped before vnode 1 was moved.
+        write_vnode_rename(&storage_opt, 1, &new_owner).unwrap();
+        let renames = read_vnode_renames(&storage_opt).unwrap();
+        assert_eq!(renames, HashMap::from([(1, new_owner.clone())]));
+        complete_vnode_renames(&storage_opt).await.unwrap();
+        assert!(!file_manager::try_exists(
+            storage_opt.tsfamily_dir(&owner, 1)
+        ));
+        assert!(file_manager::try_exists(storage_opt.tsm_dir(&new_owner, 1)));
+        assert!(file_manager::try_exists(storage_opt.tsm_dir(&owner, 2)));
+
+        // Completing again changes nothing.
+        complete_vnode_renames(&storage_opt).await.unwrap();
+        assert!(file_manager::try_exists(storage_opt.tsm_dir(&new_owner, 1)));
+
+        remove_vnode_rename(&storage_opt, 1).unwrap();
+        assert!(read_vnode_renames(&storage_opt).unwrap().is_empty());
     }
 
     #[test]
@@ -1291,6 +1644,8 @@ mod test {
                 &meta,
                 Arc::new(BloomFilter::default()),
                 tsm_reader_cache,
+                None,
+                None,
             );
             tsf.write().await.new_version(version, None);
             edit.add_file(meta, 1);
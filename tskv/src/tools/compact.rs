@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
+use lru_cache::asynchronous::ShardedCache;
+use meta::model::MetaRef;
+use models::meta_data::VnodeId;
+use models::schema::{split_owner, DatabaseSchema};
+use utils::BloomFilter;
+
+use crate::compaction::{run_compaction_job, CompactReq, FieldTypeResolver};
+use crate::context::GlobalContext;
+use crate::error::{Error, Result};
+use crate::file_system::file_manager;
+use crate::index::ts_index::TSIndex;
+use crate::kv_option::Options;
+use crate::record_file::{self, RecordDataType, RecordDataVersion};
+use crate::schema::schemas::DBschemas;
+use crate::summary::{self, CompactMeta};
+use crate::tseries_family::{LevelInfo, Version};
+use crate::{file_utils, ColumnFileId};
+
+/// Level of files generated by the full compaction.
+const OUT_LEVEL: u32 = 4;
+
+#[derive(Debug)]
+pub struct CompactVnodeReport {
+    pub vnode_id: VnodeId,
+    pub owner: String,
+    /// Ids of compacted files.
+    pub input_files: Vec<ColumnFileId>,
+    /// Ids of generated files.
+    pub output_files: Vec<ColumnFileId>,
+}
+
+impl Display for CompactVnodeReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.input_files.is_empty() {
+            return write!(
+                f,
+                "There is nothing to compact in vnode {} of '{}'.",
+                self.vnode_id, self.owner
+            );
+        }
+        write!(
+            f,
+            "Compacted vnode {} of '{}', files {:?} -> {:?}.",
+            self.vnode_id, self.owner, self.input_files, self.output_files
+        )
+    }
+}
+
+/// Compacts all local files of a vnode into files of the last level, the node
+/// which the vnode belongs to must be stopped.
+///
+/// Files of the vnode are read from the summary file under storage path in
+/// `opt`, the compaction result is appended to the summary file and then the
+/// compacted files are removed.
+///
+/// If `meta` is not None, data blocks of field columns are converted to the
+/// types and encodings of the columns in the current table schemas, like the
+/// compaction of a running node does.
+pub async fn compact_vnode(
+    opt: &Options,
+    vnode_id: VnodeId,
+    meta: Option<MetaRef>,
+) -> Result<CompactVnodeReport> {
+    let summary_path = file_utils::make_summary_file(opt.storage.summary_dir(), 0);
+    if !file_manager::try_exists(&summary_path) {
+        return Err(Error::InvalidParam {
+            reason: format!("summary file '{}' not found", summary_path.display()),
+        });
+    }
+    let mut reader = record_file::Reader::open(&summary_path).await?;
+    let (mut tsf_edits_map, mut tsf_database_map) = summary::read_tsf_edits(&mut reader).await?;
+    drop(reader);
+
+    // File ids are unique in a node, new files must not use ids of other vnodes.
+    let max_file_id = tsf_edits_map
+        .values()
+        .flatten()
+        .filter(|e| e.has_file_id)
+        .map(|e| e.file_id)
+        .max()
+        .unwrap_or(0);
+    let (edits, database) = match (
+        tsf_edits_map.remove(&vnode_id),
+        tsf_database_map.remove(&vnode_id),
+    ) {
+        (Some(edits), Some(database)) => (edits, database),
+        _ => {
+            return Err(Error::InvalidParam {
+                reason: format!("vnode {vnode_id} not found in summary"),
+            })
+        }
+    };
+
+    let mut files: HashMap<ColumnFileId, CompactMeta> = HashMap::new();
+    let mut max_seq_no = 0;
+    let mut max_level_ts = i64::MIN;
+    for e in edits {
+        if e.has_seq_no {
+            max_seq_no = max_seq_no.max(e.seq_no);
+        }
+        max_level_ts = max_level_ts.max(e.max_level_ts);
+        for m in e.del_files {
+            files.remove(&m.file_id);
+        }
+        for m in e.add_files {
+            files.insert(m.file_id, m);
+        }
+    }
+
+    let tsm_reader_cache = Arc::new(ShardedCache::with_capacity(opt.storage.max_cached_readers));
+    let mut levels = LevelInfo::init_levels(database.clone(), vnode_id, opt.storage.clone());
+    for meta in files.values() {
+        // Files moved to the cold storage are not compacted.
+        if meta.remote_path.is_some() {
+            continue;
+        }
+        levels[meta.level as usize].push_compact_meta(
+            meta,
+            Arc::new(BloomFilter::default()),
+            Arc::downgrade(&tsm_reader_cache),
+            None,
+            None,
+        );
+    }
+    let version = Arc::new(Version::new(
+        vnode_id,
+        database.clone(),
+        opt.storage.clone(),
+        max_seq_no,
+        levels,
+        max_level_ts,
+        tsm_reader_cache,
+        None,
+        None,
+    ));
+    let compact_files: Vec<_> = version
+        .levels_info()
+        .iter()
+        .flat_map(|l| l.files.iter().cloned())
+        .collect();
+    let mut report = CompactVnodeReport {
+        vnode_id,
+        owner: database.to_string(),
+        input_files: compact_files.iter().map(|f| f.file_id()).collect(),
+        output_files: vec![],
+    };
+    // A single file in the last level is already compacted.
+    if compact_files.is_empty()
+        || (compact_files.len() == 1 && compact_files[0].level() == OUT_LEVEL)
+    {
+        report.input_files.clear();
+        return Ok(report);
+    }
+
+    let ctx = Arc::new(GlobalContext::new());
+    ctx.set_file_id(max_file_id + 1);
+    let mut request = CompactReq::new(version.clone(), compact_files.clone(), OUT_LEVEL);
+    if let Some(meta) = meta {
+        let resolver = field_type_resolver(opt, &database, vnode_id, meta).await?;
+        request = request.with_field_type_resolver(Some(resolver));
+    }
+    let version_edit = match run_compaction_job(request, ctx).await? {
+        Some((version_edit, _)) => version_edit,
+        None => {
+            report.input_files.clear();
+            return Ok(report);
+        }
+    };
+    report.output_files = version_edit.add_files.iter().map(|f| f.file_id).collect();
+
+    let mut writer = record_file::Writer::open(&summary_path, RecordDataType::Summary).await?;
+    writer
+        .write_record(
+            RecordDataVersion::V1.into(),
+            RecordDataType::Summary.into(),
+            &[&version_edit.encode()?],
+        )
+        .await?;
+    writer.close().await?;
+
+    for file in compact_files.iter() {
+        remove_file(file.file_path());
+        let tombstone_path = file_utils::make_tsm_tombstone_file_name(
+            file.file_path().parent().unwrap(),
+            file.file_id(),
+        );
+        if file_manager::try_exists(&tombstone_path) {
+            remove_file(&tombstone_path);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Creates a `FieldTypeResolver` with the index of the vnode and table schemas
+/// of the database `owner` in meta.
+async fn field_type_resolver(
+    opt: &Options,
+    owner: &str,
+    vnode_id: VnodeId,
+    meta: MetaRef,
+) -> Result<FieldTypeResolver> {
+    let (tenant, database) = split_owner(owner);
+    let client = meta
+        .tenant_meta(tenant)
+        .await
+        .ok_or_else(|| Error::InvalidParam {
+            reason: format!("tenant '{tenant}' not found in meta"),
+        })?;
+    // DBschemas creates the database if it's not found, which is not wanted here.
+    if client.get_db_schema(database)?.is_none() {
+        return Err(Error::InvalidParam {
+            reason: format!("database '{owner}' not found in meta"),
+        });
+    }
+    let schemas = DBschemas::new(DatabaseSchema::new(tenant, database), meta).await?;
+    let ts_index = TSIndex::new(opt.storage.index_dir(owner, vnode_id)).await?;
+    Ok(FieldTypeResolver::new(ts_index, Arc::new(schemas)))
+}
+
+fn remove_file(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        trace::error!("Failed to remove file '{}': {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{compact_vnode, OUT_LEVEL};
+    use crate::compaction::test::{create_options, write_data_blocks_to_column_file};
+    use crate::file_system::file_manager;
+    use crate::file_utils;
+    use crate::record_file::{self, RecordDataType, RecordDataVersion};
+    use crate::summary::{self, CompactMetaBuilder, VersionEdit};
+    use crate::tsm::codec::DataBlockEncoding;
+    use crate::tsm::{DataBlock, TsmReader};
+
+    #[tokio::test]
+    async fn test_compact_vnode() {
+        let dir = "/tmp/test/tools/compact";
+        let _ = std::fs::remove_dir_all(dir);
+        let opt = create_options(dir.to_string());
+        let database = "cnosdb.db";
+        let vnode_id = 1;
+
+        #[rustfmt::skip]
+        let data = vec![
+            HashMap::from([
+                (1, vec![DataBlock::I64 { ts: vec![1, 2, 3], val: vec![1, 2, 3], enc: DataBlockEncoding::default() }]),
+            ]),
+            HashMap::from([
+                (1, vec![DataBlock::I64 { ts: vec![3, 4, 5], val: vec![30, 4, 5], enc: DataBlockEncoding::default() }]),
+            ]),
+        ];
+        let tsm_dir = opt.storage.tsm_dir(database, vnode_id);
+        let (_, files) = write_data_blocks_to_column_file(&tsm_dir, data).await;
+
+        let summary_dir = opt.storage.summary_dir();
+        std::fs::create_dir_all(&summary_dir).unwrap();
+        let summary_path = file_utils::make_summary_file(&summary_dir, 0);
+        let mut edits = vec![VersionEdit::new_add_vnode(
+            vnode_id,
+            database.to_string(),
+            0,
+        )];
+        let mut edit = VersionEdit::new(vnode_id);
+        let meta_builder = CompactMetaBuilder::new(vnode_id);
+        for file in files.iter() {
+            let time_range = file.time_range();
+            let meta = meta_builder.build(
+                file.file_id(),
+                file.size(),
+                file.level(),
+                time_range.min_ts,
+                time_range.max_ts,
+            );
+            edit.add_file(meta, time_range.max_ts);
+        }
+        edits.push(edit);
+        let mut writer = record_file::Writer::open(&summary_path, RecordDataType::Summary)
+            .await
+            .unwrap();
+        for edit in edits {
+            writer
+                .write_record(
+                    RecordDataVersion::V1.into(),
+                    RecordDataType::Summary.into(),
+                    &[&edit.encode().unwrap()],
+                )
+                .await
+                .unwrap();
+        }
+        writer.close().await.unwrap();
+
+        let mut report = compact_vnode(&opt, vnode_id, None).await.unwrap();
+        report.input_files.sort_unstable();
+        assert_eq!(report.input_files, vec![1, 2]);
+        assert_eq!(report.output_files.len(), 1);
+        for file in files.iter() {
+            assert!(!file_manager::try_exists(file.file_path()));
+        }
+
+        // The summary file contains only the generated file.
+        let mut reader = record_file::Reader::open(&summary_path).await.unwrap();
+        let (tsf_edits_map, _) = summary::read_tsf_edits(&mut reader).await.unwrap();
+        let mut live_files = HashMap::new();
+        for e in tsf_edits_map.get(&vnode_id).unwrap() {
+            for m in e.del_files.iter() {
+                live_files.remove(&m.file_id);
+            }
+            for m in e.add_files.iter() {
+                live_files.insert(m.file_id, m.level);
+            }
+        }
+        assert_eq!(
+            live_files,
+            HashMap::from([(report.output_files[0], OUT_LEVEL)])
+        );
+
+        // Values of the newer file are kept.
+        let path = file_utils::make_tsm_file_name(&tsm_dir, report.output_files[0]);
+        let reader = TsmReader::open(path).await.unwrap();
+        let idx = reader.index_iterator().next().unwrap();
+        let blk = idx.block_iterator().next().unwrap();
+        let data_block = reader.get_data_block(&blk).await.unwrap();
+        assert_eq!(
+            data_block,
+            DataBlock::I64 {
+                ts: vec![1, 2, 3, 4, 5],
+                val: vec![1, 2, 30, 4, 5],
+                enc: DataBlockEncoding::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use models::utils::split_id;
+use models::{SeriesId, SeriesKey};
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+use crate::index::ts_index::TSIndex;
+use crate::kv_option::INDEX_PATH;
+use crate::memcache::DataType;
+use crate::tools::vnode_data_files;
+use crate::tsm::TsmReader;
+
+/// Table name of series not found in the index.
+const UNKNOWN_TABLE: &str = "_unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// Rows of `series,column,time,value`.
+    Csv,
+    /// Lines of `table[,tags] <column>=<value> <time>`.
+    LineProtocol,
+}
+
+/// Writes values in a TSM (or delta) file, or in all data files of a vnode
+/// directory, with tombstones applied.
+///
+/// Series keys are read from the index of the vnode, a series not found in
+/// the index is written as table `_unknown` with tag `sid=<series_id>`. Schemas
+/// are not available offline, so columns are named as `c<column_id>` and
+/// timestamps are written in the precision of the database.
+pub async fn dump(path: &Path, format: DumpFormat, writer: &mut impl Write) -> Result<()> {
+    let (files, index_dir) = if path.is_dir() {
+        (vnode_data_files(path), path.join(INDEX_PATH))
+    } else {
+        // <vnode_dir>/{tsm,delta}/<file>
+        let vnode_dir = path
+            .parent()
+            .and_then(|p| p.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        (vec![path.to_path_buf()], vnode_dir.join(INDEX_PATH))
+    };
+    let index = if index_dir.is_dir() {
+        Some(TSIndex::new(&index_dir).await?)
+    } else {
+        None
+    };
+    let mut dumper = Dumper {
+        format,
+        index,
+        series_keys: HashMap::new(),
+    };
+
+    if format == DumpFormat::Csv {
+        writeln!(writer, "series,column,time,value")?;
+    }
+    for file in files {
+        dumper.dump_file(file, writer).await?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+struct Dumper {
+    format: DumpFormat,
+    index: Option<Arc<TSIndex>>,
+    /// Series keys found in the index, None if not found.
+    series_keys: HashMap<SeriesId, Option<SeriesKey>>,
+}
+
+impl Dumper {
+    async fn dump_file(&mut self, path: PathBuf, writer: &mut impl Write) -> Result<()> {
+        let reader = TsmReader::open(&path).await?;
+        for idx in reader.index_iterator() {
+            let (column_id, series_id) = split_id(idx.field_id());
+            let series = self.series_prefix(series_id).await?;
+            for blk in idx.block_iterator() {
+                let data_block = reader
+                    .get_data_block(&blk)
+                    .await
+                    .context(error::ReadTsmSnafu)?;
+                for i in 0..data_block.len() {
+                    if let Some(value) = data_block.get(i) {
+                        self.write_value(&series, column_id, value, writer)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the series part of lines: the series key for CSV, and the
+    /// escaped table and tags for line protocol.
+    async fn series_prefix(&mut self, series_id: SeriesId) -> Result<String> {
+        if !self.series_keys.contains_key(&series_id) {
+            let key = match &self.index {
+                Some(index) => index.get_series_key(series_id).await?,
+                None => None,
+            };
+            self.series_keys.insert(series_id, key);
+        }
+        let prefix = match (&self.series_keys[&series_id], self.format) {
+            (Some(key), DumpFormat::Csv) => csv_escape(&key.string()),
+            (Some(key), DumpFormat::LineProtocol) => {
+                let mut buf = lp_escape(key.table(), &[',', ' ']);
+                for tag in key.tags() {
+                    buf.push(',');
+                    buf.push_str(&lp_escape(
+                        &String::from_utf8_lossy(&tag.key),
+                        &[',', '=', ' '],
+                    ));
+                    buf.push('=');
+                    buf.push_str(&lp_escape(
+                        &String::from_utf8_lossy(&tag.value),
+                        &[',', '=', ' '],
+                    ));
+                }
+                buf
+            }
+            (None, DumpFormat::Csv) => csv_escape(&format!("{UNKNOWN_TABLE},sid={series_id}")),
+            (None, DumpFormat::LineProtocol) => format!("{UNKNOWN_TABLE},sid={series_id}"),
+        };
+        Ok(prefix)
+    }
+
+    fn write_value(
+        &self,
+        series: &str,
+        column_id: u32,
+        value: DataType,
+        writer: &mut impl Write,
+    ) -> Result<()> {
+        match self.format {
+            DumpFormat::Csv => {
+                let (ts, val) = match value {
+                    DataType::U64(ts, v) => (ts, v.to_string()),
+                    DataType::I64(ts, v) => (ts, v.to_string()),
+                    DataType::F64(ts, v) => (ts, v.to_string()),
+                    DataType::Bool(ts, v) => (ts, v.to_string()),
+                    DataType::Str(ts, v) => (ts, csv_escape(&String::from_utf8_lossy(&v))),
+                };
+                writeln!(writer, "{series},c{column_id},{ts},{val}")?;
+            }
+            DumpFormat::LineProtocol => {
+                let (ts, val) = match value {
+                    DataType::U64(ts, v) => (ts, format!("{v}u")),
+                    DataType::I64(ts, v) => (ts, format!("{v}i")),
+                    DataType::F64(ts, v) => (ts, v.to_string()),
+                    DataType::Bool(ts, v) => (ts, v.to_string()),
+                    DataType::Str(ts, v) => (
+                        ts,
+                        format!(
+                            "\"{}\"",
+                            lp_escape(&String::from_utf8_lossy(&v), &['"', '\\'])
+                        ),
+                    ),
+                };
+                writeln!(writer, "{series} c{column_id}={val} {ts}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn lp_escape(s: &str, chars: &[char]) -> String {
+    let mut buf = String::with_capacity(s.len());
+    for c in s.chars() {
+        if chars.contains(&c) {
+            buf.push('\\');
+        }
+        buf.push(c);
+    }
+    buf
+}
+
+#[cfg(test)]
+mod test {
+    use super::{csv_escape, lp_escape};
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(csv_escape("ma,ta=1"), "\"ma,ta=1\"");
+        assert_eq!(csv_escape("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(csv_escape("plain"), "plain");
+
+        assert_eq!(lp_escape("a b,c=d", &[',', '=', ' ']), "a\\ b\\,c\\=d");
+        assert_eq!(
+            lp_escape("say \"hi\\\"", &['"', '\\']),
+            "say \\\"hi\\\\\\\""
+        );
+    }
+}
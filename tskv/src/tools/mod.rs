@@ -0,0 +1,38 @@
+//! Offline tools working on files of a stopped node.
+
+use std::path::{Path, PathBuf};
+
+use crate::file_system::file_manager;
+use crate::file_utils;
+use crate::kv_option::{DELTA_PATH, TSM_PATH};
+
+mod compact;
+mod dump;
+mod rebuild_index;
+mod verify;
+
+pub use compact::{compact_vnode, CompactVnodeReport};
+pub use dump::{dump, DumpFormat};
+pub use rebuild_index::{rebuild_index, RebuildIndexReport};
+pub use verify::{verify, FileKind, FileReport, VerifyReport};
+
+/// Returns paths of delta files and TSM files in a vnode directory, ordered by file id.
+fn vnode_data_files(vnode_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for (dir, suffix) in [(DELTA_PATH, ".delta"), (TSM_PATH, ".tsm")] {
+        let dir = vnode_dir.join(dir);
+        let mut dir_files: Vec<(u64, PathBuf)> = file_manager::list_file_names(&dir)
+            .into_iter()
+            .filter(|name| name.ends_with(suffix))
+            .filter_map(|name| {
+                let path = dir.join(name);
+                file_utils::get_tsm_file_id_by_path(&path)
+                    .ok()
+                    .map(|id| (id, path))
+            })
+            .collect();
+        dir_files.sort_by_key(|(id, _)| *id);
+        files.extend(dir_files.into_iter().map(|(_, path)| path));
+    }
+    files
+}
@@ -0,0 +1,258 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use models::utils::split_id;
+use models::{SeriesId, SeriesKey};
+use trace::{info, warn};
+
+use crate::error::Result;
+use crate::file_system::file_manager;
+use crate::file_utils;
+use crate::index::binlog::BinlogReader;
+use crate::index::ts_index::TSIndex;
+use crate::kv_option::INDEX_PATH;
+use crate::tools::vnode_data_files;
+use crate::tsm::TsmReader;
+
+#[derive(Debug)]
+pub struct RebuildIndexReport {
+    /// Number of series in data files.
+    pub series_count: usize,
+    /// Series in data files but without series keys, their data can't be queried.
+    pub missing_series: Vec<SeriesId>,
+    /// Where the replaced index is moved to.
+    pub backup_dir: Option<PathBuf>,
+}
+
+impl Display for RebuildIndexReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Rebuilt index with {} of {} series",
+            self.series_count - self.missing_series.len(),
+            self.series_count
+        )?;
+        if let Some(dir) = &self.backup_dir {
+            write!(f, ", the old index is moved to '{}'", dir.display())?;
+        }
+        write!(f, ".")?;
+        if !self.missing_series.is_empty() {
+            write!(
+                f,
+                "\nSeries keys not found for series ids: {:?}",
+                self.missing_series
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Regenerates the index of the vnode at `vnode_dir` with series in TSM and
+/// delta files of the vnode, keeping their series ids.
+///
+/// Data files only contain series ids, so series keys are read from the old
+/// index, and from its binlog files if the index is broken.
+pub async fn rebuild_index(vnode_dir: &Path) -> Result<RebuildIndexReport> {
+    let mut series_ids = BTreeSet::new();
+    for path in vnode_data_files(vnode_dir) {
+        let reader = TsmReader::open(&path).await?;
+        for idx in reader.index_iterator() {
+            series_ids.insert(split_id(idx.field_id()).1);
+        }
+    }
+
+    let index_dir = vnode_dir.join(INDEX_PATH);
+    let mut series_keys: HashMap<SeriesId, SeriesKey> = HashMap::new();
+    let mut min_incr_id = 0;
+    if index_dir.is_dir() {
+        match TSIndex::new(&index_dir).await {
+            Ok(index) => {
+                for sid in series_ids.iter() {
+                    match index.get_series_key(*sid).await {
+                        Ok(Some(key)) => {
+                            series_keys.insert(*sid, key);
+                        }
+                        Ok(None) => {}
+                        Err(e) => warn!("Failed to get series key of {sid}: {e}"),
+                    }
+                }
+                min_incr_id = index.max_series_id();
+            }
+            Err(e) => warn!("Failed to open index '{}': {e}", index_dir.display()),
+        }
+        if series_keys.len() < series_ids.len() {
+            let (binlog_keys, max_id) = read_binlog_series_keys(&index_dir).await;
+            min_incr_id = min_incr_id.max(max_id);
+            for (sid, key) in binlog_keys {
+                if series_ids.contains(&sid) {
+                    series_keys.entry(sid).or_insert(key);
+                }
+            }
+        }
+    }
+    let missing_series: Vec<SeriesId> = series_ids
+        .iter()
+        .filter(|sid| !series_keys.contains_key(sid))
+        .copied()
+        .collect();
+
+    let new_index_dir = vnode_dir.join(format!("{INDEX_PATH}.rebuild"));
+    if new_index_dir.exists() {
+        std::fs::remove_dir_all(&new_index_dir)?;
+    }
+    let mut series: Vec<(SeriesId, SeriesKey)> = series_keys.into_iter().collect();
+    series.sort_by_key(|(sid, _)| *sid);
+    let index = TSIndex::rebuild(&new_index_dir, series, min_incr_id).await?;
+    info!(
+        "Rebuilt index at '{}', incr_id start at: {}",
+        new_index_dir.display(),
+        index.max_series_id()
+    );
+    drop(index);
+
+    let backup_dir = if index_dir.exists() {
+        let backup_dir = vnode_dir.join(format!(
+            "{INDEX_PATH}.bak.{}",
+            chrono::Local::now().format("%Y%m%d%H%M%S")
+        ));
+        std::fs::rename(&index_dir, &backup_dir)?;
+        Some(backup_dir)
+    } else {
+        None
+    };
+    std::fs::rename(&new_index_dir, &index_dir)?;
+
+    Ok(RebuildIndexReport {
+        series_count: series_ids.len(),
+        missing_series,
+        backup_dir,
+    })
+}
+
+/// Reads series keys from all blocks of binlog files in an index directory,
+/// returns the latest series key of each series id and the max series id.
+async fn read_binlog_series_keys(index_dir: &Path) -> (HashMap<SeriesId, SeriesKey>, SeriesId) {
+    let mut series_keys = HashMap::new();
+    let mut max_id = 0;
+    for filename in file_manager::list_file_names(index_dir) {
+        let file_id = match file_utils::get_index_binlog_file_id(&filename) {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+        let path = index_dir.join(&filename);
+        let file = match file_manager::open_file(&path).await {
+            Ok(f) => f,
+            Err(e) => {
+                warn!("Failed to open index binlog '{}': {e}", path.display());
+                continue;
+            }
+        };
+        let mut reader = match BinlogReader::new(file_id, file.into()).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Failed to open index binlog '{}': {e}", path.display());
+                continue;
+            }
+        };
+        reader.seek_to_first_block();
+        loop {
+            match reader.next_block().await {
+                Ok(Some(block)) => {
+                    max_id = max_id.max(block.series_id);
+                    if block.data_len == 0 {
+                        series_keys.remove(&block.series_id);
+                        continue;
+                    }
+                    match SeriesKey::decode(&block.data) {
+                        Ok(key) => {
+                            series_keys.insert(block.series_id, key);
+                        }
+                        Err(e) => warn!(
+                            "Failed to decode series key of {} in '{}': {e}",
+                            block.series_id,
+                            path.display()
+                        ),
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("Failed to read index binlog '{}': {e}", path.display());
+                    break;
+                }
+            }
+        }
+    }
+    (series_keys, max_id)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use models::utils::unite_id;
+    use models::{SeriesKey, Tag};
+
+    use super::rebuild_index;
+    use crate::compaction::test::write_data_blocks_to_column_file;
+    use crate::index::ts_index::{SeriesLimit, TSIndex};
+    use crate::kv_option::{INDEX_PATH, TSM_PATH};
+    use crate::tsm::codec::DataBlockEncoding;
+    use crate::tsm::DataBlock;
+
+    fn data_block() -> Vec<DataBlock> {
+        vec![DataBlock::I64 {
+            ts: vec![1, 2, 3],
+            val: vec![1, 2, 3],
+            enc: DataBlockEncoding::default(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_index() {
+        let vnode_dir = PathBuf::from("/tmp/test/tools/rebuild_index/1");
+        let _ = std::fs::remove_dir_all(&vnode_dir);
+
+        let series_keys: Vec<SeriesKey> = (1..=3)
+            .map(|i| SeriesKey {
+                id: 0,
+                tags: vec![Tag::new(b"host".to_vec(), format!("h{i}").into_bytes())],
+                table: "ma".to_string(),
+                db: "db".to_string(),
+            })
+            .collect();
+        let index = TSIndex::new(vnode_dir.join(INDEX_PATH)).await.unwrap();
+        let sids = index
+            .add_series_if_not_exists(series_keys.clone(), &SeriesLimit::default())
+            .await
+            .unwrap();
+        index.flush().await.unwrap();
+        let max_sid = index.max_series_id();
+        drop(index);
+
+        // Data files contain the first two series and a series without series key.
+        let missing_sid = max_sid + 10;
+        let data = vec![HashMap::from([
+            (unite_id(1, sids[0]), data_block()),
+            (unite_id(1, sids[1]), data_block()),
+            (unite_id(1, missing_sid), data_block()),
+        ])];
+        write_data_blocks_to_column_file(vnode_dir.join(TSM_PATH), data).await;
+
+        let report = rebuild_index(&vnode_dir).await.unwrap();
+        assert_eq!(report.series_count, 3);
+        assert_eq!(report.missing_series, vec![missing_sid]);
+        assert!(report.backup_dir.as_ref().unwrap().is_dir());
+
+        let index = TSIndex::new(vnode_dir.join(INDEX_PATH)).await.unwrap();
+        for (sid, series_key) in sids.iter().zip(series_keys.iter()).take(2) {
+            let key = index.get_series_key(*sid).await.unwrap().unwrap();
+            assert_eq!(key.to_string(), series_key.to_string());
+        }
+        // Series not in data files are not in the rebuilt index.
+        assert!(index.get_series_key(sids[2]).await.unwrap().is_none());
+        // Series ids allocated later don't reuse ids of the old index.
+        assert!(index.max_series_id() >= max_sid);
+    }
+}
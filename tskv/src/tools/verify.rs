@@ -0,0 +1,235 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::error::{Error, Result};
+use crate::summary::VersionEdit;
+use crate::tsm::{TsmReader, TOMBSTONE_ENTRY_LEN};
+use crate::{file_utils, record_file};
+
+/// Kinds of files checked by `verify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Tsm,
+    Delta,
+    Tombstone,
+    Wal,
+    Summary,
+}
+
+impl FileKind {
+    fn of(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        if file_utils::check_summary_file_name(file_name) {
+            return Some(Self::Summary);
+        }
+        match path.extension()?.to_str()? {
+            "tsm" => Some(Self::Tsm),
+            "delta" => Some(Self::Delta),
+            "tombstone" => Some(Self::Tombstone),
+            "wal" => Some(Self::Wal),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FileKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tsm => write!(f, "tsm"),
+            Self::Delta => write!(f, "delta"),
+            Self::Tombstone => write!(f, "tombstone"),
+            Self::Wal => write!(f, "wal"),
+            Self::Summary => write!(f, "summary"),
+        }
+    }
+}
+
+/// Result of checking a file, the file is corrupted if `errors` is not empty.
+#[derive(Debug)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub kind: FileKind,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub files: Vec<FileReport>,
+}
+
+impl VerifyReport {
+    pub fn corrupted_files(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|f| !f.errors.is_empty())
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.corrupted_files().next().is_none()
+    }
+}
+
+impl Display for VerifyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for file in self.files.iter() {
+            if file.errors.is_empty() {
+                writeln!(f, "OK      {:<9} {}", file.kind, file.path.display())?;
+            } else {
+                writeln!(f, "CORRUPT {:<9} {}", file.kind, file.path.display())?;
+                for e in file.errors.iter() {
+                    writeln!(f, "    {e}")?;
+                }
+            }
+        }
+        write!(
+            f,
+            "Checked {} files, {} corrupted.",
+            self.files.len(),
+            self.corrupted_files().count()
+        )
+    }
+}
+
+/// Checks all TSM, delta, tombstone, WAL and summary files in `paths`,
+/// directories are walked recursively.
+pub async fn verify(paths: &[PathBuf]) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    for path in paths {
+        for entry in WalkDir::new(path).sort_by_file_name() {
+            let entry = entry.map_err(|e| Error::IO { source: e.into() })?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.into_path();
+            let kind = match FileKind::of(&path) {
+                Some(k) => k,
+                None => continue,
+            };
+            let errors = match kind {
+                FileKind::Tsm | FileKind::Delta => verify_tsm_file(&path).await,
+                FileKind::Tombstone | FileKind::Wal | FileKind::Summary => {
+                    verify_record_file(&path, kind).await
+                }
+            };
+            report.files.push(FileReport { path, kind, errors });
+        }
+    }
+    Ok(report)
+}
+
+/// Reads and decodes all data blocks of a TSM or delta file.
+async fn verify_tsm_file(path: &Path) -> Vec<String> {
+    let reader = match TsmReader::open(path).await {
+        Ok(r) => r,
+        Err(e) => return vec![format!("failed to open file: {e}")],
+    };
+    let mut errors = Vec::new();
+    for idx in reader.index_iterator() {
+        for blk in idx.block_iterator() {
+            match reader.get_data_block_opt(&blk, false).await {
+                Ok(data_block) => {
+                    // Tombstones are applied to the returned block.
+                    if !reader.has_tombstone() && data_block.len() != blk.count() as usize {
+                        errors.push(format!(
+                            "block of field_id {} at {}: expected {} values, found {}",
+                            blk.field_id(),
+                            blk.offset(),
+                            blk.count(),
+                            data_block.len()
+                        ));
+                    }
+                }
+                Err(e) => errors.push(format!(
+                    "block of field_id {} at {}: {e}",
+                    blk.field_id(),
+                    blk.offset()
+                )),
+            }
+        }
+    }
+    errors
+}
+
+/// Reads all records of a tombstone, WAL or summary file.
+async fn verify_record_file(path: &Path, kind: FileKind) -> Vec<String> {
+    let mut reader = match record_file::Reader::open(path).await {
+        Ok(r) => r,
+        Err(e) => return vec![format!("failed to open file: {e}")],
+    };
+    let mut errors = Vec::new();
+    loop {
+        match reader.read_record().await {
+            Ok(record) => match kind {
+                FileKind::Tombstone if record.data.len() < TOMBSTONE_ENTRY_LEN => {
+                    errors.push(format!(
+                        "record at {}: tombstone entry length {} < {}",
+                        record.pos,
+                        record.data.len(),
+                        TOMBSTONE_ENTRY_LEN
+                    ));
+                }
+                FileKind::Summary => {
+                    if let Err(e) = VersionEdit::decode(&record.data) {
+                        errors.push(format!("record at {}: {e}", record.pos));
+                    }
+                }
+                _ => {}
+            },
+            Err(Error::Eof) => break,
+            Err(e @ Error::RecordFileHashCheckFailed { .. }) => errors.push(e.to_string()),
+            Err(e @ Error::RecordFileInvalidDataSize { .. }) => {
+                // Records after it are not reachable.
+                errors.push(e.to_string());
+                break;
+            }
+            Err(e) => {
+                errors.push(e.to_string());
+                break;
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::{verify, FileKind};
+    use crate::compaction::test::write_data_blocks_to_column_file;
+    use crate::tsm::codec::DataBlockEncoding;
+    use crate::tsm::{DataBlock, TsmReader};
+
+    #[tokio::test]
+    async fn test_verify_tsm_file() {
+        let dir = PathBuf::from("/tmp/test/tools/verify");
+        let _ = std::fs::remove_dir_all(&dir);
+        #[rustfmt::skip]
+        let data = vec![HashMap::from([
+            (1, vec![DataBlock::I64 { ts: vec![1, 2, 3], val: vec![1, 2, 3], enc: DataBlockEncoding::default() }]),
+        ])];
+        let (_, files) = write_data_blocks_to_column_file(&dir, data).await;
+        let path = files[0].file_path().clone();
+
+        let report = verify(&[dir.clone()]).await.unwrap();
+        assert!(report.is_ok(), "{report}");
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].kind, FileKind::Tsm);
+
+        // Corrupt a byte in the data block.
+        let offset = {
+            let reader = TsmReader::open(&path).await.unwrap();
+            let idx = reader.index_iterator().next().unwrap();
+            let blk = idx.block_iterator().next().unwrap();
+            blk.offset() as usize
+        };
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[offset + 8] ^= 0xff;
+        std::fs::write(&path, bytes).unwrap();
+
+        let report = verify(&[dir]).await.unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.corrupted_files().count(), 1);
+    }
+}
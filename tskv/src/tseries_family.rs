@@ -21,13 +21,14 @@ use tokio_util::sync::CancellationToken;
 use trace::{debug, error, info, warn};
 use utils::BloomFilter;
 
+use crate::cold_storage::ColdStorage;
 use crate::compaction::{CompactTask, FlushReq};
 use crate::error::Result;
 use crate::file_utils::{make_delta_file_name, make_tsm_file_name};
 use crate::kv_option::{CacheOptions, StorageOptions};
-use crate::memcache::{DataType, FieldVal, MemCache, RowGroup};
+use crate::memcache::{DataType, FieldVal, LastValueCache, MemCache, RowGroup};
 use crate::summary::{CompactMeta, VersionEdit};
-use crate::tsm::{DataBlock, TsmReader, TsmTombstone};
+use crate::tsm::{DataBlock, DataBlockCache, TsmReader, TsmTombstone};
 use crate::Error::CommonError;
 use crate::{ColumnFileId, LevelId, TseriesFamilyId};
 
@@ -44,6 +45,10 @@ pub struct ColumnFile {
 
     path: PathBuf,
     tsm_reader_cache: Weak<ShardedCache<String, Arc<TsmReader>>>,
+    /// Path in the cold storage if the file was moved to it.
+    remote_path: Option<String>,
+    cold_storage: Option<Arc<ColdStorage>>,
+    data_block_cache: Option<Arc<DataBlockCache>>,
 }
 
 impl ColumnFile {
@@ -52,6 +57,8 @@ impl ColumnFile {
         path: impl AsRef<Path>,
         field_id_filter: Arc<BloomFilter>,
         tsm_reader_cache: Weak<ShardedCache<String, Arc<TsmReader>>>,
+        cold_storage: Option<Arc<ColdStorage>>,
+        data_block_cache: Option<Arc<DataBlockCache>>,
     ) -> Self {
         Self {
             file_id: meta.file_id,
@@ -64,6 +71,9 @@ impl ColumnFile {
             compacting: AtomicBool::new(false),
             path: path.as_ref().into(),
             tsm_reader_cache,
+            remote_path: meta.remote_path.clone(),
+            cold_storage,
+            data_block_cache,
         }
     }
 
@@ -91,6 +101,45 @@ impl ColumnFile {
         &self.path
     }
 
+    pub fn is_remote(&self) -> bool {
+        self.remote_path.is_some()
+    }
+
+    pub fn remote_path(&self) -> Option<&str> {
+        self.remote_path.as_deref()
+    }
+
+    pub fn field_id_filter(&self) -> Arc<BloomFilter> {
+        self.field_id_filter.clone()
+    }
+
+    /// Key of the `TsmReader` of this file in the tsm reader cache.
+    fn tsm_reader_cache_key(&self) -> String {
+        match &self.remote_path {
+            Some(p) => p.clone(),
+            None => format!("{}", self.path.display()),
+        }
+    }
+
+    pub async fn open_tsm_reader(&self) -> Result<TsmReader> {
+        let tsm_reader = match (&self.remote_path, &self.cold_storage) {
+            (Some(remote_path), Some(cold_storage)) => {
+                let file = cold_storage.open_file(remote_path).await?;
+                TsmReader::open_remote(&self.path, file).await?
+            }
+            (Some(remote_path), None) => {
+                return Err(CommonError {
+                    reason: format!(
+                        "file {} is in cold storage at '{}', but cold storage is not configured",
+                        self.file_id, remote_path
+                    ),
+                })
+            }
+            (None, _) => TsmReader::open(&self.path).await?,
+        };
+        Ok(tsm_reader.with_data_block_cache(self.data_block_cache.clone()))
+    }
+
     pub fn overlap(&self, time_range: &TimeRange) -> bool {
         self.time_range.overlaps(time_range)
     }
@@ -114,6 +163,10 @@ impl ColumnFile {
         let mut tombstone = TsmTombstone::open(dir, self.file_id).await?;
         tombstone.add_range(field_ids, time_range).await?;
         tombstone.flush().await?;
+        // Cached TsmReader holds the old tombstone, remove it to reload.
+        if let Some(cache) = self.tsm_reader_cache.upgrade() {
+            cache.remove(&self.tsm_reader_cache_key()).await;
+        }
         Ok(())
     }
 }
@@ -148,11 +201,29 @@ impl Drop for ColumnFile {
         if self.is_deleted() {
             let path = self.file_path();
             if let Some(cache) = self.tsm_reader_cache.upgrade() {
-                let k = format!("{}", path.display());
+                let k = self.tsm_reader_cache_key();
                 tokio::spawn(async move {
                     cache.remove(&k).await;
                 });
             }
+            if let Some(cache) = self.data_block_cache.as_ref() {
+                cache.remove_file(self.file_id);
+            }
+
+            if let Some(remote_path) = self.remote_path.clone() {
+                if let Some(cold_storage) = self.cold_storage.clone() {
+                    let file_id = self.file_id;
+                    tokio::spawn(async move {
+                        match cold_storage.delete(&remote_path).await {
+                            Ok(()) => info!("Removed file {file_id} at '{remote_path}'"),
+                            Err(e) => {
+                                error!("Error when removing file {file_id} at '{remote_path}': {e}")
+                            }
+                        }
+                    });
+                }
+                return;
+            }
 
             if let Err(e) = std::fs::remove_file(path) {
                 error!(
@@ -189,6 +260,9 @@ impl ColumnFile {
             compacting: AtomicBool::new(false),
             path: path.as_ref().into(),
             tsm_reader_cache: Weak::new(),
+            remote_path: None,
+            cold_storage: None,
+            data_block_cache: None,
         }
     }
 
@@ -253,6 +327,8 @@ impl LevelInfo {
         compact_meta: &CompactMeta,
         field_filter: Arc<BloomFilter>,
         tsm_reader_cache: Weak<ShardedCache<String, Arc<TsmReader>>>,
+        cold_storage: Option<Arc<ColdStorage>>,
+        data_block_cache: Option<Arc<DataBlockCache>>,
     ) {
         let file_path = if compact_meta.is_delta {
             let base_dir = self.storage_opt.delta_dir(&self.database, self.tsf_id);
@@ -266,6 +342,8 @@ impl LevelInfo {
             file_path,
             field_filter,
             tsm_reader_cache,
+            cold_storage,
+            data_block_cache,
         )));
         self.tsf_id = compact_meta.tsf_id;
         self.cur_size += compact_meta.file_size;
@@ -312,7 +390,7 @@ impl LevelInfo {
                 continue;
             }
 
-            let tsm_reader = match TsmReader::open(file.file_path()).await {
+            let tsm_reader = match file.open_tsm_reader().await {
                 Ok(tr) => tr,
                 Err(e) => {
                     error!("failed to load tsm reader, in case {:?}", e);
@@ -370,6 +448,8 @@ pub struct Version {
     pub max_level_ts: i64,
     pub levels_info: [LevelInfo; 5],
     pub tsm_reader_cache: Arc<ShardedCache<String, Arc<TsmReader>>>,
+    pub cold_storage: Option<Arc<ColdStorage>>,
+    pub data_block_cache: Option<Arc<DataBlockCache>>,
 }
 
 impl Version {
@@ -382,6 +462,8 @@ impl Version {
         levels_info: [LevelInfo; 5],
         max_level_ts: i64,
         tsm_reader_cache: Arc<ShardedCache<String, Arc<TsmReader>>>,
+        cold_storage: Option<Arc<ColdStorage>>,
+        data_block_cache: Option<Arc<DataBlockCache>>,
     ) -> Self {
         Self {
             ts_family_id,
@@ -391,6 +473,8 @@ impl Version {
             max_level_ts,
             levels_info,
             tsm_reader_cache,
+            cold_storage,
+            data_block_cache,
         }
     }
 
@@ -431,11 +515,18 @@ impl Version {
                 new_levels[level.level as usize].push_column_file(file.clone());
             }
             for file in added_files[level.level as usize].iter() {
+                // Snapshots of the vnode (e.g. written when the summary file is rolled)
+                // contain files already in the version.
+                if level.files.iter().any(|f| f.file_id == file.file_id) {
+                    continue;
+                }
                 let field_filter = file_metas.remove(&file.file_id).unwrap_or_default();
                 new_levels[level.level as usize].push_compact_meta(
                     file,
                     field_filter,
                     weak_tsm_reader_cache.clone(),
+                    self.cold_storage.clone(),
+                    self.data_block_cache.clone(),
                 );
             }
             new_levels[level.level as usize].update_time_range();
@@ -449,6 +540,8 @@ impl Version {
             max_level_ts: self.max_level_ts,
             levels_info: new_levels,
             tsm_reader_cache: self.tsm_reader_cache.clone(),
+            cold_storage: self.cold_storage.clone(),
+            data_block_cache: self.data_block_cache.clone(),
         };
         new_version.update_max_level_ts();
         new_version
@@ -509,17 +602,17 @@ impl Version {
         vec![]
     }
 
-    pub async fn get_tsm_reader(&self, path: impl AsRef<Path>) -> Result<Arc<TsmReader>> {
-        let path = path.as_ref().display().to_string();
-        let tsm_reader = match self.tsm_reader_cache.get(&path).await {
+    pub async fn get_tsm_reader(&self, column_file: &ColumnFile) -> Result<Arc<TsmReader>> {
+        let key = column_file.tsm_reader_cache_key();
+        let tsm_reader = match self.tsm_reader_cache.get(&key).await {
             Some(val) => val.clone(),
             None => {
-                let mut lock = self.tsm_reader_cache.lock_shard(&path).await;
-                match lock.get(&path) {
+                let mut lock = self.tsm_reader_cache.lock_shard(&key).await;
+                match lock.get(&key) {
                     Some(val) => val.clone(),
                     None => {
-                        let tsm_reader = TsmReader::open(&path).await?;
-                        lock.insert(path, Arc::new(tsm_reader)).unwrap().clone()
+                        let tsm_reader = column_file.open_tsm_reader().await?;
+                        lock.insert(key, Arc::new(tsm_reader)).unwrap().clone()
                     }
                 }
             }
@@ -600,6 +693,7 @@ pub struct SuperVersion {
     pub ts_family_id: u32,
     pub storage_opt: Arc<StorageOptions>,
     pub caches: CacheGroup,
+    pub last_cache: Arc<LastValueCache>,
     pub version: Arc<Version>,
     pub version_number: u64,
 }
@@ -609,6 +703,7 @@ impl SuperVersion {
         ts_family_id: u32,
         storage_opt: Arc<StorageOptions>,
         caches: CacheGroup,
+        last_cache: Arc<LastValueCache>,
         version: Arc<Version>,
         version_number: u64,
     ) -> Self {
@@ -616,6 +711,7 @@ impl SuperVersion {
             ts_family_id,
             storage_opt,
             caches,
+            last_cache,
             version,
             version_number,
         }
@@ -682,6 +778,7 @@ pub struct TseriesFamily {
     database: Arc<String>,
     mut_cache: Arc<RwLock<MemCache>>,
     immut_cache: Vec<Arc<RwLock<MemCache>>>,
+    last_cache: Arc<LastValueCache>,
     super_version: Arc<SuperVersion>,
     super_version_id: AtomicU64,
     version: Arc<Version>,
@@ -713,6 +810,7 @@ impl TseriesFamily {
         register: &Arc<MetricsRegister>,
     ) -> Self {
         let mm = Arc::new(RwLock::new(cache));
+        let last_cache = Arc::new(LastValueCache::new(cache_opt.last_value_cache_max_series));
 
         Self {
             tf_id,
@@ -720,6 +818,7 @@ impl TseriesFamily {
             seq_no: version.last_seq,
             mut_cache: mm.clone(),
             immut_cache: Default::default(),
+            last_cache: last_cache.clone(),
             super_version: Arc::new(SuperVersion::new(
                 tf_id,
                 storage_opt.clone(),
@@ -727,6 +826,7 @@ impl TseriesFamily {
                     mut_cache: mm,
                     immut_cache: Default::default(),
                 },
+                last_cache,
                 version.clone(),
                 0,
             )),
@@ -755,6 +855,7 @@ impl TseriesFamily {
                 mut_cache: self.mut_cache.clone(),
                 immut_cache: self.immut_cache.clone(),
             },
+            self.last_cache.clone(),
             version,
             self.super_version_id.load(Ordering::SeqCst),
         ))
@@ -862,7 +963,13 @@ impl TseriesFamily {
         for ((sid, _schema_id), group) in points {
             let mem = self.mut_cache.read();
             res += group.rows.len();
+            let last_values = if self.last_cache.is_enabled(&group.schema) {
+                LastValueCache::collect_row_group(&group)
+            } else {
+                vec![]
+            };
             mem.write_group(sid, seq, group)?;
+            self.last_cache.update(sid, last_values);
         }
         Ok(res as u64)
     }
@@ -893,6 +1000,13 @@ impl TseriesFamily {
         for memcache in self.immut_cache.iter() {
             memcache.read().delete_columns(field_ids);
         }
+        self.last_cache.delete_columns(field_ids);
+    }
+
+    /// Invalidates cached latest values of the fields, for values of them
+    /// written into column files directly, not through the caches.
+    pub fn invalidate_last_values(&self, field_ids: &[FieldId]) {
+        self.last_cache.delete_columns(field_ids);
     }
 
     pub fn change_column(&self, sids: &[SeriesId], column_name: &str, new_column: &TableColumn) {
@@ -902,6 +1016,7 @@ impl TseriesFamily {
         for memcache in self.immut_cache.iter() {
             memcache.read().change_column(sids, column_name, new_column);
         }
+        self.last_cache.delete_column(sids, new_column.id);
     }
 
     pub fn add_column(&self, sids: &[SeriesId], new_column: &TableColumn) {
@@ -916,6 +1031,7 @@ impl TseriesFamily {
         for memcache in self.immut_cache.iter() {
             memcache.read().delete_series(sids, time_range);
         }
+        self.last_cache.delete_series(sids);
     }
 
     pub fn schedule_compaction(&self, runtime: Arc<Runtime>) {
@@ -1135,7 +1251,7 @@ pub mod test_tseries_family {
         ];
         let tsm_reader_cache = Arc::new(ShardedCache::with_capacity(16));
         #[rustfmt::skip]
-            let version = Version::new(1, database, opt.storage.clone(), 1, levels, 3100, tsm_reader_cache);
+            let version = Version::new(1, database, opt.storage.clone(), 1, levels, 3100, tsm_reader_cache, None, None);
         let mut version_edits = Vec::new();
         let mut ve = VersionEdit::new(1);
         #[rustfmt::skip]
@@ -1231,7 +1347,7 @@ pub mod test_tseries_family {
         ];
         let tsm_reader_cache = Arc::new(ShardedCache::with_capacity(16));
         #[rustfmt::skip]
-            let version = Version::new(1, database, opt.storage.clone(), 1, levels, 3150, tsm_reader_cache);
+            let version = Version::new(1, database, opt.storage.clone(), 1, levels, 3150, tsm_reader_cache, None, None);
 
         let mut version_edits = Vec::new();
         let mut ve = VersionEdit::new(1);
@@ -1323,6 +1439,8 @@ pub mod test_tseries_family {
             levels,
             max_level_ts,
             tsm_reader_cache,
+            None,
+            None,
         )
     }
 
@@ -1350,6 +1468,8 @@ pub mod test_tseries_family {
                 LevelInfo::init_levels(database, 0, opt.storage.clone()),
                 0,
                 Arc::new(ShardedCache::with_capacity(1)),
+                None,
+                None,
             )),
             opt.cache.clone(),
             opt.storage.clone(),
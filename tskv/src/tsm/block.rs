@@ -2,16 +2,19 @@ use std::cmp::min;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 
+use datafusion::scalar::ScalarValue;
 use minivec::MiniVec;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::{PhysicalDType as ValueType, Timestamp};
 use trace::error;
 
+use crate::byte_utils::{decode_be_u32, decode_be_u64};
 use crate::memcache::DataType;
 use crate::tsm::codec::{
     get_bool_codec, get_encoding, get_f64_codec, get_i64_codec, get_str_codec, get_ts_codec,
     get_u64_codec, DataBlockEncoding,
 };
+use crate::tsm::BLOCK_STATS_SIZE;
 
 pub trait ByTimeRange {
     fn time_range(&self) -> Option<TimeRange>;
@@ -260,6 +263,18 @@ impl DataBlock {
         }
     }
 
+    /// Returns the size of timestamps and values of this `DataBlock` before encoding.
+    pub fn raw_size(&self) -> usize {
+        let val_size = match &self {
+            Self::U64 { val, .. } => val.len() * 8,
+            Self::I64 { val, .. } => val.len() * 8,
+            Self::F64 { val, .. } => val.len() * 8,
+            Self::Str { val, .. } => val.iter().map(|v| v.len()).sum(),
+            Self::Bool { val, .. } => val.len(),
+        };
+        self.len() * 8 + val_size
+    }
+
     /// Returns the `ValueType` by this `DataBlock` variant.
     pub fn field_type(&self) -> ValueType {
         match &self {
@@ -381,6 +396,33 @@ impl DataBlock {
         }
     }
 
+    /// Converts values of this `DataBlock` to `field_type` for a field column
+    /// whose data type is changed, returns an error if values of the type can't
+    /// be converted to `field_type`, so no value is dropped.
+    /// Encodings of the returned `DataBlock` are reset to default.
+    pub fn convert_to(&self, field_type: ValueType) -> crate::Result<Self> {
+        let from = self.field_type();
+        if from == field_type {
+            return Ok(self.clone());
+        }
+        let convert_err = || crate::Error::ConvertValueType {
+            from,
+            to: field_type,
+        };
+        if !from.can_convert_to(field_type) {
+            return Err(convert_err());
+        }
+        let mut block = Self::new(self.len(), field_type);
+        for i in 0..self.len() {
+            let v = self
+                .get(i)
+                .and_then(|v| v.convert_to(field_type))
+                .ok_or_else(convert_err)?;
+            block.insert(v);
+        }
+        Ok(block)
+    }
+
     pub fn merge(&self, other: Self) -> Self {
         let (mut i, mut j) = (0_usize, 0_usize);
         let len_1 = self.len();
@@ -954,6 +996,7 @@ pub struct EncodedDataBlock {
     pub count: u32,
     pub field_type: ValueType,
     pub time_range: Option<TimeRange>,
+    pub stats: BlockStatistics,
 }
 
 impl Display for EncodedDataBlock {
@@ -984,6 +1027,7 @@ impl EncodedDataBlock {
             count: (end - start) as u32,
             field_type: data_block.field_type(),
             time_range: Some(TimeRange::new(min_ts, max_ts)),
+            stats: BlockStatistics::new(data_block, start, end),
         })
     }
 
@@ -992,11 +1036,185 @@ impl EncodedDataBlock {
     }
 }
 
+const STATS_FLAG_MIN_MAX: u8 = 1;
+const STATS_FLAG_SUM: u8 = 1 << 1;
+
+/// Statistics of values in a data block, stored in the TSM index since
+/// `TSM_VERSION_V2`. Values are stored as raw 64-bit patterns and interpreted
+/// by the field type of the block.
+///
+/// ```text
+/// +------------+---------+
+/// | flags      | 1 bytes |
+/// | min        | 8 bytes |
+/// | max        | 8 bytes |
+/// | sum        | 8 bytes |
+/// | null_count | 4 bytes |
+/// +------------+---------+
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlockStatistics {
+    flags: u8,
+    min: u64,
+    max: u64,
+    sum: u64,
+    null_count: u32,
+}
+
+impl BlockStatistics {
+    /// Computes statistics of values in `data_block[start..end]`.
+    ///
+    /// Min and max are not recorded for string blocks and float blocks
+    /// containing NaN, sum is not recorded if it overflows.
+    pub fn new(data_block: &DataBlock, start: usize, end: usize) -> Self {
+        let mut stats = Self::default();
+        if start >= end {
+            return stats;
+        }
+        match data_block {
+            DataBlock::U64 { val, .. } => {
+                let val = &val[start..end];
+                let (min, max) = min_max(val, |a, b| a < b);
+                stats.set_min_max(min, max);
+                if let Some(sum) = val.iter().try_fold(0_u64, |acc, v| acc.checked_add(*v)) {
+                    stats.set_sum(sum);
+                }
+            }
+            DataBlock::I64 { val, .. } => {
+                let val = &val[start..end];
+                let (min, max) = min_max(val, |a, b| a < b);
+                stats.set_min_max(min as u64, max as u64);
+                if let Some(sum) = val.iter().try_fold(0_i64, |acc, v| acc.checked_add(*v)) {
+                    stats.set_sum(sum as u64);
+                }
+            }
+            DataBlock::F64 { val, .. } => {
+                let val = &val[start..end];
+                if !val.iter().any(|v| v.is_nan()) {
+                    let (min, max) = min_max(val, |a, b| a < b);
+                    stats.set_min_max(min.to_bits(), max.to_bits());
+                }
+                let sum: f64 = val.iter().sum();
+                if sum.is_finite() {
+                    stats.set_sum(sum.to_bits());
+                }
+            }
+            DataBlock::Bool { val, .. } => {
+                let val = &val[start..end];
+                let (min, max) = min_max(val, |a, b| !*a && *b);
+                stats.set_min_max(min as u64, max as u64);
+            }
+            DataBlock::Str { .. } => {}
+        }
+        stats
+    }
+
+    fn set_min_max(&mut self, min: u64, max: u64) {
+        self.flags |= STATS_FLAG_MIN_MAX;
+        self.min = min;
+        self.max = max;
+    }
+
+    fn set_sum(&mut self, sum: u64) {
+        self.flags |= STATS_FLAG_SUM;
+        self.sum = sum;
+    }
+
+    pub fn has_min_max(&self) -> bool {
+        self.flags & STATS_FLAG_MIN_MAX != 0
+    }
+
+    pub fn has_sum(&self) -> bool {
+        self.flags & STATS_FLAG_SUM != 0
+    }
+
+    /// Count of null values, always 0 for dense TSM blocks.
+    pub fn null_count(&self) -> u32 {
+        self.null_count
+    }
+
+    pub fn min(&self, field_type: ValueType) -> Option<ScalarValue> {
+        if !self.has_min_max() {
+            return None;
+        }
+        bits_to_scalar(self.min, field_type)
+    }
+
+    pub fn max(&self, field_type: ValueType) -> Option<ScalarValue> {
+        if !self.has_min_max() {
+            return None;
+        }
+        bits_to_scalar(self.max, field_type)
+    }
+
+    pub fn sum(&self, field_type: ValueType) -> Option<ScalarValue> {
+        if !self.has_sum() || field_type == ValueType::Boolean {
+            return None;
+        }
+        bits_to_scalar(self.sum, field_type)
+    }
+
+    pub fn encode(&self, buf: &mut [u8]) {
+        assert!(buf.len() >= BLOCK_STATS_SIZE);
+        buf[0] = self.flags;
+        buf[1..9].copy_from_slice(&self.min.to_be_bytes()[..]);
+        buf[9..17].copy_from_slice(&self.max.to_be_bytes()[..]);
+        buf[17..25].copy_from_slice(&self.sum.to_be_bytes()[..]);
+        buf[25..29].copy_from_slice(&self.null_count.to_be_bytes()[..]);
+    }
+
+    pub fn decode(data: &[u8]) -> Self {
+        assert!(data.len() >= BLOCK_STATS_SIZE);
+        Self {
+            flags: data[0],
+            min: decode_be_u64(&data[1..9]),
+            max: decode_be_u64(&data[9..17]),
+            sum: decode_be_u64(&data[17..25]),
+            null_count: decode_be_u32(&data[25..29]),
+        }
+    }
+}
+
+impl Display for BlockStatistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "flags: {}, min: {}, max: {}, sum: {}, null_count: {}",
+            self.flags, self.min, self.max, self.sum, self.null_count
+        )
+    }
+}
+
+fn min_max<T: Copy>(val: &[T], less: impl Fn(&T, &T) -> bool) -> (T, T) {
+    let mut min = val[0];
+    let mut max = val[0];
+    for v in val.iter().skip(1) {
+        if less(v, &min) {
+            min = *v;
+        }
+        if less(&max, v) {
+            max = *v;
+        }
+    }
+    (min, max)
+}
+
+fn bits_to_scalar(bits: u64, field_type: ValueType) -> Option<ScalarValue> {
+    match field_type {
+        ValueType::Unsigned => Some(ScalarValue::UInt64(Some(bits))),
+        ValueType::Integer => Some(ScalarValue::Int64(Some(bits as i64))),
+        ValueType::Float => Some(ScalarValue::Float64(Some(f64::from_bits(bits)))),
+        ValueType::Boolean => Some(ScalarValue::Boolean(Some(bits != 0))),
+        ValueType::String | ValueType::Unknown => None,
+    }
+}
+
 #[cfg(test)]
 pub mod test {
 
     use minivec::mini_vec;
     use models::predicate::domain::TimeRange;
+    use models::PhysicalDType as ValueType;
 
     use crate::memcache::DataType;
     use crate::tsm::codec::DataBlockEncoding;
@@ -1027,6 +1245,21 @@ pub mod test {
         ]);
     }
 
+    #[test]
+    fn test_data_block_convert_to() {
+        #[rustfmt::skip]
+        let blk = DataBlock::I64 { ts: vec![1, 2, 3], val: vec![-1, 0, 1], enc: DataBlockEncoding::default() };
+
+        #[rustfmt::skip]
+        assert_eq!(blk.convert_to(ValueType::Float).unwrap(), DataBlock::F64 { ts: vec![1, 2, 3], val: vec![-1.0, 0.0, 1.0], enc: DataBlockEncoding::default() });
+        #[rustfmt::skip]
+        assert_eq!(blk.convert_to(ValueType::Unsigned).unwrap(), DataBlock::U64 { ts: vec![1, 2, 3], val: vec![0, 0, 1], enc: DataBlockEncoding::default() });
+        #[rustfmt::skip]
+        assert_eq!(blk.convert_to(ValueType::String).unwrap(), DataBlock::Str { ts: vec![1, 2, 3], val: vec![mini_vec![b'-', b'1'], mini_vec![b'0'], mini_vec![b'1']], enc: DataBlockEncoding::default() });
+        assert_eq!(blk.convert_to(ValueType::Integer).unwrap(), blk);
+        assert!(blk.convert_to(ValueType::Boolean).is_err());
+    }
+
     #[test]
     fn test_data_block_exclude_1() {
         #[rustfmt::skip]
@@ -0,0 +1,111 @@
+/// Writes bits from the most significant bit of each byte.
+#[derive(Default)]
+pub struct BitWriter {
+    buf: Vec<u8>,
+    /// Number of bits written.
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(bytes),
+            len: 0,
+        }
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len & 7 == 0 {
+            self.buf.push(0);
+        }
+        if bit {
+            let last = self.buf.len() - 1;
+            self.buf[last] |= 128 >> (self.len & 7);
+        }
+        self.len += 1;
+    }
+
+    /// Write the lowest `n` bits of `value`, `n` should not be greater than 64.
+    pub fn write_bits(&mut self, value: u64, n: u32) {
+        let mut n = n;
+        while n > 0 {
+            let used = (self.len & 7) as u32;
+            if used == 0 {
+                self.buf.push(0);
+            }
+            let available = 8 - used;
+            let size = available.min(n);
+            let bits = ((value >> (n - size)) & ((1_u64 << size) - 1)) as u8;
+            let last = self.buf.len() - 1;
+            self.buf[last] |= bits << (available - size);
+            self.len += size as usize;
+            n -= size;
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+pub struct BitReader<'a> {
+    buf: &'a [u8],
+    /// Number of bits read.
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    pub fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.buf.get(self.pos >> 3)?;
+        let bit = byte & (128 >> (self.pos & 7)) != 0;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    /// Read `n` bits as the lowest bits of the returned value, `n` should not be
+    /// greater than 64.
+    pub fn read_bits(&mut self, n: u32) -> Option<u64> {
+        let mut n = n;
+        let mut value = 0_u64;
+        while n > 0 {
+            let byte = *self.buf.get(self.pos >> 3)? as u64;
+            let used = (self.pos & 7) as u32;
+            let available = 8 - used;
+            let size = available.min(n);
+            let bits = (byte >> (available - size)) & ((1_u64 << size) - 1);
+            value = (value << size) | bits;
+            self.pos += size as usize;
+            n -= size;
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BitReader, BitWriter};
+
+    #[test]
+    fn test_bit_writer_reader() {
+        let mut writer = BitWriter::default();
+        writer.write_bit(true);
+        writer.write_bits(0b101, 3);
+        writer.write_bits(u64::MAX, 64);
+        writer.write_bit(false);
+        writer.write_bits(0x1234, 13);
+        let buf = writer.finish();
+        assert_eq!(buf.len(), 11);
+
+        let mut reader = BitReader::new(&buf);
+        assert_eq!(reader.read_bit(), Some(true));
+        assert_eq!(reader.read_bits(3), Some(0b101));
+        assert_eq!(reader.read_bits(64), Some(u64::MAX));
+        assert_eq!(reader.read_bit(), Some(false));
+        assert_eq!(reader.read_bits(13), Some(0x1234));
+        assert_eq!(reader.read_bits(8), None);
+    }
+}
@@ -2,8 +2,10 @@ use std::error::Error;
 
 use q_compress::{auto_compress, auto_decompress, DEFAULT_COMPRESSION_LEVEL};
 
-use crate::byte_utils::decode_be_f64;
+use crate::byte_utils::{decode_be_f64, decode_be_u32};
+use crate::tsm::codec::bits::{BitReader, BitWriter};
 use crate::tsm::codec::Encoding;
+use crate::tsm::MAX_BLOCK_VALUES;
 
 // note: encode/decode adapted from influxdb_iox
 // https://github.com/influxdata/influxdb_iox/tree/main/influxdb_tsm/src/encoders
@@ -397,6 +399,133 @@ pub fn f64_without_compress_decode(
     Ok(())
 }
 
+/// Leading zeros of a XOR are rounded down to one of these values in the Chimp
+/// encoding, so that they can be stored in 3 bits.
+const CHIMP_LEADING_ZEROS: [u32; 8] = [0, 8, 12, 16, 18, 20, 22, 24];
+
+/// Chimp uses the center bits of a XOR if it has more trailing zeros than this.
+const CHIMP_TRAILING_ZEROS_THRESHOLD: u32 = 6;
+
+fn chimp_leading_zeros_index(leading_zeros: u32) -> u32 {
+    match leading_zeros {
+        0..=7 => 0,
+        8..=11 => 1,
+        12..=15 => 2,
+        16..=17 => 3,
+        18..=19 => 4,
+        20..=21 => 5,
+        22..=23 => 6,
+        _ => 7,
+    }
+}
+
+/// Encodes floats with the Chimp algorithm, which improves the Gorilla encoding
+/// for XORs with few trailing zeros, that is common for real-world floats.
+///
+/// The number of values (u32) follows the encoding type, then the first value,
+/// then the XOR of each value and the previous value with a 2-bit flag:
+/// - `00`: the XOR is zero.
+/// - `01`: 3 bits of rounded leading zeros, 6 bits of the center bits length,
+///   then the center bits, it's used if trailing zeros are more than 6.
+/// - `10`: the XOR without the leading zeros same as the previous XOR.
+/// - `11`: 3 bits of rounded leading zeros, then the XOR without them.
+pub fn f64_chimp_encode(
+    src: &[f64],
+    dst: &mut Vec<u8>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    let len = u32::try_from(src.len())?;
+    dst.push(Encoding::Chimp as u8);
+    dst.extend_from_slice(&len.to_be_bytes());
+
+    let mut writer = BitWriter::with_capacity(src.len() * 8);
+    let mut prev = src[0].to_bits();
+    writer.write_bits(prev, 64);
+    // Rounded leading zeros are never 65, so that flag `10` is not used.
+    let mut prev_leading = 65_u32;
+    for v in src[1..].iter() {
+        let cur = v.to_bits();
+        let xor = cur ^ prev;
+        prev = cur;
+
+        let trailing = xor.trailing_zeros();
+        if trailing > CHIMP_TRAILING_ZEROS_THRESHOLD {
+            if xor == 0 {
+                writer.write_bits(0b00, 2);
+            } else {
+                let index = chimp_leading_zeros_index(xor.leading_zeros());
+                let center = 64 - CHIMP_LEADING_ZEROS[index as usize] - trailing;
+                writer.write_bits(0b01, 2);
+                writer.write_bits(index as u64, 3);
+                writer.write_bits(center as u64, 6);
+                writer.write_bits(xor >> trailing, center);
+            }
+            prev_leading = 65;
+        } else {
+            let index = chimp_leading_zeros_index(xor.leading_zeros());
+            let leading = CHIMP_LEADING_ZEROS[index as usize];
+            if leading == prev_leading {
+                writer.write_bits(0b10, 2);
+            } else {
+                writer.write_bits(0b11, 2);
+                writer.write_bits(index as u64, 3);
+                prev_leading = leading;
+            }
+            writer.write_bits(xor, 64 - leading);
+        }
+    }
+    dst.append(&mut writer.finish());
+    Ok(())
+}
+
+pub fn f64_chimp_decode(
+    src: &[u8],
+    dst: &mut Vec<f64>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if src.len() < 5 {
+        return Ok(());
+    }
+
+    let len = decode_be_u32(&src[1..5]) as usize;
+    if len == 0 {
+        return Ok(());
+    }
+    // The length is not trusted, so don't reserve more than a block.
+    dst.reserve(len.min(MAX_BLOCK_VALUES as usize));
+
+    let mut reader = BitReader::new(&src[5..]);
+    let mut read_bits = |n: u32| reader.read_bits(n).ok_or("short buffer");
+    let mut prev = read_bits(64)?;
+    dst.push(f64::from_bits(prev));
+    let mut prev_leading = 0_u32;
+    for _ in 1..len {
+        let xor = match read_bits(2)? {
+            0b00 => 0,
+            0b01 => {
+                let leading = CHIMP_LEADING_ZEROS[read_bits(3)? as usize];
+                let center = read_bits(6)? as u32;
+                // Center bits of a non-zero XOR are not empty, so trailing is less than 64.
+                let trailing = 64_u32
+                    .checked_sub(leading + center)
+                    .filter(|t| *t < 64)
+                    .ok_or("invalid center bits length")?;
+                read_bits(center)? << trailing
+            }
+            0b10 => read_bits(64 - prev_leading)?,
+            _ => {
+                prev_leading = CHIMP_LEADING_ZEROS[read_bits(3)? as usize];
+                read_bits(64 - prev_leading)?
+            }
+        };
+        prev ^= xor;
+        dst.push(f64::from_bits(prev));
+    }
+    Ok(())
+}
+
 /// decode decodes a slice of bytes into a vector of floats.
 #[allow(clippy::many_single_char_names)]
 #[allow(clippy::useless_let_if_seq)]
@@ -580,9 +709,12 @@ fn decode_with_sentinel(
 mod tests {
     // use test_helpers::approximately_equal;
 
+    use crate::tsm::codec::bits::BitWriter;
     use crate::tsm::codec::float::{
-        f64_gorilla_decode, f64_gorilla_encode, f64_q_compress_decode, f64_q_compress_encode,
+        f64_chimp_decode, f64_chimp_encode, f64_gorilla_decode, f64_gorilla_encode,
+        f64_q_compress_decode, f64_q_compress_encode,
     };
+    use crate::tsm::codec::Encoding;
 
     #[test]
     fn encode_no_values() {
@@ -1795,5 +1927,44 @@ mod tests {
             // verify got same values back
             assert_eq!(got, src, "{}", test.name);
         }
+
+        for test in tests.iter() {
+            let mut dst = vec![];
+            let src = test.input.clone();
+
+            f64_chimp_encode(&src, &mut dst).expect("failed to encode");
+
+            let mut got = vec![];
+            f64_chimp_decode(&dst, &mut got).expect("failed to decode");
+            // verify got same values back
+            assert_eq!(
+                got.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                src.iter().map(|v| v.to_bits()).collect::<Vec<_>>(),
+                "{}",
+                test.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_chimp_decode_invalid() {
+        // The second value has a XOR of empty center bits.
+        let mut writer = BitWriter::default();
+        writer.write_bits(1.0_f64.to_bits(), 64);
+        writer.write_bits(0b01, 2);
+        writer.write_bits(0, 3);
+        writer.write_bits(0, 6);
+        let mut src = vec![Encoding::Chimp as u8];
+        src.extend_from_slice(&2_u32.to_be_bytes());
+        src.append(&mut writer.finish());
+        let mut got = vec![];
+        assert!(f64_chimp_decode(&src, &mut got).is_err());
+
+        // The number of values is larger than the values encoded.
+        let mut dst = vec![];
+        f64_chimp_encode(&[1.0, 2.0], &mut dst).unwrap();
+        dst[1..5].copy_from_slice(&u32::MAX.to_be_bytes());
+        let mut got = vec![];
+        assert!(f64_chimp_decode(&dst, &mut got).is_err());
     }
 }
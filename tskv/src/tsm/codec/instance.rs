@@ -8,17 +8,19 @@ use crate::tsm::codec::boolean::{
     bool_without_compress_encode,
 };
 use crate::tsm::codec::float::{
-    f64_gorilla_decode, f64_gorilla_encode, f64_q_compress_decode, f64_q_compress_encode,
-    f64_without_compress_decode, f64_without_compress_encode,
+    f64_chimp_decode, f64_chimp_encode, f64_gorilla_decode, f64_gorilla_encode,
+    f64_q_compress_decode, f64_q_compress_encode, f64_without_compress_decode,
+    f64_without_compress_encode,
 };
 use crate::tsm::codec::integer::{
     i64_q_compress_decode, i64_q_compress_encode, i64_without_compress_decode,
     i64_without_compress_encode, i64_zigzag_simple8b_decode, i64_zigzag_simple8b_encode,
 };
 use crate::tsm::codec::string::{
-    str_bzip_decode, str_bzip_encode, str_gzip_decode, str_gzip_encode, str_snappy_decode,
-    str_snappy_encode, str_without_compress_decode, str_without_compress_encode, str_zlib_decode,
-    str_zlib_encode, str_zstd_decode, str_zstd_encode,
+    str_bzip_decode, str_bzip_encode, str_dictionary_decode, str_dictionary_encode,
+    str_gzip_decode, str_gzip_encode, str_snappy_decode, str_snappy_encode,
+    str_without_compress_decode, str_without_compress_encode, str_zlib_decode, str_zlib_encode,
+    str_zstd_decode, str_zstd_encode,
 };
 use crate::tsm::codec::timestamp::{
     ts_q_compress_decode, ts_q_compress_encode, ts_without_compress_decode,
@@ -152,6 +154,18 @@ impl FloatCodec for QuantileFloatCodec {
     }
 }
 
+struct ChimpFloatCodec();
+
+impl FloatCodec for ChimpFloatCodec {
+    fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        f64_chimp_encode(src, dst)
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<f64>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        f64_chimp_decode(src, dst)
+    }
+}
+
 pub trait UnsignedCodec {
     fn encode(&self, src: &[u64], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>>;
     fn decode(&self, src: &[u8], dst: &mut Vec<u64>) -> Result<(), Box<dyn Error + Send + Sync>>;
@@ -327,6 +341,156 @@ impl StringCodec for ZlibStringCodec {
     }
 }
 
+struct DictionaryStringCodec();
+
+impl StringCodec for DictionaryStringCodec {
+    fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        str_dictionary_encode(src, dst)
+    }
+
+    fn decode(
+        &self,
+        src: &[u8],
+        dst: &mut Vec<MiniVec<u8>>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        str_dictionary_decode(src, dst)
+    }
+}
+
+/// Candidate codecs of `Encoding::Auto` for each type.
+const AUTO_TIMESTAMP_CODECS: [Encoding; 2] = [Encoding::Delta, Encoding::Quantile];
+const AUTO_INTEGER_CODECS: [Encoding; 2] = [Encoding::Delta, Encoding::Quantile];
+const AUTO_UNSIGNED_CODECS: [Encoding; 2] = [Encoding::Delta, Encoding::Quantile];
+const AUTO_FLOAT_CODECS: [Encoding; 3] = [Encoding::Gorilla, Encoding::Chimp, Encoding::Quantile];
+const AUTO_BOOLEAN_CODECS: [Encoding; 1] = [Encoding::BitPack];
+const AUTO_STRING_CODECS: [Encoding; 3] = [Encoding::Snappy, Encoding::Zstd, Encoding::Dictionary];
+
+/// Encodes with each of the candidate codecs and appends the smallest result to `dst`,
+/// codecs that failed to encode are skipped.
+fn encode_with_smallest_codec(
+    candidates: &[Encoding],
+    dst: &mut Vec<u8>,
+    encode: impl Fn(Encoding, &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut smallest: Option<Vec<u8>> = None;
+    let mut last_err = None;
+    for encoding in candidates {
+        let mut buf = vec![];
+        match encode(*encoding, &mut buf) {
+            Ok(()) => {
+                if smallest.as_ref().map_or(true, |s| buf.len() < s.len()) {
+                    smallest = Some(buf);
+                }
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+    match (smallest, last_err) {
+        (Some(mut buf), _) => {
+            dst.append(&mut buf);
+            Ok(())
+        }
+        (None, Some(e)) => Err(e),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Returns the codec recorded in a block encoded by `Encoding::Auto`.
+fn encoding_of_auto_encoded(src: &[u8]) -> Result<Encoding, Box<dyn Error + Send + Sync>> {
+    match get_encoding(src) {
+        Encoding::Auto => Err("invalid encoding type AUTO of encoded block".into()),
+        encoding => Ok(encoding),
+    }
+}
+
+struct AutoTimestampCodec();
+
+impl TimestampCodec for AutoTimestampCodec {
+    fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        encode_with_smallest_codec(&AUTO_TIMESTAMP_CODECS, dst, |encoding, buf| {
+            get_ts_codec(encoding).encode(src, buf)
+        })
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<i64>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        get_ts_codec(encoding_of_auto_encoded(src)?).decode(src, dst)
+    }
+}
+
+struct AutoIntegerCodec();
+
+impl IntegerCodec for AutoIntegerCodec {
+    fn encode(&self, src: &[i64], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        encode_with_smallest_codec(&AUTO_INTEGER_CODECS, dst, |encoding, buf| {
+            get_i64_codec(encoding).encode(src, buf)
+        })
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<i64>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        get_i64_codec(encoding_of_auto_encoded(src)?).decode(src, dst)
+    }
+}
+
+struct AutoUnsignedCodec();
+
+impl UnsignedCodec for AutoUnsignedCodec {
+    fn encode(&self, src: &[u64], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        encode_with_smallest_codec(&AUTO_UNSIGNED_CODECS, dst, |encoding, buf| {
+            get_u64_codec(encoding).encode(src, buf)
+        })
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<u64>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        get_u64_codec(encoding_of_auto_encoded(src)?).decode(src, dst)
+    }
+}
+
+struct AutoFloatCodec();
+
+impl FloatCodec for AutoFloatCodec {
+    fn encode(&self, src: &[f64], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        encode_with_smallest_codec(&AUTO_FLOAT_CODECS, dst, |encoding, buf| {
+            get_f64_codec(encoding).encode(src, buf)
+        })
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<f64>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        get_f64_codec(encoding_of_auto_encoded(src)?).decode(src, dst)
+    }
+}
+
+struct AutoBooleanCodec();
+
+impl BooleanCodec for AutoBooleanCodec {
+    fn encode(&self, src: &[bool], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        encode_with_smallest_codec(&AUTO_BOOLEAN_CODECS, dst, |encoding, buf| {
+            get_bool_codec(encoding).encode(src, buf)
+        })
+    }
+
+    fn decode(&self, src: &[u8], dst: &mut Vec<bool>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        get_bool_codec(encoding_of_auto_encoded(src)?).decode(src, dst)
+    }
+}
+
+struct AutoStringCodec();
+
+impl StringCodec for AutoStringCodec {
+    fn encode(&self, src: &[&[u8]], dst: &mut Vec<u8>) -> Result<(), Box<dyn Error + Send + Sync>> {
+        encode_with_smallest_codec(&AUTO_STRING_CODECS, dst, |encoding, buf| {
+            get_str_codec(encoding).encode(src, buf)
+        })
+    }
+
+    fn decode(
+        &self,
+        src: &[u8],
+        dst: &mut Vec<MiniVec<u8>>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        get_str_codec(encoding_of_auto_encoded(src)?).decode(src, dst)
+    }
+}
+
 pub fn get_encoding(src: &[u8]) -> Encoding {
     if src.is_empty() {
         return Encoding::Unknown;
@@ -339,6 +503,7 @@ pub fn get_ts_codec(algo: Encoding) -> Box<dyn TimestampCodec + Send + Sync> {
         Encoding::Null => Box::new(NullTimestampCodec()),
         Encoding::Delta => Box::new(DeltaTimestampCodec()),
         Encoding::Quantile => Box::new(QuantileTimestampCodec()),
+        Encoding::Auto => Box::new(AutoTimestampCodec()),
         _ => Box::new(DeltaTimestampCodec()),
     }
 }
@@ -348,6 +513,7 @@ pub fn get_i64_codec(algo: Encoding) -> Box<dyn IntegerCodec + Send + Sync> {
         Encoding::Null => Box::new(NullIntegerCodec()),
         Encoding::Delta => Box::new(DeltaIntegerCodec()),
         Encoding::Quantile => Box::new(QuantileIntegerCodec()),
+        Encoding::Auto => Box::new(AutoIntegerCodec()),
         _ => Box::new(DeltaIntegerCodec()),
     }
 }
@@ -357,6 +523,7 @@ pub fn get_u64_codec(algo: Encoding) -> Box<dyn UnsignedCodec + Send + Sync> {
         Encoding::Null => Box::new(NullUnsignedCodec()),
         Encoding::Delta => Box::new(DeltaUnsignedCodec()),
         Encoding::Quantile => Box::new(QuantileUnsignedCodec()),
+        Encoding::Auto => Box::new(AutoUnsignedCodec()),
         _ => Box::new(DeltaUnsignedCodec()),
     }
 }
@@ -366,6 +533,8 @@ pub fn get_f64_codec(algo: Encoding) -> Box<dyn FloatCodec + Send + Sync> {
         Encoding::Null => Box::new(NullFloatCodec()),
         Encoding::Gorilla => Box::new(GorillaFloatCodec()),
         Encoding::Quantile => Box::new(QuantileFloatCodec()),
+        Encoding::Chimp => Box::new(ChimpFloatCodec()),
+        Encoding::Auto => Box::new(AutoFloatCodec()),
         _ => Box::new(GorillaFloatCodec()),
     }
 }
@@ -378,6 +547,8 @@ pub fn get_str_codec(algo: Encoding) -> Box<dyn StringCodec + Send + Sync> {
         Encoding::Snappy => Box::new(SnappyStringCodec()),
         Encoding::Zstd => Box::new(ZstdStringCodec()),
         Encoding::Zlib => Box::new(ZlibStringCodec()),
+        Encoding::Dictionary => Box::new(DictionaryStringCodec()),
+        Encoding::Auto => Box::new(AutoStringCodec()),
         _ => Box::new(SnappyStringCodec()),
     }
 }
@@ -386,6 +557,54 @@ pub fn get_bool_codec(algo: Encoding) -> Box<dyn BooleanCodec + Send + Sync> {
     match algo {
         Encoding::Null => Box::new(NullBooleanCodec()),
         Encoding::BitPack => Box::new(BitPackBooleanCodec()),
+        Encoding::Auto => Box::new(AutoBooleanCodec()),
         _ => Box::new(BitPackBooleanCodec()),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use models::codec::Encoding;
+
+    use super::{get_encoding, get_f64_codec, get_i64_codec, get_str_codec};
+
+    #[test]
+    fn test_auto_codec() {
+        let codec = get_f64_codec(Encoding::Auto);
+        let src: Vec<f64> = (0..1000).map(|i| (i % 10) as f64 * 0.25).collect();
+        let mut dst = vec![];
+        codec.encode(&src, &mut dst).unwrap();
+        for encoding in [Encoding::Gorilla, Encoding::Chimp, Encoding::Quantile] {
+            let mut buf = vec![];
+            get_f64_codec(encoding).encode(&src, &mut buf).unwrap();
+            assert!(dst.len() <= buf.len());
+        }
+        assert_ne!(get_encoding(&dst), Encoding::Auto);
+        let mut got = vec![];
+        codec.decode(&dst, &mut got).unwrap();
+        assert_eq!(got, src);
+
+        let codec = get_i64_codec(Encoding::Auto);
+        let src: Vec<i64> = (0..1000).collect();
+        let mut dst = vec![];
+        codec.encode(&src, &mut dst).unwrap();
+        let mut got = vec![];
+        get_i64_codec(get_encoding(&dst))
+            .decode(&dst, &mut got)
+            .unwrap();
+        assert_eq!(got, src);
+
+        let codec = get_str_codec(Encoding::Auto);
+        let src: Vec<&[u8]> = (0..1000)
+            .map(|i| [b"ok".as_slice(), b"error"][i % 2])
+            .collect();
+        let mut dst = vec![];
+        codec.encode(&src, &mut dst).unwrap();
+        let mut got = vec![];
+        codec.decode(&dst, &mut got).unwrap();
+        assert_eq!(got, src);
+
+        let mut got = vec![];
+        assert!(codec.decode(&[Encoding::Auto as u8, 0], &mut got).is_err());
+    }
+}
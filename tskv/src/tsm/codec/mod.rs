@@ -1,3 +1,4 @@
+mod bits;
 mod boolean;
 mod float;
 mod instance;
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::error::Error;
 use std::io::Write;
@@ -10,7 +11,9 @@ use integer_encoding::VarInt;
 use minivec::MiniVec;
 
 use crate::byte_utils::decode_be_u64;
+use crate::tsm::codec::bits::{BitReader, BitWriter};
 use crate::tsm::codec::Encoding;
+use crate::tsm::MAX_BLOCK_VALUES;
 // note: encode/decode adapted from influxdb_iox
 // https://github.com/influxdata/influxdb_iox/tree/main/influxdb_tsm/src/encoders
 
@@ -194,6 +197,49 @@ pub fn str_without_compress_encode(
     Ok(())
 }
 
+/// Encodes strings into a dictionary of distinct strings and bit-packed indices
+/// of the dictionary, it's efficient for low-cardinality fields like status codes.
+///
+/// The number of dictionary entries (varint) follows the encoding type, then
+/// each entry with its length (varint), then the number of values (varint),
+/// the bit width of indices (u8), and the bit-packed indices.
+pub fn str_dictionary_encode(
+    src: &[&[u8]],
+    dst: &mut Vec<u8>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    let mut dictionary: Vec<&[u8]> = vec![];
+    let mut dictionary_indices: HashMap<&[u8], u64> = HashMap::new();
+    let mut indices = Vec::with_capacity(src.len());
+    for s in src {
+        let index = *dictionary_indices.entry(s).or_insert_with(|| {
+            dictionary.push(s);
+            dictionary.len() as u64 - 1
+        });
+        indices.push(index);
+    }
+
+    dst.push(Encoding::Dictionary as u8);
+    dst.append(&mut (dictionary.len() as u64).encode_var_vec());
+    for s in dictionary.iter() {
+        dst.append(&mut (s.len() as u64).encode_var_vec());
+        dst.extend_from_slice(s);
+    }
+    dst.append(&mut (src.len() as u64).encode_var_vec());
+
+    let bit_width = 64 - (dictionary.len() as u64 - 1).leading_zeros();
+    dst.push(bit_width as u8);
+    let mut writer = BitWriter::with_capacity(src.len() * bit_width as usize / 8 + 1);
+    for index in indices {
+        writer.write_bits(index, bit_width);
+    }
+    dst.append(&mut writer.finish());
+    Ok(())
+}
+
 /// Decodes a slice of bytes representing Snappy-compressed data into a vector
 /// of vectors of bytes representing string data, which may or may not be valid
 /// UTF-8.
@@ -346,6 +392,58 @@ pub fn str_without_compress_decode(
     Ok(())
 }
 
+fn decode_var_usize(src: &mut &[u8]) -> Result<usize, Box<dyn Error + Send + Sync>> {
+    let (value, num_bytes_read) = u64::decode_var(src).ok_or("invalid encoded length")?;
+    *src = &src[num_bytes_read..];
+    Ok(value.try_into()?)
+}
+
+pub fn str_dictionary_decode(
+    src: &[u8],
+    dst: &mut Vec<MiniVec<u8>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if src.is_empty() {
+        return Ok(());
+    }
+
+    let mut src = &src[1..];
+    let dictionary_len = decode_var_usize(&mut src)?;
+    // Each entry of the dictionary takes at least 1 byte for its length.
+    let mut dictionary = Vec::with_capacity(dictionary_len.min(src.len()));
+    for _ in 0..dictionary_len {
+        let len = decode_var_usize(&mut src)?;
+        if len > src.len() {
+            return Err("short buffer".into());
+        }
+        let (s, remaining) = src.split_at(len);
+        dictionary.push(s);
+        src = remaining;
+    }
+
+    let len = decode_var_usize(&mut src)?;
+    if len > MAX_BLOCK_VALUES as usize {
+        return Err("too many values of a block".into());
+    }
+    let (bit_width, src) = src.split_first().ok_or("short buffer")?;
+    // Indices take no bits only if there is only one entry in the dictionary.
+    if *bit_width > 64 || (*bit_width == 0 && dictionary.len() != 1) {
+        return Err("invalid bit width of dictionary indices".into());
+    }
+    if len * *bit_width as usize > src.len() * 8 {
+        return Err("short buffer".into());
+    }
+    let mut reader = BitReader::new(src);
+    dst.reserve(len);
+    for _ in 0..len {
+        let index = reader.read_bits(*bit_width as u32).ok_or("short buffer")?;
+        let s = dictionary
+            .get(index as usize)
+            .ok_or("dictionary index out of range")?;
+        dst.push(MiniVec::from(*s));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -551,5 +649,66 @@ mod tests {
         str_without_compress_encode(&data, &mut dst).unwrap();
         str_without_compress_decode(&dst, &mut got).unwrap();
         assert_eq!(data_exp, got);
+        dst.clear();
+        got.clear();
+
+        str_dictionary_encode(&data, &mut dst).unwrap();
+        str_dictionary_decode(&dst, &mut got).unwrap();
+        assert_eq!(data_exp, got);
+    }
+
+    #[test]
+    fn test_dictionary_encode_decode() {
+        let mut dst = vec![];
+        let mut got = vec![];
+
+        let data: Vec<&[u8]> = vec![b"200"; 100];
+        str_dictionary_encode(&data, &mut dst).unwrap();
+        str_dictionary_decode(&dst, &mut got).unwrap();
+        assert_eq!(got, data);
+        dst.clear();
+        got.clear();
+
+        let data: Vec<&[u8]> = (0..1000)
+            .map(|i| ["200", "404", "500", ""][i % 4].as_bytes())
+            .collect();
+        str_dictionary_encode(&data, &mut dst).unwrap();
+        // 1 byte of encoding type, 14 bytes of dictionary, 3 bytes of the number
+        // of values and the bit width, then 250 bytes of 2-bit indices.
+        assert_eq!(dst.len(), 268);
+        str_dictionary_decode(&dst, &mut got).unwrap();
+        assert_eq!(got, data);
+
+        assert!(str_dictionary_decode(&dst[..dst.len() - 1], &mut got).is_err());
+
+        // Lengths larger than the encoded data.
+        let mut src = vec![Encoding::Dictionary as u8];
+        src.extend_from_slice(&u64::MAX.encode_var_vec());
+        assert!(str_dictionary_decode(&src, &mut got).is_err());
+        let mut src = vec![Encoding::Dictionary as u8, 1, 1, b'a'];
+        src.extend_from_slice(&u64::MAX.encode_var_vec());
+        src.push(1);
+        assert!(str_dictionary_decode(&src, &mut got).is_err());
+
+        // Indices of a one-entry dictionary take no bits, but the number of
+        // values is still limited.
+        let mut src = vec![Encoding::Dictionary as u8, 1, 1, b'a'];
+        src.extend_from_slice(&u64::MAX.encode_var_vec());
+        src.push(0);
+        assert!(str_dictionary_decode(&src, &mut got).is_err());
+        let mut src = vec![Encoding::Dictionary as u8, 1, 1, b'a'];
+        src.extend_from_slice(&(MAX_BLOCK_VALUES as u64 + 1).encode_var_vec());
+        src.push(0);
+        assert!(str_dictionary_decode(&src, &mut got).is_err());
+        // Indices take no bits with more than one entry in the dictionary.
+        let mut src = vec![Encoding::Dictionary as u8, 2, 1, b'a', 1, b'b'];
+        src.extend_from_slice(&10_u64.encode_var_vec());
+        src.push(0);
+        assert!(str_dictionary_decode(&src, &mut got).is_err());
+        // Bits of indices exceed the remaining data.
+        let mut src = vec![Encoding::Dictionary as u8, 2, 1, b'a', 1, b'b'];
+        src.extend_from_slice(&10_u64.encode_var_vec());
+        src.extend_from_slice(&[1, 0]);
+        assert!(str_dictionary_decode(&src, &mut got).is_err());
     }
 }
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+use lru_cache::ShardedCache;
+use parking_lot::Mutex;
+
+use crate::tsm::DataBlock;
+
+/// Key of a decoded `DataBlock` in `DataBlockCache`, ids of column files are
+/// unique in a node, so (file_id, offset) identifies a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct DataBlockCacheKey {
+    file_id: u64,
+    offset: u64,
+}
+
+impl Display for DataBlockCacheKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file_id, self.offset)
+    }
+}
+
+/// Node-wide cache of decoded `DataBlock`s (without tombstones applied),
+/// bounded by the raw size of the cached `DataBlock`s.
+pub struct DataBlockCache {
+    cache: ShardedCache<DataBlockCacheKey, Arc<DataBlock>>,
+    /// Offsets of cached blocks of each file, used to remove them when the file is deleted.
+    /// Offsets of blocks evicted from the cache are removed by the evicting callback.
+    file_offsets: Arc<Mutex<HashMap<u64, HashSet<u64>>>>,
+}
+
+impl DataBlockCache {
+    /// Creates a `DataBlockCache` of `capacity` bytes, returns None if `capacity` is 0.
+    pub fn open(capacity: u64) -> Option<Arc<Self>> {
+        if capacity == 0 {
+            return None;
+        }
+        Some(Arc::new(Self::with_capacity(capacity as usize)))
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            cache: ShardedCache::with_capacity(capacity),
+            file_offsets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn get(&self, file_id: u64, offset: u64) -> Option<Arc<DataBlock>> {
+        let key = DataBlockCacheKey { file_id, offset };
+        let block = self.cache.get_cloned(&key);
+        if block.is_some() {
+            metrics::incr_data_block_cache_hit();
+        } else {
+            metrics::incr_data_block_cache_miss();
+        }
+        block
+    }
+
+    pub fn insert(&self, file_id: u64, offset: u64, block: Arc<DataBlock>) {
+        let charge = block.raw_size().max(1);
+        let file_offsets = self.file_offsets.clone();
+        let after_removed = move |k: &DataBlockCacheKey, _: &mut Arc<DataBlock>| {
+            let mut file_offsets = file_offsets.lock();
+            if let Some(offsets) = file_offsets.get_mut(&k.file_id) {
+                offsets.remove(&k.offset);
+                if offsets.is_empty() {
+                    file_offsets.remove(&k.file_id);
+                }
+            }
+        };
+        // The replaced block removes the offset in the callback, so the offset is
+        // recorded after the block is inserted.
+        let inserted = self
+            .cache
+            .insert_opt(
+                DataBlockCacheKey { file_id, offset },
+                block,
+                charge,
+                Some(Box::new(after_removed)),
+            )
+            .is_some();
+        if inserted {
+            self.file_offsets
+                .lock()
+                .entry(file_id)
+                .or_default()
+                .insert(offset);
+        }
+    }
+
+    /// Removes all cached blocks of a file.
+    pub fn remove_file(&self, file_id: u64) {
+        let offsets = self.file_offsets.lock().remove(&file_id);
+        if let Some(offsets) = offsets {
+            for offset in offsets {
+                self.cache.remove(&DataBlockCacheKey { file_id, offset });
+            }
+        }
+    }
+}
+
+impl Debug for DataBlockCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataBlockCache")
+            .field("files", &self.file_offsets.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::DataBlockCache;
+    use crate::tsm::codec::DataBlockEncoding;
+    use crate::tsm::DataBlock;
+
+    #[test]
+    fn test_data_block_cache() {
+        let cache = DataBlockCache::with_capacity(1024 * 1024);
+        let block = DataBlock::I64 {
+            ts: vec![1, 2, 3],
+            val: vec![10, 20, 30],
+            enc: DataBlockEncoding::default(),
+        };
+        cache.insert(1, 5, Arc::new(block.clone()));
+        cache.insert(1, 105, Arc::new(block.clone()));
+        cache.insert(2, 5, Arc::new(block.clone()));
+        assert_eq!(cache.get(1, 5).as_deref(), Some(&block));
+        assert_eq!(cache.get(1, 105).as_deref(), Some(&block));
+        assert!(cache.get(1, 205).is_none());
+
+        cache.remove_file(1);
+        assert!(cache.get(1, 5).is_none());
+        assert!(cache.get(1, 105).is_none());
+        assert_eq!(cache.get(2, 5).as_deref(), Some(&block));
+
+        assert!(cache.file_offsets.lock().get(&1).is_none());
+
+        // Offsets of evicted blocks are removed.
+        let charge = block.raw_size();
+        let cache = DataBlockCache::with_capacity(charge * 16);
+        for offset in 0..100 {
+            cache.insert(3, offset, Arc::new(block.clone()));
+        }
+        let cached = (0..100).filter(|o| cache.get(3, *o).is_some()).count();
+        let recorded = cache.file_offsets.lock().get(&3).map(|o| o.len());
+        assert!(cached < 100);
+        assert_eq!(recorded, Some(cached));
+
+        assert!(DataBlockCache::open(0).is_none());
+    }
+}
@@ -8,12 +8,15 @@ use utils::BloomFilter;
 
 use crate::byte_utils::{decode_be_i64, decode_be_u16, decode_be_u32, decode_be_u64};
 use crate::tsm::{
-    BlockMetaIterator, DataBlock, WriteTsmError, WriteTsmResult, BLOCK_META_SIZE, INDEX_META_SIZE,
+    block_meta_size, BlockMetaIterator, BlockStatistics, DataBlock, WriteTsmError, WriteTsmResult,
+    BLOCK_META_SIZE, BLOCK_META_SIZE_V1, BLOCK_STATS_SIZE, INDEX_META_SIZE, TSM_VERSION_V2,
 };
 
 #[derive(Debug, Clone)]
 pub struct Index {
     tsm_id: u64,
+    /// Version of the TSM file, determines the size of each block meta.
+    version: u8,
     bloom_filter: Arc<BloomFilter>,
 
     /// In-memory index-block data
@@ -35,18 +38,29 @@ impl Index {
     #[inline(always)]
     pub fn new(
         tsm_id: u64,
+        version: u8,
         bloom_filter: Arc<BloomFilter>,
         data: Vec<u8>,
         field_id_offs: Vec<(FieldId, usize)>,
     ) -> Self {
         Self {
             tsm_id,
+            version,
             bloom_filter,
             data,
             field_id_offs,
         }
     }
 
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Size of each block meta in `data`.
+    pub fn block_meta_size(&self) -> usize {
+        block_meta_size(self.version)
+    }
+
     pub fn bloom_filter(&self) -> Arc<BloomFilter> {
         self.bloom_filter.clone()
     }
@@ -117,7 +131,8 @@ impl IndexMeta {
         }
         let first_blk_beg = self.index_ref.field_id_offs()[self.index_idx].1 + INDEX_META_SIZE;
         let min_ts = decode_be_i64(&self.index_ref.data[first_blk_beg..first_blk_beg + 8]);
-        let last_blk_beg = first_blk_beg + BLOCK_META_SIZE * (self.block_count as usize - 1);
+        let last_blk_beg =
+            first_blk_beg + self.index_ref.block_meta_size() * (self.block_count as usize - 1);
         let max_ts = decode_be_i64(&self.index_ref.data[last_blk_beg + 8..last_blk_beg + 16]);
         TimeRange::new(min_ts, max_ts)
     }
@@ -239,6 +254,18 @@ impl BlockMeta {
     pub fn val_off(&self) -> u64 {
         decode_be_u64(&self.index_ref.data()[self.block_offset + 36..self.block_offset + 44])
     }
+
+    /// Returns statistics of values in this block, or None if the TSM file
+    /// was written before `TSM_VERSION_V2`.
+    pub fn stats(&self) -> Option<BlockStatistics> {
+        if self.index_ref.version() < TSM_VERSION_V2 {
+            return None;
+        }
+        let stats_beg = self.block_offset + BLOCK_META_SIZE_V1;
+        Some(BlockStatistics::decode(
+            &self.index_ref.data()[stats_beg..stats_beg + BLOCK_STATS_SIZE],
+        ))
+    }
 }
 
 impl Display for BlockMeta {
@@ -277,7 +304,7 @@ pub(crate) fn get_data_block_meta_unchecked(
     field_id: FieldId,
     field_type: ValueType,
 ) -> BlockMeta {
-    let base = index_offset + INDEX_META_SIZE + block_idx * BLOCK_META_SIZE;
+    let base = index_offset + INDEX_META_SIZE + block_idx * index.block_meta_size();
     BlockMeta::new(index, field_id, field_type, base)
 }
 
@@ -324,6 +351,7 @@ pub(crate) struct BlockEntry {
     pub offset: u64,
     pub size: u64,
     pub val_offset: u64,
+    pub stats: BlockStatistics,
 }
 
 impl BlockEntry {
@@ -336,6 +364,7 @@ impl BlockEntry {
             offset,
             size,
             val_offset: offset + ts_len,
+            stats: block_meta.stats().unwrap_or_default(),
         }
     }
 
@@ -357,6 +386,7 @@ impl BlockEntry {
             size,
             // Encoded timestamps block need a 4-bytes crc checksum together.
             val_offset: offset + encoded_ts_size + 4,
+            stats: BlockStatistics::new(data_block, 0, data_block.len()),
         })
     }
 
@@ -368,10 +398,18 @@ impl BlockEntry {
         buf[20..28].copy_from_slice(&self.offset.to_be_bytes()[..]);
         buf[28..36].copy_from_slice(&self.size.to_be_bytes()[..]);
         buf[36..44].copy_from_slice(&self.val_offset.to_be_bytes()[..]);
-    }
-
-    pub fn decode(data: &[u8]) -> Self {
-        assert!(data.len() >= BLOCK_META_SIZE);
+        self.stats
+            .encode(&mut buf[BLOCK_META_SIZE_V1..BLOCK_META_SIZE]);
+    }
+
+    /// Decodes a block meta of TSM file of the given version.
+    pub fn decode(data: &[u8], version: u8) -> Self {
+        assert!(data.len() >= block_meta_size(version));
+        let stats = if version >= TSM_VERSION_V2 {
+            BlockStatistics::decode(&data[BLOCK_META_SIZE_V1..BLOCK_META_SIZE])
+        } else {
+            BlockStatistics::default()
+        };
         Self {
             min_ts: decode_be_i64(&data[0..8]),
             max_ts: decode_be_i64(&data[8..16]),
@@ -379,6 +417,7 @@ impl BlockEntry {
             offset: decode_be_u64(&data[20..28]),
             size: decode_be_u64(&data[28..36]),
             val_offset: decode_be_u64(&data[36..44]),
+            stats,
         }
     }
 }
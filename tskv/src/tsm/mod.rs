@@ -1,13 +1,16 @@
 mod block;
 pub mod codec;
+mod data_block_cache;
 mod index;
 mod reader;
 mod tombstone;
 mod writer;
 
 pub use block::*;
+pub use data_block_cache::DataBlockCache;
 pub use index::*;
 pub use reader::*;
+pub(crate) use tombstone::ENTRY_LEN as TOMBSTONE_ENTRY_LEN;
 pub use tombstone::{Tombstone, TsmTombstone};
 pub use writer::*;
 
@@ -16,10 +19,37 @@ pub(crate) const MAX_BLOCK_VALUES: u32 = 1000;
 
 const HEADER_SIZE: usize = 5;
 const INDEX_META_SIZE: usize = 11;
-const BLOCK_META_SIZE: usize = 44;
-const BLOOM_FILTER_SIZE: usize = 64;
-const BLOOM_FILTER_BITS: u64 = 512; // 64 * 8
-const FOOTER_SIZE: usize = BLOOM_FILTER_SIZE + 8; // 72
+/// Size of a block meta in TSM files of version 1, without block statistics.
+const BLOCK_META_SIZE_V1: usize = 44;
+/// Size of block statistics: flags(1) + min(8) + max(8) + sum(8) + null_count(4).
+const BLOCK_STATS_SIZE: usize = 29;
+const BLOCK_META_SIZE: usize = BLOCK_META_SIZE_V1 + BLOCK_STATS_SIZE; // 73
+/// Size of the fixed bloom filter in footer of TSM files before version 3.
+const BLOOM_FILTER_SIZE_V2: usize = 64;
+/// Size of footer of TSM files before version 3: bloom_filter(64) + index_offset(8).
+const FOOTER_SIZE_V2: usize = BLOOM_FILTER_SIZE_V2 + 8; // 72
+/// Size of footer after the bloom filter in TSM files of version 3:
+/// bloom_filter_size(4) + bloom_filter_hashes(1) + index_offset(8).
+const FOOTER_TAIL_SIZE: usize = 13;
+/// Target false positive rate of the bloom filter of field ids in TSM files.
+const BLOOM_FILTER_FP_RATE: f64 = 0.01;
+
+/// TSM files without block statistics in index.
+pub const TSM_VERSION_V1: u8 = 1;
+/// TSM files with block statistics (min, max, sum, null_count) in index.
+pub const TSM_VERSION_V2: u8 = 2;
+/// TSM files with a bloom filter sized by the number of field ids in footer.
+pub const TSM_VERSION_V3: u8 = 3;
+pub const TSM_VERSION: u8 = TSM_VERSION_V3;
+
+/// Returns the size of a block meta in TSM files of the given version.
+pub(crate) fn block_meta_size(version: u8) -> usize {
+    if version >= TSM_VERSION_V2 {
+        BLOCK_META_SIZE
+    } else {
+        BLOCK_META_SIZE_V1
+    }
+}
 
 pub trait BlockReader {
     fn decode(&mut self, block: &BlockMeta) -> crate::error::Result<DataBlock>;
@@ -1,7 +1,9 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::path::Path;
 use std::sync::Arc;
 
+use datafusion::scalar::ScalarValue;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::{FieldId, PhysicalDType as ValueType};
 use parking_lot::RwLock;
@@ -9,8 +11,8 @@ use snafu::{ResultExt, Snafu};
 use utils::BloomFilter;
 
 use crate::byte_utils::{self, decode_be_i64, decode_be_u16, decode_be_u64};
+use crate::cold_storage::RemoteFile;
 use crate::error::{self, Error, Result};
-use crate::file_system::file::async_file::AsyncFile;
 use crate::file_system::file::IFile;
 use crate::file_system::file_manager;
 use crate::file_utils;
@@ -20,9 +22,10 @@ use crate::tsm::codec::{
 };
 use crate::tsm::tombstone::TsmTombstone;
 use crate::tsm::{
-    get_data_block_meta_unchecked, get_index_meta_unchecked, BlockEntry, BlockMeta, DataBlock,
-    Index, IndexEntry, IndexMeta, BLOCK_META_SIZE, BLOOM_FILTER_SIZE, FOOTER_SIZE, INDEX_META_SIZE,
-    MAX_BLOCK_VALUES,
+    block_meta_size, get_data_block_meta_unchecked, get_index_meta_unchecked, BlockEntry,
+    BlockMeta, DataBlock, DataBlockCache, Index, IndexEntry, IndexMeta, BLOCK_META_SIZE,
+    BLOOM_FILTER_SIZE_V2, FOOTER_SIZE_V2, FOOTER_TAIL_SIZE, HEADER_SIZE, INDEX_META_SIZE,
+    MAX_BLOCK_VALUES, TSM_VERSION, TSM_VERSION_V1, TSM_VERSION_V3,
 };
 
 pub type ReadTsmResult<T, E = ReadTsmError> = std::result::Result<T, E>;
@@ -62,7 +65,8 @@ impl From<ReadTsmError> for Error {
 
 /// Disk-based index reader
 pub struct IndexFile {
-    reader: Arc<AsyncFile>,
+    reader: Arc<dyn IFile>,
+    version: u8,
     bloom_filter: BloomFilter,
     idx_meta_buf: [u8; INDEX_META_SIZE],
     blk_meta_buf: [u8; BLOCK_META_SIZE],
@@ -75,23 +79,20 @@ pub struct IndexFile {
 }
 
 impl IndexFile {
-    pub(crate) async fn open(reader: Arc<AsyncFile>) -> ReadTsmResult<Self> {
+    pub(crate) async fn open(reader: Arc<dyn IFile>) -> ReadTsmResult<Self> {
         let file_len = reader.len();
-        let mut footer = [0_u8; FOOTER_SIZE];
-        reader
-            .read_at(file_len - FOOTER_SIZE as u64, &mut footer)
-            .await
-            .context(ReadIOSnafu)?;
-        let bloom_filter = BloomFilter::with_data(&footer[..BLOOM_FILTER_SIZE]);
-        let index_offset = decode_be_u64(&footer[BLOOM_FILTER_SIZE..]);
+        let version = read_version(reader.as_ref()).await?;
+        let (bloom_filter, index_offset, footer_size) =
+            read_footer(reader.as_ref(), version).await?;
         Ok(Self {
             reader,
+            version,
             bloom_filter,
             idx_meta_buf: [0_u8; INDEX_META_SIZE],
             blk_meta_buf: [0_u8; BLOCK_META_SIZE],
             index_offset,
             pos: index_offset,
-            end_pos: file_len - FOOTER_SIZE as u64,
+            end_pos: file_len - footer_size,
             index_block_idx: 0,
             index_block_count: 0,
         })
@@ -119,12 +120,13 @@ impl IndexFile {
         if self.index_block_idx >= self.index_block_count {
             return Ok(None);
         }
+        let blk_meta_size = block_meta_size(self.version);
         self.reader
-            .read_at(self.pos, &mut self.blk_meta_buf[..])
+            .read_at(self.pos, &mut self.blk_meta_buf[..blk_meta_size])
             .await
             .context(ReadIOSnafu)?;
-        self.pos += BLOCK_META_SIZE as u64;
-        let entry = BlockEntry::decode(&self.blk_meta_buf);
+        self.pos += blk_meta_size as u64;
+        let entry = BlockEntry::decode(&self.blk_meta_buf, self.version);
         self.index_block_idx += 1;
 
         Ok(Some(entry))
@@ -135,17 +137,59 @@ pub async fn print_tsm_statistics(path: impl AsRef<Path>, show_tombstone: bool)
     let reader = TsmReader::open(path).await.unwrap();
     let mut points_cnt = 0_usize;
     println!("============================================================");
+    println!("Version: {}", reader.version());
+    let mut blk_buf = vec![];
     for idx in reader.index_iterator() {
         let tr = idx.time_range();
         let mut buffer = String::with_capacity(1024);
         let mut idx_points_cnt = 0_usize;
+        let mut val_encodings: BTreeMap<&'static str, usize> = BTreeMap::new();
+        let (mut raw_size, mut encoded_size) = (0_usize, 0_usize);
         for blk in idx.block_iterator() {
             buffer.push_str(
                 format!(
-                    "\tBlock | FieldId: {}, MinTime: {}, MaxTime: {}, Count: {}, Offset: {}, Size: {}, ValOffset: {}\n",
+                    "\tBlock | FieldId: {}, MinTime: {}, MaxTime: {}, Count: {}, Offset: {}, Size: {}, ValOffset: {}",
                     blk.field_id(), blk.min_ts(), blk.max_ts(), blk.count(), blk.offset(), blk.size(), blk.val_off()
                 ).as_str()
             );
+            if let Ok(size) = reader.get_raw_data(&blk, &mut blk_buf).await {
+                let val_off = (blk.val_off() - blk.offset()) as usize;
+                let ts_encoding = get_encoding(&blk_buf[4..val_off]);
+                let val_encoding = get_encoding(&blk_buf[val_off + 4..size]);
+                buffer.push_str(
+                    format!(
+                        ", TsEncoding: {}, ValEncoding: {}",
+                        ts_encoding.as_str(),
+                        val_encoding.as_str()
+                    )
+                    .as_str(),
+                );
+                *val_encodings.entry(val_encoding.as_str()).or_default() += 1;
+                if let Ok(data_block) =
+                    decode_data_block(&blk_buf[..size], blk.field_type(), val_off as u64)
+                {
+                    raw_size += data_block.raw_size();
+                    encoded_size += size;
+                }
+            }
+            if let Some(stats) = blk.stats() {
+                let field_type = blk.field_type();
+                let display = |v: Option<ScalarValue>| {
+                    v.map(|v| v.to_string())
+                        .unwrap_or_else(|| "None".to_string())
+                };
+                buffer.push_str(
+                    format!(
+                        ", Min: {}, Max: {}, Sum: {}, NullCount: {}",
+                        display(stats.min(field_type)),
+                        display(stats.max(field_type)),
+                        display(stats.sum(field_type)),
+                        stats.null_count()
+                    )
+                    .as_str(),
+                );
+            }
+            buffer.push('\n');
             points_cnt += blk.count() as usize;
             idx_points_cnt += blk.count() as usize;
         }
@@ -163,6 +207,22 @@ pub async fn print_tsm_statistics(path: impl AsRef<Path>, show_tombstone: bool)
                  idx_points_cnt);
         println!("------------------------------------------------------------");
         println!("{}", buffer);
+        println!("------------------------------------------------------------");
+        println!(
+            "Compression | ValEncodings: {}, RawSize: {}, EncodedSize: {}, Ratio: {:.2}",
+            val_encodings
+                .iter()
+                .map(|(encoding, count)| format!("{encoding}({count})"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            raw_size,
+            encoded_size,
+            if encoded_size == 0 {
+                0_f64
+            } else {
+                raw_size as f64 / encoded_size as f64
+            }
+        );
         if show_tombstone {
             println!("------------------------------------------------------------");
             print!("Tombstone | ");
@@ -188,26 +248,80 @@ pub async fn print_tsm_statistics(path: impl AsRef<Path>, show_tombstone: bool)
     println!("PointsCount: {}", points_cnt);
 }
 
-pub async fn load_index(tsm_id: u64, reader: Arc<AsyncFile>) -> ReadTsmResult<Index> {
-    let len = reader.len();
-    if len < FOOTER_SIZE as u64 {
+/// Reads the version in header of a TSM file.
+async fn read_version(reader: &dyn IFile) -> ReadTsmResult<u8> {
+    if reader.len() < HEADER_SIZE as u64 {
         return Err(ReadTsmError::Invalid {
-            reason: format!(
-                "TSM file ({}) size less than FOOTER_SIZE({})",
-                tsm_id, FOOTER_SIZE
-            ),
+            reason: format!("TSM file size less than HEADER_SIZE({})", HEADER_SIZE),
+        });
+    }
+    let mut header = [0_u8; HEADER_SIZE];
+    reader.read_at(0, &mut header).await.context(ReadIOSnafu)?;
+    let version = header[HEADER_SIZE - 1];
+    if !(TSM_VERSION_V1..=TSM_VERSION).contains(&version) {
+        return Err(ReadTsmError::Invalid {
+            reason: format!("TSM file version {} is not supported", version),
         });
     }
-    let mut buf = [0u8; FOOTER_SIZE];
+    Ok(version)
+}
 
-    // Read index data offset
+/// Reads the footer of a TSM file, returns the bloom filter of field ids,
+/// the offset of index data and the size of the footer.
+async fn read_footer(reader: &dyn IFile, version: u8) -> ReadTsmResult<(BloomFilter, u64, u64)> {
+    let len = reader.len();
+    if version < TSM_VERSION_V3 {
+        if len < (HEADER_SIZE + FOOTER_SIZE_V2) as u64 {
+            return Err(ReadTsmError::Invalid {
+                reason: format!("TSM file size less than FOOTER_SIZE({})", FOOTER_SIZE_V2),
+            });
+        }
+        let mut footer = [0_u8; FOOTER_SIZE_V2];
+        reader
+            .read_at(len - FOOTER_SIZE_V2 as u64, &mut footer)
+            .await
+            .context(ReadIOSnafu)?;
+        let bloom_filter = BloomFilter::with_data(&footer[..BLOOM_FILTER_SIZE_V2]);
+        let index_offset = decode_be_u64(&footer[BLOOM_FILTER_SIZE_V2..]);
+        return Ok((bloom_filter, index_offset, FOOTER_SIZE_V2 as u64));
+    }
+
+    if len < (HEADER_SIZE + FOOTER_TAIL_SIZE) as u64 {
+        return Err(ReadTsmError::Invalid {
+            reason: format!("TSM file size less than FOOTER_SIZE({})", FOOTER_TAIL_SIZE),
+        });
+    }
+    let mut footer_tail = [0_u8; FOOTER_TAIL_SIZE];
+    reader
+        .read_at(len - FOOTER_TAIL_SIZE as u64, &mut footer_tail)
+        .await
+        .context(ReadIOSnafu)?;
+    let bloom_filter_size = byte_utils::decode_be_u32(&footer_tail[..4]) as u64;
+    let bloom_filter_hashes = footer_tail[4] as u32;
+    let index_offset = decode_be_u64(&footer_tail[5..]);
+    let footer_size = bloom_filter_size + FOOTER_TAIL_SIZE as u64;
+    if len < HEADER_SIZE as u64 + footer_size {
+        return Err(ReadTsmError::Invalid {
+            reason: format!("TSM file size less than FOOTER_SIZE({})", footer_size),
+        });
+    }
+    let mut bloom_filter_data = vec![0_u8; bloom_filter_size as usize];
     reader
-        .read_at(len - FOOTER_SIZE as u64, &mut buf)
+        .read_at(len - footer_size, &mut bloom_filter_data)
         .await
         .context(ReadIOSnafu)?;
-    let bloom_filter = BloomFilter::with_data(&buf[..BLOOM_FILTER_SIZE]);
-    let offset = decode_be_u64(&buf[BLOOM_FILTER_SIZE..]);
-    if offset > len - FOOTER_SIZE as u64 {
+    let bloom_filter = BloomFilter::with_data_and_hashes(&bloom_filter_data, bloom_filter_hashes);
+    Ok((bloom_filter, index_offset, footer_size))
+}
+
+pub async fn load_index(tsm_id: u64, reader: Arc<dyn IFile>) -> ReadTsmResult<Index> {
+    let len = reader.len();
+    let version = read_version(reader.as_ref()).await?;
+    let blk_meta_size = block_meta_size(version);
+
+    // Read bloom filter and index data offset
+    let (bloom_filter, offset, footer_size) = read_footer(reader.as_ref(), version).await?;
+    if offset > len - footer_size {
         return Err(ReadTsmError::Invalid {
             reason: format!(
                 "TSM file ({}) size less than index offset({})",
@@ -215,7 +329,7 @@ pub async fn load_index(tsm_id: u64, reader: Arc<AsyncFile>) -> ReadTsmResult<In
             ),
         });
     }
-    let data_len = (len - offset - FOOTER_SIZE as u64) as usize;
+    let data_len = (len - offset - footer_size) as usize;
     // TODO if data_len is too big, read data part in parts and do not store it.
     let mut data = vec![0_u8; data_len];
     // Read index data
@@ -225,12 +339,12 @@ pub async fn load_index(tsm_id: u64, reader: Arc<AsyncFile>) -> ReadTsmResult<In
         .context(ReadIOSnafu)?;
 
     // Decode index data
-    let assumed_field_count = (data_len / (INDEX_META_SIZE + BLOCK_META_SIZE)) + 1;
+    let assumed_field_count = (data_len / (INDEX_META_SIZE + blk_meta_size)) + 1;
     let mut field_id_offs: Vec<(FieldId, usize)> = Vec::with_capacity(assumed_field_count);
     let mut pos = 0_usize;
     while pos < data_len {
         field_id_offs.push((decode_be_u64(&data[pos..pos + 8]), pos));
-        pos += INDEX_META_SIZE + BLOCK_META_SIZE * decode_be_u16(&data[pos + 9..pos + 11]) as usize;
+        pos += INDEX_META_SIZE + blk_meta_size * decode_be_u16(&data[pos + 9..pos + 11]) as usize;
     }
 
     // Sort by field id
@@ -239,6 +353,7 @@ pub async fn load_index(tsm_id: u64, reader: Arc<AsyncFile>) -> ReadTsmResult<In
 
     Ok(Index::new(
         tsm_id,
+        version,
         Arc::new(bloom_filter),
         data,
         field_id_offs,
@@ -251,7 +366,7 @@ pub struct IndexReader {
 }
 
 impl IndexReader {
-    pub async fn open(tsm_id: u64, reader: Arc<AsyncFile>) -> Result<Self> {
+    pub async fn open(tsm_id: u64, reader: Arc<dyn IFile>) -> Result<Self> {
         let idx = load_index(tsm_id, reader)
             .await
             .context(error::ReadTsmSnafu)?;
@@ -378,15 +493,16 @@ impl BlockMetaIterator {
         debug_assert!(min_ts <= max_ts, "time_ranges invalid: {:#?}", time_ranges);
 
         self.time_ranges = Some(time_ranges);
+        let blk_meta_size = self.index_ref.block_meta_size();
         let base = self.index_offset + INDEX_META_SIZE;
-        let sli = &self.index_ref.data()[base..base + self.block_count as usize * BLOCK_META_SIZE];
+        let sli = &self.index_ref.data()[base..base + self.block_count as usize * blk_meta_size];
         let mut pos = 0_usize;
         let mut idx = 0_usize;
         // Find `idx` of index blocks that time_range.min_ts <= block.max_ts .
         while pos < sli.len() {
             if min_ts > decode_be_i64(&sli[pos + 8..pos + 16]) {
                 // If time_range.min_ts > block.max_ts, go on to check next block.
-                pos += BLOCK_META_SIZE;
+                pos += blk_meta_size;
                 idx += 1;
             } else {
                 // If time_range.min_ts <= block.max_ts, This block may be the start block.
@@ -413,7 +529,7 @@ impl BlockMetaIterator {
             } else {
                 // If time_range.max_ts >= block.max_ts, go on to check next block.
                 self.block_meta_idx_end += 1;
-                pos += BLOCK_META_SIZE;
+                pos += blk_meta_size;
             }
         }
     }
@@ -437,7 +553,7 @@ impl Iterator for BlockMetaIterator {
                     self.field_type,
                 );
                 self.block_meta_idx += 1;
-                self.block_offset += BLOCK_META_SIZE;
+                self.block_offset += self.index_ref.block_meta_size();
                 if time_ranges.overlaps(&(block_meta.min_ts(), block_meta.max_ts()).into()) {
                     ret = Some(block_meta);
                     break;
@@ -452,7 +568,7 @@ impl Iterator for BlockMetaIterator {
                 self.field_type,
             );
             self.block_meta_idx += 1;
-            self.block_offset += BLOCK_META_SIZE;
+            self.block_offset += self.index_ref.block_meta_size();
             ret = Some(block_meta);
         }
 
@@ -463,16 +579,27 @@ impl Iterator for BlockMetaIterator {
 #[derive(Clone)]
 pub struct TsmReader {
     file_id: u64,
-    reader: Arc<AsyncFile>,
+    reader: Arc<dyn IFile>,
     index_reader: Arc<IndexReader>,
     tombstone: Arc<RwLock<TsmTombstone>>,
+    data_block_cache: Option<Arc<DataBlockCache>>,
 }
 
 impl TsmReader {
     pub async fn open(tsm_path: impl AsRef<Path>) -> Result<Self> {
+        let tsm = Arc::new(file_manager::open_direct_file(&tsm_path).await?);
+        Self::open_with_file(tsm_path, tsm).await
+    }
+
+    /// Open a tsm file moved to the cold storage, tombstone of it
+    /// is still at the parent directory of `tsm_path`.
+    pub async fn open_remote(tsm_path: impl AsRef<Path>, file: RemoteFile) -> Result<Self> {
+        Self::open_with_file(tsm_path, Arc::new(file)).await
+    }
+
+    async fn open_with_file(tsm_path: impl AsRef<Path>, tsm: Arc<dyn IFile>) -> Result<Self> {
         let path = tsm_path.as_ref().to_path_buf();
         let file_id = file_utils::get_tsm_file_id_by_path(&path)?;
-        let tsm = Arc::new(file_manager::open_file(tsm_path).await?);
         let tsm_idx = IndexReader::open(file_id, tsm.clone()).await?;
         let tombstone_path = path.parent().unwrap_or_else(|| Path::new("/"));
         let tombstone = TsmTombstone::open(tombstone_path, file_id).await?;
@@ -481,29 +608,63 @@ impl TsmReader {
             reader: tsm,
             index_reader: Arc::new(tsm_idx),
             tombstone: Arc::new(RwLock::new(tombstone)),
+            data_block_cache: None,
         })
     }
 
+    /// Set the cache of decoded `DataBlock`s shared by readers.
+    pub fn with_data_block_cache(mut self, data_block_cache: Option<Arc<DataBlockCache>>) -> Self {
+        self.data_block_cache = data_block_cache;
+        self
+    }
+
     pub fn index_iterator(&self) -> IndexIterator {
         self.index_reader.iter()
     }
 
+    /// Returns the version in header of the TSM file.
+    pub fn version(&self) -> u8 {
+        self.index_reader.index_ref.version()
+    }
+
     pub fn index_iterator_opt(&self, field_id: FieldId) -> IndexIterator {
         self.index_reader.iter_opt(field_id)
     }
 
     /// Returns a DataBlock without tombstone
     pub async fn get_data_block(&self, block_meta: &BlockMeta) -> ReadTsmResult<DataBlock> {
-        let _blk_range = (block_meta.min_ts(), block_meta.max_ts());
-        let mut buf = vec![0_u8; block_meta.size() as usize];
-        let mut blk = read_data_block(
-            self.reader.clone(),
-            &mut buf,
-            block_meta.field_type(),
-            block_meta.offset(),
-            block_meta.val_off(),
-        )
-        .await?;
+        self.get_data_block_opt(block_meta, true).await
+    }
+
+    /// Returns a DataBlock without tombstone, the decoded DataBlock is put
+    /// into the data block cache only if `fill_cache` is true.
+    pub async fn get_data_block_opt(
+        &self,
+        block_meta: &BlockMeta,
+        fill_cache: bool,
+    ) -> ReadTsmResult<DataBlock> {
+        let cached = self
+            .data_block_cache
+            .as_ref()
+            .and_then(|c| c.get(self.file_id, block_meta.offset()));
+        let mut blk = match cached {
+            Some(blk) => blk.as_ref().clone(),
+            None => {
+                let mut buf = vec![0_u8; block_meta.size() as usize];
+                let blk = read_data_block(
+                    self.reader.clone(),
+                    &mut buf,
+                    block_meta.field_type(),
+                    block_meta.offset(),
+                    block_meta.val_off(),
+                )
+                .await?;
+                if let (true, Some(cache)) = (fill_cache, &self.data_block_cache) {
+                    cache.insert(self.file_id, block_meta.offset(), Arc::new(blk.clone()));
+                }
+                blk
+            }
+        };
         self.tombstone
             .read()
             .data_block_exclude_tombstones(block_meta.field_id(), &mut blk);
@@ -564,19 +725,19 @@ impl Debug for TsmReader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TsmReader")
             .field("id", &self.file_id)
-            .field("fd", &self.reader.fd())
+            .field("size", &self.reader.len())
             .finish()
     }
 }
 
 pub struct ColumnReader {
-    reader: Arc<AsyncFile>,
+    reader: Arc<dyn IFile>,
     inner: BlockMetaIterator,
     buf: Vec<u8>,
 }
 
 impl ColumnReader {
-    pub fn new(reader: Arc<AsyncFile>, inner: BlockMetaIterator) -> Self {
+    pub fn new(reader: Arc<dyn IFile>, inner: BlockMetaIterator) -> Self {
         Self {
             reader,
             inner,
@@ -609,7 +770,7 @@ impl ColumnReader {
 }
 
 async fn read_data_block(
-    reader: Arc<AsyncFile>,
+    reader: Arc<dyn IFile>,
     buf: &mut [u8],
     field_type: ValueType,
     offset: u64,
@@ -724,8 +885,9 @@ pub mod tsm_reader_tests {
     use std::path::{Path, PathBuf};
     use std::sync::Arc;
 
+    use datafusion::scalar::ScalarValue;
     use models::predicate::domain::{TimeRange, TimeRanges};
-    use models::{FieldId, Timestamp};
+    use models::{FieldId, PhysicalDType as ValueType, Timestamp};
     use snafu::ResultExt;
 
     use crate::error::{self, Error, Result};
@@ -733,7 +895,9 @@ pub mod tsm_reader_tests {
     use crate::file_utils;
     use crate::tsm::codec::DataBlockEncoding;
     use crate::tsm::tsm_writer_tests::write_to_tsm;
-    use crate::tsm::{BlockEntry, DataBlock, IndexEntry, IndexFile, TsmReader, TsmTombstone};
+    use crate::tsm::{
+        BlockEntry, DataBlock, IndexEntry, IndexFile, TsmReader, TsmTombstone, TSM_VERSION,
+    };
 
     async fn prepare(dir: impl AsRef<Path>) -> Result<(PathBuf, PathBuf)> {
         if file_manager::try_exists(&dir) {
@@ -994,4 +1158,33 @@ pub mod tsm_reader_tests {
         assert_eq!(blk_metas[3].max_ts, 12);
         assert_eq!(blk_metas[3].count, 4);
     }
+
+    #[tokio::test]
+    async fn test_block_statistics() {
+        let (tsm_file, _) = prepare("/tmp/test/tsm_reader/4").await.unwrap();
+        let reader = TsmReader::open(&tsm_file).await.unwrap();
+        assert_eq!(reader.version(), TSM_VERSION);
+
+        let u64_val = |v: u64| Some(ScalarValue::UInt64(Some(v)));
+        let mut stats = Vec::new();
+        for idx in reader.index_iterator_opt(2) {
+            for blk in idx.block_iterator() {
+                let blk_stats = blk.stats().unwrap();
+                assert_eq!(blk_stats.null_count(), 0);
+                stats.push((
+                    blk_stats.min(ValueType::Unsigned),
+                    blk_stats.max(ValueType::Unsigned),
+                    blk_stats.sum(ValueType::Unsigned),
+                ));
+            }
+        }
+        assert_eq!(
+            stats,
+            vec![
+                (u64_val(101), u64_val(104), u64_val(410)),
+                (u64_val(105), u64_val(108), u64_val(426)),
+                (u64_val(109), u64_val(112), u64_val(442)),
+            ]
+        );
+    }
 }
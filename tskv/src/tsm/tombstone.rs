@@ -26,7 +26,7 @@ use crate::{byte_utils, file_utils, Error, Result};
 const TOMBSTONE_FILE_SUFFIX: &str = ".tombstone";
 const FOOTER_MAGIC_NUMBER: u32 = u32::from_be_bytes([b'r', b'o', b'm', b'b']);
 const FOOTER_MAGIC_NUMBER_LEN: usize = 4;
-const ENTRY_LEN: usize = 24; // 8 + 8 + 8
+pub(crate) const ENTRY_LEN: usize = 24; // 8 + 8 + 8
 
 #[derive(Debug, Clone, Copy)]
 pub struct Tombstone {
@@ -13,8 +13,8 @@ use crate::file_system::file::IFile;
 use crate::file_system::file_manager;
 use crate::file_utils;
 use crate::tsm::{
-    BlockEntry, BlockMeta, DataBlock, IndexEntry, BLOCK_META_SIZE, BLOOM_FILTER_BITS,
-    INDEX_META_SIZE,
+    BlockEntry, BlockMeta, DataBlock, IndexEntry, BLOCK_META_SIZE, BLOOM_FILTER_FP_RATE,
+    INDEX_META_SIZE, TSM_VERSION,
 };
 
 // A TSM file is composed for four sections: header, blocks, index and the footer.
@@ -47,16 +47,32 @@ use crate::tsm::{
 // │ 8 bytes │1 byte│2 bytes│ 8 bytes │ 8 bytes │4 bytes │8 bytes │8 bytes │8 bytes│
 // └─────────┴──────┴───────┴─────────┴─────────┴────────┴────────┴────────┴───────┘
 //
+// Since version 2, each block meta in index is followed by block statistics:
+//
+// ┌───────┬─────────┬─────────┬─────────┬────────────┐
+// │ Flags │   Min   │   Max   │   Sum   │ Null Count │
+// │1 byte │ 8 bytes │ 8 bytes │ 8 bytes │  4 bytes   │
+// └───────┴─────────┴─────────┴─────────┴────────────┘
+//
 // ┌─────────────────────────┐
 // │ Footer                  │
 // ├───────────────┬─────────┤
 // │ Bloom Filter  │Index Ofs│
-// │ 8 bytes       │ 8 bytes │
+// │ 64 bytes      │ 8 bytes │
 // └───────────────┴─────────┘
+//
+// Since version 3, the bloom filter is sized by the number of field ids:
+//
+// ┌──────────────────────────────────────────────────────┐
+// │ Footer                                               │
+// ├──────────────┬─────────────┬───────────────┬─────────┤
+// │ Bloom Filter │ Bloom Size  │ Bloom Hashes  │Index Ofs│
+// │ N bytes      │ 4 bytes     │ 1 byte        │ 8 bytes │
+// └──────────────┴─────────────┴───────────────┴─────────┘
 
 const HEADER_LEN: u64 = 5;
 const TSM_MAGIC: [u8; 4] = 0x01346613_u32.to_be_bytes();
-const VERSION: [u8; 1] = [1];
+const VERSION: [u8; 1] = [TSM_VERSION];
 
 pub type WriteTsmResult<T, E = WriteTsmError> = std::result::Result<T, E>;
 
@@ -93,7 +109,6 @@ impl From<WriteTsmError> for Error {
 struct IndexBuf {
     index_offset: u64,
     buf: BTreeMap<FieldId, IndexEntry>,
-    bloom_filter: BloomFilter,
 }
 
 impl IndexBuf {
@@ -101,7 +116,6 @@ impl IndexBuf {
         Self {
             index_offset: 0,
             buf: BTreeMap::new(),
-            bloom_filter: BloomFilter::new(BLOOM_FILTER_BITS),
         }
     }
 
@@ -113,7 +127,15 @@ impl IndexBuf {
         let fid = ie.field_id;
         let idx = self.buf.entry(fid).or_insert(ie);
         idx.blocks.push(be);
-        self.bloom_filter.insert(&fid.to_be_bytes()[..]);
+    }
+
+    /// Build a bloom filter of all field ids in index.
+    pub fn bloom_filter(&self) -> BloomFilter {
+        let mut bloom_filter = BloomFilter::with_capacity(self.buf.len(), BLOOM_FILTER_FP_RATE);
+        for fid in self.buf.keys() {
+            bloom_filter.insert(&fid.to_be_bytes()[..]);
+        }
+        bloom_filter
     }
 
     pub async fn write_to(&self, writer: &mut FileCursor) -> WriteTsmResult<usize> {
@@ -170,6 +192,8 @@ pub struct TsmWriter {
     size: u64,
     max_size: u64,
     index_buf: IndexBuf,
+    /// Bloom filter of field ids, built when index is written.
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl TsmWriter {
@@ -184,7 +208,7 @@ impl TsmWriter {
         tmp_path_str.push(".tmp");
         let tmp_path = PathBuf::from(tmp_path_str);
 
-        let writer = file_manager::create_file(&tmp_path).await?.into();
+        let writer = file_manager::create_direct_file(&tmp_path).await?.into();
         let mut w = Self {
             tmp_path,
             final_path,
@@ -197,6 +221,7 @@ impl TsmWriter {
             size: 0,
             max_size,
             index_buf: IndexBuf::new(),
+            bloom_filter: None,
         };
         write_header_to(&mut w.writer)
             .await
@@ -336,12 +361,10 @@ impl TsmWriter {
 
         self.index_buf.set_index_offset(self.writer.pos());
         let len1 = self.index_buf.write_to(&mut self.writer).await?;
-        let len2 = write_footer_to(
-            &mut self.writer,
-            &self.index_buf.bloom_filter,
-            self.index_buf.index_offset,
-        )
-        .await?;
+        let bloom_filter = self.index_buf.bloom_filter();
+        let len2 =
+            write_footer_to(&mut self.writer, &bloom_filter, self.index_buf.index_offset).await?;
+        self.bloom_filter = Some(bloom_filter);
 
         Ok(len1 + len2)
     }
@@ -360,7 +383,10 @@ impl TsmWriter {
 
     /// Get a cloned `BloomFilter` from currently buffered index data.
     pub fn bloom_filter_cloned(&self) -> BloomFilter {
-        self.index_buf.bloom_filter.clone()
+        match &self.bloom_filter {
+            Some(bloom_filter) => bloom_filter.clone(),
+            None => self.index_buf.bloom_filter(),
+        }
     }
 }
 
@@ -504,6 +530,7 @@ async fn write_encoded_block_to(
             size: size as u64,
             // Encoded timestamps block need a 4-byte crc checksum together.
             val_offset: offset + block.ts.len() as u64 + 4,
+            stats: block.stats,
         },
     );
 
@@ -519,6 +546,8 @@ async fn write_footer_to(
         .write_vec(
             [
                 IoSlice::new(bloom_filter.bytes()),
+                IoSlice::new((bloom_filter.len() as u32).to_be_bytes().as_slice()),
+                IoSlice::new(&[bloom_filter.hashes() as u8]),
                 IoSlice::new(index_offset.to_be_bytes().as_slice()),
             ]
             .as_mut_slice(),
@@ -535,13 +564,17 @@ pub mod tsm_writer_tests {
 
     use models::FieldId;
     use snafu::ResultExt;
+    use utils::BloomFilter;
 
+    use super::HEADER_LEN;
     use crate::error::{self, Result};
     use crate::file_system::file_manager::{self};
     use crate::file_utils::{self, make_tsm_file_name};
     use crate::tsm::codec::DataBlockEncoding;
     use crate::tsm::tsm_reader_tests::read_and_check;
-    use crate::tsm::{DataBlock, TsmReader, TsmWriter};
+    use crate::tsm::{
+        DataBlock, TsmReader, TsmWriter, BLOOM_FILTER_SIZE_V2, FOOTER_TAIL_SIZE, TSM_VERSION_V2,
+    };
 
     const TEST_PATH: &str = "/tmp/test/tsm_writer";
 
@@ -612,4 +645,51 @@ pub mod tsm_writer_tests {
         let reader = TsmReader::open(tsm_file).await.unwrap();
         read_and_check(&reader, &data).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_tsm_write_bloom_filter() {
+        let data: HashMap<FieldId, Vec<DataBlock>> = (0..1000_u64)
+            .map(|fid| {
+                let block = DataBlock::U64 {
+                    ts: vec![1, 2],
+                    val: vec![fid, fid],
+                    enc: DataBlockEncoding::default(),
+                };
+                (fid, vec![block])
+            })
+            .collect();
+
+        let tsm_file = make_tsm_file_name(TEST_PATH, 2);
+        write_to_tsm(&tsm_file, &data).await.unwrap();
+
+        let reader = TsmReader::open(&tsm_file).await.unwrap();
+        let bloom_filter = reader.bloom_filter();
+        assert!(bloom_filter.len() > BLOOM_FILTER_SIZE_V2);
+        assert!(bloom_filter.hashes() > 1);
+        for fid in 0..1000_u64 {
+            assert!(bloom_filter.contains(&fid.to_be_bytes()));
+        }
+        read_and_check(&reader, &data).await.unwrap();
+
+        // Rewrite the footer in layout of version 2, it should still be readable.
+        let file_data = std::fs::read(&tsm_file).unwrap();
+        let tail = &file_data[file_data.len() - FOOTER_TAIL_SIZE..];
+        let bloom_filter_size = u32::from_be_bytes(tail[..4].try_into().unwrap()) as usize;
+        let footer_size = bloom_filter_size + FOOTER_TAIL_SIZE;
+        let mut old_bloom_filter = BloomFilter::new((BLOOM_FILTER_SIZE_V2 * 8) as u64);
+        for fid in 0..1000_u64 {
+            old_bloom_filter.insert(&fid.to_be_bytes());
+        }
+        let mut old_file_data = file_data[..file_data.len() - footer_size].to_vec();
+        old_file_data[HEADER_LEN as usize - 1] = TSM_VERSION_V2;
+        old_file_data.extend_from_slice(old_bloom_filter.bytes());
+        old_file_data.extend_from_slice(&tail[5..]);
+        let old_tsm_file = make_tsm_file_name(TEST_PATH, 3);
+        std::fs::write(&old_tsm_file, old_file_data).unwrap();
+
+        let reader = TsmReader::open(&old_tsm_file).await.unwrap();
+        assert_eq!(reader.version(), TSM_VERSION_V2);
+        assert_eq!(*reader.bloom_filter(), old_bloom_filter);
+        read_and_check(&reader, &data).await.unwrap();
+    }
 }
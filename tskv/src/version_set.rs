@@ -161,7 +161,11 @@ impl VersionSet {
         database: &str,
     ) -> Result<Option<DatabaseSchema>> {
         let owner = make_owner(tenant, database);
-        let db = self.dbs.get(&owner);
+        self.get_db_schema_by_owner(&owner).await
+    }
+
+    pub async fn get_db_schema_by_owner(&self, owner: &str) -> Result<Option<DatabaseSchema>> {
+        let db = self.dbs.get(owner);
         match db {
             None => Ok(None),
             Some(db) => Ok(Some(db.read().await.get_schema()?)),
@@ -5,6 +5,13 @@
 //! ## Record Data
 //! ```text
 //! # type = Write
+//! +------------+------------+------------+------------+-------------+--------------+-----------------+-----------+
+//! | 0: 1 byte  | 1: 8 bytes | 9: 4 bytes | 13: 1 byte | 14: 1 byte  | 15: 8 bytes  | 23: tenant_size |  n bytes  |
+//! +------------+------------+------------+------------+-------------+--------------+-----------------+-----------+
+//! |    type    |  sequence  |  vnode_id  |  precision | compression | tenant_size  |  tenant         |   data    |
+//! +------------+------------+------------+------------+-------------+--------------+-----------------+-----------+
+//!
+//! # type = Write (record data version 1, data is compressed by zstd)
 //! +------------+------------+------------+------------+--------------+-----------------+-----------+
 //! | 0: 1 byte  | 1: 8 bytes | 9: 4 bytes | 13: 1 byte | 14: 8 bytes  | 22: tenant_size |  n bytes  |
 //! +------------+------------+------------+------------+--------------+-----------------+-----------+
@@ -24,6 +31,13 @@
 //! +------------+------------+-------------+---------------+-----------------+---------------+---------+
 //! |    type    |  sequence  | tenant_size | database_size |  tenant         |  database     | table   |
 //! +------------+------------+-------------+---------------+-----------------+---------------+---------+
+//!
+//! # type = DeleteFromTable
+//! +------------+------------+------------+-------------+---------------+-------------+-----------------+---------------+------------+-----------+
+//! | 0: 1 byte  | 1: 8 bytes | 9: 4 bytes | 13: 8 bytes | 21: 4 bytes   | 25: 4 bytes | 29: tenant_size | database_size | table_size | n bytes   |
+//! +------------+------------+------------+-------------+---------------+-------------+-----------------+---------------+------------+-----------+
+//! |    type    |  sequence  |  vnode_id  | tenant_size | database_size | table_size  |  tenant         |  database     | table      | predicate |
+//! +------------+------------+------------+-------------+---------------+-------------+-----------------+---------------+------------+-----------+
 //! ```
 //!
 //! ## Footer
@@ -53,10 +67,11 @@ use self::reader::WalReader;
 use crate::context::GlobalSequenceContext;
 use crate::file_system::file_manager;
 use crate::kv_option::WalOptions;
-use crate::tsm::codec::{get_str_codec, StringCodec};
+use crate::tsm::codec::get_str_codec;
 use crate::version_set::VersionSet;
 pub use crate::wal::reader::{
-    print_wal_statistics, DeleteTableBlock, DeleteVnodeBlock, WalEntry, WriteBlock,
+    print_wal_statistics, repair_wal_file, DeleteFromTableBlock, DeleteTableBlock,
+    DeleteVnodeBlock, WalEntry, WriteBlock,
 };
 use crate::{error, file_utils, Result, TseriesFamilyId};
 
@@ -70,6 +85,7 @@ const ENTRY_HEADER_LEN: usize = 9;
 
 const ENTRY_VNODE_ID_LEN: usize = 4;
 const ENTRY_PRECISION_LEN: usize = 1;
+const ENTRY_COMPRESSION_LEN: usize = 1;
 const ENTRY_TENANT_SIZE_LEN: usize = 8;
 const ENTRY_DATABASE_SIZE_LEN: usize = 4;
 const ENTRY_TABLE_SIZE_LEN: usize = 4;
@@ -89,6 +105,7 @@ pub enum WalEntryType {
     Write = 1,
     DeleteVnode = 11,
     DeleteTable = 21,
+    DeleteFromTable = 31,
     Unknown = 127,
 }
 
@@ -98,6 +115,7 @@ impl From<u8> for WalEntryType {
             1 => WalEntryType::Write,
             11 => WalEntryType::DeleteVnode,
             21 => WalEntryType::DeleteTable,
+            31 => WalEntryType::DeleteFromTable,
             _ => WalEntryType::Unknown,
         }
     }
@@ -109,6 +127,7 @@ impl Display for WalEntryType {
             WalEntryType::Write => write!(f, "write"),
             WalEntryType::DeleteVnode => write!(f, "delete_vnode"),
             WalEntryType::DeleteTable => write!(f, "delete_table"),
+            WalEntryType::DeleteFromTable => write!(f, "delete_from_table"),
             WalEntryType::Unknown => write!(f, "unknown"),
         }
     }
@@ -120,7 +139,9 @@ pub enum WalTask {
         database: String,
         vnode_id: VnodeId,
         precision: Precision,
+        /// Points compressed by `compression`.
         points: Vec<u8>,
+        compression: Encoding,
         cb: WriteResultSender,
     },
     DeleteVnode {
@@ -135,28 +156,49 @@ pub enum WalTask {
         table: String,
         cb: WriteResultSender,
     },
+    DeleteFromTable {
+        tenant: String,
+        database: String,
+        vnode_id: VnodeId,
+        table: String,
+        predicate: Vec<u8>,
+        cb: WriteResultSender,
+    },
+    /// Moves WAL files of the vnode into the directory of the database, it's not
+    /// written into WAL.
+    MoveVnode {
+        tenant: String,
+        database: String,
+        vnode_id: VnodeId,
+        cb: WriteResultSender,
+    },
 }
 
 impl WalTask {
+    /// Creates a write task, points are compressed here by the caller, rather than
+    /// by the single WAL job.
     pub fn new_write(
         tenant: String,
         database: String,
         vnode_id: VnodeId,
         precision: Precision,
-        points: Vec<u8>,
-    ) -> (WalTask, WriteResultReceiver) {
+        points: &[u8],
+        compression: Encoding,
+    ) -> Result<(WalTask, WriteResultReceiver)> {
+        let points = encode_points(compression, points)?;
         let (cb, rx) = oneshot::channel();
-        (
+        Ok((
             WalTask::Write {
                 tenant,
                 database,
                 vnode_id,
                 precision,
                 points,
+                compression,
                 cb,
             },
             rx,
-        )
+        ))
     }
 
     pub fn new_delete_vnode(
@@ -193,6 +235,44 @@ impl WalTask {
         )
     }
 
+    pub fn new_delete_from_table(
+        tenant: String,
+        database: String,
+        vnode_id: VnodeId,
+        table: String,
+        predicate: Vec<u8>,
+    ) -> (WalTask, WriteResultReceiver) {
+        let (cb, rx) = oneshot::channel();
+        (
+            WalTask::DeleteFromTable {
+                tenant,
+                database,
+                vnode_id,
+                table,
+                predicate,
+                cb,
+            },
+            rx,
+        )
+    }
+
+    pub fn new_move_vnode(
+        tenant: String,
+        database: String,
+        vnode_id: VnodeId,
+    ) -> (WalTask, WriteResultReceiver) {
+        let (cb, rx) = oneshot::channel();
+        (
+            WalTask::MoveVnode {
+                tenant,
+                database,
+                vnode_id,
+                cb,
+            },
+            rx,
+        )
+    }
+
     pub fn new_from(wal_task: &WalTask, cb: WriteResultSender) -> WalTask {
         match wal_task {
             WalTask::Write {
@@ -201,6 +281,7 @@ impl WalTask {
                 vnode_id,
                 precision,
                 points,
+                compression,
                 ..
             } => WalTask::Write {
                 tenant: tenant.clone(),
@@ -208,6 +289,7 @@ impl WalTask {
                 vnode_id: *vnode_id,
                 precision: *precision,
                 points: points.clone(),
+                compression: *compression,
                 cb,
             },
             WalTask::DeleteVnode {
@@ -232,6 +314,32 @@ impl WalTask {
                 table: table.clone(),
                 cb,
             },
+            WalTask::DeleteFromTable {
+                tenant,
+                database,
+                vnode_id,
+                table,
+                predicate,
+                ..
+            } => WalTask::DeleteFromTable {
+                tenant: tenant.clone(),
+                database: database.clone(),
+                vnode_id: *vnode_id,
+                table: table.clone(),
+                predicate: predicate.clone(),
+                cb,
+            },
+            WalTask::MoveVnode {
+                tenant,
+                database,
+                vnode_id,
+                ..
+            } => WalTask::MoveVnode {
+                tenant: tenant.clone(),
+                database: database.clone(),
+                vnode_id: *vnode_id,
+                cb,
+            },
         }
     }
 
@@ -240,6 +348,8 @@ impl WalTask {
             WalTask::Write { .. } => WalEntryType::Write,
             WalTask::DeleteVnode { .. } => WalEntryType::DeleteVnode,
             WalTask::DeleteTable { .. } => WalEntryType::DeleteTable,
+            WalTask::DeleteFromTable { .. } => WalEntryType::DeleteFromTable,
+            WalTask::MoveVnode { .. } => WalEntryType::Unknown,
         }
     }
 
@@ -248,6 +358,8 @@ impl WalTask {
             WalTask::Write { cb, .. } => cb,
             WalTask::DeleteVnode { cb, .. } => cb,
             WalTask::DeleteTable { cb, .. } => cb,
+            WalTask::DeleteFromTable { cb, .. } => cb,
+            WalTask::MoveVnode { cb, .. } => cb,
         }
     }
 
@@ -269,6 +381,12 @@ impl WalTask {
             WalTask::DeleteTable {
                 tenant, database, ..
             } => make_owner(tenant, database),
+            WalTask::DeleteFromTable {
+                tenant, database, ..
+            } => make_owner(tenant, database),
+            WalTask::MoveVnode {
+                tenant, database, ..
+            } => make_owner(tenant, database),
         }
     }
     pub fn vnode_id(&self) -> Option<TseriesFamilyId> {
@@ -277,6 +395,8 @@ impl WalTask {
             WalTask::DeleteVnode { vnode_id, .. } => Some(*vnode_id),
             //todo: change delete table to delete time series;
             WalTask::DeleteTable { .. } => None,
+            WalTask::DeleteFromTable { vnode_id, .. } => Some(*vnode_id),
+            WalTask::MoveVnode { vnode_id, .. } => Some(*vnode_id),
         }
     }
 }
@@ -354,6 +474,41 @@ impl VnodeWal {
         Ok(())
     }
 
+    /// Moves WAL files of the vnode to `wal_dir`, when the vnode is moved to
+    /// another database, then starts writing a new WAL file in it.
+    async fn move_dir(&mut self, wal_dir: PathBuf) -> Result<()> {
+        trace::info!(
+            "Moving WAL of vnode {} from '{}' to '{}'",
+            self.vnode_id,
+            self.wal_dir.display(),
+            wal_dir.display()
+        );
+        let max_sequence = self.current_wal.max_sequence();
+        if self.current_wal.max_sequence() <= self.current_wal.min_sequence() {
+            let min_sequence = self.current_wal.min_sequence();
+            self.current_wal.set_max_sequence(min_sequence);
+        } else {
+            self.current_wal.set_max_sequence(max_sequence - 1);
+        }
+        self.old_file_max_sequence
+            .insert(self.current_wal.id(), self.current_wal.max_sequence());
+        self.total_file_size += self.current_wal.close().await? as u64;
+        file_utils::rename(&self.wal_dir, &wal_dir).await?;
+
+        let new_file_id = self.current_wal.id() + 1;
+        let new_file = writer::WalWriter::open(
+            self.config.clone(),
+            new_file_id,
+            file_utils::make_wal_file(&wal_dir, new_file_id),
+            max_sequence,
+        )
+        .await?;
+        self.total_file_size += new_file.size();
+        self.current_wal = new_file;
+        self.wal_dir = wal_dir;
+        Ok(())
+    }
+
     pub async fn check_to_delete(&mut self, min_seq: u64) {
         let mut old_files_to_delete: Vec<u64> = Vec::new();
         for (old_file_id, old_file_max_seq) in self.old_file_max_sequence.iter() {
@@ -404,10 +559,11 @@ impl VnodeWal {
                 vnode_id,
                 precision,
                 points,
+                compression,
                 cb,
             } => (
                 self.current_wal
-                    .write(tenant, vnode_id, precision, points)
+                    .write(tenant, vnode_id, precision, compression, points)
                     .await,
                 cb,
             ),
@@ -431,6 +587,21 @@ impl VnodeWal {
                 self.current_wal.delete_table(tenant, database, table).await,
                 cb,
             ),
+            WalTask::DeleteFromTable {
+                tenant,
+                database,
+                vnode_id,
+                table,
+                predicate,
+                cb,
+            } => (
+                self.current_wal
+                    .delete_from_table(tenant, database, vnode_id, table, predicate)
+                    .await,
+                cb,
+            ),
+            // WAL files are moved by WalManager.
+            WalTask::MoveVnode { cb, .. } => (Ok((0, 0)), cb),
         };
         let send_ret = match write_ret {
             Ok((seq, size)) => {
@@ -518,16 +689,10 @@ impl WalManager {
                 let file_names = file_manager::list_file_names(wal_dir.clone());
                 for f in file_names {
                     let file_path = wal_dir.join(&f);
-                    match tokio::fs::metadata(&file_path).await {
-                        Ok(m) => {
-                            total_file_size += m.len();
-                        }
-                        Err(e) => {
-                            trace::error!("Failed to get WAL file metadata for '{}': {:?}", &f, e)
-                        }
-                    }
-                    match reader::read_footer(file_path).await {
-                        Ok(Some((_, max_seq))) => match file_utils::get_wal_file_id(&f) {
+                    // WAL files without footer were not closed normally, truncate the torn
+                    // bytes after the last valid record, and get max sequence by records.
+                    match reader::repair_wal_file(&file_path).await {
+                        Ok((max_seq, _)) => match file_utils::get_wal_file_id(&f) {
                             Ok(file_id) => {
                                 old_file_max_sequence.insert(file_id, max_seq);
                             }
@@ -535,9 +700,16 @@ impl WalManager {
                                 trace::error!("Failed to parse WAL file name for '{}': {:?}", &f, e)
                             }
                         },
-                        Ok(None) => trace::warn!("Failed to parse WAL file footer for '{}'", &f),
                         Err(e) => {
-                            trace::warn!("Failed to parse WAL file footer for '{}': {:?}", &f, e)
+                            trace::warn!("Failed to repair WAL file '{}': {:?}", &f, e)
+                        }
+                    }
+                    match tokio::fs::metadata(&file_path).await {
+                        Ok(m) => {
+                            total_file_size += m.len();
+                        }
+                        Err(e) => {
+                            trace::error!("Failed to get WAL file metadata for '{}': {:?}", &f, e)
                         }
                     }
                 }
@@ -548,9 +720,7 @@ impl WalManager {
                     file_utils::get_wal_file_id,
                 ) {
                     Some((_, id)) => {
-                        let path = file_utils::make_wal_file(&wal_dir, id);
-                        let (_, max_seq) =
-                            reader::read_footer(&path).await?.unwrap_or((1_u64, 1_u64));
+                        let max_seq = old_file_max_sequence.get(&id).copied().unwrap_or(1);
                         (max_seq + 1, id + 1)
                     }
                     None => (1_u64, 1_u64),
@@ -619,8 +789,31 @@ impl WalManager {
                     trace::error!("Failed to send wal delete table result: {:?}", e);
                 }
             }
+            Some(vnode_id) if matches!(wal_task, WalTask::MoveVnode { .. }) => {
+                let wal_dir = self
+                    .config
+                    .wal_dir(&wal_task.owner(), &vnode_id.to_string());
+                let res = match self.wal_set.get_mut(&vnode_id) {
+                    Some(vnode_wal) if vnode_wal.wal_dir != wal_dir => {
+                        vnode_wal.move_dir(wal_dir).await.map(|_| (0, 0))
+                    }
+                    _ => Ok((0, 0)),
+                };
+                if let Err(e) = wal_task.write_wal_result_sender().send(res) {
+                    trace::error!("Failed to send wal move vnode result: {:?}", e);
+                }
+            }
             Some(vnode_id) => {
                 if let Some(vnode_wal) = self.wal_set.get_mut(&vnode_id) {
+                    // The vnode was moved to another database, e.g. the database was renamed.
+                    let wal_dir = self
+                        .config
+                        .wal_dir(&wal_task.owner(), &vnode_id.to_string());
+                    if vnode_wal.wal_dir != wal_dir {
+                        if let Err(e) = vnode_wal.move_dir(wal_dir).await {
+                            trace::error!("Failed to move WAL of vnode {}: {:?}", vnode_id, e);
+                        }
+                    }
                     vnode_wal
                         .write(wal_task, self.global_seq_ctx.min_seq())
                         .await;
@@ -691,25 +884,32 @@ impl WalManager {
 
 pub struct WalDecoder {
     buffer: Vec<MiniVec<u8>>,
-    decoder: Box<dyn StringCodec + Send + Sync>,
 }
 
 impl WalDecoder {
     pub fn new() -> Self {
-        Self {
-            buffer: Vec::new(),
-            decoder: get_str_codec(Encoding::Zstd),
-        }
+        Self { buffer: Vec::new() }
     }
-    pub fn decode(&mut self, data: &[u8]) -> Result<Option<MiniVec<u8>>> {
+
+    /// Decompress the data of a write entry by it's compression algorithm.
+    pub fn decode(&mut self, block: &WriteBlock) -> Result<Option<MiniVec<u8>>> {
         self.buffer.truncate(0);
-        self.decoder
-            .decode(data, &mut self.buffer)
+        get_str_codec(block.compression())
+            .decode(block.points(), &mut self.buffer)
             .context(error::DecodeSnafu)?;
         Ok(self.buffer.drain(..).next())
     }
 }
 
+/// Compress the data of a write entry, returns the compressed data.
+fn encode_points(compression: Encoding, points: &[u8]) -> Result<Vec<u8>> {
+    let mut enc_points = Vec::with_capacity(points.len() / 2);
+    get_str_codec(compression)
+        .encode(&[points], &mut enc_points)
+        .context(error::EncodeSnafu)?;
+    Ok(enc_points)
+}
+
 #[cfg(test)]
 mod test {
     use core::panic;
@@ -718,7 +918,6 @@ mod test {
     use std::sync::Arc;
 
     use minivec::MiniVec;
-    use models::codec::Encoding;
     use models::schema::{make_owner, Precision};
     use models::Timestamp;
     use protos::models::FieldType;
@@ -731,11 +930,10 @@ mod test {
     use crate::file_system::file_manager::list_file_names;
     use crate::kv_option::WalOptions;
     use crate::memcache::FieldVal;
-    use crate::tsm::codec::{get_str_codec, StringCodec};
     use crate::version_set::VersionSet;
     use crate::wal::reader::{WalEntry, WalReader};
-    use crate::wal::{WalManager, WalTask};
-    use crate::{error, Error, Result};
+    use crate::wal::{WalDecoder, WalManager, WalTask};
+    use crate::{Error, Result};
 
     fn random_write_data() -> Vec<u8> {
         let mut fbb = flatbuffers::FlatBufferBuilder::new();
@@ -785,7 +983,7 @@ mod test {
             let path = wal_dir.join(wal_file);
 
             let mut reader = WalReader::open(&path).await.unwrap();
-            let decoder = get_str_codec(Encoding::Zstd);
+            let mut decoder = WalDecoder::new();
             println!("Reading data from wal file '{}'", path.display());
             loop {
                 match reader.next_wal_entry().await {
@@ -793,19 +991,17 @@ mod test {
                         println!("Reading entry from wal file '{}'", path.display());
                         match entry_block.entry {
                             WalEntry::Write(entry) => {
-                                let ety_data = entry.points();
+                                let ety_data = decoder.decode(&entry).unwrap().unwrap();
                                 let ori_data = match data_iter.next() {
                                     Some(d) => d,
                                     None => {
                                         panic!("unexpected data to compare that is less than file count.")
                                     }
                                 };
+                                assert_eq!(ety_data.as_slice(), ori_data.as_slice());
                                 if is_flatbuffers {
-                                    let mut data_buf = Vec::new();
-                                    decoder.decode(ety_data, &mut data_buf).unwrap();
-                                    assert_eq!(data_buf[0].as_slice(), ori_data.as_slice());
                                     if let Err(e) =
-                                        flatbuffers::root::<fb_models::Points>(&data_buf[0])
+                                        flatbuffers::root::<fb_models::Points>(&ety_data)
                                     {
                                         panic!(
                                             "unexpected data in wal file, ignored file '{}' because '{}'",
@@ -813,12 +1009,11 @@ mod test {
                                             e
                                         );
                                     }
-                                } else {
-                                    assert_eq!(ety_data, ori_data.as_slice());
                                 }
                             }
                             WalEntry::DeleteVnode(_) => todo!(),
                             WalEntry::DeleteTable(_) => todo!(),
+                            WalEntry::DeleteFromTable(_) => todo!(),
                             WalEntry::Unknown => todo!(),
                         }
                     }
@@ -870,8 +1065,10 @@ mod test {
                     database.to_string(),
                     vnode_id,
                     Precision::NS,
-                    data,
-                );
+                    &data,
+                    mgr.config.compression,
+                )
+                .unwrap();
                 mgr.write(wal_task).await.unwrap();
                 let (seq, _) = rx.await.unwrap().unwrap();
                 assert_eq!(i, seq)
@@ -929,8 +1126,10 @@ mod test {
                     database.to_string(),
                     vnode_id,
                     Precision::NS,
-                    data,
-                );
+                    &data,
+                    mgr.config.compression,
+                )
+                .unwrap();
                 mgr.write(wal_task).await.unwrap();
                 let (write_seq, _) = rx.await.unwrap().unwrap();
                 assert_eq!(seq, write_seq)
@@ -964,27 +1163,21 @@ mod test {
             )
             .await
             .unwrap();
-            let coder = get_str_codec(Encoding::Zstd);
             let mut data_vec: Vec<Vec<u8>> = Vec::new();
 
             for _i in 0..10 {
                 let data = random_write_data();
                 data_vec.push(data.clone());
 
-                let mut enc_points = Vec::new();
-                coder
-                    .encode(&[&data], &mut enc_points)
-                    .map_err(|_| Error::ChannelSend {
-                        source: crate::error::ChannelSendError::WalTask,
-                    })
-                    .unwrap();
                 let (wal_task, rx) = WalTask::new_write(
                     tenant.clone(),
                     "test".to_string(),
                     0,
                     Precision::NS,
-                    enc_points,
-                );
+                    &data,
+                    mgr.config.compression,
+                )
+                .unwrap();
                 mgr.write(wal_task).await.unwrap();
                 rx.await.unwrap().unwrap();
             }
@@ -999,7 +1192,6 @@ mod test {
         max_ts: i64,
         tenant: String,
         wal_mgr: &mut WalManager,
-        coder: Box<dyn StringCodec + Send + Sync>,
         data_vec: &mut Vec<Vec<u8>>,
         wrote_data: &mut HashMap<String, Vec<(Timestamp, FieldVal)>>,
     ) {
@@ -1011,20 +1203,15 @@ mod test {
                 wrote_data.entry(col_name).or_default().extend(values);
             }
 
-            let mut enc_points = Vec::new();
-            coder
-                .encode(&[&data], &mut enc_points)
-                .map_err(|_| Error::ChannelSend {
-                    source: error::ChannelSendError::WalTask,
-                })
-                .unwrap();
             let (wal_task, rx) = WalTask::new_write(
                 tenant.clone(),
                 "dba".to_string(),
                 10,
                 Precision::NS,
-                enc_points,
-            );
+                &data,
+                wal_mgr.config.compression,
+            )
+            .unwrap();
             wal_mgr.write(wal_task).await.unwrap();
             rx.await.unwrap().unwrap();
         }
@@ -7,12 +7,13 @@ use protos::models_helper::print_points;
 use snafu::ResultExt;
 
 use super::{
-    WalEntryType, ENTRY_DATABASE_SIZE_LEN, ENTRY_HEADER_LEN, ENTRY_PRECISION_LEN,
-    ENTRY_TENANT_SIZE_LEN, ENTRY_VNODE_ID_LEN, FOOTER_MAGIC_NUMBER,
+    WalDecoder, WalEntryType, ENTRY_COMPRESSION_LEN, ENTRY_DATABASE_SIZE_LEN, ENTRY_HEADER_LEN,
+    ENTRY_PRECISION_LEN, ENTRY_TABLE_SIZE_LEN, ENTRY_TENANT_SIZE_LEN, ENTRY_VNODE_ID_LEN,
+    FOOTER_MAGIC_NUMBER,
 };
 use crate::byte_utils::{decode_be_u32, decode_be_u64};
 use crate::file_system::file_manager;
-use crate::tsm::codec::get_str_codec;
+use crate::record_file::RecordDataVersion;
 use crate::{error, record_file, Error, Result};
 
 /// Reads a wal file and parse footer, returns sequence range
@@ -75,8 +76,8 @@ impl WalReader {
 
     pub async fn next_wal_entry(&mut self) -> Result<Option<WalEntryBlock>> {
         loop {
-            let data = match self.inner.read_record().await {
-                Ok(r) => r.data,
+            let (data_version, data) = match self.inner.read_record().await {
+                Ok(r) => (r.data_version, r.data),
                 Err(Error::Eof) => {
                     return Ok(None);
                 }
@@ -86,7 +87,7 @@ impl WalReader {
                     return Err(Error::WalTruncated);
                 }
             };
-            return Ok(Some(WalEntryBlock::new(data)));
+            return Ok(Some(WalEntryBlock::new(data_version, data)));
         }
     }
 
@@ -129,7 +130,7 @@ pub struct WalEntryBlock {
 }
 
 impl WalEntryBlock {
-    pub fn new(buf: Vec<u8>) -> WalEntryBlock {
+    pub fn new(data_version: u8, buf: Vec<u8>) -> WalEntryBlock {
         if buf.len() < ENTRY_HEADER_LEN {
             return Self {
                 typ: WalEntryType::Unknown,
@@ -140,9 +141,12 @@ impl WalEntryBlock {
         let seq = decode_be_u64(&buf[1..9]);
         let entry_type: WalEntryType = buf[0].into();
         let entry: WalEntry = match entry_type {
-            WalEntryType::Write => WalEntry::Write(WriteBlock::new(buf)),
+            WalEntryType::Write => WalEntry::Write(WriteBlock::new(data_version, buf)),
             WalEntryType::DeleteVnode => WalEntry::DeleteVnode(DeleteVnodeBlock::new(buf)),
             WalEntryType::DeleteTable => WalEntry::DeleteTable(DeleteTableBlock::new(buf)),
+            WalEntryType::DeleteFromTable => {
+                WalEntry::DeleteFromTable(DeleteFromTableBlock::new(buf))
+            }
             WalEntryType::Unknown => WalEntry::Unknown,
         };
         Self {
@@ -158,6 +162,7 @@ pub enum WalEntry {
     Write(WriteBlock),
     DeleteVnode(DeleteVnodeBlock),
     DeleteTable(DeleteTableBlock),
+    DeleteFromTable(DeleteFromTableBlock),
     Unknown,
 }
 
@@ -165,21 +170,35 @@ pub enum WalEntry {
 /// - header: ENTRY_HEADER_LEN
 /// - vnode_id: ENTRY_VNODE_ID_LEN
 /// - precision: ENTRY_PRECISION_LEN
+/// - compression: ENTRY_COMPRESSION_LEN (since record data version 2)
 /// - tenant_size: ENTRY_TENANT_SIZE_LEN
 /// - tenant: tenant_size
 /// - data: ..
 #[derive(Debug, Clone, PartialEq)]
 pub struct WriteBlock {
     buf: Vec<u8>,
+    /// Length of the compression flag, 0 if the entry is written
+    /// in record data version 1, which data is always compressed by zstd.
+    compression_len: usize,
     tenant_size: usize,
 }
 
 impl WriteBlock {
-    pub fn new(buf: Vec<u8>) -> WriteBlock {
-        let tenatn_size_pos = ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN + ENTRY_PRECISION_LEN;
+    pub fn new(data_version: u8, buf: Vec<u8>) -> WriteBlock {
+        let compression_len = if data_version >= RecordDataVersion::V2 as u8 {
+            ENTRY_COMPRESSION_LEN
+        } else {
+            0
+        };
+        let tenatn_size_pos =
+            ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN + ENTRY_PRECISION_LEN + compression_len;
         let tenant_size =
             decode_be_u64(&buf[tenatn_size_pos..tenatn_size_pos + ENTRY_TENANT_SIZE_LEN]) as usize;
-        Self { buf, tenant_size }
+        Self {
+            buf,
+            compression_len,
+            tenant_size,
+        }
     }
 
     pub fn check_buf_size(size: usize) -> bool {
@@ -194,9 +213,21 @@ impl WriteBlock {
         Precision::from(self.buf[ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN])
     }
 
+    /// Returns the compression algorithm of data.
+    pub fn compression(&self) -> Encoding {
+        if self.compression_len == 0 {
+            Encoding::Zstd
+        } else {
+            Encoding::from(self.buf[ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN + ENTRY_PRECISION_LEN])
+        }
+    }
+
     pub fn tenant(&self) -> &[u8] {
-        let tenant_pos =
-            ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN + ENTRY_PRECISION_LEN + ENTRY_TENANT_SIZE_LEN;
+        let tenant_pos = ENTRY_HEADER_LEN
+            + ENTRY_VNODE_ID_LEN
+            + ENTRY_PRECISION_LEN
+            + self.compression_len
+            + ENTRY_TENANT_SIZE_LEN;
         &self.buf[tenant_pos..tenant_pos + self.tenant_size]
     }
 
@@ -206,10 +237,12 @@ impl WriteBlock {
         })
     }
 
+    /// Returns the compressed data, use `WalDecoder` to decompress it.
     pub fn points(&self) -> &[u8] {
         let points_pos = ENTRY_HEADER_LEN
             + ENTRY_VNODE_ID_LEN
             + ENTRY_PRECISION_LEN
+            + self.compression_len
             + ENTRY_TENANT_SIZE_LEN
             + self.tenant_size;
         &self.buf[points_pos..]
@@ -345,11 +378,166 @@ impl DeleteTableBlock {
     }
 }
 
+/// buf:
+/// - header: ENTRY_HEADER_LEN
+/// - vnode_id: ENTRY_VNODE_ID_LEN
+/// - tenant_size: ENTRY_TENANT_SIZE_LEN
+/// - database_size: ENTRY_DATABASE_SIZE_LEN
+/// - table_size: ENTRY_TABLE_SIZE_LEN
+/// - tenant: tenant_size
+/// - database: database_size
+/// - table: table_size
+/// - predicate: ..
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteFromTableBlock {
+    buf: Vec<u8>,
+    tenant_len: usize,
+    database_len: usize,
+    table_len: usize,
+}
+
+impl DeleteFromTableBlock {
+    pub fn new(buf: Vec<u8>) -> DeleteFromTableBlock {
+        let tenant_len_pos = ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN;
+        let tenant_len =
+            decode_be_u64(&buf[tenant_len_pos..tenant_len_pos + ENTRY_TENANT_SIZE_LEN]) as usize;
+        let database_len_pos = tenant_len_pos + ENTRY_TENANT_SIZE_LEN;
+        let database_len =
+            decode_be_u32(&buf[database_len_pos..database_len_pos + ENTRY_DATABASE_SIZE_LEN])
+                as usize;
+        let table_len_pos = database_len_pos + ENTRY_DATABASE_SIZE_LEN;
+        let table_len =
+            decode_be_u32(&buf[table_len_pos..table_len_pos + ENTRY_TABLE_SIZE_LEN]) as usize;
+        Self {
+            buf,
+            tenant_len,
+            database_len,
+            table_len,
+        }
+    }
+
+    pub fn check_buf_size(size: usize) -> bool {
+        size >= ENTRY_HEADER_LEN
+            + ENTRY_VNODE_ID_LEN
+            + ENTRY_TENANT_SIZE_LEN
+            + ENTRY_DATABASE_SIZE_LEN
+            + ENTRY_TABLE_SIZE_LEN
+    }
+
+    pub fn vnode_id(&self) -> VnodeId {
+        decode_be_u32(&self.buf[ENTRY_HEADER_LEN..ENTRY_HEADER_LEN + ENTRY_VNODE_ID_LEN])
+    }
+
+    pub fn tenant(&self) -> &[u8] {
+        let tenant_pos = ENTRY_HEADER_LEN
+            + ENTRY_VNODE_ID_LEN
+            + ENTRY_TENANT_SIZE_LEN
+            + ENTRY_DATABASE_SIZE_LEN
+            + ENTRY_TABLE_SIZE_LEN;
+        &self.buf[tenant_pos..tenant_pos + self.tenant_len]
+    }
+
+    pub fn tenant_utf8(&self) -> Result<&str> {
+        std::str::from_utf8(self.tenant()).with_context(|_| error::InvalidUtf8Snafu {
+            message: "wal::DeleteFromTableBlock::tenant",
+        })
+    }
+
+    pub fn database(&self) -> &[u8] {
+        let database_pos = ENTRY_HEADER_LEN
+            + ENTRY_VNODE_ID_LEN
+            + ENTRY_TENANT_SIZE_LEN
+            + ENTRY_DATABASE_SIZE_LEN
+            + ENTRY_TABLE_SIZE_LEN
+            + self.tenant_len;
+        &self.buf[database_pos..database_pos + self.database_len]
+    }
+
+    pub fn database_utf8(&self) -> Result<&str> {
+        std::str::from_utf8(self.database()).with_context(|_| error::InvalidUtf8Snafu {
+            message: "wal::DeleteFromTableBlock::database",
+        })
+    }
+
+    pub fn table(&self) -> &[u8] {
+        let table_pos = ENTRY_HEADER_LEN
+            + ENTRY_VNODE_ID_LEN
+            + ENTRY_TENANT_SIZE_LEN
+            + ENTRY_DATABASE_SIZE_LEN
+            + ENTRY_TABLE_SIZE_LEN
+            + self.tenant_len
+            + self.database_len;
+        &self.buf[table_pos..table_pos + self.table_len]
+    }
+
+    pub fn table_utf8(&self) -> Result<&str> {
+        std::str::from_utf8(self.table()).with_context(|_| error::InvalidUtf8Snafu {
+            message: "wal::DeleteFromTableBlock::table",
+        })
+    }
+
+    /// Returns the encoded `ResolvedPredicate` of this delete action.
+    pub fn predicate(&self) -> &[u8] {
+        let predicate_pos = ENTRY_HEADER_LEN
+            + ENTRY_VNODE_ID_LEN
+            + ENTRY_TENANT_SIZE_LEN
+            + ENTRY_DATABASE_SIZE_LEN
+            + ENTRY_TABLE_SIZE_LEN
+            + self.tenant_len
+            + self.database_len
+            + self.table_len;
+        &self.buf[predicate_pos..]
+    }
+}
+
+/// Reads all records of a wal file, and if the file was not closed normally,
+/// truncates the torn bytes after the last valid record. Returns max sequence
+/// of the wal file and count of bytes truncated.
+pub async fn repair_wal_file(path: impl AsRef<Path>) -> Result<(u64, u64)> {
+    let path = path.as_ref();
+    let reader = WalReader::open(path).await?;
+    if reader.has_footer() {
+        return Ok((reader.max_sequence(), 0));
+    }
+
+    let mut reader = reader.take_record_reader();
+    let mut max_sequence = 0_u64;
+    loop {
+        match reader.read_record().await {
+            Ok(r) => {
+                let block = WalEntryBlock::new(r.data_version, r.data);
+                max_sequence = max_sequence.max(block.seq);
+            }
+            Err(Error::Eof) | Err(Error::RecordFileInvalidDataSize { .. }) => break,
+            Err(Error::RecordFileHashCheckFailed { record, .. }) => {
+                trace::warn!(
+                    "WAL '{}': skipped a broken record at {}, {} bytes lost",
+                    path.display(),
+                    record.pos,
+                    record.data.len()
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let truncated = reader.truncate_invalid_tail().await?;
+    if truncated > 0 {
+        trace::warn!(
+            "WAL '{}': truncated {truncated} bytes after the last valid record, max sequence is {max_sequence}",
+            path.display()
+        );
+    }
+    Ok((max_sequence, truncated))
+}
+
 pub async fn print_wal_statistics(path: impl AsRef<Path>) {
     use protos::models as fb_models;
 
     let mut reader = WalReader::open(path).await.unwrap();
-    let decoder = get_str_codec(Encoding::Zstd);
+    let mut decoder = WalDecoder::new();
+    // Total size of data of write entries, (compressed, uncompressed).
+    let (mut compressed_size, mut uncompressed_size) = (0_usize, 0_usize);
     loop {
         match reader.next_wal_entry().await {
             Ok(Some(entry_block)) => {
@@ -363,10 +551,19 @@ pub async fn print_wal_statistics(path: impl AsRef<Path>) {
                             blk.vnode_id(),
                             blk.precision(),
                         );
-                        let ety_points = blk.points();
-                        let mut data_buf = Vec::with_capacity(ety_points.len());
-                        decoder.decode(ety_points, &mut data_buf).unwrap();
-                        match flatbuffers::root::<fb_models::Points>(&data_buf[0]) {
+                        let data_buf = match decoder.decode(&blk).unwrap() {
+                            Some(d) => d,
+                            None => continue,
+                        };
+                        compressed_size += blk.points().len();
+                        uncompressed_size += data_buf.len();
+                        println!(
+                            "Compression: {}, Size: {} B -> {} B",
+                            blk.compression().as_str(),
+                            data_buf.len(),
+                            blk.points().len(),
+                        );
+                        match flatbuffers::root::<fb_models::Points>(&data_buf) {
                             Ok(points) => {
                                 print_points(points);
                             }
@@ -389,6 +586,16 @@ pub async fn print_wal_statistics(path: impl AsRef<Path>) {
                             std::str::from_utf8(blk.table()).unwrap(),
                         );
                     }
+                    WalEntry::DeleteFromTable(blk) => {
+                        println!(
+                            "Tenant: {}, Database: {}, VnodeId: {}, Table: {}, PredicateSize: {}",
+                            std::str::from_utf8(blk.tenant()).unwrap(),
+                            std::str::from_utf8(blk.database()).unwrap(),
+                            blk.vnode_id(),
+                            std::str::from_utf8(blk.table()).unwrap(),
+                            blk.predicate().len(),
+                        );
+                    }
                     WalEntry::Unknown => {
                         println!("Unknown WAL entry type.");
                     }
@@ -408,17 +615,38 @@ pub async fn print_wal_statistics(path: impl AsRef<Path>) {
             }
         }
     }
+    if compressed_size > 0 {
+        println!(
+            "Write entries data size: {} B, compressed size: {} B, compression ratio: {:.2}",
+            uncompressed_size,
+            compressed_size,
+            uncompressed_size as f64 / compressed_size as f64,
+        );
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use models::codec::Encoding;
     use models::meta_data::VnodeId;
     use models::schema::Precision;
 
-    use crate::wal::reader::{DeleteTableBlock, DeleteVnodeBlock, WriteBlock};
-    use crate::wal::WalEntryType;
+    use crate::kv_option::WalOptions;
+    use crate::record_file::RecordDataVersion;
+    use crate::wal::reader::{
+        repair_wal_file, DeleteFromTableBlock, DeleteTableBlock, DeleteVnodeBlock, WalReader,
+        WriteBlock,
+    };
+    use crate::wal::writer::WalWriter;
+    use crate::wal::{WalEntryType, ENTRY_COMPRESSION_LEN};
+    use crate::Error;
 
     impl WriteBlock {
+        /// Build a WriteBlock with uncompressed points.
         pub fn build(
             seq: u64,
             tenant: &str,
@@ -431,12 +659,14 @@ mod test {
             buf.extend_from_slice(&seq.to_be_bytes());
             buf.extend_from_slice(&vnode_id.to_be_bytes());
             buf.push(precision as u8);
+            buf.push(Encoding::Null as u8);
             buf.extend_from_slice(&(tenant.len() as u64).to_be_bytes());
             buf.extend_from_slice(tenant.as_bytes());
             buf.extend_from_slice(&points);
 
             Self {
                 buf,
+                compression_len: ENTRY_COMPRESSION_LEN,
                 tenant_size: tenant.len(),
             }
         }
@@ -482,14 +712,66 @@ mod test {
         }
     }
 
+    impl DeleteFromTableBlock {
+        pub fn build(
+            seq: u64,
+            tenant: &str,
+            database: &str,
+            vnode_id: VnodeId,
+            table: &str,
+            predicate: Vec<u8>,
+        ) -> Self {
+            let mut buf = Vec::new();
+            let tenant_bytes = tenant.as_bytes();
+            let database_bytes = database.as_bytes();
+            let table_bytes = table.as_bytes();
+            buf.push(WalEntryType::DeleteFromTable as u8);
+            buf.extend_from_slice(&seq.to_be_bytes());
+            buf.extend_from_slice(&vnode_id.to_be_bytes());
+            buf.extend_from_slice(&(tenant_bytes.len() as u64).to_be_bytes());
+            buf.extend_from_slice(&(database_bytes.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&(table_bytes.len() as u32).to_be_bytes());
+            buf.extend_from_slice(tenant_bytes);
+            buf.extend_from_slice(database_bytes);
+            buf.extend_from_slice(table_bytes);
+            buf.extend_from_slice(&predicate);
+
+            Self {
+                buf,
+                tenant_len: tenant_bytes.len(),
+                database_len: database_bytes.len(),
+                table_len: table_bytes.len(),
+            }
+        }
+    }
+
     #[test]
     fn test_wal_blocks() {
         {
-            let block = WriteBlock::build(1, "tenant", 2, Precision::MS, vec![]);
+            let block = WriteBlock::build(1, "tenant", 2, Precision::MS, vec![3]);
             assert_eq!(block.tenant(), b"tenant");
             assert_eq!(block.tenant_utf8().unwrap(), "tenant");
             assert_eq!(block.vnode_id(), 2);
             assert_eq!(block.precision(), Precision::MS);
+            assert_eq!(block.compression(), Encoding::Null);
+            assert_eq!(block.points(), &[3]);
+        }
+        {
+            // WriteBlock of record data version 1 has no compression flag.
+            let mut buf = Vec::new();
+            buf.push(WalEntryType::Write as u8);
+            buf.extend_from_slice(&1_u64.to_be_bytes());
+            buf.extend_from_slice(&2_u32.to_be_bytes());
+            buf.push(Precision::MS as u8);
+            buf.extend_from_slice(&6_u64.to_be_bytes());
+            buf.extend_from_slice(b"tenant");
+            buf.push(3);
+            let block = WriteBlock::new(RecordDataVersion::V1 as u8, buf);
+            assert_eq!(block.tenant(), b"tenant");
+            assert_eq!(block.vnode_id(), 2);
+            assert_eq!(block.precision(), Precision::MS);
+            assert_eq!(block.compression(), Encoding::Zstd);
+            assert_eq!(block.points(), &[3]);
         }
         {
             let block = DeleteVnodeBlock::build(3, "tenant", "database", 4);
@@ -507,5 +789,74 @@ mod test {
             assert_eq!(block.table(), b"table");
             assert_eq!(block.table_utf8().unwrap(), "table");
         }
+        {
+            let block = DeleteFromTableBlock::build(6, "tenant", "database", 7, "table", vec![8]);
+            assert_eq!(block.vnode_id(), 7);
+            assert_eq!(block.tenant(), b"tenant");
+            assert_eq!(block.tenant_utf8().unwrap(), "tenant");
+            assert_eq!(block.database(), b"database");
+            assert_eq!(block.database_utf8().unwrap(), "database");
+            assert_eq!(block.table(), b"table");
+            assert_eq!(block.table_utf8().unwrap(), "table");
+            assert_eq!(block.predicate(), &[8]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repair_wal_file() {
+        let dir = "/tmp/test/wal_reader/1";
+        let _ = std::fs::remove_dir_all(dir);
+        let mut global_config = config::get_config_for_test();
+        global_config.wal.path = dir.to_string();
+        let wal_config = Arc::new(WalOptions::from(&global_config));
+
+        let wal_path = PathBuf::from(dir).join("1.wal");
+        {
+            // Write 3 entries and do not close the writer, so there is no footer.
+            let mut writer = WalWriter::open(wal_config, 1, &wal_path, 1).await.unwrap();
+            for _ in 0..3 {
+                writer
+                    .write("cnosdb".to_string(), 2, Precision::NS, vec![1, 2, 3])
+                    .await
+                    .unwrap();
+            }
+            writer.sync().await.unwrap();
+        }
+        let valid_len = std::fs::metadata(&wal_path).unwrap().len();
+        {
+            // Append some torn bytes.
+            let mut file = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&wal_path)
+                .unwrap();
+            file.write_all(&crate::record_file::RECORD_MAGIC_NUMBER.to_be_bytes())
+                .unwrap();
+            file.write_all(&[RecordDataVersion::V2 as u8, 1, 0, 0, 0])
+                .unwrap();
+        }
+
+        let (max_seq, truncated) = repair_wal_file(&wal_path).await.unwrap();
+        assert_eq!(max_seq, 3);
+        assert_eq!(truncated, 9);
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), valid_len);
+
+        // Repair again, nothing to truncate.
+        let (max_seq, truncated) = repair_wal_file(&wal_path).await.unwrap();
+        assert_eq!(max_seq, 3);
+        assert_eq!(truncated, 0);
+
+        let mut reader = WalReader::open(&wal_path).await.unwrap();
+        let mut seq = 1;
+        loop {
+            match reader.next_wal_entry().await {
+                Ok(Some(blk)) => {
+                    assert_eq!(blk.seq, seq);
+                    seq += 1;
+                }
+                Ok(None) | Err(Error::WalTruncated) => break,
+                Err(e) => panic!("Failed reading from wal {}: {e}", wal_path.display()),
+            }
+        }
+        assert_eq!(seq, 4);
     }
 }
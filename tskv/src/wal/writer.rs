@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use models::codec::Encoding;
 use models::meta_data::VnodeId;
 use models::schema::Precision;
 
@@ -72,12 +73,13 @@ impl WalWriter {
         })
     }
 
-    /// Writes data, returns data sequence and data size.
+    /// Writes data compressed by `compression`, returns data sequence and data size.
     pub async fn write(
         &mut self,
         tenant: String,
         vnode_id: VnodeId,
         precision: Precision,
+        compression: Encoding,
         points: Vec<u8>,
     ) -> Result<(u64, usize)> {
         let seq = self.max_sequence;
@@ -86,13 +88,14 @@ impl WalWriter {
         let written_size = self
             .inner
             .write_record(
-                RecordDataVersion::V1 as u8,
+                RecordDataVersion::V2 as u8,
                 RecordDataType::Wal as u8,
                 [
                     &[WalEntryType::Write as u8][..],
                     &seq.to_be_bytes(),
                     &vnode_id.to_be_bytes(),
                     &(precision as u8).to_be_bytes(),
+                    &[compression as u8],
                     &tenant_len.to_be_bytes(),
                     tenant.as_bytes(),
                     &points,
@@ -122,7 +125,7 @@ impl WalWriter {
         let written_size = self
             .inner
             .write_record(
-                RecordDataVersion::V1 as u8,
+                RecordDataVersion::V2 as u8,
                 RecordDataType::Wal as u8,
                 [
                     &[WalEntryType::DeleteVnode as u8][..],
@@ -158,7 +161,7 @@ impl WalWriter {
         let written_size = self
             .inner
             .write_record(
-                RecordDataVersion::V1 as u8,
+                RecordDataVersion::V2 as u8,
                 RecordDataType::Wal as u8,
                 [
                     &[WalEntryType::DeleteTable as u8][..],
@@ -182,6 +185,49 @@ impl WalWriter {
         Ok((seq, written_size))
     }
 
+    pub async fn delete_from_table(
+        &mut self,
+        tenant: String,
+        database: String,
+        vnode_id: VnodeId,
+        table: String,
+        predicate: Vec<u8>,
+    ) -> Result<(u64, usize)> {
+        let seq = self.max_sequence;
+        let tenant_len = tenant.len() as u64;
+        let database_len = database.len() as u32;
+        let table_len = table.len() as u32;
+
+        let written_size = self
+            .inner
+            .write_record(
+                RecordDataVersion::V2 as u8,
+                RecordDataType::Wal as u8,
+                [
+                    &[WalEntryType::DeleteFromTable as u8][..],
+                    &seq.to_be_bytes(),
+                    &vnode_id.to_be_bytes(),
+                    &tenant_len.to_be_bytes(),
+                    &database_len.to_be_bytes(),
+                    &table_len.to_be_bytes(),
+                    tenant.as_bytes(),
+                    database.as_bytes(),
+                    table.as_bytes(),
+                    &predicate,
+                ]
+                .as_slice(),
+            )
+            .await?;
+
+        if self.config.sync {
+            self.inner.sync().await?;
+        }
+        // write & fsync succeed
+        self.max_sequence += 1;
+        self.size += written_size as u64;
+        Ok((seq, written_size))
+    }
+
     pub async fn sync(&self) -> Result<()> {
         self.inner.sync().await
     }
@@ -227,8 +273,11 @@ mod test {
     use models::schema::Precision;
 
     use crate::kv_option::WalOptions;
-    use crate::wal::reader::{DeleteTableBlock, DeleteVnodeBlock, WalEntry, WalReader, WriteBlock};
+    use crate::wal::reader::{
+        DeleteFromTableBlock, DeleteTableBlock, DeleteVnodeBlock, WalEntry, WalReader, WriteBlock,
+    };
     use crate::wal::writer::WalWriter;
+    use crate::wal::{encode_points, WalDecoder};
     use crate::Error;
 
     #[tokio::test]
@@ -247,17 +296,24 @@ mod test {
             )),
             WalEntry::DeleteVnode(DeleteVnodeBlock::build(2, "cnosdb", "public", 6)),
             WalEntry::DeleteTable(DeleteTableBlock::build(3, "cnosdb", "public", "table")),
+            WalEntry::DeleteFromTable(DeleteFromTableBlock::build(
+                4, "cnosdb", "public", 6, "table", vec![4, 5, 6],
+            )),
         ];
 
         let wal_path = PathBuf::from(dir).join("1.wal");
         let wal_path = {
-            let mut writer = WalWriter::open(wal_config, 1, wal_path, 1).await.unwrap();
+            let mut writer = WalWriter::open(wal_config.clone(), 1, wal_path, 1)
+                .await
+                .unwrap();
             for ent in entries.iter() {
                 match ent {
                     WalEntry::Write(d) => {
                         let tenant = String::from_utf8(d.tenant().to_vec()).unwrap();
+                        let compression = wal_config.compression;
+                        let points = encode_points(compression, d.points()).unwrap();
                         writer
-                            .write(tenant, d.vnode_id(), d.precision(), d.points().to_vec())
+                            .write(tenant, d.vnode_id(), d.precision(), compression, points)
                             .await
                             .unwrap();
                     }
@@ -275,6 +331,21 @@ mod test {
                         let table = String::from_utf8(d.table().to_vec()).unwrap();
                         writer.delete_table(tenant, database, table).await.unwrap();
                     }
+                    WalEntry::DeleteFromTable(d) => {
+                        let tenant = String::from_utf8(d.tenant().to_vec()).unwrap();
+                        let database = String::from_utf8(d.database().to_vec()).unwrap();
+                        let table = String::from_utf8(d.table().to_vec()).unwrap();
+                        writer
+                            .delete_from_table(
+                                tenant,
+                                database,
+                                d.vnode_id(),
+                                table,
+                                d.predicate().to_vec(),
+                            )
+                            .await
+                            .unwrap();
+                    }
                     WalEntry::Unknown => {
                         // ignore
                     }
@@ -284,12 +355,21 @@ mod test {
         };
 
         let mut reader = WalReader::open(&wal_path).await.unwrap();
+        let mut decoder = WalDecoder::new();
         let mut i = 0;
         loop {
             match reader.next_wal_entry().await {
-                Ok(Some(blk)) => {
-                    assert_eq!(blk.entry, entries[i]);
-                }
+                Ok(Some(blk)) => match (&blk.entry, &entries[i]) {
+                    (WalEntry::Write(read), WalEntry::Write(expected)) => {
+                        assert_eq!(read.tenant(), expected.tenant());
+                        assert_eq!(read.vnode_id(), expected.vnode_id());
+                        assert_eq!(read.precision(), expected.precision());
+                        assert_eq!(read.compression(), wal_config.compression);
+                        let points = decoder.decode(read).unwrap().unwrap();
+                        assert_eq!(points.as_slice(), expected.points());
+                    }
+                    (read, expected) => assert_eq!(read, expected),
+                },
                 Ok(None) | Err(Error::WalTruncated) => break,
                 Err(e) => {
                     panic!("Failed reading from wal {}: {e}", wal_path.display());